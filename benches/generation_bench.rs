@@ -0,0 +1,122 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use dball::bench::{acceptance_rate, seeded_batches, seeded_candidates};
+use dball_combora::dball::{DBallBit, Reward};
+use dball_combora::generator::Generator;
+
+/// 所有基准共用的种子，保证每次运行的数据一致
+const SEED: u64 = 20_250_101;
+
+/// 候选号码生成吞吐量
+fn bench_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    for count in [1, 100, 1_000] {
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::new("bluemorn/seeded", count),
+            &count,
+            |b, &count| b.iter(|| seeded_candidates(black_box(count), SEED)),
+        );
+    }
+    group.finish();
+}
+
+/// 每注 / 每批的检查器耗时
+fn bench_checkers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checkers");
+
+    let candidates = seeded_candidates(1_000, SEED);
+    group.throughput(Throughput::Elements(candidates.len() as u64));
+    group.bench_function("candidate/evaluate", |b| {
+        b.iter(|| {
+            for candidate in black_box(&candidates) {
+                black_box(candidate.evaluate());
+            }
+        });
+    });
+
+    let batches = seeded_batches(200, SEED);
+    group.throughput(Throughput::Elements(batches.len() as u64));
+    group.bench_function("batch/evaluate", |b| {
+        b.iter(|| {
+            for batch in black_box(&batches) {
+                black_box(batch.evaluate());
+            }
+        });
+    });
+
+    group.finish();
+}
+
+/// 数组逐个比较 vs 位掩码 AND 的兑奖
+fn bench_prize_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prize_check");
+
+    let tickets = seeded_candidates(1_000, SEED);
+    let winning = seeded_candidates(1, SEED ^ 0xDEAD_BEEF)[0];
+    let ticket_bits = tickets.iter().map(DBallBit::from_dball).collect::<Vec<_>>();
+    let winning_bits = DBallBit::from_dball(&winning);
+    group.throughput(Throughput::Elements(tickets.len() as u64));
+
+    group.bench_function("array", |b| {
+        b.iter(|| {
+            black_box(&tickets)
+                .iter()
+                .filter(|ticket| ticket.check_prize(black_box(&winning)) != Reward::NoWin)
+                .count()
+        });
+    });
+    group.bench_function("bitmask", |b| {
+        b.iter(|| {
+            black_box(&ticket_bits)
+                .iter()
+                .filter(|bits| bits.check_prize(black_box(&winning_bits)) != Reward::NoWin)
+                .count()
+        });
+    });
+    // 包含把号码转换成位掩码的开销
+    group.bench_function("bitmask/with_conversion", |b| {
+        b.iter(|| {
+            black_box(&tickets)
+                .iter()
+                .filter(|ticket| {
+                    DBallBit::from_dball(ticket).check_prize(black_box(&winning_bits))
+                        != Reward::NoWin
+                })
+                .count()
+        });
+    });
+
+    group.finish();
+}
+
+/// 每个生成器对批次的接受率，以及打分的耗时
+fn bench_acceptance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("acceptance");
+
+    let batches = seeded_batches(1_000, SEED);
+    group.throughput(Throughput::Elements(batches.len() as u64));
+    for generator in Generator::ALL {
+        let random = Generator::create_generator(generator);
+        let rate = acceptance_rate(random.as_ref(), &batches);
+        println!(
+            "{} accepts {:.2}% of {} seeded batches",
+            generator.name(),
+            rate * 100.0,
+            batches.len()
+        );
+        group.bench_function(generator.name(), |b| {
+            b.iter(|| acceptance_rate(random.as_ref(), black_box(&batches)));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_generation,
+    bench_checkers,
+    bench_prize_check,
+    bench_acceptance
+);
+criterion_main!(benches);
@@ -0,0 +1,762 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use clap::{Args, Parser, Subcommand};
+use dball_client::{
+    db,
+    models::{
+        ExportRequest, ExportTable, ManualSpot, NewPrizeClaim, NewPurchase, RecommendRequest, Spot,
+        SpotDeprecation, SpotQuery, SpotScope,
+    },
+    service,
+};
+use dball_combora::dball::{CSV_HEADER, ComplexBet, DBallBatch, DantuoBet};
+
+/// Actor recorded for changes made with the CLI
+const ACTOR: &str = "cli";
+
+/// Run dball jobs straight against the database, without the TUI or the daemon
+#[derive(Parser)]
+#[command(name = "dball-cli", version, about)]
+struct Cli {
+    /// Set verbose output level
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Only see and save the spots of this profile, every spot without one
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    #[command(subcommand)]
+    command: CliCommand,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Fetch drawn tickets, all of them unless a year is given
+    Crawl {
+        #[arg(long)]
+        year: Option<usize>,
+        /// Only report the periods that would be inserted
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Generate spots for the next period
+    Generate {
+        /// Generator strategy, defaults to the one in `dball.toml`
+        #[arg(long)]
+        generator: Option<String>,
+        #[arg(long, default_value_t = 5)]
+        count: usize,
+    },
+    /// Generate a batch for each of the next draws, tagged by their period
+    Plan {
+        /// Next draws to generate for
+        #[arg(default_value_t = 3)]
+        periods: usize,
+        /// Generator strategy, defaults to the one in `dball.toml`
+        #[arg(long)]
+        generator: Option<String>,
+    },
+    /// Print the best of several generated batches without saving them
+    Recommend {
+        /// Generator strategy, defaults to the one in `dball.toml`
+        #[arg(long)]
+        generator: Option<String>,
+        /// Batches generated to choose from
+        #[arg(long, default_value_t = 20)]
+        candidates: usize,
+        #[arg(long, default_value_t = 3)]
+        top: usize,
+    },
+    /// Check the unprized spots against the drawn tickets
+    Settle,
+    /// List, add or deprecate spots
+    #[command(subcommand)]
+    Spots(SpotsCommand),
+    /// Label spots with tags or list the tags in use
+    #[command(subcommand)]
+    Tags(TagsCommand),
+    /// Print reports over the settled spots
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// Back up, export or sync the database
+    #[command(subcommand)]
+    Db(DbCommand),
+}
+
+#[derive(Subcommand)]
+enum SpotsCommand {
+    /// Print matching spots, one per line
+    List(ListArgs),
+    /// Save numbers picked by hand for the next period
+    Add {
+        /// Six red balls, e.g. `1,7,12,18,25,33`
+        #[arg(long, value_delimiter = ',', num_args = 6)]
+        reds: Vec<u8>,
+        #[arg(long)]
+        blue: u8,
+        #[arg(short, long, default_value_t = 1)]
+        magnification: usize,
+    },
+    /// Save a complex bet of more reds or blues for the next period as one spot
+    AddComplex {
+        /// Six to twenty red balls, e.g. `1,7,12,18,25,30,33`
+        #[arg(long, value_delimiter = ',', num_args = 6..=20)]
+        reds: Vec<u8>,
+        /// One or more blue balls, e.g. `4,11`
+        #[arg(long, value_delimiter = ',', num_args = 1..=16)]
+        blues: Vec<u8>,
+        #[arg(short, long, default_value_t = 1)]
+        magnification: usize,
+    },
+    /// Save a 胆拖 bet of bankers in every bet and drags filling the rest for
+    /// the next period as one spot
+    AddDantuo {
+        /// One to five banker reds, e.g. `3,17`
+        #[arg(long, value_delimiter = ',', num_args = 1..=5)]
+        bankers: Vec<u8>,
+        /// Drag reds, more than six reds with the bankers, e.g. `5,9,21,26,30`
+        #[arg(long, value_delimiter = ',', num_args = 2..=32)]
+        drags: Vec<u8>,
+        /// One or more blue balls, e.g. `4,11`
+        #[arg(long, value_delimiter = ',', num_args = 1..=16)]
+        blues: Vec<u8>,
+        #[arg(short, long, default_value_t = 1)]
+        magnification: usize,
+    },
+    /// Save every spot of a file for the next period, one `02,06,07,13,16,28+11`
+    /// per line or CSV rows as exported by the generators
+    Import { path: PathBuf },
+    /// Record tickets bought at a store for the spots of the ids and mark them
+    /// purchased
+    Purchase {
+        /// Ids of the spots bought, e.g. `12,13,14`
+        #[arg(value_delimiter = ',', num_args = 1.., required = true)]
+        ids: Vec<i32>,
+        /// Yuan paid
+        #[arg(long)]
+        cost: i32,
+        /// Day of the purchase as `2025-09-14`, defaults to today
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        #[arg(long)]
+        store: Option<String>,
+        /// Receipt note, e.g. its serial number
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Record the prize of a winning spot as claimed
+    Claim {
+        id: i32,
+        /// Yuan paid out, the winnings of the spot by default, needed for the
+        /// floating first and second tiers
+        #[arg(long)]
+        amount: Option<i32>,
+        /// Day of the claim as `2025-09-16`, defaults to today
+        #[arg(long)]
+        date: Option<chrono::NaiveDate>,
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Deprecate a spot by id, or the last unprized batch
+    Deprecate {
+        #[arg(required_unless_present = "last_batch", conflicts_with = "last_batch")]
+        id: Option<i32>,
+        #[arg(long)]
+        last_batch: bool,
+    },
+    /// Restore deprecated spots by id, those deprecated last without any
+    Restore {
+        #[arg(value_delimiter = ',')]
+        ids: Vec<i32>,
+        /// Why the spots are restored
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Print when a spot was deprecated or restored, by whom and why
+    History { id: i32 },
+    /// Print the ids of the live spots repeating one another, one group per line
+    Duplicates,
+}
+
+#[derive(Args)]
+struct ListArgs {
+    /// Only spots not settled yet
+    #[arg(long, conflicts_with = "prized")]
+    unprized: bool,
+    /// Only settled spots
+    #[arg(long)]
+    prized: bool,
+    /// Only periods starting with this prefix, e.g. `2025`
+    #[arg(long)]
+    period: Option<String>,
+    /// Only spots that won a prize
+    #[arg(long)]
+    winning: bool,
+    /// Only spots carrying every one of these tags
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+}
+
+impl ListArgs {
+    fn query(self) -> SpotQuery {
+        let scope = match (self.unprized, self.prized) {
+            (true, _) => SpotScope::Unprized,
+            (false, true) => SpotScope::Prized,
+            (false, false) => SpotScope::All,
+        };
+        SpotQuery {
+            period_prefix: self.period,
+            winning_only: self.winning,
+            tags: self.tags,
+            ..SpotQuery::default()
+        }
+        .with_scope(scope)
+    }
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    /// Tag spots by id, e.g. `tags add 12,13 "birthday numbers"`
+    Add {
+        #[arg(value_delimiter = ',', required = true)]
+        ids: Vec<i32>,
+        tag: String,
+    },
+    /// Take a tag off spots by id
+    Remove {
+        #[arg(value_delimiter = ',', required = true)]
+        ids: Vec<i32>,
+        tag: String,
+    },
+    /// Print every tag with its number of spots, or the tags of one spot
+    List { id: Option<i32> },
+}
+
+#[derive(Subcommand)]
+enum ReportCommand {
+    /// Cost and winnings of every settled period
+    Roi,
+    /// How the latest generated batches were picked
+    Generations {
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    /// Pool, sales and first and second prize winners of the latest draws
+    Draws {
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Copy the database to a new file
+    Backup { path: PathBuf },
+    /// Replace every row with the ones of a backup, stop the daemon first
+    Restore { path: PathBuf },
+    /// Checkpoint the WAL, analyze and vacuum past the threshold of `dball.toml`
+    Maintain,
+    /// Check the integrity of the database and print its sizes and row counts
+    Health,
+    /// Write tickets or spots as CSV, to stdout unless an output file is given
+    Export {
+        #[arg(value_enum)]
+        table: TableArg,
+        /// Only periods starting with this prefix, e.g. `2025`
+        #[arg(long)]
+        period: Option<String>,
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Fill the ticket log from the published results archive, a CSV file or URL
+    SyncLog { source: String },
+    /// Fill the drawn tickets from the published results archive instead of crawling
+    ImportTickets { source: String },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum TableArg {
+    Tickets,
+    Spots,
+}
+
+impl From<TableArg> for ExportTable {
+    fn from(table: TableArg) -> Self {
+        match table {
+            TableArg::Tickets => Self::Tickets,
+            TableArg::Spots => Self::Spots,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    // stdout is kept for results, so only warnings are logged by default
+    let log_level = match cli.verbose {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    dball_client::Client::builder()
+        .log_level(Some(log_level))
+        .build()?;
+
+    let profile_id = match &cli.profile {
+        Some(name) => Some(service::profile_id(name).await?),
+        None => None,
+    };
+    dball_client::profile::scope(profile_id, run(cli.command)).await
+}
+
+async fn run(command: CliCommand) -> Result<()> {
+    match command {
+        CliCommand::Crawl { year, dry_run } => crawl(year, dry_run).await,
+        CliCommand::Generate { generator, count } => generate(generator, count).await,
+        CliCommand::Plan { periods, generator } => plan(periods, generator).await,
+        CliCommand::Recommend {
+            generator,
+            candidates,
+            top,
+        } => recommend(generator, candidates, top).await,
+        CliCommand::Settle => {
+            let spots = service::update_all_unprize_spots().await?;
+            println!("Settled spots, {} prized in total", spots.len());
+            Ok(())
+        }
+        CliCommand::Spots(command) => spots(command).await,
+        CliCommand::Tags(command) => tags(command).await,
+        CliCommand::Report(ReportCommand::Roi) => roi().await,
+        CliCommand::Report(ReportCommand::Generations { limit }) => generations(limit),
+        CliCommand::Report(ReportCommand::Draws { limit }) => draws(limit),
+        CliCommand::Db(command) => database(command).await,
+    }
+}
+
+async fn crawl(year: Option<usize>, dry_run: bool) -> Result<()> {
+    let report = if let Some(year) = year {
+        service::update_tickets_with_year(year, dry_run).await?
+    } else {
+        service::crawl_all_tickets(dry_run).await?
+    };
+    let inserted = if dry_run { "Would insert" } else { "Inserted" };
+    println!(
+        "{} missing periods, {inserted} {}: {}",
+        report.missing.len(),
+        report.inserted.len(),
+        report.inserted.join(", ")
+    );
+    for mismatch in &report.mismatches {
+        println!("{mismatch}");
+    }
+    Ok(())
+}
+
+async fn generate(generator: Option<String>, count: usize) -> Result<()> {
+    let generator = service::get_config()
+        .await?
+        .generator
+        .generator(generator.as_deref())?;
+    let period = service::get_next_period().await?;
+    for dball in service::generate_spots(&generator, count).await? {
+        println!("{period}\t{dball}");
+    }
+    Ok(())
+}
+
+async fn plan(periods: usize, generator: Option<String>) -> Result<()> {
+    for batch in service::generate_spots_for_periods(periods, generator.as_deref()).await? {
+        let draw_time = batch.draw_time.format("%Y-%m-%d %H:%M UTC");
+        if batch.spot_ids.is_empty() {
+            println!(
+                "{}\t{draw_time}\tskipped, enough spots waiting",
+                batch.period
+            );
+        } else {
+            println!(
+                "{}\t{draw_time}\t{} spots tagged {}",
+                batch.period,
+                batch.spot_ids.len(),
+                service::plan_tag(&batch.period)
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn recommend(generator: Option<String>, candidates: usize, top: usize) -> Result<()> {
+    let request = RecommendRequest {
+        strategy: generator,
+        candidates,
+        top,
+    };
+    for (rank, recommendation) in service::recommend(request).await?.iter().enumerate() {
+        println!(
+            "#{} {} score {:.3} (checks {:.3}, history {:.3})",
+            rank + 1,
+            recommendation.generator,
+            recommendation.score,
+            recommendation.check_score,
+            recommendation.history_score
+        );
+        for dball in &recommendation.spots {
+            println!("\t{dball}");
+        }
+        for detail in &recommendation.diagnostics {
+            println!("\t! {detail}");
+        }
+    }
+    Ok(())
+}
+
+fn print_spot(spot: &Spot) {
+    let numbers = |numbers: Vec<i32>| {
+        numbers
+            .iter()
+            .map(|number| format!("{number:02}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let reds = numbers(spot.red_numbers());
+    let blues = numbers(spot.blue_numbers());
+    let prize = spot
+        .prize_status
+        .map_or_else(|| "-".to_owned(), |level| level.to_string());
+    println!(
+        "{}\t{}\t{reds} + {blues}\tx{}\t{prize}{}",
+        spot.id.map(|id| id.to_string()).unwrap_or_default(),
+        spot.period,
+        spot.magnification,
+        if spot.deprecated { "\tdeprecated" } else { "" }
+    );
+}
+
+fn print_deprecation(change: &SpotDeprecation) {
+    println!(
+        "{}\t{}\t{}\t{}",
+        change.created_time.format("%Y-%m-%d %H:%M:%S"),
+        if change.deprecated {
+            "deprecated"
+        } else {
+            "restored"
+        },
+        change.actor,
+        change.reason.as_deref().unwrap_or("-")
+    );
+}
+
+async fn spots(command: SpotsCommand) -> Result<()> {
+    match command {
+        SpotsCommand::List(args) => {
+            for spot in service::query_spots(args.query()).await? {
+                print_spot(&spot);
+            }
+        }
+        SpotsCommand::Add {
+            reds,
+            blue,
+            magnification,
+        } => {
+            let reds = <[u8; 6]>::try_from(reds)
+                .map_err(|reds| anyhow!("Expected six red balls, got {}", reds.len()))?;
+            let period = service::add_manual_spot(ManualSpot {
+                reds,
+                blue,
+                magnification,
+            })
+            .await?;
+            println!("Saved spot for period {period}");
+        }
+        SpotsCommand::AddComplex {
+            reds,
+            blues,
+            magnification,
+        } => {
+            let bet = ComplexBet::new(reds, blues, magnification)?;
+            let (bets, cost) = (bet.bets(), bet.cost());
+            let period = service::add_complex_spot(bet).await?;
+            println!("Saved complex spot of {bets} bets costing {cost} for period {period}");
+        }
+        SpotsCommand::AddDantuo {
+            bankers,
+            drags,
+            blues,
+            magnification,
+        } => {
+            let bet = DantuoBet::new(bankers, drags, blues, magnification)?;
+            let (bets, cost) = (bet.bets(), bet.cost());
+            let period = service::add_dantuo_spot(bet).await?;
+            println!("Saved dantuo spot of {bets} bets costing {cost} for period {period}");
+        }
+        SpotsCommand::Import { path } => import(&path).await?,
+        SpotsCommand::Purchase {
+            ids,
+            cost,
+            date,
+            store,
+            note,
+        } => {
+            let count = ids.len();
+            let id = service::record_purchase(NewPurchase {
+                spot_ids: ids,
+                date,
+                store,
+                cost,
+                note,
+            })
+            .await?;
+            println!("Recorded purchase {id} of {count} spots costing {cost}");
+        }
+        SpotsCommand::Claim {
+            id,
+            amount,
+            date,
+            note,
+        } => {
+            claim(NewPrizeClaim {
+                spot_id: id,
+                date,
+                amount,
+                note,
+            })
+            .await?;
+        }
+        SpotsCommand::Deprecate { id: Some(id), .. } => {
+            service::deprecate_spot(id, ACTOR).await?;
+            println!("Deprecated spot {id}");
+        }
+        SpotsCommand::Deprecate { id: None, .. } => {
+            let count = service::deprecated_last_batch_unprized_spot(ACTOR).await?;
+            println!("Deprecated {count} spots");
+        }
+        SpotsCommand::Restore { ids, reason } => {
+            let restored = service::restore_spots(ids, ACTOR, reason).await?;
+            println!("Restored {} spots {restored:?}", restored.len());
+        }
+        SpotsCommand::History { id } => {
+            for change in service::spot_deprecation_history(id).await? {
+                print_deprecation(&change);
+            }
+        }
+        SpotsCommand::Duplicates => print_duplicates().await?,
+    }
+    Ok(())
+}
+
+async fn import(path: &Path) -> Result<()> {
+    let text = std::fs::read_to_string(path)?;
+    let batch = if text.lines().next().map(str::trim) == Some(CSV_HEADER) {
+        DBallBatch::from_csv(&text)?
+    } else {
+        text.parse::<DBallBatch>()?
+    };
+    for dball in &batch.0 {
+        let period = service::add_manual_spot(ManualSpot {
+            reds: dball.rball,
+            blue: dball.bball,
+            magnification: dball.magnification,
+        })
+        .await?;
+        println!("Saved {} for period {period}", dball.to_text());
+    }
+    Ok(())
+}
+
+async fn print_duplicates() -> Result<()> {
+    for group in service::duplicate_spots().await? {
+        let ids = group
+            .ids
+            .iter()
+            .map(i32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        println!("{}\t{ids}", group.period);
+    }
+    Ok(())
+}
+
+async fn tags(command: TagsCommand) -> Result<()> {
+    match command {
+        TagsCommand::Add { ids, tag } => {
+            let tagged = service::tag_spots(ids, tag.clone()).await?;
+            println!("Tagged {tagged} spots with {tag}");
+        }
+        TagsCommand::Remove { ids, tag } => {
+            let untagged = service::untag_spots(ids, tag.clone()).await?;
+            println!("Took {tag} off {untagged} spots");
+        }
+        TagsCommand::List { id: Some(id) } => {
+            for tag in service::spot_tags(id).await? {
+                println!("{}", tag.name);
+            }
+        }
+        TagsCommand::List { id: None } => {
+            for tag in service::list_tags().await? {
+                println!("{}\t{}", tag.name, tag.spots);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn claim(claim: NewPrizeClaim) -> Result<()> {
+    let spot_id = claim.spot_id;
+    let id = service::claim_prize(claim).await?;
+    let amount = db::prize_claim::get_claim_by_spot(spot_id)?.map_or(0, |claim| claim.amount);
+    println!("Recorded claim {id} of {amount} yuan for spot {spot_id}");
+    Ok(())
+}
+
+async fn roi() -> Result<()> {
+    let report = service::roi_report().await?;
+    println!("period\tcost\twinnings\tclaimed\tnet");
+    for period in &report.periods {
+        println!(
+            "{}\t{}\t{}\t{}\t{:+}",
+            period.period,
+            period.cost,
+            period.winnings,
+            period.claimed,
+            period.net()
+        );
+    }
+    println!(
+        "total\t{}\t{}\t{}\t{:+}",
+        report.total_cost,
+        report.total_winnings,
+        report.total_claimed,
+        report.total_winnings as i64 - report.total_cost as i64
+    );
+    Ok(())
+}
+
+fn generations(limit: i64) -> Result<()> {
+    println!("id\tperiod\tgenerator\tseed\tscore\tattempts\tms\tspots");
+    for log in db::generation_log::get_latest_generation_logs(limit)? {
+        let id = log.id.unwrap_or_default();
+        let spots = db::generation_log::get_generation_spot_ids(id)?
+            .iter()
+            .map(i32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        println!(
+            "{id}\t{}\t{}\t{}\t{:.4}\t{}\t{}\t{spots}",
+            log.period,
+            log.generator,
+            log.seed.as_deref().unwrap_or("-"),
+            log.score,
+            log.attempts,
+            log.duration_ms
+        );
+    }
+    Ok(())
+}
+
+fn draws(limit: i64) -> Result<()> {
+    let figure = |value: Option<i64>| value.map_or_else(|| "-".to_owned(), |v| v.to_string());
+    println!("period\tpool\tsales\tfirst\tfirst amount\tsecond\tsecond amount");
+    for detail in db::draw_detail::get_latest_draw_details(limit)? {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            detail.period,
+            figure(detail.pool),
+            figure(detail.sales),
+            figure(detail.prize1_winners.map(i64::from)),
+            figure(detail.prize1_amount.map(i64::from)),
+            figure(detail.prize2_winners.map(i64::from)),
+            figure(detail.prize2_amount.map(i64::from)),
+        );
+    }
+    Ok(())
+}
+
+/// Print the health of the database, failing when the integrity check does
+async fn print_health() -> Result<()> {
+    let health = service::database_health().await?;
+    println!("file\t{} bytes", health.file_bytes);
+    println!("wal\t{} bytes", health.wal_bytes);
+    for (table, rows) in &health.rows {
+        println!("{table}\t{rows} rows");
+    }
+    for problem in &health.problems {
+        println!("problem\t{problem}");
+    }
+    if !health.is_ok() {
+        return Err(anyhow!(
+            "Integrity check found {} problems",
+            health.problems.len()
+        ));
+    }
+    Ok(())
+}
+
+async fn database(command: DbCommand) -> Result<()> {
+    match command {
+        DbCommand::Backup { path } => {
+            db::maintenance::backup(&path)?;
+            println!("Backed up database to {}", path.display());
+        }
+        DbCommand::Restore { path } => {
+            db::maintenance::restore(&path)?;
+            println!("Restored database from {}", path.display());
+        }
+        DbCommand::Maintain => {
+            let report = service::maintain_database().await?;
+            println!(
+                "Checkpointed {} WAL pages, {} of {} pages free, vacuumed: {}",
+                report.checkpointed_pages, report.free_pages, report.total_pages, report.vacuumed
+            );
+        }
+        DbCommand::Health => print_health().await?,
+        DbCommand::Export {
+            table,
+            period,
+            output,
+        } => {
+            let csv = service::export_csv(ExportRequest {
+                table: table.into(),
+                period_prefix: period,
+            })
+            .await?;
+            match output {
+                Some(path) => std::fs::write(&path, csv)?,
+                None => print!("{csv}"),
+            }
+        }
+        DbCommand::SyncLog { source } => {
+            let report = service::sync_from_archive(&source).await?;
+            println!(
+                "{} rows: {} inserted, {} updated",
+                report.rows, report.inserted, report.updated
+            );
+            for conflict in &report.conflicts {
+                println!(
+                    "conflict\t{}\tarchive {}\t{} {}",
+                    conflict.period, conflict.archive, conflict.table, conflict.recorded
+                );
+            }
+            for row in &report.invalid_rows {
+                println!("invalid\t{row}");
+            }
+        }
+        DbCommand::ImportTickets { source } => {
+            let report = service::import_tickets_from_archive(&source).await?;
+            println!(
+                "{} rows: {} inserted, {} skipped",
+                report.rows, report.inserted, report.skipped
+            );
+            for conflict in &report.conflicts {
+                println!(
+                    "conflict\t{}\tarchive {}\ttickets {}",
+                    conflict.period, conflict.archive, conflict.recorded
+                );
+            }
+            for row in &report.invalid_rows {
+                println!("invalid\t{row}");
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::{Result, anyhow};
 use clap::{Arg, Command};
-use dball_client::{api, daemon::DaemonService, db};
+use dball_client::{api, daemon::DaemonService, db, telemetry};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -35,7 +35,10 @@ async fn main() -> Result<()> {
         _ => log::LevelFilter::Trace,
     };
 
-    dball_client::setup(Some(log_level));
+    dball_client::Client::builder()
+        .log_level(Some(log_level))
+        .build()?;
+    let _telemetry = telemetry::init()?;
 
     // check configuration if requested
     if matches.get_flag("config-check") {
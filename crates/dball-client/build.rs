@@ -0,0 +1,4 @@
+fn main() {
+    // migrations are embedded by `embed_migrations!`, rebuild when one is added
+    println!("cargo:rerun-if-changed=../../migrations");
+}
@@ -1,7 +1,9 @@
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    dball_client::setup(Some(log::LevelFilter::Info));
-    dball_client::service::crawl_all_tickets().await?;
+    dball_client::Client::builder()
+        .log_level(Some(log::LevelFilter::Info))
+        .build()?;
+    dball_client::service::crawl_all_tickets(false).await?;
 
     Ok(())
 }
@@ -1,7 +1,9 @@
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    dball_client::setup(Some(log::LevelFilter::Info));
-    dball_client::service::update_tickets_with_year(2025).await?;
+    dball_client::Client::builder()
+        .log_level(Some(log::LevelFilter::Info))
+        .build()?;
+    dball_client::service::update_tickets_with_year(2025, false).await?;
 
     Ok(())
 }
@@ -1,6 +1,8 @@
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    dball_client::setup(Some(log::LevelFilter::Info));
+    dball_client::Client::builder()
+        .log_level(Some(log::LevelFilter::Info))
+        .build()?;
     dball_client::service::update_all_unprize_spots().await?;
 
     Ok(())
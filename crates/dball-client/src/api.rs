@@ -7,8 +7,16 @@ mod rest;
 mod websocket;
 
 pub use config::ApiConfig;
+/// Provider of the drawn tickets, the offline fixture under the `simulation` feature
+#[cfg(feature = "simulation")]
+pub use provider::fixture::FIXTURE_PROVIDER as LOTTERY_PROVIDER;
+#[cfg(feature = "simulation")]
+pub use provider::fixture::{FIXTURE_PROVIDER, FixtureProvider, UPCOMING_DRAWS};
 pub use provider::mxnzp::MXNZP_PROVIDER;
-pub use provider::{ProviderRequest, ProviderResponse};
+/// Provider of the drawn tickets
+#[cfg(not(feature = "simulation"))]
+pub use provider::mxnzp::MXNZP_PROVIDER as LOTTERY_PROVIDER;
+pub use provider::{ProviderError, ProviderRequest, ProviderResponse};
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
@@ -5,10 +5,7 @@ use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator as _;
 use toml::Value;
 
-use crate::{
-    ENV_GUARD,
-    api::{Protocol, provider::ApiProvider},
-};
+use crate::api::{Protocol, provider::ApiProvider};
 
 const API_CONFIG_FILE: &str = "api.toml";
 const API_DIR: &str = "api";
@@ -23,19 +20,11 @@ fn get_valid_providers() -> Vec<&'static str> {
     ApiProvider::iter().map(|p| p.id()).collect()
 }
 
-pub static API_CONFIG: LazyLock<Result<ApiConfig>> = LazyLock::new(|| match ENV_GUARD.as_ref() {
-    Ok(env_file_path) => {
-        let root_path = env_file_path
-            .parent()
-            .context("Could not get parent directory of .env file")?;
+pub static API_CONFIG: LazyLock<Result<ApiConfig>> = LazyLock::new(|| {
+    let root_path = crate::root_dir()?;
 
-        // Use new multi-file loading approach
-        ApiConfig::new(root_path.join(API_CONFIG_FILE), root_path.join(API_DIR))
-    }
-    Err(e) => {
-        log::error!("Failed to load .env file: {e}, using default config");
-        Err(anyhow::anyhow!("Failed to load .env file: {e}"))
-    }
+    // Use new multi-file loading approach
+    ApiConfig::new(root_path.join(API_CONFIG_FILE), root_path.join(API_DIR))
 });
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
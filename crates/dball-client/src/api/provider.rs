@@ -4,10 +4,13 @@ use std::{
 };
 
 use strum_macros::{Display, EnumIter};
-use tokio::sync::{Mutex, Semaphore};
+use tokio::sync::Mutex;
+use tracing::Instrument as _;
 
 use crate::api::{ApiCommon, Protocol};
 
+#[cfg(feature = "simulation")]
+pub mod fixture;
 pub mod mxnzp;
 
 /// Enum representing different API service providers
@@ -60,6 +63,30 @@ impl ApiProvider {
     }
 }
 
+/// Failure of a provider request, see [`Self::is_transient`]
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    #[error("Request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Request failed with status: {0}")]
+    Status(reqwest::StatusCode),
+    #[error("API returned error: {0}")]
+    Api(String),
+}
+
+impl ProviderError {
+    /// Whether the same request may succeed when sent again later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) => true,
+            Self::Status(status) => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::Api(_) => false,
+        }
+    }
+}
+
 /// Request that can be executed through a provider (protocol-agnostic)
 #[expect(async_fn_in_trait)]
 pub trait ProviderRequest: Send + 'static {
@@ -115,42 +142,37 @@ pub trait Provider: Send + Sync + 'static {
 }
 
 /// QPS-limited executor that manages request queues and rate limiting
+///
+/// Requests start at least `1 / QPS` apart but may be in flight together, so
+/// concurrent callers overlap their waits for the provider without going over
+/// its limit
 #[derive(Debug)]
 pub struct QpsLimitedExecutor {
     provider: ApiProvider,
-    semaphore: Arc<Semaphore>,
+    /// Start of the latest request, reserved before it waits its turn
     last_request_time: Arc<Mutex<Instant>>,
 }
 
 impl QpsLimitedExecutor {
     pub fn new(provider: ApiProvider) -> Self {
-        let qps_limit = provider.qps_limit();
         Self {
             provider,
-            semaphore: Arc::new(Semaphore::new(qps_limit)),
             last_request_time: Arc::new(Mutex::new(Instant::now())),
         }
     }
 
     /// Execute a request with QPS limiting
+    #[tracing::instrument(skip_all, fields(provider = self.provider.id()))]
     pub async fn execute<R>(&self, request: R) -> anyhow::Result<R::Response>
     where
         R: ProviderRequest,
     {
-        // Acquire semaphore permit to ensure serial execution
-        let _permit = self
-            .semaphore
-            .acquire()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to acquire semaphore permit: {}", e))?;
-
-        // Calculate delay based on last request time
+        // Reserve the next free start time, the lock orders concurrent callers
         let delay = {
-            let last_time = self.last_request_time.lock().await;
-            let elapsed = last_time.elapsed();
+            let mut last_time = self.last_request_time.lock().await;
             let qps = self.provider.qps_limit();
 
-            if qps == 0 {
+            let min_interval = if qps == 0 {
                 log::warn!(
                     "QPS limit for provider {} is 0, skipping delay calculation to avoid division by zero.",
                     self.provider.id()
@@ -158,10 +180,13 @@ impl QpsLimitedExecutor {
                 Duration::ZERO
             } else {
                 // Calculate minimum interval between requests (1 second / QPS)
-                let min_interval = Duration::from_secs_f64(1.0 / qps as f64);
+                Duration::from_secs_f64(1.0 / qps as f64)
+            };
 
-                min_interval.saturating_sub(elapsed)
-            }
+            let now = Instant::now();
+            let start = (*last_time + min_interval).max(now);
+            *last_time = start;
+            start - now
         };
 
         // Apply delay if needed
@@ -171,19 +196,13 @@ impl QpsLimitedExecutor {
                 self.provider.id(),
                 delay
             );
-            tokio::time::sleep(delay).await;
-        }
-
-        let response = request.execute().await;
-
-        // Update last request time to now (right before execution)
-        {
-            let mut last_time = self.last_request_time.lock().await;
-            *last_time = Instant::now();
+            tokio::time::sleep(delay)
+                .instrument(tracing::debug_span!("qps_wait", ?delay))
+                .await;
         }
 
         log::debug!("Executing request for provider: {}", self.provider.id());
-        response
+        request.execute().await
     }
 }
 
@@ -199,3 +218,78 @@ impl std::str::FromStr for ApiProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers after `latency`, reporting when it started
+    struct Delayed {
+        latency: Duration,
+    }
+
+    struct Started(Instant);
+
+    impl ProviderResponse for Started {
+        type Data = Instant;
+
+        fn get_code(&self) -> i32 {
+            0
+        }
+
+        fn get_msg(&self) -> String {
+            String::new()
+        }
+
+        fn get_data(&self) -> Option<&Instant> {
+            Some(&self.0)
+        }
+    }
+
+    impl ProviderRequest for Delayed {
+        type Response = Started;
+
+        async fn execute(self) -> anyhow::Result<Started> {
+            let started = Instant::now();
+            tokio::time::sleep(self.latency).await;
+            Ok(Started(started))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_keep_qps() -> anyhow::Result<()> {
+        // 5 QPS, requests start at least 200ms apart
+        let executor = QpsLimitedExecutor::new(ApiProvider::Custom);
+        let latency = Duration::from_millis(600);
+        let begin = Instant::now();
+        let responses =
+            futures_util::future::join_all((0..3).map(|_| executor.execute(Delayed { latency })))
+                .await;
+        let total = begin.elapsed();
+
+        let mut starts = responses
+            .into_iter()
+            .map(|response| response.map(|started| started.0))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        starts.sort_unstable();
+        for pair in starts.windows(2) {
+            assert!(
+                pair[1] - pair[0] >= Duration::from_millis(190),
+                "{starts:?}"
+            );
+        }
+        // in flight together rather than one after another
+        assert!(total < latency * 3, "Took {total:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_transient_provider_errors() {
+        use reqwest::StatusCode;
+
+        assert!(ProviderError::Status(StatusCode::BAD_GATEWAY).is_transient());
+        assert!(ProviderError::Status(StatusCode::TOO_MANY_REQUESTS).is_transient());
+        assert!(!ProviderError::Status(StatusCode::NOT_FOUND).is_transient());
+        assert!(!ProviderError::Api("数据不存在".to_owned()).is_transient());
+    }
+}
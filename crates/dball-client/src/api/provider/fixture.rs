@@ -0,0 +1,88 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDateTime;
+
+use crate::api::rest::mxnzp::{
+    DEFAULT_LOTTERY_CODE, GeneralLatestLotteryResponse, GeneralSpecifiedLotteryResponse,
+    LotteryData,
+};
+use crate::db::testing::FIXTURE_DRAWS;
+use crate::schedule::{DrawSchedule as _, WeeklySchedule};
+
+/// Global fixture provider instance
+pub static FIXTURE_PROVIDER: LazyLock<FixtureProvider> = LazyLock::new(|| FixtureProvider);
+
+/// Draws after the ones in the fixture database as `(period, draw time, reds, blue)`
+pub const UPCOMING_DRAWS: [(&str, &str, [i32; 6], i32); 3] = [
+    ("2025089", "2025-08-03 21:15:00", [4, 9, 15, 22, 27, 31], 8),
+    (
+        "2025090",
+        "2025-08-05 21:15:00",
+        [2, 11, 17, 19, 26, 32],
+        13,
+    ),
+    ("2025091", "2025-08-07 21:15:00", [7, 10, 14, 23, 28, 33], 5),
+];
+
+const SUCCESS: i32 = super::mxnzp::RETURN_CODE_SUCCESS;
+
+/// Answers like the MXNZP API from the fixture and [`UPCOMING_DRAWS`], offline
+///
+/// A draw is only published once [`crate::clock::now`] has passed its draw time,
+/// so a test advancing the clock sees new draws just like the real API would
+#[derive(Debug)]
+pub struct FixtureProvider;
+
+impl FixtureProvider {
+    /// Latest published draw
+    pub async fn get_latest_lottery(&self) -> anyhow::Result<GeneralLatestLotteryResponse> {
+        let data = Self::published()?.last().cloned();
+        Ok(GeneralLatestLotteryResponse {
+            code: SUCCESS,
+            msg: "fixture".to_owned(),
+            data,
+        })
+    }
+
+    /// Published draw of `expect`, a 5-digit period string, e.g. "25089"
+    pub async fn get_specified_lottery(
+        &self,
+        expect: &str,
+    ) -> anyhow::Result<GeneralSpecifiedLotteryResponse> {
+        let data = Self::published()?
+            .into_iter()
+            .find(|data| data.period.ends_with(expect));
+        Ok(GeneralSpecifiedLotteryResponse {
+            code: SUCCESS,
+            msg: "fixture".to_owned(),
+            data,
+        })
+    }
+
+    /// Every draw whose time has passed, oldest first
+    fn published() -> anyhow::Result<Vec<LotteryData>> {
+        // draw times are local to the game
+        let schedule = WeeklySchedule::ssq();
+        let now = crate::clock::now();
+
+        let mut published = Vec::new();
+        for (period, time, reds, blue) in FIXTURE_DRAWS.iter().chain(&UPCOMING_DRAWS) {
+            let local = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")?;
+            if schedule.to_utc(local).is_none_or(|draw| draw > now) {
+                break;
+            }
+            let reds = reds.map(|red| format!("{red:02}")).join(",");
+            published.push(LotteryData {
+                open_code: format!("{reds}+{blue:02}"),
+                code: DEFAULT_LOTTERY_CODE.to_owned(),
+                period: (*period).to_owned(),
+                name: "双色球".to_owned(),
+                time: (*time).to_owned(),
+                pool_money: None,
+                sales: None,
+                prize_grades: Vec::new(),
+            });
+        }
+        Ok(published)
+    }
+}
@@ -2,6 +2,13 @@ mod common;
 mod latest_ticket;
 mod specified_ticket;
 
+#[cfg(feature = "simulation")]
+pub(crate) use common::{DEFAULT_LOTTERY_CODE, LotteryData};
+#[cfg(feature = "simulation")]
+pub(crate) use latest_ticket::GeneralLatestLotteryResponse;
+#[cfg(feature = "simulation")]
+pub(crate) use specified_ticket::GeneralSpecifiedLotteryResponse;
+
 #[cfg(test)]
 mod tests {
     use crate::api::MXNZP_PROVIDER;
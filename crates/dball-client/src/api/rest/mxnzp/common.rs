@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-pub const DEFAULT_LOTTERY_CODE: &str = "ssq";
+pub const DEFAULT_LOTTERY_CODE: &str = crate::schedule::SSQ;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct LotteryData {
@@ -11,6 +11,64 @@ pub struct LotteryData {
     pub period: String,
     pub name: String,
     pub time: String,
+    /// Yuan left in the jackpot pool, only in detailed answers
+    #[serde(rename = "poolMoney", alias = "poolmoney", default)]
+    pub pool_money: Option<Amount>,
+    /// Yuan of tickets sold for the period, only in detailed answers
+    #[serde(rename = "sales", alias = "salesMoney", default)]
+    pub sales: Option<Amount>,
+    /// Winners and prize of each tier, only in detailed answers
+    #[serde(rename = "prizeGrades", alias = "prizegrades", default)]
+    pub prize_grades: Vec<PrizeGrade>,
+}
+
+/// Yuan or a count, sent as a number or as text like `"2,178,617,654"`
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Amount {
+    Number(i64),
+    Text(String),
+}
+
+impl Amount {
+    fn value(&self) -> Option<i64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            Self::Text(text) => text.trim().replace(',', "").parse().ok(),
+        }
+    }
+}
+
+/// Winners and prize per bet of one tier of a draw
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct PrizeGrade {
+    /// Tier, 1 for the first prize
+    #[serde(rename = "type")]
+    pub tier: u8,
+    #[serde(rename = "typeNum", alias = "typenum", default)]
+    pub winners: Option<Amount>,
+    #[serde(rename = "typeMoney", alias = "typemoney", default)]
+    pub money: Option<Amount>,
+}
+
+impl LotteryData {
+    /// Pool, sales and winners of the draw, `None` when the answer carries none
+    pub fn draw_detail(&self) -> Option<crate::models::DrawDetail> {
+        let count = |amount: &Option<Amount>| {
+            amount
+                .as_ref()
+                .and_then(Amount::value)
+                .and_then(|value| i32::try_from(value).ok())
+        };
+        let mut detail =
+            crate::models::DrawDetail::new(&self.period, chrono::Utc::now().naive_utc());
+        detail.pool = self.pool_money.as_ref().and_then(Amount::value);
+        detail.sales = self.sales.as_ref().and_then(Amount::value);
+        for grade in &self.prize_grades {
+            detail.set_tier(grade.tier, count(&grade.winners), count(&grade.money));
+        }
+        (!detail.is_empty()).then_some(detail)
+    }
 }
 
 impl TryFrom<LotteryData> for crate::models::Ticket {
@@ -75,3 +133,49 @@ impl TryFrom<&LotteryData> for crate::models::Ticket {
         )?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_detail_from_answer() -> anyhow::Result<()> {
+        let data = serde_json::from_str::<LotteryData>(
+            r#"{
+                "openCode": "03,08,14,21,27,32+09",
+                "code": "ssq",
+                "expect": "2025088",
+                "name": "双色球",
+                "time": "2025-08-03 21:15:00",
+                "poolmoney": "2,178,617,654",
+                "sales": 351234567,
+                "prizegrades": [
+                    {"type": 1, "typenum": "4", "typemoney": "7312640"},
+                    {"type": 2, "typenum": "98", "typemoney": ""}
+                ]
+            }"#,
+        )?;
+        let detail = data
+            .draw_detail()
+            .ok_or_else(|| anyhow::anyhow!("Answer has details"))?;
+        assert_eq!(detail.pool, Some(2_178_617_654));
+        assert_eq!(detail.sales, Some(351_234_567));
+        assert_eq!(
+            (detail.prize1_winners, detail.prize1_amount),
+            (Some(4), Some(7_312_640))
+        );
+        assert_eq!(
+            (detail.prize2_winners, detail.prize2_amount),
+            (Some(98), None)
+        );
+
+        let plain = LotteryData {
+            pool_money: None,
+            sales: None,
+            prize_grades: Vec::new(),
+            ..data
+        };
+        assert!(plain.draw_detail().is_none());
+        Ok(())
+    }
+}
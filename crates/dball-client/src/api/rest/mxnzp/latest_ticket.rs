@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::api::{
     ApiCommon, CLIENT, MXNZP_PROVIDER,
-    provider::{Provider as _, ProviderRequest, ProviderResponse},
+    provider::{Provider as _, ProviderError, ProviderRequest, ProviderResponse},
 };
 
 impl crate::api::provider::mxnzp::MxnzpProvider {
@@ -77,30 +77,23 @@ impl ProviderRequest for GeneralLatestLotteryRequest {
 
         let resp = CLIENT.get(common.url()).query(&self).send().await;
 
-        let response = match resp {
-            Ok(response) => {
-                if response.status().is_success() {
-                    response
-                } else {
-                    let error_message = format!(
-                        "GeneralLatestLotteryRequest failed with status: {}",
-                        response.status()
-                    );
-                    let text = response.text().await.unwrap_or_default();
-                    log::error!("{error_message}\n==== Response: ====\n {text}");
-                    return Err(anyhow::anyhow!("{error_message}"));
-                }
-            }
-            Err(e) => return Err(anyhow::anyhow!("Request failed: {}", e)),
-        };
-
-        let response_text = response.text().await?;
+        let response = resp.map_err(ProviderError::Network)?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            log::error!(
+                "GeneralLatestLotteryRequest failed with status: {status}\n==== Response: ====\n {text}"
+            );
+            return Err(ProviderError::Status(status).into());
+        }
+
+        let response_text = response.text().await.map_err(ProviderError::Network)?;
 
         let api_response: GeneralLatestLotteryResponse = serde_json::from_str(&response_text)
             .map_err(|e| anyhow::anyhow!("Failed to parse JSON response: {}", e))?;
 
         if api_response.code != crate::api::provider::mxnzp::RETURN_CODE_SUCCESS {
-            return Err(anyhow::anyhow!("API returned error: {}", api_response.msg));
+            return Err(ProviderError::Api(api_response.msg).into());
         }
 
         Ok(api_response)
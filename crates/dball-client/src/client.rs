@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+
+/// Initialized dball library, returned by [`ClientBuilder::build`]
+///
+/// Building it loads the environment, installs the logger and resolves the
+/// database. Nothing panics and no `.env` file is required, so the crate can
+/// be embedded in applications with their own configuration and logger
+#[derive(Debug, Clone)]
+pub struct Client {
+    env_file: Option<PathBuf>,
+    database_url: String,
+}
+
+impl Client {
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Env file the environment was loaded from, if any
+    pub fn env_file(&self) -> Option<&Path> {
+        self.env_file.as_deref()
+    }
+
+    /// Database every service of the library uses
+    pub fn database_url(&self) -> &str {
+        &self.database_url
+    }
+}
+
+/// Options of [`Client`], every one of them may be left out
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    env_file: Option<PathBuf>,
+    log_level: Option<log::LevelFilter>,
+    database_url: Option<String>,
+}
+
+impl ClientBuilder {
+    /// Env file to load, without one a `.env` in the working directory or
+    /// its parents is loaded when present
+    pub fn env_file(mut self, env_file: Option<PathBuf>) -> Self {
+        self.env_file = env_file;
+        self
+    }
+
+    /// Level of the logger, `RUST_LOG` decides without one
+    pub fn log_level(mut self, log_level: Option<log::LevelFilter>) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    /// Database to use instead of `DATABASE_URL`
+    pub fn database_url(mut self, database_url: Option<String>) -> Self {
+        self.database_url = database_url;
+        self
+    }
+
+    /// Load the environment, install the logger unless the application has one
+    /// and settle the database
+    ///
+    /// The library keeps one environment and database per process, building
+    /// again with a different env file or database is an error
+    pub fn build(self) -> anyhow::Result<Client> {
+        let env_file = match self.env_file {
+            Some(path) => Some(crate::load_env_file(&path)?),
+            None => crate::ENV_GUARD.as_ref().ok().cloned(),
+        };
+
+        let mut logger = env_logger::Builder::from_default_env();
+        if let Some(level) = self.log_level {
+            logger.filter_level(level);
+        }
+        if logger.try_init().is_err() {
+            log::debug!("A logger is already installed, keeping it");
+        }
+        match &env_file {
+            Some(path) => log::debug!("Loaded environment from {}", path.display()),
+            None => log::debug!("No .env file found, using the process environment"),
+        }
+
+        if let Some(url) = &self.database_url {
+            crate::db::set_database_url(url)?;
+        }
+        let database_url = crate::db::database_url().context("No database configured")?;
+
+        Ok(Client {
+            env_file,
+            database_url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_client() -> anyhow::Result<()> {
+        let client = Client::builder()
+            .log_level(Some(log::LevelFilter::Debug))
+            .build()?;
+        assert!(client.env_file().is_some());
+        assert!(!client.database_url().is_empty());
+
+        // the environment of the process is already loaded from the repository's .env
+        let error = Client::builder()
+            .env_file(Some(PathBuf::from("/nonexistent/.env")))
+            .build()
+            .expect_err("Second env file");
+        assert!(error.to_string().contains("already loaded"), "{error}");
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+//! Time as seen by the services and the daemon
+//!
+//! Everything that depends on the current time, like the next draw or the
+//! year to update, asks this module instead of [`Utc::now`], so the
+//! `simulation` feature can swap in [`crate::simulation::CLOCK`]
+
+use chrono::{DateTime, Utc};
+
+/// Current time, the simulated one under the `simulation` feature
+pub fn now() -> DateTime<Utc> {
+    #[cfg(feature = "simulation")]
+    {
+        crate::simulation::CLOCK.now()
+    }
+    #[cfg(not(feature = "simulation"))]
+    {
+        Utc::now()
+    }
+}
+
+/// Wait for `duration` of clock time, shortened by the speed of the simulated clock
+pub async fn sleep(duration: std::time::Duration) {
+    #[cfg(feature = "simulation")]
+    let duration = crate::simulation::CLOCK.real_duration(duration);
+    tokio::time::sleep(duration).await;
+}
@@ -0,0 +1,1140 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use chrono::{NaiveDate, NaiveTime, Weekday};
+use dball_combora::dball::{COST_PER_TICKET, DEFAULT_BATCH_SIZE};
+use dball_combora::generator::Generator;
+use dball_combora::generator::frequency::Frequency;
+use dball_combora::generator::rng::RngBackend;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::WeeklySchedule;
+
+const CONFIG_FILE: &str = "dball.toml";
+
+/// Unified application config stored in `dball.toml` next to the `.env` file
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct DballConfig {
+    pub budget: BudgetConfig,
+    pub crawl: CrawlConfig,
+    pub generator: GeneratorConfig,
+    pub maintenance: MaintenanceConfig,
+    pub notification: NotificationConfig,
+    pub refresh: RefreshConfig,
+    /// Draw schedules by game code, replacing the built-in ones, e.g. `[schedule.ssq]`
+    pub schedule: BTreeMap<String, ScheduleConfig>,
+    pub scheduler: SchedulerConfig,
+    pub spots: SpotsConfig,
+    pub ui: UiConfig,
+}
+
+/// Spending limits in yuan, `None` means unlimited
+///
+/// Saving spots that would take a spend over its limit is refused, weeks start
+/// on Monday and both weeks and months follow the timezone of the draw schedule
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub per_period: Option<u32>,
+    pub per_week: Option<u32>,
+    pub per_month: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct GeneratorConfig {
+    /// Name of the generator strategy, see [`Generator::name`]
+    pub strategy: String,
+    /// Spots generated for a period at once
+    pub batch_size: usize,
+    /// Latest draws a strategy using history learns from
+    pub frequency_window: usize,
+    /// Lean towards hot numbers in percent, 100 picks in proportion to the
+    /// frequency, 0 ignores it and negative values favour cold numbers
+    pub frequency_bias: i32,
+    /// Name of the random number generator, see [`RngBackend::name`]
+    pub rng: String,
+}
+
+impl GeneratorConfig {
+    /// Variable naming a strategy that takes precedence over [`Self::strategy`]
+    pub const STRATEGY_VAR: &str = "GENERATION_STRATEGY";
+
+    /// Generator named `requested`, else by [`Self::STRATEGY_VAR`], else by
+    /// [`Self::strategy`], names match case-insensitively
+    ///
+    /// Only an unknown `requested` name is an error, the others fall back to
+    /// the next choice and finally to `BlueMorn`
+    pub fn generator(&self, requested: Option<&str>) -> anyhow::Result<Generator> {
+        if let Some(name) = requested {
+            return Generator::from_name(name).ok_or_else(|| {
+                let known = Generator::ALL.map(|generator| generator.name()).join(", ");
+                anyhow::anyhow!("Unknown generator `{name}`, one of {known}")
+            });
+        }
+        if let Ok(name) = std::env::var(Self::STRATEGY_VAR) {
+            match Generator::from_name(&name) {
+                Some(generator) => return Ok(generator),
+                None => log::warn!("Unknown generator `{name}` in {}", Self::STRATEGY_VAR),
+            }
+        }
+        Ok(Generator::from_name(&self.strategy).unwrap_or_else(|| {
+            log::warn!("Unknown generator `{}`, using BlueMorn", self.strategy);
+            Generator::BlueMorn
+        }))
+    }
+
+    /// [`Self::frequency_bias`] as the exponent [`Generator::create_generator_with_history`] takes
+    pub fn bias(&self) -> f64 {
+        f64::from(self.frequency_bias) / 100.0
+    }
+
+    /// [`Self::rng`] as a backend, the default one for unknown names
+    pub fn rng_backend(&self) -> RngBackend {
+        RngBackend::from_name(&self.rng).unwrap_or_default()
+    }
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            strategy: Generator::BlueMorn.name().to_owned(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            frequency_window: Frequency::DEFAULT_WINDOW,
+            frequency_bias: (Frequency::DEFAULT_BIAS * 100.0) as i32,
+            rng: RngBackend::default().name().to_owned(),
+        }
+    }
+}
+
+/// Fetching the tickets of whole years from the lottery provider
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct CrawlConfig {
+    /// Periods fetched at the same time, the provider's QPS limit still spaces the requests
+    pub concurrency: usize,
+    /// Attempts at fetching a period when the provider is unreachable or overloaded
+    pub max_attempts: u32,
+    /// Wait before the second attempt in milliseconds, doubled for every further one
+    pub backoff_ms: u64,
+}
+
+impl CrawlConfig {
+    /// Most periods fetched at the same time
+    pub const MAX_CONCURRENCY: usize = 16;
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            max_attempts: 3,
+            backoff_ms: 1000,
+        }
+    }
+}
+
+/// Upkeep of the database run by the daemon: WAL checkpoint, `ANALYZE` and
+/// `VACUUM` when enough pages are free
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    /// Hours between two runs, 0 turns the upkeep off
+    pub interval_hours: u64,
+    /// Vacuum once free pages make up this percent of the database file, 0 never vacuums
+    pub vacuum_free_percent: u32,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            interval_hours: 24,
+            vacuum_free_percent: 20,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// URLs the daemon POSTs won prizes to, several separated by commas
+    pub webhook_url: Option<String>,
+    /// Address the daemon mails draw results and won prizes to through [`Self::smtp`]
+    pub email: Option<String>,
+    /// Chat the Telegram bot of [`Self::TELEGRAM_TOKEN_VAR`] sends draw results to
+    pub telegram_chat_id: Option<String>,
+    /// Answer `/status` and `/generate` sent to the bot from [`Self::telegram_chat_id`]
+    pub telegram_commands: bool,
+    /// Suppress notification toasts in the clients
+    pub do_not_disturb: bool,
+    /// Lowest prize tier sent out, 1 only sends the first prize and 6 every win
+    pub min_tier: u8,
+    pub smtp: SmtpConfig,
+}
+
+impl NotificationConfig {
+    /// Variable holding the token of the Telegram bot, kept out of `dball.toml`
+    pub const TELEGRAM_TOKEN_VAR: &str = "DBALL_TELEGRAM_TOKEN";
+
+    /// Every URL of [`Self::webhook_url`]
+    pub fn webhook_urls(&self) -> Vec<&str> {
+        self.webhook_url
+            .iter()
+            .flat_map(|urls| urls.split(','))
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .collect()
+    }
+
+    /// Whether a prize of `tier` is sent out, see [`Self::min_tier`]
+    pub fn sends_tier(&self, tier: u8) -> bool {
+        tier <= self.min_tier
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            email: None,
+            telegram_chat_id: None,
+            telegram_commands: false,
+            do_not_disturb: false,
+            min_tier: 6,
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+/// Mail server sending to [`NotificationConfig::email`], the password comes
+/// from [`Self::PASSWORD_VAR`] rather than `dball.toml`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct SmtpConfig {
+    /// Server host, no mail is sent without one
+    pub host: Option<String>,
+    pub port: u16,
+    /// Login of the server, none to send without authentication
+    pub username: Option<String>,
+    /// Sender address, the username without one
+    pub from: Option<String>,
+    /// Connect with TLS right away, usually on port 465, instead of upgrading with STARTTLS
+    pub tls: bool,
+}
+
+impl SmtpConfig {
+    pub const PASSWORD_VAR: &str = "DBALL_SMTP_PASSWORD";
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: 587,
+            username: None,
+            from: None,
+            tls: false,
+        }
+    }
+}
+
+/// Client refresh intervals in seconds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct RefreshConfig {
+    pub state_secs: u64,
+    pub spots_secs: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            state_secs: 5,
+            spots_secs: 60,
+        }
+    }
+}
+
+/// Draw schedule of a game, see [`WeeklySchedule`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// IANA timezone of the draw days and time, e.g. `Asia/Shanghai`
+    pub timezone: String,
+    /// Draw days as `Mon` to `Sun`
+    pub weekdays: Vec<String>,
+    /// Local draw time as `HH:MM`
+    pub draw_time: String,
+    /// Dates without a draw as `YYYY-MM-DD`
+    pub blackout_dates: Vec<String>,
+    /// Dates with a one-off draw off the weekdays as `YYYY-MM-DD`
+    pub extra_dates: Vec<String>,
+    /// TOML file with further `blackout_dates` and `extra_dates`, e.g. the
+    /// suspensions of a Spring Festival, relative to `dball.toml`
+    pub overrides_file: Option<String>,
+}
+
+/// One-off changes to a schedule kept apart from `dball.toml`, see
+/// [`ScheduleConfig::overrides_file`]
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ScheduleOverrides {
+    blackout_dates: Vec<String>,
+    extra_dates: Vec<String>,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        WeeklySchedule::ssq().into()
+    }
+}
+
+impl From<WeeklySchedule> for ScheduleConfig {
+    fn from(schedule: WeeklySchedule) -> Self {
+        Self {
+            timezone: schedule.timezone.name().to_owned(),
+            weekdays: schedule.weekdays.iter().map(ToString::to_string).collect(),
+            draw_time: schedule.draw_time.format("%H:%M").to_string(),
+            blackout_dates: schedule
+                .blackout_dates
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            extra_dates: schedule
+                .extra_dates
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            overrides_file: None,
+        }
+    }
+}
+
+impl ScheduleConfig {
+    /// Parse the schedule
+    pub fn schedule(&self) -> anyhow::Result<WeeklySchedule> {
+        let timezone = self
+            .timezone
+            .parse()
+            .map_err(|e| anyhow::anyhow!("unknown timezone `{}`: {e}", self.timezone))?;
+        let weekdays = self
+            .weekdays
+            .iter()
+            .map(|day| {
+                day.parse::<Weekday>()
+                    .ok()
+                    .ok_or_else(|| anyhow::anyhow!("`{day}` is not a weekday"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if weekdays.is_empty() {
+            anyhow::bail!("needs at least one weekday");
+        }
+        let draw_time = NaiveTime::parse_from_str(&self.draw_time, "%H:%M")
+            .with_context(|| format!("`{}` is not a time as HH:MM", self.draw_time))?;
+        let mut blackout_dates = parse_dates(&self.blackout_dates)?;
+        let mut extra_dates = parse_dates(&self.extra_dates)?;
+        if let Some(file) = &self.overrides_file {
+            let overrides = Self::load_overrides(file)?;
+            blackout_dates.extend(parse_dates(&overrides.blackout_dates)?);
+            extra_dates.extend(parse_dates(&overrides.extra_dates)?);
+        }
+
+        Ok(WeeklySchedule {
+            timezone,
+            weekdays,
+            draw_time,
+            blackout_dates,
+            extra_dates,
+        })
+    }
+
+    fn load_overrides(file: &str) -> anyhow::Result<ScheduleOverrides> {
+        let path = crate::root_dir()?.join(file);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read overrides file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse overrides file {}", path.display()))
+    }
+}
+
+fn parse_dates(dates: &[String]) -> anyhow::Result<Vec<NaiveDate>> {
+    dates
+        .iter()
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .with_context(|| format!("`{date}` is not a date as YYYY-MM-DD"))
+        })
+        .collect()
+}
+
+/// Pipeline the daemon runs after every draw: update the latest ticket, settle
+/// the unprized spots and generate the next batch
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub enabled: bool,
+    /// Minutes after the draw the pipeline starts, giving the API time to publish it
+    pub delay_minutes: u64,
+    /// Attempts of a step before the run gives up
+    pub max_attempts: u32,
+    /// Seconds between two attempts of a step
+    pub retry_secs: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delay_minutes: 30,
+            max_attempts: 3,
+            retry_secs: 300,
+        }
+    }
+}
+
+/// Saving spots
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct SpotsConfig {
+    /// What to do with a new spot repeating the numbers of a spot of its period
+    pub duplicates: DuplicatePolicy,
+}
+
+/// Handling of a spot repeating the numbers of another spot of the same period
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    /// Save it like any other spot
+    #[default]
+    Allow,
+    /// Save it tagged as a duplicate
+    Flag,
+    /// Refuse to save it along with the rest of its batch
+    Reject,
+}
+
+impl DuplicatePolicy {
+    pub const ALL: [Self; 3] = [Self::Allow, Self::Flag, Self::Reject];
+
+    /// Name as written in `dball.toml`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Flag => "flag",
+            Self::Reject => "reject",
+        }
+    }
+
+    /// Parse a name case-insensitively
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Language of the clients' labels, messages and notifications
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+}
+
+impl Locale {
+    pub const ALL: [Self; 2] = [Self::En, Self::ZhCn];
+
+    /// BCP 47 tag, as written in `dball.toml`
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::ZhCn => "zh-CN",
+        }
+    }
+
+    /// Name of the language in the language itself, for pickers
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::ZhCn => "简体中文",
+        }
+    }
+
+    /// Parse a tag case-insensitively, `zh` and `zh_CN` are taken for `zh-CN`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "en" | "en-us" | "en-gb" => Some(Self::En),
+            "zh" | "zh-cn" | "zh-hans" => Some(Self::ZhCn),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct UiConfig {
+    pub locale: Locale,
+}
+
+/// A validation problem of a single config field
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ConfigIssue {
+    /// Dotted field path, e.g. `budget.per_period`
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_owned(),
+            message: message.into(),
+        }
+    }
+}
+
+impl DballConfig {
+    /// Dotted paths of all editable fields, in display order
+    pub const FIELDS: [&str; 32] = [
+        "budget.per_period",
+        "budget.per_week",
+        "budget.per_month",
+        "crawl.concurrency",
+        "crawl.max_attempts",
+        "crawl.backoff_ms",
+        "generator.strategy",
+        "generator.batch_size",
+        "generator.frequency_window",
+        "generator.frequency_bias",
+        "generator.rng",
+        "maintenance.interval_hours",
+        "maintenance.vacuum_free_percent",
+        "notification.webhook_url",
+        "notification.email",
+        "notification.telegram_chat_id",
+        "notification.telegram_commands",
+        "notification.do_not_disturb",
+        "notification.min_tier",
+        "notification.smtp.host",
+        "notification.smtp.port",
+        "notification.smtp.username",
+        "notification.smtp.from",
+        "notification.smtp.tls",
+        "refresh.state_secs",
+        "refresh.spots_secs",
+        "scheduler.enabled",
+        "scheduler.delay_minutes",
+        "scheduler.max_attempts",
+        "scheduler.retry_secs",
+        "spots.duplicates",
+        "ui.locale",
+    ];
+
+    /// Load config from `path`, a missing file yields the default config
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            log::debug!("Config file {} not found, using defaults", path.display());
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write config file {}", path.display()))
+    }
+
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        self.validate_budget(&mut issues);
+        self.validate_crawl(&mut issues);
+        self.validate_generator(&mut issues);
+
+        if self.maintenance.interval_hours > 24 * 30 {
+            issues.push(ConfigIssue::new(
+                "maintenance.interval_hours",
+                "must be at most 720 hours, 0 turns the upkeep off",
+            ));
+        }
+        if self.maintenance.vacuum_free_percent > 100 {
+            issues.push(ConfigIssue::new(
+                "maintenance.vacuum_free_percent",
+                "must be between 0 and 100 percent",
+            ));
+        }
+
+        self.validate_notification(&mut issues);
+
+        for (field, secs) in [
+            ("refresh.state_secs", self.refresh.state_secs),
+            ("refresh.spots_secs", self.refresh.spots_secs),
+        ] {
+            if !(1..=3600).contains(&secs) {
+                issues.push(ConfigIssue::new(
+                    field,
+                    "must be between 1 and 3600 seconds",
+                ));
+            }
+        }
+
+        if self.scheduler.delay_minutes > 24 * 60 {
+            issues.push(ConfigIssue::new(
+                "scheduler.delay_minutes",
+                "must be at most 1440 minutes",
+            ));
+        }
+        if !(1..=10).contains(&self.scheduler.max_attempts) {
+            issues.push(ConfigIssue::new(
+                "scheduler.max_attempts",
+                "must be between 1 and 10 attempts",
+            ));
+        }
+        if !(1..=3600).contains(&self.scheduler.retry_secs) {
+            issues.push(ConfigIssue::new(
+                "scheduler.retry_secs",
+                "must be between 1 and 3600 seconds",
+            ));
+        }
+
+        for (game, schedule) in &self.schedule {
+            if let Err(e) = schedule.schedule() {
+                issues.push(ConfigIssue::new(&format!("schedule.{game}"), e.to_string()));
+            }
+        }
+
+        issues
+    }
+
+    /// Issues of the `[notification]` section, see [`Self::validate`]
+    fn validate_notification(&self, issues: &mut Vec<ConfigIssue>) {
+        if self
+            .notification
+            .webhook_urls()
+            .iter()
+            .any(|url| !(url.starts_with("http://") || url.starts_with("https://")))
+        {
+            issues.push(ConfigIssue::new(
+                "notification.webhook_url",
+                "must start with http:// or https://",
+            ));
+        }
+        let is_address = |email: &String| {
+            email
+                .split_once('@')
+                .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+        };
+        for (field, email) in [
+            ("notification.email", &self.notification.email),
+            ("notification.smtp.from", &self.notification.smtp.from),
+        ] {
+            if email.as_ref().is_some_and(|email| !is_address(email)) {
+                issues.push(ConfigIssue::new(field, "not a valid email address"));
+            }
+        }
+        if self.notification.smtp.port == 0 {
+            issues.push(ConfigIssue::new(
+                "notification.smtp.port",
+                "must be a port between 1 and 65535",
+            ));
+        }
+        if let Some(chat_id) = &self.notification.telegram_chat_id
+            && chat_id.parse::<i64>().is_err()
+        {
+            issues.push(ConfigIssue::new(
+                "notification.telegram_chat_id",
+                "must be a numeric chat id",
+            ));
+        }
+        if !(1..=6).contains(&self.notification.min_tier) {
+            issues.push(ConfigIssue::new(
+                "notification.min_tier",
+                "must be a prize tier between 1 and 6",
+            ));
+        }
+    }
+
+    /// Issues of the `[budget]` section, see [`Self::validate`]
+    fn validate_crawl(&self, issues: &mut Vec<ConfigIssue>) {
+        if !(1..=CrawlConfig::MAX_CONCURRENCY).contains(&self.crawl.concurrency) {
+            issues.push(ConfigIssue::new(
+                "crawl.concurrency",
+                format!(
+                    "must be between 1 and {} periods",
+                    CrawlConfig::MAX_CONCURRENCY
+                ),
+            ));
+        }
+        if !(1..=10).contains(&self.crawl.max_attempts) {
+            issues.push(ConfigIssue::new(
+                "crawl.max_attempts",
+                "must be between 1 and 10 attempts",
+            ));
+        }
+        if self.crawl.backoff_ms > 60_000 {
+            issues.push(ConfigIssue::new(
+                "crawl.backoff_ms",
+                "must be at most 60000 milliseconds",
+            ));
+        }
+    }
+
+    fn validate_budget(&self, issues: &mut Vec<ConfigIssue>) {
+        for (field, budget) in [
+            ("budget.per_period", self.budget.per_period),
+            ("budget.per_week", self.budget.per_week),
+            ("budget.per_month", self.budget.per_month),
+        ] {
+            if budget == Some(0) {
+                issues.push(ConfigIssue::new(
+                    field,
+                    "must be greater than 0, leave empty for unlimited",
+                ));
+            }
+        }
+        if let (Some(period), Some(week)) = (self.budget.per_period, self.budget.per_week)
+            && week < period
+        {
+            issues.push(ConfigIssue::new(
+                "budget.per_week",
+                format!("must not be less than the per period budget {period}"),
+            ));
+        }
+        if let (Some(period), Some(month)) = (self.budget.per_period, self.budget.per_month)
+            && month < period
+        {
+            issues.push(ConfigIssue::new(
+                "budget.per_month",
+                format!("must not be less than the per period budget {period}"),
+            ));
+        }
+    }
+
+    /// Issues of the `[generator]` section, see [`Self::validate`]
+    fn validate_generator(&self, issues: &mut Vec<ConfigIssue>) {
+        if Generator::from_name(&self.generator.strategy).is_none() {
+            issues.push(ConfigIssue::new(
+                "generator.strategy",
+                format!("unknown strategy `{}`", self.generator.strategy),
+            ));
+        }
+        if !(1..=50).contains(&self.generator.batch_size) {
+            issues.push(ConfigIssue::new(
+                "generator.batch_size",
+                "must be between 1 and 50 spots",
+            ));
+        }
+        if let Some(budget) = self.budget.per_period
+            && budget > 0
+            && self.generator.batch_size * COST_PER_TICKET > budget as usize
+        {
+            issues.push(ConfigIssue::new(
+                "generator.batch_size",
+                format!("a batch costs more than the per period budget {budget}"),
+            ));
+        }
+        if !(1..=5000).contains(&self.generator.frequency_window) {
+            issues.push(ConfigIssue::new(
+                "generator.frequency_window",
+                "must be between 1 and 5000 draws",
+            ));
+        }
+        if !(-500..=500).contains(&self.generator.frequency_bias) {
+            issues.push(ConfigIssue::new(
+                "generator.frequency_bias",
+                "must be between -500 and 500 percent",
+            ));
+        }
+        if RngBackend::from_name(&self.generator.rng).is_none() {
+            let known = RngBackend::ALL.map(|rng| rng.name()).join(", ");
+            issues.push(ConfigIssue::new(
+                "generator.rng",
+                format!("unknown generator `{}`, one of {known}", self.generator.rng),
+            ));
+        }
+    }
+
+    /// Draw schedule of `game`, the configured one or else the built-in one
+    pub fn draw_schedule(&self, game: &str) -> anyhow::Result<WeeklySchedule> {
+        match self.schedule.get(game) {
+            Some(schedule) => schedule
+                .schedule()
+                .with_context(|| format!("Invalid draw schedule of `{game}`")),
+            None => WeeklySchedule::builtin(game)
+                .ok_or_else(|| anyhow::anyhow!("No draw schedule for game `{game}`")),
+        }
+    }
+
+    /// Display value of a field from [`Self::FIELDS`], empty for unset optional fields
+    pub fn field_value(&self, field: &str) -> Option<String> {
+        let value = match field {
+            "budget.per_period" => self.budget.per_period.map(|v| v.to_string()),
+            "budget.per_week" => self.budget.per_week.map(|v| v.to_string()),
+            "budget.per_month" => self.budget.per_month.map(|v| v.to_string()),
+            "crawl.concurrency" => Some(self.crawl.concurrency.to_string()),
+            "crawl.max_attempts" => Some(self.crawl.max_attempts.to_string()),
+            "crawl.backoff_ms" => Some(self.crawl.backoff_ms.to_string()),
+            "generator.strategy" => Some(self.generator.strategy.clone()),
+            "generator.batch_size" => Some(self.generator.batch_size.to_string()),
+            "generator.frequency_window" => Some(self.generator.frequency_window.to_string()),
+            "generator.frequency_bias" => Some(self.generator.frequency_bias.to_string()),
+            "generator.rng" => Some(self.generator.rng.clone()),
+            "maintenance.interval_hours" => Some(self.maintenance.interval_hours.to_string()),
+            "maintenance.vacuum_free_percent" => {
+                Some(self.maintenance.vacuum_free_percent.to_string())
+            }
+            "notification.webhook_url" => self.notification.webhook_url.clone(),
+            "notification.email" => self.notification.email.clone(),
+            "notification.telegram_chat_id" => self.notification.telegram_chat_id.clone(),
+            "notification.telegram_commands" => {
+                Some(self.notification.telegram_commands.to_string())
+            }
+            "notification.do_not_disturb" => Some(self.notification.do_not_disturb.to_string()),
+            "notification.min_tier" => Some(self.notification.min_tier.to_string()),
+            "notification.smtp.host" => self.notification.smtp.host.clone(),
+            "notification.smtp.port" => Some(self.notification.smtp.port.to_string()),
+            "notification.smtp.username" => self.notification.smtp.username.clone(),
+            "notification.smtp.from" => self.notification.smtp.from.clone(),
+            "notification.smtp.tls" => Some(self.notification.smtp.tls.to_string()),
+            "refresh.state_secs" => Some(self.refresh.state_secs.to_string()),
+            "refresh.spots_secs" => Some(self.refresh.spots_secs.to_string()),
+            "scheduler.enabled" => Some(self.scheduler.enabled.to_string()),
+            "scheduler.delay_minutes" => Some(self.scheduler.delay_minutes.to_string()),
+            "scheduler.max_attempts" => Some(self.scheduler.max_attempts.to_string()),
+            "scheduler.retry_secs" => Some(self.scheduler.retry_secs.to_string()),
+            "spots.duplicates" => Some(self.spots.duplicates.name().to_owned()),
+            "ui.locale" => Some(self.ui.locale.tag().to_owned()),
+            _ => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+
+    /// Set a field from [`Self::FIELDS`] from its text form, an empty value unsets optional fields
+    ///
+    /// Only checks that the value parses, use [`Self::validate`] for the field constraints
+    #[expect(clippy::too_many_lines)]
+    pub fn set_field(&mut self, field: &str, value: &str) -> anyhow::Result<()> {
+        let value = value.trim();
+        let optional = || (!value.is_empty()).then(|| value.to_owned());
+        let number = |value: &str| -> anyhow::Result<Option<u32>> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value
+                .parse()
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("`{value}` is not a number: {e}"))
+        };
+        let flag = |value: &str| -> anyhow::Result<bool> {
+            match value.to_ascii_lowercase().as_str() {
+                "true" | "on" | "yes" => Ok(true),
+                "false" | "off" | "no" | "" => Ok(false),
+                _ => anyhow::bail!("`{value}` is not one of true/false"),
+            }
+        };
+        let seconds = |value: &str| -> anyhow::Result<u64> {
+            value
+                .parse()
+                .map_err(|e| anyhow::anyhow!("`{value}` is not a number of seconds: {e}"))
+        };
+
+        match field {
+            "budget.per_period" => self.budget.per_period = number(value)?,
+            "budget.per_week" => self.budget.per_week = number(value)?,
+            "budget.per_month" => self.budget.per_month = number(value)?,
+            "crawl.concurrency" => {
+                self.crawl.concurrency = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of periods: {e}"))?;
+            }
+            "crawl.max_attempts" => {
+                self.crawl.max_attempts = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of attempts: {e}"))?;
+            }
+            "crawl.backoff_ms" => {
+                self.crawl.backoff_ms = value.parse().map_err(|e| {
+                    anyhow::anyhow!("`{value}` is not a number of milliseconds: {e}")
+                })?;
+            }
+            "generator.strategy" => value.clone_into(&mut self.generator.strategy),
+            "generator.batch_size" => {
+                self.generator.batch_size = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of spots: {e}"))?;
+            }
+            "generator.frequency_window" => {
+                self.generator.frequency_window = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of draws: {e}"))?;
+            }
+            "generator.frequency_bias" => {
+                self.generator.frequency_bias = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a percentage: {e}"))?;
+            }
+            "generator.rng" => value.clone_into(&mut self.generator.rng),
+            "maintenance.interval_hours" => {
+                self.maintenance.interval_hours = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of hours: {e}"))?;
+            }
+            "maintenance.vacuum_free_percent" => {
+                self.maintenance.vacuum_free_percent = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a percentage: {e}"))?;
+            }
+            "notification.webhook_url" => self.notification.webhook_url = optional(),
+            "notification.email" => self.notification.email = optional(),
+            "notification.telegram_chat_id" => self.notification.telegram_chat_id = optional(),
+            "notification.telegram_commands" => {
+                self.notification.telegram_commands = flag(value)?;
+            }
+            "notification.do_not_disturb" => self.notification.do_not_disturb = flag(value)?,
+            "notification.min_tier" => {
+                self.notification.min_tier = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a prize tier: {e}"))?;
+            }
+            "notification.smtp.host" => self.notification.smtp.host = optional(),
+            "notification.smtp.port" => {
+                self.notification.smtp.port = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a port: {e}"))?;
+            }
+            "notification.smtp.username" => self.notification.smtp.username = optional(),
+            "notification.smtp.from" => self.notification.smtp.from = optional(),
+            "notification.smtp.tls" => self.notification.smtp.tls = flag(value)?,
+            "refresh.state_secs" => self.refresh.state_secs = seconds(value)?,
+            "refresh.spots_secs" => self.refresh.spots_secs = seconds(value)?,
+            "scheduler.enabled" => self.scheduler.enabled = flag(value)?,
+            "scheduler.delay_minutes" => {
+                self.scheduler.delay_minutes = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of minutes: {e}"))?;
+            }
+            "scheduler.max_attempts" => {
+                self.scheduler.max_attempts = value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("`{value}` is not a number of attempts: {e}"))?;
+            }
+            "scheduler.retry_secs" => self.scheduler.retry_secs = seconds(value)?,
+            "spots.duplicates" => {
+                self.spots.duplicates = DuplicatePolicy::from_name(value).ok_or_else(|| {
+                    let names = DuplicatePolicy::ALL.map(DuplicatePolicy::name).join(", ");
+                    anyhow::anyhow!("`{value}` is not one of {names}")
+                })?;
+            }
+            "ui.locale" => {
+                self.ui.locale = Locale::from_tag(value).ok_or_else(|| {
+                    let tags = Locale::ALL.map(Locale::tag).join(", ");
+                    anyhow::anyhow!("`{value}` is not one of {tags}")
+                })?;
+            }
+            _ => anyhow::bail!("Unknown config field `{field}`"),
+        }
+        Ok(())
+    }
+}
+
+/// Path of `dball.toml`, located next to the `.env` file or in the working directory without one
+pub fn config_path() -> anyhow::Result<PathBuf> {
+    Ok(crate::root_dir()?.join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::SSQ;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(DballConfig::default().validate().is_empty());
+    }
+
+    #[test]
+    fn test_config_toml_roundtrip() -> anyhow::Result<()> {
+        let mut config = DballConfig::default();
+        config.set_field("budget.per_period", "20")?;
+        config.set_field("notification.email", "me@example.com")?;
+        config.set_field("notification.do_not_disturb", "on")?;
+        assert!(config.notification.do_not_disturb);
+        config.set_field("ui.locale", "zh_CN")?;
+        assert_eq!(config.ui.locale, Locale::ZhCn);
+        config.set_field("spots.duplicates", "Reject")?;
+        assert_eq!(config.spots.duplicates, DuplicatePolicy::Reject);
+        assert!(config.set_field("spots.duplicates", "warn").is_err());
+
+        let parsed: DballConfig = toml::from_str(&toml::to_string_pretty(&config)?)?;
+        assert_eq!(parsed, config);
+
+        let partial: DballConfig = toml::from_str("[budget]\nper_month = 100\n")?;
+        assert_eq!(partial.budget.per_month, Some(100));
+        assert_eq!(partial.refresh, RefreshConfig::default());
+
+        let chinese: DballConfig = toml::from_str("[ui]\nlocale = \"zh-CN\"\n")?;
+        assert_eq!(chinese.ui.locale, Locale::ZhCn);
+        Ok(())
+    }
+
+    #[test]
+    fn test_generator_strategy() -> anyhow::Result<()> {
+        let mut config = GeneratorConfig {
+            strategy: "markov".to_owned(),
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(config.generator(Some("Anneal"))?, Generator::Anneal);
+        assert_eq!(config.generator(Some(" frequency "))?, Generator::Frequency);
+        assert!(config.generator(Some("Genetic")).is_err());
+        if std::env::var(GeneratorConfig::STRATEGY_VAR).is_err() {
+            assert_eq!(config.generator(None)?, Generator::Markov);
+            config.strategy = "Unknown".to_owned();
+            assert_eq!(config.generator(None)?, Generator::BlueMorn);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_validation_issues() -> anyhow::Result<()> {
+        let mut config = DballConfig::default();
+        config.set_field("budget.per_period", "50")?;
+        config.set_field("budget.per_week", "30")?;
+        config.set_field("budget.per_month", "20")?;
+        config.set_field("crawl.concurrency", "0")?;
+        config.set_field("crawl.max_attempts", "0")?;
+        assert!(config.set_field("crawl.backoff_ms", "-1").is_err());
+        config.set_field("generator.strategy", "Unknown")?;
+        config.set_field("generator.batch_size", "30")?;
+        config.set_field("generator.frequency_bias", "-900")?;
+        config.set_field("generator.rng", "ChaCha")?;
+        config.set_field("maintenance.vacuum_free_percent", "150")?;
+        assert!(
+            config
+                .set_field("maintenance.interval_hours", "-1")
+                .is_err()
+        );
+        assert!(
+            config
+                .set_field("generator.frequency_window", "-1")
+                .is_err()
+        );
+        config.set_field(
+            "notification.webhook_url",
+            "https://example.com/hook, example.com/hook",
+        )?;
+        config.set_field("notification.min_tier", "0")?;
+        config.set_field("notification.smtp.from", "dball")?;
+        assert!(config.set_field("notification.smtp.port", "70000").is_err());
+        config.set_field("refresh.state_secs", "0")?;
+        config.set_field("scheduler.max_attempts", "0")?;
+        config.set_field("scheduler.enabled", "off")?;
+        assert!(!config.scheduler.enabled);
+        assert!(config.set_field("refresh.spots_secs", "soon").is_err());
+        assert!(config.set_field("ui.locale", "fr").is_err());
+
+        let fields = config
+            .validate()
+            .into_iter()
+            .map(|issue| issue.field)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                "budget.per_week",
+                "budget.per_month",
+                "crawl.concurrency",
+                "crawl.max_attempts",
+                "generator.strategy",
+                "generator.batch_size",
+                "generator.frequency_bias",
+                "generator.rng",
+                "maintenance.vacuum_free_percent",
+                "notification.webhook_url",
+                "notification.smtp.from",
+                "notification.min_tier",
+                "refresh.state_secs",
+                "scheduler.max_attempts",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_schedule_config() -> anyhow::Result<()> {
+        let config: DballConfig = toml::from_str(
+            "[schedule.ssq]\nweekdays = [\"Mon\", \"Fri\"]\nblackout_dates = [\"2026-02-16\"]\n",
+        )?;
+        let schedule = config.draw_schedule(SSQ)?;
+        assert_eq!(schedule.weekdays, vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!(schedule.timezone, WeeklySchedule::ssq().timezone);
+        assert_eq!(schedule.blackout_dates.len(), 1);
+        assert_eq!(
+            DballConfig::default().draw_schedule(SSQ)?,
+            WeeklySchedule::ssq()
+        );
+        assert!(config.draw_schedule("dlt").is_err());
+
+        let mut config = DballConfig::default();
+        config.schedule.insert(
+            SSQ.to_owned(),
+            ScheduleConfig {
+                timezone: "Mars/Olympus".to_owned(),
+                ..ScheduleConfig::default()
+            },
+        );
+        let issues = config.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "schedule.ssq");
+        Ok(())
+    }
+
+    #[test]
+    fn test_schedule_overrides_file() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("dball-overrides-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "blackout_dates = [\"2026-02-17\", \"2026-02-19\"]\nextra_dates = [\"2026-02-25\"]\n",
+        )?;
+        let config = ScheduleConfig {
+            blackout_dates: vec!["2026-02-22".to_owned()],
+            overrides_file: Some(path.display().to_string()),
+            ..ScheduleConfig::default()
+        };
+        let schedule = config.schedule();
+        std::fs::remove_file(&path)?;
+        let schedule = schedule?;
+        assert_eq!(schedule.blackout_dates.len(), 3);
+        assert_eq!(
+            schedule.extra_dates,
+            [NaiveDate::from_ymd_opt(2026, 2, 25).expect("Valid date")]
+        );
+
+        let missing = ScheduleConfig {
+            overrides_file: Some(path.display().to_string()),
+            ..ScheduleConfig::default()
+        };
+        assert!(missing.schedule().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_fields_are_readable() {
+        let config = DballConfig::default();
+        for field in DballConfig::FIELDS {
+            assert!(config.field_value(field).is_some(), "{field}");
+        }
+    }
+}
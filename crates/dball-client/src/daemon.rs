@@ -5,6 +5,7 @@
 pub mod ipc_server;
 pub mod lock;
 pub mod service;
+mod telegram;
 
 // 重新导出主要类型
 pub use ipc_server::IpcServer;
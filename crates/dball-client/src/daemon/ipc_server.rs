@@ -3,14 +3,24 @@ use std::path::Path;
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
 use tokio::net::{UnixListener, UnixStream};
-use tokio::sync::{RwLock, broadcast};
+use tokio::sync::{RwLock, broadcast, mpsc};
 use tokio::task::JoinHandle;
+use tracing::Instrument as _;
 
 use crate::ipc::{
     codec::{FrameBuffer, IpcCodec},
     envelope::{IpcEnvelope, IpcKind},
-    protocol::{AppState, ErrorMessage, HelloMessage, RpcService},
+    error::{RpcError, RpcErrorCode},
+    protocol::{AppState, ErrorMessage, EventMessage, EventType, HelloMessage, RpcService},
 };
+use crate::models::SpotQuery;
+use crate::service::DaemonEvent;
+
+/// Encoded frames queued for the writer task of one client
+type MessageSender = mpsc::UnboundedSender<Vec<u8>>;
+
+/// Id of the profile a client named in its Hello message, see [`crate::profile`]
+type ClientProfile = Arc<RwLock<Option<i32>>>;
 
 /// IPC Server
 /// Provides an asynchronous IPC server using Unix Domain Sockets
@@ -22,6 +32,9 @@ pub struct IpcServer {
     socket_path: String,
 }
 
+/// Actor recorded for changes asked for over IPC
+const ACTOR: &str = "ipc";
+
 impl IpcServer {
     /// Unix Domain Socket path
     #[cfg(unix)]
@@ -42,6 +55,9 @@ impl IpcServer {
         #[cfg(windows)]
         let socket_path = Self::PIPE_NAME.to_string();
 
+        #[cfg(feature = "simulation")]
+        let socket_path = crate::simulation::scoped_path(&socket_path);
+
         Ok(Self {
             state,
             state_broadcaster,
@@ -105,7 +121,7 @@ impl IpcServer {
     }
 
     async fn handle_client(
-        mut stream: UnixStream,
+        stream: UnixStream,
         state: Arc<RwLock<AppState>>,
         state_broadcaster: broadcast::Sender<AppState>,
     ) -> Result<()> {
@@ -114,10 +130,24 @@ impl IpcServer {
         let mut buffer = FrameBuffer::new();
         let mut read_buf = vec![0u8; 4096];
         let mut state_receiver = state_broadcaster.subscribe();
+        let mut event_receiver = crate::service::subscribe_events();
+        let profile = ClientProfile::default();
+
+        // requests run in their own tasks, so all writes go through one writer task
+        let (mut reader, mut writer) = stream.into_split();
+        let (sender, mut outgoing) = mpsc::unbounded_channel::<Vec<u8>>();
+        let writer_handle = tokio::spawn(async move {
+            while let Some(frame) = outgoing.recv().await {
+                if let Err(e) = writer.write_all(&frame).await {
+                    log::error!("Failed to write to client: {e}");
+                    break;
+                }
+            }
+        });
 
         loop {
             tokio::select! {
-                result = stream.read(&mut read_buf) => {
+                result = reader.read(&mut read_buf) => {
                     match result {
                         Ok(0) => {
                             log::info!("Client disconnected");
@@ -128,7 +158,7 @@ impl IpcServer {
 
                             // try to decode messages
                             while let Some(envelope) = buffer.try_decode::<serde_json::Value>()? {
-                                if let Err(e) = Self::process_message(envelope, &mut stream, &state).await {
+                                if let Err(e) = Self::process_message(envelope, &sender, &state, &profile).await {
                                     log::error!("Failed to process message: {e}");
                                 }
                             }
@@ -149,7 +179,7 @@ impl IpcServer {
                                 serde_json::to_value(&new_state)?
                             );
 
-                            if let Err(e) = Self::send_message(&mut stream, &event_envelope).await {
+                            if let Err(e) = Self::send_message(&sender, &event_envelope).await {
                                 log::error!("Failed to send state update: {e}");
                                 break;
                             }
@@ -163,23 +193,86 @@ impl IpcServer {
                         }
                     }
                 }
+
+                // forward service events
+                result = event_receiver.recv() => {
+                    match result {
+                        Ok(event) => {
+                            let event_envelope = IpcEnvelope::new(
+                                IpcKind::Event,
+                                serde_json::to_value(Self::event_message(&event)?)?
+                            );
+
+                            if let Err(e) = Self::send_message(&sender, &event_envelope).await {
+                                log::error!("Failed to send event: {e}");
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            log::warn!("Client lagged behind, {skipped} events dropped");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            log::info!("Event publisher closed");
+                            break;
+                        }
+                    }
+                }
             }
         }
 
+        writer_handle.abort();
         log::info!("Client handler finished");
         Ok(())
     }
 
+    fn event_message(event: &DaemonEvent) -> Result<EventMessage> {
+        let event_type = match event {
+            DaemonEvent::DrawSettled { .. } | DaemonEvent::CrawlProgress { .. } => {
+                EventType::TicketUpdate
+            }
+            DaemonEvent::PrizeWon { .. }
+            | DaemonEvent::PeriodEvaluated { .. }
+            | DaemonEvent::GenerationFinished { .. } => EventType::SpotUpdate,
+            DaemonEvent::ApiFailure { .. } => EventType::ApiStatus,
+        };
+        Ok(EventMessage {
+            event_type,
+            data: serde_json::to_value(event)?,
+            source: "daemon".to_owned(),
+        })
+    }
+
     /// Process incoming messages from the client
     async fn process_message(
         envelope: IpcEnvelope,
-        stream: &mut UnixStream,
+        sender: &MessageSender,
         state: &Arc<RwLock<AppState>>,
+        profile: &ClientProfile,
     ) -> Result<()> {
         match &envelope.kind {
-            IpcKind::Hello => Self::handle_hello(envelope, stream).await,
-            IpcKind::Subscribe => Self::handle_subscribe(envelope, stream, state).await,
-            IpcKind::Request(_rpc_service) => Self::handle_request(envelope, stream, state).await,
+            IpcKind::Hello => Self::handle_hello(envelope, sender, profile).await,
+            IpcKind::Subscribe => Self::handle_subscribe(envelope, sender, state).await,
+            IpcKind::Request(service) => {
+                let span = tracing::info_span!(
+                    "rpc",
+                    uuid = %envelope.uuid,
+                    service = <&'static str>::from(service),
+                    transport = "ipc"
+                );
+                // long requests must not block the connection, e.g. a cancel request
+                let sender = sender.clone();
+                let state = state.clone();
+                let profile_id = *profile.read().await;
+                tokio::spawn(
+                    crate::profile::scope(profile_id, async move {
+                        if let Err(e) = Self::handle_request(envelope, &sender, &state).await {
+                            log::error!("Failed to handle request: {e}");
+                        }
+                    })
+                    .instrument(span),
+                );
+                Ok(())
+            }
             _ => {
                 log::warn!("Unexpected message kind: {:?}", envelope.kind);
                 Ok(())
@@ -187,9 +280,26 @@ impl IpcServer {
         }
     }
 
-    /// Process Hello message from the client
-    async fn handle_hello(envelope: IpcEnvelope, stream: &mut UnixStream) -> Result<()> {
+    /// Process Hello message from the client, the requests following it act for
+    /// the profile it names
+    async fn handle_hello(
+        envelope: IpcEnvelope,
+        sender: &MessageSender,
+        profile: &ClientProfile,
+    ) -> Result<()> {
         log::info!("Received Hello message from client");
+        let hello = serde_json::from_value::<HelloMessage>(envelope.msg.clone())?;
+        if let Some(name) = &hello.profile {
+            match crate::service::profile_id(name).await {
+                Ok(id) => {
+                    log::info!("Client acts for profile {name} ({id})");
+                    *profile.write().await = Some(id);
+                }
+                Err(e) => {
+                    return Self::send_error(sender, envelope.uuid, 400, e.to_string()).await;
+                }
+            }
+        }
 
         // 创建Hello响应
         let hello_response = HelloMessage {
@@ -200,7 +310,11 @@ impl IpcServer {
                 "basic_rpc".to_owned(),
                 "state_subscription".to_owned(),
                 "compression".to_owned(),
+                "progress".to_owned(),
+                "events".to_owned(),
+                "profiles".to_owned(),
             ],
+            profile: hello.profile,
         };
 
         let response_envelope = IpcEnvelope::new_with_uuid(
@@ -209,13 +323,13 @@ impl IpcServer {
             envelope.uuid,
         );
 
-        Self::send_message(stream, &response_envelope).await
+        Self::send_message(sender, &response_envelope).await
     }
 
     /// Process Subscribe message from the client
     async fn handle_subscribe(
         envelope: IpcEnvelope,
-        stream: &mut UnixStream,
+        sender: &MessageSender,
         state: &Arc<RwLock<AppState>>,
     ) -> Result<()> {
         log::info!("Received Subscribe message from client");
@@ -227,7 +341,7 @@ impl IpcServer {
             envelope.uuid,
         );
 
-        Self::send_message(stream, &response).await
+        Self::send_message(sender, &response).await
     }
 
     /// Get current application state
@@ -237,16 +351,71 @@ impl IpcServer {
         Ok(state.clone())
     }
 
-    /// Process and send message to the client
-    async fn send_message(stream: &mut UnixStream, envelope: &IpcEnvelope) -> Result<()> {
+    /// Encode and queue a message for the client
+    async fn send_message(sender: &MessageSender, envelope: &IpcEnvelope) -> Result<()> {
         let encoded = IpcCodec::encode(envelope)?;
-        stream.write_all(&encoded).await?;
-        Ok(())
+        sender
+            .send(encoded)
+            .map_err(|e| anyhow!("Client connection closed: {e}"))
+    }
+
+    /// Convert a service error to the error sent to the client of the request
+    fn rpc_error(request_id: &str) -> impl Fn(anyhow::Error) -> RpcError + '_ {
+        move |e| RpcError::from_anyhow(&e).with_request_id(request_id)
+    }
+
+    /// Run a long operation, streaming its progress to the client under the request uuid
+    async fn with_progress<F, T>(uuid: &str, sender: &MessageSender, operation: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let sender = sender.clone();
+        let request_uuid = uuid.to_owned();
+        crate::service::track_progress(
+            uuid.to_owned(),
+            move |progress| {
+                let sent = serde_json::to_value(progress)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|msg| {
+                        let envelope = IpcEnvelope::new_with_uuid(
+                            IpcKind::Progress,
+                            msg,
+                            request_uuid.clone(),
+                        );
+                        IpcCodec::encode(&envelope).map_err(anyhow::Error::from)
+                    })
+                    .and_then(|encoded| {
+                        sender
+                            .send(encoded)
+                            .map_err(|e| anyhow!("Client connection closed: {e}"))
+                    });
+                if let Err(e) = sent {
+                    log::warn!("Failed to send progress of request {request_uuid}: {e}");
+                }
+            },
+            operation,
+        )
+        .await?
+    }
+
+    /// Send every page of the query as a chunk under the request uuid, returning the number of spots
+    async fn stream_spots(uuid: &str, sender: &MessageSender, query: SpotQuery) -> Result<usize> {
+        let mut total = 0;
+        for page in crate::service::spot_pages(query)? {
+            let page = page?;
+            total += page.len();
+            let chunk = IpcEnvelope::new_with_uuid(
+                IpcKind::Chunk,
+                serde_json::to_value(page)?,
+                uuid.to_owned(),
+            );
+            Self::send_message(sender, &chunk).await?;
+        }
+        Ok(total)
     }
 
-    #[expect(unused)]
     async fn send_error(
-        stream: &mut UnixStream,
+        sender: &MessageSender,
         request_uuid: String,
         code: u32,
         message: String,
@@ -263,14 +432,14 @@ impl IpcServer {
             request_uuid,
         );
 
-        Self::send_message(stream, &error_envelope).await
+        Self::send_message(sender, &error_envelope).await
     }
 
     /// Process RPC request from the client
     #[expect(clippy::too_many_lines)]
     async fn handle_request(
         envelope: IpcEnvelope,
-        stream: &mut UnixStream,
+        sender: &MessageSender,
         state: &Arc<RwLock<AppState>>,
     ) -> Result<()> {
         log::debug!(
@@ -289,96 +458,548 @@ impl IpcServer {
                             serde_json::to_value(current_state)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
                     RpcService::UpdateLatestTicket => {
                         let ticket = crate::service::update_latest_ticket()
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(ticket)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
                     RpcService::GetLatestPeriod => {
                         let next_period = crate::service::get_next_period()
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(next_period)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
                     RpcService::UpdateAllUnprizeSpots => {
-                        let state = crate::service::update_all_unprize_spots()
-                            .await
-                            .map_err(|e| e.to_string());
+                        let state = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::update_all_unprize_spots(),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(state)?,
                             envelope.uuid,
                         );
 
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::EvaluatePeriod(period) => {
+                        let evaluation = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::evaluate_period(&period),
+                        )
+                        .await;
+                        if let Ok(evaluation) = &evaluation {
+                            state.write().await.total_return += evaluation.winnings_change as f64;
+                        }
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(
+                                evaluation.map_err(Self::rpc_error(&envelope.uuid)),
+                            )?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
                     }
                     RpcService::DeprecatedLastBatchUnprizedSpot => {
-                        let result = crate::service::deprecated_last_batch_unprized_spot()
+                        let result = crate::service::deprecated_last_batch_unprized_spot(ACTOR)
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(result)?,
                             envelope.uuid,
                         );
 
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
                     RpcService::GetUnprizeSpots => {
                         let dballs = crate::service::get_next_period_unprized_spots()
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(dballs)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetNextBatchSummary => {
+                        let summary = crate::service::next_batch_summary()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(summary)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
                     }
-                    RpcService::GetPrizedSpots => {
-                        let dballs = crate::service::get_prized_spots()
+                    RpcService::GetPrizedSpots(page) => {
+                        let dballs = crate::service::get_prized_spots(page)
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(dballs)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
-                    RpcService::GenerateBatchSpots => {
-                        let result = crate::service::generate_batch_spots()
+                    RpcService::FindSpots(filter, page) => {
+                        let spots = crate::service::find_spots(filter, page)
                             .await
-                            .map_err(|e| e.to_string());
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(spots)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetRoiAnalytics(window) => {
+                        let analytics = crate::service::roi_analytics(window)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(analytics)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetNumberFrequencies(window) => {
+                        let stats = crate::service::number_frequencies(window)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(stats)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetRoiReport => {
+                        let report = crate::service::roi_report()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(report)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetPeriodicReport(year, month) => {
+                        let report = crate::service::periodic_report(year, month)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(report)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::QuerySpots(query) => {
+                        let spots = crate::service::query_spots(query)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(spots)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::StreamSpots(query) => {
+                        let total = Self::stream_spots(&envelope.uuid, sender, query)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(total)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetTickets(period_prefix, page) => {
+                        let tickets = crate::service::get_tickets(period_prefix, page)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(tickets)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::ExportCsv(request) => {
+                        let csv = crate::service::export_csv(request)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(csv)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::SyncTicketLog(source) => {
+                        let report = crate::service::sync_from_archive(&source)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(report)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::ImportTickets(source) => {
+                        let report = crate::service::import_tickets_from_archive(&source)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(report)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::BackupDatabase(path) => {
+                        let path = crate::service::backup_database(path)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(path)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetDbHealth => {
+                        let result = crate::service::database_health()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
                         let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
                             serde_json::to_value(result)?,
                             envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetMetrics => {
+                        let result = Ok::<_, RpcError>(crate::metrics::snapshot());
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetConfig => {
+                        let config = crate::service::get_config()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(config)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::UpdateConfig(config) => {
+                        let issues = crate::service::update_config(*config)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(issues)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::DeprecateSpot(id) => {
+                        let result = crate::service::deprecate_spot(id, ACTOR)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::RestoreSpots(ids, reason) => {
+                        let result = crate::service::restore_spots(ids, ACTOR, reason)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::AnnotateSpot(id, note) => {
+                        let result = crate::service::annotate_spot(id, note)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::TagSpots(ids, tag) => {
+                        let result = crate::service::tag_spots(ids, tag)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::UntagSpots(ids, tag) => {
+                        let result = crate::service::untag_spots(ids, tag)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetTags => {
+                        let result = crate::service::list_tags()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetSpotTags(id) => {
+                        let result = crate::service::spot_tags(id)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GetDuplicateSpots => {
+                        let result = crate::service::duplicate_spots()
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::MarkSpotPurchased(id, purchased) => {
+                        let result = crate::service::mark_spot_purchased(id, purchased)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::AddManualSpot(manual) => {
+                        let result = crate::service::add_manual_spot(manual)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::RecordPurchase(purchase) => {
+                        let id = crate::service::record_purchase(purchase)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(id)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::ClaimPrize(claim) => {
+                        let id = crate::service::claim_prize(claim)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(id)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GenerateSpotsForPeriods(periods, strategy) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::generate_spots_for_periods(
+                                periods,
+                                strategy.as_deref(),
+                            ),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::SimulateStrategy(year, strategy) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::simulate_strategy(year, strategy.as_deref()),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::GenerateBatchSpots(strategy) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::generate_batch_spots(strategy.as_deref()),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::Recommend(request) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::recommend(request),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::CrawlAllTickets(dry_run) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::crawl_all_tickets(dry_run),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::UpdateTicketsWithYear(year, dry_run) if year > 0 => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::update_tickets_with_year(year as usize, dry_run),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::AuditTickets(request) => {
+                        let result = Self::with_progress(
+                            &envelope.uuid,
+                            sender,
+                            crate::service::audit_tickets(request),
+                        )
+                        .await
+                        .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(result)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
+                    }
+                    RpcService::CancelOperation(id) => {
+                        let cancelled = crate::service::cancel_operation(id)
+                            .await
+                            .map_err(Self::rpc_error(&envelope.uuid));
+                        let response = IpcEnvelope::new_with_uuid(
+                            IpcKind::Response,
+                            serde_json::to_value(cancelled)?,
+                            envelope.uuid,
+                        );
+                        Self::send_message(sender, &response).await
                     }
                     _ => {
                         // other RPC services are not implemented yet
-                        let response = IpcEnvelope::new(
+                        let error: Result<(), RpcError> = Err(RpcError::new(
+                            RpcErrorCode::InvalidRequest,
+                            format!("RPC service {service:?} not implemented yet"),
+                        )
+                        .with_request_id(envelope.uuid.clone()));
+                        let response = IpcEnvelope::new_with_uuid(
                             IpcKind::Response,
-                            serde_json::to_value(format!(
-                                "RPC service {service:?} not implemented yet"
-                            ))?,
+                            serde_json::to_value(error)?,
+                            envelope.uuid,
                         );
-                        Self::send_message(stream, &response).await
+                        Self::send_message(sender, &response).await
                     }
                 }
             }
@@ -415,7 +1036,9 @@ mod tests {
             pending_tickets: vec![],
             unprize_spots_count: 0,
             total_investment: 0.0,
+            theoretical_investment: 0.0,
             total_return: 0.0,
+            claimed_return: 0.0,
             api_status: crate::ipc::protocol::ApiStatusInfo {
                 api_provider: "test".to_owned(),
                 last_success: None,
@@ -426,6 +1049,7 @@ mod tests {
             daemon_uptime: Duration::from_secs(0),
             generation_status: crate::ipc::protocol::GenerationStatus::Idle,
             last_generation_time: None,
+            budget: Default::default(),
         };
 
         let state = Arc::new(RwLock::new(initial_state));
@@ -25,7 +25,10 @@ impl InstanceLock {
 
     /// Acquires an instance lock, ensuring that only one instance of the daemon is running at a time.
     pub async fn acquire() -> Result<Self> {
+        #[cfg(not(feature = "simulation"))]
         let lock_file_path = PathBuf::from(Self::LOCK_FILE_PATH);
+        #[cfg(feature = "simulation")]
+        let lock_file_path = PathBuf::from(crate::simulation::scoped_path(Self::LOCK_FILE_PATH));
 
         // Check if an existing lock file exists
         if lock_file_path.exists() {
@@ -3,8 +3,11 @@ use std::sync::Arc;
 use tokio::sync::{RwLock, broadcast};
 
 use super::{InstanceLock, IpcServer};
+use crate::config::{MaintenanceConfig, SchedulerConfig};
 use crate::ipc::protocol::AppState;
+use crate::models::{Investment, Winnings};
 use crate::server::HttpServer;
+use crate::service::BudgetStatus;
 
 /// daemon process main service
 ///
@@ -25,9 +28,23 @@ pub struct DaemonService {
 }
 
 impl DaemonService {
+    /// Interval of refreshing the state fields derived from local data
+    const STATE_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+    /// Interval of looking whether a turned off database upkeep was turned on
+    const MAINTENANCE_RECHECK_INTERVAL: std::time::Duration =
+        std::time::Duration::from_secs(60 * 60);
+    /// Interval of looking whether a turned off post-draw pipeline was turned on
+    const SCHEDULER_RECHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
     pub async fn new() -> Result<Self> {
         let instance_lock = InstanceLock::acquire().await?;
 
+        let applied =
+            tokio::task::spawn_blocking(crate::db::maintenance::run_pending_migrations).await??;
+        if !applied.is_empty() {
+            log::info!("Upgraded the database with {} migrations", applied.len());
+        }
+
         let initial_state = Self::create_initial_state().await?;
         let state = Arc::new(RwLock::new(initial_state.clone()));
 
@@ -60,7 +77,7 @@ impl DaemonService {
         let ipc_server = IpcServer::new(self.state.clone(), self.state_broadcaster.clone()).await?;
 
         self.ipc_server = Some(ipc_server);
-        self.http_server = Some(HttpServer::new(self.state.clone()));
+        self.http_server = Some(HttpServer::new(self.state.clone())?);
 
         log::info!("Daemon service started successfully");
         Ok(())
@@ -80,6 +97,21 @@ impl DaemonService {
             }
         });
 
+        // keep uptime, spot count, investment and draw countdown current for subscribers
+        let state = self.state.clone();
+        let state_broadcaster = self.state_broadcaster.clone();
+        let refresh_handle = tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            loop {
+                crate::clock::sleep(Self::STATE_REFRESH_INTERVAL).await;
+                Self::refresh_local_state(&state, &state_broadcaster, started.elapsed()).await;
+            }
+        });
+
+        let maintenance_handle = tokio::spawn(Self::maintain_database_periodically());
+        let scheduler_handle = tokio::spawn(Self::run_pipeline_after_draws());
+        let telegram_handle = tokio::spawn(super::telegram::answer_commands(self.state.clone()));
+
         // start IPC + HTTP servers
         if let Some(ref ipc_server) = self.ipc_server {
             let ipc_handle = ipc_server.start().await?;
@@ -100,6 +132,10 @@ impl DaemonService {
             }
             ipc_handle.abort();
         }
+        refresh_handle.abort();
+        maintenance_handle.abort();
+        scheduler_handle.abort();
+        telegram_handle.abort();
 
         log::info!("Daemon service stopped");
         Ok(())
@@ -144,12 +180,118 @@ impl DaemonService {
         self.state.read().await.clone()
     }
 
+    /// refresh the state fields that need no network access and broadcast the result
+    async fn refresh_local_state(
+        state: &Arc<RwLock<AppState>>,
+        state_broadcaster: &broadcast::Sender<AppState>,
+        uptime: std::time::Duration,
+    ) {
+        let unprize_spots_count = crate::db::spot::get_all_unprize_spots();
+        let next_draw_time = crate::service::next_draw_time(None).await;
+        let investment = crate::service::investment().await;
+        let winnings = crate::service::winnings().await;
+        let budget = crate::service::budget_status().await;
+
+        let mut state = state.write().await;
+        state.daemon_uptime = uptime;
+        match unprize_spots_count {
+            Ok(spots) => state.unprize_spots_count = spots.len() as u32,
+            Err(e) => log::warn!("Failed to count unprized spots: {e}"),
+        }
+        match next_draw_time {
+            Ok(time) => state.next_draw_time = Some(time),
+            Err(e) => log::warn!("Failed to compute next draw time: {e}"),
+        }
+        match investment {
+            Ok(investment) => {
+                state.total_investment = investment.realized;
+                state.theoretical_investment = investment.theoretical;
+            }
+            Err(e) => log::warn!("Failed to compute investment: {e}"),
+        }
+        match winnings {
+            Ok(winnings) => {
+                state.total_return = winnings.theoretical;
+                state.claimed_return = winnings.realized;
+            }
+            Err(e) => log::warn!("Failed to compute winnings: {e}"),
+        }
+        match budget {
+            Ok(budget) => state.budget = budget,
+            Err(e) => log::warn!("Failed to compute budget status: {e}"),
+        }
+        state.last_update = crate::clock::now();
+
+        if state_broadcaster.send(state.clone()).is_err() {
+            log::debug!("No client subscribed to state updates");
+        }
+    }
+
+    /// Run the database upkeep every `maintenance.interval_hours`, the config is
+    /// read again before each wait so changes apply without a restart
+    async fn maintain_database_periodically() -> ! {
+        loop {
+            let hours = match crate::service::get_config().await {
+                Ok(config) => config.maintenance.interval_hours,
+                Err(e) => {
+                    log::warn!("Failed to read the maintenance config: {e}");
+                    MaintenanceConfig::default().interval_hours
+                }
+            };
+            if hours == 0 {
+                crate::clock::sleep(Self::MAINTENANCE_RECHECK_INTERVAL).await;
+                continue;
+            }
+            crate::clock::sleep(std::time::Duration::from_secs(hours * 60 * 60)).await;
+            if let Err(e) = crate::service::maintain_database().await {
+                log::warn!("Database maintenance failed: {e}");
+            }
+        }
+    }
+
+    /// Run the post-draw pipeline `scheduler.delay_minutes` after every draw,
+    /// the config is read again before each wait so changes apply without a restart
+    ///
+    /// A draw whose delay has not passed yet when the daemon starts still gets its run
+    async fn run_pipeline_after_draws() -> ! {
+        loop {
+            let config = match crate::service::get_config().await {
+                Ok(config) => config.scheduler,
+                Err(e) => {
+                    log::warn!("Failed to read the scheduler config: {e}");
+                    SchedulerConfig::default()
+                }
+            };
+            if !config.enabled {
+                crate::clock::sleep(Self::SCHEDULER_RECHECK_INTERVAL).await;
+                continue;
+            }
+            let delay = chrono::TimeDelta::minutes(config.delay_minutes as i64);
+            let draw_time =
+                match crate::service::next_draw_time(Some(crate::clock::now() - delay)).await {
+                    Ok(time) => time,
+                    Err(e) => {
+                        log::warn!("Failed to compute the next draw time: {e}");
+                        crate::clock::sleep(Self::SCHEDULER_RECHECK_INTERVAL).await;
+                        continue;
+                    }
+                };
+            let wait = (draw_time + delay - crate::clock::now())
+                .to_std()
+                .unwrap_or_default();
+            log::info!("Running the post-draw pipeline for the draw at {draw_time} in {wait:?}");
+            crate::clock::sleep(wait).await;
+            if let Err(e) = crate::service::run_post_draw_pipeline(draw_time).await {
+                log::warn!("Post-draw pipeline failed: {e}");
+            }
+        }
+    }
+
     // TODO: remove this method once IPC server is fully implemented
     /// create initial application state
     async fn create_initial_state() -> Result<AppState> {
         use crate::db::{spot, tickets};
         use crate::ipc::protocol::{ApiStatusInfo, GenerationStatus};
-        use chrono::Utc;
         use std::time::Duration;
 
         // get latest ticket information
@@ -162,8 +304,16 @@ impl DaemonService {
                 (latest_period.period, next)
             }
             Err(e) => {
-                log::warn!("Failed to get latest period: {e}, using defaults");
-                ("25001".to_owned(), "25002".to_owned())
+                log::warn!("Failed to get latest period: {e}, using the stored tickets");
+                let current = tickets::get_latest_tickets(1)
+                    .ok()
+                    .and_then(|mut tickets| tickets.pop())
+                    .map_or_else(|| "25001".to_owned(), |ticket| ticket.period);
+                // counted on the draw calendar, which needs no network
+                let next = crate::service::get_next_period()
+                    .await
+                    .unwrap_or_else(|_| "25002".to_owned());
+                (current, next)
             }
         };
 
@@ -179,26 +329,18 @@ impl DaemonService {
             .unwrap_or(0);
 
         // calculate total investment and return
-        let (total_investment, total_return) = spot::get_all_spots()
-            .map(|spots| {
-                spots.iter().fold((0.0, 0.0), |(inv, ret), spot| {
-                    let investment = 2.0; // 每注2元
-                    let return_amount = spot
-                        .prize_status
-                        .map(|status| match status {
-                            1 => 10000000.0,
-                            2 => 200000.0,
-                            3 => 3000.0,
-                            4 => 200.0,
-                            5 => 10.0,
-                            6 => 5.0,
-                            _ => 0.0,
-                        })
-                        .unwrap_or(0.0);
-                    (inv + investment, ret + return_amount)
-                })
-            })
-            .unwrap_or((0.0, 0.0));
+        let investment = crate::service::investment().await.unwrap_or_else(|e| {
+            log::warn!("Failed to compute investment: {e}");
+            Investment::default()
+        });
+        let budget = crate::service::budget_status().await.unwrap_or_else(|e| {
+            log::warn!("Failed to compute budget status: {e}");
+            BudgetStatus::default()
+        });
+        let winnings = crate::service::winnings().await.unwrap_or_else(|e| {
+            log::warn!("Failed to compute winnings: {e}");
+            Winnings::default()
+        });
 
         Ok(AppState {
             current_period,
@@ -207,22 +349,30 @@ impl DaemonService {
                 .ok()
                 .and_then(|mut tickets| tickets.pop())
                 .map(|ticket| ticket.time.and_utc()),
-            next_draw_time: None,
+            next_draw_time: crate::service::next_draw_time(None).await.ok(),
             latest_ticket,
             pending_tickets: vec![],
             unprize_spots_count,
-            total_investment,
-            total_return,
+            total_investment: investment.realized,
+            theoretical_investment: investment.theoretical,
+            total_return: winnings.theoretical,
+            claimed_return: winnings.realized,
             api_status: ApiStatusInfo {
-                api_provider: "mxnzp".to_owned(),
+                api_provider: if cfg!(feature = "simulation") {
+                    "fixture"
+                } else {
+                    "mxnzp"
+                }
+                .to_owned(),
                 last_success: None,
                 success_rate: 0.0,
                 average_response_time: Duration::from_millis(1000),
             },
-            last_update: Utc::now(),
+            last_update: crate::clock::now(),
             daemon_uptime: Duration::from_secs(0),
             generation_status: GenerationStatus::Idle,
             last_generation_time: None,
+            budget,
         })
     }
 
@@ -297,7 +447,9 @@ mod tests {
             pending_tickets: vec![],
             unprize_spots_count: 0,
             total_investment: 0.0,
+            theoretical_investment: 0.0,
             total_return: 0.0,
+            claimed_return: 0.0,
             api_status: crate::ipc::protocol::ApiStatusInfo {
                 api_provider: "test".to_owned(),
                 last_success: None,
@@ -308,6 +460,7 @@ mod tests {
             daemon_uptime: Duration::from_secs(0),
             generation_status: crate::ipc::protocol::GenerationStatus::Idle,
             last_generation_time: None,
+            budget: Default::default(),
         };
 
         let _state = Arc::new(RwLock::new(initial_state.clone()));
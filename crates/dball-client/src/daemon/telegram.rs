@@ -0,0 +1,156 @@
+//! Commands sent to the Telegram bot from the configured chat, answered through
+//! the dispatcher of the HTTP API, see [`NotificationConfig::telegram_commands`]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use tokio::sync::RwLock;
+
+use crate::config::NotificationConfig;
+use crate::ipc::protocol::{AppState, RpcService};
+use crate::models::Spot;
+use crate::service::TelegramBot;
+
+/// Longest wait of a single poll for new messages
+const POLL_WAIT: Duration = Duration::from_secs(30);
+/// Interval of looking whether turned off commands were turned on, and of polling again after a failure
+const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const HELP: &str = "/status - state of the daemon\n/generate [strategy] - generate the next batch";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Status,
+    /// Generate the next batch with the named strategy, the configured one without
+    Generate(Option<String>),
+    Help,
+}
+
+impl Command {
+    /// Command of a message, `None` for text that is no command
+    fn parse(text: &str) -> Option<Self> {
+        let mut words = text.split_whitespace();
+        let command = words.next()?.strip_prefix('/')?;
+        // commands sent in groups name the bot, e.g. `/status@dball_bot`
+        let command = command
+            .split_once('@')
+            .map_or(command, |(command, _)| command);
+        Some(match command {
+            "status" => Self::Status,
+            "generate" => Self::Generate(words.next().map(str::to_owned)),
+            _ => Self::Help,
+        })
+    }
+
+    async fn answer(self, state: &Arc<RwLock<AppState>>) -> String {
+        let answer = match self {
+            Self::Status => call(RpcService::GetCurrentState, state)
+                .await
+                .map(|current: AppState| status_text(&current)),
+            Self::Generate(strategy) => generate(strategy, state).await,
+            Self::Help => Ok(HELP.to_owned()),
+        };
+        answer.unwrap_or_else(|e| format!("Failed: {e}"))
+    }
+}
+
+/// Answer the commands of the configured chat until aborted, the config is
+/// read again before each poll so changes apply without a restart
+pub(super) async fn answer_commands(state: Arc<RwLock<AppState>>) -> ! {
+    let mut offset = 0;
+    loop {
+        let config = match crate::service::get_config().await {
+            Ok(config) => config.notification,
+            Err(e) => {
+                log::warn!("Failed to read the notification config: {e}");
+                NotificationConfig::default()
+            }
+        };
+        let chat_id = config
+            .telegram_commands
+            .then_some(config.telegram_chat_id)
+            .flatten();
+        let (Some(chat_id), Some(bot)) = (chat_id, TelegramBot::from_env()) else {
+            crate::clock::sleep(RECHECK_INTERVAL).await;
+            continue;
+        };
+
+        let messages = match bot.messages(offset, POLL_WAIT).await {
+            Ok((messages, next)) => {
+                offset = next;
+                messages
+            }
+            Err(e) => {
+                log::warn!("Failed to poll the Telegram bot: {e:#}");
+                crate::clock::sleep(RECHECK_INTERVAL).await;
+                continue;
+            }
+        };
+        for message in messages {
+            // only the configured chat may command the daemon
+            if message.chat_id.to_string() != chat_id {
+                log::warn!("Ignoring a Telegram message from chat {}", message.chat_id);
+                continue;
+            }
+            let Some(command) = Command::parse(&message.text) else {
+                continue;
+            };
+            log::info!("Answering Telegram command {command:?}");
+            let answer = command.answer(&state).await;
+            if let Err(e) = bot.send_message(&chat_id, &answer).await {
+                log::warn!("Failed to answer on Telegram: {e:#}");
+            }
+        }
+    }
+}
+
+async fn call<T: DeserializeOwned>(
+    service: RpcService,
+    state: &Arc<RwLock<AppState>>,
+) -> Result<T, String> {
+    let value = crate::server::call_rpc_service(service, Arc::clone(state)).await?;
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+async fn generate(
+    strategy: Option<String>,
+    state: &Arc<RwLock<AppState>>,
+) -> Result<String, String> {
+    call::<()>(RpcService::GenerateBatchSpots(strategy), state).await?;
+    let spots = call::<Vec<Spot>>(RpcService::GetUnprizeSpots, state).await?;
+    Ok(format!(
+        "Generated, {} spots wait for the next draw",
+        spots.len()
+    ))
+}
+
+fn status_text(state: &AppState) -> String {
+    let next_draw = state.next_draw_time.map_or_else(
+        || "unknown".to_owned(),
+        |time| time.format("%Y-%m-%d %H:%M UTC").to_string(),
+    );
+    format!(
+        "Period {} is drawn at {next_draw}\n{} spots wait for the draw\nInvested {:.0} yuan, won {:.0} yuan",
+        state.next_period, state.unprize_spots_count, state.total_investment, state.total_return
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(Command::parse("/status"), Some(Command::Status));
+        assert_eq!(Command::parse(" /status@dball_bot "), Some(Command::Status));
+        assert_eq!(Command::parse("/generate"), Some(Command::Generate(None)));
+        assert_eq!(
+            Command::parse("/generate Markov"),
+            Some(Command::Generate(Some("Markov".to_owned())))
+        );
+        assert_eq!(Command::parse("/start"), Some(Command::Help));
+        assert_eq!(Command::parse("status"), None);
+        assert_eq!(Command::parse(""), None);
+    }
+}
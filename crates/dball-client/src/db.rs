@@ -1,12 +1,33 @@
 use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, CustomizeConnection, Pool, PooledConnection};
 use diesel::sqlite::SqliteConnection;
-use std::sync::LazyLock;
-
+use std::sync::{LazyLock, OnceLock};
+
+mod cache;
+#[cfg(feature = "sqlcipher")]
+pub(crate) mod cipher;
+pub mod draw_detail;
+pub mod generation_log;
+pub mod maintenance;
+pub mod notification_delivery;
+pub mod pipeline_run;
+pub mod prize_claim;
+pub mod profile;
+pub mod purchase;
 pub mod spot;
+pub mod tag;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod ticket_log;
 pub mod tickets;
 
+pub use maintenance::health;
+
+diesel::define_sql_function! {
+    /// Rowid of the last row inserted on the connection
+    fn last_insert_rowid() -> diesel::sql_types::Integer;
+}
+
 #[derive(Debug)]
 struct SqliteConnectionCustomizer;
 
@@ -14,6 +35,13 @@ impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqliteConnec
     fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
         use diesel::RunQueryDsl as _;
 
+        #[cfg(feature = "sqlcipher")]
+        cipher::unlock(conn).map_err(|e| {
+            diesel::r2d2::Error::ConnectionError(diesel::ConnectionError::BadConnection(format!(
+                "{e:#}"
+            )))
+        })?;
+
         // using WAL mode for better concurrency
         diesel::sql_query("PRAGMA journal_mode = WAL;")
             .execute(conn)
@@ -38,9 +66,33 @@ impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for SqliteConnec
     }
 }
 
-fn get_database_url() -> String {
-    #[cfg(not(test))]
-    let database_url = { std::env::var("DATABASE_URL").expect("DATABASE_URL must be set") };
+/// Database given to [`crate::ClientBuilder::database_url`], used instead of `DATABASE_URL`
+static DATABASE_URL: OnceLock<String> = OnceLock::new();
+
+/// Use `url` as the database of the process, which can only be set once
+pub(crate) fn set_database_url(url: &str) -> anyhow::Result<()> {
+    let current = DATABASE_URL.get_or_init(|| url.to_owned());
+    if *current != url {
+        anyhow::bail!("Database already set to {current}, cannot use {url}");
+    }
+    Ok(())
+}
+
+/// Database of the process: the one given to the client builder, then `DATABASE_URL`
+#[cfg_attr(
+    any(test, feature = "simulation"),
+    expect(clippy::unnecessary_wraps, reason = "tests always have a database")
+)]
+pub(crate) fn database_url() -> anyhow::Result<String> {
+    #[cfg(not(any(test, feature = "simulation")))]
+    let database_url = DATABASE_URL
+        .get()
+        .cloned()
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .ok_or_else(|| anyhow::anyhow!("DATABASE_URL must be set"))?;
+
+    #[cfg(all(feature = "simulation", not(test)))]
+    let database_url = crate::simulation::database_path().display().to_string();
 
     #[cfg(test)]
     let database_url = {
@@ -49,23 +101,24 @@ fn get_database_url() -> String {
         url.display().to_string()
     };
 
-    database_url
+    Ok(database_url)
 }
 
-static DB_POOL: LazyLock<Pool<ConnectionManager<SqliteConnection>>> = LazyLock::new(|| {
-    let database_url = get_database_url();
+static DB_POOL: LazyLock<anyhow::Result<Pool<ConnectionManager<SqliteConnection>>>> =
+    LazyLock::new(|| {
+        let database_url = database_url()?;
 
-    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
-    Pool::builder()
-        .max_size(10)
-        .connection_timeout(std::time::Duration::from_secs(30))
-        .connection_customizer(Box::new(SqliteConnectionCustomizer))
-        .build(manager)
-        .expect("Failed to create pool")
-});
+        let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+        Pool::builder()
+            .max_size(10)
+            .connection_timeout(std::time::Duration::from_secs(30))
+            .connection_customizer(Box::new(SqliteConnectionCustomizer))
+            .build(manager)
+            .map_err(|e| anyhow::Error::new(e).context("Failed to create pool"))
+    });
 
 pub fn establish_db_connection() -> anyhow::Result<SqliteConnection> {
-    let database_url = get_database_url();
+    let database_url = database_url()?;
     let mut conn = SqliteConnection::establish(&database_url).map_err(|e| {
         let err_message = format!("Error connecting to {database_url}: {e}");
         log::error!("{err_message}");
@@ -80,10 +133,13 @@ pub fn establish_db_connection() -> anyhow::Result<SqliteConnection> {
     Ok(conn)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn get_db_connection() -> anyhow::Result<PooledConnection<ConnectionManager<SqliteConnection>>> {
     DB_POOL
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("{e:#}"))?
         .get()
-        .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {}", e))
+        .map_err(|e| anyhow::Error::new(e).context("Failed to get DB connection"))
 }
 
 #[cfg(test)]
@@ -0,0 +1,117 @@
+//! Read-through cache of the queries run by every client refresh
+//!
+//! Entries are dropped whenever this process writes the table they were read
+//! from, and expire after [`MAX_AGE`] to catch up with writes of other
+//! processes sharing the database
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Longest time an entry is served without reading the database again
+const MAX_AGE: Duration = Duration::from_secs(10);
+
+/// Most entries kept, the cache starts over once full
+const CAPACITY: usize = 64;
+
+/// Results of one query by key, typically a period
+#[derive(Debug)]
+pub(crate) struct PeriodCache<K, V> {
+    state: Mutex<State<K, V>>,
+}
+
+#[derive(Debug)]
+struct State<K, V> {
+    /// Bumped on every invalidation, so a load racing a write is not kept
+    generation: u64,
+    entries: HashMap<K, (Instant, V)>,
+}
+
+impl<K, V> Default for PeriodCache<K, V> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                generation: 0,
+                entries: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> PeriodCache<K, V> {
+    /// The cached value of `key`, loaded with `load` when missing or expired
+    pub(crate) fn get_or_load(
+        &self,
+        key: K,
+        load: impl FnOnce() -> anyhow::Result<V>,
+    ) -> anyhow::Result<V> {
+        let generation = {
+            let state = self.lock();
+            if let Some((loaded, value)) = state.entries.get(&key)
+                && loaded.elapsed() < MAX_AGE
+            {
+                return Ok(value.clone());
+            }
+            state.generation
+        };
+
+        let loaded = Instant::now();
+        let value = load()?;
+        let mut state = self.lock();
+        if state.generation == generation {
+            if state.entries.len() >= CAPACITY {
+                state.entries.clear();
+            }
+            state.entries.insert(key, (loaded, value.clone()));
+        }
+        Ok(value)
+    }
+
+    /// Drop every entry, called after each write of the table read
+    pub(crate) fn invalidate(&self) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<K, V>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_period_cache() -> anyhow::Result<()> {
+        let cache = PeriodCache::<String, i32>::default();
+        let loads = Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Ok(loads.get())
+        };
+
+        assert_eq!(cache.get_or_load("2025001".to_owned(), load)?, 1);
+        assert_eq!(cache.get_or_load("2025001".to_owned(), load)?, 1);
+        assert_eq!(cache.get_or_load("2025002".to_owned(), load)?, 2);
+        assert!(
+            cache
+                .get_or_load("2025003".to_owned(), || anyhow::bail!("down"))
+                .is_err()
+        );
+
+        cache.invalidate();
+        assert_eq!(cache.get_or_load("2025001".to_owned(), load)?, 3);
+
+        // A load finishing after a write must not be kept
+        let racing = cache.get_or_load("2025004".to_owned(), || {
+            cache.invalidate();
+            Ok(10)
+        })?;
+        assert_eq!(racing, 10);
+        assert_eq!(cache.get_or_load("2025004".to_owned(), load)?, 4);
+        Ok(())
+    }
+}
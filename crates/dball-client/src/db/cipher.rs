@@ -0,0 +1,118 @@
+//! Database encrypted with `SQLCipher`, see the `sqlcipher` feature
+//!
+//! The passphrase comes from `DBALL_DB_PASSPHRASE`, otherwise from the keyring
+//! of the system under service `dball` and account `database`: the Secret
+//! Service through `secret-tool` on Linux, the login keychain through
+//! `security` on macOS. A database created this way is encrypted, an existing
+//! plain database cannot be opened with a passphrase
+use diesel::RunQueryDsl as _;
+use diesel::connection::SimpleConnection as _;
+use diesel::sqlite::SqliteConnection;
+use std::process::Command;
+use std::sync::LazyLock;
+
+const PASSPHRASE_VAR: &str = "DBALL_DB_PASSPHRASE";
+const KEYRING_SERVICE: &str = "dball";
+const KEYRING_ACCOUNT: &str = "database";
+
+/// Looked up once per process, every connection is keyed with it
+static PASSPHRASE: LazyLock<Result<String, String>> =
+    LazyLock::new(|| lookup_passphrase().map_err(|e| format!("{e:#}")));
+
+/// Key `connection` with the passphrase, before any other statement runs on it
+///
+/// A wrong passphrase only shows on the first read, so the schema is read
+/// right away to fail here instead of on some later query
+pub(crate) fn unlock(connection: &mut SqliteConnection) -> anyhow::Result<()> {
+    let passphrase = PASSPHRASE
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("No passphrase for the encrypted database: {e}"))?;
+    connection
+        .batch_execute(&key_pragma(passphrase))
+        .map_err(|e| anyhow::anyhow!("Error keying the database: {e}"))?;
+    diesel::sql_query("SELECT count(*) FROM sqlite_master")
+        .execute(connection)
+        .map_err(|e| anyhow::anyhow!("Error opening the database, wrong passphrase? {e}"))?;
+    Ok(())
+}
+
+/// `PRAGMA key` setting `passphrase`, quoted as an SQL string
+fn key_pragma(passphrase: &str) -> String {
+    format!("PRAGMA key = '{}';", passphrase.replace('\'', "''"))
+}
+
+fn lookup_passphrase() -> anyhow::Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_VAR)
+        && !passphrase.is_empty()
+    {
+        return Ok(passphrase);
+    }
+    keyring_passphrase()
+        .map_err(|e| anyhow::anyhow!("{PASSPHRASE_VAR} is not set and the keyring has none: {e}"))
+}
+
+fn keyring_passphrase() -> anyhow::Result<String> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("security");
+        command.args(["find-generic-password", "-s", KEYRING_SERVICE]);
+        command.args(["-a", KEYRING_ACCOUNT, "-w"]);
+        command
+    } else {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", KEYRING_SERVICE]);
+        command.args(["account", KEYRING_ACCOUNT]);
+        command
+    };
+    let output = command
+        .output()
+        .map_err(|e| anyhow::anyhow!("Error running {:?}: {e}", command.get_program()))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{:?} failed: {}",
+            command.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let passphrase = String::from_utf8(output.stdout)?
+        .trim_end_matches('\n')
+        .to_owned();
+    if passphrase.is_empty() {
+        anyhow::bail!("empty passphrase in the keyring");
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::Connection as _;
+
+    #[test]
+    fn test_key_pragma() {
+        assert_eq!(key_pragma("s3cret"), "PRAGMA key = 's3cret';");
+        assert_eq!(key_pragma("it's"), "PRAGMA key = 'it''s';");
+    }
+
+    #[test]
+    fn test_wrong_passphrase() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("dball_test_cipher_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let url = path.display().to_string();
+
+        let mut connection = SqliteConnection::establish(&url)?;
+        connection.batch_execute(&key_pragma("right"))?;
+        connection.batch_execute("CREATE TABLE secret (value TEXT)")?;
+        drop(connection);
+
+        let mut connection = SqliteConnection::establish(&url)?;
+        connection.batch_execute(&key_pragma("wrong"))?;
+        assert!(
+            diesel::sql_query("SELECT count(*) FROM sqlite_master")
+                .execute(&mut connection)
+                .is_err()
+        );
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+}
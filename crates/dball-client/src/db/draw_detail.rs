@@ -0,0 +1,59 @@
+use diesel::prelude::*;
+
+use crate::db::get_db_connection;
+use crate::models::DrawDetail;
+use crate::models::schema::draw_details;
+
+/// Insert `detail`, replacing the one of the same period
+#[tracing::instrument(skip_all, fields(period = %detail.period))]
+pub fn upsert_draw_detail(detail: &DrawDetail) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    diesel::replace_into(draw_details::table)
+        .values(detail)
+        .execute(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error upserting draw detail: {e}"))?;
+    Ok(())
+}
+
+pub fn get_draw_detail(period: &str) -> anyhow::Result<Option<DrawDetail>> {
+    let mut connection = get_db_connection()?;
+    draw_details::table
+        .find(period)
+        .first::<DrawDetail>(&mut connection)
+        .optional()
+        .map_err(|e| anyhow::anyhow!("Error loading draw detail of {period}: {e}"))
+}
+
+/// Up to `limit` details, the latest period first
+pub fn get_latest_draw_details(limit: i64) -> anyhow::Result<Vec<DrawDetail>> {
+    let mut connection = get_db_connection()?;
+    draw_details::table
+        .order(draw_details::period.desc())
+        .limit(limit)
+        .load::<DrawDetail>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading draw details: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_draw_detail() -> anyhow::Result<()> {
+        let period = "2025992";
+        let mut detail = DrawDetail::new(period, chrono::Utc::now().naive_utc());
+        detail.pool = Some(2_178_617_654);
+        upsert_draw_detail(&detail)?;
+        detail.set_tier(1, Some(4), Some(7_312_640));
+        upsert_draw_detail(&detail)?;
+
+        assert_eq!(get_draw_detail(period)?, Some(detail));
+        assert!(get_draw_detail("2025991")?.is_none());
+        assert!(
+            get_latest_draw_details(10)?
+                .iter()
+                .any(|detail| detail.period == period)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use diesel::prelude::*;
+
+use crate::db::spot::{insert_spots, invalidating};
+use crate::db::{get_db_connection, last_insert_rowid};
+use crate::models::schema::{generation_log, generation_log_spots};
+use crate::models::{GenerationLog, Spot};
+
+/// Insert the spots of every generated batch linked to the log of their batch,
+/// all or nothing, returns the ids of the spots in order
+#[tracing::instrument(skip_all, fields(batches = batches.len()))]
+pub fn insert_generated_spots(batches: &[(GenerationLog, Vec<Spot>)]) -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    invalidating(connection.transaction(|connection| {
+        let mut spot_ids = Vec::new();
+        for (log, spots) in batches {
+            diesel::insert_into(generation_log::table)
+                .values(log)
+                .execute(connection)?;
+            let generation_id =
+                diesel::select(last_insert_rowid()).get_result::<i32>(connection)?;
+            let ids = insert_spots(connection, spots)?;
+            for &spot_id in &ids {
+                diesel::insert_into(generation_log_spots::table)
+                    .values((
+                        generation_log_spots::generation_id.eq(generation_id),
+                        generation_log_spots::spot_id.eq(spot_id),
+                    ))
+                    .execute(connection)?;
+            }
+            spot_ids.extend(ids);
+        }
+        Ok(spot_ids)
+    }))
+    .map_err(|e: diesel::result::Error| {
+        anyhow::anyhow!("Error inserting {} generated batches: {e}", batches.len())
+    })
+}
+
+/// The latest `limit` generation logs, the latest one first
+pub fn get_latest_generation_logs(limit: i64) -> anyhow::Result<Vec<GenerationLog>> {
+    let mut connection = get_db_connection()?;
+    generation_log::table
+        .order(generation_log::id.desc())
+        .limit(limit)
+        .load::<GenerationLog>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading generation logs: {e}"))
+}
+
+/// Log of the batch the spot `spot_id` was generated in, `None` for spots
+/// added by hand or generated before batches were logged
+pub fn get_generation_log_by_spot_id(spot_id: i32) -> anyhow::Result<Option<GenerationLog>> {
+    let mut connection = get_db_connection()?;
+    generation_log::table
+        .inner_join(generation_log_spots::table)
+        .filter(generation_log_spots::spot_id.eq(spot_id))
+        .select(GenerationLog::as_select())
+        .first::<GenerationLog>(&mut connection)
+        .optional()
+        .map_err(|e| anyhow::anyhow!("Error loading generation log of spot {spot_id}: {e}"))
+}
+
+/// Ids of the spots generated in the batch `generation_id`
+pub fn get_generation_spot_ids(generation_id: i32) -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    generation_log_spots::table
+        .filter(generation_log_spots::generation_id.eq(generation_id))
+        .select(generation_log_spots::spot_id)
+        .order(generation_log_spots::spot_id.asc())
+        .load::<i32>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading spots of generation {generation_id}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dball_combora::dball::DBall;
+
+    #[test]
+    fn test_insert_generated_spots() -> anyhow::Result<()> {
+        let period = "2025996";
+        let spots = [[1, 8, 15, 19, 23, 29], [6, 12, 17, 24, 28, 32]]
+            .into_iter()
+            .map(|reds| Spot::from_dball(period, &DBall::new_one(reds, 2)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let log = GenerationLog {
+            id: None,
+            period: period.to_owned(),
+            generator: "Markov".to_owned(),
+            seed: Some(u64::MAX.to_string()),
+            score: 0.5,
+            attempts: 1,
+            duration_ms: 12,
+            created_time: chrono::Utc::now().naive_utc(),
+        };
+
+        let spot_ids = insert_generated_spots(&[(log.clone(), spots)])?;
+        assert_eq!(spot_ids.len(), 2);
+        let logged = get_generation_log_by_spot_id(spot_ids[0])?
+            .ok_or(anyhow::anyhow!("Generation log missing"))?;
+        assert_eq!(logged.seed, log.seed);
+        assert_eq!(logged.generator, log.generator);
+        let generation_id = logged
+            .id
+            .ok_or(anyhow::anyhow!("Generation log has no id"))?;
+        assert_eq!(get_generation_spot_ids(generation_id)?, spot_ids);
+        Ok(())
+    }
+}
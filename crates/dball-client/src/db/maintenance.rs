@@ -0,0 +1,373 @@
+use std::path::{Path, PathBuf};
+
+use diesel::connection::SimpleConnection as _;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+use diesel::sqlite::SqliteConnection;
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness as _, embed_migrations};
+use serde::{Deserialize, Serialize};
+
+use crate::db::get_db_connection;
+use crate::models::DbHealth;
+
+/// Migrations of the schema, built into the binary so upgrading needs no diesel CLI
+pub(crate) const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../../migrations");
+
+/// Schema migrations table of diesel, restored like any other table would
+/// mix up which migrations the restored rows were written with
+const MIGRATIONS_TABLE: &str = "__diesel_schema_migrations";
+
+#[derive(QueryableByName)]
+struct Name {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+/// What one run of [`run_maintenance`] did
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    /// Pages of the WAL written back to the database file
+    pub checkpointed_pages: i64,
+    /// Free pages and all pages of the database before a vacuum
+    pub free_pages: i64,
+    pub total_pages: i64,
+    pub vacuumed: bool,
+}
+
+#[derive(QueryableByName)]
+struct Checkpoint {
+    #[diesel(sql_type = BigInt)]
+    checkpointed: i64,
+}
+
+#[derive(QueryableByName)]
+struct IntegrityCheck {
+    #[diesel(sql_type = Text)]
+    integrity_check: String,
+}
+
+#[derive(QueryableByName)]
+struct Count {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Checkpoint the WAL, refresh the statistics of the query planner and
+/// vacuum once free pages make up `vacuum_free_percent` of the file, 0 never
+/// vacuums
+#[tracing::instrument]
+pub fn run_maintenance(vacuum_free_percent: u32) -> anyhow::Result<MaintenanceReport> {
+    let mut connection = get_db_connection()?;
+    maintain(&mut connection, vacuum_free_percent)
+}
+
+fn maintain(
+    connection: &mut SqliteConnection,
+    vacuum_free_percent: u32,
+) -> anyhow::Result<MaintenanceReport> {
+    let count = |connection: &mut SqliteConnection, pragma: &str| {
+        diesel::sql_query(format!("SELECT {pragma} AS count FROM pragma_{pragma}()"))
+            .get_result::<Count>(connection)
+            .map(|row| row.count)
+            .map_err(|e| anyhow::anyhow!("Error reading {pragma}: {e}"))
+    };
+
+    let checkpoint = diesel::sql_query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .get_result::<Checkpoint>(connection)
+        .map_err(|e| anyhow::anyhow!("Error checkpointing the WAL: {e}"))?;
+    connection
+        .batch_execute("ANALYZE")
+        .map_err(|e| anyhow::anyhow!("Error analyzing the database: {e}"))?;
+
+    let free_pages = count(connection, "freelist_count")?;
+    let total_pages = count(connection, "page_count")?;
+    let vacuumed = vacuum_free_percent > 0
+        && total_pages > 0
+        && free_pages * 100 >= total_pages * i64::from(vacuum_free_percent);
+    if vacuumed {
+        connection
+            .batch_execute("VACUUM")
+            .map_err(|e| anyhow::anyhow!("Error vacuuming the database: {e}"))?;
+    }
+    Ok(MaintenanceReport {
+        checkpointed_pages: checkpoint.checkpointed.max(0),
+        free_pages,
+        total_pages,
+        vacuumed,
+    })
+}
+
+/// Run `PRAGMA integrity_check` and measure the database file, its WAL and tables
+#[tracing::instrument]
+pub fn health() -> anyhow::Result<DbHealth> {
+    let mut connection = get_db_connection()?;
+    let mut health = check_health(&mut connection)?;
+    let wal = PathBuf::from(format!("{}-wal", super::database_url()?));
+    health.wal_bytes = std::fs::metadata(wal).map_or(0, |wal| wal.len());
+    Ok(health)
+}
+
+fn check_health(connection: &mut SqliteConnection) -> anyhow::Result<DbHealth> {
+    // the check stops after this many problems
+    const MAX_PROBLEMS: usize = 100;
+
+    let problems = diesel::sql_query(format!("PRAGMA integrity_check({MAX_PROBLEMS})"))
+        .load::<IntegrityCheck>(connection)
+        .map_err(|e| anyhow::anyhow!("Error checking the integrity of the database: {e}"))?
+        .into_iter()
+        .map(|row| row.integrity_check)
+        .filter(|message| message != "ok")
+        .collect();
+
+    let pages = diesel::sql_query(
+        "SELECT page_count * page_size AS count FROM pragma_page_count(), pragma_page_size()",
+    )
+    .get_result::<Count>(connection)
+    .map_err(|e| anyhow::anyhow!("Error reading the size of the database: {e}"))?;
+
+    let tables = diesel::sql_query(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .load::<Name>(connection)
+    .map_err(|e| anyhow::anyhow!("Error listing tables: {e}"))?;
+    let rows = tables
+        .into_iter()
+        .map(|Name { name }| {
+            diesel::sql_query(format!("SELECT COUNT(*) AS count FROM \"{name}\""))
+                .get_result::<Count>(connection)
+                .map(|rows| (name.clone(), rows.count))
+                .map_err(|e| anyhow::anyhow!("Error counting rows of {name}: {e}"))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(DbHealth {
+        problems,
+        file_bytes: u64::try_from(pages.count).unwrap_or_default(),
+        wal_bytes: 0,
+        rows,
+    })
+}
+
+/// Write a consistent copy of the database to `path`, which must not exist yet
+///
+/// `VACUUM INTO` reads one snapshot of the database, so the copy is safe to
+/// take while the daemon keeps writing
+#[tracing::instrument]
+pub fn backup(path: &Path) -> anyhow::Result<()> {
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+    let mut connection = get_db_connection()?;
+    diesel::sql_query("VACUUM INTO ?")
+        .bind::<Text, _>(path.display().to_string())
+        .execute(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error backing up database to {}: {e}", path.display()))?;
+    Ok(())
+}
+
+/// Apply the migrations the database misses, returns the versions applied
+///
+/// A database holding data already is backed up to [`snapshot_path`] first, so
+/// a failed migration can be undone with [`restore`]
+#[tracing::instrument]
+pub fn run_pending_migrations() -> anyhow::Result<Vec<String>> {
+    let mut connection = get_db_connection()?;
+    let (applied, pending) = migration_status(&mut connection)?;
+    if pending == 0 {
+        return Ok(Vec::new());
+    }
+    if applied > 0 {
+        let path = snapshot_path()?;
+        backup(&path)?;
+        log::info!(
+            "Backed up the database to {} before {pending} migrations",
+            path.display()
+        );
+    }
+    migrate(&mut connection)
+}
+
+/// Number of migrations applied to the database and of those still pending
+fn migration_status(connection: &mut SqliteConnection) -> anyhow::Result<(usize, usize)> {
+    let applied = connection
+        .applied_migrations()
+        .map_err(|e| anyhow::anyhow!("Error listing applied migrations: {e}"))?;
+    let pending = connection
+        .pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Error listing pending migrations: {e}"))?;
+    Ok((applied.len(), pending.len()))
+}
+
+fn migrate(connection: &mut SqliteConnection) -> anyhow::Result<Vec<String>> {
+    let versions = connection
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Error running migrations: {e}"))?
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    log::info!("Applied migrations {versions:?}");
+    Ok(versions)
+}
+
+/// Backup next to the database named after the current time, e.g.
+/// `dball-20250910T080000.backup.db` for `dball.db`
+pub fn snapshot_path() -> anyhow::Result<PathBuf> {
+    let database = PathBuf::from(super::database_url()?);
+    let stem = database
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "dball".to_owned());
+    let time = crate::clock::now().format("%Y%m%dT%H%M%S");
+    Ok(database.with_file_name(format!("{stem}-{time}.backup.db")))
+}
+
+/// Replace every row of the database with the rows of the backup at `path`
+///
+/// The backup must have been written by [`backup`] with the same migrations
+/// applied, the tables are replaced in one transaction so a failed restore
+/// leaves the database as it was
+#[tracing::instrument]
+pub fn restore(path: &Path) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    let restored = restore_into(&mut connection, path);
+    crate::db::tickets::invalidating(crate::db::spot::invalidating(restored))
+}
+
+fn restore_into(connection: &mut SqliteConnection, path: &Path) -> anyhow::Result<()> {
+    if !path.is_file() {
+        anyhow::bail!("No backup at {}", path.display());
+    }
+    diesel::sql_query("ATTACH DATABASE ? AS backup")
+        .bind::<Text, _>(path.display().to_string())
+        .execute(connection)
+        .map_err(|e| anyhow::anyhow!("Error opening backup {}: {e}", path.display()))?;
+
+    let restored = copy_backup_tables(connection);
+    connection
+        .batch_execute("DETACH DATABASE backup")
+        .map_err(|e| anyhow::anyhow!("Error closing backup {}: {e}", path.display()))?;
+    restored.map_err(|e| anyhow::anyhow!("Error restoring {}: {e}", path.display()))
+}
+
+fn copy_backup_tables(connection: &mut SqliteConnection) -> anyhow::Result<()> {
+    let migrations = |connection: &mut SqliteConnection, schema: &str| {
+        diesel::sql_query(format!(
+            "SELECT version AS name FROM {schema}.{MIGRATIONS_TABLE} ORDER BY version"
+        ))
+        .load::<Name>(connection)
+        .map(|versions| versions.into_iter().map(|v| v.name).collect::<Vec<_>>())
+    };
+    if migrations(connection, "main")? != migrations(connection, "backup")? {
+        anyhow::bail!("the backup was written with other migrations than the database");
+    }
+
+    let tables = diesel::sql_query(format!(
+        "SELECT name FROM main.sqlite_master WHERE type = 'table' \
+         AND name NOT LIKE 'sqlite_%' AND name != '{MIGRATIONS_TABLE}'"
+    ))
+    .load::<Name>(connection)?;
+    connection.transaction(|connection| {
+        connection.batch_execute("PRAGMA defer_foreign_keys = ON")?;
+        for Name { name } in &tables {
+            connection.batch_execute(&format!(
+                "DELETE FROM main.\"{name}\"; \
+                 INSERT INTO main.\"{name}\" SELECT * FROM backup.\"{name}\";"
+            ))?;
+        }
+        log::info!("Restored {} tables from the backup", tables.len());
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_spots(connection: &mut SqliteConnection) -> anyhow::Result<i64> {
+        use crate::models::schema::spot;
+        Ok(spot::table.count().get_result(connection)?)
+    }
+
+    #[test]
+    fn test_backup_and_restore() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("dball_test_backup_{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir)?;
+        let (target, source) = (dir.join("target.db"), dir.join("source.db"));
+        backup(&target)?;
+        assert!(backup(&target).is_err());
+        std::fs::copy(&target, &source)?;
+
+        let mut connection = SqliteConnection::establish(&target.display().to_string())?;
+        let count = count_spots(&mut connection)?;
+        connection.batch_execute("DELETE FROM spot")?;
+
+        restore_into(&mut connection, &source)?;
+        assert_eq!(count_spots(&mut connection)?, count);
+        assert!(restore_into(&mut connection, &dir.join("missing.db")).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_maintain_vacuums_free_pages() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("dball_test_maintain_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let mut connection = SqliteConnection::establish(&path.display().to_string())?;
+        connection.batch_execute(
+            "PRAGMA journal_mode = WAL; CREATE TABLE filler (data BLOB); \
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200) \
+             INSERT INTO filler SELECT zeroblob(4096) FROM n; DELETE FROM filler;",
+        )?;
+
+        let report = maintain(&mut connection, 0)?;
+        assert!(!report.vacuumed);
+        assert!(report.free_pages * 2 > report.total_pages);
+        assert!(maintain(&mut connection, 50)?.vacuumed);
+        assert_eq!(maintain(&mut connection, 50)?.free_pages, 0);
+        drop(connection);
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{suffix}", path.display())).ok();
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_health() -> anyhow::Result<()> {
+        let health = health()?;
+        assert!(health.is_ok(), "{:?}", health.problems);
+        assert!(health.file_bytes > 0);
+        assert!(health.rows["tickets"] > 0);
+        assert!(health.rows.contains_key(MIGRATIONS_TABLE));
+
+        let path =
+            std::env::temp_dir().join(format!("dball_test_health_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let mut connection = SqliteConnection::establish(&path.display().to_string())?;
+        connection
+            .batch_execute("CREATE TABLE filler (data BLOB); INSERT INTO filler VALUES (1);")?;
+        let health = check_health(&mut connection)?;
+        assert_eq!(health.rows, [("filler".to_owned(), 1)].into());
+        assert_eq!(health.wal_bytes, 0);
+        drop(connection);
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_empty_database() -> anyhow::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("dball_test_migrate_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let mut connection = SqliteConnection::establish(&path.display().to_string())?;
+
+        assert_eq!(migration_status(&mut connection)?.0, 0);
+        let applied = migrate(&mut connection)?;
+        assert_eq!(migration_status(&mut connection)?, (applied.len(), 0));
+        assert!(migrate(&mut connection)?.is_empty());
+        assert!(run_pending_migrations()?.is_empty());
+        std::fs::remove_file(&path).ok();
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use diesel::prelude::*;
+
+use crate::db::get_db_connection;
+use crate::models::NotificationDelivery;
+use crate::models::schema::notification_deliveries;
+
+pub fn insert_notification_delivery(delivery: &NotificationDelivery) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    diesel::insert_into(notification_deliveries::table)
+        .values(delivery)
+        .execute(&mut connection)
+        .map(drop)
+        .map_err(|e| anyhow::anyhow!("Error inserting notification delivery: {e}"))
+}
+
+/// The latest `limit` notification deliveries, the latest one first
+pub fn get_latest_notification_deliveries(limit: i64) -> anyhow::Result<Vec<NotificationDelivery>> {
+    let mut connection = get_db_connection()?;
+    notification_deliveries::table
+        .order(notification_deliveries::id.desc())
+        .limit(limit)
+        .load::<NotificationDelivery>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading notification deliveries: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_notification_delivery() -> anyhow::Result<()> {
+        let delivery = NotificationDelivery {
+            id: None,
+            channel: "webhook".to_owned(),
+            target: "https://example.com/test_insert_notification_delivery".to_owned(),
+            period: "2099001".to_owned(),
+            payload: "{}".to_owned(),
+            attempts: 3,
+            error: Some("HTTP status 500".to_owned()),
+            sent_time: chrono::NaiveDateTime::parse_from_str(
+                "2099-01-01 21:20:00",
+                "%Y-%m-%d %H:%M:%S",
+            )?,
+        };
+        insert_notification_delivery(&delivery)?;
+        let logged = get_latest_notification_deliveries(10)?
+            .into_iter()
+            .find(|logged| logged.target == delivery.target)
+            .ok_or(anyhow::anyhow!("Notification delivery missing"))?;
+        assert_eq!(
+            NotificationDelivery {
+                id: None,
+                ..logged.clone()
+            },
+            delivery
+        );
+        assert!(!logged.delivered());
+        Ok(())
+    }
+}
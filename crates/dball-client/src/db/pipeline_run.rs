@@ -0,0 +1,59 @@
+use diesel::prelude::*;
+
+use crate::db::get_db_connection;
+use crate::models::PipelineRun;
+use crate::models::schema::pipeline_runs;
+
+pub fn insert_pipeline_run(run: &PipelineRun) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    diesel::insert_into(pipeline_runs::table)
+        .values(run)
+        .execute(&mut connection)
+        .map(drop)
+        .map_err(|e| anyhow::anyhow!("Error inserting pipeline run: {e}"))
+}
+
+/// The latest `limit` pipeline runs, the latest one first
+pub fn get_latest_pipeline_runs(limit: i64) -> anyhow::Result<Vec<PipelineRun>> {
+    let mut connection = get_db_connection()?;
+    pipeline_runs::table
+        .order(pipeline_runs::id.desc())
+        .limit(limit)
+        .load::<PipelineRun>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading pipeline runs: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_pipeline_run() -> anyhow::Result<()> {
+        let time =
+            chrono::NaiveDateTime::parse_from_str("2099-01-01 21:20:00", "%Y-%m-%d %H:%M:%S")?;
+        let run = PipelineRun {
+            id: None,
+            draw_time: time,
+            started_time: time,
+            finished_time: time,
+            completed_steps: 1,
+            attempts: 4,
+            failed_step: Some("update_all_unprize_spots".to_owned()),
+            error: Some("unreachable".to_owned()),
+        };
+        insert_pipeline_run(&run)?;
+        let logged = get_latest_pipeline_runs(10)?
+            .into_iter()
+            .find(|logged| logged.draw_time == time)
+            .ok_or(anyhow::anyhow!("Pipeline run missing"))?;
+        assert_eq!(
+            PipelineRun {
+                id: None,
+                ..logged.clone()
+            },
+            run
+        );
+        assert!(!logged.succeeded());
+        Ok(())
+    }
+}
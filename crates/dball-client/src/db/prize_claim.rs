@@ -0,0 +1,60 @@
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+
+use crate::db::{get_db_connection, last_insert_rowid};
+use crate::models::PrizeClaim;
+use crate::models::schema::prize_claims;
+use crate::profile;
+
+/// Insert `claim`, returns its id
+#[tracing::instrument(skip(claim))]
+pub fn insert_claim(claim: &PrizeClaim) -> anyhow::Result<i32> {
+    let mut connection = get_db_connection()?;
+    connection
+        .transaction(|connection| {
+            diesel::insert_into(prize_claims::table)
+                .values(claim)
+                .execute(connection)?;
+            diesel::select(last_insert_rowid()).get_result::<i32>(connection)
+        })
+        .map_err(|e: diesel::result::Error| anyhow::anyhow!("Error inserting prize claim: {e}"))
+}
+
+/// Claims of the active profile, every claim outside of a profile scope
+fn visible_claims<'a>() -> prize_claims::BoxedQuery<'a, Sqlite> {
+    let query = prize_claims::table.into_boxed();
+    match profile::active() {
+        Some(id) => query.filter(prize_claims::profile_id.eq(id)),
+        None => query,
+    }
+}
+
+/// Claim of the spot `spot_id`, whoever made it
+pub fn get_claim_by_spot(spot_id: i32) -> anyhow::Result<Option<PrizeClaim>> {
+    let mut connection = get_db_connection()?;
+    prize_claims::table
+        .filter(prize_claims::spot_id.eq(spot_id))
+        .first::<PrizeClaim>(&mut connection)
+        .optional()
+        .map_err(|e| anyhow::anyhow!("Error loading prize claim of spot {spot_id}: {e}"))
+}
+
+/// Every claim, the latest one first
+pub fn get_all_claims() -> anyhow::Result<Vec<PrizeClaim>> {
+    let mut connection = get_db_connection()?;
+    visible_claims()
+        .order((prize_claims::claim_date.desc(), prize_claims::id.desc()))
+        .load::<PrizeClaim>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading prize claims: {e}"))
+}
+
+/// Yuan paid out for the claims
+pub fn claims_total() -> anyhow::Result<i64> {
+    let mut connection = get_db_connection()?;
+    visible_claims()
+        .select(sum(prize_claims::amount))
+        .get_result::<Option<i64>>(&mut connection)
+        .map(Option::unwrap_or_default)
+        .map_err(|e| anyhow::anyhow!("Error summing prize claims: {e}"))
+}
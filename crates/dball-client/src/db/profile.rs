@@ -0,0 +1,52 @@
+use diesel::prelude::*;
+
+use crate::db::get_db_connection;
+use crate::models::Profile;
+use crate::models::schema::profiles;
+
+/// The profile `name`, created on first use
+#[tracing::instrument]
+pub fn get_or_create_profile(name: &str) -> anyhow::Result<Profile> {
+    let mut connection = get_db_connection()?;
+    connection
+        .transaction(|connection| {
+            diesel::insert_or_ignore_into(profiles::table)
+                .values((
+                    profiles::name.eq(name),
+                    profiles::created_time.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(connection)?;
+            profiles::table
+                .filter(profiles::name.eq(name))
+                .select(Profile::as_select())
+                .first::<Profile>(connection)
+        })
+        .map_err(|e: diesel::result::Error| anyhow::anyhow!("Error loading profile {name}: {e}"))
+}
+
+/// Every profile, the default one first
+pub fn get_all_profiles() -> anyhow::Result<Vec<Profile>> {
+    let mut connection = get_db_connection()?;
+    profiles::table
+        .order(profiles::id.asc())
+        .load::<Profile>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading profiles: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DEFAULT_PROFILE_ID;
+
+    #[test]
+    fn test_get_or_create_profile() -> anyhow::Result<()> {
+        let created = get_or_create_profile("test alice")?;
+        assert_eq!(get_or_create_profile("test alice")?, created);
+        assert_ne!(created.id, Some(DEFAULT_PROFILE_ID));
+
+        let profiles = get_all_profiles()?;
+        assert_eq!(profiles[0].id, Some(DEFAULT_PROFILE_ID));
+        assert!(profiles.contains(&created));
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+
+use crate::db::spot::invalidating;
+use crate::db::{get_db_connection, last_insert_rowid};
+use crate::models::Purchase;
+use crate::models::schema::{purchase_spots, purchases, spot};
+use crate::profile;
+
+/// Insert `purchase` linked to `spot_ids` and mark the spots purchased, all or
+/// nothing, returns the id of the purchase
+#[tracing::instrument(skip(purchase))]
+pub fn insert_purchase(purchase: &Purchase, spot_ids: &[i32]) -> anyhow::Result<i32> {
+    let mut connection = get_db_connection()?;
+    invalidating(connection.transaction(|connection| {
+        diesel::insert_into(purchases::table)
+            .values(purchase)
+            .execute(connection)?;
+        let id = diesel::select(last_insert_rowid()).get_result::<i32>(connection)?;
+        for &spot_id in spot_ids {
+            diesel::insert_into(purchase_spots::table)
+                .values((
+                    purchase_spots::purchase_id.eq(id),
+                    purchase_spots::spot_id.eq(spot_id),
+                ))
+                .execute(connection)?;
+        }
+        diesel::update(spot::table.filter(spot::id.eq_any(spot_ids)))
+            .set((
+                spot::purchased.eq(true),
+                spot::modified_time.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(connection)?;
+        Ok(id)
+    }))
+    .map_err(|e: diesel::result::Error| anyhow::anyhow!("Error inserting purchase: {e}"))
+}
+
+/// Purchases of the active profile, every purchase outside of a profile scope
+fn visible_purchases<'a>() -> purchases::BoxedQuery<'a, Sqlite> {
+    let query = purchases::table.into_boxed();
+    match profile::active() {
+        Some(id) => query.filter(purchases::profile_id.eq(id)),
+        None => query,
+    }
+}
+
+/// Every purchase, the latest one first
+pub fn get_all_purchases() -> anyhow::Result<Vec<Purchase>> {
+    let mut connection = get_db_connection()?;
+    visible_purchases()
+        .order((purchases::purchase_date.desc(), purchases::id.desc()))
+        .load::<Purchase>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading purchases: {e}"))
+}
+
+/// Ids of the spots bought with the purchase `purchase_id`
+pub fn get_purchase_spot_ids(purchase_id: i32) -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    purchase_spots::table
+        .filter(purchase_spots::purchase_id.eq(purchase_id))
+        .select(purchase_spots::spot_id)
+        .order(purchase_spots::spot_id.asc())
+        .load::<i32>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading spots of purchase {purchase_id}: {e}"))
+}
+
+/// Number of purchases and yuan paid for them
+pub fn purchases_total() -> anyhow::Result<(i64, i64)> {
+    let mut connection = get_db_connection()?;
+    let count = visible_purchases()
+        .count()
+        .get_result::<i64>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error counting purchases: {e}"))?;
+    let cost = visible_purchases()
+        .select(sum(purchases::cost))
+        .get_result::<Option<i64>>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error summing purchases: {e}"))?;
+    Ok((count, cost.unwrap_or(0)))
+}
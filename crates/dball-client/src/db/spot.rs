@@ -1,8 +1,28 @@
-use crate::db::get_db_connection;
-use crate::models::Spot;
-use crate::models::schema::spot;
+use crate::db::cache::PeriodCache;
+use crate::db::{get_db_connection, last_insert_rowid};
+use crate::models::schema::{spot, spot_deprecations, spot_tags, tags};
+use crate::models::{
+    DuplicateSpots, Page, PageRequest, SortOrder, Spot, SpotDeprecation, SpotFilter,
+};
+use crate::profile;
+use chrono::NaiveTime;
 use dball_combora::dball::DBall;
 use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+use std::sync::LazyLock;
+
+/// Active profile and period of a cached [`get_spots_by_period`]
+type ScopedPeriod = (Option<i32>, String);
+
+/// Spots of a period by profile scope, read on every client refresh
+static SPOTS_BY_PERIOD: LazyLock<PeriodCache<ScopedPeriod, Vec<Spot>>> =
+    LazyLock::new(PeriodCache::default);
+
+/// Drop the cached spots once a spot write is done, returns `written`
+pub(crate) fn invalidating<T>(written: T) -> T {
+    SPOTS_BY_PERIOD.invalidate();
+    written
+}
 
 /// Insert a new spot from `DBall`
 pub fn insert_spot_from_dball(
@@ -15,73 +35,257 @@ pub fn insert_spot_from_dball(
     insert_spot(&new_spot)
 }
 
+#[tracing::instrument(skip_all, fields(period = %new_spot.period))]
 pub fn insert_spot(new_spot: &Spot) -> anyhow::Result<()> {
     let mut connection = get_db_connection()?;
-    diesel::insert_into(spot::table)
-        .values(new_spot)
-        .execute(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error inserting spot: {e}"))
-        .and_then(|count| {
-            if count != 1 {
-                Err(anyhow::anyhow!(
-                    "Expected to insert exactly one spot, but inserted {}",
-                    count
-                ))
-            } else {
-                Ok(())
-            }
+    invalidating(
+        diesel::insert_into(spot::table)
+            .values(new_spot)
+            .execute(&mut connection),
+    )
+    .map_err(|e| anyhow::anyhow!("Error inserting spot: {e}"))
+    .and_then(|count| {
+        if count != 1 {
+            Err(anyhow::anyhow!(
+                "Expected to insert exactly one spot, but inserted {}",
+                count
+            ))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Insert every spot in one transaction, none of them when one fails, returns their ids in order
+#[tracing::instrument(skip_all, fields(spots = new_spots.len()))]
+pub fn insert_spots_batch(new_spots: &[Spot]) -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    invalidating(connection.transaction(|connection| insert_spots(connection, new_spots)))
+        .map_err(|e| anyhow::anyhow!("Error inserting a batch of {} spots: {e}", new_spots.len()))
+}
+
+/// Insert every spot on `connection`, returns their ids in order
+pub(crate) fn insert_spots(
+    connection: &mut SqliteConnection,
+    new_spots: &[Spot],
+) -> QueryResult<Vec<i32>> {
+    new_spots
+        .iter()
+        .map(|new_spot| {
+            diesel::insert_into(spot::table)
+                .values(new_spot)
+                .execute(connection)?;
+            diesel::select(last_insert_rowid()).get_result::<i32>(connection)
         })
+        .collect()
 }
 
 /// Should update only one spot's prize status
+#[tracing::instrument(skip(prize_status))]
 pub fn update_spot_prize_status_by_id(id: i32, prize_status: Option<i32>) -> anyhow::Result<()> {
     let mut connection = get_db_connection()?;
-    diesel::update(spot::table.filter(spot::id.eq(id)))
-        .set((
-            spot::prize_status.eq(prize_status),
-            spot::modified_time.eq(chrono::Utc::now().naive_utc()),
-        ))
-        .execute(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error updating spot prize status: {e}"))
-        .and_then(|count| {
+    invalidating(
+        diesel::update(spot::table.filter(spot::id.eq(id)))
+            .set((
+                spot::prize_status.eq(prize_status),
+                spot::modified_time.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(&mut connection),
+    )
+    .map_err(|e| anyhow::anyhow!("Error updating spot prize status: {e}"))
+    .and_then(|count| {
+        if count != 1 {
+            Err(anyhow::anyhow!(
+                "Expected to update exactly one spot, but updated {count}",
+            ))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Set the prize status of every `(id, status)` of `statuses`, all or nothing,
+/// so a failure never leaves a period half settled
+#[tracing::instrument(skip_all, fields(spots = statuses.len()))]
+pub fn update_prize_statuses(statuses: &[(i32, i32)]) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    let now = chrono::Utc::now().naive_utc();
+    invalidating(connection.transaction(|connection| {
+        for &(id, status) in statuses {
+            let count = diesel::update(spot::table.filter(spot::id.eq(id)))
+                .set((spot::prize_status.eq(status), spot::modified_time.eq(now)))
+                .execute(connection)
+                .map_err(|e| anyhow::anyhow!("Error updating prize status of spot {id}: {e}"))?;
             if count != 1 {
-                Err(anyhow::anyhow!(
-                    "Expected to update exactly one spot, but updated {count}",
-                ))
-            } else {
-                Ok(())
+                anyhow::bail!("Expected to update spot {id}, but updated {count} spots");
             }
-        })
+        }
+        Ok(())
+    }))
 }
 
-/// Mark spots as deprecated (deprecated = true)
-/// Only marks spots that are currently not deprecated
-pub fn mark_spots_deprecated(spot_ids: &[i32]) -> anyhow::Result<usize> {
-    if spot_ids.is_empty() {
-        return Ok(0);
-    }
-
+/// Set or clear the note of one spot
+pub fn update_spot_note_by_id(id: i32, note: Option<&str>) -> anyhow::Result<()> {
     let mut connection = get_db_connection()?;
+    invalidating(
+        diesel::update(spot::table.filter(spot::id.eq(id)))
+            .set((
+                spot::note.eq(note),
+                spot::modified_time.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(&mut connection),
+    )
+    .map_err(|e| anyhow::anyhow!("Error updating spot note: {e}"))
+    .and_then(|count| {
+        if count != 1 {
+            Err(anyhow::anyhow!(
+                "Expected to update exactly one spot, but updated {count}",
+            ))
+        } else {
+            Ok(())
+        }
+    })
+}
 
-    // Update only spots that are currently not deprecated
-    let updated_count = diesel::update(
-        spot::table
-            .filter(spot::id.eq_any(spot_ids))
-            .filter(spot::deprecated.eq(false)),
+/// Should update only one spot's purchased flag
+pub fn update_spot_purchased_by_id(id: i32, purchased: bool) -> anyhow::Result<()> {
+    let mut connection = get_db_connection()?;
+    invalidating(
+        diesel::update(spot::table.filter(spot::id.eq(id)))
+            .set((
+                spot::purchased.eq(purchased),
+                spot::modified_time.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(&mut connection),
     )
-    .set((
-        spot::deprecated.eq(true),
-        spot::modified_time.eq(chrono::Utc::now().naive_utc()),
-    ))
-    .execute(&mut connection)
-    .map_err(|e| anyhow::anyhow!("Error marking spots as deprecated: {e}"))?;
+    .map_err(|e| anyhow::anyhow!("Error updating spot purchased flag: {e}"))
+    .and_then(|count| {
+        if count != 1 {
+            Err(anyhow::anyhow!(
+                "Expected to update exactly one spot, but updated {count}",
+            ))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Mark spots as deprecated (deprecated = true)
+/// Only marks spots that are currently not deprecated, recording who did it and why
+#[tracing::instrument(skip_all, fields(spots = spot_ids.len()))]
+pub fn mark_spots_deprecated(
+    spot_ids: &[i32],
+    actor: &str,
+    reason: Option<&str>,
+) -> anyhow::Result<usize> {
+    let updated = invalidating(set_spots_deprecated(spot_ids, true, actor, reason))
+        .map_err(|e| anyhow::anyhow!("Error marking spots as deprecated: {e}"))?;
 
     log::debug!(
         "Marked {} spots as deprecated out of {} requested",
-        updated_count,
+        updated.len(),
+        spot_ids.len()
+    );
+    Ok(updated.len())
+}
+
+/// Undo [`mark_spots_deprecated`] for the deprecated spots of `spot_ids`,
+/// returns the ids of the spots restored
+#[tracing::instrument(skip_all, fields(spots = spot_ids.len()))]
+pub fn restore_spots(
+    spot_ids: &[i32],
+    actor: &str,
+    reason: Option<&str>,
+) -> anyhow::Result<Vec<i32>> {
+    let restored = invalidating(set_spots_deprecated(spot_ids, false, actor, reason))
+        .map_err(|e| anyhow::anyhow!("Error restoring spots: {e}"))?;
+    log::debug!(
+        "Restored {} spots out of {} requested",
+        restored.len(),
         spot_ids.len()
     );
-    Ok(updated_count)
+    Ok(restored)
+}
+
+/// Flip `deprecated` of the spots of `spot_ids` not set that way yet and record
+/// the change in their history, returns the ids of the spots changed
+fn set_spots_deprecated(
+    spot_ids: &[i32],
+    deprecated: bool,
+    actor: &str,
+    reason: Option<&str>,
+) -> anyhow::Result<Vec<i32>> {
+    if spot_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut connection = get_db_connection()?;
+    let now = chrono::Utc::now().naive_utc();
+    let changed = connection.transaction(|connection| {
+        let changed = spot::table
+            .filter(spot::id.eq_any(spot_ids))
+            .filter(spot::deprecated.eq(!deprecated))
+            .select(spot::id.assume_not_null())
+            .load::<i32>(connection)?;
+        diesel::update(spot::table.filter(spot::id.eq_any(&changed)))
+            .set((spot::deprecated.eq(deprecated), spot::modified_time.eq(now)))
+            .execute(connection)?;
+        let history = changed
+            .iter()
+            .map(|&spot_id| SpotDeprecation {
+                id: None,
+                spot_id,
+                deprecated,
+                actor: actor.to_owned(),
+                reason: reason.map(str::to_owned),
+                created_time: now,
+            })
+            .collect::<Vec<_>>();
+        diesel::insert_into(spot_deprecations::table)
+            .values(&history)
+            .execute(connection)?;
+        diesel::QueryResult::Ok(changed)
+    })?;
+    Ok(changed)
+}
+
+/// Every time the spot `spot_id` was deprecated or restored, oldest first
+pub fn get_deprecation_history(spot_id: i32) -> anyhow::Result<Vec<SpotDeprecation>> {
+    let mut connection = get_db_connection()?;
+    spot_deprecations::table
+        .filter(spot_deprecations::spot_id.eq(spot_id))
+        .order(spot_deprecations::id.asc())
+        .load::<SpotDeprecation>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading deprecation history of spot {spot_id}: {e}"))
+}
+
+/// Spots of the latest [`mark_spots_deprecated`] call that are still deprecated
+pub fn get_last_deprecated_spot_ids() -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    let deprecations = || {
+        let query = spot_deprecations::table
+            .inner_join(spot::table)
+            .filter(spot_deprecations::deprecated.eq(true))
+            .into_boxed();
+        match profile::active() {
+            Some(id) => query.filter(spot::profile_id.eq(id)),
+            None => query,
+        }
+    };
+    let latest = deprecations()
+        .select(diesel::dsl::max(spot_deprecations::created_time))
+        .first::<Option<chrono::NaiveDateTime>>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading the latest deprecation: {e}"))?;
+    let Some(latest) = latest else {
+        return Ok(Vec::new());
+    };
+    deprecations()
+        .filter(spot_deprecations::created_time.eq(latest))
+        .filter(spot::deprecated.eq(true))
+        .select(spot_deprecations::spot_id)
+        .order(spot_deprecations::spot_id.asc())
+        .load::<i32>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading the latest deprecated spots: {e}"))
 }
 
 /// Get spots by period and convert them to `DBall`
@@ -102,13 +306,159 @@ pub fn get_spots_by_period_as_dball(period: &str) -> anyhow::Result<Vec<DBall>>
     Ok(result)
 }
 
-pub fn get_all_spots() -> anyhow::Result<Vec<Spot>> {
+/// The spot `id`, `None` for a spot of another profile than the active one
+pub fn get_spot_by_id(id: i32) -> anyhow::Result<Option<Spot>> {
     let mut connection = get_db_connection()?;
-    spot::table
+    visible_spots()
+        .filter(spot::id.eq(id))
+        .first::<Spot>(&mut connection)
+        .optional()
+        .map_err(|e| anyhow::anyhow!("Error finding spot {id}: {e}"))
+}
+
+/// The spots of `page`, ordered by period and id within a period
+pub fn get_all_spots(page: PageRequest) -> anyhow::Result<Page<Spot>> {
+    find_spots(&SpotFilter::default(), page)
+}
+
+/// The spots of `page` checked against a draw, deprecated ones included
+pub fn get_prized_spots(page: PageRequest) -> anyhow::Result<Page<Spot>> {
+    find_spots(&SpotFilter::default().settled(true), page)
+}
+
+/// The spots of `page` matching `filter`, ordered by period and id within a period
+pub fn find_spots(filter: &SpotFilter, page: PageRequest) -> anyhow::Result<Page<Spot>> {
+    let mut connection = get_db_connection()?;
+    let total = filtered_spots(filter)
+        .count()
+        .get_result::<i64>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error counting spots: {e}"))?;
+
+    let mut query = match page.order {
+        SortOrder::Asc => filtered_spots(filter).order((spot::period.asc(), spot::id.asc())),
+        SortOrder::Desc => filtered_spots(filter).order((spot::period.desc(), spot::id.desc())),
+    }
+    .offset(page.offset);
+    if let Some(limit) = page.limit {
+        query = query.limit(limit);
+    }
+    let items = query
         .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error loading spots: {e}"))
+        .map_err(|e| anyhow::anyhow!("Error loading spots: {e}"))?;
+    Ok(Page {
+        items,
+        total,
+        offset: page.offset,
+    })
+}
+
+/// Whether the space separated numbers of the text column `$column` hold `$number`
+macro_rules! lists_number {
+    ($column:expr, $number:expr) => {{
+        let number = $number;
+        $column
+            .eq(number.to_string())
+            .or($column.like(format!("{number} %")))
+            .or($column.like(format!("% {number}")))
+            .or($column.like(format!("% {number} %")))
+    }};
+}
+
+/// Spots of the active profile, every spot outside of a profile scope
+fn visible_spots<'a>() -> spot::BoxedQuery<'a, Sqlite> {
+    let query = spot::table.into_boxed();
+    match profile::active() {
+        Some(id) => query.filter(spot::profile_id.eq(id)),
+        None => query,
+    }
 }
 
+/// Spots matching every condition set in `filter`
+fn filtered_spots(filter: &SpotFilter) -> spot::BoxedQuery<'_, Sqlite> {
+    let mut query = visible_spots();
+    if let Some(from) = &filter.period_from {
+        query = query.filter(spot::period.ge(from));
+    }
+    if let Some(to) = &filter.period_to {
+        query = query.filter(spot::period.le(to));
+    }
+    if let Some(from) = filter.created_from {
+        query = query.filter(spot::created_time.ge(from.and_time(NaiveTime::MIN)));
+    }
+    if let Some(to) = filter.created_to.and_then(|to| to.succ_opt()) {
+        query = query.filter(spot::created_time.lt(to.and_time(NaiveTime::MIN)));
+    }
+    match filter.settled {
+        Some(true) => query = query.filter(spot::prize_status.is_not_null()),
+        Some(false) => query = query.filter(spot::prize_status.is_null()),
+        None => {}
+    }
+    if let Some(min) = filter.min_prize {
+        query = query.filter(spot::prize_status.ge(min));
+    }
+    if let Some(max) = filter.max_prize {
+        query = query.filter(spot::prize_status.le(max));
+    }
+    if let Some(deprecated) = filter.deprecated {
+        query = query.filter(spot::deprecated.eq(deprecated));
+    }
+    if let Some(magnification) = filter.magnification {
+        query = query.filter(spot::magnification.eq(magnification));
+    }
+    if let Some(red) = filter.red {
+        query = query.filter(
+            spot::red1
+                .eq(red)
+                .or(spot::red2.eq(red))
+                .or(spot::red3.eq(red))
+                .or(spot::red4.eq(red))
+                .or(spot::red5.eq(red))
+                .or(spot::red6.eq(red))
+                .nullable()
+                .or(lists_number!(spot::extra_reds, red)),
+        );
+    }
+    if let Some(blue) = filter.blue {
+        query = query.filter(
+            spot::blue
+                .eq(blue)
+                .nullable()
+                .or(lists_number!(spot::extra_blues, blue)),
+        );
+    }
+    if let Some(tag) = &filter.tag {
+        query = query.filter(
+            spot::id.eq_any(
+                spot_tags::table
+                    .inner_join(tags::table)
+                    .filter(tags::name.eq(tag))
+                    .select(spot_tags::spot_id.nullable()),
+            ),
+        );
+    }
+    query
+}
+
+/// Up to `limit` spots, newest period first and newest id first within a period,
+/// continuing after `after`, the `(period, id)` of the last spot of the previous page
+pub fn get_spots_page(after: Option<(&str, i32)>, limit: i64) -> anyhow::Result<Vec<Spot>> {
+    let mut connection = get_db_connection()?;
+    let mut query = visible_spots()
+        .order((spot::period.desc(), spot::id.desc()))
+        .limit(limit);
+    if let Some((period, id)) = after {
+        query = query.filter(
+            spot::period
+                .lt(period)
+                .or(spot::period.eq(period).and(spot::id.lt(id))),
+        );
+    }
+    query
+        .load::<Spot>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading a page of {limit} spots: {e}"))
+}
+
+#[tracing::instrument]
 pub fn get_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
     let mut connection = get_db_connection()?;
     spot::table
@@ -117,17 +467,39 @@ pub fn get_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
         .map_err(|e| anyhow::anyhow!("Error loading spots: {e}"))
 }
 
-pub fn get_spots_by_period(period: &str) -> anyhow::Result<Vec<Spot>> {
+/// The spots of `period` of every profile, as settled against its draw
+pub fn get_all_spots_by_period(period: &str) -> anyhow::Result<Vec<Spot>> {
     let mut connection = get_db_connection()?;
     spot::table
         .filter(spot::period.eq(period))
         .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding spots for period {period}: {e}"))
+        .map_err(|e| anyhow::anyhow!("Error loading all spots of period {period}: {e}"))
+}
+
+/// The spots of `period` visible to the active profile, served from a cache
+/// dropped on every spot write
+pub fn get_spots_by_period(period: &str) -> anyhow::Result<Vec<Spot>> {
+    SPOTS_BY_PERIOD.get_or_load((profile::active(), period.to_owned()), || {
+        let mut connection = get_db_connection()?;
+        visible_spots()
+            .filter(spot::period.eq(period))
+            .load::<Spot>(&mut connection)
+            .map_err(|e| anyhow::anyhow!("Error finding spots for period {period}: {e}"))
+    })
+}
+
+/// The spots visible to the active profile saved at or after `since`
+pub fn get_spots_created_since(since: chrono::NaiveDateTime) -> anyhow::Result<Vec<Spot>> {
+    let mut connection = get_db_connection()?;
+    visible_spots()
+        .filter(spot::created_time.ge(since))
+        .load::<Spot>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading spots created since {since}: {e}"))
 }
 
 pub fn get_latest_spots(limit: i64) -> anyhow::Result<Vec<Spot>> {
     let mut connection = get_db_connection()?;
-    spot::table
+    visible_spots()
         .order(spot::created_time.desc())
         .limit(limit)
         .load::<Spot>(&mut connection)
@@ -136,7 +508,7 @@ pub fn get_latest_spots(limit: i64) -> anyhow::Result<Vec<Spot>> {
 
 pub fn get_latest_unprized_spots(limit: i64) -> anyhow::Result<Vec<Spot>> {
     let mut connection = get_db_connection()?;
-    spot::table
+    visible_spots()
         .filter(spot::prize_status.is_null())
         .order(spot::created_time.desc())
         .limit(limit)
@@ -144,58 +516,54 @@ pub fn get_latest_unprized_spots(limit: i64) -> anyhow::Result<Vec<Spot>> {
         .map_err(|e| anyhow::anyhow!("Error loading latest {limit} unprized spots: {e}"))
 }
 
-pub fn find_spots_with_red_number(number: i32) -> anyhow::Result<Vec<Spot>> {
-    let mut connection = get_db_connection()?;
-    spot::table
-        .filter(
-            spot::red1
-                .eq(number)
-                .or(spot::red2.eq(number))
-                .or(spot::red3.eq(number))
-                .or(spot::red4.eq(number))
-                .or(spot::red5.eq(number))
-                .or(spot::red6.eq(number)),
-        )
-        .order(spot::id.desc())
-        .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding spots with red number {number}: {e}"))
-}
-
-pub fn find_spots_with_blue_number(blue: i32) -> anyhow::Result<Vec<Spot>> {
-    let mut connection = get_db_connection()?;
-    spot::table
-        .filter(spot::blue.eq(blue))
-        .order(spot::id.desc())
-        .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding spots with blue number {blue}: {e}"))
-}
-
-pub fn find_spots_by_prize_status(status: Option<i32>) -> anyhow::Result<Vec<Spot>> {
+/// Ids of the live spots of `new_spot`'s period and profile holding its numbers
+pub fn get_spot_ids_with_numbers(new_spot: &Spot) -> anyhow::Result<Vec<i32>> {
     let mut connection = get_db_connection()?;
     spot::table
-        .filter(spot::prize_status.eq(status))
-        .order(spot::id.desc())
-        .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding spots with prize status {status:?}: {e}"))
-}
-
-pub fn find_winning_spots() -> anyhow::Result<Vec<Spot>> {
-    let mut connection = get_db_connection()?;
-    spot::table
-        .filter(spot::prize_status.is_not_null())
-        .filter(spot::prize_status.gt(0))
-        .order(spot::prize_status.asc())
-        .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding winning spots: {e}"))
+        .filter(spot::period.eq(&new_spot.period))
+        .filter(spot::profile_id.eq(new_spot.profile_id))
+        .filter(spot::red1.eq(new_spot.red1))
+        .filter(spot::red2.eq(new_spot.red2))
+        .filter(spot::red3.eq(new_spot.red3))
+        .filter(spot::red4.eq(new_spot.red4))
+        .filter(spot::red5.eq(new_spot.red5))
+        .filter(spot::red6.eq(new_spot.red6))
+        .filter(spot::blue.eq(new_spot.blue))
+        .filter(spot::extra_reds.is(&new_spot.extra_reds))
+        .filter(spot::extra_blues.is(&new_spot.extra_blues))
+        .filter(spot::bankers.is(&new_spot.bankers))
+        .filter(spot::deprecated.eq(false))
+        .select(spot::id.assume_not_null())
+        .order(spot::id.asc())
+        .load::<i32>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error finding spots like one of {}: {e}", new_spot.period))
 }
 
-pub fn find_spots_by_magnification(magnification: i32) -> anyhow::Result<Vec<Spot>> {
+/// Live spots repeating the numbers of another one of their period and
+/// profile, grouped by period and numbers
+pub fn find_duplicate_spots() -> anyhow::Result<Vec<DuplicateSpots>> {
     let mut connection = get_db_connection()?;
-    spot::table
-        .filter(spot::magnification.eq(magnification))
-        .order(spot::id.desc())
+    let spots = visible_spots()
+        .filter(spot::deprecated.eq(false))
+        .order((spot::period.asc(), spot::id.asc()))
         .load::<Spot>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error finding spots with magnification {magnification}: {e}"))
+        .map_err(|e| anyhow::anyhow!("Error loading spots: {e}"))?;
+
+    let mut groups = Vec::<DuplicateSpots>::new();
+    let mut positions = std::collections::HashMap::new();
+    for spot in &spots {
+        let key = (spot.profile_id, spot.numbers_key());
+        let position = *positions.entry(key).or_insert_with(|| {
+            groups.push(DuplicateSpots {
+                period: spot.period.clone(),
+                ids: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[position].ids.extend(spot.id);
+    }
+    groups.retain(|group| group.ids.len() > 1);
+    Ok(groups)
 }
 
 pub fn count_spots() -> anyhow::Result<i64> {
@@ -218,13 +586,13 @@ pub fn count_spots_by_period(period: &str) -> anyhow::Result<i64> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use dball_combora::dball::DBall;
+    use dball_combora::dball::{ComplexBet, DBall};
 
     #[test]
     fn all_spots() -> anyhow::Result<()> {
         // Retrieve all spots
-        match get_all_spots() {
-            Ok(spots) => {
+        match get_all_spots(PageRequest::default()) {
+            Ok(Page { items: spots, .. }) => {
                 log::info!("Successfully retrieved {count} spots:", count = spots.len());
                 for spot in &spots {
                     log::info!("{spot}");
@@ -287,82 +655,90 @@ mod test {
     }
 
     #[test]
-    fn test_find_spots_with_red_number() -> anyhow::Result<()> {
-        let red_number = 13;
-        match find_spots_with_red_number(red_number) {
-            Ok(spots) => {
-                log::info!(
-                    "Found {} spots containing red number {}",
-                    spots.len(),
-                    red_number
-                );
-                for spot in &spots {
-                    log::info!("{spot}");
-                }
-                Ok(())
-            }
-            Err(e) => {
-                log::error!("Failed to find spots with red number {red_number}: {e}");
-                Err(e)
-            }
-        }
-    }
-
-    #[test]
-    fn test_find_spots_with_blue_number() -> anyhow::Result<()> {
-        let blue_number = 11;
-        match find_spots_with_blue_number(blue_number) {
-            Ok(spots) => {
-                log::info!(
-                    "Found {} spots with blue number {}",
-                    spots.len(),
-                    blue_number
-                );
-                for spot in &spots {
-                    log::info!("{spot}");
-                }
-                Ok(())
-            }
-            Err(e) => {
-                log::error!("Failed to find spots with blue number {blue_number}: {e}");
-                Err(e)
-            }
-        }
-    }
-
-    #[test]
-    fn test_find_spots_by_prize_status() -> anyhow::Result<()> {
-        // Test finding spots waiting for results (None)
-        match find_spots_by_prize_status(None) {
-            Ok(spots) => {
-                log::debug!("Found {} spots waiting for results", spots.len());
-                spots.iter().take(3).for_each(|spot| {
-                    log::debug!("{spot}");
-                });
-                Ok(())
-            }
-            Err(e) => {
-                log::error!("Failed to find spots by prize status: {e}");
-                Err(e)
-            }
-        }
+    fn test_find_spots() -> anyhow::Result<()> {
+        let period = "2025994";
+        let simple =
+            Spot::from_dball(period, &DBall::new(vec![2, 7, 13, 19, 24, 30], 5, 3)?, None)?;
+        let complex = Spot::from_complex(
+            period,
+            &ComplexBet::new(vec![1, 4, 9, 16, 22, 27, 31], vec![3, 12], 1)?,
+            None,
+        )?;
+        let ids = insert_spots_batch(&[simple, complex])?;
+        let in_period = SpotFilter::default().periods(Some(period), Some(period));
+        let found = |filter: SpotFilter| -> anyhow::Result<Vec<i32>> {
+            Ok(
+                find_spots(&filter, PageRequest::default().with_order(SortOrder::Asc))?
+                    .items
+                    .iter()
+                    .filter_map(|spot| spot.id)
+                    .collect(),
+            )
+        };
+
+        assert_eq!(found(in_period.clone())?, ids);
+        assert_eq!(found(in_period.clone().containing_red(13))?, ids[..1]);
+        assert_eq!(found(in_period.clone().containing_red(31))?, ids[1..]);
+        assert!(found(in_period.clone().containing_red(3))?.is_empty());
+        assert_eq!(found(in_period.clone().containing_blue(12))?, ids[1..]);
+        assert_eq!(found(in_period.clone().magnification(3))?, ids[..1]);
+        assert_eq!(
+            found(in_period.clone().settled(false).deprecated(false))?,
+            ids
+        );
+        assert!(found(in_period.clone().prize_between(Some(0), None))?.is_empty());
+        crate::db::tag::tag_spots(&ids[1..], "test complex")?;
+        assert_eq!(found(in_period.clone().tagged("test complex"))?, ids[1..]);
+
+        let today = chrono::Utc::now().date_naive();
+        assert_eq!(
+            found(in_period.clone().created_between(Some(today), Some(today)))?,
+            ids
+        );
+        assert!(SpotFilter::default().containing_red(34).validate().is_err());
+        assert!(
+            in_period
+                .periods(Some("2025995"), Some("2025994"))
+                .validate()
+                .is_err()
+        );
+        Ok(())
     }
 
     #[test]
-    fn test_find_winning_spots() -> anyhow::Result<()> {
-        match find_winning_spots() {
-            Ok(spots) => {
-                log::info!("Found {count} winning spots", count = spots.len());
-                for spot in &spots {
-                    log::info!("{spot}");
-                }
-                Ok(())
-            }
-            Err(e) => {
-                log::error!("Failed to find winning spots: {e}");
-                Err(e)
-            }
-        }
+    fn test_find_duplicate_spots() -> anyhow::Result<()> {
+        let period = "2025989";
+        let spot = |reds, magnification| -> anyhow::Result<Spot> {
+            Ok(Spot::from_dball(
+                period,
+                &DBall::new(reds, 6, magnification)?,
+                None,
+            )?)
+        };
+        let twin = spot(vec![1, 6, 11, 16, 21, 26], 1)?;
+        let spots = [
+            twin.clone(),
+            spot(vec![2, 7, 12, 17, 22, 27], 1)?,
+            spot(vec![1, 6, 11, 16, 21, 26], 3)?,
+        ];
+        let ids = insert_spots_batch(&spots)?;
+        assert_eq!(get_spot_ids_with_numbers(&twin)?, [ids[0], ids[2]]);
+
+        let duplicates = find_duplicate_spots()?;
+        assert!(duplicates.contains(&DuplicateSpots {
+            period: period.to_owned(),
+            ids: vec![ids[0], ids[2]],
+        }));
+        assert!(!duplicates.iter().any(|group| group.ids.contains(&ids[1])));
+
+        mark_spots_deprecated(&ids[2..], "test", None)?;
+        assert_eq!(get_spot_ids_with_numbers(&twin)?, ids[..1]);
+        assert!(
+            !find_duplicate_spots()?
+                .iter()
+                .any(|group| group.period == period)
+        );
+        Ok(())
     }
 
     #[test]
@@ -404,6 +780,30 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_insert_spots_batch() -> anyhow::Result<()> {
+        let period = "2025998";
+        let spots = [[2, 9, 14, 21, 27, 33], [3, 8, 15, 22, 26, 31]]
+            .into_iter()
+            .map(|reds| Spot::from_dball(period, &DBall::new_one(reds, 4)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ids = insert_spots_batch(&spots)?;
+        assert_eq!(ids.len(), 2);
+        for (id, spot) in ids.iter().zip(&spots) {
+            let inserted = get_spot_by_id(*id)?.ok_or(anyhow::anyhow!("Spot {id} missing"))?;
+            assert_eq!(inserted.red_numbers(), spot.red_numbers());
+        }
+
+        // the taken id fails the second insert and rolls back the first one
+        let count = count_spots_by_period(period)?;
+        let mut taken = spots[1].clone();
+        taken.id = Some(ids[0]);
+        assert!(insert_spots_batch(&[spots[0].clone(), taken]).is_err());
+        assert_eq!(count_spots_by_period(period)?, count);
+        Ok(())
+    }
+
     #[test]
     fn test_mark_spots_deprecated() -> anyhow::Result<()> {
         // First insert some test spots
@@ -425,7 +825,7 @@ mod test {
         assert!(!spot_ids.is_empty());
 
         // Mark them as deprecated
-        let updated_count = mark_spots_deprecated(&spot_ids)?;
+        let updated_count = mark_spots_deprecated(&spot_ids, "cli", Some("test"))?;
         log::info!("Marked {updated_count} spots as deprecated");
 
         // Verify they were marked
@@ -438,6 +838,60 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_update_prize_statuses() -> anyhow::Result<()> {
+        let period = "2025990";
+        let spots = [[5, 10, 15, 20, 25, 30], [6, 11, 16, 21, 26, 31]]
+            .into_iter()
+            .map(|reds| Spot::from_dball(period, &DBall::new_one(reds, 3)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ids = insert_spots_batch(&spots)?;
+        let status = |id: i32| -> anyhow::Result<Option<i32>> {
+            Ok(get_spot_by_id(id)?
+                .ok_or(anyhow::anyhow!("Spot missing"))?
+                .prize_status)
+        };
+
+        assert!(update_prize_statuses(&[(ids[0], 5), (i32::MAX, 10)]).is_err());
+        assert_eq!(status(ids[0])?, None);
+
+        update_prize_statuses(&[(ids[0], 5), (ids[1], 0)])?;
+        assert_eq!(status(ids[0])?, Some(5));
+        assert_eq!(status(ids[1])?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_spots() -> anyhow::Result<()> {
+        let period = "2025995";
+        let spots = [[3, 9, 14, 20, 27, 31], [2, 11, 18, 22, 25, 33]]
+            .into_iter()
+            .map(|reds| Spot::from_dball(period, &DBall::new_one(reds, 7)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ids = insert_spots_batch(&spots)?;
+
+        assert_eq!(mark_spots_deprecated(&ids, "ipc", Some("last batch"))?, 2);
+        assert_eq!(mark_spots_deprecated(&ids, "ipc", None)?, 0);
+        assert_eq!(
+            restore_spots(&ids[..1], "http", Some("pressed D by mistake"))?,
+            ids[..1]
+        );
+        assert!(restore_spots(&ids[..1], "http", None)?.is_empty());
+
+        let spot = get_spot_by_id(ids[0])?.ok_or(anyhow::anyhow!("Spot missing"))?;
+        assert!(!spot.deprecated);
+        let history = get_deprecation_history(ids[0])?;
+        assert_eq!(
+            history
+                .iter()
+                .map(|change| (change.deprecated, change.actor.as_str()))
+                .collect::<Vec<_>>(),
+            [(true, "ipc"), (false, "http")]
+        );
+        assert_eq!(history[1].reason.as_deref(), Some("pressed D by mistake"));
+        Ok(())
+    }
+
     #[test]
     fn test_get_latest_unprized_spots() -> anyhow::Result<()> {
         let spots = get_latest_unprized_spots(3)?;
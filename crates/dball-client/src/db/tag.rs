@@ -0,0 +1,141 @@
+use diesel::dsl::count;
+use diesel::prelude::*;
+
+use crate::db::get_db_connection;
+use crate::models::schema::{spot_tags, tags};
+use crate::models::{Tag, TagCount};
+
+/// Tag the spots `spot_ids` with `name`, creating the tag on first use,
+/// returns the number of spots that did not carry it yet
+#[tracing::instrument]
+pub fn tag_spots(spot_ids: &[i32], name: &str) -> anyhow::Result<usize> {
+    let mut connection = get_db_connection()?;
+    connection
+        .transaction(|connection| {
+            diesel::insert_or_ignore_into(tags::table)
+                .values((
+                    tags::name.eq(name),
+                    tags::created_time.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(connection)?;
+            let tag_id = tags::table
+                .filter(tags::name.eq(name))
+                .select(tags::id.assume_not_null())
+                .first::<i32>(connection)?;
+            let rows = spot_ids
+                .iter()
+                .map(|&spot_id| (spot_tags::spot_id.eq(spot_id), spot_tags::tag_id.eq(tag_id)))
+                .collect::<Vec<_>>();
+            diesel::insert_or_ignore_into(spot_tags::table)
+                .values(rows)
+                .execute(connection)
+        })
+        .map_err(|e: diesel::result::Error| anyhow::anyhow!("Error tagging spots with {name}: {e}"))
+}
+
+/// Take the tag `name` off the spots `spot_ids`, returns the number of spots
+/// that carried it, the tag itself is kept for later use
+#[tracing::instrument]
+pub fn untag_spots(spot_ids: &[i32], name: &str) -> anyhow::Result<usize> {
+    let mut connection = get_db_connection()?;
+    let tag_ids = tags::table
+        .filter(tags::name.eq(name))
+        .select(tags::id.assume_not_null());
+    diesel::delete(
+        spot_tags::table
+            .filter(spot_tags::spot_id.eq_any(spot_ids))
+            .filter(spot_tags::tag_id.eq_any(tag_ids)),
+    )
+    .execute(&mut connection)
+    .map_err(|e| anyhow::anyhow!("Error untagging spots from {name}: {e}"))
+}
+
+/// Every tag by name, with the number of spots carrying it
+pub fn get_all_tags() -> anyhow::Result<Vec<TagCount>> {
+    let mut connection = get_db_connection()?;
+    tags::table
+        .left_join(spot_tags::table)
+        .group_by(tags::name)
+        .select((tags::name, count(spot_tags::spot_id.nullable())))
+        .order(tags::name.asc())
+        .load::<(String, i64)>(&mut connection)
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(name, spots)| TagCount { name, spots })
+                .collect()
+        })
+        .map_err(|e| anyhow::anyhow!("Error loading tags: {e}"))
+}
+
+/// Tags of the spot `spot_id` by name
+pub fn get_spot_tags(spot_id: i32) -> anyhow::Result<Vec<Tag>> {
+    let mut connection = get_db_connection()?;
+    tags::table
+        .inner_join(spot_tags::table)
+        .filter(spot_tags::spot_id.eq(spot_id))
+        .select(Tag::as_select())
+        .order(tags::name.asc())
+        .load::<Tag>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading tags of spot {spot_id}: {e}"))
+}
+
+/// Ids of the spots carrying every tag of `names`
+pub fn get_spot_ids_with_tags(names: &[String]) -> anyhow::Result<Vec<i32>> {
+    let mut connection = get_db_connection()?;
+    let mut names = names.to_vec();
+    names.sort_unstable();
+    names.dedup();
+    spot_tags::table
+        .inner_join(tags::table)
+        .filter(tags::name.eq_any(&names))
+        .group_by(spot_tags::spot_id)
+        .having(count(spot_tags::tag_id).eq(names.len() as i64))
+        .select(spot_tags::spot_id)
+        .order(spot_tags::spot_id.asc())
+        .load::<i32>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading spots tagged {names:?}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::spot::insert_spots_batch;
+    use crate::models::Spot;
+    use dball_combora::dball::DBall;
+
+    #[test]
+    fn test_tag_spots() -> anyhow::Result<()> {
+        let period = "2025993";
+        let spots = [[4, 8, 15, 16, 23, 32], [1, 7, 12, 19, 26, 30]]
+            .into_iter()
+            .map(|reds| Spot::from_dball(period, &DBall::new_one(reds, 9)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ids = insert_spots_batch(&spots)?;
+        let (lucky, pick) = ("test lucky", "test machine pick");
+
+        assert_eq!(tag_spots(&ids, lucky)?, 2);
+        assert_eq!(tag_spots(&ids, lucky)?, 0);
+        assert_eq!(tag_spots(&ids[..1], pick)?, 1);
+        assert_eq!(
+            get_spot_tags(ids[0])?
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect::<Vec<_>>(),
+            [lucky, pick]
+        );
+        assert_eq!(
+            get_spot_ids_with_tags(&[lucky.to_owned(), pick.to_owned()])?,
+            ids[..1]
+        );
+
+        assert_eq!(untag_spots(&ids, pick)?, 1);
+        assert!(get_spot_ids_with_tags(&[pick.to_owned()])?.is_empty());
+        let counts = get_all_tags()?;
+        assert!(counts.contains(&TagCount {
+            name: pick.to_owned(),
+            spots: 0
+        }));
+        assert!(counts.iter().any(|tag| tag.name == lucky && tag.spots >= 2));
+        Ok(())
+    }
+}
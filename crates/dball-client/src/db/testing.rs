@@ -0,0 +1,175 @@
+//! A small, deterministic database for tests, so they never touch real data
+
+use std::path::Path;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use dball_combora::dball::DBall;
+use diesel::prelude::*;
+use diesel_migrations::MigrationHarness as _;
+
+use crate::db::maintenance::MIGRATIONS;
+use crate::models::schema::{spot, ticket_log, tickets};
+use crate::models::{NewTicketLog, Spot, Ticket};
+
+/// Drawn tickets of the fixture as `(period, draw time, reds, blue)`
+pub const FIXTURE_DRAWS: [(&str, &str, [i32; 6], i32); 9] = [
+    ("2025080", "2025-07-13 21:15:00", [3, 8, 12, 19, 26, 31], 5),
+    ("2025081", "2025-07-15 21:15:00", [1, 7, 14, 20, 27, 33], 9),
+    ("2025082", "2025-07-17 21:15:00", [2, 6, 11, 18, 24, 30], 14),
+    ("2025083", "2025-07-20 21:15:00", [5, 9, 13, 22, 28, 32], 2),
+    ("2025084", "2025-07-22 21:15:00", [2, 6, 13, 16, 28, 31], 11),
+    ("2025085", "2025-07-24 21:15:00", [4, 10, 15, 21, 25, 29], 7),
+    ("2025086", "2025-07-27 21:15:00", [8, 9, 19, 24, 27, 33], 3),
+    ("2025087", "2025-07-29 21:15:00", [1, 5, 17, 23, 26, 30], 12),
+    (
+        "2025088",
+        "2025-07-31 21:15:00",
+        [6, 11, 16, 20, 29, 33],
+        16,
+    ),
+];
+
+/// Spots of the fixture on top of the ones inserted by the migrations,
+/// as `(period, reds, blue, purchased)`, none of them settled yet
+pub const FIXTURE_SPOTS: [(&str, [u8; 6], u8, bool); 4] = [
+    ("2025087", [1, 5, 17, 23, 28, 32], 12, true),
+    ("2025087", [2, 9, 14, 20, 25, 31], 4, false),
+    ("2025088", [6, 11, 16, 20, 29, 33], 1, true),
+    ("2025088", [3, 7, 12, 21, 27, 30], 16, false),
+];
+
+/// Time every fixture row was created and last modified
+pub const FIXTURE_TIME: &str = "2025-08-01 08:00:00";
+
+/// Recreate the database at `path` from the migrations and fill it with the fixture
+///
+/// The result only depends on the migrations and the constants above, any
+/// database already at `path` is removed first
+pub fn seed_fixture(path: &Path) -> anyhow::Result<()> {
+    for file in [
+        path.to_path_buf(),
+        path.with_extension("db-shm"),
+        path.with_extension("db-wal"),
+    ] {
+        if file.exists() {
+            std::fs::remove_file(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to remove {}: {e}", file.display()))?;
+        }
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let url = path.display().to_string();
+    let mut connection = SqliteConnection::establish(&url)
+        .map_err(|e| anyhow::anyhow!("Error connecting to {url}: {e}"))?;
+    #[cfg(feature = "sqlcipher")]
+    crate::db::cipher::unlock(&mut connection)?;
+    connection
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Failed to run migrations on {url}: {e}"))?;
+
+    let time = NaiveDateTime::parse_from_str(FIXTURE_TIME, "%Y-%m-%d %H:%M:%S")?;
+    let draws = FIXTURE_DRAWS
+        .iter()
+        .map(|(period, draw_time, reds, blue)| {
+            let mut ticket = Ticket::new((*period).to_owned(), draw_time, reds, *blue)?;
+            ticket.created_time = time;
+            ticket.modified_time = time;
+            Ok(ticket)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let spots = FIXTURE_SPOTS
+        .iter()
+        .map(|(period, reds, blue, purchased)| {
+            let dball = DBall::new_one(*reds, *blue)?;
+            let mut spot =
+                Spot::from_dball_with_datetime((*period).to_owned(), &dball, None, time, time)?;
+            spot.purchased = *purchased;
+            Ok(spot)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let logs = [
+        // the very first draw, then two of the fixture draws
+        ticket_log_entry("2003001", "2003-02-23", [10, 11, 12, 13, 26, 28], 11, 0)?,
+        ticket_log_entry(
+            "2025084",
+            "2025-07-22",
+            [2, 6, 13, 16, 28, 31],
+            11,
+            1_876_543_210,
+        )?,
+        ticket_log_entry(
+            "2025086",
+            "2025-07-27",
+            [8, 9, 19, 24, 27, 33],
+            3,
+            1_912_345_678,
+        )?,
+    ];
+
+    connection
+        .transaction(|connection| {
+            diesel::insert_into(tickets::table)
+                .values(&draws)
+                .execute(connection)?;
+            diesel::insert_into(spot::table)
+                .values(&spots)
+                .execute(connection)?;
+            diesel::insert_into(ticket_log::table)
+                .values(&logs)
+                .execute(connection)
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to insert the fixture into {url}: {e}"))?;
+
+    log::debug!("Seeded fixture database: {}", path.display());
+    Ok(())
+}
+
+fn ticket_log_entry(
+    code: &str,
+    date: &str,
+    reds: [i32; 6],
+    blue: i32,
+    jackpot: i32,
+) -> anyhow::Result<NewTicketLog> {
+    let numbers = reds.iter().chain([&blue]).copied().collect::<Vec<_>>();
+    Ok(NewTicketLog {
+        code: code.to_owned(),
+        kj_date: Some(NaiveDate::parse_from_str(date, "%Y-%m-%d")?),
+        xq: None,
+        number1: Some(reds[0]),
+        number2: Some(reds[1]),
+        number3: Some(reds[2]),
+        number4: Some(reds[3]),
+        number5: Some(reds[4]),
+        number6: Some(reds[5]),
+        number7: Some(blue),
+        jsondata: serde_json::to_string(&numbers).ok(),
+        total_sales: Some(380_000_000),
+        jackpot: Some(jackpot),
+        prize1_num: Some(3),
+        prize1_money: Some(7_000_000),
+        prize2_num: Some(120),
+        prize2_money: Some(200_000),
+        prize3_num: Some(1_500),
+        prize3_money: Some(3_000),
+        prize4_num: Some(80_000),
+        prize4_money: Some(200),
+        prize5_num: Some(1_600_000),
+        prize5_money: Some(10),
+        prize6_num: Some(9_000_000),
+        prize6_money: Some(5),
+    })
+}
+
+/// Drawn ticket of `period` built by a test, drawn at `time`
+pub fn ticket(period: &str, time: &str, reds: [i32; 6], blue: i32) -> Ticket {
+    Ticket::new(period.to_owned(), time, &reds, blue).expect("Valid test ticket")
+}
+
+/// Simple spot of `period` built by a test, settled at `prize_status` if given
+pub fn spot(period: &str, reds: [u8; 6], blue: u8, prize_status: Option<i32>) -> Spot {
+    let dball = DBall::new_one(reds, blue).expect("Valid test DBall");
+    Spot::from_dball(period, &dball, prize_status).expect("Valid test spot")
+}
@@ -1,10 +1,16 @@
-use diesel::{BoolExpressionMethods as _, ExpressionMethods as _, QueryDsl as _, RunQueryDsl as _};
+use diesel::{
+    BoolExpressionMethods as _, Connection as _, ExpressionMethods as _, QueryDsl as _,
+    RunQueryDsl as _,
+};
 
 use crate::{
     db::get_db_connection,
-    models::{TicketLog, schema::ticket_log},
+    models::{NewTicketLog, TicketLog, schema::ticket_log},
 };
 
+/// Rows per statement, keeping the bound values below the database's limit
+const UPSERT_CHUNK: usize = 500;
+
 pub fn get_all_records() -> anyhow::Result<Vec<TicketLog>> {
     let mut connection = get_db_connection()?;
     ticket_log::table
@@ -12,6 +18,22 @@ pub fn get_all_records() -> anyhow::Result<Vec<TicketLog>> {
         .map_err(|e| anyhow::anyhow!("Error loading records: {}", e))
 }
 
+/// Insert `records`, replacing the ones with the same code, all or nothing
+#[tracing::instrument(skip_all, fields(records = records.len()))]
+pub fn upsert_records(records: &[NewTicketLog]) -> anyhow::Result<usize> {
+    let mut connection = get_db_connection()?;
+    connection
+        .transaction(|connection| {
+            records.chunks(UPSERT_CHUNK).try_fold(0, |count, chunk| {
+                diesel::replace_into(ticket_log::table)
+                    .values(chunk)
+                    .execute(connection)
+                    .map(|inserted| count + inserted)
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("Error upserting records: {e}"))
+}
+
 pub fn get_record_by_code(record_code: &str) -> anyhow::Result<Option<TicketLog>> {
     let mut connection = get_db_connection()?;
     ticket_log::table
@@ -1,45 +1,79 @@
+use crate::db::cache::PeriodCache;
 use crate::db::get_db_connection;
-use crate::models::Ticket;
 use crate::models::schema::tickets;
+use crate::models::{Page, PageRequest, SortOrder, Ticket};
+use chrono::{NaiveDate, NaiveTime};
 use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use diesel::sqlite::Sqlite;
+use std::sync::LazyLock;
 
+/// Ticket of a period, read on every client refresh
+static TICKET_BY_PERIOD: LazyLock<PeriodCache<String, Option<Ticket>>> =
+    LazyLock::new(PeriodCache::default);
+
+/// Drop the cached tickets once a ticket write is done, returns `written`
+pub(crate) fn invalidating<T>(written: T) -> T {
+    TICKET_BY_PERIOD.invalidate();
+    written
+}
+
+#[tracing::instrument(skip_all, fields(period = %new_ticket.period))]
 pub fn insert_ticket(new_ticket: &Ticket) -> anyhow::Result<()> {
     let mut connection = get_db_connection()?;
-    diesel::insert_into(tickets::table)
-        .values(new_ticket)
-        .execute(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error inserting ticket: {e}"))
-        .and_then(|count| {
-            if count != 1 {
-                Err(anyhow::anyhow!(
-                    "Expected to insert exactly one ticket, but inserted {count} instead"
-                ))
-            } else {
-                Ok(())
-            }
-        })
+    invalidating(
+        diesel::insert_into(tickets::table)
+            .values(new_ticket)
+            .execute(&mut connection),
+    )
+    .map_err(|e| anyhow::anyhow!("Error inserting ticket: {e}"))
+    .and_then(|count| {
+        if count != 1 {
+            Err(anyhow::anyhow!(
+                "Expected to insert exactly one ticket, but inserted {count} instead"
+            ))
+        } else {
+            Ok(())
+        }
+    })
 }
 
-pub fn get_all_tickets() -> anyhow::Result<Vec<Ticket>> {
-    let mut connection = get_db_connection()?;
-    tickets::table
-        .load::<Ticket>(&mut connection)
-        .map_err(|e| anyhow::anyhow!("Error loading tickets: {e}"))
+/// The tickets of `page`, ordered by period
+pub fn get_all_tickets(page: PageRequest) -> anyhow::Result<Page<Ticket>> {
+    load_tickets_page(None, page)
 }
 
-pub fn get_ticket_by_period(period: &str) -> anyhow::Result<Option<Ticket>> {
+/// Insert every ticket in one transaction, none of them when one fails
+#[tracing::instrument(skip_all, fields(tickets = new_tickets.len()))]
+pub fn insert_tickets_batch(new_tickets: &[Ticket]) -> anyhow::Result<usize> {
     let mut connection = get_db_connection()?;
-    tickets::table
-        .filter(tickets::period.eq(period))
-        .load::<Ticket>(&mut connection)
-        .and_then(|results| match results.len() {
-            0 => Ok(None),
-            1 => Ok(results.first().cloned()),
-            _ => Err(diesel::result::Error::QueryBuilderError(Box::new(
-                std::io::Error::other("Multiple records found, but expected only one"),
-            ))),
+    invalidating(connection.transaction(|connection| {
+        new_tickets.iter().try_fold(0, |count, new_ticket| {
+            diesel::insert_into(tickets::table)
+                .values(new_ticket)
+                .execute(connection)
+                .map(|inserted| count + inserted)
         })
-        .map_err(|e| anyhow::anyhow!("Error finding tickets for period {period}: {e}"))
+    }))
+    .map_err(|e| anyhow::anyhow!("Error inserting {} tickets: {e}", new_tickets.len()))
+}
+
+/// The ticket drawn for `period`, served from a cache dropped on every ticket write
+pub fn get_ticket_by_period(period: &str) -> anyhow::Result<Option<Ticket>> {
+    TICKET_BY_PERIOD.get_or_load(period.to_owned(), || {
+        let mut connection = get_db_connection()?;
+        tickets::table
+            .filter(tickets::period.eq(period))
+            .load::<Ticket>(&mut connection)
+            .and_then(|results| match results.len() {
+                0 => Ok(None),
+                1 => Ok(results.first().cloned()),
+                _ => Err(diesel::result::Error::QueryBuilderError(Box::new(
+                    std::io::Error::other("Multiple records found, but expected only one"),
+                ))),
+            })
+            .map_err(|e| anyhow::anyhow!("Error finding tickets for period {period}: {e}"))
+    })
 }
 
 pub fn get_latest_tickets(limit: i64) -> anyhow::Result<Vec<Ticket>> {
@@ -51,18 +85,63 @@ pub fn get_latest_tickets(limit: i64) -> anyhow::Result<Vec<Ticket>> {
         .map_err(|e| anyhow::anyhow!("Error loading latest {limit} tickets: {e}"))
 }
 
+/// The tickets of `page` whose period starts with `prefix`, ordered by period
+pub fn get_tickets_by_period_prefix(
+    prefix: &str,
+    page: PageRequest,
+) -> anyhow::Result<Page<Ticket>> {
+    load_tickets_page(Some(prefix), page)
+}
+
+fn load_tickets_page(prefix: Option<&str>, page: PageRequest) -> anyhow::Result<Page<Ticket>> {
+    let mut connection = get_db_connection()?;
+    let filtered = || {
+        let query = tickets::table.into_boxed();
+        match prefix {
+            Some(prefix) => query.filter(tickets::period.like(format!("{prefix}%"))),
+            None => query,
+        }
+    };
+    let total = filtered()
+        .count()
+        .get_result::<i64>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error counting tickets: {e}"))?;
+
+    let mut query = match page.order {
+        SortOrder::Asc => filtered().order(tickets::period.asc()),
+        SortOrder::Desc => filtered().order(tickets::period.desc()),
+    }
+    .offset(page.offset);
+    if let Some(limit) = page.limit {
+        query = query.limit(limit);
+    }
+    let items = query
+        .load::<Ticket>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading tickets: {e}"))?;
+    Ok(Page {
+        items,
+        total,
+        offset: page.offset,
+    })
+}
+
+/// Whether any of the six reds of a ticket is `number`
+fn has_red(number: i32) -> Box<dyn BoxableExpression<tickets::table, Sqlite, SqlType = Bool>> {
+    Box::new(
+        tickets::red1
+            .eq(number)
+            .or(tickets::red2.eq(number))
+            .or(tickets::red3.eq(number))
+            .or(tickets::red4.eq(number))
+            .or(tickets::red5.eq(number))
+            .or(tickets::red6.eq(number)),
+    )
+}
+
 pub fn find_tickets_with_red_number(number: i32) -> anyhow::Result<Vec<Ticket>> {
     let mut connection = get_db_connection()?;
     tickets::table
-        .filter(
-            tickets::red1
-                .eq(number)
-                .or(tickets::red2.eq(number))
-                .or(tickets::red3.eq(number))
-                .or(tickets::red4.eq(number))
-                .or(tickets::red5.eq(number))
-                .or(tickets::red6.eq(number)),
-        )
+        .filter(has_red(number))
         .order(tickets::id.desc())
         .load::<Ticket>(&mut connection)
         .map_err(|e| anyhow::anyhow!("Error finding tickets with red number {number}: {e}"))
@@ -77,6 +156,61 @@ pub fn find_tickets_with_blue_number(blue: i32) -> anyhow::Result<Vec<Ticket>> {
         .map_err(|e| anyhow::anyhow!("Error finding tickets with blue number {blue}: {e}"))
 }
 
+/// The tickets of the periods from `from` to `to`, both included and either
+/// open when `None`, ordered by period
+pub fn get_tickets_in_period_range(
+    from: Option<&str>,
+    to: Option<&str>,
+) -> anyhow::Result<Vec<Ticket>> {
+    let mut connection = get_db_connection()?;
+    let mut query = tickets::table.into_boxed();
+    if let Some(from) = from {
+        query = query.filter(tickets::period.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(tickets::period.le(to));
+    }
+    query
+        .order(tickets::period.asc())
+        .load::<Ticket>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading tickets from {from:?} to {to:?}: {e}"))
+}
+
+/// The tickets drawn from `start` to `end`, both days included, ordered by period
+pub fn get_tickets_between(start: NaiveDate, end: NaiveDate) -> anyhow::Result<Vec<Ticket>> {
+    let mut connection = get_db_connection()?;
+    let from = start.and_time(NaiveTime::MIN);
+    let until = end
+        .succ_opt()
+        .ok_or_else(|| anyhow::anyhow!("No day after {end}"))?
+        .and_time(NaiveTime::MIN);
+    tickets::table
+        .filter(tickets::time.ge(from))
+        .filter(tickets::time.lt(until))
+        .order(tickets::period.asc())
+        .load::<Ticket>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error loading tickets from {start} to {end}: {e}"))
+}
+
+/// The tickets whose reds include all of `reds` and whose blue is `blue` when
+/// given, ordered by period
+///
+/// With six reds and a blue this answers whether a combination was ever drawn
+pub fn find_tickets_containing(reds: &[i32], blue: Option<i32>) -> anyhow::Result<Vec<Ticket>> {
+    let mut connection = get_db_connection()?;
+    let mut query = tickets::table.into_boxed();
+    for &red in reds {
+        query = query.filter(has_red(red));
+    }
+    if let Some(blue) = blue {
+        query = query.filter(tickets::blue.eq(blue));
+    }
+    query
+        .order(tickets::period.asc())
+        .load::<Ticket>(&mut connection)
+        .map_err(|e| anyhow::anyhow!("Error finding tickets containing {reds:?} {blue:?}: {e}"))
+}
+
 pub fn count_tickets() -> anyhow::Result<i64> {
     let mut connection = get_db_connection()?;
     tickets::table
@@ -106,12 +240,40 @@ mod test {
         Ok(())
     }
 
+    fn periods(tickets: &[Ticket]) -> Vec<&str> {
+        tickets
+            .iter()
+            .map(|ticket| ticket.period.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_tickets_between() -> anyhow::Result<()> {
+        let day = |day| NaiveDate::from_ymd_opt(2025, 7, day).expect("valid date");
+        let tickets = get_tickets_between(day(15), day(20))?;
+        assert_eq!(periods(&tickets), ["2025081", "2025082", "2025083"]);
+        assert!(get_tickets_between(day(20), day(15))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_tickets_containing() -> anyhow::Result<()> {
+        let tickets = find_tickets_containing(&[6, 11], None)?;
+        assert_eq!(periods(&tickets), ["2025082", "2025088"]);
+        let tickets = find_tickets_containing(&[6, 11], Some(16))?;
+        assert_eq!(periods(&tickets), ["2025088"]);
+        let tickets = find_tickets_containing(&[1, 5, 17, 23, 26, 30], Some(12))?;
+        assert_eq!(periods(&tickets), ["2025087"]);
+        assert!(find_tickets_containing(&[1, 5, 17, 23, 26, 30], Some(1))?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn all_tickets() -> anyhow::Result<()> {
         // Retrieve all tickets
 
-        match get_all_tickets() {
-            Ok(tickets) => {
+        match get_all_tickets(PageRequest::default()) {
+            Ok(Page { items: tickets, .. }) => {
                 for ticket in &tickets {
                     log::info!("{ticket}");
                 }
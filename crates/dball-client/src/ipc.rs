@@ -3,10 +3,16 @@
 /// provides the protocol definitions, message encapsulation,
 /// and encoding/decoding functionality for inter-process communication.
 pub mod client;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod codec;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod envelope;
+pub mod error;
 pub mod protocol;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub use codec::*;
+#[cfg(not(target_arch = "wasm32"))]
 pub use envelope::IpcEnvelope;
+pub use error::{RpcError, RpcErrorCode};
 pub use protocol::*;
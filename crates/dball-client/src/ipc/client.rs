@@ -1,8 +1,18 @@
+#[cfg(not(target_arch = "wasm32"))]
 #[expect(clippy::module_inception)]
 pub mod client;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod reconnect;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod subscriber;
+pub mod transport;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub use client::IpcClient;
+#[cfg(not(target_arch = "wasm32"))]
 pub use reconnect::ReconnectManager;
+#[cfg(not(target_arch = "wasm32"))]
 pub use subscriber::StateSubscriber;
+#[cfg(not(target_arch = "wasm32"))]
+pub use transport::DaemonTransport;
+pub use transport::{HttpTransport, RpcTransport, TransportMode};
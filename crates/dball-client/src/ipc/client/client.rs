@@ -3,13 +3,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
 use tokio::net::UnixStream;
-use tokio::sync::{RwLock, mpsc, oneshot};
+use tokio::sync::{RwLock, broadcast, mpsc, oneshot};
 
 use crate::ipc::{
     codec::{FrameBuffer, IpcCodec},
     envelope::{IpcEnvelope, IpcKind},
-    protocol::{AppState, EventType, HelloMessage, SubscribeMessage},
+    protocol::{AppState, EventMessage, EventType, HelloMessage, SubscribeMessage},
 };
+use crate::service::{DaemonEvent, OperationProgress};
+
+type PendingRequests = Arc<RwLock<HashMap<String, oneshot::Sender<serde_json::Value>>>>;
+type Listeners<T> = Arc<RwLock<HashMap<String, mpsc::UnboundedSender<T>>>>;
+
+/// Receivers of the messages a pending request gets before its response, by request uuid
+#[derive(Clone, Default)]
+struct RequestListeners {
+    progress: Listeners<OperationProgress>,
+    chunks: Listeners<serde_json::Value>,
+}
 
 #[derive(Debug, Clone)]
 pub enum ClientState {
@@ -34,7 +45,13 @@ pub struct IpcClient {
     /// Message sender channel
     message_sender: Option<mpsc::UnboundedSender<IpcEnvelope>>,
     /// Pending requests waiting for responses
-    pending_requests: Arc<RwLock<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+    pending_requests: PendingRequests,
+    /// Progress and chunk receivers of pending requests
+    listeners: RequestListeners,
+    /// Events pushed by the daemon
+    event_sender: broadcast::Sender<DaemonEvent>,
+    /// Profile named in the Hello message, the requests act for it
+    profile: Option<String>,
 }
 
 impl IpcClient {
@@ -46,7 +63,7 @@ impl IpcClient {
     #[cfg(windows)]
     const PIPE_NAME: &'static str = r"\\.\pipe\dball-daemon";
 
-    /// Create a new IPC client
+    /// Create a new IPC client acting for the profile of `DBALL_PROFILE`, if set
     pub fn new() -> Self {
         #[cfg(unix)]
         let socket_path = Self::SOCKET_PATH.to_owned();
@@ -54,13 +71,28 @@ impl IpcClient {
         #[cfg(windows)]
         let socket_path = Self::PIPE_NAME.to_string();
 
+        #[cfg(feature = "simulation")]
+        let socket_path = crate::simulation::scoped_path(&socket_path);
+
         Self {
             state: Arc::new(RwLock::new(ClientState::Disconnected)),
             socket_path,
             app_state: Arc::new(RwLock::new(None)),
             message_sender: None,
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
+            listeners: RequestListeners::default(),
+            event_sender: broadcast::channel(64).0,
+            profile: None,
         }
+        .with_profile(std::env::var("DBALL_PROFILE").ok())
+    }
+
+    /// Act for the profile `name` from the next connection on, a blank name
+    /// sees every spot
+    #[must_use]
+    pub fn with_profile(mut self, name: Option<String>) -> Self {
+        self.profile = name.filter(|name| !name.trim().is_empty());
+        self
     }
 
     pub async fn new_connected() -> Result<Self> {
@@ -93,6 +125,8 @@ impl IpcClient {
         let state = self.state.clone();
         let app_state = self.app_state.clone();
         let pending_requests = self.pending_requests.clone();
+        let listeners = self.listeners.clone();
+        let event_sender = self.event_sender.clone();
 
         tokio::spawn(async move {
             if let Err(e) = Self::handle_connection(
@@ -100,6 +134,8 @@ impl IpcClient {
                 state,
                 app_state,
                 pending_requests,
+                listeners,
+                event_sender,
                 message_receiver,
             )
             .await
@@ -129,11 +165,60 @@ impl IpcClient {
         self.app_state.clone()
     }
 
+    /// Receive the events the daemon pushes from now on
+    pub fn subscribe_daemon_events(&self) -> broadcast::Receiver<DaemonEvent> {
+        self.event_sender.subscribe()
+    }
+
     pub async fn send_rpc_request(
         &self,
         service: crate::ipc::protocol::RpcService,
     ) -> Result<serde_json::Value> {
         let envelope = IpcEnvelope::new(IpcKind::Request(service), serde_json::Value::Null);
+        self.send_request_envelope(envelope).await
+    }
+
+    /// Send a RPC request and forward the progress reports of the daemon to `progress`
+    /// until the response arrives
+    pub async fn send_rpc_request_with_progress(
+        &self,
+        service: crate::ipc::protocol::RpcService,
+        progress: mpsc::UnboundedSender<OperationProgress>,
+    ) -> Result<serde_json::Value> {
+        let envelope = IpcEnvelope::new(IpcKind::Request(service), serde_json::Value::Null);
+        let request_uuid = envelope.uuid.clone();
+
+        self.listeners
+            .progress
+            .write()
+            .await
+            .insert(request_uuid.clone(), progress);
+        let response = self.send_request_envelope(envelope).await;
+        self.listeners.progress.write().await.remove(&request_uuid);
+        response
+    }
+
+    /// Send a RPC request and forward the chunks of its result to `chunks`
+    /// until the response arrives, e.g. the pages of `StreamSpots`
+    pub async fn send_rpc_request_chunked(
+        &self,
+        service: crate::ipc::protocol::RpcService,
+        chunks: mpsc::UnboundedSender<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let envelope = IpcEnvelope::new(IpcKind::Request(service), serde_json::Value::Null);
+        let request_uuid = envelope.uuid.clone();
+
+        self.listeners
+            .chunks
+            .write()
+            .await
+            .insert(request_uuid.clone(), chunks);
+        let response = self.send_request_envelope(envelope).await;
+        self.listeners.chunks.write().await.remove(&request_uuid);
+        response
+    }
+
+    async fn send_request_envelope(&self, envelope: IpcEnvelope) -> Result<serde_json::Value> {
         let request_uuid = envelope.uuid.clone();
         log::debug!("Sending RPC request id : {request_uuid}");
 
@@ -181,6 +266,7 @@ impl IpcClient {
             client_info: Some("dball-tui".to_owned()),
             server_name: None,
             supported_features: vec!["basic_rpc".to_owned(), "state_subscription".to_owned()],
+            profile: self.profile.clone(),
         };
 
         let envelope = IpcEnvelope::new(IpcKind::Hello, serde_json::to_value(hello_msg)?);
@@ -208,6 +294,29 @@ impl IpcClient {
 
         let envelope = IpcEnvelope::new(IpcKind::Subscribe, serde_json::to_value(subscribe_msg)?);
 
+        // the daemon answers with its current state, later changes arrive as events
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.pending_requests
+            .write()
+            .await
+            .insert(envelope.uuid.clone(), response_sender);
+        let app_state = self.app_state.clone();
+        tokio::spawn(async move {
+            let Ok(response) = response_receiver.await else {
+                return;
+            };
+            match serde_json::from_value::<AppState>(response) {
+                Ok(state) => {
+                    let mut app_state = app_state.write().await;
+                    // an event may have arrived first
+                    if app_state.is_none() {
+                        *app_state = Some(state);
+                    }
+                }
+                Err(e) => log::error!("Failed to parse subscribe response: {e}"),
+            }
+        });
+
         if let Some(sender) = &self.message_sender {
             sender.send(envelope)?;
             *self.state.write().await = ClientState::Subscribed;
@@ -221,7 +330,9 @@ impl IpcClient {
         mut stream: UnixStream,
         state: Arc<RwLock<ClientState>>,
         app_state: Arc<RwLock<Option<AppState>>>,
-        pending_requests: Arc<RwLock<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+        pending_requests: PendingRequests,
+        listeners: RequestListeners,
+        event_sender: broadcast::Sender<DaemonEvent>,
         mut message_receiver: mpsc::UnboundedReceiver<IpcEnvelope>,
     ) -> Result<()> {
         let mut buffer = FrameBuffer::new();
@@ -240,7 +351,7 @@ impl IpcClient {
                             buffer.push(&read_buf[0..n]);
 
                             while let Some(envelope) = buffer.try_decode::<serde_json::Value>()? {
-                                Self::process_server_message(envelope, &app_state, &pending_requests).await?;
+                                Self::process_server_message(envelope, &app_state, &pending_requests, &listeners, &event_sender).await?;
                             }
                         }
                         Err(e) => {
@@ -267,7 +378,9 @@ impl IpcClient {
     async fn process_server_message(
         envelope: IpcEnvelope,
         app_state: &Arc<RwLock<Option<AppState>>>,
-        pending_requests: &Arc<RwLock<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+        pending_requests: &PendingRequests,
+        listeners: &RequestListeners,
+        event_sender: &broadcast::Sender<DaemonEvent>,
     ) -> Result<()> {
         match envelope.kind {
             IpcKind::Hello => {
@@ -285,8 +398,43 @@ impl IpcClient {
                     return Ok(());
                 };
             }
+            IpcKind::Progress => {
+                let listeners = listeners.progress.read().await;
+                if let Some(listener) = listeners.get(&envelope.uuid) {
+                    match serde_json::from_value::<OperationProgress>(envelope.msg) {
+                        Ok(progress) => {
+                            if listener.send(progress).is_err() {
+                                log::debug!("Progress receiver of {} dropped", envelope.uuid);
+                            }
+                        }
+                        Err(e) => log::error!("Failed to parse progress message: {e}"),
+                    }
+                } else {
+                    log::debug!("No progress listener for UUID: {}", envelope.uuid);
+                }
+            }
+            IpcKind::Chunk => {
+                let listeners = listeners.chunks.read().await;
+                if let Some(listener) = listeners.get(&envelope.uuid) {
+                    if listener.send(envelope.msg).is_err() {
+                        log::debug!("Chunk receiver of {} dropped", envelope.uuid);
+                    }
+                } else {
+                    log::warn!("No chunk listener for UUID: {}", envelope.uuid);
+                }
+            }
             IpcKind::Event => {
-                if let Ok(state) = serde_json::from_value::<AppState>(envelope.msg) {
+                // service events are wrapped in an `EventMessage`, state updates are sent as is
+                if let Ok(message) = serde_json::from_value::<EventMessage>(envelope.msg.clone()) {
+                    match serde_json::from_value::<DaemonEvent>(message.data) {
+                        Ok(event) => {
+                            if event_sender.send(event).is_err() {
+                                log::debug!("No listener for daemon events");
+                            }
+                        }
+                        Err(e) => log::error!("Failed to parse daemon event: {e}"),
+                    }
+                } else if let Ok(state) = serde_json::from_value::<AppState>(envelope.msg) {
                     *app_state.write().await = Some(state);
                     log::debug!("Updated app state from event");
                 }
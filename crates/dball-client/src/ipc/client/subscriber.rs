@@ -37,7 +37,7 @@ impl StateSubscriber {
         self
     }
 
-    pub async fn start_subscription(&self, client: &mut IpcClient) -> Result<()> {
+    pub async fn start_subscription(&self, client: &IpcClient) -> Result<()> {
         log::info!("Starting state subscription");
 
         if let Some(initial_state) = client.get_app_state().await {
@@ -131,7 +131,8 @@ impl StateSubscriber {
         }
     }
 
-    async fn update_state(&self, new_state: AppState) -> Result<()> {
+    /// Replace the current state, used by clients that poll the daemon instead of subscribing
+    pub async fn update_state(&self, new_state: AppState) -> Result<()> {
         *self.current_state.write().await = Some(new_state.clone());
 
         // notify subscribers
@@ -249,7 +250,9 @@ mod tests {
             pending_tickets: vec![],
             unprize_spots_count: 0,
             total_investment: 0.0,
+            theoretical_investment: 0.0,
             total_return: 0.0,
+            claimed_return: 0.0,
             api_status: crate::ipc::protocol::ApiStatusInfo {
                 api_provider: "mxnzp".to_owned(),
                 last_success: None,
@@ -260,6 +263,7 @@ mod tests {
             daemon_uptime: Duration::from_secs(0),
             generation_status: crate::ipc::protocol::GenerationStatus::Idle,
             last_generation_time: None,
+            budget: Default::default(),
         };
 
         // 更新状态
@@ -299,7 +303,9 @@ mod tests {
             pending_tickets: vec![],
             unprize_spots_count: 5,
             total_investment: 0.0,
+            theoretical_investment: 0.0,
             total_return: 0.0,
+            claimed_return: 0.0,
             api_status: crate::ipc::protocol::ApiStatusInfo {
                 api_provider: "mxnzp".to_owned(),
                 last_success: None,
@@ -310,6 +316,7 @@ mod tests {
             daemon_uptime: Duration::from_secs(0),
             generation_status: crate::ipc::protocol::GenerationStatus::Idle,
             last_generation_time: None,
+            budget: Default::default(),
         };
 
         subscriber
@@ -341,7 +348,9 @@ mod tests {
                 pending_tickets: vec![],
                 unprize_spots_count: 0,
                 total_investment: 0.0,
+                theoretical_investment: 0.0,
                 total_return: 0.0,
+                claimed_return: 0.0,
                 api_status: crate::ipc::protocol::ApiStatusInfo {
                     api_provider: "mxnzp".to_owned(),
                     last_success: None,
@@ -352,6 +361,7 @@ mod tests {
                 daemon_uptime: Duration::from_secs(0),
                 generation_status: crate::ipc::protocol::GenerationStatus::Idle,
                 last_generation_time: None,
+                budget: Default::default(),
             };
 
             subscriber_clone
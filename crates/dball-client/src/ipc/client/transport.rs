@@ -0,0 +1,276 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::IpcClient;
+use crate::ipc::error::{RpcError, RpcErrorCode};
+use crate::ipc::protocol::RpcService;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::server::{DEFAULT_PORT, HttpServerConfig};
+
+/// A way of sending RPC requests to the daemon
+///
+/// Responses are in the shape the IPC server sends them, so callers can switch
+/// transports without changing how they parse responses
+#[expect(async_fn_in_trait)]
+pub trait RpcTransport: Send + Sync {
+    /// Short name for logs and status display
+    fn name(&self) -> &'static str;
+
+    async fn send_rpc(&self, service: RpcService) -> Result<Value>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RpcTransport for IpcClient {
+    fn name(&self) -> &'static str {
+        "ipc"
+    }
+
+    async fn send_rpc(&self, service: RpcService) -> Result<Value> {
+        self.send_rpc_request(service).await
+    }
+}
+
+/// How a client reaches the daemon
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportMode {
+    /// Unix socket, falling back to HTTP when the socket is not available
+    #[default]
+    Auto,
+    Ipc,
+    Http,
+}
+
+impl TransportMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "ipc" => Some(Self::Ipc),
+            "http" => Some(Self::Http),
+            _ => None,
+        }
+    }
+}
+
+/// Transport picked by [`TransportMode`] once the daemon answered over it
+#[cfg(not(target_arch = "wasm32"))]
+pub enum DaemonTransport {
+    Ipc(IpcClient),
+    Http(HttpTransport),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DaemonTransport {
+    /// Connect with `mode`, `http` is used in HTTP mode and as the fallback of auto mode
+    pub async fn connect(mode: TransportMode, http: HttpTransport) -> Result<Self> {
+        let connect_http = |http: HttpTransport| async move {
+            http.check_health().await?;
+            log::info!("Using the HTTP API at {}", http.base_url());
+            Ok(Self::Http(http))
+        };
+        match mode {
+            TransportMode::Ipc => Ok(Self::Ipc(IpcClient::new_connected().await?)),
+            TransportMode::Http => connect_http(http).await,
+            TransportMode::Auto => match IpcClient::new_connected().await {
+                Ok(client) => Ok(Self::Ipc(client)),
+                Err(e) => {
+                    log::warn!("Unix socket unavailable, falling back to HTTP: {e}");
+                    connect_http(http).await
+                }
+            },
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RpcTransport for DaemonTransport {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Ipc(client) => client.name(),
+            Self::Http(http) => http.name(),
+        }
+    }
+
+    async fn send_rpc(&self, service: RpcService) -> Result<Value> {
+        match self {
+            Self::Ipc(client) => client.send_rpc(service).await,
+            Self::Http(http) => http.send_rpc(service).await,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HttpApiError {
+    code: String,
+    message: String,
+}
+
+impl HttpApiError {
+    fn into_rpc_error(self) -> RpcError {
+        RpcError::new(RpcErrorCode::from_api_code(&self.code), self.message)
+    }
+}
+
+/// Body of the responses of the HTTP API
+#[derive(Deserialize)]
+struct HttpApiResponse {
+    success: bool,
+    data: Option<Value>,
+    error: Option<HttpApiError>,
+}
+
+/// Sends requests to the `/api/rpc` endpoint of the daemon's HTTP server,
+/// for clients that can't reach its Unix socket
+pub struct HttpTransport {
+    base_url: String,
+    /// Bearer token sent with every request, for daemons that require one
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpTransport {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            token: None,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Authenticate with `token`, a blank token sends none
+    #[must_use]
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token.filter(|token| !token.trim().is_empty());
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Address and token from the `DBALL_HTTP_*` variables the server is configured with,
+    /// the default address when they are invalid or only bind Unix sockets
+    pub fn from_env() -> Self {
+        let config = HttpServerConfig::from_env().unwrap_or_else(|e| {
+            log::warn!("{e}, connecting to the default HTTP address");
+            HttpServerConfig::default()
+        });
+        let base_url = config
+            .client_url()
+            .unwrap_or_else(|| format!("http://127.0.0.1:{DEFAULT_PORT}"));
+        Self::new(base_url).with_token(config.auth_token)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Check that the HTTP server answers
+    pub async fn check_health(&self) -> Result<()> {
+        let url = format!("{}/health", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach daemon at {}: {e}", self.base_url))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Daemon health check failed with {}", response.status());
+        }
+        Ok(())
+    }
+
+    /// Rebuild the IPC response from the HTTP envelope, services answered over
+    /// IPC with a `Result` get their data wrapped in `Ok` or their error in `Err`
+    fn ipc_value(service: &RpcService, response: HttpApiResponse) -> Result<Value> {
+        if matches!(service, RpcService::GetCurrentState) {
+            return match (response.success, response.data, response.error) {
+                (true, Some(data), _) => Ok(data),
+                (_, _, Some(error)) => Err(error.into_rpc_error().into()),
+                _ => Err(anyhow!("Empty response from the HTTP API")),
+            };
+        }
+
+        let value = if response.success {
+            serde_json::json!({ "Ok": response.data.unwrap_or(Value::Null) })
+        } else {
+            let error = response.error.map_or_else(
+                || RpcError::new(RpcErrorCode::Internal, "Unknown error from the HTTP API"),
+                HttpApiError::into_rpc_error,
+            );
+            serde_json::json!({ "Err": error })
+        };
+        Ok(value)
+    }
+}
+
+impl RpcTransport for HttpTransport {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn send_rpc(&self, service: RpcService) -> Result<Value> {
+        let url = format!("{}/api/rpc", self.base_url);
+        log::debug!("Sending RPC {service:?} to {url}");
+        let mut request = self.client.post(&url).json(&service);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach daemon at {}: {e}", self.base_url))?
+            .json::<HttpApiResponse>()
+            .await
+            .map_err(|e| anyhow!("Invalid response from the HTTP API: {e}"))?;
+        Self::ipc_value(&service, response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_response_matches_ipc_shape() -> Result<()> {
+        let ok = HttpApiResponse {
+            success: true,
+            data: Some(serde_json::json!(true)),
+            error: None,
+        };
+        let value = HttpTransport::ipc_value(&RpcService::CancelOperation("op".to_owned()), ok)?;
+        assert_eq!(
+            serde_json::from_value::<Result<bool, RpcError>>(value)?,
+            Ok(true)
+        );
+
+        let failed = HttpApiResponse {
+            success: false,
+            data: None,
+            error: Some(HttpApiError {
+                code: "busy".to_owned(),
+                message: "boom".to_owned(),
+            }),
+        };
+        let value = HttpTransport::ipc_value(&RpcService::GetConfig, failed)?;
+        assert_eq!(
+            serde_json::from_value::<Result<bool, RpcError>>(value)?,
+            Err(RpcError::new(RpcErrorCode::Busy, "boom"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_transport_trims_base_url() {
+        let transport = HttpTransport::new("http://localhost:8081/");
+        assert_eq!(transport.base_url(), "http://localhost:8081");
+        assert_eq!(transport.name(), "http");
+        assert!(transport.with_token(Some(" ".to_owned())).token.is_none());
+    }
+
+    #[test]
+    fn test_transport_mode_from_name() {
+        assert_eq!(TransportMode::from_name("HTTP"), Some(TransportMode::Http));
+        assert_eq!(TransportMode::from_name("ipc"), Some(TransportMode::Ipc));
+        assert_eq!(TransportMode::from_name("socket"), None);
+    }
+}
@@ -184,6 +184,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned()],
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
@@ -217,6 +218,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: large_features.clone(),
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
@@ -250,6 +252,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned()],
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
@@ -288,6 +291,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned()],
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
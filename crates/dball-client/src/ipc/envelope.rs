@@ -56,6 +56,10 @@ pub enum IpcKind {
     Request(RpcService),
     /// Server response (success/failure)
     Response,
+    /// Progress of a running request, carries the uuid of that request
+    Progress,
+    /// Part of the result of a streamed request, carries the uuid of that request
+    Chunk,
     /// Event notification (status change)
     Event,
     /// Error message
@@ -74,6 +78,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned()],
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
@@ -93,6 +98,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned()],
+            profile: None,
         };
 
         let envelope = IpcEnvelope::new(
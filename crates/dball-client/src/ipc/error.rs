@@ -0,0 +1,183 @@
+#[cfg(not(target_arch = "wasm32"))]
+use dball_combora::dball::DBallError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::api::ProviderError;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::service::{BudgetExceeded, OperationCancelled, OperationRunning};
+
+/// Category of a failed RPC, decides whether retrying can help
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum RpcErrorCode {
+    /// The lottery API could not be reached or answered with an error
+    ProviderUnavailable,
+    /// The database or daemon is busy with other work
+    Busy,
+    NotFound,
+    InvalidRequest,
+    Cancelled,
+    /// The daemon could not be reached
+    Transport,
+    /// The daemon's HTTP API requires an auth token that was missing or wrong
+    Unauthorized,
+    Internal,
+}
+
+impl RpcErrorCode {
+    const ALL: [Self; 8] = [
+        Self::ProviderUnavailable,
+        Self::Busy,
+        Self::NotFound,
+        Self::InvalidRequest,
+        Self::Cancelled,
+        Self::Transport,
+        Self::Unauthorized,
+        Self::Internal,
+    ];
+
+    /// Code used in the HTTP API error body
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ProviderUnavailable => "provider_unavailable",
+            Self::Busy => "busy",
+            Self::NotFound => "not_found",
+            Self::InvalidRequest => "bad_request",
+            Self::Cancelled => "cancelled",
+            Self::Transport => "transport",
+            Self::Unauthorized => "unauthorized",
+            Self::Internal => "internal_error",
+        }
+    }
+
+    /// Parse a code of the HTTP API, unknown codes are internal errors
+    pub fn from_api_code(code: &str) -> Self {
+        match code {
+            "not_supported" => Self::InvalidRequest,
+            _ => Self::ALL
+                .into_iter()
+                .find(|known| known.as_str() == code)
+                .unwrap_or(Self::Internal),
+        }
+    }
+
+    /// Whether the same request may succeed when sent again later
+    pub fn is_transient(self) -> bool {
+        matches!(
+            self,
+            Self::ProviderUnavailable | Self::Busy | Self::Transport
+        )
+    }
+}
+
+/// Error of a RPC as sent to the clients
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct RpcError {
+    pub code: RpcErrorCode,
+    pub message: String,
+    /// Uuid of the failed request, `None` when it never reached the daemon
+    pub request_id: Option<String>,
+}
+
+impl RpcError {
+    pub fn new(code: RpcErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// Classify a service error by the errors in its chain
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_anyhow(error: &anyhow::Error) -> Self {
+        let code = error
+            .chain()
+            .find_map(|cause| {
+                if cause.is::<OperationCancelled>() {
+                    Some(RpcErrorCode::Cancelled)
+                } else if cause.is::<DBallError>()
+                    || cause.is::<BudgetExceeded>()
+                    || cause.is::<OperationRunning>()
+                {
+                    Some(RpcErrorCode::InvalidRequest)
+                } else if cause.is::<reqwest::Error>() || cause.is::<ProviderError>() {
+                    Some(RpcErrorCode::ProviderUnavailable)
+                } else if cause.is::<diesel::r2d2::PoolError>() {
+                    Some(RpcErrorCode::Busy)
+                } else if let Some(db_error) = cause.downcast_ref::<diesel::result::Error>() {
+                    Self::classify_database(db_error)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(RpcErrorCode::Internal);
+        Self::new(code, format!("{error:#}"))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn classify_database(error: &diesel::result::Error) -> Option<RpcErrorCode> {
+        match error {
+            diesel::result::Error::NotFound => Some(RpcErrorCode::NotFound),
+            diesel::result::Error::DatabaseError(_, info)
+                if info.message().contains("locked") || info.message().contains("busy") =>
+            {
+                Some(RpcErrorCode::Busy)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.code.is_transient()
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_errors() {
+        let cancelled = anyhow::Error::new(OperationCancelled).context("Generating spots");
+        let error = RpcError::from_anyhow(&cancelled);
+        assert_eq!(error.code, RpcErrorCode::Cancelled);
+        assert_eq!(error.message, "Generating spots: Operation cancelled");
+
+        let missing = anyhow::Error::new(diesel::result::Error::NotFound);
+        assert_eq!(RpcError::from_anyhow(&missing).code, RpcErrorCode::NotFound);
+
+        let other = RpcError::from_anyhow(&anyhow::anyhow!("boom"));
+        assert_eq!(other.code, RpcErrorCode::Internal);
+        assert!(!other.is_transient());
+    }
+
+    #[test]
+    fn test_api_codes_roundtrip() {
+        for code in RpcErrorCode::ALL {
+            assert_eq!(RpcErrorCode::from_api_code(code.as_str()), code);
+        }
+        assert_eq!(
+            RpcErrorCode::from_api_code("not_supported"),
+            RpcErrorCode::InvalidRequest
+        );
+        assert_eq!(
+            RpcErrorCode::from_api_code("teapot"),
+            RpcErrorCode::Internal
+        );
+    }
+}
@@ -1,3 +1,9 @@
+use crate::config::DballConfig;
+use crate::models::{
+    ExportRequest, ManualSpot, NewPrizeClaim, NewPurchase, PageRequest, RecommendRequest,
+    SpotFilter, SpotQuery, TicketAuditRequest,
+};
+use crate::service::BudgetStatus;
 use chrono::{DateTime, Utc};
 use dball_combora::dball::DBall;
 use schemars::JsonSchema;
@@ -5,22 +11,116 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 /// Rpc service definition
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, strum_macros::IntoStaticStr)]
 pub enum RpcService {
-    GenerateBatchSpots,
+    /// Generate a batch for the next period with the named strategy, the
+    /// configured one without
+    GenerateBatchSpots(Option<String>),
+    /// Generate a batch for each of the next draws with the named strategy,
+    /// responds with the `PlannedBatch` of every period
+    GenerateSpotsForPeriods(usize, Option<String>),
+    /// Generate candidate batches and respond with the best ones as
+    /// `Recommendation`s without saving them
+    Recommend(RecommendRequest),
+    /// Replay the named strategy, the configured one without, over the draws
+    /// of a year, responds with the `StrategySimulation`
+    SimulateStrategy(i32, Option<String>),
 
     UpdateAllUnprizeSpots,
+    /// Fetch the draw of a period, settle its spots and add their return to
+    /// the state, responds with a `PeriodEvaluation`
+    EvaluatePeriod(String),
     DeprecatedLastBatchUnprizedSpot,
+    /// Deprecate one unprized spot by id
+    DeprecateSpot(i32),
+    /// Restore deprecated spots by id with why, those of the latest
+    /// deprecation without any ids, responds with the ids restored
+    RestoreSpots(Vec<i32>, Option<String>),
+    /// Set the note of a spot by id, `None` clears it
+    AnnotateSpot(i32, Option<String>),
+    MarkSpotPurchased(i32, bool),
+    /// Label spots by id with a tag, responds with the number of spots newly tagged
+    TagSpots(Vec<i32>, String),
+    /// Take a tag off spots by id, responds with the number of spots untagged
+    UntagSpots(Vec<i32>, String),
+    /// Save numbers picked by hand as a spot of the next period, responds with the period
+    AddManualSpot(ManualSpot),
+    /// Record tickets bought at a store and mark their spots purchased,
+    /// responds with the id of the purchase
+    RecordPurchase(NewPurchase),
+    /// Record the prize of a winning spot as claimed with the amount paid
+    /// out, responds with the id of the claim
+    ClaimPrize(NewPrizeClaim),
 
     UpdateLatestTicket,
-    CrawlAllTickets,
-    UpdateTicketsByPeriod(Vec<String>),
-    UpdateTicketsWithYear(i32),
+    /// Crawl the tickets of every year, responds with a `CrawlReport`; a dry
+    /// run only reports what would be inserted
+    CrawlAllTickets(bool),
+    /// Fetch the tickets of periods, responds with the result of each; a dry
+    /// run tells whether they would be inserted
+    UpdateTicketsByPeriod(Vec<String>, bool),
+    /// Crawl the tickets of a year like `CrawlAllTickets`
+    UpdateTicketsWithYear(i32, bool),
+    /// Fetch stored periods again and compare them with `tickets` and
+    /// `ticket_log`, responds with a `TicketAudit`
+    AuditTickets(TicketAuditRequest),
+    /// Fill `ticket_log` from the published results archive at a path of the
+    /// daemon's machine or a URL, responds with the sync report
+    SyncTicketLog(String),
+    /// Fill `tickets` from the published results archive like `SyncTicketLog`,
+    /// responds with the import report
+    ImportTickets(String),
+    /// Copy the database to a new file at a path of the daemon's machine, or
+    /// next to the database without one, responds with the path written
+    BackupDatabase(Option<String>),
+    /// Integrity check, sizes and row counts of the database
+    GetDbHealth,
+    /// Counters and operation durations of the daemon since it started
+    GetMetrics,
 
     GetCurrentState,
     GetLatestPeriod,
     GetUnprizeSpots,
-    GetPrizedSpots,
+    /// Page of the spots checked against a draw, responds with a `Page`
+    GetPrizedSpots(PageRequest),
+    /// Page of the spots matching the filter, responds with a `Page`
+    FindSpots(SpotFilter, PageRequest),
+    /// Every tag with the number of spots carrying it
+    GetTags,
+    /// Tags of one spot by id
+    GetSpotTags(i32),
+    /// Live spots repeating another one of their period, grouped by period and numbers
+    GetDuplicateSpots,
+    /// Statistics of the spots of the next period as one batch
+    GetNextBatchSummary,
+    /// Red/blue frequency, omission and hot/cold rank over the latest N draws,
+    /// the configured `generator.frequency_window` without N
+    GetNumberFrequencies(Option<usize>),
+    /// Settled spots grouped by period with cost and winnings
+    GetRoiReport,
+    /// Spend, returns, wins by tier and best and worst periods of the settled
+    /// spots drawn in a year, or in the month of it given
+    GetPeriodicReport(i32, Option<u32>),
+    /// Cumulative cost and winnings per period, win rate and break-even
+    /// projection over the latest N periods
+    GetRoiAnalytics(usize),
+    /// Spots matching the search filters
+    QuerySpots(SpotQuery),
+    /// Like `QuerySpots`, but the spots arrive page by page as `Chunk` messages,
+    /// responds with the number of spots sent
+    StreamSpots(SpotQuery),
+    /// Page of the drawn tickets, only the periods starting with the prefix if
+    /// given, responds with a `Page`
+    GetTickets(Option<String>, PageRequest),
+    /// Tickets or spots as CSV text
+    ExportCsv(ExportRequest),
+
+    GetConfig,
+    /// Validate and save `dball.toml`, responds with the validation issues
+    UpdateConfig(Box<DballConfig>),
+
+    /// Cancel a running operation by the id from its progress reports
+    CancelOperation(String),
 
     Shutdown,
     Restart,
@@ -40,6 +140,11 @@ pub struct HelloMessage {
 
     /// Supported features
     pub supported_features: Vec<String>,
+
+    /// C2D, name of the profile whose spots the client sees and saves, every
+    /// spot is seen without one
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 /// 订阅消息
@@ -117,10 +222,19 @@ pub struct AppState {
 
     pub unprize_spots_count: u32,
 
+    /// Yuan paid for the recorded purchases
     pub total_investment: f64,
 
+    /// Yuan every spot not deprecated costs, bought or not
+    pub theoretical_investment: f64,
+
+    /// Yuan the settled spots not deprecated won, claimed or not
     pub total_return: f64,
 
+    /// Yuan paid out for the claimed prizes
+    #[serde(default)]
+    pub claimed_return: f64,
+
     pub api_status: ApiStatusInfo,
 
     pub last_update: DateTime<Utc>,
@@ -130,6 +244,10 @@ pub struct AppState {
     pub generation_status: GenerationStatus,
 
     pub last_generation_time: Option<DateTime<Utc>>,
+
+    /// Spends against the limits of `[budget]`
+    #[serde(default)]
+    pub budget: BudgetStatus,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -164,14 +282,17 @@ mod tests {
 
     #[test]
     fn test_rpc_service_serialization() {
-        let service =
-            RpcService::UpdateTicketsByPeriod(vec!["2024001".to_owned(), "2024002".to_owned()]);
+        let service = RpcService::UpdateTicketsByPeriod(
+            vec!["2024001".to_owned(), "2024002".to_owned()],
+            true,
+        );
         let serialized = serde_json::to_string(&service).expect("Failed to serialize");
         let deserialized: RpcService =
             serde_json::from_str(&serialized).expect("Failed to deserialize");
 
         match deserialized {
-            RpcService::UpdateTicketsByPeriod(periods) => {
+            RpcService::UpdateTicketsByPeriod(periods, dry_run) => {
+                assert!(dry_run);
                 assert_eq!(periods.len(), 2);
                 assert_eq!(periods[0], "2024001");
             }
@@ -186,6 +307,7 @@ mod tests {
             client_info: Some("test_client".to_owned()),
             server_name: None,
             supported_features: vec!["basic".to_owned(), "advanced".to_owned()],
+            profile: Some("alice".to_owned()),
         };
 
         let serialized = serde_json::to_string(&hello).expect("Failed to serialize");
@@ -194,10 +316,18 @@ mod tests {
 
         assert_eq!(hello.version, deserialized.version);
         assert_eq!(hello.client_info, deserialized.client_info);
+        assert_eq!(hello.profile, deserialized.profile);
         assert_eq!(
             hello.supported_features.len(),
             deserialized.supported_features.len()
         );
+
+        // clients from before profiles see every spot
+        let legacy: HelloMessage = serde_json::from_str(
+            r#"{"version":1,"client_info":null,"server_name":null,"supported_features":[]}"#,
+        )
+        .expect("Failed to deserialize");
+        assert_eq!(legacy.profile, None);
     }
 
     #[test]
@@ -211,7 +341,9 @@ mod tests {
             pending_tickets: vec![],
             unprize_spots_count: 0,
             total_investment: 0.0,
+            theoretical_investment: 0.0,
             total_return: 0.0,
+            claimed_return: 0.0,
             api_status: ApiStatusInfo {
                 api_provider: "mxnzp".to_owned(),
                 last_success: None,
@@ -222,6 +354,7 @@ mod tests {
             daemon_uptime: Duration::from_secs(3600),
             generation_status: GenerationStatus::Idle,
             last_generation_time: None,
+            budget: Default::default(),
         };
 
         // 确保可以序列化
@@ -1,37 +1,82 @@
-use std::{path::PathBuf, sync::LazyLock};
-
-pub(crate) static ENV_GUARD: LazyLock<Result<PathBuf, anyhow::Error>> = LazyLock::new(|| {
-    dotenvy::dotenv().map_err(|e| anyhow::anyhow!("Failed to load .env file: {e}"))
-});
-
+use std::{
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
+
+/// Env file given to [`ClientBuilder::env_file`], loaded by [`ENV_GUARD`] instead of `.env`
+static ENV_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+pub(crate) static ENV_GUARD: LazyLock<Result<PathBuf, anyhow::Error>> =
+    LazyLock::new(|| match ENV_FILE.get() {
+        Some(path) => dotenvy::from_path(path)
+            .map(|()| path.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to load {}: {e}", path.display())),
+        None => dotenvy::dotenv().map_err(|e| anyhow::anyhow!("Failed to load .env file: {e}")),
+    });
+
+#[cfg(not(target_arch = "wasm32"))]
 pub mod api;
+#[cfg(not(target_arch = "wasm32"))]
+mod client;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clock;
+pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod daemon;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod db;
 pub mod ipc;
+pub mod metrics;
 pub mod models;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod profile;
+pub mod schedule;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod server;
 pub mod service;
+#[cfg(all(feature = "simulation", not(target_arch = "wasm32")))]
+pub mod simulation;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod telemetry;
 
-const NEVER_NONE_BY_DATABASE: &str = "Should not be None guaranteed by database";
+#[cfg(not(target_arch = "wasm32"))]
+pub use client::{Client, ClientBuilder};
 
-pub fn setup(log_level: Option<log::LevelFilter>) {
-    init_env();
+#[cfg(not(target_arch = "wasm32"))]
+const NEVER_NONE_BY_DATABASE: &str = "Should not be None guaranteed by database";
 
-    let mut logger = env_logger::Builder::from_default_env();
-    if let Some(level) = log_level {
-        logger.filter_level(level);
+/// Load `path` as the env file of the process, an environment already loaded
+/// from another file is an error
+#[cfg(not(target_arch = "wasm32"))]
+fn load_env_file(path: &Path) -> anyhow::Result<PathBuf> {
+    // only used when the environment has not been loaded yet
+    ENV_FILE.get_or_init(|| path.to_path_buf());
+    let loaded = ENV_GUARD.as_ref().map_err(|e| anyhow::anyhow!("{e}"))?;
+    if loaded != path {
+        anyhow::bail!(
+            "Environment already loaded from {}, cannot load {}",
+            loaded.display(),
+            path.display()
+        );
     }
-
-    logger.try_init().expect("Failed to initialize logger");
+    Ok(loaded.clone())
 }
 
-/// load env file, panic if failed
-fn init_env() {
-    crate::ENV_GUARD
-        .as_ref()
-        .expect("Failed to load environment variables. Ensure .env file exists and is correctly configured.");
+/// Directory of `dball.toml` and the API configs: the one of the env file,
+/// or the working directory when no env file was loaded
+pub(crate) fn root_dir() -> anyhow::Result<PathBuf> {
+    use anyhow::Context as _;
+
+    match ENV_GUARD.as_ref() {
+        Ok(env_file) => env_file
+            .parent()
+            .map(Path::to_path_buf)
+            .context("Could not get parent directory of .env file"),
+        Err(_) => std::env::current_dir().context("Could not get the working directory"),
+    }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) fn parse_from_env<T: std::str::FromStr>(key: &str) -> Option<T>
 where
     <T as std::str::FromStr>::Err: std::fmt::Display,
@@ -50,7 +95,9 @@ where
 #[ctor::ctor]
 #[cfg(test)]
 fn init_test_logger() {
-    init_env();
+    ENV_GUARD
+        .as_ref()
+        .expect("Tests need the .env file of the repository");
 
     eprintln!("Initializing test logger");
     env_logger::builder()
@@ -61,7 +108,7 @@ fn init_test_logger() {
 
 #[cfg(test)]
 static TEST_ENV_GUARD: LazyLock<TestEnvGuard> = LazyLock::new(|| TestEnvGuard {
-    test_db: copy_test_db(),
+    test_db: seed_test_db(),
 });
 
 #[cfg(test)]
@@ -77,40 +124,18 @@ struct TestEnvGuard {
 }
 
 #[cfg(test)]
-/// copy test database from main database
+/// seed the fixture database at `TEST_DATABASE_URL`, the main database is never read
 /// return the path of test database
-fn copy_test_db() -> std::path::PathBuf {
+fn seed_test_db() -> std::path::PathBuf {
     let root_path = crate::ENV_GUARD
         .as_ref()
         .expect("ENV_GUARD not initialized")
         .parent()
         .expect("env parent path not found");
     let test_db_url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
-    let main_db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-
-    let main_db_path = root_path.join(main_db_url);
     let test_db_path = root_path.join(test_db_url);
 
-    // clean old test database
-    let files_to_remove = [
-        test_db_path.clone(),
-        test_db_path.with_extension("db-shm"),
-        test_db_path.with_extension("db-wal"),
-    ];
-
-    for file in &files_to_remove {
-        if file.exists() && std::fs::remove_file(file).is_err() {
-            log::debug!("Failed to remove old test db: {}", file.display());
-        }
-    }
-
-    // copy main database as test database
-    if std::fs::copy(main_db_path, &test_db_path).is_err() {
-        log::error!(
-            "Failed to copy main db to test db: {}",
-            test_db_path.display()
-        );
-    }
+    db::testing::seed_fixture(&test_db_path).expect("Failed to seed test db");
 
     log::debug!("Created test db: {}", test_db_path.display());
     test_db_path
@@ -0,0 +1,226 @@
+//! Counters and duration histograms of the service functions, collected in a
+//! registry of the process
+//!
+//! The daemon answers [`RpcService::GetMetrics`] with a [`MetricsSnapshot`]
+//! and the HTTP server serves the same registry at `/metrics` in the
+//! Prometheus text format.
+//!
+//! [`RpcService::GetMetrics`]: crate::ipc::protocol::RpcService::GetMetrics
+
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Spots saved from a generator
+pub const SPOTS_GENERATED: &str = "dball_spots_generated_total";
+/// Periods the provider answered with a draw
+pub const PERIODS_CRAWLED: &str = "dball_periods_crawled_total";
+/// Requests to the provider that failed, retries included
+pub const API_FAILURES: &str = "dball_api_failures_total";
+
+/// Counters and their help, reported as 0 until first counted
+const COUNTERS: [(&str, &str); 3] = [
+    (SPOTS_GENERATED, "Spots saved from a generator"),
+    (
+        PERIODS_CRAWLED,
+        "Periods the lottery provider answered with a draw",
+    ),
+    (API_FAILURES, "Requests to the lottery provider that failed"),
+];
+
+/// Histogram of the durations of the service operations, by operation
+const OPERATION_DURATION: &str = "dball_operation_duration_seconds";
+
+/// Upper bounds of the duration buckets in seconds
+pub const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0,
+];
+
+static REGISTRY: LazyLock<Mutex<MetricsSnapshot>> =
+    LazyLock::new(|| Mutex::new(MetricsSnapshot::default()));
+
+/// Durations of one operation
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DurationHistogram {
+    pub count: u64,
+    pub sum_secs: f64,
+    /// Operations that took at most the bound of [`DURATION_BUCKETS`] at the same index
+    pub buckets: Vec<u64>,
+}
+
+impl DurationHistogram {
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        self.buckets.resize(DURATION_BUCKETS.len(), 0);
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum_secs += secs;
+    }
+}
+
+/// Everything counted and timed since the process started
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Counters by name, e.g. [`SPOTS_GENERATED`]
+    pub counters: BTreeMap<String, u64>,
+    /// Durations by operation, named after the service function timed
+    pub durations: BTreeMap<String, DurationHistogram>,
+}
+
+impl Default for MetricsSnapshot {
+    fn default() -> Self {
+        Self {
+            counters: COUNTERS
+                .iter()
+                .map(|(name, _)| ((*name).to_owned(), 0))
+                .collect(),
+            durations: BTreeMap::new(),
+        }
+    }
+}
+
+impl MetricsSnapshot {
+    /// Value of the counter `name`, 0 for one never counted
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or_default()
+    }
+
+    /// The snapshot in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Prometheus text exposition format
+impl std::fmt::Display for MetricsSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.counters {
+            let help = COUNTERS
+                .iter()
+                .find(|(counter, _)| counter == name)
+                .map_or("", |(_, help)| help);
+            writeln!(f, "# HELP {name} {help}")?;
+            writeln!(f, "# TYPE {name} counter")?;
+            writeln!(f, "{name} {value}")?;
+        }
+
+        writeln!(
+            f,
+            "# HELP {OPERATION_DURATION} Durations of the service operations"
+        )?;
+        writeln!(f, "# TYPE {OPERATION_DURATION} histogram")?;
+        for (operation, histogram) in &self.durations {
+            let series = format!("operation=\"{operation}\"");
+            for (bound, count) in DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                writeln!(
+                    f,
+                    "{OPERATION_DURATION}_bucket{{{series},le=\"{bound}\"}} {count}"
+                )?;
+            }
+            let count = histogram.count;
+            writeln!(
+                f,
+                "{OPERATION_DURATION}_bucket{{{series},le=\"+Inf\"}} {count}"
+            )?;
+            writeln!(
+                f,
+                "{OPERATION_DURATION}_sum{{{series}}} {}",
+                histogram.sum_secs
+            )?;
+            writeln!(f, "{OPERATION_DURATION}_count{{{series}}} {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Add `by` to the counter `name`
+pub fn increment(name: &str, by: u64) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        *registry.counters.entry(name.to_owned()).or_default() += by;
+    }
+}
+
+/// Record that `operation` took `duration`
+pub fn observe(operation: &str, duration: Duration) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry
+            .durations
+            .entry(operation.to_owned())
+            .or_default()
+            .observe(duration);
+    }
+}
+
+/// Copy of the registry
+pub fn snapshot() -> MetricsSnapshot {
+    REGISTRY
+        .lock()
+        .map(|registry| registry.clone())
+        .unwrap_or_default()
+}
+
+/// Times an operation from [`time`] until dropped, failed operations included
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use = "the operation is timed until the timer is dropped"]
+pub struct OperationTimer {
+    operation: &'static str,
+    started: std::time::Instant,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for OperationTimer {
+    fn drop(&mut self) {
+        observe(self.operation, self.started.elapsed());
+    }
+}
+
+/// Start timing `operation`, recorded when the timer is dropped
+#[cfg(not(target_arch = "wasm32"))]
+pub fn time(operation: &'static str) -> OperationTimer {
+    OperationTimer {
+        operation,
+        started: std::time::Instant::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut histogram = DurationHistogram::default();
+        histogram.observe(Duration::from_millis(20));
+        histogram.observe(Duration::from_secs(2));
+        histogram.observe(Duration::from_secs(600));
+        assert_eq!(histogram.count, 3);
+        assert!((histogram.sum_secs - 602.02).abs() < 1e-9);
+        assert_eq!(histogram.buckets, [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_registry_and_prometheus() {
+        // other tests may count at the same time, only compare what grew
+        let before = snapshot();
+        increment(PERIODS_CRAWLED, 2);
+        drop(time("test_operation"));
+        let after = snapshot();
+        assert!(after.counter(PERIODS_CRAWLED) >= before.counter(PERIODS_CRAWLED) + 2);
+        assert!(after.durations["test_operation"].count >= 1);
+
+        let text = after.to_prometheus();
+        assert!(text.contains("# TYPE dball_spots_generated_total counter\n"));
+        assert!(text.contains(
+            "dball_operation_duration_seconds_bucket{operation=\"test_operation\",le=\"0.005\"}"
+        ));
+        assert!(
+            text.contains("dball_operation_duration_seconds_count{operation=\"test_operation\"}")
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tickets a crawl or update saved, or would save in a dry run
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct CrawlReport {
+    /// Nothing was written, the periods below say what a real run would do
+    pub dry_run: bool,
+    /// Periods the draw calendar has drawn but the database misses, before fetching
+    pub missing: Vec<String>,
+    /// Periods whose tickets were inserted
+    pub inserted: Vec<String>,
+    /// Periods the provider answered differently from what is stored
+    pub mismatches: Vec<TicketMismatch>,
+}
+
+impl CrawlReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            ..Self::default()
+        }
+    }
+
+    /// Add the periods of `other`, a report of the same mode
+    pub fn merge(&mut self, other: Self) {
+        self.missing.extend(other.missing);
+        self.inserted.extend(other.inserted);
+        self.mismatches.extend(other.mismatches);
+    }
+}
+
+/// A provider's ticket differing from the stored one or from the ticket log
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, thiserror::Error)]
+#[error("Ticket mismatch for period {period} - {against}: {stored}, API: {fetched}")]
+pub struct TicketMismatch {
+    pub period: String,
+    /// `database` or `ticket log`
+    pub against: String,
+    pub stored: String,
+    pub fetched: String,
+}
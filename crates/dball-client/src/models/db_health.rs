@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// State of the database file, for monitoring to catch corruption early
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct DbHealth {
+    /// Problems `PRAGMA integrity_check` found, empty for a sound database
+    pub problems: Vec<String>,
+    /// Bytes of the database pages, free pages included
+    pub file_bytes: u64,
+    /// Bytes of the write-ahead log not checkpointed yet, 0 without one
+    pub wal_bytes: u64,
+    /// Rows of every table by name
+    pub rows: BTreeMap<String, i64>,
+}
+
+impl DbHealth {
+    /// Whether the integrity check found nothing wrong
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::NaiveDateTime;
+use dball_combora::dball::PrizeTable;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Pool, sales and winners of one draw, amounts in yuan
+///
+/// Tiers the provider did not report are `None`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::draw_details),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct DrawDetail {
+    pub period: String,
+    /// Left in the jackpot pool after the draw
+    pub pool: Option<i64>,
+    /// Tickets sold for the period
+    pub sales: Option<i64>,
+    pub prize1_winners: Option<i32>,
+    /// Paid per winning bet of the first tier
+    pub prize1_amount: Option<i32>,
+    pub prize2_winners: Option<i32>,
+    pub prize2_amount: Option<i32>,
+    pub prize3_winners: Option<i32>,
+    pub prize3_amount: Option<i32>,
+    pub prize4_winners: Option<i32>,
+    pub prize4_amount: Option<i32>,
+    pub prize5_winners: Option<i32>,
+    pub prize5_amount: Option<i32>,
+    pub prize6_winners: Option<i32>,
+    pub prize6_amount: Option<i32>,
+    pub fetched_time: NaiveDateTime,
+}
+
+impl DrawDetail {
+    /// Detail of `period` without any figure
+    pub fn new(period: &str, fetched_time: NaiveDateTime) -> Self {
+        Self {
+            period: period.to_owned(),
+            pool: None,
+            sales: None,
+            prize1_winners: None,
+            prize1_amount: None,
+            prize2_winners: None,
+            prize2_amount: None,
+            prize3_winners: None,
+            prize3_amount: None,
+            prize4_winners: None,
+            prize4_amount: None,
+            prize5_winners: None,
+            prize5_amount: None,
+            prize6_winners: None,
+            prize6_amount: None,
+            fetched_time,
+        }
+    }
+
+    /// Set the winners and amount per bet of `tier`, 1 to 6, other tiers are ignored
+    pub fn set_tier(&mut self, tier: u8, winners: Option<i32>, amount: Option<i32>) {
+        let slot = match tier {
+            1 => (&mut self.prize1_winners, &mut self.prize1_amount),
+            2 => (&mut self.prize2_winners, &mut self.prize2_amount),
+            3 => (&mut self.prize3_winners, &mut self.prize3_amount),
+            4 => (&mut self.prize4_winners, &mut self.prize4_amount),
+            5 => (&mut self.prize5_winners, &mut self.prize5_amount),
+            6 => (&mut self.prize6_winners, &mut self.prize6_amount),
+            _ => return,
+        };
+        *slot.0 = winners;
+        *slot.1 = amount;
+    }
+
+    /// Whether the provider reported any figure besides the period
+    pub fn is_empty(&self) -> bool {
+        *self == Self::new(&self.period, self.fetched_time)
+    }
+
+    /// Prizes paid in the period, the default ones for amounts not reported
+    ///
+    /// A floating tier nobody won is reported as 0 and keeps its default too
+    pub fn prize_table(&self) -> PrizeTable {
+        let amount = |money: Option<i32>| {
+            money
+                .and_then(|money| u32::try_from(money).ok())
+                .filter(|money| *money > 0)
+        };
+        PrizeTable::new(amount(self.prize1_amount), amount(self.prize2_amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_detail_prize_table() {
+        let mut detail = DrawDetail::new("2025088", chrono::Utc::now().naive_utc());
+        assert!(detail.is_empty());
+        assert_eq!(detail.prize_table(), PrizeTable::default());
+
+        detail.set_tier(1, Some(4), Some(7_312_640));
+        detail.set_tier(2, Some(0), Some(0));
+        detail.set_tier(7, Some(1), Some(1));
+        assert!(!detail.is_empty());
+        assert_eq!(detail.prize_table(), PrizeTable::new(Some(7_312_640), None));
+    }
+}
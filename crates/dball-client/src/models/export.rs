@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Table exported as CSV
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum ExportTable {
+    /// Drawn tickets
+    Tickets,
+    /// Generated and manual spots, deprecated ones included
+    Spots,
+}
+
+impl ExportTable {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tickets => "tickets",
+            Self::Spots => "spots",
+        }
+    }
+}
+
+/// Rows to export, newest period first
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct ExportRequest {
+    pub table: ExportTable,
+    /// Period must start with this prefix, e.g. `2025`
+    pub period_prefix: Option<String>,
+}
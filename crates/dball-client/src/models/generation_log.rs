@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How a generated batch of spots was picked
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::generation_log),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct GenerationLog {
+    pub id: Option<i32>,
+    /// Period the spots were generated for
+    pub period: String,
+    /// Name of the generator, e.g. `BlueMorn`
+    pub generator: String,
+    /// Decimal seed reproducing the batch, `None` when no single seed drew it
+    pub seed: Option<String>,
+    /// Score the generator gave the batch
+    pub score: f64,
+    /// Candidate batches evaluated before this one was kept
+    pub attempts: i32,
+    pub duration_ms: i64,
+    pub created_time: NaiveDateTime,
+}
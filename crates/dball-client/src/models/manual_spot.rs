@@ -0,0 +1,50 @@
+use dball_combora::dball::{DBall, DBallError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Numbers picked by hand, saved as a spot of the next period
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub struct ManualSpot {
+    /// Red balls in any order, 1-33 without duplicates
+    pub reds: [u8; 6],
+    /// Blue ball, 1-16
+    pub blue: u8,
+    pub magnification: usize,
+}
+
+impl ManualSpot {
+    pub fn to_dball(&self) -> Result<DBall, DBallError> {
+        DBall::builder()
+            .reds(self.reds)
+            .blue(self.blue)
+            .magnification(self.magnification)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_spot_validates_numbers() {
+        let spot = ManualSpot {
+            reds: [33, 1, 12, 7, 25, 18],
+            blue: 16,
+            magnification: 1,
+        };
+        assert_eq!(
+            spot.to_dball().map(|dball| dball.rball),
+            Ok([1, 7, 12, 18, 25, 33])
+        );
+
+        let duplicate = ManualSpot {
+            reds: [1, 1, 12, 7, 25, 18],
+            ..spot
+        };
+        assert_eq!(duplicate.to_dball(), Err(DBallError::RBallDuplicate));
+
+        let blue = ManualSpot { blue: 17, ..spot };
+        assert_eq!(blue.to_dball(), Err(DBallError::InvalidBBall(17)));
+    }
+}
@@ -1,8 +1,47 @@
+pub mod crawl_report;
+pub mod db_health;
+pub mod draw_detail;
+pub mod export;
+pub mod generation_log;
+pub mod manual_spot;
+pub mod notification_delivery;
+pub mod page;
+pub mod pipeline_run;
+pub mod prize_claim;
+pub mod profile;
+pub mod purchase;
+pub mod recommend_request;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod schema;
 pub mod spot;
+pub mod spot_deprecation;
+pub mod spot_filter;
+pub mod spot_query;
+pub mod tag;
+pub mod ticket_audit;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ticket_log;
 pub mod tickets;
 
-pub use spot::Spot;
+pub use crawl_report::{CrawlReport, TicketMismatch};
+pub use db_health::DbHealth;
+pub use draw_detail::DrawDetail;
+pub use export::{ExportRequest, ExportTable};
+pub use generation_log::GenerationLog;
+pub use manual_spot::ManualSpot;
+pub use notification_delivery::NotificationDelivery;
+pub use page::{Page, PageRequest, SortOrder};
+pub use pipeline_run::PipelineRun;
+pub use prize_claim::{NewPrizeClaim, PrizeClaim, Winnings};
+pub use profile::{DEFAULT_PROFILE_ID, Profile};
+pub use purchase::{Investment, NewPurchase, Purchase};
+pub use recommend_request::RecommendRequest;
+pub use spot::{DuplicateSpots, Spot};
+pub use spot_deprecation::SpotDeprecation;
+pub use spot_filter::SpotFilter;
+pub use spot_query::{SpotQuery, SpotScope};
+pub use tag::{Tag, TagCount};
+pub use ticket_audit::{AuditFailure, TicketAudit, TicketAuditRequest, TicketDiscrepancy};
+#[cfg(not(target_arch = "wasm32"))]
 pub use ticket_log::{NewTicketLog, TicketLog};
 pub use tickets::Ticket;
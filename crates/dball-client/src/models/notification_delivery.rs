@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A notification the daemon sent out to one target, see `service::notify`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::notification_deliveries),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct NotificationDelivery {
+    pub id: Option<i32>,
+    /// How it was sent, e.g. `webhook`
+    pub channel: String,
+    /// Where it was sent, e.g. the webhook URL
+    pub target: String,
+    /// Period whose prizes were announced
+    pub period: String,
+    pub payload: String,
+    /// Attempts made, retries included
+    pub attempts: i32,
+    /// Last error, `None` once delivered
+    pub error: Option<String>,
+    pub sent_time: NaiveDateTime,
+}
+
+impl NotificationDelivery {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+}
@@ -0,0 +1,82 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Direction rows are sorted in by period
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+pub enum SortOrder {
+    /// Oldest period first
+    Asc,
+    /// Newest period first
+    #[default]
+    Desc,
+}
+
+/// Slice of a bulk query to load, the default loads every row newest first
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct PageRequest {
+    /// Rows to skip
+    pub offset: i64,
+    /// Most rows to load, every row after `offset` without one
+    pub limit: Option<i64>,
+    pub order: SortOrder,
+}
+
+impl PageRequest {
+    pub fn new(offset: i64, limit: i64) -> Self {
+        Self {
+            offset,
+            limit: Some(limit),
+            order: SortOrder::default(),
+        }
+    }
+
+    pub fn with_order(mut self, order: SortOrder) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+/// Rows of one [`PageRequest`] with the number of rows of the whole query
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Rows matching the query on every page
+    pub total: i64,
+    /// Rows skipped before the first item
+    pub offset: i64,
+}
+
+impl<T> Page<T> {
+    /// Whether rows are left after this page
+    pub fn has_more(&self) -> bool {
+        self.offset + (self.items.len() as i64) < self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_request_defaults_missing_fields() -> anyhow::Result<()> {
+        let request = serde_json::from_str::<PageRequest>(r#"{"limit": 20}"#)?;
+        assert_eq!(
+            request,
+            PageRequest {
+                offset: 0,
+                limit: Some(20),
+                order: SortOrder::Desc,
+            }
+        );
+
+        let page = Page {
+            items: vec![1, 2],
+            total: 5,
+            offset: 2,
+        };
+        assert!(page.has_more());
+        assert!(!Page { offset: 3, ..page }.has_more());
+        Ok(())
+    }
+}
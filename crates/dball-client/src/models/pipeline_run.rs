@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A run of the pipeline the daemon starts after a draw, see `service::run_post_draw_pipeline`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::pipeline_runs),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct PipelineRun {
+    pub id: Option<i32>,
+    /// Draw the run followed
+    pub draw_time: NaiveDateTime,
+    pub started_time: NaiveDateTime,
+    pub finished_time: NaiveDateTime,
+    /// Steps that finished, in order
+    pub completed_steps: i32,
+    /// Attempts made over all steps, retries included
+    pub attempts: i32,
+    /// Name of the step that gave up, `None` for a successful run
+    pub failed_step: Option<String>,
+    /// Last error of [`Self::failed_step`]
+    pub error: Option<String>,
+}
+
+impl PipelineRun {
+    pub fn succeeded(&self) -> bool {
+        self.failed_step.is_none()
+    }
+}
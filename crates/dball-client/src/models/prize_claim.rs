@@ -0,0 +1,48 @@
+use chrono::{NaiveDate, NaiveDateTime};
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Prize collected for a winning spot, with what was actually paid out
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::prize_claims),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct PrizeClaim {
+    pub id: Option<i32>,
+    pub spot_id: i32,
+    pub claim_date: NaiveDate,
+    /// Yuan paid out, the floating first and second tiers after tax
+    pub amount: i32,
+    /// Where or how it was claimed, e.g. the lottery center
+    pub note: Option<String>,
+    pub created_time: NaiveDateTime,
+    /// Profile that claimed the prize, see [`crate::models::Profile`]
+    pub profile_id: i32,
+}
+
+/// Claim to record for a winning spot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct NewPrizeClaim {
+    pub spot_id: i32,
+    /// Day of the claim as `2025-09-16`, today when left out
+    #[schemars(with = "Option<String>")]
+    pub date: Option<NaiveDate>,
+    /// Yuan paid out, the winnings of the spot when left out, which only the
+    /// fixed tiers 3 to 6 allow
+    pub amount: Option<i32>,
+    pub note: Option<String>,
+}
+
+/// Money won on spots, as paid out and as their prizes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+pub struct Winnings {
+    /// Yuan paid out for the claimed prizes
+    pub realized: f64,
+    /// Yuan every settled spot not deprecated won, claimed or not
+    pub theoretical: f64,
+}
@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Profile owning the spots and purchases recorded without one
+pub const DEFAULT_PROFILE_ID: i32 = 1;
+
+/// One of the people sharing the daemon, owning their spots and purchases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::profiles),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct Profile {
+    pub id: Option<i32>,
+    pub name: String,
+    #[schemars(with = "String")]
+    pub created_time: NaiveDateTime,
+}
@@ -0,0 +1,48 @@
+use chrono::{NaiveDate, NaiveDateTime};
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tickets actually bought at a store, the money spent on the spots it links
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::purchases),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct Purchase {
+    pub id: Option<i32>,
+    pub purchase_date: NaiveDate,
+    pub store: Option<String>,
+    /// Yuan paid, may differ from the price of the spots
+    pub cost: i32,
+    /// Receipt note, e.g. its serial number
+    pub note: Option<String>,
+    pub created_time: NaiveDateTime,
+    /// Profile that bought the tickets, see [`crate::models::Profile`]
+    pub profile_id: i32,
+}
+
+/// Purchase to record with the ids of the spots bought
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct NewPurchase {
+    pub spot_ids: Vec<i32>,
+    /// Day of the purchase as `2025-09-14`, today when left out
+    #[schemars(with = "Option<String>")]
+    pub date: Option<NaiveDate>,
+    pub store: Option<String>,
+    /// Yuan paid
+    pub cost: i32,
+    pub note: Option<String>,
+}
+
+/// Money spent on spots, as paid and as their price
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, JsonSchema)]
+pub struct Investment {
+    /// Yuan of the recorded purchases
+    pub realized: f64,
+    /// Yuan every spot not deprecated would cost, bought or not
+    pub theoretical: f64,
+}
@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Batches to generate and keep for a recommendation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct RecommendRequest {
+    /// Generator strategy, the configured one without
+    pub strategy: Option<String>,
+    /// Candidate batches generated, up to [`Self::MAX_CANDIDATES`]
+    pub candidates: usize,
+    /// Best candidates returned
+    pub top: usize,
+}
+
+impl RecommendRequest {
+    pub const MAX_CANDIDATES: usize = 200;
+}
+
+impl Default for RecommendRequest {
+    fn default() -> Self {
+        Self {
+            strategy: None,
+            candidates: 20,
+            top: 3,
+        }
+    }
+}
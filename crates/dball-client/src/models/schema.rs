@@ -1,5 +1,111 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    draw_details (period) {
+        period -> Text,
+        pool -> Nullable<BigInt>,
+        sales -> Nullable<BigInt>,
+        prize1_winners -> Nullable<Integer>,
+        prize1_amount -> Nullable<Integer>,
+        prize2_winners -> Nullable<Integer>,
+        prize2_amount -> Nullable<Integer>,
+        prize3_winners -> Nullable<Integer>,
+        prize3_amount -> Nullable<Integer>,
+        prize4_winners -> Nullable<Integer>,
+        prize4_amount -> Nullable<Integer>,
+        prize5_winners -> Nullable<Integer>,
+        prize5_amount -> Nullable<Integer>,
+        prize6_winners -> Nullable<Integer>,
+        prize6_amount -> Nullable<Integer>,
+        fetched_time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    generation_log (id) {
+        id -> Nullable<Integer>,
+        period -> Text,
+        generator -> Text,
+        seed -> Nullable<Text>,
+        score -> Double,
+        attempts -> Integer,
+        duration_ms -> BigInt,
+        created_time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    generation_log_spots (generation_id, spot_id) {
+        generation_id -> Integer,
+        spot_id -> Integer,
+    }
+}
+
+diesel::table! {
+    notification_deliveries (id) {
+        id -> Nullable<Integer>,
+        channel -> Text,
+        target -> Text,
+        period -> Text,
+        payload -> Text,
+        attempts -> Integer,
+        error -> Nullable<Text>,
+        sent_time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    pipeline_runs (id) {
+        id -> Nullable<Integer>,
+        draw_time -> Timestamp,
+        started_time -> Timestamp,
+        finished_time -> Timestamp,
+        completed_steps -> Integer,
+        attempts -> Integer,
+        failed_step -> Nullable<Text>,
+        error -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    prize_claims (id) {
+        id -> Nullable<Integer>,
+        spot_id -> Integer,
+        claim_date -> Date,
+        amount -> Integer,
+        note -> Nullable<Text>,
+        created_time -> Timestamp,
+        profile_id -> Integer,
+    }
+}
+
+diesel::table! {
+    profiles (id) {
+        id -> Nullable<Integer>,
+        name -> Text,
+        created_time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    purchase_spots (purchase_id, spot_id) {
+        purchase_id -> Integer,
+        spot_id -> Integer,
+    }
+}
+
+diesel::table! {
+    purchases (id) {
+        id -> Nullable<Integer>,
+        purchase_date -> Date,
+        store -> Nullable<Text>,
+        cost -> Integer,
+        note -> Nullable<Text>,
+        created_time -> Timestamp,
+        profile_id -> Integer,
+    }
+}
+
 diesel::table! {
     spot (id) {
         id -> Nullable<Integer>,
@@ -16,6 +122,38 @@ diesel::table! {
         created_time -> Timestamp,
         modified_time -> Timestamp,
         deprecated -> Bool,
+        note -> Nullable<Text>,
+        purchased -> Bool,
+        extra_reds -> Nullable<Text>,
+        extra_blues -> Nullable<Text>,
+        bankers -> Nullable<Text>,
+        profile_id -> Integer,
+    }
+}
+
+diesel::table! {
+    spot_deprecations (id) {
+        id -> Nullable<Integer>,
+        spot_id -> Integer,
+        deprecated -> Bool,
+        actor -> Text,
+        reason -> Nullable<Text>,
+        created_time -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spot_tags (spot_id, tag_id) {
+        spot_id -> Integer,
+        tag_id -> Integer,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Nullable<Integer>,
+        name -> Text,
+        created_time -> Timestamp,
     }
 }
 
@@ -66,4 +204,29 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(spot, ticket_log, tickets,);
+diesel::joinable!(generation_log_spots -> generation_log (generation_id));
+diesel::joinable!(generation_log_spots -> spot (spot_id));
+diesel::joinable!(prize_claims -> spot (spot_id));
+diesel::joinable!(purchase_spots -> purchases (purchase_id));
+diesel::joinable!(purchase_spots -> spot (spot_id));
+diesel::joinable!(spot_deprecations -> spot (spot_id));
+diesel::joinable!(spot_tags -> spot (spot_id));
+diesel::joinable!(spot_tags -> tags (tag_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    draw_details,
+    generation_log,
+    generation_log_spots,
+    notification_deliveries,
+    pipeline_runs,
+    prize_claims,
+    profiles,
+    purchase_spots,
+    purchases,
+    spot,
+    spot_deprecations,
+    spot_tags,
+    tags,
+    ticket_log,
+    tickets,
+);
@@ -1,14 +1,22 @@
 use chrono::NaiveDateTime;
-use dball_combora::dball::{DBall, DBallError, Reward};
+use dball_combora::dball::{ComplexBet, DBall, DBallError, DantuoBet, Reward};
+#[cfg(not(target_arch = "wasm32"))]
 use diesel::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
+use crate::models::DEFAULT_PROFILE_ID;
+
 /// Spot record structure for generated ticket numbers
 /// The id field will be None for new records and Some(value) for existing records
-#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
-#[diesel(table_name = crate::models::schema::spot)]
-#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::spot),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
 pub struct Spot {
     pub id: Option<i32>,
     pub period: String,
@@ -24,6 +32,21 @@ pub struct Spot {
     pub created_time: NaiveDateTime,
     pub modified_time: NaiveDateTime,
     pub deprecated: bool,
+    pub note: Option<String>,
+    pub purchased: bool,
+    /// Reds of a complex bet after the first 6, space separated
+    pub extra_reds: Option<String>,
+    /// Blues of a complex bet after the first one, space separated
+    pub extra_blues: Option<String>,
+    /// Banker reds of a 胆拖 bet, space separated, the other reds are its drags
+    pub bankers: Option<String>,
+    /// Profile owning the spot, see [`crate::models::Profile`]
+    #[serde(default = "default_profile_id")]
+    pub profile_id: i32,
+}
+
+fn default_profile_id() -> i32 {
+    DEFAULT_PROFILE_ID
 }
 
 impl Spot {
@@ -52,6 +75,12 @@ impl Spot {
             magnification: dball.magnification as i32,
             prize_status,
             deprecated: false,
+            note: None,
+            purchased: false,
+            extra_reds: None,
+            extra_blues: None,
+            bankers: None,
+            profile_id: DEFAULT_PROFILE_ID,
             created_time: now,
             modified_time: now,
         })
@@ -82,25 +111,146 @@ impl Spot {
             magnification: dball.magnification as i32,
             prize_status,
             deprecated: false,
+            note: None,
+            purchased: false,
+            extra_reds: None,
+            extra_blues: None,
+            bankers: None,
+            profile_id: DEFAULT_PROFILE_ID,
             created_time,
             modified_time,
         })
     }
 
-    /// Convert to `DBall` for validation and operations
-    pub fn to_dball(&self) -> Result<DBall, SpotError> {
-        let red_numbers = self.red_numbers();
-        let red_u8: Vec<u8> = red_numbers.iter().map(|&x| x as u8).collect();
-        let blue_u8 = self.blue as u8;
-        let magnification_usize = self.magnification as usize;
+    /// Create a new spot from a complex bet, the numbers after the first 6 reds
+    /// and the first blue go to `extra_reds` and `extra_blues`
+    pub fn from_complex(
+        period: &str,
+        bet: &ComplexBet,
+        prize_status: Option<i32>,
+    ) -> Result<Self, SpotError> {
+        let (reds, extra_reds) = bet.rball.split_at(6.min(bet.rball.len()));
+        let (blues, extra_blues) = bet.bball.split_at(1.min(bet.bball.len()));
+        let first = DBall::new(
+            reds.to_vec(),
+            blues.first().copied().unwrap_or(0),
+            bet.magnification,
+        )?;
+        let mut spot = Self::from_dball(period, &first, prize_status)?;
+        spot.extra_reds = join_extra(extra_reds);
+        spot.extra_blues = join_extra(extra_blues);
+        Ok(spot)
+    }
+
+    /// Create a new spot from a 胆拖 bet, stored as the complex bet of all its
+    /// reds and blues with the bankers marked in `bankers`
+    pub fn from_dantuo(
+        period: &str,
+        bet: &DantuoBet,
+        prize_status: Option<i32>,
+    ) -> Result<Self, SpotError> {
+        let mut reds = [bet.bankers.as_slice(), bet.drags.as_slice()].concat();
+        reds.sort_unstable();
+        let all = ComplexBet::new(reds, bet.bball.clone(), bet.magnification)?;
+        let mut spot = Self::from_complex(period, &all, prize_status)?;
+        spot.bankers = join_extra(&bet.bankers);
+        Ok(spot)
+    }
+
+    /// Whether the spot holds several simple bets, as a complex or a 胆拖 bet
+    pub fn is_complex(&self) -> bool {
+        self.extra_reds.is_some() || self.extra_blues.is_some()
+    }
 
-        DBall::new(red_u8, blue_u8, magnification_usize).map_err(SpotError::from)
+    /// Whether the spot holds a 胆拖 bet, see [`Self::to_dantuo`]
+    pub fn is_dantuo(&self) -> bool {
+        self.bankers.is_some()
     }
 
-    /// Validate spot using `DBall`'s validation logic
+    /// Convert to `DantuoBet`, fails for other spots
+    pub fn to_dantuo(&self) -> Result<DantuoBet, SpotError> {
+        let bankers = parse_extra(self.bankers.as_deref()).collect::<Result<Vec<_>, _>>()?;
+        if bankers.is_empty() {
+            return Err(SpotError::NotDantuo);
+        }
+        let all = self.complex_bet()?;
+        let drags = all
+            .rball
+            .iter()
+            .copied()
+            .filter(|red| !bankers.contains(red))
+            .collect::<Vec<_>>();
+        DantuoBet::new(bankers, drags, all.bball, all.magnification).map_err(SpotError::from)
+    }
+
+    /// Convert to `ComplexBet`, which also covers a simple spot as a single bet,
+    /// fails for 胆拖 spots
+    pub fn to_complex(&self) -> Result<ComplexBet, SpotError> {
+        if self.is_dantuo() {
+            return Err(SpotError::Dantuo);
+        }
+        self.complex_bet()
+    }
+
+    /// Every simple bet of the spot, whichever bet it holds
+    pub fn bets(&self) -> Result<Vec<DBall>, SpotError> {
+        if self.is_dantuo() {
+            Ok(self.to_dantuo()?.expand())
+        } else {
+            Ok(self.to_complex()?.expand())
+        }
+    }
+
+    /// Price of the spot, every simple bet it holds times its magnification
+    pub fn cost(&self) -> Result<usize, SpotError> {
+        if self.is_dantuo() {
+            Ok(self.to_dantuo()?.cost())
+        } else {
+            Ok(self.to_complex()?.cost())
+        }
+    }
+
+    /// All numbers of the spot as a complex bet, bankers or not
+    fn complex_bet(&self) -> Result<ComplexBet, SpotError> {
+        let reds = [
+            self.red1, self.red2, self.red3, self.red4, self.red5, self.red6,
+        ]
+        .into_iter()
+        .map(|red| u8::try_from(red).map_err(|_e| SpotError::InvalidNumbers(red.to_string())))
+        .chain(parse_extra(self.extra_reds.as_deref()))
+        .collect::<Result<Vec<_>, _>>()?;
+        let blues = std::iter::once(
+            u8::try_from(self.blue).map_err(|_e| SpotError::InvalidNumbers(self.blue.to_string())),
+        )
+        .chain(parse_extra(self.extra_blues.as_deref()))
+        .collect::<Result<Vec<_>, _>>()?;
+        let magnification = self.magnification as usize;
+
+        ComplexBet::new(reds, blues, magnification).map_err(SpotError::from)
+    }
+
+    /// Convert to `DBall` for validation and operations, fails for complex spots
+    pub fn to_dball(&self) -> Result<DBall, SpotError> {
+        if self.is_complex() {
+            return Err(SpotError::Complex);
+        }
+        DBall::builder()
+            .reds(
+                self.red_numbers()
+                    .iter()
+                    .map(|&x| x as u8)
+                    .collect::<Vec<_>>(),
+            )
+            .blue(self.blue as u8)
+            .magnification(self.magnification as usize)
+            .build()
+            .map_err(SpotError::from)
+    }
+
+    /// Validate spot using `ComplexBet`'s or `DantuoBet`'s validation logic
     pub fn check(&self) -> Result<(), SpotError> {
-        // Use DBall for number validation
-        self.to_dball()?;
+        // Use ComplexBet for number validation, simple spots included
+        self.cost()?;
 
         // Additional spot-specific validations
         if self.period.trim().is_empty() {
@@ -125,16 +275,50 @@ impl Spot {
         self.modified_time.format("%Y-%m-%d %H:%M:%S").to_string()
     }
 
+    /// Period and numbers of the spot, equal for spots repeating one another
+    /// whatever their magnification
+    pub fn numbers_key(&self) -> (&str, [i32; 7], [Option<&str>; 3]) {
+        (
+            &self.period,
+            [
+                self.red1, self.red2, self.red3, self.red4, self.red5, self.red6, self.blue,
+            ],
+            [
+                self.extra_reds.as_deref(),
+                self.extra_blues.as_deref(),
+                self.bankers.as_deref(),
+            ],
+        )
+    }
+
+    /// Every red of the spot, the extra reds of a complex spot included
     pub fn red_numbers(&self) -> Vec<i32> {
-        vec![
+        let mut reds = vec![
             self.red1, self.red2, self.red3, self.red4, self.red5, self.red6,
-        ]
+        ];
+        reds.extend(
+            parse_extra(self.extra_reds.as_deref())
+                .flatten()
+                .map(i32::from),
+        );
+        reds
     }
 
     pub fn blue_number(&self) -> i32 {
         self.blue
     }
 
+    /// Every blue of the spot, the extra blues of a complex spot included
+    pub fn blue_numbers(&self) -> Vec<i32> {
+        let mut blues = vec![self.blue];
+        blues.extend(
+            parse_extra(self.extra_blues.as_deref())
+                .flatten()
+                .map(i32::from),
+        );
+        blues
+    }
+
     /// Get reward enum based on prize status
     pub fn reward_level(&self) -> anyhow::Result<Option<Reward>> {
         if let Some(num) = self.prize_status {
@@ -146,11 +330,44 @@ impl Spot {
     }
 }
 
+fn join_extra(numbers: &[u8]) -> Option<String> {
+    (!numbers.is_empty()).then(|| {
+        numbers
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+fn parse_extra(extra: Option<&str>) -> impl Iterator<Item = Result<u8, SpotError>> {
+    extra.unwrap_or_default().split_whitespace().map(|number| {
+        number
+            .parse()
+            .map_err(|_e| SpotError::InvalidNumbers(number.to_owned()))
+    })
+}
+
+/// Spots of one period holding the same numbers, by id in the order saved
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct DuplicateSpots {
+    pub period: String,
+    pub ids: Vec<i32>,
+}
+
 /// Spot validation error types
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SpotError {
     EmptyPeriod,
     DBallError(String), // Wrapper for DBallError
+    /// A stored number is not a ball number at all
+    InvalidNumbers(String),
+    /// The spot holds a complex bet, which is not a single `DBall`
+    Complex,
+    /// The spot holds a 胆拖 bet, which is no `ComplexBet` of all its numbers
+    Dantuo,
+    /// The spot holds no 胆拖 bet
+    NotDantuo,
 }
 
 // Convert DBallError to SpotError
@@ -177,6 +394,12 @@ impl From<DBall> for Spot {
             magnification: dball.magnification as i32,
             prize_status: None,
             deprecated: false,
+            note: None,
+            purchased: false,
+            extra_reds: None,
+            extra_blues: None,
+            bankers: None,
+            profile_id: DEFAULT_PROFILE_ID,
             created_time: now,
             modified_time: now,
         }
@@ -212,6 +435,9 @@ impl PartialEq for Spot {
             && self.red6 == other.red6
             && self.blue == other.blue
             && self.magnification == other.magnification
+            && self.extra_reds == other.extra_reds
+            && self.extra_blues == other.extra_blues
+            && self.bankers == other.bankers
     }
 }
 
@@ -220,6 +446,10 @@ impl Display for SpotError {
         match self {
             Self::EmptyPeriod => write!(f, "Period cannot be empty"),
             Self::DBallError(msg) => write!(f, "invalid spot record: {msg}"),
+            Self::InvalidNumbers(number) => write!(f, "invalid spot number: {number}"),
+            Self::Complex => write!(f, "spot holds a complex bet of several tickets"),
+            Self::Dantuo => write!(f, "spot holds a dantuo bet of bankers and drags"),
+            Self::NotDantuo => write!(f, "spot holds no dantuo bet"),
         }
     }
 }
@@ -229,7 +459,18 @@ impl std::error::Error for SpotError {}
 impl std::fmt::Display for Spot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use console::style;
-        // Try to use DBall's display format for the numbers part
+        // Try to use DBall's display format for the numbers part, or
+        // ComplexBet's and DantuoBet's for complex spots
+        if self.is_dantuo()
+            && let Ok(bet) = self.to_dantuo()
+        {
+            return write!(f, "{bet}");
+        }
+        if self.is_complex()
+            && let Ok(bet) = self.to_complex()
+        {
+            return write!(f, "{bet}");
+        }
         match self.to_dball() {
             Ok(dball) => {
                 write!(f, "{dball}")
@@ -354,4 +595,42 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_complex_spot() -> anyhow::Result<()> {
+        let bet = ComplexBet::new([2, 6, 7, 13, 16, 28, 31, 33], [11, 4], 1)?;
+        let test_spot = Spot::from_complex("2025084", &bet, None)?;
+
+        assert!(test_spot.is_complex());
+        assert_eq!(test_spot.extra_reds.as_deref(), Some("31 33"));
+        assert_eq!(test_spot.extra_blues.as_deref(), Some("11"));
+        assert_eq!(test_spot.red_numbers(), vec![2, 6, 7, 13, 16, 28, 31, 33]);
+        assert_eq!(test_spot.blue_numbers(), vec![4, 11]);
+        assert_eq!(test_spot.to_complex()?, bet);
+        assert_eq!(test_spot.check(), Ok(()));
+        assert_eq!(test_spot.to_dball(), Err(SpotError::Complex));
+
+        let simple =
+            Spot::from_dball("2025084", &DBall::new_one([2, 6, 7, 13, 16, 28], 11)?, None)?;
+        assert!(!simple.is_complex());
+        assert!(simple.to_complex()?.is_simple());
+        assert_eq!(simple.to_dantuo(), Err(SpotError::NotDantuo));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dantuo_spot() -> anyhow::Result<()> {
+        let bet = DantuoBet::new([7, 2], [6, 13, 16, 28, 31], [11], 1)?;
+        let test_spot = Spot::from_dantuo("2025084", &bet, None)?;
+
+        assert!(test_spot.is_dantuo());
+        assert_eq!(test_spot.bankers.as_deref(), Some("2 7"));
+        assert_eq!(test_spot.red_numbers(), vec![2, 6, 7, 13, 16, 28, 31]);
+        assert_eq!(test_spot.to_dantuo()?, bet);
+        assert_eq!(test_spot.to_complex(), Err(SpotError::Dantuo));
+        assert_eq!(test_spot.bets()?, bet.expand());
+        assert_eq!(test_spot.cost()?, bet.cost());
+        assert_eq!(test_spot.check(), Ok(()));
+        Ok(())
+    }
 }
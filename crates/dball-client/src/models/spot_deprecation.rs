@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// One time a spot was deprecated or restored
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::spot_deprecations),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct SpotDeprecation {
+    pub id: Option<i32>,
+    pub spot_id: i32,
+    /// Whether the spot was deprecated, `false` when it was restored
+    pub deprecated: bool,
+    /// Where the change was asked for, `ipc`, `http` or `cli`
+    pub actor: String,
+    pub reason: Option<String>,
+    #[schemars(with = "String")]
+    pub created_time: NaiveDateTime,
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Conditions on spots translated into a single database query
+///
+/// All set conditions must hold for a spot to match, the default filter matches
+/// everything. Unlike [`super::SpotQuery`] every field fits in a URL query string
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct SpotFilter {
+    /// First period included, e.g. `2025001`
+    pub period_from: Option<String>,
+    /// Last period included
+    pub period_to: Option<String>,
+    /// First day of creation included, as `2025-09-01`
+    #[schemars(with = "Option<String>")]
+    pub created_from: Option<NaiveDate>,
+    /// Last day of creation included
+    #[schemars(with = "Option<String>")]
+    pub created_to: Option<NaiveDate>,
+    /// Only spots checked against a draw when `true`, only waiting ones when `false`
+    pub settled: Option<bool>,
+    /// Least prize in yuan, e.g. `200` for the fourth tier and up, unsettled spots never match
+    pub min_prize: Option<i32>,
+    /// Most prize in yuan, `0` for settled spots that won nothing
+    pub max_prize: Option<i32>,
+    /// Only deprecated spots when `true`, only live ones when `false`
+    pub deprecated: Option<bool>,
+    pub magnification: Option<i32>,
+    /// Red number the spot must contain, extra reds of complex bets included
+    pub red: Option<i32>,
+    /// Blue number the spot must contain, extra blues of complex bets included
+    pub blue: Option<i32>,
+    /// Tag the spot must carry
+    pub tag: Option<String>,
+}
+
+impl SpotFilter {
+    /// Periods from `from` to `to`, both included and either open
+    pub fn periods(mut self, from: Option<&str>, to: Option<&str>) -> Self {
+        self.period_from = from.map(str::to_owned);
+        self.period_to = to.map(str::to_owned);
+        self
+    }
+
+    /// Spots created from `from` to `to`, both days included and either open
+    pub fn created_between(mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        self.created_from = from;
+        self.created_to = to;
+        self
+    }
+
+    pub fn settled(mut self, settled: bool) -> Self {
+        self.settled = Some(settled);
+        self
+    }
+
+    /// Prizes from `min` to `max` yuan, both included and either open
+    pub fn prize_between(mut self, min: Option<i32>, max: Option<i32>) -> Self {
+        self.min_prize = min;
+        self.max_prize = max;
+        self
+    }
+
+    pub fn deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = Some(deprecated);
+        self
+    }
+
+    pub fn magnification(mut self, magnification: i32) -> Self {
+        self.magnification = Some(magnification);
+        self
+    }
+
+    pub fn containing_red(mut self, red: i32) -> Self {
+        self.red = Some(red);
+        self
+    }
+
+    pub fn containing_blue(mut self, blue: i32) -> Self {
+        self.blue = Some(blue);
+        self
+    }
+
+    pub fn tagged(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_owned());
+        self
+    }
+
+    /// Check the numbers and ranges, the query would silently match nothing otherwise
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(red) = self.red
+            && !(1..=33).contains(&red)
+        {
+            anyhow::bail!("Red number {red} out of range 1-33");
+        }
+        if let Some(blue) = self.blue
+            && !(1..=16).contains(&blue)
+        {
+            anyhow::bail!("Blue number {blue} out of range 1-16");
+        }
+        if let (Some(from), Some(to)) = (&self.period_from, &self.period_to)
+            && from > to
+        {
+            anyhow::bail!("Period range {from}-{to} is empty");
+        }
+        if let (Some(from), Some(to)) = (self.created_from, self.created_to)
+            && from > to
+        {
+            anyhow::bail!("Creation date range {from} to {to} is empty");
+        }
+        if let (Some(min), Some(max)) = (self.min_prize, self.max_prize)
+            && min > max
+        {
+            anyhow::bail!("Prize range {min}-{max} is empty");
+        }
+        if self.tag.as_deref().is_some_and(|tag| tag.trim().is_empty()) {
+            anyhow::bail!("Empty tag in filter");
+        }
+        Ok(())
+    }
+}
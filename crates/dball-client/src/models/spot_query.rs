@@ -0,0 +1,191 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::Spot;
+
+/// Which spots a query looks at, depending on whether they were checked against a draw
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+pub enum SpotScope {
+    #[default]
+    All,
+    /// Spots waiting for a draw, deprecated ones excluded
+    Unprized,
+    /// Spots already checked against a draw
+    Prized,
+}
+
+/// Filter parameters for spot searches
+///
+/// All set conditions must hold for a spot to match, the default query matches everything
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct SpotQuery {
+    pub scope: SpotScope,
+    /// Period must start with this prefix, e.g. `2025` or `20250`
+    pub period_prefix: Option<String>,
+    /// Only spots with a prize amount greater than 0
+    pub winning_only: bool,
+    /// Spot must carry every one of these tags
+    pub tags: Vec<String>,
+    /// Spot must contain every one of these red numbers
+    pub reds: Vec<i32>,
+    pub blue: Option<i32>,
+}
+
+impl SpotQuery {
+    pub fn with_scope(mut self, scope: SpotScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Whether the query has no filter besides its scope
+    pub fn is_unfiltered(&self) -> bool {
+        self.period_prefix.is_none()
+            && !self.winning_only
+            && self.tags.is_empty()
+            && self.reds.is_empty()
+            && self.blue.is_none()
+    }
+
+    /// Whether the spot matches every condition but the tags, which are not
+    /// stored on the spot itself
+    pub fn matches(&self, spot: &Spot) -> bool {
+        let scope_matches = match self.scope {
+            SpotScope::All => true,
+            SpotScope::Unprized => spot.prize_status.is_none() && !spot.deprecated,
+            SpotScope::Prized => spot.prize_status.is_some(),
+        };
+
+        scope_matches
+            && self
+                .period_prefix
+                .as_ref()
+                .is_none_or(|prefix| spot.period.starts_with(prefix.as_str()))
+            && (!self.winning_only || spot.prize_status.is_some_and(|p| p > 0))
+            && self.reds.iter().all(|red| spot.red_numbers().contains(red))
+            && self.blue.is_none_or(|blue| spot.blue == blue)
+    }
+}
+
+/// Parse a search bar input, space separated terms:
+///
+/// - `2025084`, `2025`: period prefix
+/// - `win`: winning spots only
+/// - `#tag`, `#"birthday numbers"`: tag filter, quoted when it holds spaces
+/// - `r:1,5,33`: contains red numbers
+/// - `b:7`: blue number
+impl FromStr for SpotQuery {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Self::default();
+
+        for term in terms(s)? {
+            let term = term.as_str();
+            if term.eq_ignore_ascii_case("win") {
+                query.winning_only = true;
+            } else if let Some(tag) = term.strip_prefix('#') {
+                if tag.is_empty() {
+                    anyhow::bail!("Empty tag in search term `{term}`");
+                }
+                query.tags.push(tag.to_owned());
+            } else if let Some(reds) = term.strip_prefix("r:") {
+                for red in reds.split(',').filter(|r| !r.is_empty()) {
+                    let red = parse_number(red, 33)?;
+                    if !query.reds.contains(&red) {
+                        query.reds.push(red);
+                    }
+                }
+            } else if let Some(blue) = term.strip_prefix("b:") {
+                query.blue = Some(parse_number(blue, 16)?);
+            } else if term.chars().all(|c| c.is_ascii_digit()) {
+                query.period_prefix = Some(term.to_owned());
+            } else {
+                anyhow::bail!("Unknown search term `{term}`");
+            }
+        }
+
+        if query.reds.len() > 6 {
+            anyhow::bail!("A spot contains at most 6 red numbers");
+        }
+
+        Ok(query)
+    }
+}
+
+/// Split the input on whitespace outside of double quotes, dropping the quotes
+fn terms(s: &str) -> anyhow::Result<Vec<String>> {
+    let (mut terms, mut term, mut quoted) = (Vec::new(), String::new(), false);
+    for c in s.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            c => term.push(c),
+        }
+    }
+    if quoted {
+        anyhow::bail!("Unclosed quote in search `{s}`");
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+fn parse_number(s: &str, max: i32) -> anyhow::Result<i32> {
+    let number = s
+        .parse::<i32>()
+        .map_err(|e| anyhow::anyhow!("Invalid number `{s}`: {e}"))?;
+    if !(1..=max).contains(&number) {
+        anyhow::bail!("Number {number} out of range 1-{max}");
+    }
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dball_combora::dball::DBall;
+
+    #[test]
+    fn test_parse_spot_query() -> anyhow::Result<()> {
+        let query: SpotQuery = "2025 win #lucky r:3,15 b:7".parse()?;
+        assert_eq!(query.period_prefix.as_deref(), Some("2025"));
+        assert!(query.winning_only);
+        assert_eq!(query.tags, vec!["lucky".to_owned()]);
+        assert_eq!(query.reds, vec![3, 15]);
+        assert_eq!(query.blue, Some(7));
+
+        let query: SpotQuery = r##"#"birthday numbers" #lucky"##.parse()?;
+        assert_eq!(query.tags, ["birthday numbers", "lucky"]);
+        assert!(r##"#"birthday"##.parse::<SpotQuery>().is_err());
+
+        assert!("".parse::<SpotQuery>()?.is_unfiltered());
+        assert!("r:34".parse::<SpotQuery>().is_err());
+        assert!("b:0".parse::<SpotQuery>().is_err());
+        assert!("foo".parse::<SpotQuery>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_spot_query_matches() -> anyhow::Result<()> {
+        let dball = DBall::new(vec![3, 6, 7, 13, 15, 28], 7, 1)
+            .map_err(|e| anyhow::anyhow!("DBall creation failed: {e}"))?;
+        let spot = Spot::from_dball("2025084", &dball, Some(5))?;
+
+        assert!(SpotQuery::default().matches(&spot));
+        assert!("20250 win r:3,15 b:7".parse::<SpotQuery>()?.matches(&spot));
+        assert!(!"2024".parse::<SpotQuery>()?.matches(&spot));
+        assert!(!"r:1".parse::<SpotQuery>()?.matches(&spot));
+        assert!(
+            !SpotQuery::default()
+                .with_scope(SpotScope::Unprized)
+                .matches(&spot)
+        );
+        Ok(())
+    }
+}
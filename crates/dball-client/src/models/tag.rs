@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+#[cfg(not(target_arch = "wasm32"))]
+use diesel::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Label spots can carry, e.g. `birthday numbers` or `strategy-A`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::tags),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
+pub struct Tag {
+    pub id: Option<i32>,
+    pub name: String,
+    #[schemars(with = "String")]
+    pub created_time: NaiveDateTime,
+}
+
+/// Tag along with the number of spots carrying it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TagCount {
+    pub name: String,
+    pub spots: i64,
+}
@@ -0,0 +1,48 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Stored periods to compare against the provider, all of them by default
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct TicketAuditRequest {
+    /// First period to audit, e.g. `2025001`
+    pub from: Option<String>,
+    /// Last period to audit, included
+    pub to: Option<String>,
+    /// Audit only this many periods spread evenly over the range
+    pub sample: Option<usize>,
+}
+
+/// Outcome of comparing stored tickets with the ticket log and the provider
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct TicketAudit {
+    /// Periods the provider answered for
+    pub checked: Vec<String>,
+    /// Periods whose sources do not agree
+    pub discrepancies: Vec<TicketDiscrepancy>,
+    /// Periods the provider could not be asked about
+    pub failed: Vec<AuditFailure>,
+}
+
+impl TicketAudit {
+    /// Whether every checked period agrees everywhere and none failed
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// The numbers each source has for a period, `None` where it has none
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct TicketDiscrepancy {
+    pub period: String,
+    pub database: String,
+    pub ticket_log: Option<String>,
+    /// `None` when the provider has no draw for the period
+    pub api: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct AuditFailure {
+    pub period: String,
+    pub error: String,
+}
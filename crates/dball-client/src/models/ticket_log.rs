@@ -1,4 +1,4 @@
-use dball_combora::dball::DBall;
+use dball_combora::dball::{DBall, PrizeTable};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -97,6 +97,15 @@ impl TicketLog {
         }
     }
 
+    /// Prizes paid in the period, the default ones for amounts not recorded
+    pub fn prize_table(&self) -> PrizeTable {
+        let amount = |money: Option<i32>| money.and_then(|money| u32::try_from(money).ok());
+        PrizeTable::new(
+            amount(self.prize1_money).filter(|money| *money > 0),
+            amount(self.prize2_money).filter(|money| *money > 0),
+        )
+    }
+
     pub fn to_dball(&self) -> anyhow::Result<DBall> {
         let red_numbers = self.red_numbers();
         let red_u8: Vec<u8> = red_numbers.iter().map(|&x| x as u8).collect();
@@ -132,3 +141,42 @@ impl TryFrom<TicketLog> for DBall {
         ticket_log.to_dball()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prize_table() {
+        let mut record = TicketLog {
+            code: "2025088".to_owned(),
+            kj_date: None,
+            xq: None,
+            number1: Some(3),
+            number2: Some(8),
+            number3: Some(14),
+            number4: Some(21),
+            number5: Some(27),
+            number6: Some(32),
+            number7: Some(9),
+            jsondata: None,
+            total_sales: None,
+            jackpot: None,
+            prize1_num: Some(4),
+            prize1_money: Some(7_312_640),
+            prize2_num: Some(0),
+            prize2_money: Some(0),
+            prize3_num: None,
+            prize3_money: None,
+            prize4_num: None,
+            prize4_money: None,
+            prize5_num: None,
+            prize5_money: None,
+            prize6_num: None,
+            prize6_money: None,
+        };
+        assert_eq!(record.prize_table(), PrizeTable::new(Some(7_312_640), None));
+        record.prize1_money = None;
+        assert_eq!(record.prize_table(), PrizeTable::default());
+    }
+}
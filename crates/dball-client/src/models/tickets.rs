@@ -1,14 +1,19 @@
 use chrono::NaiveDateTime;
 use dball_combora::dball::DBall;
+#[cfg(not(target_arch = "wasm32"))]
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// Complete ticket record structure for both querying and inserting
 /// The id field will be None for new records and Some(value) for existing records
-#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
-#[diesel(table_name = crate::models::schema::tickets)]
-#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    derive(Queryable, Selectable, Insertable),
+    diesel(table_name = crate::models::schema::tickets),
+    diesel(check_for_backend(diesel::sqlite::Sqlite))
+)]
 pub struct Ticket {
     pub id: Option<i32>,
     pub period: String,
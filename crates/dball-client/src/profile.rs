@@ -0,0 +1,54 @@
+//! Profile the current request acts for
+//!
+//! The daemon runs the requests of a client that named its profile in the
+//! Hello message within [`scope`], so the spots and purchases listed or saved
+//! are those of the profile. Outside of a scope, as in the jobs of the daemon
+//! or the CLI without `--profile`, every spot is seen
+
+use crate::models::DEFAULT_PROFILE_ID;
+
+tokio::task_local! {
+    static ACTIVE: i32;
+}
+
+/// Id of the profile the current task acts for, `None` outside of [`scope`]
+pub fn active() -> Option<i32> {
+    ACTIVE.try_with(|id| *id).ok()
+}
+
+/// Profile owning the spots and purchases saved now, the default one outside of [`scope`]
+pub fn owner() -> i32 {
+    active().unwrap_or(DEFAULT_PROFILE_ID)
+}
+
+/// Whether a record of `profile_id` is seen by the current task
+pub fn sees(profile_id: i32) -> bool {
+    active().is_none_or(|id| id == profile_id)
+}
+
+/// Run `future` acting for `profile_id`, as before without one
+pub async fn scope<F: Future>(profile_id: Option<i32>, future: F) -> F::Output {
+    match profile_id {
+        Some(id) => ACTIVE.scope(id, future).await,
+        None => future.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_profile_scope() {
+        assert_eq!(active(), None);
+        assert_eq!(owner(), DEFAULT_PROFILE_ID);
+        assert!(sees(7));
+        scope(Some(7), async {
+            assert_eq!(owner(), 7);
+            assert!(sees(7));
+            assert!(!sees(DEFAULT_PROFILE_ID));
+        })
+        .await;
+        scope(None, async { assert_eq!(active(), None) }).await;
+    }
+}
@@ -0,0 +1,232 @@
+//! When the draws of a game take place
+//!
+//! A [`DrawSchedule`] gives the draw days and time in the game's own timezone,
+//! so nothing else needs to know where a lottery is drawn. The schedule of a
+//! game can be replaced in `dball.toml`, see [`crate::config::ScheduleConfig`]
+
+use chrono::{DateTime, Datelike as _, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use chrono::{TimeZone as _, Weekday};
+use chrono_tz::Tz;
+
+/// Code of 双色球 as the lottery APIs name it, the game the crate plays
+pub const SSQ: &str = "ssq";
+
+/// Days searched for the next draw, a schedule without one in a year is broken
+const MAX_DAYS_AHEAD: i64 = 366;
+
+/// 春节 of the years 双色球 has been drawn, as `(year, month, day)`
+const SPRING_FESTIVALS: [(i32, u32, u32); 28] = [
+    (2003, 2, 1),
+    (2004, 1, 22),
+    (2005, 2, 9),
+    (2006, 1, 29),
+    (2007, 2, 18),
+    (2008, 2, 7),
+    (2009, 1, 26),
+    (2010, 2, 14),
+    (2011, 2, 3),
+    (2012, 1, 23),
+    (2013, 2, 10),
+    (2014, 1, 31),
+    (2015, 2, 19),
+    (2016, 2, 8),
+    (2017, 1, 28),
+    (2018, 2, 16),
+    (2019, 2, 5),
+    (2020, 1, 25),
+    (2021, 2, 12),
+    (2022, 2, 1),
+    (2023, 1, 22),
+    (2024, 2, 10),
+    (2025, 1, 29),
+    (2026, 2, 17),
+    (2027, 2, 6),
+    (2028, 1, 26),
+    (2029, 2, 13),
+    (2030, 2, 3),
+];
+
+/// Draw days and time of a game
+pub trait DrawSchedule {
+    /// Timezone the draw days and time are given in
+    fn timezone(&self) -> Tz;
+
+    /// Days of the week with a draw
+    fn weekdays(&self) -> &[Weekday];
+
+    /// Local time of the draw
+    fn draw_time(&self) -> NaiveTime;
+
+    /// Dates without a draw although their weekday has one, e.g. holidays
+    fn blackout_dates(&self) -> &[NaiveDate];
+
+    /// One-off draws on dates whose weekday has none, e.g. a make-up draw
+    fn extra_dates(&self) -> &[NaiveDate] {
+        &[]
+    }
+
+    fn is_draw_day(&self, date: NaiveDate) -> bool {
+        self.extra_dates().contains(&date)
+            || (self.weekdays().contains(&date.weekday()) && !self.blackout_dates().contains(&date))
+    }
+
+    /// `local` time of the game's timezone in UTC, `None` when a clock change skips it
+    fn to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.timezone()
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// First draw after `time`, `None` when none is scheduled within a year
+    fn next_draw(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let today = time.with_timezone(&self.timezone()).date_naive();
+        (0..MAX_DAYS_AHEAD)
+            .map(|days| today + TimeDelta::days(days))
+            .filter(|date| self.is_draw_day(*date))
+            .filter_map(|date| self.to_utc(date.and_time(self.draw_time())))
+            .find(|draw| *draw > time)
+    }
+}
+
+/// Draws on the same weekdays at the same local time every week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklySchedule {
+    pub timezone: Tz,
+    pub weekdays: Vec<Weekday>,
+    pub draw_time: NaiveTime,
+    pub blackout_dates: Vec<NaiveDate>,
+    pub extra_dates: Vec<NaiveDate>,
+}
+
+impl WeeklySchedule {
+    /// 双色球, drawn on Tuesday, Thursday and Sunday at 21:15 Beijing time
+    ///
+    /// The draw counts as done at 21:20, once the numbers are announced. Sales
+    /// stop from 除夕 to 正月初六 every year, see [`spring_festival_breaks`];
+    /// longer suspensions such as the one of 2020 belong in an overrides file
+    pub fn ssq() -> Self {
+        Self {
+            timezone: chrono_tz::Asia::Shanghai,
+            weekdays: vec![Weekday::Tue, Weekday::Thu, Weekday::Sun],
+            draw_time: NaiveTime::from_hms_opt(21, 20, 0).expect("Valid draw time"),
+            blackout_dates: spring_festival_breaks(),
+            extra_dates: Vec::new(),
+        }
+    }
+
+    /// Built-in schedule of `game`, `None` for games the crate does not know
+    pub fn builtin(game: &str) -> Option<Self> {
+        (game == SSQ).then(Self::ssq)
+    }
+}
+
+/// Days without a draw around each Spring Festival, 除夕 to 正月初六
+pub fn spring_festival_breaks() -> Vec<NaiveDate> {
+    SPRING_FESTIVALS
+        .iter()
+        .filter_map(|&(year, month, day)| NaiveDate::from_ymd_opt(year, month, day))
+        .flat_map(|festival| (-1..=5).map(move |days| festival + TimeDelta::days(days)))
+        .collect()
+}
+
+impl DrawSchedule for WeeklySchedule {
+    fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    fn weekdays(&self) -> &[Weekday] {
+        &self.weekdays
+    }
+
+    fn draw_time(&self) -> NaiveTime {
+        self.draw_time
+    }
+
+    fn blackout_dates(&self) -> &[NaiveDate] {
+        &self.blackout_dates
+    }
+
+    fn extra_dates(&self) -> &[NaiveDate] {
+        &self.extra_dates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M")
+            .expect("Valid test time")
+            .and_utc()
+    }
+
+    #[test]
+    fn test_ssq_next_draw() {
+        let schedule = WeeklySchedule::ssq();
+        // Tuesday 2025-08-05, the draw is at 13:20 UTC
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-05 01:00")),
+            Some(utc("2025-08-05 13:20"))
+        );
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-05 13:20")),
+            Some(utc("2025-08-07 13:20"))
+        );
+        // late Saturday in UTC is already Sunday in Beijing
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-09 20:00")),
+            Some(utc("2025-08-10 13:20"))
+        );
+        // no draw from 除夕 to 正月初六, the break of 2025 runs 01-28 to 02-03
+        assert_eq!(
+            schedule.next_draw(utc("2025-01-26 14:00")),
+            Some(utc("2025-02-04 13:20"))
+        );
+    }
+
+    #[test]
+    fn test_blackout_and_timezone() {
+        let mut schedule = WeeklySchedule::ssq();
+        schedule
+            .blackout_dates
+            .push(NaiveDate::from_ymd_opt(2025, 8, 7).expect("Valid date"));
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-05 14:00")),
+            Some(utc("2025-08-10 13:20"))
+        );
+
+        // New York is on summer time in August and winter time in December
+        schedule.timezone = chrono_tz::America::New_York;
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-05 14:00")),
+            Some(utc("2025-08-06 01:20"))
+        );
+        assert_eq!(
+            schedule.next_draw(utc("2025-12-02 14:00")),
+            Some(utc("2025-12-03 02:20"))
+        );
+
+        schedule.weekdays.clear();
+        assert_eq!(schedule.next_draw(utc("2025-08-05 14:00")), None);
+    }
+
+    #[test]
+    fn test_extra_dates() {
+        let mut schedule = WeeklySchedule::ssq();
+        // Wednesday 2025-08-06 gets a make-up draw, even when also blacked out
+        let wednesday = NaiveDate::from_ymd_opt(2025, 8, 6).expect("Valid date");
+        schedule.extra_dates.push(wednesday);
+        schedule.blackout_dates.push(wednesday);
+        assert!(schedule.is_draw_day(wednesday));
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-05 14:00")),
+            Some(utc("2025-08-06 13:20"))
+        );
+        assert_eq!(
+            schedule.next_draw(utc("2025-08-06 13:20")),
+            Some(utc("2025-08-07 13:20"))
+        );
+    }
+}
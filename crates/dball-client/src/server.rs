@@ -1,43 +1,79 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
 
 use crate::ipc::protocol::AppState;
 
+mod auth;
+mod bind;
 mod handlers;
 mod router;
 mod rpc;
+mod stream;
 mod types;
 
+pub use bind::{BindAddr, BindError, DEFAULT_PORT};
+pub(crate) use rpc::call_rpc_service;
+
 #[derive(Clone)]
 pub struct HttpServer {
     state: Arc<RwLock<AppState>>,
-    addr: SocketAddr,
+    config: HttpServerConfig,
 }
 
 impl HttpServer {
-    pub fn new(state: Arc<RwLock<AppState>>) -> Self {
-        Self::with_config(state, &HttpServerConfig::from_env())
+    pub fn new(state: Arc<RwLock<AppState>>) -> Result<Self, BindError> {
+        Ok(Self::with_config(state, &HttpServerConfig::from_env()?))
     }
 
     pub fn with_config(state: Arc<RwLock<AppState>>, config: &HttpServerConfig) -> Self {
         Self {
             state,
-            addr: config.socket_addr(),
+            config: config.clone(),
         }
     }
 
+    /// Listen on every bind address, aborting the returned handle stops them all
     pub async fn start(&self) -> anyhow::Result<tokio::task::JoinHandle<()>> {
-        let addr = self.addr;
-        let app = router::build_router(self.state.clone());
+        if self.config.binds.is_empty() {
+            anyhow::bail!("HTTP server has no address to listen on");
+        }
+        let app = router::build_router(self.state.clone(), &self.config);
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        log::info!("HTTP server listening on {addr}");
+        let mut servers = tokio::task::JoinSet::new();
+        for bind in &self.config.binds {
+            match bind {
+                BindAddr::Tcp(addr) => {
+                    let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+                        anyhow::anyhow!("Failed to bind HTTP server to {addr}: {e}")
+                    })?;
+                    let app = app.clone();
+                    servers.spawn(async move { axum::serve(listener, app).await });
+                }
+                #[cfg(unix)]
+                BindAddr::Unix(path) => {
+                    // a socket left behind by a previous run blocks the bind
+                    if path.exists() {
+                        std::fs::remove_file(path)?;
+                    }
+                    let listener = tokio::net::UnixListener::bind(path).map_err(|e| {
+                        anyhow::anyhow!("Failed to bind HTTP server to {bind}: {e}")
+                    })?;
+                    servers.spawn(serve_unix(listener, app.clone()));
+                }
+            }
+            log::info!("HTTP server listening on {bind}");
+        }
 
         let handle = tokio::spawn(async move {
-            if let Err(e) = axum::serve(listener, app).await {
-                log::error!("HTTP server stopped: {e}");
+            while let Some(result) = servers.join_next().await {
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => log::error!("HTTP server stopped: {e}"),
+                    Err(e) => log::error!("HTTP server task failed: {e}"),
+                }
             }
         });
 
@@ -45,24 +81,106 @@ impl HttpServer {
     }
 }
 
-#[derive(Default)]
+/// `axum::serve` only takes TCP listeners, so connections on a Unix socket are
+/// handed to hyper one by one
+#[cfg(unix)]
+async fn serve_unix(listener: tokio::net::UnixListener, app: axum::Router) -> std::io::Result<()> {
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use hyper_util::server::conn::auto::Builder;
+    use hyper_util::service::TowerToHyperService;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            if let Err(e) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                log::debug!("HTTP connection on unix socket closed: {e}");
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
 pub struct HttpServerConfig {
-    pub host: String,
-    pub port: u16,
+    /// Addresses listened on at the same time
+    pub binds: Vec<BindAddr>,
+    /// Directory of the web dashboard build, served for every path outside the API
+    pub web_dir: Option<PathBuf>,
+    /// Origins allowed to call the API from a browser, `*` allows any
+    pub cors_origins: Vec<String>,
+    /// Bearer token required by the API, `None` leaves it open
+    pub auth_token: Option<String>,
+}
+
+impl Default for HttpServerConfig {
+    fn default() -> Self {
+        Self {
+            binds: vec![BindAddr::Tcp(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                DEFAULT_PORT,
+            ))],
+            web_dir: None,
+            cors_origins: Vec::new(),
+            auth_token: None,
+        }
+    }
 }
 
 impl HttpServerConfig {
-    pub fn from_env() -> Self {
-        let host = std::env::var("DBALL_HTTP_HOST").unwrap_or_else(|_| "127.0.0.1".to_owned());
-        let port = std::env::var("DBALL_HTTP_PORT")
+    /// Configuration from the `DBALL_HTTP_*` variables, an invalid address is an error
+    pub fn from_env() -> Result<Self, BindError> {
+        let var = |key: &str| std::env::var(key).ok();
+        #[cfg(feature = "simulation")]
+        let var = |key: &str| {
+            var(key).or_else(|| {
+                (key == "DBALL_HTTP_BIND").then(|| crate::simulation::HTTP_BIND.to_owned())
+            })
+        };
+        let binds = bind::binds_from(var)?;
+        let web_dir = std::env::var("DBALL_WEB_DIR").ok().map(PathBuf::from);
+        let cors_origins = std::env::var("DBALL_HTTP_CORS_ORIGINS")
+            .map(|value| split_origins(&value))
+            .unwrap_or_default();
+        let auth_token = std::env::var("DBALL_HTTP_TOKEN")
             .ok()
-            .and_then(|value| value.parse().ok())
-            .unwrap_or(8081);
-        Self { host, port }
+            .filter(|token| !token.trim().is_empty());
+        Ok(Self {
+            binds,
+            web_dir,
+            cors_origins,
+            auth_token,
+        })
     }
 
-    pub fn socket_addr(&self) -> SocketAddr {
-        let ip: IpAddr = self.host.parse().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
-        SocketAddr::new(ip, self.port)
+    /// URL of the first TCP address for clients on this machine
+    pub fn client_url(&self) -> Option<String> {
+        self.binds.iter().find_map(BindAddr::client_url)
+    }
+}
+
+/// Comma separated origins, blanks dropped
+fn split_origins(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_origins() {
+        assert_eq!(
+            split_origins(" http://localhost:8080, ,https://dball.example.com,"),
+            vec!["http://localhost:8080", "https://dball.example.com"]
+        );
+        assert!(split_origins("").is_empty());
     }
 }
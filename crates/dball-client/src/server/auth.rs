@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse as _, Response},
+};
+
+use crate::ipc::error::RpcErrorCode;
+
+use super::types::err_response;
+
+/// Reject API requests without the bearer `token`, the health check,
+/// the API docs and the web dashboard stay public
+pub(super) async fn require_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let public = !path.starts_with("/api/") || path.starts_with("/api/docs");
+    if public || bearer_token(request.headers()) == Some(&*token) {
+        return next.run(request).await;
+    }
+    log::warn!("Rejected unauthorized request to {path}");
+    err_response(
+        StatusCode::UNAUTHORIZED,
+        RpcErrorCode::Unauthorized.as_str(),
+        "Missing or wrong auth token, set the token of the daemon in the client",
+    )
+    .into_response()
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret"),
+        );
+        assert_eq!(bearer_token(&headers), Some("s3cret"));
+    }
+}
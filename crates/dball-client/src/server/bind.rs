@@ -0,0 +1,250 @@
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Port of the HTTP server when neither `DBALL_HTTP_PORT` nor `PORT` is set
+pub const DEFAULT_PORT: u16 = 8081;
+
+/// Address the HTTP server listens on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindAddr {
+    /// `host:port`, e.g. `0.0.0.0:8081` or `[::1]:8081`
+    Tcp(SocketAddr),
+    /// `unix:` followed by the socket path, e.g. `unix:/run/dball/http.sock`
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl BindAddr {
+    /// URL a client on this machine reaches a TCP address with,
+    /// an unspecified address like `0.0.0.0` is reached over loopback
+    pub fn client_url(&self) -> Option<String> {
+        match self {
+            Self::Tcp(addr) => {
+                let mut addr = *addr;
+                if addr.ip().is_unspecified() {
+                    addr.set_ip(match addr.ip() {
+                        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                    });
+                }
+                Some(format!("http://{addr}"))
+            }
+            #[cfg(unix)]
+            Self::Unix(_) => None,
+        }
+    }
+}
+
+impl Display for BindAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for BindAddr {
+    type Err = BindError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Some(path) = value.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return if path.is_empty() {
+                Err(BindError::InvalidAddr(value.to_owned()))
+            } else {
+                Ok(Self::Unix(PathBuf::from(path)))
+            };
+            #[cfg(not(unix))]
+            return Err(BindError::UnixUnsupported(path.to_owned()));
+        }
+        let addr = match value.strip_prefix("localhost:") {
+            Some(port) => port
+                .parse()
+                .ok()
+                .map(|port| SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)),
+            None => value.parse().ok(),
+        };
+        addr.map(Self::Tcp)
+            .ok_or_else(|| BindError::InvalidAddr(value.to_owned()))
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum BindError {
+    #[error("Invalid bind address `{0}`, expected `host:port` like `0.0.0.0:8081` or `unix:/path`")]
+    InvalidAddr(String),
+    #[error("Invalid host `{value}` in {var}, expected an IP address like `0.0.0.0`")]
+    InvalidHost { var: &'static str, value: String },
+    #[error("Invalid port `{value}` in {var}, expected a number from 1 to 65535")]
+    InvalidPort { var: &'static str, value: String },
+    #[error("{0} lists no address to bind")]
+    Empty(&'static str),
+    #[error("Unix socket `{0}` is not supported on this platform")]
+    UnixUnsupported(String),
+}
+
+/// Addresses to listen on, read through `var` so they can be resolved from any environment
+///
+/// `DBALL_HTTP_BIND` lists them separated by commas and wins over everything else.
+/// Without it a single address is built from `DBALL_HTTP_HOST` and `DBALL_HTTP_PORT`,
+/// falling back to the `PORT` a platform as a service hands out. As it routes to the
+/// container from outside, `PORT` alone listens on all interfaces instead of loopback
+pub fn binds_from(var: impl Fn(&str) -> Option<String>) -> Result<Vec<BindAddr>, BindError> {
+    let var = |key: &str| var(key).filter(|value| !value.trim().is_empty());
+
+    if let Some(value) = var("DBALL_HTTP_BIND") {
+        let binds = value
+            .split(',')
+            .map(str::trim)
+            .filter(|bind| !bind.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if binds.is_empty() {
+            return Err(BindError::Empty("DBALL_HTTP_BIND"));
+        }
+        return Ok(binds);
+    }
+
+    let (port, paas) = match (var("DBALL_HTTP_PORT"), var("PORT")) {
+        (Some(port), _) => (parse_port("DBALL_HTTP_PORT", &port)?, false),
+        (None, Some(port)) => (parse_port("PORT", &port)?, true),
+        (None, None) => (DEFAULT_PORT, false),
+    };
+    let host = match var("DBALL_HTTP_HOST") {
+        Some(host) => parse_host("DBALL_HTTP_HOST", &host)?,
+        None if paas => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        None => IpAddr::V4(Ipv4Addr::LOCALHOST),
+    };
+    Ok(vec![BindAddr::Tcp(SocketAddr::new(host, port))])
+}
+
+fn parse_port(var: &'static str, value: &str) -> Result<u16, BindError> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&port| port != 0)
+        .ok_or_else(|| BindError::InvalidPort {
+            var,
+            value: value.to_owned(),
+        })
+}
+
+fn parse_host(var: &'static str, value: &str) -> Result<IpAddr, BindError> {
+    let value = value.trim();
+    if value == "localhost" {
+        return Ok(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    }
+    value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+        .ok_or_else(|| BindError::InvalidHost {
+            var,
+            value: value.to_owned(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binds(vars: &[(&str, &str)]) -> Result<Vec<BindAddr>, BindError> {
+        binds_from(|key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| (*value).to_owned())
+        })
+    }
+
+    fn tcp(addr: &str) -> BindAddr {
+        BindAddr::Tcp(addr.parse().expect("Valid socket address"))
+    }
+
+    #[test]
+    fn test_default_bind() {
+        assert_eq!(binds(&[]), Ok(vec![tcp("127.0.0.1:8081")]));
+    }
+
+    #[test]
+    fn test_bind_list() {
+        assert_eq!(
+            binds(&[("DBALL_HTTP_BIND", "0.0.0.0:8081, [::]:9000,")]),
+            Ok(vec![tcp("0.0.0.0:8081"), tcp("[::]:9000")])
+        );
+        assert_eq!(
+            binds(&[("DBALL_HTTP_BIND", " , ")]),
+            Err(BindError::Empty("DBALL_HTTP_BIND"))
+        );
+        assert_eq!(
+            binds(&[("DBALL_HTTP_BIND", "example.com:80")]),
+            Err(BindError::InvalidAddr("example.com:80".to_owned()))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_bind() {
+        let bind = "unix:/run/dball/http.sock"
+            .parse::<BindAddr>()
+            .expect("Valid unix bind");
+        assert_eq!(bind, BindAddr::Unix(PathBuf::from("/run/dball/http.sock")));
+        assert_eq!(bind.to_string(), "unix:/run/dball/http.sock");
+        assert_eq!(bind.client_url(), None);
+        assert!("unix:".parse::<BindAddr>().is_err());
+    }
+
+    #[test]
+    fn test_host_and_port() {
+        assert_eq!(
+            binds(&[("DBALL_HTTP_HOST", "0.0.0.0"), ("DBALL_HTTP_PORT", "9000")]),
+            Ok(vec![tcp("0.0.0.0:9000")])
+        );
+        assert_eq!(
+            binds(&[("DBALL_HTTP_HOST", "my-host")]),
+            Err(BindError::InvalidHost {
+                var: "DBALL_HTTP_HOST",
+                value: "my-host".to_owned()
+            })
+        );
+        assert_eq!(
+            binds(&[("DBALL_HTTP_PORT", "0")]),
+            Err(BindError::InvalidPort {
+                var: "DBALL_HTTP_PORT",
+                value: "0".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_paas_port() {
+        assert_eq!(binds(&[("PORT", "5000")]), Ok(vec![tcp("0.0.0.0:5000")]));
+        assert_eq!(
+            binds(&[("PORT", "5000"), ("DBALL_HTTP_PORT", "9000")]),
+            Ok(vec![tcp("127.0.0.1:9000")])
+        );
+        assert_eq!(
+            binds(&[("PORT", "5000"), ("DBALL_HTTP_HOST", "127.0.0.1")]),
+            Ok(vec![tcp("127.0.0.1:5000")])
+        );
+    }
+
+    #[test]
+    fn test_client_url() {
+        assert_eq!(
+            tcp("0.0.0.0:8081").client_url().as_deref(),
+            Some("http://127.0.0.1:8081")
+        );
+        assert_eq!(
+            tcp("[::]:8081").client_url().as_deref(),
+            Some("http://[::1]:8081")
+        );
+    }
+}
@@ -1,13 +1,51 @@
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+};
 use serde_json::json;
 
+use crate::config::DballConfig;
 use crate::ipc::protocol::RpcService;
+use crate::models::{
+    ExportRequest, ManualSpot, NewPrizeClaim, NewPurchase, PageRequest, RecommendRequest,
+    SpotFilter, SpotQuery, SpotScope, TicketAuditRequest,
+};
+use crate::service::PeriodicReport;
 
-use super::rpc::handle_rpc_service;
-use super::types::{ApiResult, PeriodsRequest, RouterState, YearRequest, err_response, ok_value};
+use super::rpc::{handle_rpc_service, handle_tracked_rpc_service, rpc_value};
+use super::stream::{NdjsonSpots, ndjson_spots};
+use super::types::{
+    ApiResult, ArchiveRequest, BackupRequest, DryRunQuery, NoteRequest, OperationIdPath,
+    OperationQuery, PeriodPath, PeriodPrefixQuery, PeriodicReportQuery, PeriodsRequest,
+    PlanRequest, PurchasedRequest, ReportFormat, ReportFormatQuery, RestoreRequest, RouterState,
+    SimulationRequest, SpotIdPath, StrategyQuery, TagRequest, WindowQuery, YearRequest,
+    err_response, ok_value,
+};
 
+/// Liveness of the server along with the health of its database, `degraded`
+/// when the integrity check fails or the database cannot be read
 pub(super) async fn health() -> ApiResult {
-    ok_value(json!({"status": "ok"}))
+    match crate::service::database_health().await {
+        Ok(database) => ok_value(json!({
+            "status": if database.is_ok() { "ok" } else { "degraded" },
+            "database": database,
+        })),
+        Err(e) => ok_value(json!({"status": "degraded", "database_error": e.to_string()})),
+    }
+}
+
+/// Counters and operation durations in the Prometheus text format
+pub(super) async fn prometheus_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::snapshot().to_prometheus(),
+    )
+}
+
+pub(super) async fn get_metrics(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetMetrics, state).await
 }
 
 pub(super) async fn get_state(State(state): State<RouterState>) -> ApiResult {
@@ -30,42 +68,335 @@ pub(super) async fn get_unprized_spots(State(state): State<RouterState>) -> ApiR
     handle_rpc_service(RpcService::GetUnprizeSpots, state).await
 }
 
-pub(super) async fn get_prized_spots(State(state): State<RouterState>) -> ApiResult {
-    handle_rpc_service(RpcService::GetPrizedSpots, state).await
+pub(super) async fn get_next_batch_summary(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetNextBatchSummary, state).await
+}
+
+pub(super) async fn get_prized_spots(
+    State(state): State<RouterState>,
+    Query(page): Query<PageRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::GetPrizedSpots(page), state).await
+}
+
+pub(super) async fn find_spots(
+    State(state): State<RouterState>,
+    Query(filter): Query<SpotFilter>,
+    Query(page): Query<PageRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::FindSpots(filter, page), state).await
+}
+
+pub(super) async fn get_number_frequencies(
+    State(state): State<RouterState>,
+    Query(query): Query<WindowQuery>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::GetNumberFrequencies(query.window), state).await
+}
+
+pub(super) async fn get_roi_analytics(
+    State(state): State<RouterState>,
+    Query(query): Query<WindowQuery>,
+) -> ApiResult {
+    const DEFAULT_WINDOW: usize = 10;
+    let window = query.window.unwrap_or(DEFAULT_WINDOW);
+    handle_rpc_service(RpcService::GetRoiAnalytics(window), state).await
+}
+
+pub(super) async fn get_roi_report(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetRoiReport, state).await
+}
+
+pub(super) async fn get_periodic_report(
+    State(state): State<RouterState>,
+    Query(query): Query<PeriodicReportQuery>,
+) -> ApiResult {
+    handle_rpc_service(
+        RpcService::GetPeriodicReport(query.year, query.month),
+        state,
+    )
+    .await
+}
+
+/// The month or year report as a Markdown or JSON file to save
+pub(super) async fn download_periodic_report(
+    State(state): State<RouterState>,
+    Query(query): Query<PeriodicReportQuery>,
+    Query(format): Query<ReportFormatQuery>,
+) -> Result<impl IntoResponse, ApiResult> {
+    let value = rpc_value(
+        RpcService::GetPeriodicReport(query.year, query.month),
+        state,
+    )
+    .await?;
+    let report = serde_json::from_value::<PeriodicReport>(value).map_err(|e| {
+        err_response(
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "serialize",
+            e.to_string(),
+        )
+    })?;
+    let (content_type, extension, body) = match format.format {
+        ReportFormat::Markdown => ("text/markdown; charset=utf-8", "md", report.to_markdown()),
+        ReportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&report).unwrap_or_default(),
+        ),
+    };
+    let disposition = format!(
+        "attachment; filename=\"dball-report-{}.{extension}\"",
+        report.title()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_owned()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+pub(super) async fn query_spots(
+    State(state): State<RouterState>,
+    Json(query): Json<SpotQuery>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::QuerySpots(query), state).await
+}
+
+pub(super) async fn stream_query_spots(
+    Json(query): Json<SpotQuery>,
+) -> Result<NdjsonSpots, ApiResult> {
+    ndjson_spots(query)
+}
+
+pub(super) async fn stream_prized_spots() -> Result<NdjsonSpots, ApiResult> {
+    ndjson_spots(SpotQuery::default().with_scope(SpotScope::Prized))
+}
+
+pub(super) async fn get_tickets(
+    State(state): State<RouterState>,
+    Query(query): Query<PeriodPrefixQuery>,
+    Query(page): Query<PageRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::GetTickets(query.period, page), state).await
+}
+
+pub(super) async fn export_csv(
+    State(state): State<RouterState>,
+    Json(request): Json<ExportRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::ExportCsv(request), state).await
+}
+
+pub(super) async fn get_config(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetConfig, state).await
+}
+
+pub(super) async fn update_config(
+    State(state): State<RouterState>,
+    Json(config): Json<DballConfig>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::UpdateConfig(Box::new(config)), state).await
 }
 
-pub(super) async fn update_all_unprize_spots(State(state): State<RouterState>) -> ApiResult {
-    handle_rpc_service(RpcService::UpdateAllUnprizeSpots, state).await
+pub(super) async fn update_all_unprize_spots(
+    State(state): State<RouterState>,
+    Query(operation): Query<OperationQuery>,
+) -> ApiResult {
+    let service = RpcService::UpdateAllUnprizeSpots;
+    handle_tracked_rpc_service(service, state, operation.operation).await
+}
+
+pub(super) async fn evaluate_period(
+    State(state): State<RouterState>,
+    Path(path): Path<PeriodPath>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::EvaluatePeriod(path.period), state).await
 }
 
 pub(super) async fn deprecate_last_batch_spots(State(state): State<RouterState>) -> ApiResult {
     handle_rpc_service(RpcService::DeprecatedLastBatchUnprizedSpot, state).await
 }
 
-pub(super) async fn generate_batch_spots(State(state): State<RouterState>) -> ApiResult {
-    handle_rpc_service(RpcService::GenerateBatchSpots, state).await
+pub(super) async fn deprecate_spot(
+    State(state): State<RouterState>,
+    Path(path): Path<SpotIdPath>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::DeprecateSpot(path.id), state).await
+}
+
+pub(super) async fn restore_spots(
+    State(state): State<RouterState>,
+    Json(payload): Json<RestoreRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::RestoreSpots(payload.ids, payload.reason), state).await
+}
+
+pub(super) async fn annotate_spot(
+    State(state): State<RouterState>,
+    Path(path): Path<SpotIdPath>,
+    Json(payload): Json<NoteRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::AnnotateSpot(path.id, payload.note), state).await
+}
+
+pub(super) async fn tag_spots(
+    State(state): State<RouterState>,
+    Json(payload): Json<TagRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::TagSpots(payload.ids, payload.tag), state).await
+}
+
+pub(super) async fn untag_spots(
+    State(state): State<RouterState>,
+    Json(payload): Json<TagRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::UntagSpots(payload.ids, payload.tag), state).await
+}
+
+pub(super) async fn get_tags(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetTags, state).await
+}
+
+pub(super) async fn get_spot_tags(
+    State(state): State<RouterState>,
+    Path(path): Path<SpotIdPath>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::GetSpotTags(path.id), state).await
+}
+
+pub(super) async fn get_duplicate_spots(State(state): State<RouterState>) -> ApiResult {
+    handle_rpc_service(RpcService::GetDuplicateSpots, state).await
+}
+
+pub(super) async fn mark_spot_purchased(
+    State(state): State<RouterState>,
+    Path(path): Path<SpotIdPath>,
+    Json(payload): Json<PurchasedRequest>,
+) -> ApiResult {
+    handle_rpc_service(
+        RpcService::MarkSpotPurchased(path.id, payload.purchased),
+        state,
+    )
+    .await
+}
+
+pub(super) async fn add_manual_spot(
+    State(state): State<RouterState>,
+    Json(manual): Json<ManualSpot>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::AddManualSpot(manual), state).await
+}
+
+pub(super) async fn record_purchase(
+    State(state): State<RouterState>,
+    Json(purchase): Json<NewPurchase>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::RecordPurchase(purchase), state).await
+}
+
+pub(super) async fn claim_prize(
+    State(state): State<RouterState>,
+    Json(claim): Json<NewPrizeClaim>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::ClaimPrize(claim), state).await
+}
+
+pub(super) async fn generate_spots_for_periods(
+    State(state): State<RouterState>,
+    Json(payload): Json<PlanRequest>,
+) -> ApiResult {
+    let service = RpcService::GenerateSpotsForPeriods(payload.periods, payload.strategy);
+    handle_rpc_service(service, state).await
+}
+
+pub(super) async fn simulate_strategy(
+    State(state): State<RouterState>,
+    Json(payload): Json<SimulationRequest>,
+) -> ApiResult {
+    let service = RpcService::SimulateStrategy(payload.year, payload.strategy);
+    handle_rpc_service(service, state).await
+}
+
+pub(super) async fn generate_batch_spots(
+    State(state): State<RouterState>,
+    Query(query): Query<StrategyQuery>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::GenerateBatchSpots(query.strategy), state).await
+}
+
+pub(super) async fn recommend(
+    State(state): State<RouterState>,
+    Json(payload): Json<RecommendRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::Recommend(payload), state).await
 }
 
 pub(super) async fn update_latest_ticket(State(state): State<RouterState>) -> ApiResult {
     handle_rpc_service(RpcService::UpdateLatestTicket, state).await
 }
 
-pub(super) async fn crawl_all_tickets(State(state): State<RouterState>) -> ApiResult {
-    handle_rpc_service(RpcService::CrawlAllTickets, state).await
+pub(super) async fn crawl_all_tickets(
+    State(state): State<RouterState>,
+    Query(query): Query<DryRunQuery>,
+    Query(operation): Query<OperationQuery>,
+) -> ApiResult {
+    let service = RpcService::CrawlAllTickets(query.dry_run);
+    handle_tracked_rpc_service(service, state, operation.operation).await
 }
 
 pub(super) async fn update_tickets_by_periods(
     State(state): State<RouterState>,
+    Query(operation): Query<OperationQuery>,
     Json(payload): Json<PeriodsRequest>,
 ) -> ApiResult {
-    handle_rpc_service(RpcService::UpdateTicketsByPeriod(payload.periods), state).await
+    let service = RpcService::UpdateTicketsByPeriod(payload.periods, payload.dry_run);
+    handle_tracked_rpc_service(service, state, operation.operation).await
 }
 
 pub(super) async fn update_tickets_with_year(
     State(state): State<RouterState>,
+    Query(operation): Query<OperationQuery>,
     Json(payload): Json<YearRequest>,
 ) -> ApiResult {
-    handle_rpc_service(RpcService::UpdateTicketsWithYear(payload.year), state).await
+    let service = RpcService::UpdateTicketsWithYear(payload.year, payload.dry_run);
+    handle_tracked_rpc_service(service, state, operation.operation).await
+}
+
+pub(super) async fn audit_tickets(
+    State(state): State<RouterState>,
+    Json(payload): Json<TicketAuditRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::AuditTickets(payload), state).await
+}
+
+pub(super) async fn sync_ticket_log(
+    State(state): State<RouterState>,
+    Json(payload): Json<ArchiveRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::SyncTicketLog(payload.source), state).await
+}
+
+pub(super) async fn import_tickets(
+    State(state): State<RouterState>,
+    Json(payload): Json<ArchiveRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::ImportTickets(payload.source), state).await
+}
+
+pub(super) async fn backup_database(
+    State(state): State<RouterState>,
+    Json(payload): Json<BackupRequest>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::BackupDatabase(payload.path), state).await
+}
+
+pub(super) async fn cancel_operation(
+    State(state): State<RouterState>,
+    Path(path): Path<OperationIdPath>,
+) -> ApiResult {
+    handle_rpc_service(RpcService::CancelOperation(path.id), state).await
 }
 
 pub(super) async fn handle_rpc(
@@ -6,19 +6,31 @@ use aide::axum::{
 };
 use aide::openapi::{Info, OpenApi};
 use aide::scalar::Scalar;
-use axum::{Extension, Json, Router, routing::get as axum_get};
+use axum::http::{HeaderValue, Method, header};
+use axum::{Extension, Json, Router, middleware, routing::get as axum_get};
 use tokio::sync::RwLock;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::services::{ServeDir, ServeFile};
 
 use crate::ipc::protocol::AppState;
 
+use super::HttpServerConfig;
+use super::auth::require_token;
 use super::handlers::{
-    crawl_all_tickets, deprecate_last_batch_spots, generate_batch_spots, get_latest_period,
-    get_prized_spots, get_state, get_unprized_spots, handle_rpc, health, update_all_unprize_spots,
-    update_latest_ticket, update_tickets_by_periods, update_tickets_with_year,
+    add_manual_spot, annotate_spot, audit_tickets, backup_database, cancel_operation, claim_prize,
+    crawl_all_tickets, deprecate_last_batch_spots, deprecate_spot, download_periodic_report,
+    evaluate_period, export_csv, find_spots, generate_batch_spots, generate_spots_for_periods,
+    get_config, get_duplicate_spots, get_latest_period, get_metrics, get_next_batch_summary,
+    get_number_frequencies, get_periodic_report, get_prized_spots, get_roi_analytics,
+    get_roi_report, get_spot_tags, get_state, get_tags, get_tickets, get_unprized_spots,
+    handle_rpc, health, import_tickets, mark_spot_purchased, prometheus_metrics, query_spots,
+    recommend, record_purchase, restore_spots, simulate_strategy, stream_prized_spots,
+    stream_query_spots, sync_ticket_log, tag_spots, untag_spots, update_all_unprize_spots,
+    update_config, update_latest_ticket, update_tickets_by_periods, update_tickets_with_year,
 };
 use super::types::RouterState;
 
-pub(super) fn build_router(app_state: Arc<RwLock<AppState>>) -> Router {
+pub(super) fn build_router(app_state: Arc<RwLock<AppState>>, config: &HttpServerConfig) -> Router {
     let mut api = OpenApi {
         info: Info {
             title: "DBall HTTP API".to_owned(),
@@ -36,13 +48,47 @@ pub(super) fn build_router(app_state: Arc<RwLock<AppState>>) -> Router {
                 .axum_route(),
         )
         .api_route("/health", get(health))
+        .route("/metrics", axum_get(prometheus_metrics))
+        .api_route("/api/metrics", get(get_metrics))
         .api_route("/api/state", get(get_state))
         .api_route("/api/period/latest", get(get_latest_period))
         .api_route("/api/spots/unprized", get(get_unprized_spots))
+        .api_route("/api/spots/unprized/summary", get(get_next_batch_summary))
         .api_route("/api/spots/prized", get(get_prized_spots))
+        .api_route("/api/spots/filter", get(find_spots))
+        .api_route("/api/spots/prized/stream", get(stream_prized_spots))
+        .api_route("/api/stats/frequencies", get(get_number_frequencies))
+        .api_route("/api/stats/roi", get(get_roi_analytics))
+        .api_route("/api/report/roi", get(get_roi_report))
+        .api_route("/api/report/summary", get(get_periodic_report))
+        .route(
+            "/api/report/summary/download",
+            axum_get(download_periodic_report),
+        )
+        .api_route("/api/spots/query", post(query_spots))
+        .api_route("/api/spots/query/stream", post(stream_query_spots))
+        .api_route("/api/tickets", get(get_tickets))
+        .api_route("/api/export", post(export_csv))
+        .api_route("/api/config", get(get_config).post(update_config))
         .api_route("/api/spots/update", post(update_all_unprize_spots))
         .api_route("/api/spots/deprecate", post(deprecate_last_batch_spots))
+        .api_route("/api/periods/:period/evaluate", post(evaluate_period))
+        .api_route("/api/spots/restore", post(restore_spots))
+        .api_route("/api/spots/:id/deprecate", post(deprecate_spot))
+        .api_route("/api/spots/:id/note", post(annotate_spot))
+        .api_route("/api/spots/:id/purchased", post(mark_spot_purchased))
+        .api_route("/api/spots/:id/tags", get(get_spot_tags))
+        .api_route("/api/spots/duplicates", get(get_duplicate_spots))
+        .api_route("/api/spots/tag", post(tag_spots))
+        .api_route("/api/spots/untag", post(untag_spots))
+        .api_route("/api/tags", get(get_tags))
+        .api_route("/api/spots/manual", post(add_manual_spot))
         .api_route("/api/spots/generate", post(generate_batch_spots))
+        .api_route("/api/spots/plan", post(generate_spots_for_periods))
+        .api_route("/api/spots/recommend", post(recommend))
+        .api_route("/api/stats/simulate", post(simulate_strategy))
+        .api_route("/api/purchases", post(record_purchase))
+        .api_route("/api/claims", post(claim_prize))
         .api_route("/api/tickets/update-latest", post(update_latest_ticket))
         .api_route("/api/tickets/crawl", post(crawl_all_tickets))
         .api_route(
@@ -50,13 +96,62 @@ pub(super) fn build_router(app_state: Arc<RwLock<AppState>>) -> Router {
             post(update_tickets_by_periods),
         )
         .api_route("/api/tickets/update/year", post(update_tickets_with_year))
+        .api_route("/api/tickets/audit", post(audit_tickets))
+        .api_route("/api/ticket-log/sync", post(sync_ticket_log))
+        .api_route("/api/tickets/import", post(import_tickets))
+        .api_route("/api/db/backup", post(backup_database))
+        .api_route("/api/operations/:id/cancel", post(cancel_operation))
         .api_route("/api/rpc", post(handle_rpc))
         .with_state(RouterState { app_state })
         .finish_api(&mut api);
 
     let api = Arc::new(api);
-    app.route("/api/docs/openapi.json", axum_get(serve_openapi))
-        .layer(Extension(api))
+    let mut app = app
+        .route("/api/docs/openapi.json", axum_get(serve_openapi))
+        .layer(Extension(api));
+
+    if let Some(web_dir) = &config.web_dir {
+        log::info!("Serving the web dashboard from {}", web_dir.display());
+        // unknown paths get the index so the dashboard can handle them
+        let index = ServeFile::new(web_dir.join("index.html"));
+        app = app.fallback_service(ServeDir::new(web_dir).fallback(index));
+    }
+    if let Some(token) = &config.auth_token {
+        log::info!("Requiring an auth token for the API");
+        app = app.layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token.as_str()),
+            require_token,
+        ));
+    }
+    // outermost, so preflight requests are answered without a token
+    if let Some(cors) = cors_layer(&config.cors_origins) {
+        app = app.layer(cors);
+    }
+    app
+}
+
+/// Let browsers on `origins` call the API, `None` keeps it same-origin only
+fn cors_layer(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|origin| {
+            origin
+                .parse::<HeaderValue>()
+                .inspect_err(|e| log::warn!("Ignoring invalid CORS origin {origin}: {e}"))
+                .ok()
+        }))
+    };
+    log::info!("Allowing cross-origin requests from {}", origins.join(", "));
+    Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]),
+    )
 }
 
 async fn serve_openapi(Extension(api): Extension<Arc<OpenApi>>) -> Json<OpenApi> {
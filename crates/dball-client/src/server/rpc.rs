@@ -3,16 +3,64 @@ use std::sync::Arc;
 use axum::http::StatusCode;
 use serde_json::Value;
 use tokio::sync::RwLock;
+use tracing::Instrument as _;
 
-use crate::ipc::protocol::{AppState, RpcService};
+use crate::ipc::{
+    error::{RpcError, RpcErrorCode},
+    protocol::{AppState, RpcService},
+};
 
 use super::types::{ApiResult, PeriodUpdateResult, RouterState, err_response, ok_value};
 
+/// Actor recorded for changes asked for over HTTP
+const ACTOR: &str = "http";
+
 pub(super) async fn handle_rpc_service(service: RpcService, state: RouterState) -> ApiResult {
-    match dispatch_rpc(service, state.app_state).await {
-        Ok(value) => ok_value(value),
-        Err(err) => err_response(err.status, err.code, err.message),
-    }
+    rpc_value(service, state)
+        .await
+        .map_or_else(|err| err, ok_value)
+}
+
+/// [`handle_rpc_service`] for a long operation, tracked under the `operation`
+/// id the caller picked so that `POST /api/operations/{id}/cancel` stops it
+pub(super) async fn handle_tracked_rpc_service(
+    service: RpcService,
+    state: RouterState,
+    operation: Option<String>,
+) -> ApiResult {
+    let Some(id) = operation else {
+        return handle_rpc_service(service, state).await;
+    };
+    // progress is logged as it is reported, HTTP has no way to stream it
+    crate::service::track_progress(id, |_| {}, handle_rpc_service(service, state))
+        .await
+        .unwrap_or_else(|e| err_response(StatusCode::CONFLICT, "operation_running", e.to_string()))
+}
+
+/// Run `service` for a handler answering with something else than JSON, a
+/// failure as its response
+pub(super) async fn rpc_value(service: RpcService, state: RouterState) -> Result<Value, ApiResult> {
+    // HTTP requests carry no uuid of their own, one is made up to tell their spans apart
+    let span = tracing::info_span!(
+        "rpc",
+        uuid = %uuid::Uuid::new_v4(),
+        service = <&'static str>::from(&service),
+        transport = "http"
+    );
+    dispatch_rpc(service, state.app_state)
+        .instrument(span)
+        .await
+        .map_err(|err| err_response(err.status, err.code, err.message))
+}
+
+/// Run `service` for a caller outside of HTTP, e.g. a chat command, a failure as its message
+pub(crate) async fn call_rpc_service(
+    service: RpcService,
+    state: Arc<RwLock<AppState>>,
+) -> Result<Value, String> {
+    dispatch_rpc(service, state)
+        .await
+        .map_err(|failure| failure.message)
 }
 
 struct ApiFailure {
@@ -45,8 +93,28 @@ impl ApiFailure {
             message: message.into(),
         }
     }
+
+    /// Failure of a service call, with the status and code of its [`RpcErrorCode`]
+    fn from_error(error: &anyhow::Error) -> Self {
+        let error = RpcError::from_anyhow(error);
+        let status = match error.code {
+            RpcErrorCode::ProviderUnavailable | RpcErrorCode::Transport => StatusCode::BAD_GATEWAY,
+            RpcErrorCode::Busy => StatusCode::SERVICE_UNAVAILABLE,
+            RpcErrorCode::NotFound => StatusCode::NOT_FOUND,
+            RpcErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            RpcErrorCode::Cancelled => StatusCode::CONFLICT,
+            RpcErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            RpcErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            code: error.code.as_str(),
+            message: error.message,
+        }
+    }
 }
 
+#[expect(clippy::too_many_lines)]
 async fn dispatch_rpc(
     service: RpcService,
     state: Arc<RwLock<AppState>>,
@@ -59,63 +127,244 @@ async fn dispatch_rpc(
         RpcService::UpdateLatestTicket => {
             let ticket = crate::service::update_latest_ticket()
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
             serde_json::to_value(ticket).map_err(|e| ApiFailure::internal(e.to_string()))
         }
         RpcService::GetLatestPeriod => {
             let period = crate::service::get_next_period()
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
             Ok(Value::String(period))
         }
         RpcService::UpdateAllUnprizeSpots => {
             let spots = crate::service::update_all_unprize_spots()
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
             serde_json::to_value(spots).map_err(|e| ApiFailure::internal(e.to_string()))
         }
+        RpcService::EvaluatePeriod(period) => {
+            let evaluation = crate::service::evaluate_period(&period)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            state.write().await.total_return += evaluation.winnings_change as f64;
+            serde_json::to_value(evaluation).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
         RpcService::DeprecatedLastBatchUnprizedSpot => {
-            let count = crate::service::deprecated_last_batch_unprized_spot()
+            let count = crate::service::deprecated_last_batch_unprized_spot(ACTOR)
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
             serde_json::to_value(count).map_err(|e| ApiFailure::internal(e.to_string()))
         }
+        RpcService::DeprecateSpot(id) => {
+            crate::service::deprecate_spot(id, ACTOR)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::Null)
+        }
+        RpcService::RestoreSpots(ids, reason) => {
+            let restored = crate::service::restore_spots(ids, ACTOR, reason)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(restored).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::AnnotateSpot(id, note) => {
+            crate::service::annotate_spot(id, note)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::Null)
+        }
+        RpcService::TagSpots(ids, tag) => {
+            let tagged = crate::service::tag_spots(ids, tag)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(tagged).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::UntagSpots(ids, tag) => {
+            let untagged = crate::service::untag_spots(ids, tag)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(untagged).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::MarkSpotPurchased(id, purchased) => {
+            crate::service::mark_spot_purchased(id, purchased)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::Null)
+        }
+        RpcService::AddManualSpot(manual) => {
+            let period = crate::service::add_manual_spot(manual)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::String(period))
+        }
+        RpcService::RecordPurchase(purchase) => {
+            if purchase.spot_ids.is_empty() {
+                return Err(ApiFailure::bad_request("spot_ids must not be empty"));
+            }
+            let id = crate::service::record_purchase(purchase)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::from(id))
+        }
+        RpcService::ClaimPrize(claim) => {
+            let id = crate::service::claim_prize(claim)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::from(id))
+        }
         RpcService::GetUnprizeSpots => {
             let spots = crate::service::get_next_period_unprized_spots()
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(spots).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetNextBatchSummary => {
+            let summary = crate::service::next_batch_summary()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(summary).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetPrizedSpots(page) => {
+            let spots = crate::service::get_prized_spots(page)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
             serde_json::to_value(spots).map_err(|e| ApiFailure::internal(e.to_string()))
         }
-        RpcService::GetPrizedSpots => {
-            let spots = crate::service::get_prized_spots()
+        RpcService::FindSpots(filter, page) => {
+            filter
+                .validate()
+                .map_err(|e| ApiFailure::bad_request(e.to_string()))?;
+            let spots = crate::service::find_spots(filter, page)
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
             serde_json::to_value(spots).map_err(|e| ApiFailure::internal(e.to_string()))
         }
-        RpcService::GenerateBatchSpots => {
-            crate::service::generate_batch_spots()
+        RpcService::GetNumberFrequencies(window) => {
+            if window == Some(0) {
+                return Err(ApiFailure::bad_request("window must be positive"));
+            }
+            let stats = crate::service::number_frequencies(window)
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
-            Ok(Value::Null)
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(stats).map_err(|e| ApiFailure::internal(e.to_string()))
         }
-        RpcService::CrawlAllTickets => {
-            crate::service::crawl_all_tickets()
+        RpcService::GetRoiAnalytics(window) => {
+            if window == 0 {
+                return Err(ApiFailure::bad_request("window must be positive"));
+            }
+            let analytics = crate::service::roi_analytics(window)
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(analytics).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetTags => {
+            let tags = crate::service::list_tags()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(tags).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetSpotTags(id) => {
+            let tags = crate::service::spot_tags(id)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(tags).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetDuplicateSpots => {
+            let duplicates = crate::service::duplicate_spots()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(duplicates).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetRoiReport => {
+            let report = crate::service::roi_report()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetPeriodicReport(year, month) => {
+            let report = crate::service::periodic_report(year, month)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::QuerySpots(query) => {
+            let spots = crate::service::query_spots(query)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(spots).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::StreamSpots(_) => Err(ApiFailure::not_supported(
+            "streamed spots are served by POST /api/spots/query/stream",
+        )),
+        RpcService::GetTickets(period_prefix, page) => {
+            let tickets = crate::service::get_tickets(period_prefix, page)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(tickets).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::ExportCsv(request) => {
+            let csv = crate::service::export_csv(request)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::String(csv))
+        }
+        RpcService::GetConfig => {
+            let config = crate::service::get_config()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(config).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::UpdateConfig(config) => {
+            let issues = crate::service::update_config(*config)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(issues).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GenerateBatchSpots(strategy) => {
+            crate::service::generate_batch_spots(strategy.as_deref())
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
             Ok(Value::Null)
         }
-        RpcService::UpdateTicketsByPeriod(periods) => {
+        RpcService::GenerateSpotsForPeriods(periods, strategy) => {
+            let planned = crate::service::generate_spots_for_periods(periods, strategy.as_deref())
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(planned).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::SimulateStrategy(year, strategy) => {
+            let simulation = crate::service::simulate_strategy(year, strategy.as_deref())
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(simulation).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::Recommend(request) => {
+            let recommendations = crate::service::recommend(request)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(recommendations).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::CrawlAllTickets(dry_run) => {
+            let report = crate::service::crawl_all_tickets(dry_run)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::UpdateTicketsByPeriod(periods, dry_run) => {
             if periods.is_empty() {
                 return Err(ApiFailure::bad_request("periods must not be empty"));
             }
             let mut results = Vec::with_capacity(periods.len());
             for period in periods {
-                match crate::service::update_tickets_by_period(&period).await {
+                match crate::service::update_tickets_by_period(&period, dry_run).await {
                     Ok(inserted) => results.push(PeriodUpdateResult {
                         period,
                         inserted: Some(inserted),
                         error: None,
                     }),
+                    Err(e) if e.is::<crate::service::OperationCancelled>() => {
+                        return Err(ApiFailure::from_error(&e));
+                    }
                     Err(e) => results.push(PeriodUpdateResult {
                         period,
                         inserted: None,
@@ -125,14 +374,58 @@ async fn dispatch_rpc(
             }
             serde_json::to_value(results).map_err(|e| ApiFailure::internal(e.to_string()))
         }
-        RpcService::UpdateTicketsWithYear(year) => {
+        RpcService::UpdateTicketsWithYear(year, dry_run) => {
             if year <= 0 {
                 return Err(ApiFailure::bad_request("year must be positive"));
             }
-            crate::service::update_tickets_with_year(year as usize)
+            let report = crate::service::update_tickets_with_year(year as usize, dry_run)
                 .await
-                .map_err(|e| ApiFailure::internal(e.to_string()))?;
-            Ok(Value::Null)
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::AuditTickets(request) => {
+            let audit = crate::service::audit_tickets(request)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(audit).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::SyncTicketLog(source) => {
+            if source.trim().is_empty() {
+                return Err(ApiFailure::bad_request("source must not be empty"));
+            }
+            let report = crate::service::sync_from_archive(source.trim())
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::ImportTickets(source) => {
+            if source.trim().is_empty() {
+                return Err(ApiFailure::bad_request("source must not be empty"));
+            }
+            let report = crate::service::import_tickets_from_archive(source.trim())
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(report).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::BackupDatabase(path) => {
+            let path = crate::service::backup_database(path)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            Ok(Value::String(path))
+        }
+        RpcService::GetDbHealth => {
+            let health = crate::service::database_health()
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(health).map_err(|e| ApiFailure::internal(e.to_string()))
+        }
+        RpcService::GetMetrics => serde_json::to_value(crate::metrics::snapshot())
+            .map_err(|e| ApiFailure::internal(e.to_string())),
+        RpcService::CancelOperation(id) => {
+            let cancelled = crate::service::cancel_operation(id)
+                .await
+                .map_err(|e| ApiFailure::from_error(&e))?;
+            serde_json::to_value(cancelled).map_err(|e| ApiFailure::internal(e.to_string()))
         }
         RpcService::Shutdown | RpcService::Restart => Err(ApiFailure::not_supported(
             "operation is not supported via HTTP",
@@ -0,0 +1,83 @@
+use aide::OperationOutput;
+use aide::r#gen::GenContext;
+use aide::openapi::{Operation, Response as ApiResponseDoc};
+use axum::body::Body;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use tokio::sync::mpsc;
+
+use crate::models::SpotQuery;
+use crate::service::SpotPages;
+
+use super::types::{ApiResult, err_response};
+
+/// Content type of [`NdjsonSpots`]
+const NDJSON: &str = "application/x-ndjson";
+
+/// Pages in flight between the database reader and the client
+const PAGES_IN_FLIGHT: usize = 4;
+
+/// Spots sent as newline-delimited JSON, one spot per line, read page by page
+/// while the client receives them
+pub(super) struct NdjsonSpots(SpotPages);
+
+/// Spots matching `query` as an NDJSON body, a query the service rejects is a bad request
+pub(super) fn ndjson_spots(query: SpotQuery) -> Result<NdjsonSpots, ApiResult> {
+    crate::service::spot_pages(query)
+        .map(NdjsonSpots)
+        .map_err(|e| err_response(StatusCode::BAD_REQUEST, "bad_request", e.to_string()))
+}
+
+impl IntoResponse for NdjsonSpots {
+    fn into_response(self) -> Response {
+        let (sender, receiver) = mpsc::channel::<anyhow::Result<Vec<u8>>>(PAGES_IN_FLIGHT);
+        // the database is blocking, the pages are read on their own thread
+        tokio::task::spawn_blocking(move || {
+            for page in self.0 {
+                let lines = page.and_then(|spots| {
+                    let mut lines = Vec::new();
+                    for spot in spots {
+                        serde_json::to_writer(&mut lines, &spot)?;
+                        lines.push(b'\n');
+                    }
+                    Ok(lines)
+                });
+                if let Err(e) = &lines {
+                    log::error!("Failed to stream spots: {e}");
+                }
+                let failed = lines.is_err();
+                if sender.blocking_send(lines).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        let body = futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|lines| (lines, receiver))
+        });
+        ([(header::CONTENT_TYPE, NDJSON)], Body::from_stream(body)).into_response()
+    }
+}
+
+impl OperationOutput for NdjsonSpots {
+    type Inner = ();
+
+    fn operation_response(
+        _ctx: &mut GenContext,
+        _operation: &mut Operation,
+    ) -> Option<ApiResponseDoc> {
+        Some(ApiResponseDoc {
+            description: format!("Spots as {NDJSON}, one JSON spot per line"),
+            ..Default::default()
+        })
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, ApiResponseDoc)> {
+        Self::operation_response(ctx, operation)
+            .map(|response| vec![(Some(200), response)])
+            .unwrap_or_default()
+    }
+}
@@ -60,11 +60,137 @@ pub(super) fn err_response(
 #[derive(Deserialize, JsonSchema)]
 pub(super) struct PeriodsRequest {
     pub(super) periods: Vec<String>,
+    /// Report whether the tickets would be inserted without writing them
+    #[serde(default)]
+    pub(super) dry_run: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PlanRequest {
+    /// Next draws to generate a batch for
+    pub(super) periods: usize,
+    /// Generator to use instead of the configured one, e.g. `Markov`
+    pub(super) strategy: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct SimulationRequest {
+    pub(super) year: i32,
+    /// Generator to replay instead of the configured one, e.g. `Markov`
+    pub(super) strategy: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub(super) struct YearRequest {
     pub(super) year: i32,
+    /// Report what the crawl would insert without writing it
+    #[serde(default)]
+    pub(super) dry_run: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct DryRunQuery {
+    /// Report what the crawl would insert without writing it
+    #[serde(default)]
+    pub(super) dry_run: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct ArchiveRequest {
+    /// Path on the daemon's machine or `http(s)` URL of the results archive
+    pub(super) source: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct BackupRequest {
+    /// Path on the daemon's machine, next to the database when left out
+    pub(super) path: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct RestoreRequest {
+    /// Spots to restore, those of the latest deprecation when empty
+    #[serde(default)]
+    pub(super) ids: Vec<i32>,
+    pub(super) reason: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PeriodPath {
+    pub(super) period: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct SpotIdPath {
+    pub(super) id: i32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct OperationIdPath {
+    pub(super) id: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct OperationQuery {
+    /// Id picked by the caller to cancel the operation by with
+    /// `POST /api/operations/{id}/cancel`, the request fails with 409 while
+    /// another operation runs under it
+    pub(super) operation: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct NoteRequest {
+    pub(super) note: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct TagRequest {
+    pub(super) ids: Vec<i32>,
+    pub(super) tag: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PurchasedRequest {
+    pub(super) purchased: bool,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PeriodPrefixQuery {
+    /// Only periods starting with this prefix, e.g. `2025`
+    pub(super) period: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct StrategyQuery {
+    /// Generator to use instead of the configured one, e.g. `Markov`
+    pub(super) strategy: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct PeriodicReportQuery {
+    pub(super) year: i32,
+    /// Month from 1 to report on, the whole year without
+    pub(super) month: Option<u32>,
+}
+
+/// Document a report is downloaded as
+#[derive(Deserialize, JsonSchema, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ReportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct ReportFormatQuery {
+    #[serde(default)]
+    pub(super) format: ReportFormat,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct WindowQuery {
+    pub(super) window: Option<usize>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -1,15 +1,118 @@
+mod analysis;
+mod analytics;
+#[cfg(not(target_arch = "wasm32"))]
+mod audit;
+mod backtest;
+mod budget;
+mod calendar;
+#[cfg(not(target_arch = "wasm32"))]
+mod claim;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod events;
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
+#[cfg(not(target_arch = "wasm32"))]
+mod maintenance;
+#[cfg(not(target_arch = "wasm32"))]
+mod notify;
+mod plan;
+#[cfg(not(target_arch = "wasm32"))]
+mod profile;
+#[cfg(not(target_arch = "wasm32"))]
+mod progress;
+#[cfg(not(target_arch = "wasm32"))]
+mod purchase;
+mod recommend;
+mod report;
+#[cfg(not(target_arch = "wasm32"))]
+mod scheduler;
+#[cfg(not(target_arch = "wasm32"))]
 mod spot;
+mod summary;
+#[cfg(not(target_arch = "wasm32"))]
+mod tag;
+#[cfg(not(target_arch = "wasm32"))]
 mod ticket;
+mod ticket_log;
 
+pub use analysis::{NumberFrequencies, NumberStat};
+pub use analytics::{BreakEvenProjection, RoiAnalytics, RoiPoint};
+pub use backtest::{BacktestResult, BacktestRun, SimulatedWeek, StrategySimulation, replay};
+pub use budget::{BudgetExceeded, BudgetStatus};
+pub use calendar::{
+    PeriodDraw, missing_periods, next_period, period_name, upcoming_periods, year_calendar,
+};
+pub use plan::{MAX_PLANNED_PERIODS, PLAN_TAG_PREFIX, PlannedBatch, plan_tag};
+pub use recommend::{Recommendation, history_score};
+pub use report::{PeriodEvaluation, PeriodRoi, RoiReport};
+pub use summary::{PRIZE_TIERS, PeriodNet, PeriodicReport};
+pub use ticket_log::{TicketImportReport, TicketLogConflict, TicketLogSyncReport};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use analysis::number_frequencies;
+#[cfg(not(target_arch = "wasm32"))]
+pub use analytics::roi_analytics;
+#[cfg(not(target_arch = "wasm32"))]
+pub use audit::audit_tickets;
+#[cfg(not(target_arch = "wasm32"))]
+pub use backtest::{backtest, simulate_strategy};
+#[cfg(not(target_arch = "wasm32"))]
+pub use budget::budget_status;
+#[cfg(not(target_arch = "wasm32"))]
+pub use calendar::period_calendar;
+#[cfg(not(target_arch = "wasm32"))]
+pub use claim::{claim_prize, winnings};
+#[cfg(not(target_arch = "wasm32"))]
+pub use config::{get_config, update_config};
+#[cfg(not(target_arch = "wasm32"))]
+pub use events::{DaemonEvent, subscribe_events};
+#[cfg(not(target_arch = "wasm32"))]
+pub use export::export_csv;
+#[cfg(not(target_arch = "wasm32"))]
+pub use maintenance::{backup_database, database_health, maintain_database};
+#[cfg(not(target_arch = "wasm32"))]
+pub use notify::{
+    BetPrize, PrizeNotification, PrizeWin, SettledDraw, TelegramBot, TelegramMessage,
+    notification_deliveries, send_draw_email, send_draw_telegram, send_prize_webhooks,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use plan::generate_spots_for_periods;
+#[cfg(not(target_arch = "wasm32"))]
+pub use profile::{list_profiles, profile_id};
+#[cfg(not(target_arch = "wasm32"))]
+pub use progress::{
+    OperationCancelled, OperationProgress, OperationRunning, cancel_operation, track_progress,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use purchase::{investment, record_purchase};
+#[cfg(not(target_arch = "wasm32"))]
+pub use recommend::recommend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use report::roi_report;
+#[cfg(not(target_arch = "wasm32"))]
+pub use scheduler::{PipelineStep, pipeline_runs, run_post_draw_pipeline};
+#[cfg(not(target_arch = "wasm32"))]
 pub use spot::{
-    deprecated_last_batch_unprized_spot, generate_batch_spots, get_next_period_unprized_spots,
-    get_prized_spots, insert_new_spots_batch_to_next_period, next_draw_time,
-    update_all_unprize_spots,
+    DUPLICATE_TAG, SPOT_PAGE_SIZE, SpotPages, add_complex_spot, add_dantuo_spot, add_manual_spot,
+    annotate_spot, deprecate_spot, deprecated_last_batch_unprized_spot, duplicate_spots,
+    evaluate_period, find_spots, generate_batch_spots, generate_spots,
+    get_next_period_unprized_spots, get_prized_spots, insert_new_spots_batch_to_next_period,
+    mark_spot_purchased, next_batch_summary, next_draw_time, query_spots, restore_spots,
+    spot_deprecation_history, spot_pages, spots_summary, update_all_unprize_spots,
 };
+#[cfg(not(target_arch = "wasm32"))]
+pub use summary::periodic_report;
+#[cfg(not(target_arch = "wasm32"))]
+pub use tag::{list_tags, spot_tags, tag_spots, untag_spots};
+#[cfg(not(target_arch = "wasm32"))]
 pub use ticket::{
-    check_ticket_in_log_db, crawl_all_tickets, get_next_period, update_latest_ticket,
-    update_tickets_by_period, update_tickets_with_year,
+    PeriodNotDrawn, check_ticket_in_log_db, crawl_all_tickets, get_next_period, get_tickets,
+    is_transient, update_latest_ticket, update_tickets_by_period, update_tickets_with_year,
 };
+#[cfg(not(target_arch = "wasm32"))]
+pub use ticket_log::{import_tickets_from_archive, sync_from_archive};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,206 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::db::tickets;
+use crate::models::Ticket;
+use serde::{Deserialize, Serialize};
+
+const RED_BALL_COUNT: usize = 33;
+const BLUE_BALL_COUNT: usize = 16;
+
+/// Frequency and omission of a single number over a lookback window
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NumberStat {
+    pub number: u8,
+    /// How many draws in the window contained this number
+    pub frequency: usize,
+    /// How many draws have passed since the number last appeared,
+    /// equals `draws` if it never appeared in the window
+    pub omission: usize,
+    /// Place among the numbers of its color from 1 for the hottest, the most
+    /// frequent and, on a tie, the most recently drawn
+    #[serde(default)]
+    pub rank: usize,
+}
+
+/// Red/blue number statistics over the latest `window` draws
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NumberFrequencies {
+    pub window: usize,
+    /// Number of draws actually found, may be less than `window`
+    pub draws: usize,
+    pub latest_period: Option<String>,
+    pub red: Vec<NumberStat>,
+    pub blue: Vec<NumberStat>,
+}
+
+impl NumberFrequencies {
+    /// Build statistics from tickets ordered from newest to oldest
+    pub fn from_tickets(window: usize, tickets: &[Ticket]) -> Self {
+        let draws = tickets.len();
+        let mut red = new_stats(RED_BALL_COUNT, draws);
+        let mut blue = new_stats(BLUE_BALL_COUNT, draws);
+
+        for (age, ticket) in tickets.iter().enumerate() {
+            for number in ticket.red_numbers() {
+                record(&mut red, number, age);
+            }
+            record(&mut blue, ticket.blue_number(), age);
+        }
+        rank(&mut red);
+        rank(&mut blue);
+
+        Self {
+            window,
+            draws,
+            latest_period: tickets.first().map(|t| t.period.clone()),
+            red,
+            blue,
+        }
+    }
+
+    /// Highest red frequency, used to normalize color scales
+    pub fn max_red_frequency(&self) -> usize {
+        self.red.iter().map(|s| s.frequency).max().unwrap_or(0)
+    }
+
+    /// Highest blue frequency, used to normalize color scales
+    pub fn max_blue_frequency(&self) -> usize {
+        self.blue.iter().map(|s| s.frequency).max().unwrap_or(0)
+    }
+
+    /// The `count` hottest red numbers, hottest first
+    pub fn hot_reds(&self, count: usize) -> Vec<u8> {
+        by_rank(&self.red).take(count).collect()
+    }
+
+    /// The `count` coldest red numbers, coldest first
+    pub fn cold_reds(&self, count: usize) -> Vec<u8> {
+        by_rank(&self.red).rev().take(count).collect()
+    }
+
+    /// The `count` hottest blue numbers, hottest first
+    pub fn hot_blues(&self, count: usize) -> Vec<u8> {
+        by_rank(&self.blue).take(count).collect()
+    }
+
+    /// The `count` coldest blue numbers, coldest first
+    pub fn cold_blues(&self, count: usize) -> Vec<u8> {
+        by_rank(&self.blue).rev().take(count).collect()
+    }
+}
+
+fn new_stats(count: usize, draws: usize) -> Vec<NumberStat> {
+    (1..=count as u8)
+        .map(|number| NumberStat {
+            number,
+            frequency: 0,
+            omission: draws,
+            rank: 0,
+        })
+        .collect()
+}
+
+fn record(stats: &mut [NumberStat], number: i32, age: usize) {
+    let Some(stat) = usize::try_from(number)
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|idx| stats.get_mut(idx))
+    else {
+        log::warn!("Ignoring out of range number {number} in frequency statistics");
+        return;
+    };
+
+    stat.frequency += 1;
+    stat.omission = stat.omission.min(age);
+}
+
+fn rank(stats: &mut [NumberStat]) {
+    let mut order = (0..stats.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| {
+        let stat = &stats[idx];
+        (
+            std::cmp::Reverse(stat.frequency),
+            stat.omission,
+            stat.number,
+        )
+    });
+    for (rank, idx) in order.into_iter().enumerate() {
+        stats[idx].rank = rank + 1;
+    }
+}
+
+/// Numbers of `stats` from the hottest to the coldest
+fn by_rank(stats: &[NumberStat]) -> impl DoubleEndedIterator<Item = u8> {
+    let mut order = stats.to_vec();
+    order.sort_by_key(|stat| stat.rank);
+    order.into_iter().map(|stat| stat.number)
+}
+
+/// Compute red/blue number frequency, omission and rank over the latest
+/// `window` draws, or the configured `generator.frequency_window` without one
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn number_frequencies(window: Option<usize>) -> anyhow::Result<NumberFrequencies> {
+    let window = match window {
+        Some(window) => window,
+        None => super::get_config().await?.generator.frequency_window,
+    };
+    if window == 0 {
+        anyhow::bail!("Lookback window must be greater than 0");
+    }
+
+    let limit = i64::try_from(window)?;
+    let tickets = tickets::get_latest_tickets(limit)?;
+    log::debug!(
+        "Computing number frequencies over {} of {window} requested draws",
+        tickets.len()
+    );
+
+    Ok(NumberFrequencies::from_tickets(window, &tickets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::ticket;
+
+    #[test]
+    fn test_frequencies_from_tickets() {
+        let tickets = vec![
+            ticket("2025003", "2025-01-01 21:15:00", [1, 2, 3, 4, 5, 6], 1),
+            ticket("2025002", "2025-01-01 21:15:00", [1, 7, 8, 9, 10, 11], 2),
+            ticket("2025001", "2025-01-01 21:15:00", [1, 2, 12, 13, 14, 15], 1),
+        ];
+
+        let stats = NumberFrequencies::from_tickets(10, &tickets);
+        assert_eq!(stats.draws, 3);
+        assert_eq!(stats.latest_period.as_deref(), Some("2025003"));
+        assert_eq!(stats.red.len(), 33);
+        assert_eq!(stats.blue.len(), 16);
+
+        assert_eq!(stats.red[0].frequency, 3);
+        assert_eq!(stats.red[0].omission, 0);
+        assert_eq!(stats.red[6].frequency, 1);
+        assert_eq!(stats.red[6].omission, 1);
+        assert_eq!(stats.red[32].frequency, 0);
+        assert_eq!(stats.red[32].omission, 3);
+
+        assert_eq!(stats.blue[0].frequency, 2);
+        assert_eq!(stats.blue[1].omission, 1);
+        assert_eq!(stats.max_red_frequency(), 3);
+        assert_eq!(stats.max_blue_frequency(), 2);
+
+        assert_eq!(stats.red[0].rank, 1);
+        assert_eq!(stats.red[1].rank, 2);
+        // drawn once like 3 to 6, but a draw earlier
+        assert_eq!(stats.red[6].rank, 7);
+        assert_eq!(stats.red[32].rank, 33);
+        assert_eq!(stats.hot_reds(3), [1, 2, 3]);
+        assert_eq!(stats.cold_reds(2), [33, 32]);
+        assert_eq!(stats.hot_blues(2), [1, 2]);
+        assert_eq!(stats.cold_blues(1), [16]);
+    }
+
+    #[tokio::test]
+    async fn test_number_frequencies_rejects_empty_window() {
+        assert!(number_frequencies(Some(0)).await.is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use super::report::{RoiReport, spot_winnings};
+
+/// Cost and winnings of one period along with the totals up to it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoiPoint {
+    pub period: String,
+    pub cost: u64,
+    pub winnings: u64,
+    pub cumulative_cost: u64,
+    pub cumulative_winnings: u64,
+    /// Yuan paid out for claimed prizes up to this period, the realized part
+    /// of the cumulative winnings
+    #[serde(default)]
+    pub cumulative_claimed: u64,
+    /// Share of the bought spots that won anything over the latest `window`
+    /// periods up to this one, from 0 to 1
+    pub win_rate: f64,
+}
+
+impl RoiPoint {
+    pub fn cumulative_net(&self) -> i64 {
+        self.cumulative_winnings as i64 - self.cumulative_cost as i64
+    }
+}
+
+/// Where the latest `window` periods lead if they keep their pace
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BreakEvenProjection {
+    /// Average cost per period over the window
+    pub average_cost: f64,
+    /// Average winnings per period over the window
+    pub average_winnings: f64,
+    /// Periods until the winnings catch up with the cost, 0 once they have,
+    /// `None` when the pace never gets there
+    pub periods_to_break_even: Option<u64>,
+    /// Winnings per period needed to break even within another window at the
+    /// average cost, 0 once broken even
+    pub required_winnings: f64,
+}
+
+/// Return on investment over time, see [`RoiAnalytics::from_report`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoiAnalytics {
+    /// Periods the win rate and the projection look back on
+    pub window: usize,
+    /// One point per period with settled spots, oldest period first
+    pub series: Vec<RoiPoint>,
+    pub projection: BreakEvenProjection,
+}
+
+impl RoiAnalytics {
+    /// Build the series from the periods of `report`, rolling over the latest `window` periods
+    pub fn from_report(report: &RoiReport, window: usize) -> Self {
+        let window = window.max(1);
+        // spots bought and spots that won of every period, oldest first
+        let mut counts = Vec::with_capacity(report.periods.len());
+        let mut series = Vec::with_capacity(report.periods.len());
+        let (mut cumulative_cost, mut cumulative_winnings, mut cumulative_claimed) = (0, 0, 0);
+
+        for period in report.periods.iter().rev() {
+            let bought = period.spots.iter().filter(|spot| !spot.deprecated);
+            let won = bought
+                .clone()
+                .filter(|spot| spot_winnings(spot) > 0)
+                .count();
+            counts.push((bought.count(), won));
+            let recent = &counts[counts.len().saturating_sub(window)..];
+            let (bought, won) = recent.iter().fold((0, 0), |(bought, won), count| {
+                (bought + count.0, won + count.1)
+            });
+
+            cumulative_cost += period.cost;
+            cumulative_winnings += period.winnings;
+            cumulative_claimed += period.claimed;
+            series.push(RoiPoint {
+                period: period.period.clone(),
+                cost: period.cost,
+                winnings: period.winnings,
+                cumulative_cost,
+                cumulative_winnings,
+                cumulative_claimed,
+                win_rate: if bought == 0 {
+                    0.0
+                } else {
+                    won as f64 / bought as f64
+                },
+            });
+        }
+
+        Self {
+            window,
+            projection: project(&series, window),
+            series,
+        }
+    }
+}
+
+fn project(series: &[RoiPoint], window: usize) -> BreakEvenProjection {
+    let Some(latest) = series.last() else {
+        return BreakEvenProjection::default();
+    };
+    let recent = &series[series.len().saturating_sub(window)..];
+    let average = |value: fn(&RoiPoint) -> u64| {
+        recent.iter().map(value).sum::<u64>() as f64 / recent.len() as f64
+    };
+    let average_cost = average(|point| point.cost);
+    let average_winnings = average(|point| point.winnings);
+
+    let deficit = -latest.cumulative_net() as f64;
+    let pace = average_winnings - average_cost;
+    let (periods_to_break_even, required_winnings) = if deficit <= 0.0 {
+        (Some(0), 0.0)
+    } else {
+        let periods = (pace > 0.0).then(|| (deficit / pace).ceil() as u64);
+        (periods, average_cost + deficit / window as f64)
+    };
+
+    BreakEvenProjection {
+        average_cost,
+        average_winnings,
+        periods_to_break_even,
+        required_winnings,
+    }
+}
+
+/// Build the ROI series of all settled spots, rolling over the latest `window` periods
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn roi_analytics(window: usize) -> anyhow::Result<RoiAnalytics> {
+    if window == 0 {
+        anyhow::bail!("Lookback window must be greater than 0");
+    }
+    let report = super::roi_report().await?;
+    Ok(RoiAnalytics::from_report(&report, window))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::spot;
+
+    #[test]
+    fn test_roi_series() {
+        let report = RoiReport::from_spots(vec![
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(5)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025003", [1, 2, 3, 4, 5, 6], 7, Some(10)),
+            spot("2025003", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+        ]);
+        let analytics = RoiAnalytics::from_report(&report, 2);
+
+        let periods = analytics
+            .series
+            .iter()
+            .map(|point| point.period.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(periods, ["2025001", "2025002", "2025003"]);
+        let latest = &analytics.series[2];
+        assert_eq!(latest.cumulative_cost, 12);
+        assert_eq!(latest.cumulative_winnings, 15);
+        assert_eq!(latest.cumulative_net(), 3);
+        assert_eq!(analytics.series[0].win_rate, 0.5);
+        assert_eq!(analytics.series[1].win_rate, 0.25);
+        assert_eq!(latest.win_rate, 0.25);
+        assert_eq!(analytics.projection.periods_to_break_even, Some(0));
+        assert_eq!(analytics.projection.required_winnings, 0.0);
+    }
+
+    #[test]
+    fn test_break_even_projection() {
+        // 2 yuan down after the first period, winning 1 more than spent in each one since
+        let report = RoiReport::from_spots(vec![
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(3)),
+            spot("2025003", [1, 2, 3, 4, 5, 6], 7, Some(3)),
+        ]);
+        let analytics = RoiAnalytics::from_report(&report, 2);
+        let projection = &analytics.projection;
+        assert_eq!(analytics.series[2].cumulative_net(), 0);
+        assert_eq!(projection.periods_to_break_even, Some(0));
+
+        let report = RoiReport::from_spots(vec![
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(3)),
+        ]);
+        let projection = RoiAnalytics::from_report(&report, 1).projection;
+        assert_eq!(projection.average_cost, 2.0);
+        assert_eq!(projection.average_winnings, 3.0);
+        assert_eq!(projection.periods_to_break_even, Some(3));
+        assert_eq!(projection.required_winnings, 5.0);
+
+        let report = RoiReport::from_spots(vec![spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0))]);
+        let projection = RoiAnalytics::from_report(&report, 2).projection;
+        assert_eq!(projection.periods_to_break_even, None);
+        assert_eq!(projection.required_winnings, 3.0);
+
+        let empty = RoiAnalytics::from_report(&RoiReport::from_spots(Vec::new()), 5);
+        assert!(empty.series.is_empty());
+        assert_eq!(empty.projection, BreakEvenProjection::default());
+    }
+}
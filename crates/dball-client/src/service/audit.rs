@@ -0,0 +1,113 @@
+//! Stored tickets checked against the ticket log and the provider
+
+use crate::db::{ticket_log, tickets};
+use crate::models::{AuditFailure, Ticket, TicketAudit, TicketAuditRequest, TicketDiscrepancy};
+
+use super::progress;
+use super::ticket::{PeriodNotDrawn, request_ticket};
+
+/// Fetch the stored periods of `request` again and report where the database,
+/// the ticket log and the provider disagree, nothing is written
+#[tracing::instrument]
+pub async fn audit_tickets(request: TicketAuditRequest) -> anyhow::Result<TicketAudit> {
+    let _timer = crate::metrics::time("audit_tickets");
+    let stored =
+        tickets::get_tickets_in_period_range(request.from.as_deref(), request.to.as_deref())?;
+    let stored = match request.sample {
+        Some(sample) => spread(stored, sample),
+        None => stored,
+    };
+
+    let mut audit = TicketAudit::default();
+    for (step, ticket) in stored.iter().enumerate() {
+        progress::ensure_not_cancelled()?;
+        let period = &ticket.period;
+        progress::report(
+            step,
+            Some(stored.len()),
+            format!("Auditing period {period}"),
+        );
+
+        let api = match request_ticket(period).await {
+            Ok((fetched, _)) => Some(fetched.to_dball()?),
+            Err(e) if e.is::<PeriodNotDrawn>() => None,
+            Err(e) => {
+                audit.failed.push(AuditFailure {
+                    period: period.clone(),
+                    error: format!("{e:#}"),
+                });
+                continue;
+            }
+        };
+        audit.checked.push(period.clone());
+
+        let database = ticket.to_dball()?;
+        let logged = ticket_log::get_record_by_code(period)?
+            .map(|log| log.to_dball())
+            .transpose()?;
+        if api != Some(database) || logged.is_some_and(|logged| logged != database) {
+            let discrepancy = TicketDiscrepancy {
+                period: period.clone(),
+                database: database.to_string(),
+                ticket_log: logged.map(|logged| logged.to_string()),
+                api: api.map(|api| api.to_string()),
+            };
+            log::warn!("Tickets disagree: {discrepancy:?}");
+            audit.discrepancies.push(discrepancy);
+        }
+    }
+    log::info!(
+        "Audited {} periods, {} disagree and {} failed",
+        audit.checked.len(),
+        audit.discrepancies.len(),
+        audit.failed.len()
+    );
+    Ok(audit)
+}
+
+/// `sample` of `tickets` spread evenly from the first one on
+fn spread(tickets: Vec<Ticket>, sample: usize) -> Vec<Ticket> {
+    let total = tickets.len();
+    if sample >= total {
+        return tickets;
+    }
+    tickets
+        .into_iter()
+        .enumerate()
+        // the first of every `total / sample` tickets
+        .filter(|(index, _)| index * sample % total < sample)
+        .map(|(_, ticket)| ticket)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_sample() -> anyhow::Result<()> {
+        let tickets = (1..=10)
+            .map(|number| {
+                Ticket::with_datetime(
+                    format!("2025{number:03}"),
+                    chrono::NaiveDateTime::default(),
+                    &[1, 2, 3, 4, 5, 6],
+                    1,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let periods = |tickets: Vec<Ticket>| {
+            tickets
+                .into_iter()
+                .map(|ticket| ticket.period)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            periods(spread(tickets.clone(), 3)),
+            ["2025001", "2025005", "2025008"]
+        );
+        assert_eq!(periods(spread(tickets.clone(), 0)), Vec::<String>::new());
+        assert_eq!(spread(tickets.clone(), 20).len(), 10);
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use chrono::{Datelike as _, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{PageRequest, Ticket};
+use dball_combora::backtest::{PastDraw, PeriodOutcome};
+
+pub use dball_combora::backtest::{BacktestResult, BacktestRun};
+
+/// Hypothetical spend and return of a strategy over the draws of one week
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedWeek {
+    /// Monday of the week
+    pub week_start: NaiveDate,
+    pub periods: usize,
+    pub cost: u64,
+    pub winnings: u64,
+    /// Net return from the start of the simulation to the end of the week
+    pub cumulative_net: i64,
+}
+
+/// What a strategy would have earned buying its batch every draw of a year
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StrategySimulation {
+    pub year: i32,
+    pub result: BacktestResult,
+    /// Weeks with a draw, oldest first
+    pub weeks: Vec<SimulatedWeek>,
+}
+
+/// Draw days and numbers of the drawn tickets, skipping those that fail to
+/// convert to numbers with a warning
+fn past_draws(tickets: &[Ticket]) -> Vec<(NaiveDate, PastDraw)> {
+    tickets
+        .iter()
+        .filter_map(|ticket| match ticket.to_dball() {
+            Ok(winning) => Some((
+                ticket.time.date(),
+                PastDraw {
+                    period: ticket.period.parse().unwrap_or_default(),
+                    winning,
+                },
+            )),
+            Err(e) => {
+                log::warn!("Skipping period {} in backtest: {e}", ticket.period);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Replay `run` over the drawn tickets, oldest first, see [`dball_combora::backtest::replay`]
+///
+/// Tickets that fail to convert to numbers are skipped with a warning
+pub fn replay(run: &BacktestRun, tickets: &[Ticket]) -> BacktestResult {
+    let draws = past_draws(tickets)
+        .into_iter()
+        .map(|(_, draw)| draw)
+        .collect::<Vec<_>>();
+    dball_combora::backtest::replay(run, &draws)
+}
+
+/// Outcomes of the draws on their days summed up by week, Monday to Sunday
+fn weekly(outcomes: impl IntoIterator<Item = (NaiveDate, PeriodOutcome)>) -> Vec<SimulatedWeek> {
+    let mut weeks = Vec::<SimulatedWeek>::new();
+    let mut net = 0;
+    for (date, outcome) in outcomes {
+        let week_start = date - TimeDelta::days(date.weekday().num_days_from_monday().into());
+        net += outcome.winnings as i64 - outcome.cost as i64;
+        match weeks.last_mut() {
+            Some(week) if week.week_start == week_start => {
+                week.periods += 1;
+                week.cost += outcome.cost;
+                week.winnings += outcome.winnings;
+                week.cumulative_net = net;
+            }
+            _ => weeks.push(SimulatedWeek {
+                week_start,
+                periods: 1,
+                cost: outcome.cost,
+                winnings: outcome.winnings,
+                cumulative_net: net,
+            }),
+        }
+    }
+    weeks
+}
+
+/// Replay every run over the drawn tickets from period `from` to `to`, both included
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn backtest(
+    runs: &[BacktestRun],
+    from: &str,
+    to: &str,
+) -> anyhow::Result<Vec<BacktestResult>> {
+    let mut tickets = super::ticket::get_tickets(None, PageRequest::default())
+        .await?
+        .items
+        .into_iter()
+        .filter(|ticket| (from..=to).contains(&ticket.period.as_str()))
+        .collect::<Vec<_>>();
+    if tickets.is_empty() {
+        anyhow::bail!("No drawn tickets between {from} and {to}");
+    }
+    tickets.sort_by(|a, b| a.period.cmp(&b.period));
+    log::info!(
+        "Backtesting {} runs over {} periods from {from} to {to}",
+        runs.len(),
+        tickets.len()
+    );
+    Ok(runs.iter().map(|run| replay(run, &tickets)).collect())
+}
+
+/// Replay the generator named `strategy`, or the configured one without, over
+/// the stored draws of `year` week by week, buying a batch of the configured
+/// size every draw
+///
+/// The run is seeded with the year, so simulating a year again gives the same
+/// result. Generators using history only learn from the draws of the year
+/// before each period.
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument]
+pub async fn simulate_strategy(
+    year: i32,
+    strategy: Option<&str>,
+) -> anyhow::Result<StrategySimulation> {
+    use dball_combora::dball::COST_PER_TICKET;
+
+    let _timer = crate::metrics::time("simulate_strategy");
+    let config = super::get_config().await?.generator;
+    let generator = config.generator(strategy)?;
+    let mut tickets = super::ticket::get_tickets(Some(year.to_string()), PageRequest::default())
+        .await?
+        .items;
+    if tickets.is_empty() {
+        anyhow::bail!("No drawn tickets of {year} to simulate");
+    }
+    tickets.sort_by(|a, b| a.period.cmp(&b.period));
+
+    let run = BacktestRun {
+        generator,
+        seed: u64::try_from(year).unwrap_or_default(),
+        budget: Some(u32::try_from(config.batch_size * COST_PER_TICKET).unwrap_or(u32::MAX)),
+    };
+    super::progress::report(
+        0,
+        None,
+        format!(
+            "Replaying {} over {} periods of {year}",
+            generator.name(),
+            tickets.len()
+        ),
+    );
+    let (dates, draws): (Vec<_>, Vec<_>) = past_draws(&tickets).into_iter().unzip();
+    let outcomes =
+        tokio::task::spawn_blocking(move || dball_combora::backtest::replay_periods(&run, &draws))
+            .await?;
+    let result = BacktestResult::from_outcomes(&run, &outcomes);
+    log::info!(
+        "{} would have spent {} and won {} yuan in {year}",
+        result.generator,
+        result.cost,
+        result.winnings
+    );
+    Ok(StrategySimulation {
+        year,
+        result,
+        weeks: weekly(dates.into_iter().zip(outcomes)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::ticket;
+    use dball_combora::generator::Generator;
+
+    #[test]
+    fn test_replay_tickets() {
+        let tickets = vec![
+            ticket("2025001", "2025-01-01 21:15:00", [1, 5, 9, 14, 22, 30], 3),
+            ticket("2025002", "2025-01-01 21:15:00", [2, 8, 11, 19, 27, 33], 12),
+            ticket("2025003", "2025-01-01 21:15:00", [4, 6, 13, 17, 25, 31], 7),
+        ];
+        let run = BacktestRun {
+            generator: Generator::BlueMorn,
+            seed: 42,
+            budget: None,
+        };
+        let result = replay(&run, &tickets);
+        assert_eq!(result.periods, 3);
+        assert_eq!(result, replay(&run, &tickets));
+    }
+
+    #[test]
+    fn test_weekly_outcomes() {
+        let outcome = |period, cost, winnings| PeriodOutcome {
+            period,
+            spots: 1,
+            cost,
+            winnings,
+            prizes: [0; 6],
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).expect("Valid test date");
+        // Tuesday, Thursday and Sunday of the first week, Tuesday of the next
+        let weeks = weekly([
+            (date(2), outcome(2024001, 10, 0)),
+            (date(4), outcome(2024002, 10, 5)),
+            (date(7), outcome(2024003, 10, 0)),
+            (date(9), outcome(2024004, 10, 200)),
+        ]);
+        assert_eq!(
+            weeks,
+            [
+                SimulatedWeek {
+                    week_start: date(1),
+                    periods: 3,
+                    cost: 30,
+                    winnings: 5,
+                    cumulative_net: -25,
+                },
+                SimulatedWeek {
+                    week_start: date(8),
+                    periods: 1,
+                    cost: 10,
+                    winnings: 200,
+                    cumulative_net: 165,
+                },
+            ]
+        );
+    }
+}
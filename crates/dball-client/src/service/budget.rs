@@ -0,0 +1,168 @@
+//! Spending limits of `[budget]`, checked before spots are saved, see [`BudgetConfig`]
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::BudgetConfig;
+
+/// Saving spots would take a spend over its limit
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{limit} budget of {budget} yuan exceeded: {spent} yuan spent, {cost} more asked for")]
+pub struct BudgetExceeded {
+    /// `Period`, `Weekly` or `Monthly`
+    pub limit: &'static str,
+    pub budget: u32,
+    pub spent: u64,
+    pub cost: u64,
+}
+
+/// Spends against the limits of the config, in yuan
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BudgetStatus {
+    pub limits: BudgetConfig,
+    /// Cost of the spots of the next period
+    pub period_spent: u64,
+    /// Cost of the spots saved since Monday
+    pub week_spent: u64,
+    /// Cost of the spots saved since the first of the month
+    pub month_spent: u64,
+}
+
+impl BudgetStatus {
+    /// Refuse `cost` yuan more when it takes a spend over its limit
+    pub fn check(&self, cost: u64) -> Result<(), BudgetExceeded> {
+        let spends = [
+            ("Period", self.limits.per_period, self.period_spent),
+            ("Weekly", self.limits.per_week, self.week_spent),
+            ("Monthly", self.limits.per_month, self.month_spent),
+        ];
+        for (limit, budget, spent) in spends {
+            if let Some(budget) = budget
+                && spent + cost > u64::from(budget)
+            {
+                return Err(BudgetExceeded {
+                    limit,
+                    budget,
+                    spent,
+                    cost,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a spend is over its limit already
+    pub fn exceeded(&self) -> bool {
+        self.check(0).is_err()
+    }
+}
+
+/// Spends of the active profile against the configured limits
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn budget_status() -> anyhow::Result<BudgetStatus> {
+    budget_status_of(&super::get_next_period().await?).await
+}
+
+/// Spends of the active profile with the period spend of `period`
+#[cfg(not(target_arch = "wasm32"))]
+async fn budget_status_of(period: &str) -> anyhow::Result<BudgetStatus> {
+    use chrono::{Datelike as _, Days, NaiveDate, NaiveTime, TimeZone as _};
+
+    use crate::db::spot;
+    use crate::schedule::{DrawSchedule as _, SSQ};
+
+    let config = super::get_config().await?;
+    let timezone = config.draw_schedule(SSQ)?.timezone();
+    let today = crate::clock::now().with_timezone(&timezone).date_naive();
+    let week_start = today - Days::new(today.weekday().num_days_from_monday().into());
+    let month_start = today.with_day(1).unwrap_or(today);
+    // start of the day in UTC, as the spots save their time
+    let start = |date: NaiveDate| {
+        let midnight = date.and_time(NaiveTime::MIN);
+        timezone
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map_or(midnight, |time| time.naive_utc())
+    };
+    let (week_start, month_start) = (start(week_start), start(month_start));
+
+    let recent = spot::get_spots_created_since(week_start.min(month_start))?;
+    Ok(BudgetStatus {
+        limits: config.budget,
+        period_spent: spots_cost(spot::get_spots_by_period(period)?.iter())?,
+        week_spent: spots_cost(recent.iter().filter(|spot| spot.created_time >= week_start))?,
+        month_spent: spots_cost(
+            recent
+                .iter()
+                .filter(|spot| spot.created_time >= month_start),
+        )?,
+    })
+}
+
+/// Yuan the spots not deprecated cost
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn spots_cost<'a>(
+    spots: impl Iterator<Item = &'a crate::models::Spot>,
+) -> anyhow::Result<u64> {
+    spots
+        .filter(|spot| !spot.deprecated)
+        .map(|spot| spot.cost().map(|cost| cost as u64))
+        .sum::<Result<u64, _>>()
+        .map_err(|e| anyhow::anyhow!("Error pricing spots: {e}"))
+}
+
+/// Refuse spots costing `cost` yuan when they take a spend of the active
+/// profile over its limit, see [`BudgetStatus::check`]
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) async fn ensure_within_budget(cost: u64) -> anyhow::Result<()> {
+    if super::get_config().await?.budget == BudgetConfig::default() {
+        return Ok(());
+    }
+    budget_status().await?.check(cost)?;
+    Ok(())
+}
+
+/// [`ensure_within_budget`] for spots of `period` rather than the next one
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) async fn ensure_period_within_budget(period: &str, cost: u64) -> anyhow::Result<()> {
+    if super::get_config().await?.budget == BudgetConfig::default() {
+        return Ok(());
+    }
+    budget_status_of(period).await?.check(cost)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_check() {
+        let mut status = BudgetStatus {
+            limits: BudgetConfig {
+                per_period: Some(20),
+                per_week: None,
+                per_month: Some(100),
+            },
+            period_spent: 10,
+            week_spent: 30,
+            month_spent: 90,
+        };
+        assert_eq!(status.check(10), Ok(()));
+        assert_eq!(
+            status.check(12),
+            Err(BudgetExceeded {
+                limit: "Period",
+                budget: 20,
+                spent: 10,
+                cost: 12,
+            })
+        );
+        assert!(!status.exceeded());
+
+        status.limits.per_week = Some(30);
+        assert_eq!(status.check(2).map_err(|e| e.limit), Err("Weekly"));
+        status.month_spent = 110;
+        assert!(status.exceeded());
+        assert_eq!(BudgetStatus::default().check(u64::MAX), Ok(()));
+    }
+}
@@ -0,0 +1,230 @@
+//! Periods of the draws and the dates they are drawn on, worked out offline
+//!
+//! Periods are numbered from 001 every year in the order of the draw days of
+//! the [`DrawSchedule`], e.g. `2025001` is the first draw of 2025. Days the
+//! lottery skips only count right when they are listed as blackout dates; the
+//! built-in schedule knows the yearly Spring Festival break, and [`next_period`]
+//! still counts on from the latest known draw in case of other suspensions.
+
+use chrono::{DateTime, Datelike as _, NaiveDate, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::DrawSchedule;
+
+/// Days searched for the next draw day, as [`DrawSchedule::next_draw`] does
+const MAX_DAYS_AHEAD: i64 = 366;
+
+/// A scheduled draw and the period it settles
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PeriodDraw {
+    pub period: String,
+    /// Draw day in the timezone of the schedule
+    pub date: NaiveDate,
+    pub time: DateTime<Utc>,
+}
+
+impl PeriodDraw {
+    fn new(schedule: &impl DrawSchedule, period: String, date: NaiveDate) -> Option<Self> {
+        Some(Self {
+            period,
+            date,
+            time: schedule.to_utc(date.and_time(schedule.draw_time()))?,
+        })
+    }
+
+    /// Number of the period within its year, `None` for a malformed period
+    fn number(&self) -> Option<u32> {
+        self.period.get(4..)?.parse().ok()
+    }
+
+    /// The draw following this one, numbered from 001 again in a new year
+    fn next(&self, schedule: &impl DrawSchedule) -> Option<Self> {
+        let date = (1..=MAX_DAYS_AHEAD)
+            .map(|days| self.date + TimeDelta::days(days))
+            .find(|date| schedule.is_draw_day(*date))?;
+        let number = if date.year() == self.date.year() {
+            self.number()? + 1
+        } else {
+            1
+        };
+        Self::new(schedule, period_name(date.year(), number), date)
+    }
+}
+
+/// Period `number` of `year` as the lottery names it, e.g. `2025088`
+pub fn period_name(year: i32, number: u32) -> String {
+    format!("{year}{number:03}")
+}
+
+/// Every draw `schedule` holds in `year`, in order
+pub fn year_calendar(schedule: &impl DrawSchedule, year: i32) -> Vec<PeriodDraw> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| date.year() == year)
+        .filter(|date| schedule.is_draw_day(*date))
+        .zip(1..)
+        .filter_map(|(date, number)| PeriodDraw::new(schedule, period_name(year, number), date))
+        .collect()
+}
+
+/// The first draw after `now`
+///
+/// Counted on from `latest`, the period and draw day of the latest known draw,
+/// so skipped days missing from the blackout dates only matter after it.
+/// Without one the calendar of the year is used.
+pub fn next_period(
+    schedule: &impl DrawSchedule,
+    latest: Option<(&str, NaiveDate)>,
+    now: DateTime<Utc>,
+) -> Option<PeriodDraw> {
+    let anchor = latest
+        .and_then(|(period, date)| PeriodDraw::new(schedule, period.to_owned(), date))
+        .filter(|draw| draw.number().is_some());
+    let Some(mut draw) = anchor else {
+        let today = now.with_timezone(&schedule.timezone()).date_naive();
+        let mut calendar = year_calendar(schedule, today.year()).into_iter();
+        let mut draw = calendar.next()?;
+        while draw.time <= now {
+            match calendar.next() {
+                Some(next) => draw = next,
+                None => return draw.next(schedule),
+            }
+        }
+        return Some(draw);
+    };
+    loop {
+        draw = draw.next(schedule)?;
+        if draw.time > now {
+            return Some(draw);
+        }
+    }
+}
+
+/// The first `count` draws after `now`, in order, counted like [`next_period`]
+pub fn upcoming_periods(
+    schedule: &impl DrawSchedule,
+    latest: Option<(&str, NaiveDate)>,
+    now: DateTime<Utc>,
+    count: usize,
+) -> Vec<PeriodDraw> {
+    std::iter::successors(next_period(schedule, latest, now), |draw| {
+        draw.next(schedule)
+    })
+    .take(count)
+    .collect()
+}
+
+/// Periods of `year` drawn by `now` but not in `known`, in order
+pub fn missing_periods(
+    schedule: &impl DrawSchedule,
+    year: i32,
+    known: &[String],
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    year_calendar(schedule, year)
+        .into_iter()
+        .take_while(|draw| draw.time <= now)
+        .map(|draw| draw.period)
+        .filter(|period| !known.contains(period))
+        .collect()
+}
+
+/// Draws of `year` on the configured schedule
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn period_calendar(year: i32) -> anyhow::Result<Vec<PeriodDraw>> {
+    let schedule = super::get_config()
+        .await?
+        .draw_schedule(crate::schedule::SSQ)?;
+    Ok(year_calendar(&schedule, year))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::WeeklySchedule;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Valid test date")
+    }
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M")
+            .expect("Valid test time")
+            .and_utc()
+    }
+
+    #[test]
+    fn test_year_calendar() {
+        let calendar = year_calendar(&WeeklySchedule::ssq(), 2025);
+        // 2025 starts on a Wednesday, 52 weeks and a day of Tue/Thu/Sun, less the
+        // three draws of the Spring Festival break from 2025-01-28 to 2025-02-03
+        assert_eq!(calendar.len(), 153);
+        assert_eq!(calendar[0].period, "2025001");
+        assert_eq!(calendar[0].date, date(2025, 1, 2));
+        assert_eq!(calendar[0].time, utc("2025-01-02 13:20"));
+        assert_eq!(calendar[10].period, "2025011");
+        assert_eq!(calendar[10].date, date(2025, 1, 26));
+        assert_eq!(calendar[11].period, "2025012");
+        assert_eq!(calendar[11].date, date(2025, 2, 4));
+        assert_eq!(calendar[87].period, "2025088");
+        assert_eq!(calendar[87].date, date(2025, 7, 31));
+        assert_eq!(calendar[152].period, "2025153");
+        assert_eq!(calendar[152].date, date(2025, 12, 30));
+    }
+
+    #[test]
+    fn test_next_period() {
+        let schedule = WeeklySchedule::ssq();
+        // 2025088 was drawn on Thursday 2025-07-31
+        let latest = Some(("2025088", date(2025, 7, 31)));
+        let next = |now| next_period(&schedule, latest, utc(now)).map(|draw| draw.period);
+        assert_eq!(next("2025-08-01 00:00").as_deref(), Some("2025089"));
+        assert_eq!(next("2025-08-03 13:20").as_deref(), Some("2025090"));
+        assert_eq!(next("2025-08-06 00:00").as_deref(), Some("2025091"));
+        // the numbering starts again in the new year
+        let latest = Some(("2025153", date(2025, 12, 30)));
+        let next = next_period(&schedule, latest, utc("2025-12-31 00:00"));
+        assert_eq!(next.map(|draw| draw.period).as_deref(), Some("2026001"));
+
+        // the calendar of the year without a known draw
+        let next = next_period(&schedule, None, utc("2025-01-02 14:00"));
+        assert_eq!(next.map(|draw| draw.period).as_deref(), Some("2025002"));
+        let next = next_period(&schedule, None, utc("2025-12-30 14:00"));
+        assert_eq!(next.map(|draw| draw.period).as_deref(), Some("2026001"));
+    }
+
+    #[test]
+    fn test_upcoming_periods() {
+        let schedule = WeeklySchedule::ssq();
+        let latest = Some(("2025152", date(2025, 12, 28)));
+        let upcoming = upcoming_periods(&schedule, latest, utc("2025-12-29 00:00"), 3);
+        let periods = upcoming
+            .iter()
+            .map(|draw| draw.period.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(periods, ["2025153", "2026001", "2026002"]);
+        assert_eq!(upcoming[1].date, date(2026, 1, 1));
+        assert!(upcoming_periods(&schedule, latest, utc("2025-12-29 00:00"), 0).is_empty());
+    }
+
+    #[test]
+    fn test_missing_periods() {
+        let schedule = WeeklySchedule::ssq();
+        let known = ["2025001", "2025003"].map(str::to_owned);
+        assert_eq!(
+            missing_periods(&schedule, 2025, &known, utc("2025-01-09 14:00")),
+            ["2025002", "2025004"]
+        );
+        // no phantom periods over the Spring Festival break
+        let known = (1..=11)
+            .map(|number| period_name(2025, number))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            missing_periods(&schedule, 2025, &known, utc("2025-02-04 14:00")),
+            ["2025012"]
+        );
+    }
+}
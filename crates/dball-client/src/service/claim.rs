@@ -0,0 +1,135 @@
+use dball_combora::dball::Reward;
+
+use crate::db::{prize_claim, spot, tickets};
+use crate::models::{NewPrizeClaim, PrizeClaim, Spot, Winnings};
+
+use super::report::spot_winnings;
+
+/// Record the prize of a winning spot as claimed, returns the id of the claim
+///
+/// The amount paid out defaults to the winnings of the spot, except for the
+/// floating first and second tiers or a spot whose draw is not stored, which
+/// need it given.
+#[tracing::instrument]
+pub async fn claim_prize(new: NewPrizeClaim) -> anyhow::Result<i32> {
+    let id = new.spot_id;
+    let target = spot::get_spot_by_id(id)?.ok_or_else(|| anyhow::anyhow!("Spot {id} not found"))?;
+    if target.deprecated {
+        anyhow::bail!("Spot {id} is deprecated and cannot be claimed");
+    }
+    let winnings = spot_winnings(&target);
+    if winnings == 0 {
+        anyhow::bail!("Spot {id} has not won a prize");
+    }
+    if prize_claim::get_claim_by_spot(id)?.is_some() {
+        anyhow::bail!("The prize of spot {id} is claimed already");
+    }
+    let amount = match new.amount {
+        Some(amount) if amount < 0 => {
+            anyhow::bail!("Amount of a claim cannot be negative, got {amount}")
+        }
+        Some(amount) => amount,
+        None if wins_floating_prize(&target)? => {
+            anyhow::bail!("Spot {id} won a floating prize, give the amount paid out")
+        }
+        None => i32::try_from(winnings)?,
+    };
+
+    let now = crate::clock::now();
+    let claim = PrizeClaim {
+        id: None,
+        spot_id: id,
+        claim_date: new.date.unwrap_or_else(|| now.date_naive()),
+        amount,
+        note: new.note.filter(|note| !note.trim().is_empty()),
+        created_time: now.naive_utc(),
+        profile_id: crate::profile::owner(),
+    };
+    let claim_id = prize_claim::insert_claim(&claim)?;
+    log::info!("Claimed {amount} yuan of {winnings} won by spot {id}");
+    Ok(claim_id)
+}
+
+/// Whether a bet of `target` won the first or second tier, also when its draw
+/// is not stored to tell
+fn wins_floating_prize(target: &Spot) -> anyhow::Result<bool> {
+    let Some(ticket) = tickets::get_ticket_by_period(&target.period)? else {
+        return Ok(true);
+    };
+    let drawn = ticket.to_dball()?;
+    Ok(target.bets()?.iter().any(|bet| {
+        matches!(
+            bet.check_prize(&drawn),
+            Reward::FirstPrize | Reward::SecondPrize
+        )
+    }))
+}
+
+/// Yuan paid out for the claimed prizes against the winnings of every settled spot
+pub async fn winnings() -> anyhow::Result<Winnings> {
+    let realized = prize_claim::claims_total()?;
+    let theoretical = super::roi_report().await?.total_winnings;
+    Ok(Winnings {
+        realized: realized as f64,
+        theoretical: theoretical as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Ticket;
+    use dball_combora::dball::DBall;
+
+    #[tokio::test]
+    async fn test_claim_prize() -> anyhow::Result<()> {
+        let drawn = Ticket::new(
+            "2025979".to_owned(),
+            "2025-12-28 21:15:00",
+            &[1, 2, 3, 4, 5, 6],
+            9,
+        )?;
+        tickets::insert_ticket(&drawn)?;
+        // a fifth prize of 4 reds and the blue, and a second one of 6 reds
+        let spots = [
+            Spot::from_dball(
+                "2025979",
+                &DBall::new_one(vec![1, 2, 3, 4, 20, 30], 9)?,
+                Some(10),
+            )?,
+            Spot::from_dball(
+                "2025979",
+                &DBall::new_one(vec![1, 2, 3, 4, 5, 6], 8)?,
+                Some(150_000),
+            )?,
+        ];
+        let ids = spot::insert_spots_batch(&spots)?;
+        let before = winnings().await?;
+
+        let new = NewPrizeClaim {
+            spot_id: ids[0],
+            date: None,
+            amount: None,
+            note: None,
+        };
+        let id = claim_prize(new.clone()).await?;
+        let claim =
+            prize_claim::get_claim_by_spot(ids[0])?.ok_or(anyhow::anyhow!("Claim missing"))?;
+        assert_eq!(claim.id, Some(id));
+        assert_eq!(claim.amount, 10);
+        assert!(claim_prize(new.clone()).await.is_err());
+
+        let floating = NewPrizeClaim {
+            spot_id: ids[1],
+            ..new
+        };
+        assert!(claim_prize(floating.clone()).await.is_err());
+        claim_prize(NewPrizeClaim {
+            amount: Some(120_000),
+            ..floating
+        })
+        .await?;
+        assert!(winnings().await?.realized >= before.realized + 120_010.0);
+        Ok(())
+    }
+}
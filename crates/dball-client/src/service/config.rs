@@ -0,0 +1,22 @@
+use crate::config::{ConfigIssue, DballConfig, config_path};
+
+/// Read `dball.toml`, falling back to defaults when it does not exist
+pub async fn get_config() -> anyhow::Result<DballConfig> {
+    DballConfig::load(config_path()?)
+}
+
+/// Validate and write `dball.toml`
+///
+/// Returns the validation issues, the file is only written when there are none
+pub async fn update_config(config: DballConfig) -> anyhow::Result<Vec<ConfigIssue>> {
+    let issues = config.validate();
+    if !issues.is_empty() {
+        log::warn!("Rejected config update with {} issues", issues.len());
+        return Ok(issues);
+    }
+
+    let path = config_path()?;
+    config.save(&path)?;
+    log::info!("Config saved to {}", path.display());
+    Ok(issues)
+}
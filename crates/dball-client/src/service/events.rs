@@ -0,0 +1,87 @@
+//! Notable events raised by the services, forwarded by the daemon to subscribed clients
+//!
+//! Publishing never fails, events raised while nobody listens are dropped
+
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum DaemonEvent {
+    /// Spots of a period were checked against its draw
+    DrawSettled { period: String, spots: usize },
+    /// A spot won a prize, `tier` as in [`dball_combora::dball::Reward::description`]
+    PrizeWon {
+        period: String,
+        tier: String,
+        amount: u32,
+    },
+    /// Spots of a period were evaluated on request, `winnings` in yuan for the
+    /// spots of the profile that asked
+    PeriodEvaluated {
+        period: String,
+        spots: usize,
+        winnings: u64,
+    },
+    /// A new batch of spots was saved for the next period
+    GenerationFinished { period: String, spots: usize },
+    /// A request to the lottery API failed
+    ApiFailure { message: String },
+    /// A period of a year crawl was handled, published once more with
+    /// `finished` when the crawl of the year ends, whether it failed or not
+    CrawlProgress {
+        year: usize,
+        /// Period handled last, empty before the first one
+        period: String,
+        /// Periods the draw calendar missed when the crawl started
+        total: usize,
+        inserted: usize,
+        /// Periods stored already, not drawn or contradicting what is stored
+        skipped: usize,
+        failed: usize,
+        finished: bool,
+    },
+}
+
+static EVENTS: LazyLock<broadcast::Sender<DaemonEvent>> =
+    LazyLock::new(|| broadcast::channel(64).0);
+
+pub fn publish(event: DaemonEvent) {
+    log::debug!("Publishing event {event:?}");
+    if EVENTS.send(event).is_err() {
+        log::trace!("No subscriber for daemon events");
+    }
+}
+
+/// Receive every event published from now on
+pub fn subscribe_events() -> broadcast::Receiver<DaemonEvent> {
+    EVENTS.subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_reaches_subscribers() -> anyhow::Result<()> {
+        // published before subscribing, never received
+        publish(DaemonEvent::ApiFailure {
+            message: "unseen".to_owned(),
+        });
+
+        let mut receiver = subscribe_events();
+        let event = DaemonEvent::GenerationFinished {
+            period: "2099001".to_owned(),
+            spots: 5,
+        };
+        publish(event.clone());
+
+        // other tests may publish at the same time
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            while receiver.recv().await? != event {}
+            anyhow::Ok(())
+        })
+        .await?
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::{ExportRequest, ExportTable, PageRequest, Spot, SpotQuery, Ticket};
+
+/// Render the requested rows as CSV with a header line
+pub async fn export_csv(request: ExportRequest) -> anyhow::Result<String> {
+    let csv = match request.table {
+        ExportTable::Tickets => tickets_csv(
+            &super::ticket::get_tickets(request.period_prefix, PageRequest::default())
+                .await?
+                .items,
+        ),
+        ExportTable::Spots => {
+            let query = SpotQuery {
+                period_prefix: request.period_prefix,
+                ..SpotQuery::default()
+            };
+            spots_csv(&super::spot::query_spots(query).await?)
+        }
+    };
+    log::info!("Exported {} as CSV", request.table.name());
+    Ok(csv)
+}
+
+/// Quote a field holding a separator, quote or line break
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn tickets_csv(tickets: &[Ticket]) -> String {
+    let mut csv = "period,time,red1,red2,red3,red4,red5,red6,blue\n".to_owned();
+    for ticket in tickets {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            field(&ticket.period),
+            ticket.time,
+            ticket.red1,
+            ticket.red2,
+            ticket.red3,
+            ticket.red4,
+            ticket.red5,
+            ticket.red6,
+            ticket.blue
+        ));
+    }
+    csv
+}
+
+fn spots_csv(spots: &[Spot]) -> String {
+    let mut csv = "id,period,red1,red2,red3,red4,red5,red6,blue,magnification,prize_status,deprecated,purchased,note,created_time,extra_reds,extra_blues,bankers\n".to_owned();
+    for spot in spots {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            spot.id.map(|id| id.to_string()).unwrap_or_default(),
+            field(&spot.period),
+            spot.red1,
+            spot.red2,
+            spot.red3,
+            spot.red4,
+            spot.red5,
+            spot.red6,
+            spot.blue,
+            spot.magnification,
+            spot.prize_status
+                .map(|level| level.to_string())
+                .unwrap_or_default(),
+            spot.deprecated,
+            spot.purchased,
+            field(spot.note.as_deref().unwrap_or_default()),
+            spot.created_time,
+            spot.extra_reds.as_deref().unwrap_or_default(),
+            spot.extra_blues.as_deref().unwrap_or_default(),
+            spot.bankers.as_deref().unwrap_or_default()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use dball_combora::dball::DBall;
+
+    use super::*;
+
+    #[test]
+    fn test_spots_csv_quotes_notes() -> anyhow::Result<()> {
+        let dball = DBall::new([1, 2, 3, 4, 5, 6], 7, 2)?;
+        let mut spot = Spot::from_dball("2025084", &dball, None)?;
+        spot.note = Some("lucky, \"again\"".to_owned());
+        let csv = spots_csv(&[spot]);
+
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().map(|header| header.split(',').count()),
+            Some(18)
+        );
+        let row = lines.next().unwrap_or_default();
+        assert!(
+            row.starts_with(",2025084,1,2,3,4,5,6,7,2,,false,false,\"lucky, \"\"again\"\"\","),
+            "{row}"
+        );
+        assert_eq!(lines.next(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_leaves_plain_values() {
+        assert_eq!(field("2025084"), "2025084");
+        assert_eq!(field("a\nb"), "\"a\nb\"");
+    }
+}
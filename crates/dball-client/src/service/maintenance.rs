@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use crate::db::maintenance::{self, MaintenanceReport};
+use crate::models::DbHealth;
+
+/// Back up the database to `path` on the daemon's machine, or next to the
+/// database named after the current time without one, returns the path written
+#[tracing::instrument]
+pub async fn backup_database(path: Option<String>) -> anyhow::Result<String> {
+    let path = match path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        Some(path) => PathBuf::from(path),
+        None => maintenance::snapshot_path()?,
+    };
+    maintenance::backup(&path)?;
+    log::info!("Backed up the database to {}", path.display());
+    Ok(path.display().to_string())
+}
+
+/// Checkpoint, analyze and, past the free page threshold of `[maintenance]`,
+/// vacuum the database
+#[tracing::instrument]
+pub async fn maintain_database() -> anyhow::Result<MaintenanceReport> {
+    let vacuum_free_percent = super::get_config().await?.maintenance.vacuum_free_percent;
+    let report =
+        tokio::task::spawn_blocking(move || maintenance::run_maintenance(vacuum_free_percent))
+            .await??;
+    log::info!(
+        "Maintained the database: {} WAL pages checkpointed, {} of {} pages free, vacuumed: {}",
+        report.checkpointed_pages,
+        report.free_pages,
+        report.total_pages,
+        report.vacuumed
+    );
+    Ok(report)
+}
+
+/// Integrity check, sizes and row counts of the database
+pub async fn database_health() -> anyhow::Result<DbHealth> {
+    let health = tokio::task::spawn_blocking(maintenance::health).await??;
+    if !health.is_ok() {
+        log::error!("Database integrity check failed: {:?}", health.problems);
+    }
+    Ok(health)
+}
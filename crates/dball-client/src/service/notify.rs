@@ -0,0 +1,546 @@
+//! Draws and prizes sent out by the daemon beyond its clients, see
+//! [`crate::config::NotificationConfig`]
+//!
+//! Webhooks get the won prizes, the mail and the Telegram chat get every
+//! settled draw. Every target is retried on its own, and every delivery, sent
+//! or given up, is recorded as a [`NotificationDelivery`]
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use dball_combora::dball::{DBall, Reward};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport as _, Message, Tokio1Executor};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::config::{NotificationConfig, SmtpConfig};
+use crate::db::notification_delivery;
+use crate::models::NotificationDelivery;
+
+const WEBHOOK: &str = "webhook";
+const EMAIL: &str = "email";
+const TELEGRAM: &str = "telegram";
+const ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Prize won by a bet and its amount in yuan
+pub type BetPrize = (Reward, u32);
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+/// A prize won by one bet
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrizeWin {
+    /// From 1 for the first prize to 6, see [`Reward::tier`]
+    pub tier: u8,
+    /// In yuan
+    pub amount: u32,
+}
+
+/// JSON body sent to the webhooks once the spots of a period are settled
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PrizeNotification {
+    pub period: String,
+    /// Prizes of the configured tiers, the best one first
+    pub prizes: Vec<PrizeWin>,
+    /// Sum of [`Self::prizes`] in yuan
+    pub total: u64,
+}
+
+impl PrizeNotification {
+    /// Notification of the `wins` of `period` that `config` sends out, `None` without any
+    pub fn new(period: &str, wins: &[BetPrize], config: &NotificationConfig) -> Option<Self> {
+        let mut prizes = wins
+            .iter()
+            .filter_map(|(reward, amount)| {
+                let tier = reward.tier().filter(|&tier| config.sends_tier(tier))?;
+                Some(PrizeWin {
+                    tier,
+                    amount: *amount,
+                })
+            })
+            .collect::<Vec<_>>();
+        if prizes.is_empty() {
+            return None;
+        }
+        prizes.sort_by_key(|prize| prize.tier);
+        Some(Self {
+            period: period.to_owned(),
+            total: prizes.iter().map(|prize| u64::from(prize.amount)).sum(),
+            prizes,
+        })
+    }
+}
+
+/// Spots of a period settled against its draw
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettledDraw {
+    pub period: String,
+    pub drawn: DBall,
+    pub spots: usize,
+    pub wins: Vec<BetPrize>,
+}
+
+impl SettledDraw {
+    /// Subject and plain text body of the mail, the subject names the best
+    /// prize when `config` sends its tier out
+    pub fn mail(&self, config: &NotificationConfig) -> (String, String) {
+        let period = &self.period;
+        let numbers = format!(
+            "{} + {:02}",
+            self.drawn.rball.map(|ball| format!("{ball:02}")).join(" "),
+            self.drawn.bball
+        );
+        let subject = match PrizeNotification::new(period, &self.wins, config) {
+            Some(notification) => format!(
+                "Period {period}: prize #{} won, {} yuan",
+                notification.prizes[0].tier, notification.total
+            ),
+            None => format!("Period {period} drawn: {numbers}"),
+        };
+
+        let mut body = format!(
+            "Period {period} drawn: {numbers}\n{} spots settled\n",
+            self.spots
+        );
+        if self.wins.is_empty() {
+            body.push_str("No prize won\n");
+        }
+        body.extend(self.wins.iter().map(|(reward, amount)| {
+            format!("Prize {} won, {amount} yuan\n", reward.description())
+        }));
+        (subject, body)
+    }
+
+    /// Text sent to the Telegram chat, the mail followed by the `waiting` spots of the next period
+    pub fn telegram_text(&self, config: &NotificationConfig, waiting: usize) -> String {
+        let (_, body) = self.mail(config);
+        format!("{body}{waiting} spots wait for the next draw")
+    }
+}
+
+/// Text message sent to the bot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelegramMessage {
+    pub chat_id: i64,
+    pub text: String,
+}
+
+/// Bot of [`NotificationConfig::TELEGRAM_TOKEN_VAR`] on the Telegram Bot API
+#[derive(Clone)]
+pub struct TelegramBot {
+    token: String,
+}
+
+impl TelegramBot {
+    /// The configured bot, `None` without a token
+    pub fn from_env() -> Option<Self> {
+        std::env::var(NotificationConfig::TELEGRAM_TOKEN_VAR)
+            .ok()
+            .filter(|token| !token.is_empty())
+            .map(|token| Self { token })
+    }
+
+    pub async fn send_message(&self, chat_id: &str, text: &str) -> anyhow::Result<()> {
+        let body = json!({ "chat_id": chat_id, "text": text });
+        self.call::<Value>("sendMessage", &body, TIMEOUT)
+            .await
+            .map(drop)
+    }
+
+    /// Text messages after the update `offset`, waiting up to `wait` for one,
+    /// along with the offset to ask for next
+    pub async fn messages(
+        &self,
+        offset: i64,
+        wait: Duration,
+    ) -> anyhow::Result<(Vec<TelegramMessage>, i64)> {
+        #[derive(Deserialize)]
+        struct Update {
+            update_id: i64,
+            message: Option<UpdateMessage>,
+        }
+        #[derive(Deserialize)]
+        struct UpdateMessage {
+            chat: Chat,
+            text: Option<String>,
+        }
+        #[derive(Deserialize)]
+        struct Chat {
+            id: i64,
+        }
+
+        let body = json!({
+            "offset": offset,
+            "timeout": wait.as_secs(),
+            "allowed_updates": ["message"],
+        });
+        let updates = self
+            .call::<Vec<Update>>("getUpdates", &body, wait + TIMEOUT)
+            .await?;
+        let next = updates
+            .iter()
+            .map(|update| update.update_id + 1)
+            .max()
+            .unwrap_or(offset);
+        let messages = updates
+            .into_iter()
+            .filter_map(|update| {
+                let message = update.message?;
+                Some(TelegramMessage {
+                    chat_id: message.chat.id,
+                    text: message.text?,
+                })
+            })
+            .collect();
+        Ok((messages, next))
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        body: &Value,
+        timeout: Duration,
+    ) -> anyhow::Result<T> {
+        #[derive(Deserialize)]
+        struct Reply<T> {
+            ok: bool,
+            result: Option<T>,
+            description: Option<String>,
+        }
+
+        // errors of the client name the URL, which holds the token
+        let hide_url =
+            |e: reqwest::Error| anyhow::anyhow!("Telegram {method} failed: {}", e.without_url());
+        let reply = CLIENT
+            .post(format!(
+                "https://api.telegram.org/bot{}/{method}",
+                self.token
+            ))
+            .json(body)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(hide_url)?
+            .json::<Reply<T>>()
+            .await
+            .map_err(hide_url)?;
+        match reply {
+            Reply {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            Reply { description, .. } => anyhow::bail!(
+                "Telegram {method} failed: {}",
+                description.as_deref().unwrap_or("no result")
+            ),
+        }
+    }
+}
+
+/// Send the draw and the prizes of `settled` out in the background, failures are only logged
+pub(super) fn notify_settled(settled: SettledDraw) {
+    tokio::spawn(async move {
+        let period = &settled.period;
+        if let Err(e) = send_prize_webhooks(period, &settled.wins).await {
+            log::warn!("Failed to send the prizes of period {period}: {e:#}");
+        }
+        if let Err(e) = send_draw_email(&settled).await {
+            log::warn!("Failed to mail the draw of period {period}: {e:#}");
+        }
+        if let Err(e) = send_draw_telegram(&settled).await {
+            log::warn!("Failed to send the draw of period {period} to Telegram: {e:#}");
+        }
+    });
+}
+
+/// POST the `wins` of `period` of the configured tiers to every webhook,
+/// returning the recorded deliveries, none when nothing is configured or won
+#[tracing::instrument(skip(wins))]
+pub async fn send_prize_webhooks(
+    period: &str,
+    wins: &[BetPrize],
+) -> anyhow::Result<Vec<NotificationDelivery>> {
+    let config = super::get_config().await?.notification;
+    let urls = config.webhook_urls();
+    let Some(notification) = PrizeNotification::new(period, wins, &config) else {
+        return Ok(Vec::new());
+    };
+    if urls.is_empty() {
+        return Ok(Vec::new());
+    }
+    let payload = serde_json::to_string(&notification)?;
+
+    let mut deliveries = Vec::with_capacity(urls.len());
+    for url in urls {
+        let delivery = deliver(WEBHOOK, url, period, &payload, RETRY_DELAY, || {
+            post_webhook(url, &payload)
+        })
+        .await;
+        match &delivery.error {
+            None => log::info!("Sent the prizes of period {period} to {url}"),
+            Some(e) => log::warn!("Gave up sending the prizes of period {period} to {url}: {e}"),
+        }
+        notification_delivery::insert_notification_delivery(&delivery)?;
+        deliveries.push(delivery);
+    }
+    Ok(deliveries)
+}
+
+/// Mail `settled` to the configured address, returning the recorded delivery,
+/// none when no address or no mail server is configured
+#[tracing::instrument(skip_all, fields(period = settled.period))]
+pub async fn send_draw_email(
+    settled: &SettledDraw,
+) -> anyhow::Result<Option<NotificationDelivery>> {
+    let config = super::get_config().await?.notification;
+    let (Some(to), Some(host)) = (&config.email, &config.smtp.host) else {
+        return Ok(None);
+    };
+    let (subject, body) = settled.mail(&config);
+    let message = mail_message(&config.smtp, to, &subject, body.clone())?;
+    let transport = smtp_transport(&config.smtp, host)?;
+
+    let period = &settled.period;
+    let delivery = deliver(EMAIL, to, period, &body, RETRY_DELAY, || async {
+        transport.send(message.clone()).await?;
+        Ok(())
+    })
+    .await;
+    match &delivery.error {
+        None => log::info!("Mailed the draw of period {period} to {to}"),
+        Some(e) => log::warn!("Gave up mailing the draw of period {period} to {to}: {e}"),
+    }
+    notification_delivery::insert_notification_delivery(&delivery)?;
+    Ok(Some(delivery))
+}
+
+/// Send `settled` to the configured Telegram chat, returning the recorded
+/// delivery, none when no chat or no bot is configured
+#[tracing::instrument(skip_all, fields(period = settled.period))]
+pub async fn send_draw_telegram(
+    settled: &SettledDraw,
+) -> anyhow::Result<Option<NotificationDelivery>> {
+    let config = super::get_config().await?.notification;
+    let Some(chat_id) = &config.telegram_chat_id else {
+        return Ok(None);
+    };
+    let Some(bot) = TelegramBot::from_env() else {
+        log::warn!(
+            "No Telegram bot to send to chat {chat_id}, set {}",
+            NotificationConfig::TELEGRAM_TOKEN_VAR
+        );
+        return Ok(None);
+    };
+    let waiting = super::get_next_period_unprized_spots().await?.len();
+    let text = settled.telegram_text(&config, waiting);
+
+    let period = &settled.period;
+    let delivery = deliver(TELEGRAM, chat_id, period, &text, RETRY_DELAY, || {
+        bot.send_message(chat_id, &text)
+    })
+    .await;
+    match &delivery.error {
+        None => log::info!("Sent the draw of period {period} to Telegram chat {chat_id}"),
+        Some(e) => log::warn!("Gave up sending the draw of period {period} to Telegram: {e}"),
+    }
+    notification_delivery::insert_notification_delivery(&delivery)?;
+    Ok(Some(delivery))
+}
+
+/// The latest `limit` deliveries, the latest one first
+pub async fn notification_deliveries(limit: i64) -> anyhow::Result<Vec<NotificationDelivery>> {
+    notification_delivery::get_latest_notification_deliveries(limit)
+}
+
+async fn post_webhook(url: &str, payload: &str) -> anyhow::Result<()> {
+    CLIENT
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_owned())
+        .timeout(TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+fn mail_message(
+    smtp: &SmtpConfig,
+    to: &str,
+    subject: &str,
+    body: String,
+) -> anyhow::Result<Message> {
+    let from = smtp
+        .from
+        .as_ref()
+        .or(smtp.username.as_ref())
+        .context("No sender address, set notification.smtp.from")?;
+    Message::builder()
+        .from(from.parse().context("Invalid sender address")?)
+        .to(to.parse().context("Invalid notification address")?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(body)
+        .context("Failed to build the mail")
+}
+
+fn smtp_transport(
+    smtp: &SmtpConfig,
+    host: &str,
+) -> anyhow::Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let builder = if smtp.tls {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(host)?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+    };
+    let mut builder = builder.port(smtp.port).timeout(Some(TIMEOUT));
+    if let Some(username) = &smtp.username {
+        let password = std::env::var(SmtpConfig::PASSWORD_VAR)
+            .with_context(|| format!("{} is not set", SmtpConfig::PASSWORD_VAR))?;
+        builder = builder.credentials(Credentials::new(username.clone(), password));
+    }
+    Ok(builder.build())
+}
+
+/// Call `send` with retries, the outcome as a delivery of `payload` to `target` to record
+async fn deliver<F, Fut>(
+    channel: &str,
+    target: &str,
+    period: &str,
+    payload: &str,
+    delay: Duration,
+    send: F,
+) -> NotificationDelivery
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let (attempts, result) = super::scheduler::retry(ATTEMPTS, delay, send).await;
+    NotificationDelivery {
+        id: None,
+        channel: channel.to_owned(),
+        target: target.to_owned(),
+        period: period.to_owned(),
+        payload: payload.to_owned(),
+        attempts,
+        error: result.err().map(|e| format!("{e:#}")),
+        sent_time: crate::clock::now().naive_utc(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_prize_notification() {
+        let wins = [
+            (Reward::SixthPrize, 5),
+            (Reward::ThirdPrize, 3_000),
+            (Reward::FourthPrize, 200),
+        ];
+        let mut config = NotificationConfig::default();
+        let all = PrizeNotification::new("2025001", &wins, &config).expect("Every prize");
+        assert_eq!(
+            all.prizes
+                .iter()
+                .map(|prize| prize.tier)
+                .collect::<Vec<_>>(),
+            [3, 4, 6]
+        );
+        assert_eq!(all.total, 3_205);
+
+        config.min_tier = 4;
+        let best = PrizeNotification::new("2025001", &wins, &config).expect("Better prizes");
+        assert_eq!(best.total, 3_200);
+
+        config.min_tier = 2;
+        assert!(PrizeNotification::new("2025001", &wins, &config).is_none());
+    }
+
+    #[test]
+    fn test_draw_mail() -> anyhow::Result<()> {
+        let mut settled = SettledDraw {
+            period: "2025001".to_owned(),
+            drawn: DBall::new(vec![1, 2, 3, 4, 5, 16], 7, 1)?,
+            spots: 5,
+            wins: vec![(Reward::SixthPrize, 5), (Reward::FourthPrize, 200)],
+        };
+        let mut config = NotificationConfig::default();
+        let (subject, body) = settled.mail(&config);
+        assert_eq!(subject, "Period 2025001: prize #4 won, 205 yuan");
+        assert!(
+            body.starts_with("Period 2025001 drawn: 01 02 03 04 05 16 + 07\n5 spots settled\n")
+        );
+        assert!(body.contains("Prize #6 won, 5 yuan"));
+
+        config.min_tier = 3;
+        let (subject, body) = settled.mail(&config);
+        assert_eq!(subject, "Period 2025001 drawn: 01 02 03 04 05 16 + 07");
+        assert!(body.contains("Prize #4 won, 200 yuan"));
+
+        let text = settled.telegram_text(&config, 5);
+        assert!(text.starts_with("Period 2025001 drawn: 01 02 03 04 05 16 + 07\n"));
+        assert!(text.ends_with("Prize #4 won, 200 yuan\n5 spots wait for the next draw"));
+
+        settled.wins.clear();
+        assert!(settled.mail(&config).1.ends_with("No prize won\n"));
+
+        let mut smtp = SmtpConfig::default();
+        assert!(mail_message(&smtp, "me@example.com", &subject, body.clone()).is_err());
+        smtp.username = Some("dball@example.com".to_owned());
+        assert!(mail_message(&smtp, "me@example.com", &subject, body).is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries() -> anyhow::Result<()> {
+        // fails the first request, then accepts every one
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |body: String| async move {
+                assert!(body.contains("2099001"));
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                } else {
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let delay = Duration::from_millis(1);
+        let payload = r#"{"period":"2099001","prizes":[],"total":0}"#;
+        let url = format!("http://{address}/hook");
+        let delivery = deliver(WEBHOOK, &url, "2099001", payload, delay, || {
+            post_webhook(&url, payload)
+        })
+        .await;
+        assert!(delivery.delivered());
+        assert_eq!(delivery.attempts, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let url = format!("http://{address}/missing");
+        let delivery = deliver(WEBHOOK, &url, "2099001", payload, delay, || {
+            post_webhook(&url, payload)
+        })
+        .await;
+        assert!(!delivery.delivered());
+        assert_eq!(delivery.attempts, ATTEMPTS as i32);
+        Ok(())
+    }
+}
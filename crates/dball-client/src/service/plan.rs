@@ -0,0 +1,181 @@
+//! Spots generated ahead for the next draws, for buying several periods at once
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Draws spots can be generated ahead for at once
+pub const MAX_PLANNED_PERIODS: usize = 12;
+
+/// Prefix of the tag spots generated ahead carry, followed by their period
+pub const PLAN_TAG_PREFIX: &str = "plan:";
+
+/// Spots generated ahead for one of the next draws
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlannedBatch {
+    pub period: String,
+    pub draw_time: DateTime<Utc>,
+    /// Ids of the spots generated, empty when enough spots were waiting for
+    /// the draw already
+    pub spot_ids: Vec<i32>,
+}
+
+/// Tag of the spots generated ahead for `period`, e.g. `plan:2025090`
+pub fn plan_tag(period: &str) -> String {
+    format!("{PLAN_TAG_PREFIX}{period}")
+}
+
+/// Generate a batch of the configured size for each of the next `periods`
+/// draws with the `strategy` named, or the configured one without, tagged
+/// with [`plan_tag`]
+///
+/// Draws with enough spots waiting keep them, as in
+/// [`generate_batch_spots`](super::generate_batch_spots). Batches are saved one
+/// period after another, those saved before a period fails to save are kept.
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument]
+pub async fn generate_spots_for_periods(
+    periods: usize,
+    strategy: Option<&str>,
+) -> anyhow::Result<Vec<PlannedBatch>> {
+    use dball_combora::generator::GenerationProgress;
+
+    use super::progress;
+    use super::spot::{WAITING_SPOTS_LIMIT, generate_logged_batch, generation_message};
+    use crate::db::{spot, tag};
+
+    if !(1..=MAX_PLANNED_PERIODS).contains(&periods) {
+        anyhow::bail!("Spots can be generated for 1 to {MAX_PLANNED_PERIODS} periods ahead");
+    }
+    let _timer = crate::metrics::time("generate_spots_for_periods");
+    let config = super::get_config().await?.generator;
+    let kind = config.generator(strategy)?;
+    let draws = super::ticket::get_next_draws(periods).await?;
+    let mut pending = Vec::with_capacity(draws.len());
+    for draw in &draws {
+        let waiting = spot::get_spots_by_period(&draw.period)?
+            .iter()
+            .filter(|spot| spot.prize_status.is_none() && !spot.deprecated)
+            .count();
+        if waiting >= WAITING_SPOTS_LIMIT {
+            log::warn!(
+                "{waiting} unprized spots wait for period {}, skipping it",
+                draw.period
+            );
+        }
+        pending.push(waiting < WAITING_SPOTS_LIMIT);
+    }
+
+    // not `Send`, so dropped before the batches are saved
+    let batches = {
+        let generator = super::spot::load_generator(kind, &config)?;
+        let cancel = progress::cancel_flag();
+        let report = progress::reporter();
+        let mut batches = Vec::with_capacity(draws.len());
+        for (step, (draw, pending)) in draws.iter().zip(&pending).enumerate() {
+            if !pending {
+                batches.push(None);
+                continue;
+            }
+            progress::ensure_not_cancelled()?;
+            let on_progress = |stats: GenerationProgress| {
+                report(
+                    step,
+                    Some(draws.len()),
+                    format!("Period {}: {}", draw.period, generation_message(stats)),
+                );
+            };
+            let batch = generate_logged_batch(
+                generator.as_ref(),
+                kind,
+                config.batch_size,
+                &cancel,
+                &on_progress,
+            )?;
+            batches.push(Some(batch));
+        }
+        batches
+    };
+
+    let mut planned = Vec::with_capacity(draws.len());
+    for (draw, batch) in draws.into_iter().zip(batches) {
+        progress::ensure_not_cancelled()?;
+        let spot_ids = match batch {
+            Some(batch) => {
+                let ids = super::spot::save_generated_batches_for(draw.period.clone(), vec![batch])
+                    .await?;
+                tag::tag_spots(&ids, &plan_tag(&draw.period))?;
+                ids
+            }
+            None => Vec::new(),
+        };
+        planned.push(PlannedBatch {
+            period: draw.period,
+            draw_time: draw.time,
+            spot_ids,
+        });
+    }
+    log::info!(
+        "Generated spots ahead for {} of the next {periods} periods",
+        planned
+            .iter()
+            .filter(|batch| !batch.spot_ids.is_empty())
+            .count()
+    );
+    Ok(planned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generate_spots_for_periods_bounds() {
+        assert!(generate_spots_for_periods(0, None).await.is_err());
+        assert!(
+            generate_spots_for_periods(MAX_PLANNED_PERIODS + 1, None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_spots_for_periods() -> anyhow::Result<()> {
+        use crate::db::{spot, tag};
+
+        let planned = generate_spots_for_periods(3, Some("Anneal")).await?;
+        assert_eq!(planned.len(), 3);
+        let periods = planned
+            .iter()
+            .map(|batch| batch.period.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()?;
+        // after the latest draw of the fixture, a new year starting over at 001
+        assert!(periods[0] > 2_025_088, "{periods:?}");
+        assert!(
+            periods
+                .windows(2)
+                .all(|pair| pair[1] == pair[0] + 1 || pair[1] % 1000 == 1),
+            "{periods:?}"
+        );
+        assert!(
+            planned
+                .windows(2)
+                .all(|pair| pair[0].draw_time < pair[1].draw_time)
+        );
+
+        for batch in &planned {
+            assert!(!batch.spot_ids.is_empty(), "{batch:?}");
+            let saved = spot::get_spots_by_period(&batch.period)?;
+            let expected = plan_tag(&batch.period);
+            for id in &batch.spot_ids {
+                assert!(saved.iter().any(|spot| spot.id == Some(*id)), "{batch:?}");
+                assert!(
+                    tag::get_spot_tags(*id)?
+                        .iter()
+                        .any(|tag| tag.name == expected),
+                    "spot {id} is not tagged {expected}"
+                );
+            }
+        }
+        Ok(())
+    }
+}
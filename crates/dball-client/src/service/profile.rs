@@ -0,0 +1,34 @@
+use crate::db::profile;
+use crate::models::Profile;
+
+/// Id of the profile `name`, created on first use, names differing only in
+/// surrounding whitespace or case are the same profile
+#[tracing::instrument]
+pub async fn profile_id(name: &str) -> anyhow::Result<i32> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        anyhow::bail!("Profile name cannot be empty");
+    }
+    profile::get_or_create_profile(&name)?
+        .id
+        .ok_or_else(|| anyhow::anyhow!(crate::NEVER_NONE_BY_DATABASE))
+}
+
+/// Every profile sharing the daemon, the default one first
+pub async fn list_profiles() -> anyhow::Result<Vec<Profile>> {
+    profile::get_all_profiles()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DEFAULT_PROFILE_ID;
+
+    #[tokio::test]
+    async fn test_profile_id() -> anyhow::Result<()> {
+        assert_eq!(profile_id(" Default ").await?, DEFAULT_PROFILE_ID);
+        assert_eq!(profile_id("test Bob").await?, profile_id("test bob").await?);
+        assert!(profile_id("  ").await.is_err());
+        Ok(())
+    }
+}
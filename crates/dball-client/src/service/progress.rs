@@ -0,0 +1,262 @@
+//! Progress reporting and cancellation for long-running operations
+//!
+//! Services call [`report`] and [`ensure_not_cancelled`] at their checkpoints, both are
+//! no-ops unless the operation runs inside [`track_progress`], so callers that do not
+//! track their operations are unaffected. Waits that may take long, e.g. requests to
+//! the provider, run in [`cancellable`] to stop without reaching the next checkpoint.
+
+use std::collections::{HashMap, hash_map::Entry};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+/// Progress snapshot of a running operation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct OperationProgress {
+    /// Id of the operation, pass it to [`cancel_operation`] to stop it
+    pub id: String,
+    pub step: usize,
+    /// `None` when the number of steps is not known in advance
+    pub total: Option<usize>,
+    pub message: String,
+}
+
+type ProgressCallback = Arc<dyn Fn(OperationProgress) + Send + Sync>;
+
+/// Cancellation of an operation, a flag for blocking work to poll and a token
+/// for async work to wait on, both set together
+#[derive(Clone, Default)]
+struct Cancellation {
+    flag: Arc<AtomicBool>,
+    token: CancellationToken,
+}
+
+impl Cancellation {
+    fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+        self.token.cancel();
+    }
+}
+
+#[derive(Clone)]
+struct ProgressContext {
+    id: String,
+    cancellation: Cancellation,
+    on_progress: ProgressCallback,
+}
+
+tokio::task_local! {
+    static CONTEXT: ProgressContext;
+}
+
+/// Cancellations of the operations currently tracked, by id
+static RUNNING: LazyLock<Mutex<HashMap<String, Cancellation>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Removes the operation from [`RUNNING`] even when its future is dropped early
+struct RunningGuard(String);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.0);
+    }
+}
+
+/// Error of [`track_progress`] given the id of an operation still running
+#[derive(Debug, thiserror::Error)]
+#[error("Operation {0} is already running")]
+pub struct OperationRunning(pub String);
+
+/// Run `operation` as a cancellable operation with the given id,
+/// every [`report`] made inside it is passed to `on_progress`
+///
+/// Fails with [`OperationRunning`] without running it when another operation
+/// is tracked under the same id
+pub async fn track_progress<F, T>(
+    id: String,
+    on_progress: impl Fn(OperationProgress) + Send + Sync + 'static,
+    operation: F,
+) -> Result<T, OperationRunning>
+where
+    F: Future<Output = T>,
+{
+    let cancellation = Cancellation::default();
+    match RUNNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(id.clone())
+    {
+        Entry::Occupied(_) => return Err(OperationRunning(id)),
+        Entry::Vacant(entry) => entry.insert(cancellation.clone()),
+    };
+    let _guard = RunningGuard(id.clone());
+
+    let context = ProgressContext {
+        id,
+        cancellation,
+        on_progress: Arc::new(on_progress),
+    };
+    Ok(CONTEXT.scope(context, operation).await)
+}
+
+/// Report the progress of the current operation
+pub fn report(step: usize, total: Option<usize>, message: impl Into<String>) {
+    let context = CONTEXT.try_with(Clone::clone).ok();
+    send(context.as_ref(), step, total, message.into());
+}
+
+/// [`report`] for blocking work on other threads, which do not know the current operation
+pub fn reporter() -> impl Fn(usize, Option<usize>, String) + Send + Sync + 'static {
+    let context = CONTEXT.try_with(Clone::clone).ok();
+    move |step, total, message| send(context.as_ref(), step, total, message)
+}
+
+fn send(context: Option<&ProgressContext>, step: usize, total: Option<usize>, message: String) {
+    log::debug!("Progress {step}/{total:?}: {message}");
+    let Some(context) = context else {
+        log::trace!("Progress reported outside of a tracked operation");
+        return;
+    };
+    (context.on_progress)(OperationProgress {
+        id: context.id.clone(),
+        step,
+        total,
+        message,
+    });
+}
+
+/// Error of an operation stopped by [`cancel_operation`]
+#[derive(Debug, thiserror::Error)]
+#[error("Operation cancelled")]
+pub struct OperationCancelled;
+
+/// Fail with [`OperationCancelled`] if the current operation was cancelled
+pub fn ensure_not_cancelled() -> anyhow::Result<()> {
+    let cancelled = CONTEXT
+        .try_with(|context| context.cancellation.token.is_cancelled())
+        .unwrap_or(false);
+    if cancelled {
+        return Err(OperationCancelled.into());
+    }
+    Ok(())
+}
+
+/// Cancel flag of the current operation for blocking work to poll, never set
+/// outside of a tracked operation
+pub fn cancel_flag() -> Arc<AtomicBool> {
+    CONTEXT
+        .try_with(|context| context.cancellation.flag.clone())
+        .unwrap_or_default()
+}
+
+/// Cancellation token of the current operation for async work to wait on,
+/// never cancelled outside of a tracked operation
+pub fn cancellation_token() -> CancellationToken {
+    CONTEXT
+        .try_with(|context| context.cancellation.token.clone())
+        .unwrap_or_default()
+}
+
+/// Run `future` to its end, or drop it once the current operation is
+/// cancelled and fail with [`OperationCancelled`]
+pub async fn cancellable<T>(future: impl Future<Output = anyhow::Result<T>>) -> anyhow::Result<T> {
+    cancellation_token()
+        .run_until_cancelled(future)
+        .await
+        .unwrap_or_else(|| Err(OperationCancelled.into()))
+}
+
+/// Request cancellation of a running operation, returns whether it was found
+///
+/// The operation stops at its next checkpoint, not immediately
+pub async fn cancel_operation(id: String) -> anyhow::Result<bool> {
+    let running = RUNNING.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(cancellation) = running.get(&id) else {
+        log::warn!("No running operation with id {id}");
+        return Ok(false);
+    };
+    log::info!("Cancelling operation {id}");
+    cancellation.cancel();
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_track_progress_and_cancel() -> anyhow::Result<()> {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let id = "test-operation".to_owned();
+
+        let result = track_progress(
+            id.clone(),
+            move |progress| {
+                sink.lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(progress);
+            },
+            async {
+                report(1, Some(2), "first");
+                ensure_not_cancelled()?;
+                assert!(cancel_operation(id.clone()).await?);
+                report(2, Some(2), "second");
+                ensure_not_cancelled()
+            },
+        )
+        .await?;
+
+        assert!(result.is_err());
+        assert!(!cancel_operation(id).await?);
+        let reports = reports.lock().unwrap_or_else(PoisonError::into_inner);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].message, "second");
+
+        // Outside of a tracked operation both calls are no-ops
+        report(1, None, "untracked");
+        ensure_not_cancelled()
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_stops_waiting() -> anyhow::Result<()> {
+        let id = "test-cancellable".to_owned();
+        let result = track_progress(id.clone(), |_| {}, async {
+            let (waited, cancelled) = tokio::join!(
+                cancellable(std::future::pending::<anyhow::Result<()>>()),
+                cancel_operation(id.clone()),
+            );
+            assert!(cancelled?);
+            assert!(cancel_flag().load(Ordering::Relaxed));
+            waited
+        })
+        .await?;
+        assert!(result.is_err_and(|e| e.is::<OperationCancelled>()));
+
+        // Outside of a tracked operation the future runs to its end
+        assert_eq!(cancellable(async { Ok(1) }).await?, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_operation_is_rejected() -> anyhow::Result<()> {
+        let id = "test-duplicate".to_owned();
+        track_progress(id.clone(), |_| {}, async {
+            let duplicate = track_progress(id.clone(), |_| {}, async {}).await;
+            assert!(duplicate.is_err());
+            // the rejected run leaves the first one cancellable
+            assert!(cancel_operation(id.clone()).await?);
+            ensure_not_cancelled()
+        })
+        .await?
+        .expect_err("The first run is cancelled");
+        assert!(!cancel_operation(id).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::db::{purchase, spot};
+use crate::models::{Investment, NewPurchase, PageRequest, Purchase};
+
+/// Record tickets bought at a store and mark their spots purchased, returns
+/// the id of the purchase
+#[tracing::instrument]
+pub async fn record_purchase(new: NewPurchase) -> anyhow::Result<i32> {
+    let mut spot_ids = new.spot_ids;
+    spot_ids.sort_unstable();
+    spot_ids.dedup();
+    if spot_ids.is_empty() {
+        anyhow::bail!("A purchase needs at least one spot");
+    }
+    if new.cost < 0 {
+        anyhow::bail!("Cost of a purchase cannot be negative, got {}", new.cost);
+    }
+    for &id in &spot_ids {
+        let target =
+            spot::get_spot_by_id(id)?.ok_or_else(|| anyhow::anyhow!("Spot {id} not found"))?;
+        if target.deprecated {
+            anyhow::bail!("Spot {id} is deprecated and cannot be purchased");
+        }
+    }
+
+    let now = crate::clock::now();
+    let record = Purchase {
+        id: None,
+        purchase_date: new.date.unwrap_or_else(|| now.date_naive()),
+        store: new.store.filter(|store| !store.trim().is_empty()),
+        cost: new.cost,
+        note: new.note.filter(|note| !note.trim().is_empty()),
+        created_time: now.naive_utc(),
+        profile_id: crate::profile::owner(),
+    };
+    let id = purchase::insert_purchase(&record, &spot_ids)?;
+    log::info!(
+        "Recorded purchase {id} of {} spots for {} yuan",
+        spot_ids.len(),
+        record.cost
+    );
+    Ok(id)
+}
+
+/// Yuan paid for the recorded purchases against the price of every spot not
+/// deprecated
+pub async fn investment() -> anyhow::Result<Investment> {
+    let (_, realized) = purchase::purchases_total()?;
+    let theoretical = spot::get_all_spots(PageRequest::default())?
+        .items
+        .iter()
+        .filter(|spot| !spot.deprecated)
+        .map(|spot| {
+            spot.cost().unwrap_or_else(|e| {
+                log::warn!("Spot {:?} has no price: {e}", spot.id);
+                0
+            })
+        })
+        .sum::<usize>();
+    Ok(Investment {
+        realized: realized as f64,
+        theoretical: theoretical as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Spot;
+    use dball_combora::dball::DBall;
+
+    #[tokio::test]
+    async fn test_record_purchase() -> anyhow::Result<()> {
+        let spots = [[4, 10, 13, 19, 25, 32], [5, 11, 16, 20, 24, 30]]
+            .into_iter()
+            .map(|reds| Spot::from_dball("2025997", &DBall::new_one(reds, 9)?, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let spot_ids = spot::insert_spots_batch(&spots)?;
+        let before = investment().await?;
+
+        let new = NewPurchase {
+            spot_ids: spot_ids.clone(),
+            date: None,
+            store: Some("彩票站 0231".to_owned()),
+            cost: 4,
+            note: None,
+        };
+        let id = record_purchase(new.clone()).await?;
+        assert_eq!(purchase::get_purchase_spot_ids(id)?, spot_ids);
+        for spot_id in &spot_ids {
+            let bought = spot::get_spot_by_id(*spot_id)?.ok_or(anyhow::anyhow!("Spot missing"))?;
+            assert!(bought.purchased);
+        }
+        assert!(investment().await?.realized >= before.realized + 4.0);
+
+        let missing = NewPurchase {
+            spot_ids: vec![i32::MAX],
+            ..new.clone()
+        };
+        assert!(record_purchase(missing).await.is_err());
+        assert!(
+            record_purchase(NewPurchase { cost: -1, ..new })
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use dball_combora::checker::CheckDetail;
+use dball_combora::dball::{DBall, DBallBatch};
+#[cfg(not(target_arch = "wasm32"))]
+use dball_combora::generator::bluemorn::BlueMorn;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::models::RecommendRequest;
+
+use super::NumberFrequencies;
+
+/// A candidate batch with why it ranks where it does
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    /// Name of the generator, e.g. `BlueMorn`
+    pub generator: String,
+    pub spots: Vec<DBall>,
+    /// Decimal seed reproducing the batch, `None` when no single seed drew it
+    pub seed: Option<String>,
+    /// `check_score` times `history_score`, candidates are ranked by it
+    pub score: f64,
+    /// Score the generator gave the batch
+    pub generator_score: f64,
+    /// Chance of keeping the batch after its failed checks, 1 when it passed them all
+    pub check_score: f64,
+    /// How well the numbers follow the configured frequency bias, 1 for a
+    /// neutral batch, above it for numbers the bias leans towards
+    pub history_score: f64,
+    /// Failed checks of the batch and its spots, worst first
+    pub diagnostics: Vec<CheckDetail>,
+}
+
+/// Fit of `batch` to the hot numbers of `stats` for a positive `bias` or the
+/// cold ones for a negative one, 1 without history or bias
+///
+/// Each number is as hot as its rank, from 1 for the hottest to 0 for the
+/// coldest of its color, the mean heat of the batch raised to `bias` shifted
+/// so a heat of 0.5 scores 1.
+pub fn history_score(stats: &NumberFrequencies, batch: &DBallBatch, bias: f64) -> f64 {
+    if stats.draws == 0 || batch.0.is_empty() {
+        return 1.0;
+    }
+    let heat = |pool: &[super::NumberStat], number: u8| {
+        let coldest = pool.len().saturating_sub(1).max(1) as f64;
+        pool.get(usize::from(number).wrapping_sub(1))
+            .map_or(0.5, |stat| {
+                1.0 - stat.rank.saturating_sub(1) as f64 / coldest
+            })
+    };
+    let heats = batch
+        .0
+        .iter()
+        .flat_map(|dball| {
+            dball
+                .rball
+                .iter()
+                .map(|red| heat(&stats.red, *red))
+                .chain([heat(&stats.blue, dball.bball)])
+        })
+        .collect::<Vec<_>>();
+    let mean = heats.iter().sum::<f64>() / heats.len() as f64;
+    (0.5 + mean).powf(bias)
+}
+
+/// Generate `request.candidates` batches with the requested or configured
+/// generator and return the best `request.top` of them, nothing is saved
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument]
+pub async fn recommend(request: RecommendRequest) -> anyhow::Result<Vec<Recommendation>> {
+    let _timer = crate::metrics::time("recommend");
+    if request.top == 0 || request.candidates < request.top {
+        anyhow::bail!("Recommend between 1 and the number of candidates");
+    }
+    if request.candidates > RecommendRequest::MAX_CANDIDATES {
+        anyhow::bail!(
+            "At most {} candidates can be generated",
+            RecommendRequest::MAX_CANDIDATES
+        );
+    }
+
+    let config = super::get_config().await?.generator;
+    let kind = config.generator(request.strategy.as_deref())?;
+    let stats = super::number_frequencies(Some(config.frequency_window)).await?;
+    // not `Send`, so loaded after the last await
+    let generator = super::spot::load_generator(kind, &config)?;
+    let cancel = super::progress::cancel_flag();
+
+    let mut recommendations = Vec::with_capacity(request.candidates);
+    for candidate in 0..request.candidates {
+        super::progress::ensure_not_cancelled()?;
+        super::progress::report(
+            candidate,
+            Some(request.candidates),
+            format!("Generating candidate {}", candidate + 1),
+        );
+        let (batch, log) = super::spot::generate_logged_batch(
+            generator.as_ref(),
+            kind,
+            config.batch_size,
+            &cancel,
+            &|_| {},
+        )?;
+        let diagnostics = batch.evaluate_detailed();
+        let checks = diagnostics
+            .iter()
+            .map(|detail| detail.check)
+            .collect::<Vec<_>>();
+        let check_score = BlueMorn::score(&checks);
+        let history_score = history_score(&stats, &batch, config.bias());
+        recommendations.push(Recommendation {
+            generator: log.generator,
+            spots: batch.0,
+            seed: log.seed,
+            score: check_score * history_score,
+            generator_score: log.score,
+            check_score,
+            history_score,
+            diagnostics,
+        });
+    }
+
+    recommendations.sort_by(|a, b| b.score.total_cmp(&a.score));
+    recommendations.truncate(request.top);
+    log::info!(
+        "Recommending {} of {} {} batches, best score {:.3}",
+        recommendations.len(),
+        request.candidates,
+        kind.name(),
+        recommendations.first().map_or(0.0, |best| best.score)
+    );
+    Ok(recommendations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Ticket;
+
+    fn dball(reds: [u8; 6], blue: u8) -> DBall {
+        DBall::new_one(reds.to_vec(), blue).expect("Valid test ball")
+    }
+
+    #[test]
+    fn test_history_score() {
+        let tickets = [
+            ([1, 2, 3, 4, 5, 6], 1),
+            ([1, 2, 3, 4, 5, 7], 1),
+            ([1, 2, 3, 4, 5, 8], 2),
+        ]
+        .map(|(reds, blue)| {
+            Ticket::new("2025001".to_owned(), "2025-01-01 21:15:00", &reds, blue)
+                .expect("Valid test ticket")
+        });
+        let stats = NumberFrequencies::from_tickets(3, &tickets);
+        let hot = DBallBatch(vec![dball([1, 2, 3, 4, 5, 6], 1)]);
+        let cold = DBallBatch(vec![dball([28, 29, 30, 31, 32, 33], 16)]);
+
+        assert!(history_score(&stats, &hot, 1.0) > 1.0);
+        assert!(history_score(&stats, &cold, 1.0) < 1.0);
+        assert!(history_score(&stats, &cold, -1.0) > 1.0);
+        assert!((history_score(&stats, &hot, 0.0) - 1.0).abs() < f64::EPSILON);
+
+        let empty = NumberFrequencies::from_tickets(3, &[]);
+        assert!((history_score(&empty, &hot, 1.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_recommend_rejects_empty_top() {
+        let request = RecommendRequest {
+            top: 0,
+            ..RecommendRequest::default()
+        };
+        assert!(recommend(request).await.is_err());
+    }
+}
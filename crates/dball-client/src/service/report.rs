@@ -0,0 +1,184 @@
+use crate::models::{PrizeClaim, Spot};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use {super::spot::get_prized_spots, crate::models::PageRequest};
+
+/// Cost and winnings of all settled spots in one period
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PeriodRoi {
+    pub period: String,
+    pub spots: Vec<Spot>,
+    pub cost: u64,
+    pub winnings: u64,
+    /// Yuan paid out for the claimed prizes of the spots
+    #[serde(default)]
+    pub claimed: u64,
+}
+
+impl PeriodRoi {
+    pub fn net(&self) -> i64 {
+        self.winnings as i64 - self.cost as i64
+    }
+}
+
+/// Outcome of evaluating one period, see `service::evaluate_period`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PeriodEvaluation {
+    /// ROI of the period for the profile that asked
+    pub roi: PeriodRoi,
+    /// Change of the winnings of every profile in yuan, e.g. for the total
+    /// return of the daemon state
+    pub winnings_change: i64,
+}
+
+/// Return on investment over settled spots, newest period first
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoiReport {
+    pub periods: Vec<PeriodRoi>,
+    pub total_cost: u64,
+    pub total_winnings: u64,
+    /// Yuan paid out for the claimed prizes, the realized part of the winnings
+    #[serde(default)]
+    pub total_claimed: u64,
+}
+
+impl RoiReport {
+    /// Group settled spots by period, keeping the newest period first
+    pub fn from_spots(spots: Vec<Spot>) -> Self {
+        let mut periods: Vec<PeriodRoi> = Vec::new();
+
+        for spot in spots {
+            let cost = spot_cost(&spot);
+            let winnings = spot_winnings(&spot);
+
+            match periods.iter_mut().find(|p| p.period == spot.period) {
+                Some(period) => {
+                    period.cost += cost;
+                    period.winnings += winnings;
+                    period.spots.push(spot);
+                }
+                None => periods.push(PeriodRoi {
+                    period: spot.period.clone(),
+                    spots: vec![spot],
+                    cost,
+                    winnings,
+                    claimed: 0,
+                }),
+            }
+        }
+
+        periods.sort_by(|a, b| b.period.cmp(&a.period));
+
+        Self {
+            total_cost: periods.iter().map(|p| p.cost).sum(),
+            total_winnings: periods.iter().map(|p| p.winnings).sum(),
+            total_claimed: 0,
+            periods,
+        }
+    }
+
+    /// Add the amounts paid out for `claims` to the periods of their spots
+    pub fn with_claims(mut self, claims: &[PrizeClaim]) -> Self {
+        for claim in claims {
+            let period = self.periods.iter_mut().find(|period| {
+                period
+                    .spots
+                    .iter()
+                    .any(|spot| spot.id == Some(claim.spot_id))
+            });
+            if let Some(period) = period {
+                period.claimed += claim.amount.max(0) as u64;
+            }
+        }
+        self.total_claimed = self.periods.iter().map(|p| p.claimed).sum();
+        self
+    }
+
+    pub fn net(&self) -> i64 {
+        self.total_winnings as i64 - self.total_cost as i64
+    }
+}
+
+/// Cost of a spot, deprecated spots were never bought
+fn spot_cost(spot: &Spot) -> u64 {
+    if spot.deprecated {
+        return 0;
+    }
+    spot.cost().map(|cost| cost as u64).unwrap_or_else(|e| {
+        log::warn!("Failed to compute cost of spot {:?}: {e}", spot.id);
+        0
+    })
+}
+
+/// Winnings of a spot, `prize_status` stores the total the spot won with the
+/// prize table of its period, every bet of a complex spot and its
+/// magnification included
+pub(super) fn spot_winnings(spot: &Spot) -> u64 {
+    if spot.deprecated {
+        return 0;
+    }
+    spot.prize_status.unwrap_or(0).max(0) as u64
+}
+
+/// Build the ROI report over all settled spots
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn roi_report() -> anyhow::Result<RoiReport> {
+    let spots = get_prized_spots(PageRequest::default()).await?.items;
+    let claims = crate::db::prize_claim::get_all_claims()?;
+    Ok(RoiReport::from_spots(spots).with_claims(&claims))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::spot;
+
+    #[test]
+    fn test_roi_report_groups_by_period() {
+        let mut deprecated = spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(3000));
+        deprecated.deprecated = true;
+        let report = RoiReport::from_spots(vec![
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(0)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(5)),
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(200)),
+            deprecated,
+        ]);
+
+        assert_eq!(report.periods.len(), 2);
+        assert_eq!(report.periods[0].period, "2025002");
+        assert_eq!(report.periods[0].cost, 2);
+        assert_eq!(report.periods[0].winnings, 5);
+        assert_eq!(report.periods[1].cost, 4);
+        assert_eq!(report.periods[1].winnings, 200);
+        assert_eq!(report.total_cost, 6);
+        assert_eq!(report.total_winnings, 205);
+        assert_eq!(report.net(), 199);
+        assert_eq!(report.total_claimed, 0);
+    }
+
+    #[test]
+    fn test_roi_report_with_claims() {
+        let mut spots = vec![
+            spot("2025001", [1, 2, 3, 4, 5, 6], 7, Some(200)),
+            spot("2025002", [1, 2, 3, 4, 5, 6], 7, Some(5)),
+        ];
+        spots[0].id = Some(1);
+        spots[1].id = Some(2);
+        let claim = PrizeClaim {
+            id: Some(1),
+            spot_id: 1,
+            claim_date: chrono::NaiveDate::default(),
+            amount: 180,
+            note: None,
+            created_time: chrono::NaiveDateTime::default(),
+            profile_id: 1,
+        };
+        let report = RoiReport::from_spots(spots).with_claims(&[claim]);
+
+        assert_eq!(report.periods[1].claimed, 180);
+        assert_eq!(report.periods[0].claimed, 0);
+        assert_eq!(report.total_winnings, 205);
+        assert_eq!(report.total_claimed, 180);
+    }
+}
@@ -0,0 +1,165 @@
+//! Pipeline the daemon runs after every draw, see [`crate::config::SchedulerConfig`]
+//!
+//! Each step is retried on its own, a step giving up stops the run, and every
+//! run is recorded as a [`PipelineRun`]
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::db::pipeline_run;
+use crate::models::PipelineRun;
+use crate::schedule::{DrawSchedule as _, SSQ};
+
+/// Steps of the pipeline, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStep {
+    UpdateLatestTicket,
+    UpdateUnprizeSpots,
+    GenerateBatchSpots,
+}
+
+impl PipelineStep {
+    pub const ALL: [Self; 3] = [
+        Self::UpdateLatestTicket,
+        Self::UpdateUnprizeSpots,
+        Self::GenerateBatchSpots,
+    ];
+
+    /// Name of the service the step calls, as recorded in [`PipelineRun::failed_step`]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UpdateLatestTicket => "update_latest_ticket",
+            Self::UpdateUnprizeSpots => "update_all_unprize_spots",
+            Self::GenerateBatchSpots => "generate_batch_spots",
+        }
+    }
+
+    /// Run the step once for the draw of `draw_date`
+    async fn run(self, draw_date: NaiveDate) -> anyhow::Result<()> {
+        match self {
+            // the API may not have the draw yet, failing lets the step retry
+            Self::UpdateLatestTicket => {
+                let ticket = super::update_latest_ticket().await?;
+                if ticket.time.date() < draw_date {
+                    anyhow::bail!(
+                        "Draw of {draw_date} not published yet, latest is {}",
+                        ticket.period
+                    );
+                }
+                Ok(())
+            }
+            Self::UpdateUnprizeSpots => super::update_all_unprize_spots().await.map(drop),
+            Self::GenerateBatchSpots => super::generate_batch_spots(None).await,
+        }
+    }
+}
+
+/// Run the steps after the draw at `draw_time` with the retries of `[scheduler]`
+/// and record the run, a step giving up is part of the record, not an error
+#[tracing::instrument]
+pub async fn run_post_draw_pipeline(draw_time: DateTime<Utc>) -> anyhow::Result<PipelineRun> {
+    let _timer = crate::metrics::time("run_post_draw_pipeline");
+    let config = super::get_config().await?;
+    let schedule = config.draw_schedule(SSQ)?;
+    let draw_date = draw_time.with_timezone(&schedule.timezone()).date_naive();
+    let retry_delay = Duration::from_secs(config.scheduler.retry_secs);
+
+    let mut run = PipelineRun {
+        id: None,
+        draw_time: draw_time.naive_utc(),
+        started_time: crate::clock::now().naive_utc(),
+        finished_time: crate::clock::now().naive_utc(),
+        completed_steps: 0,
+        attempts: 0,
+        failed_step: None,
+        error: None,
+    };
+    for step in PipelineStep::ALL {
+        let (attempts, result) = retry(config.scheduler.max_attempts, retry_delay, || {
+            step.run(draw_date)
+        })
+        .await;
+        run.attempts += attempts;
+        match result {
+            Ok(()) => run.completed_steps += 1,
+            Err(e) => {
+                log::warn!("Pipeline step {} gave up: {e:#}", step.name());
+                run.failed_step = Some(step.name().to_owned());
+                run.error = Some(format!("{e:#}"));
+                break;
+            }
+        }
+    }
+    run.finished_time = crate::clock::now().naive_utc();
+    log::info!(
+        "Pipeline after the draw of {draw_date} finished {} of {} steps in {} attempts",
+        run.completed_steps,
+        PipelineStep::ALL.len(),
+        run.attempts
+    );
+
+    pipeline_run::insert_pipeline_run(&run)?;
+    Ok(run)
+}
+
+/// The latest `limit` runs of the pipeline, the latest one first
+pub async fn pipeline_runs(limit: i64) -> anyhow::Result<Vec<PipelineRun>> {
+    pipeline_run::get_latest_pipeline_runs(limit)
+}
+
+/// Call `attempt` until it succeeds or was made `max_attempts` times, at least
+/// once, waiting `delay` in between, returns the attempts made and the last result
+pub(super) async fn retry<F, Fut>(
+    max_attempts: u32,
+    delay: Duration,
+    mut attempt: F,
+) -> (i32, anyhow::Result<()>)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match attempt().await {
+            Ok(()) => return (attempts, Ok(())),
+            Err(e) if attempts >= max_attempts.max(1) as i32 => return (attempts, Err(e)),
+            Err(e) => {
+                log::debug!("Attempt {attempts} of {max_attempts} failed, retrying: {e:#}");
+                crate::clock::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry() {
+        let delay = Duration::from_millis(1);
+        let mut calls = 0;
+        let (attempts, result) = retry(3, delay, || {
+            calls += 1;
+            let failing = calls < 3;
+            async move {
+                if failing {
+                    anyhow::bail!("not yet");
+                }
+                Ok(())
+            }
+        })
+        .await;
+        assert_eq!(attempts, 3);
+        assert!(result.is_ok());
+
+        let (attempts, result) = retry(2, delay, || async { anyhow::bail!("never") }).await;
+        assert_eq!(attempts, 2);
+        assert!(result.is_err());
+
+        let (attempts, _) = retry(0, delay, || async { Ok(()) }).await;
+        assert_eq!(attempts, 1);
+    }
+}
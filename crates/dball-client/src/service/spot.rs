@@ -1,64 +1,60 @@
-use crate::db::{spot, tickets};
-use crate::models::Spot;
+use crate::config::{DuplicatePolicy, GeneratorConfig};
+use crate::db::{draw_detail, generation_log, spot, tag, ticket_log, tickets};
+use crate::metrics;
+use crate::models::{
+    DuplicateSpots, GenerationLog, ManualSpot, Page, PageRequest, Spot, SpotDeprecation,
+    SpotFilter, SpotQuery,
+};
+use crate::schedule::{DrawSchedule as _, SSQ};
 use crate::service::ticket::update_this_year_ticket;
-use chrono::{DateTime, Datelike as _, Duration, TimeZone as _, Utc, Weekday};
-use dball_combora::dball::DBall;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use dball_combora::dball::{
+    BatchSummary, ComplexBet, DBall, DBallBatch, DantuoBet, PrizeTable, Reward,
+};
+use dball_combora::generator::{GenerationProgress, Generator, RandomGenerator};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 
-use super::ticket;
+use super::events::{self, DaemonEvent};
+use super::report::spot_winnings;
+use super::{PeriodEvaluation, PeriodRoi, RoiReport, budget, notify, progress, ticket};
 
-pub async fn next_draw_time(time: Option<DateTime<Utc>>) -> anyhow::Result<DateTime<Utc>> {
-    const BEIJING_OFFSET_HOURS: i64 = 8;
-    let open_time = chrono::NaiveTime::from_hms_opt(21, 20, 0)
-        .ok_or_else(|| anyhow::anyhow!("Invalid draw time"))?;
-
-    let base_time = time.unwrap_or_else(Utc::now);
-    let beijing_time = base_time + Duration::hours(BEIJING_OFFSET_HOURS);
-    let current_weekday = beijing_time.weekday();
-
-    let days_offset = match current_weekday {
-        Weekday::Mon | Weekday::Wed | Weekday::Sat => 1,
-        Weekday::Fri => 2,
-        Weekday::Thu => {
-            if beijing_time.time() < open_time {
-                0
-            } else {
-                3
-            }
-        }
-        Weekday::Tue | Weekday::Sun => {
-            if beijing_time.time() < open_time {
-                0
-            } else {
-                2
-            }
-        }
-    };
+/// Id, simple bets and current prize status of a spot to settle
+type SpotBets = (i32, Vec<DBall>, Option<i32>);
 
-    let next_draw_beijing = beijing_time.date_naive() + Duration::days(days_offset);
-    let next_draw_time = next_draw_beijing.and_time(open_time);
+/// Tag of the spots saved under [`DuplicatePolicy::Flag`] though they repeat another one
+pub const DUPLICATE_TAG: &str = "duplicate";
 
-    let next_draw_utc =
-        Utc.from_utc_datetime(&next_draw_time) - Duration::hours(BEIJING_OFFSET_HOURS);
+/// Spots waiting for a draw above which no more are generated for it
+pub(super) const WAITING_SPOTS_LIMIT: usize = 10;
 
-    Ok(next_draw_utc)
+/// First draw of the game after `time`, or after now without one
+pub async fn next_draw_time(time: Option<DateTime<Utc>>) -> anyhow::Result<DateTime<Utc>> {
+    let schedule = super::get_config().await?.draw_schedule(SSQ)?;
+    let base_time = time.unwrap_or_else(crate::clock::now);
+    schedule
+        .next_draw(base_time)
+        .ok_or_else(|| anyhow::anyhow!("No draw scheduled within a year after {base_time}"))
 }
 
+#[tracing::instrument(skip_all)]
 pub async fn update_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
+    let _timer = metrics::time("update_all_unprize_spots");
     let spots = spot::get_all_unprize_spots()?;
 
     if spots.is_empty() {
         log::info!("No unprized spots found, nothing to update");
-        return get_prized_spots().await;
+        return Ok(get_prized_spots(PageRequest::default()).await?.items);
     }
 
     // Update the current year's ticket to ensure we have the latest data
+    progress::report(0, None, "Updating this year's tickets");
     update_this_year_ticket().await?;
 
     let next_period = ticket::get_next_period().await?;
 
     log::debug!("Found {} unprized spots", spots.len());
-    let mut spots_by_period: HashMap<String, Vec<(i32, DBall, Option<i32>)>> = HashMap::new();
+    let mut spots_by_period: HashMap<String, Vec<SpotBets>> = HashMap::new();
     for spot in spots {
         if spot.period == next_period {
             log::debug!("Skipping spot for next period",);
@@ -69,14 +65,20 @@ pub async fn update_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
             .or_default()
             .push((
                 spot.id.expect(crate::NEVER_NONE_BY_DATABASE),
-                TryFrom::try_from(spot.clone())?,
+                spot.bets()?,
                 spot.prize_status, // Include current prize status
             ));
     }
 
     let mut errors = Vec::new();
-    #[expect(clippy::iter_over_hash_type)]
-    for (spot_period, dballs_to_check) in spots_by_period {
+    let period_count = spots_by_period.len();
+    for (step, (spot_period, dballs_to_check)) in spots_by_period.into_iter().enumerate() {
+        progress::ensure_not_cancelled()?;
+        progress::report(
+            step,
+            Some(period_count),
+            format!("Checking spots of period {spot_period}"),
+        );
         log::debug!(
             "Processing {} spots for period {spot_period}",
             dballs_to_check.len()
@@ -89,21 +91,9 @@ pub async fn update_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
             continue;
         };
 
-        // update the spot by checking with the opened dball
-        for dball_to_check in dballs_to_check {
-            let reward_price = dball_to_check.1.check_prize(&opened_ball).to_i32();
-
-            match spot::update_spot_prize_status_by_id(dball_to_check.0, Some(reward_price)) {
-                Ok(()) => {
-                    log::debug!(
-                        "Updated spot for id {id} with reward level {reward_price}",
-                        id = dball_to_check.0
-                    );
-                }
-                Err(e) => {
-                    errors.push(e.to_string());
-                }
-            }
+        let prizes = prize_table(&spot_period)?;
+        if let Err(e) = settle_period(&spot_period, &dballs_to_check, &opened_ball, &prizes) {
+            errors.push(format!("Period {spot_period}: {e}"));
         }
     }
 
@@ -113,48 +103,482 @@ pub async fn update_all_unprize_spots() -> anyhow::Result<Vec<Spot>> {
     }
 
     log::info!("Completed updating all spots");
-    get_prized_spots().await
+    Ok(get_prized_spots(PageRequest::default()).await?.items)
 }
 
-pub async fn generate_batch_spots() -> anyhow::Result<()> {
-    use dball_combora::generator::RandomGenerator as _;
+/// Fetch the draw of `period` unless it is saved already, settle every spot of
+/// the period against it at once and return the ROI of the period for the
+/// active profile, announced as a single [`DaemonEvent::PeriodEvaluated`]
+///
+/// Settled spots are checked again, e.g. after the prize table of the period
+/// changed. Prizes are sent out as when the daemon settles spots, those of
+/// spots settled before only when their status changed
+#[tracing::instrument]
+pub async fn evaluate_period(period: &str) -> anyhow::Result<PeriodEvaluation> {
+    if tickets::get_ticket_by_period(period)?.is_none() {
+        progress::report(0, Some(2), format!("Fetching the draw of period {period}"));
+        ticket::update_tickets_by_period(period, false).await?;
+    }
+    let drawn = tickets::get_ticket_by_period(period)?
+        .ok_or_else(|| anyhow::anyhow!("Period {period} has not been drawn yet"))?
+        .to_dball()?;
+
+    progress::report(1, Some(2), format!("Checking spots of period {period}"));
+    let all_spots = spot::get_all_spots_by_period(period)?;
+    let spots = all_spots
+        .iter()
+        .map(|spot| {
+            Ok((
+                spot.id.expect(crate::NEVER_NONE_BY_DATABASE),
+                spot.bets()?,
+                spot.prize_status,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<SpotBets>>>()?;
+    let statuses = settle_period(period, &spots, &drawn, &prize_table(period)?)?;
+
+    let winnings = |spots: &[Spot]| spots.iter().map(spot_winnings).sum::<u64>();
+    let settled = all_spots
+        .iter()
+        .zip(&statuses)
+        .map(|(spot, &(_, status))| Spot {
+            prize_status: Some(status),
+            ..spot.clone()
+        })
+        .collect::<Vec<_>>();
+    let winnings_change = winnings(&settled) as i64 - winnings(&all_spots) as i64;
+
+    let roi = RoiReport::from_spots(spot::get_spots_by_period(period)?)
+        .periods
+        .pop()
+        .unwrap_or_else(|| PeriodRoi {
+            period: period.to_owned(),
+            spots: Vec::new(),
+            cost: 0,
+            winnings: 0,
+            claimed: 0,
+        });
+    log::info!(
+        "Evaluated {} spots of period {period}, cost {} won {}",
+        statuses.len(),
+        roi.cost,
+        roi.winnings
+    );
+    events::publish(DaemonEvent::PeriodEvaluated {
+        period: period.to_owned(),
+        spots: statuses.len(),
+        winnings: roi.winnings,
+    });
+    Ok(PeriodEvaluation {
+        roi,
+        winnings_change,
+    })
+}
+
+/// Prize table of `period`, the first two prizes float and are paid as the
+/// provider or the archive reported for the period when known
+fn prize_table(period: &str) -> anyhow::Result<PrizeTable> {
+    Ok(match draw_detail::get_draw_detail(period)? {
+        Some(detail) => detail.prize_table(),
+        None => ticket_log::get_record_by_code(period)?
+            .map(|record| record.prize_table())
+            .unwrap_or_default(),
+    })
+}
 
-    let generator = dball_combora::generator::bluemorn::BlueMorn;
-    if get_next_period_unprized_spots().await?.len().ge(&10) {
-        log::warn!("There are already more than 10 unprized spots, skipping generation");
+/// Settle `spots` of `period` together against the `drawn` ball, returning
+/// the prize status of each one
+///
+/// Once the statuses are saved the wins of the spots whose status changed are
+/// published and sent out, so settling a period again announces nothing new
+fn settle_period(
+    period: &str,
+    spots: &[SpotBets],
+    drawn: &DBall,
+    prizes: &PrizeTable,
+) -> anyhow::Result<Vec<(i32, i32)>> {
+    let (statuses, _) = check_period(spots, drawn, prizes);
+    spot::update_prize_statuses(&statuses)?;
+    log::debug!("Settled {} spots of period {period}", statuses.len());
+
+    let changed = spots
+        .iter()
+        .zip(&statuses)
+        .filter(|((_, _, before), (_, status))| *before != Some(*status))
+        .map(|(spot, _)| spot.clone())
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return Ok(statuses);
+    }
+    let (_, wins) = check_period(&changed, drawn, prizes);
+    for (reward, amount) in &wins {
+        events::publish(DaemonEvent::PrizeWon {
+            period: period.to_owned(),
+            tier: reward.description().to_owned(),
+            amount: *amount,
+        });
+    }
+    events::publish(DaemonEvent::DrawSettled {
+        period: period.to_owned(),
+        spots: changed.len(),
+    });
+    notify::notify_settled(notify::SettledDraw {
+        period: period.to_owned(),
+        drawn: *drawn,
+        spots: changed.len(),
+        wins,
+    });
+    Ok(statuses)
+}
+
+/// Prize amount of each spot of a period against the `drawn` ball, along with
+/// the prize and its amount for every winning bet, a complex or 胆拖 spot wins
+/// the total of every bet it covers
+fn check_period(
+    spots: &[SpotBets],
+    drawn: &DBall,
+    prizes: &PrizeTable,
+) -> (Vec<(i32, i32)>, Vec<notify::BetPrize>) {
+    let mut statuses = Vec::with_capacity(spots.len());
+    let mut wins = Vec::new();
+    for (id, bets, _) in spots {
+        let amount = bets
+            .iter()
+            .map(|bet| bet.check_prize_amount(drawn, prizes))
+            .fold(0, u32::saturating_add);
+        statuses.push((*id, i32::try_from(amount).unwrap_or(i32::MAX)));
+        wins.extend(
+            bets.iter()
+                .map(|bet| (bet.check_prize(drawn), bet))
+                .filter(|(reward, _)| *reward != Reward::NoWin)
+                .map(|(reward, bet)| (reward, bet.check_prize_amount(drawn, prizes))),
+        );
+    }
+    (statuses, wins)
+}
+
+/// `kind` ready to use with the configured random numbers, history aware
+/// generators learn from the latest drawn tickets
+pub(super) fn load_generator(
+    kind: Generator,
+    config: &GeneratorConfig,
+) -> anyhow::Result<Box<dyn RandomGenerator>> {
+    let mut generator = Generator::create_generator_with_history(kind, &[], config.bias());
+    generator.set_rng(config.rng_backend());
+    let Some(aware) = generator.history_aware() else {
+        return Ok(generator);
+    };
+
+    let window = i64::try_from(config.frequency_window).unwrap_or(i64::MAX);
+    let history = tickets::get_latest_tickets(window)?
+        .iter()
+        .rev()
+        .map(crate::models::Ticket::to_dball)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    log::debug!(
+        "{} learns from the latest {} draws",
+        kind.name(),
+        history.len()
+    );
+    aware.with_history(&history);
+    Ok(generator)
+}
+
+/// Generate a batch of the configured size for the next period with the
+/// `strategy` named, or the configured one without, unless enough spots are
+/// waiting for the draw already, see [`GeneratorConfig::generator`]
+#[tracing::instrument]
+pub async fn generate_batch_spots(strategy: Option<&str>) -> anyhow::Result<()> {
+    const STEPS: usize = 3;
+
+    let _timer = metrics::time("generate_batch_spots");
+    let config = super::get_config().await?.generator;
+    let generator = config.generator(strategy)?;
+    progress::report(0, Some(STEPS), "Checking unprized spots");
+    if get_next_period_unprized_spots().await?.len() >= WAITING_SPOTS_LIMIT {
+        log::warn!(
+            "There are already {WAITING_SPOTS_LIMIT} or more unprized spots, skipping generation"
+        );
         return Ok(());
     }
 
-    let tickets = generator.generate_batch()?;
-    insert_new_spots_batch_to_next_period(&tickets).await?;
+    progress::ensure_not_cancelled()?;
+    progress::report(1, Some(STEPS), "Generating spots");
+    let cancel = progress::cancel_flag();
+    let report = progress::reporter();
+    let on_progress = |stats: GenerationProgress| {
+        report(1, Some(STEPS), generation_message(stats));
+    };
+    let batch = tracing::info_span!("generate").in_scope(|| {
+        generate_logged_batch(
+            load_generator(generator, &config)?.as_ref(),
+            generator,
+            config.batch_size,
+            &cancel,
+            &on_progress,
+        )
+    });
+
+    progress::ensure_not_cancelled()?;
+    let batch = batch?;
+    progress::report(2, Some(STEPS), "Saving spots");
+    save_generated_batches(vec![batch]).await?;
     Ok(())
 }
 
-pub async fn insert_new_spots_batch_to_next_period(dballs: &[DBall]) -> anyhow::Result<()> {
+/// Generate `count` spots with `generator` for the next period in batches of the
+/// configured size, ignoring the unprized limit
+pub async fn generate_spots(kind: &Generator, count: usize) -> anyhow::Result<Vec<DBall>> {
+    let _timer = metrics::time("generate_spots");
+    let config = super::get_config().await?.generator;
+    let generator = load_generator(*kind, &config)?;
+    let cancel = progress::cancel_flag();
+    let report = progress::reporter();
+    let (mut batches, mut generated) = (Vec::new(), 0);
+    while generated < count {
+        let size = config.batch_size.clamp(1, count - generated);
+        let on_progress = |stats: GenerationProgress| {
+            report(generated, Some(count), generation_message(stats));
+        };
+        let batch = generate_logged_batch(generator.as_ref(), *kind, size, &cancel, &on_progress);
+        progress::ensure_not_cancelled()?;
+        let batch = batch?;
+        generated += batch.0.0.len();
+        batches.push(batch);
+    }
+    let dballs = batches
+        .iter()
+        .flat_map(|(batch, _)| batch.0.iter().copied())
+        .collect();
+    save_generated_batches(batches).await?;
+    Ok(dballs)
+}
+
+/// Batch of `size` spots from `generator` of the kind `kind`, with the log of
+/// how it was picked to save it with, its period is left empty
+pub(super) fn generate_logged_batch(
+    generator: &dyn RandomGenerator,
+    kind: Generator,
+    size: usize,
+    cancel: &AtomicBool,
+    on_progress: &(dyn Fn(GenerationProgress) + Sync),
+) -> anyhow::Result<(DBallBatch, GenerationLog)> {
+    let started = std::time::Instant::now();
+    let last = std::sync::Mutex::new(None::<GenerationProgress>);
+    let batch = generator.generate_tracked_batch(size, cancel, &|stats| {
+        if let Ok(mut last) = last.lock()
+            && last.is_none_or(|last| stats.tries >= last.tries)
+        {
+            *last = Some(stats);
+        }
+        on_progress(stats);
+    })?;
+
+    let stats = last.into_inner().ok().flatten();
+    let log = GenerationLog {
+        id: None,
+        period: String::new(),
+        generator: kind.name().to_owned(),
+        seed: stats
+            .and_then(|stats| stats.seed)
+            .map(|seed| seed.to_string()),
+        score: generator.evaluate_batch(&batch),
+        attempts: stats.map_or(1, |stats| i32::try_from(stats.tries).unwrap_or(i32::MAX)),
+        duration_ms: i64::try_from(started.elapsed().as_millis()).unwrap_or(i64::MAX),
+        created_time: crate::clock::now().naive_utc(),
+    };
+    Ok((batch, log))
+}
+
+/// Save generated batches as spots of the next period linked to their logs in
+/// one transaction, returns the ids of the spots
+async fn save_generated_batches(
+    batches: Vec<(DBallBatch, GenerationLog)>,
+) -> anyhow::Result<Vec<i32>> {
+    save_generated_batches_for(ticket::get_next_period().await?, batches).await
+}
+
+/// [`save_generated_batches`] as spots of `next_period`, a period still to draw
+pub(super) async fn save_generated_batches_for(
+    next_period: String,
+    batches: Vec<(DBallBatch, GenerationLog)>,
+) -> anyhow::Result<Vec<i32>> {
+    let batches = batches
+        .into_iter()
+        .map(|(batch, log)| {
+            let spots = batch
+                .0
+                .iter()
+                .map(|dball| Spot::from_dball(&next_period, dball, None).map(owned_spot))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow::anyhow!("Error creating spot from DBall: {e}"))?;
+            let log = GenerationLog {
+                period: next_period.clone(),
+                ..log
+            };
+            log::info!(
+                "{} picked {} spots with score {:.3} after {} attempts",
+                log.generator,
+                spots.len(),
+                log.score,
+                log.attempts
+            );
+            Ok((log, spots))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let spots = batches
+        .iter()
+        .flat_map(|(_, spots)| spots.iter().cloned())
+        .collect::<Vec<_>>();
+    budget::ensure_period_within_budget(&next_period, budget::spots_cost(spots.iter())?).await?;
+    let flagged = check_duplicates(&spots).await?;
+    let ids = generation_log::insert_generated_spots(&batches)?;
+    flag_duplicates(&ids, &flagged)?;
+    metrics::increment(metrics::SPOTS_GENERATED, ids.len() as u64);
+    events::publish(DaemonEvent::GenerationFinished {
+        period: next_period,
+        spots: ids.len(),
+    });
+    Ok(ids)
+}
+
+pub(super) fn generation_message(stats: GenerationProgress) -> String {
+    format!(
+        "Generating spots, {} tries, best score {:.3}",
+        stats.tries, stats.best_score
+    )
+}
+
+/// Save `dballs` as spots of the next period in one transaction, returns their ids
+#[tracing::instrument(skip_all, fields(spots = dballs.len()))]
+pub async fn insert_new_spots_batch_to_next_period(dballs: &[DBall]) -> anyhow::Result<Vec<i32>> {
+    let next_period = ticket::get_next_period().await?;
+
+    let spots = dballs
+        .iter()
+        .map(|dball| Spot::from_dball(&next_period, dball, None))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("Error creating spot from DBall: {e}"))?;
+    let ids = save_spots(spots).await?;
+    metrics::increment(metrics::SPOTS_GENERATED, ids.len() as u64);
+    events::publish(DaemonEvent::GenerationFinished {
+        period: next_period,
+        spots: ids.len(),
+    });
+    Ok(ids)
+}
+
+/// Save numbers picked by hand as a spot of the next period, returns the period
+pub async fn add_manual_spot(manual: ManualSpot) -> anyhow::Result<String> {
+    let dball = manual.to_dball()?;
+    let next_period = ticket::get_next_period().await?;
+    save_spots(vec![Spot::from_dball(&next_period, &dball, None)?]).await?;
+    log::info!("Added manual spot {dball} to period {next_period}");
+    Ok(next_period)
+}
+
+/// Save a complex bet as a single spot of the next period, returns the period
+pub async fn add_complex_spot(bet: ComplexBet) -> anyhow::Result<String> {
+    let next_period = ticket::get_next_period().await?;
+    save_spots(vec![Spot::from_complex(&next_period, &bet, None)?]).await?;
+    log::info!(
+        "Added complex spot {bet} of {} bets to period {next_period}",
+        bet.bets()
+    );
+    Ok(next_period)
+}
+
+/// Save a 胆拖 bet as a single spot of the next period, returns the period
+pub async fn add_dantuo_spot(bet: DantuoBet) -> anyhow::Result<String> {
     let next_period = ticket::get_next_period().await?;
+    save_spots(vec![Spot::from_dantuo(&next_period, &bet, None)?]).await?;
+    log::info!(
+        "Added dantuo spot {bet} of {} bets to period {next_period}",
+        bet.bets()
+    );
+    Ok(next_period)
+}
 
-    for dball in dballs {
-        spot::insert_spot_from_dball(&next_period, dball, None)?;
+/// `new_spot` owned by the profile of the current request
+fn owned_spot(new_spot: Spot) -> Spot {
+    Spot {
+        profile_id: crate::profile::owner(),
+        ..new_spot
+    }
+}
+
+/// Save `spots` for the profile of the current request in one transaction
+/// under the duplicate policy of the config, returns their ids
+async fn save_spots(spots: Vec<Spot>) -> anyhow::Result<Vec<i32>> {
+    let spots = spots.into_iter().map(owned_spot).collect::<Vec<_>>();
+    budget::ensure_within_budget(budget::spots_cost(spots.iter())?).await?;
+    let flagged = check_duplicates(&spots).await?;
+    let ids = spot::insert_spots_batch(&spots)?;
+    flag_duplicates(&ids, &flagged)?;
+    Ok(ids)
+}
+
+/// Apply the duplicate policy of the config to `spots` before saving them,
+/// returns the positions of those to flag once saved
+async fn check_duplicates(spots: &[Spot]) -> anyhow::Result<Vec<usize>> {
+    let policy = super::get_config().await?.spots.duplicates;
+    guard_duplicates(spots, policy)
+}
+
+fn guard_duplicates(spots: &[Spot], policy: DuplicatePolicy) -> anyhow::Result<Vec<usize>> {
+    if policy == DuplicatePolicy::Allow {
+        return Ok(Vec::new());
+    }
+    let mut seen = HashSet::new();
+    let mut positions = Vec::new();
+    for (position, new_spot) in spots.iter().enumerate() {
+        if !seen.insert(new_spot.numbers_key())
+            || !spot::get_spot_ids_with_numbers(new_spot)?.is_empty()
+        {
+            positions.push(position);
+        }
+    }
+    if policy == DuplicatePolicy::Reject
+        && let Some(&position) = positions.first()
+    {
+        let reds = spots[position].red_numbers();
+        anyhow::bail!(
+            "Spot {reds:?} + {} repeats another spot of period {}",
+            spots[position].blue,
+            spots[position].period
+        );
+    }
+    Ok(positions)
+}
+
+/// Tag the spots of `ids` at the positions `flagged` with [`DUPLICATE_TAG`]
+fn flag_duplicates(ids: &[i32], flagged: &[usize]) -> anyhow::Result<()> {
+    let duplicates = flagged
+        .iter()
+        .filter_map(|&position| ids.get(position).copied())
+        .collect::<Vec<_>>();
+    if !duplicates.is_empty() {
+        tag::tag_spots(&duplicates, DUPLICATE_TAG)?;
+        log::warn!("Saved duplicate spots {duplicates:?}, tagged {DUPLICATE_TAG}");
     }
     Ok(())
 }
 
-pub async fn deprecated_last_batch_unprized_spot() -> anyhow::Result<usize> {
-    use crate::db::spot;
+/// Live spots repeating another one of their period, for cleaning them up
+pub async fn duplicate_spots() -> anyhow::Result<Vec<DuplicateSpots>> {
+    spot::find_duplicate_spots()
+}
 
-    // Get the latest 5 unprized spots (prize_status = None)
-    let latest_unprized_spots = spot::get_latest_unprized_spots(5)?;
+/// Deprecate the unprized spots of the latest generated batch on behalf of `actor`
+pub async fn deprecated_last_batch_unprized_spot(actor: &str) -> anyhow::Result<usize> {
+    use crate::db::spot;
 
-    if latest_unprized_spots.is_empty() {
+    let Some(latest) = spot::get_latest_unprized_spots(1)?.pop() else {
         log::info!("No unprized spots found to deprecate");
         return Ok(0);
-    }
-
-    // Extract IDs, only considering spots with id
-    let spot_ids: Vec<i32> = latest_unprized_spots
-        .into_iter()
-        .filter_map(|s| s.id)
-        .collect();
+    };
+    let batch_size = super::get_config().await?.generator.batch_size;
+    let spot_ids = last_batch_spot_ids(&latest, batch_size)?;
 
     if spot_ids.is_empty() {
         log::warn!("No valid spot IDs found for deprecation");
@@ -167,26 +591,209 @@ pub async fn deprecated_last_batch_unprized_spot() -> anyhow::Result<usize> {
         spot_ids
     );
 
-    let updated_count = spot::mark_spots_deprecated(&spot_ids)?;
+    let updated_count = spot::mark_spots_deprecated(&spot_ids, actor, Some("last unprized batch"))?;
 
     log::info!("Successfully marked {updated_count} spots as deprecated");
     Ok(updated_count)
 }
 
-pub async fn get_prized_spots() -> anyhow::Result<Vec<Spot>> {
+/// Ids of the live unprized spots of the batch `latest` was generated in, or
+/// of the latest `batch_size` unprized spots for a spot generated before
+/// batches were logged
+fn last_batch_spot_ids(latest: &Spot, batch_size: usize) -> anyhow::Result<Vec<i32>> {
+    let logged = match latest.id {
+        Some(id) => generation_log::get_generation_log_by_spot_id(id)?.and_then(|log| log.id),
+        None => None,
+    };
+    let batch = match logged {
+        Some(generation_id) => generation_log::get_generation_spot_ids(generation_id)?
+            .into_iter()
+            .filter_map(|id| spot::get_spot_by_id(id).transpose())
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => spot::get_latest_unprized_spots(i64::try_from(batch_size).unwrap_or(i64::MAX))?,
+    };
+    Ok(batch
+        .into_iter()
+        .filter(|spot| spot.prize_status.is_none() && !spot.deprecated)
+        .filter_map(|spot| spot.id)
+        .collect())
+}
+
+/// Look up one spot, failing if it does not exist
+fn find_spot(id: i32) -> anyhow::Result<Spot> {
+    spot::get_spot_by_id(id)?.ok_or_else(|| anyhow::anyhow!("Spot {id} not found"))
+}
+
+/// Deprecate a single spot that has not been checked against a draw yet on
+/// behalf of `actor`
+pub async fn deprecate_spot(id: i32, actor: &str) -> anyhow::Result<()> {
+    let target = find_spot(id)?;
+    if target.prize_status.is_some() {
+        anyhow::bail!("Spot {id} of period {} is already settled", target.period);
+    }
+    if target.deprecated {
+        anyhow::bail!("Spot {id} is already deprecated");
+    }
+
+    spot::mark_spots_deprecated(&[id], actor, Some("single spot"))?;
+    log::info!("Marked spot {id} of period {} as deprecated", target.period);
+    Ok(())
+}
+
+/// Restore deprecated spots on behalf of `actor`, those of the latest
+/// deprecation without any ids, returns the ids of the spots restored
+pub async fn restore_spots(
+    ids: Vec<i32>,
+    actor: &str,
+    reason: Option<String>,
+) -> anyhow::Result<Vec<i32>> {
+    let ids = if ids.is_empty() {
+        spot::get_last_deprecated_spot_ids()?
+    } else {
+        for &id in &ids {
+            find_spot(id)?;
+        }
+        ids
+    };
+    if ids.is_empty() {
+        log::info!("No deprecated spots to restore");
+        return Ok(ids);
+    }
+
+    let reason = reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let restored = spot::restore_spots(&ids, actor, reason)?;
+    log::info!("Restored {} spots: {restored:?}", restored.len());
+    Ok(restored)
+}
+
+/// Every time the spot was deprecated or restored, oldest first
+pub async fn spot_deprecation_history(id: i32) -> anyhow::Result<Vec<SpotDeprecation>> {
+    find_spot(id)?;
+    spot::get_deprecation_history(id)
+}
+
+/// Set the note of a spot, an empty note clears it
+pub async fn annotate_spot(id: i32, note: Option<String>) -> anyhow::Result<()> {
+    find_spot(id)?;
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    spot::update_spot_note_by_id(id, note)?;
+    log::info!("Updated note of spot {id}");
+    Ok(())
+}
+
+/// Mark whether a spot was actually bought
+pub async fn mark_spot_purchased(id: i32, purchased: bool) -> anyhow::Result<()> {
+    let target = find_spot(id)?;
+    if purchased && target.deprecated {
+        anyhow::bail!("Spot {id} is deprecated and cannot be purchased");
+    }
+
+    spot::update_spot_purchased_by_id(id, purchased)?;
+    log::info!("Marked spot {id} as purchased: {purchased}");
+    Ok(())
+}
+
+/// The spots of `page` checked against a draw, deprecated ones included
+pub async fn get_prized_spots(page: PageRequest) -> anyhow::Result<Page<Spot>> {
+    crate::db::spot::get_prized_spots(page)
+}
+
+/// The spots of `page` matching `filter`, all conditions checked by the database
+pub async fn find_spots(filter: SpotFilter, page: PageRequest) -> anyhow::Result<Page<Spot>> {
+    filter.validate()?;
+    spot::find_spots(&filter, page)
+}
+
+/// Search spots matching the query, sorted by period in descending order
+pub async fn query_spots(query: SpotQuery) -> anyhow::Result<Vec<Spot>> {
     use crate::db::spot;
-    let mut prized_spots = spot::get_all_spots()?
+    let tagged = tagged_spot_ids(&query)?;
+
+    let mut spots = spot::get_all_spots(PageRequest::default())?
+        .items
         .into_iter()
-        .filter_map(|s| match s.prize_status {
-            Some(_) => Some(s), // All spots with prize status including deprecated
-            _ => None,
-        })
+        .filter(|s| query.matches(s) && carries_tags(tagged.as_ref(), s))
         .collect::<Vec<Spot>>();
+    spots.sort_by(|a, b| b.period.cmp(&a.period));
+
+    log::debug!("Query {query:?} matched {} spots", spots.len());
+    Ok(spots)
+}
+
+/// Ids of the spots carrying every tag of `query`, tags live in their own
+/// table so [`SpotQuery::matches`] cannot check them
+fn tagged_spot_ids(query: &SpotQuery) -> anyhow::Result<Option<HashSet<i32>>> {
+    if query.tags.is_empty() {
+        return Ok(None);
+    }
+    let ids = crate::db::tag::get_spot_ids_with_tags(&query.tags)?;
+    Ok(Some(ids.into_iter().collect()))
+}
+
+fn carries_tags(tagged: Option<&HashSet<i32>>, spot: &Spot) -> bool {
+    tagged.is_none_or(|ids| spot.id.is_some_and(|id| ids.contains(&id)))
+}
+
+/// Spots read from the database per page, so large results never sit in memory at once
+pub const SPOT_PAGE_SIZE: i64 = 500;
+
+/// Spots matching the query in pages of at most [`SPOT_PAGE_SIZE`], newest period first
+///
+/// Unlike [`query_spots`] only one page is loaded at a time, the HTTP and IPC
+/// servers stream each page to the client as soon as it is read
+pub fn spot_pages(query: SpotQuery) -> anyhow::Result<SpotPages> {
+    Ok(SpotPages {
+        tagged: tagged_spot_ids(&query)?,
+        query,
+        after: None,
+        done: false,
+    })
+}
+
+/// Iterator returned by [`spot_pages`], every page holds at least one spot
+#[derive(Debug)]
+pub struct SpotPages {
+    query: SpotQuery,
+    /// Ids of the spots carrying every tag of the query, `None` without tags
+    tagged: Option<HashSet<i32>>,
+    /// `(period, id)` of the last spot read
+    after: Option<(String, i32)>,
+    done: bool,
+}
+
+impl Iterator for SpotPages {
+    type Item = anyhow::Result<Vec<Spot>>;
 
-    prized_spots.sort_by_key(|s| s.period.clone());
-    prized_spots.reverse(); // Sort by period in descending order
+    fn next(&mut self) -> Option<Self::Item> {
+        use crate::db::spot;
+        while !self.done {
+            let after = self
+                .after
+                .as_ref()
+                .map(|(period, id)| (period.as_str(), *id));
+            let page = match spot::get_spots_page(after, SPOT_PAGE_SIZE) {
+                Ok(page) => page,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            self.after = page
+                .last()
+                .and_then(|last| Some((last.period.clone(), last.id?)));
+            // a short page is the last one, a spot without id could not be continued after
+            self.done = page.len() < SPOT_PAGE_SIZE as usize || self.after.is_none();
 
-    Ok(prized_spots)
+            let matched = page
+                .into_iter()
+                .filter(|s| self.query.matches(s) && carries_tags(self.tagged.as_ref(), s))
+                .collect::<Vec<Spot>>();
+            if !matched.is_empty() {
+                return Some(Ok(matched));
+            }
+        }
+        None
+    }
 }
 
 /// Excluding deprecated spots
@@ -208,15 +815,31 @@ pub async fn get_next_period_unprized_spots() -> anyhow::Result<Vec<Spot>> {
     Ok(unprized_spots)
 }
 
+/// Statistics of `spots` as one batch, a complex or 胆拖 spot counts every bet it covers
+pub fn spots_summary(spots: &[Spot]) -> BatchSummary {
+    let dballs = spots
+        .iter()
+        .filter_map(|spot| spot.bets().ok())
+        .flatten()
+        .collect();
+    DBallBatch(dballs).summary()
+}
+
+/// [`spots_summary`] of the spots waiting for the next draw
+pub async fn next_batch_summary() -> anyhow::Result<BatchSummary> {
+    Ok(spots_summary(&get_next_period_unprized_spots().await?))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use chrono::NaiveDate;
+    use crate::models::SortOrder;
+    use chrono::{NaiveDate, TimeZone as _};
 
     #[tokio::test]
     async fn bluemorn_insert_dball_batch() -> anyhow::Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        let generator = dball_combora::generator::bluemorn::BlueMorn;
+        let generator = dball_combora::generator::bluemorn::BlueMorn::default();
         let tickets = generator.generate_multiple(5);
         insert_new_spots_batch_to_next_period(&tickets).await?;
         Ok(())
@@ -306,4 +929,255 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_evaluate_period() -> anyhow::Result<()> {
+        // other tests may publish at the same time
+        fn announced(
+            receiver: &mut tokio::sync::broadcast::Receiver<DaemonEvent>,
+        ) -> Vec<DaemonEvent> {
+            std::iter::from_fn(|| receiver.try_recv().ok())
+                .filter(|event| match event {
+                    DaemonEvent::PrizeWon { period, .. }
+                    | DaemonEvent::DrawSettled { period, .. } => period == "2025087",
+                    _ => false,
+                })
+                .collect()
+        }
+
+        let mut receiver = events::subscribe_events();
+        // drawn in the fixture, one of its spots has four reds and the blue
+        let evaluation = evaluate_period("2025087").await?;
+        assert_eq!(evaluation.roi.period, "2025087");
+        assert_eq!(evaluation.roi.spots.len(), 2);
+        assert_eq!(evaluation.roi.winnings, 200);
+        // the prize is sent out like the ones of the spots the daemon settles
+        assert_eq!(
+            announced(&mut receiver),
+            [
+                DaemonEvent::PrizeWon {
+                    period: "2025087".to_owned(),
+                    tier: Reward::FourthPrize.description().to_owned(),
+                    amount: 200,
+                },
+                DaemonEvent::DrawSettled {
+                    period: "2025087".to_owned(),
+                    spots: 2,
+                },
+            ]
+        );
+
+        // evaluating again changes nothing, nor announces the prize again
+        let again = evaluate_period("2025087").await?;
+        assert_eq!(again.roi, evaluation.roi);
+        assert_eq!(again.winnings_change, 0);
+        assert!(announced(&mut receiver).is_empty());
+
+        assert!(evaluate_period("2099999").await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_settled_magnification_reported_once() -> anyhow::Result<()> {
+        let drawn = DBall::new_one([1, 5, 17, 23, 26, 30], 12)?;
+        // four reds and the blue, bought three times
+        let mut magnified = crate::db::testing::spot("2025087", [1, 5, 17, 23, 28, 32], 12, None);
+        magnified.magnification = 3;
+        let spots = [(1, magnified.bets()?, None)];
+
+        let (statuses, wins) = check_period(&spots, &drawn, &PrizeTable::default());
+        assert_eq!(statuses, [(1, 600)]);
+        assert_eq!(wins, [(Reward::FourthPrize, 600)]);
+
+        magnified.prize_status = Some(statuses[0].1);
+        let report = RoiReport::from_spots(vec![magnified]);
+        assert_eq!(report.total_cost, 6);
+        assert_eq!(report.total_winnings, 600);
+        assert_eq!(report.periods[0].winnings, 600);
+
+        // the bets of a spot add up without overflowing on a huge floating prize
+        let jackpot = PrizeTable::new(Some(u32::MAX / 2 + 1), None);
+        let (statuses, _) = check_period(&[(2, vec![drawn; 2], None)], &drawn, &jackpot);
+        assert_eq!(statuses, [(2, i32::MAX)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_batch_of_any_size() -> anyhow::Result<()> {
+        let period = "2025986";
+        let logged_batch = |reds: std::ops::RangeInclusive<u8>| -> anyhow::Result<_> {
+            let spots = reds
+                .map(|red| {
+                    let dball =
+                        DBall::new_one([red, red + 2, red + 4, red + 6, red + 8, red + 20], 1)?;
+                    Ok(Spot::from_dball(period, &dball, None)?)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let log = GenerationLog {
+                id: None,
+                period: period.to_owned(),
+                generator: "BlueMorn".to_owned(),
+                seed: None,
+                score: 0.5,
+                attempts: 1,
+                duration_ms: 1,
+                created_time: Utc::now().naive_utc(),
+            };
+            generation_log::insert_generated_spots(&[(log, spots)])
+        };
+        // batches of 10 and 3 while the default size is 5
+        let ten = logged_batch(1..=10)?;
+        let three = logged_batch(11..=13)?;
+
+        assert_eq!(last_batch_spot_ids(&find_spot(three[0])?, 5)?, three);
+        assert_eq!(last_batch_spot_ids(&find_spot(ten[9])?, 5)?, ten);
+        spot::mark_spots_deprecated(&ten[..1], "test", None)?;
+        assert_eq!(last_batch_spot_ids(&find_spot(ten[9])?, 5)?, ten[1..]);
+
+        // spots generated before batches were logged go by the configured size
+        let unlogged = spot::get_latest_unprized_spots(1)?
+            .pop()
+            .map(|spot| Spot { id: None, ..spot })
+            .ok_or(anyhow::anyhow!("No unprized spot"))?;
+        assert!(last_batch_spot_ids(&unlogged, 7)?.len() <= 7);
+
+        spot::mark_spots_deprecated(&[&ten[1..], &three].concat(), "test", None)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_actions() -> anyhow::Result<()> {
+        let dball = DBall::new(vec![2, 9, 14, 21, 27, 33], 11, 1)
+            .map_err(|e| anyhow::anyhow!("DBall creation failed: {e}"))?;
+        let period = "2099001";
+        spot::insert_spot_from_dball(period, &dball, None)?;
+        let id = spot::get_spots_by_period(period)?
+            .iter()
+            .rev()
+            .find_map(|s| s.id)
+            .ok_or(anyhow::anyhow!("Inserted spot has no id"))?;
+
+        annotate_spot(id, Some("  office pool ".to_owned())).await?;
+        mark_spot_purchased(id, true).await?;
+        let updated = find_spot(id)?;
+        assert_eq!(updated.note.as_deref(), Some("office pool"));
+        assert!(updated.purchased);
+
+        crate::service::tag_spots(vec![id], "test office pool".to_owned()).await?;
+        let tagged = SpotQuery {
+            tags: vec!["test office pool".to_owned()],
+            ..SpotQuery::default()
+        };
+        let found = query_spots(tagged).await?;
+        assert_eq!(found.iter().filter_map(|s| s.id).collect::<Vec<_>>(), [id]);
+
+        annotate_spot(id, Some(String::new())).await?;
+        deprecate_spot(id, "cli").await?;
+        let updated = find_spot(id)?;
+        assert!(updated.note.is_none());
+        assert!(updated.deprecated);
+        assert!(deprecate_spot(id, "cli").await.is_err());
+
+        assert_eq!(restore_spots(vec![id], "cli", None).await?, [id]);
+        assert!(!find_spot(id)?.deprecated);
+        assert_eq!(spot_deprecation_history(id).await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_pages() -> anyhow::Result<()> {
+        let query = SpotQuery {
+            period_prefix: Some("20250".to_owned()),
+            ..SpotQuery::default()
+        };
+        let paged = spot_pages(query.clone())?
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .concat();
+        assert!(paged.windows(2).all(|w| w[0].period >= w[1].period));
+
+        let mut paged_ids = paged.iter().filter_map(|s| s.id).collect::<Vec<_>>();
+        let mut queried_ids = query_spots(query)
+            .await?
+            .iter()
+            .filter_map(|s| s.id)
+            .collect::<Vec<_>>();
+        paged_ids.sort_unstable();
+        queried_ids.sort_unstable();
+        assert!(!paged_ids.is_empty());
+        assert_eq!(paged_ids, queried_ids);
+
+        let tagged = SpotQuery {
+            tags: vec!["test nobody uses this tag".to_owned()],
+            ..SpotQuery::default()
+        };
+        assert_eq!(spot_pages(tagged).map(Iterator::count)?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prized_spots_page() -> anyhow::Result<()> {
+        let all = get_prized_spots(PageRequest::default()).await?;
+        assert_eq!(all.total, all.items.len() as i64);
+        assert!(all.items.iter().all(|s| s.prize_status.is_some()));
+        assert!(all.items.windows(2).all(|w| w[0].period >= w[1].period));
+
+        let page = get_prized_spots(PageRequest::new(1, 2)).await?;
+        assert_eq!(page.total, all.total);
+        assert_eq!(
+            page.items,
+            all.items
+                .iter()
+                .skip(1)
+                .take(2)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        let oldest = get_prized_spots(PageRequest::new(0, 1).with_order(SortOrder::Asc)).await?;
+        assert_eq!(oldest.items.first(), all.items.last());
+        Ok(())
+    }
+
+    #[test]
+    fn test_guard_duplicates() -> anyhow::Result<()> {
+        let period = "2025988";
+        let spot = |reds| -> anyhow::Result<Spot> {
+            Ok(Spot::from_dball(period, &DBall::new_one(reds, 13)?, None)?)
+        };
+        let saved = spot([3, 9, 14, 18, 27, 32])?;
+        spot::insert_spots_batch(std::slice::from_ref(&saved))?;
+        let fresh = spot([4, 10, 15, 19, 28, 33])?;
+        let spots = [fresh.clone(), saved, fresh];
+
+        assert!(guard_duplicates(&spots, DuplicatePolicy::Allow)?.is_empty());
+        assert_eq!(guard_duplicates(&spots, DuplicatePolicy::Flag)?, [1, 2]);
+        assert!(guard_duplicates(&spots, DuplicatePolicy::Reject).is_err());
+        assert!(guard_duplicates(&spots[..1], DuplicatePolicy::Reject)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spots_of_profiles() -> anyhow::Result<()> {
+        use crate::models::DEFAULT_PROFILE_ID;
+        use crate::profile::scope;
+
+        let carol = crate::service::profile_id("test carol").await?;
+        let period = "2025987";
+        let new_spot =
+            Spot::from_dball(period, &DBall::new_one([5, 12, 17, 23, 29, 31], 2)?, None)?;
+        let ids = scope(Some(carol), save_spots(vec![new_spot])).await?;
+        assert_eq!(find_spot(ids[0])?.profile_id, carol);
+        assert!(
+            scope(Some(DEFAULT_PROFILE_ID), async { find_spot(ids[0]) })
+                .await
+                .is_err()
+        );
+
+        let of_period = |profile| scope(profile, async { spot::get_spots_by_period(period) });
+        assert_eq!(of_period(Some(carol)).await?.len(), 1);
+        assert!(of_period(Some(DEFAULT_PROFILE_ID)).await?.is_empty());
+        assert_eq!(of_period(None).await?.len(), 1);
+        Ok(())
+    }
 }
@@ -0,0 +1,246 @@
+//! Summary of the spend, returns and wins of a month or a year, as JSON or
+//! Markdown
+
+use std::collections::HashMap;
+
+use chrono::Datelike as _;
+use serde::{Deserialize, Serialize};
+
+use super::report::RoiReport;
+use crate::models::Ticket;
+
+/// Prize tiers counted in [`PeriodicReport::wins_by_tier`]
+pub const PRIZE_TIERS: usize = 6;
+
+/// Cost and winnings of one period of a [`PeriodicReport`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PeriodNet {
+    pub period: String,
+    pub cost: u64,
+    pub winnings: u64,
+}
+
+impl PeriodNet {
+    pub fn net(&self) -> i64 {
+        self.winnings as i64 - self.cost as i64
+    }
+}
+
+/// Spend, returns and wins of the settled spots drawn in a month or a year
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PeriodicReport {
+    pub year: i32,
+    /// Month from 1, `None` for the whole year
+    pub month: Option<u32>,
+    /// Periods drawn in the month or year with settled spots
+    pub periods: usize,
+    /// Spots bought for those periods, deprecated ones left out
+    pub spots: usize,
+    pub cost: u64,
+    pub winnings: u64,
+    /// Yuan paid out for the claimed prizes
+    pub claimed: u64,
+    /// Winning bets by tier, the first prize first
+    pub wins_by_tier: [usize; PRIZE_TIERS],
+    /// Period with the highest net, `None` without any period
+    pub best: Option<PeriodNet>,
+    /// Period with the lowest net, `None` without any period
+    pub worst: Option<PeriodNet>,
+}
+
+impl PeriodicReport {
+    /// Report of the periods of `roi` drawn in `year`, or in `month` of it,
+    /// told apart by the draw time of their ticket in `tickets`
+    pub fn build(year: i32, month: Option<u32>, roi: &RoiReport, tickets: &[Ticket]) -> Self {
+        let drawn = tickets
+            .iter()
+            .filter(|ticket| {
+                ticket.time.year() == year && month.is_none_or(|month| ticket.time.month() == month)
+            })
+            .map(|ticket| (ticket.period.as_str(), ticket))
+            .collect::<HashMap<_, _>>();
+
+        let mut report = Self {
+            year,
+            month,
+            periods: 0,
+            spots: 0,
+            cost: 0,
+            winnings: 0,
+            claimed: 0,
+            wins_by_tier: [0; PRIZE_TIERS],
+            best: None,
+            worst: None,
+        };
+        for period in &roi.periods {
+            let Some(ticket) = drawn.get(period.period.as_str()) else {
+                continue;
+            };
+            report.periods += 1;
+            report.cost += period.cost;
+            report.winnings += period.winnings;
+            report.claimed += period.claimed;
+
+            let spots = period.spots.iter().filter(|spot| !spot.deprecated);
+            report.spots += spots.clone().count();
+            match ticket.to_dball() {
+                Ok(drawn) => {
+                    let tiers = spots
+                        .filter_map(|spot| spot.bets().ok())
+                        .flatten()
+                        .filter_map(|bet| bet.check_prize(&drawn).tier());
+                    for tier in tiers {
+                        report.wins_by_tier[usize::from(tier) - 1] += 1;
+                    }
+                }
+                Err(e) => log::warn!("Failed to read the draw of period {}: {e}", period.period),
+            }
+
+            let net = PeriodNet {
+                period: period.period.clone(),
+                cost: period.cost,
+                winnings: period.winnings,
+            };
+            if report
+                .best
+                .as_ref()
+                .is_none_or(|best| net.net() > best.net())
+            {
+                report.best = Some(net.clone());
+            }
+            if report
+                .worst
+                .as_ref()
+                .is_none_or(|worst| net.net() < worst.net())
+            {
+                report.worst = Some(net);
+            }
+        }
+        report
+    }
+
+    pub fn net(&self) -> i64 {
+        self.winnings as i64 - self.cost as i64
+    }
+
+    /// `2025-03` for a month, `2025` for a year
+    pub fn title(&self) -> String {
+        match self.month {
+            Some(month) => format!("{}-{month:02}", self.year),
+            None => self.year.to_string(),
+        }
+    }
+
+    /// The report as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Markdown document
+impl std::fmt::Display for PeriodicReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Report {}", self.title())?;
+        writeln!(f)?;
+        writeln!(f, "| | |")?;
+        writeln!(f, "|---|---:|")?;
+        writeln!(f, "| Periods | {} |", self.periods)?;
+        writeln!(f, "| Spots | {} |", self.spots)?;
+        writeln!(f, "| Spend | ¥{} |", self.cost)?;
+        writeln!(f, "| Winnings | ¥{} |", self.winnings)?;
+        writeln!(f, "| Claimed | ¥{} |", self.claimed)?;
+        writeln!(f, "| Net | ¥{} |", self.net())?;
+        writeln!(f)?;
+        writeln!(f, "## Wins by tier")?;
+        writeln!(f)?;
+        writeln!(f, "| Tier | Wins |")?;
+        writeln!(f, "|---|---:|")?;
+        for (tier, wins) in self.wins_by_tier.iter().enumerate() {
+            writeln!(f, "| #{} | {wins} |", tier + 1)?;
+        }
+        for (heading, period) in [("Best period", &self.best), ("Worst period", &self.worst)] {
+            writeln!(f)?;
+            writeln!(f, "## {heading}")?;
+            writeln!(f)?;
+            match period {
+                Some(period) => writeln!(
+                    f,
+                    "{}: spent ¥{}, won ¥{}, net ¥{}",
+                    period.period,
+                    period.cost,
+                    period.winnings,
+                    period.net()
+                )?,
+                None => writeln!(f, "No settled period")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Summary of the settled spots drawn in `year`, or in `month` of it
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn periodic_report(year: i32, month: Option<u32>) -> anyhow::Result<PeriodicReport> {
+    use crate::models::PageRequest;
+
+    if month.is_some_and(|month| !(1..=12).contains(&month)) {
+        anyhow::bail!("Month must be from 1 to 12");
+    }
+    let _timer = crate::metrics::time("periodic_report");
+    let roi = super::roi_report().await?;
+    let tickets = super::get_tickets(Some(year.to_string()), PageRequest::default())
+        .await?
+        .items;
+    Ok(PeriodicReport::build(year, month, &roi, &tickets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::{spot, ticket};
+
+    #[test]
+    fn test_periodic_report() {
+        let roi = RoiReport::from_spots(vec![
+            spot("2025010", [1, 2, 3, 4, 5, 6], 8, Some(5_000_000)),
+            spot("2025010", [10, 11, 12, 13, 14, 15], 7, Some(5)),
+            spot("2025020", [10, 11, 12, 13, 14, 15], 16, Some(0)),
+            spot("2025030", [1, 2, 3, 10, 11, 12], 7, Some(10)),
+        ]);
+        let tickets = [
+            ticket("2025010", "2025-01-23 21:15:00", [1, 2, 3, 4, 5, 6], 7),
+            ticket("2025020", "2025-02-18 21:15:00", [1, 2, 3, 4, 5, 6], 7),
+            ticket("2025030", "2025-03-13 21:15:00", [1, 2, 3, 4, 5, 6], 7),
+        ];
+
+        let february = PeriodicReport::build(2025, Some(2), &roi, &tickets);
+        assert_eq!(february.periods, 1);
+        assert_eq!(february.cost, 2);
+        assert_eq!(february.net(), -2);
+        assert_eq!(february.wins_by_tier, [0; PRIZE_TIERS]);
+        assert_eq!(february.best, february.worst);
+
+        let year = PeriodicReport::build(2025, None, &roi, &tickets);
+        assert_eq!(year.periods, 3);
+        assert_eq!(year.spots, 4);
+        assert_eq!(year.cost, 8);
+        assert_eq!(year.winnings, 5_000_015);
+        assert_eq!(year.wins_by_tier, [0, 1, 0, 0, 1, 1]);
+        assert_eq!(
+            year.best.as_ref().map(|p| p.period.as_str()),
+            Some("2025010")
+        );
+        assert_eq!(
+            year.worst.as_ref().map(|p| p.period.as_str()),
+            Some("2025020")
+        );
+
+        let markdown = year.to_markdown();
+        assert!(markdown.starts_with("# Report 2025\n"));
+        assert!(markdown.contains("| Net | ¥5000007 |"));
+        assert!(markdown.contains("| #2 | 1 |"));
+        assert!(markdown.contains("2025020: spent ¥2, won ¥0, net ¥-2"));
+
+        assert_eq!(PeriodicReport::build(2024, None, &roi, &tickets).best, None);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::db::{spot, tag};
+use crate::models::{Tag, TagCount};
+
+/// Trimmed tag name with inner whitespace collapsed, so `birthday  numbers`
+/// and `birthday numbers` are the same tag
+fn tag_name(tag: &str) -> anyhow::Result<String> {
+    let name = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        anyhow::bail!("Tag name cannot be empty");
+    }
+    Ok(name)
+}
+
+fn spot_ids(mut ids: Vec<i32>) -> anyhow::Result<Vec<i32>> {
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        anyhow::bail!("No spot to tag given");
+    }
+    for &id in &ids {
+        if spot::get_spot_by_id(id)?.is_none() {
+            anyhow::bail!("Spot {id} not found");
+        }
+    }
+    Ok(ids)
+}
+
+/// Label the spots `ids` with `tag`, returns the number of spots newly tagged
+#[tracing::instrument]
+pub async fn tag_spots(ids: Vec<i32>, tag: String) -> anyhow::Result<usize> {
+    let (ids, name) = (spot_ids(ids)?, tag_name(&tag)?);
+    let tagged = tag::tag_spots(&ids, &name)?;
+    log::info!("Tagged {tagged} of {} spots with {name}", ids.len());
+    Ok(tagged)
+}
+
+/// Take `tag` off the spots `ids`, returns the number of spots that carried it
+#[tracing::instrument]
+pub async fn untag_spots(ids: Vec<i32>, tag: String) -> anyhow::Result<usize> {
+    let (ids, name) = (spot_ids(ids)?, tag_name(&tag)?);
+    let untagged = tag::untag_spots(&ids, &name)?;
+    log::info!("Took {name} off {untagged} of {} spots", ids.len());
+    Ok(untagged)
+}
+
+/// Every tag with the number of spots carrying it
+pub async fn list_tags() -> anyhow::Result<Vec<TagCount>> {
+    tag::get_all_tags()
+}
+
+/// Tags of the spot `id`
+pub async fn spot_tags(id: i32) -> anyhow::Result<Vec<Tag>> {
+    if spot::get_spot_by_id(id)?.is_none() {
+        anyhow::bail!("Spot {id} not found");
+    }
+    tag::get_spot_tags(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tag_spots_checks_input() -> anyhow::Result<()> {
+        assert_eq!(tag_name("  birthday   numbers ")?, "birthday numbers");
+        assert!(tag_name(" ").is_err());
+        assert!(tag_spots(Vec::new(), "lucky".to_owned()).await.is_err());
+        assert!(tag_spots(vec![i32::MAX], "lucky".to_owned()).await.is_err());
+        Ok(())
+    }
+}
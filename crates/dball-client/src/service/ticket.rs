@@ -1,36 +1,123 @@
-use crate::models::Ticket;
+use crate::api::ProviderError;
+use crate::config::CrawlConfig;
+use crate::metrics;
+use crate::models::{CrawlReport, DrawDetail, Page, PageRequest, Ticket, TicketMismatch};
+use crate::schedule::SSQ;
 use chrono::Datelike as _;
-const YEAR_MODULO: usize = 100;
+use futures_util::future::join_all;
+use std::time::Duration;
 
-/// Get the next period based on the latest ticket
+use super::events::{self, DaemonEvent};
+use super::{calendar, progress};
+
+fn publish_api_failure(e: &anyhow::Error) {
+    metrics::increment(metrics::API_FAILURES, 1);
+    events::publish(DaemonEvent::ApiFailure {
+        message: e.to_string(),
+    });
+}
+
+/// Keep the pool, sales and winners the provider answered a draw with, a
+/// failure is only logged since the drawn numbers matter more
+fn save_draw_detail(detail: Option<DrawDetail>) {
+    let Some(detail) = detail else {
+        return;
+    };
+    match crate::db::draw_detail::upsert_draw_detail(&detail) {
+        Ok(()) => log::debug!("Saved draw detail of period {}", detail.period),
+        Err(e) => log::warn!(
+            "Failed to save draw detail of period {}: {e}",
+            detail.period
+        ),
+    }
+}
+
+/// Period of the next draw, counted offline on the draw calendar from the
+/// latest stored ticket, see [`calendar::next_period`]
+#[tracing::instrument(skip_all)]
 pub async fn get_next_period() -> anyhow::Result<String> {
-    let latest_period = update_latest_ticket().await?;
-    let next_period = latest_period.period.parse::<i32>()? + 1;
-    log::debug!("Latest period is {latest_period}, next period is {next_period}");
-    Ok(next_period.to_string())
+    let next = get_next_draws(1)
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("No draw scheduled within a year"))?;
+    Ok(next.period)
+}
+
+/// The next `count` draws, counted like [`get_next_period`], see [`calendar::upcoming_periods`]
+pub(super) async fn get_next_draws(count: usize) -> anyhow::Result<Vec<calendar::PeriodDraw>> {
+    use crate::db::tickets;
+
+    let schedule = super::get_config().await?.draw_schedule(SSQ)?;
+    let latest = tickets::get_latest_tickets(1)?.pop();
+    let anchor = latest
+        .as_ref()
+        .map(|ticket| (ticket.period.as_str(), ticket.time.date()));
+    let draws = calendar::upcoming_periods(&schedule, anchor, crate::clock::now(), count);
+    log::debug!(
+        "Latest stored period is {:?}, next periods are {:?}",
+        latest.map(|ticket| ticket.period),
+        draws.iter().map(|draw| &draw.period).collect::<Vec<_>>()
+    );
+    Ok(draws)
 }
 
-pub async fn crawl_all_tickets() -> anyhow::Result<()> {
+/// Crawl the tickets of every year, see [`update_tickets_with_year`]
+pub async fn crawl_all_tickets(dry_run: bool) -> anyhow::Result<CrawlReport> {
     const YEARS: [usize; 23] = [
         2003, 2004, 2005, 2006, 2007, 2008, 2009, 2010, 2011, 2012, 2013, 2014, 2015, 2016, 2017,
         2018, 2019, 2020, 2021, 2022, 2023, 2024, 2025,
     ];
-    for &year in YEARS.iter().rev() {
+    let _timer = metrics::time("crawl_all_tickets");
+    if !dry_run {
+        // crawling rewrites every year, a snapshot taken first allows going back
+        let snapshot = super::maintenance::backup_database(None).await?;
+        log::info!("Snapshot of the database before crawling at {snapshot}");
+    }
+    let mut report = CrawlReport::new(dry_run);
+    for (step, &year) in YEARS.iter().rev().enumerate() {
+        progress::ensure_not_cancelled()?;
+        progress::report(step, Some(YEARS.len()), format!("Crawling year {year}"));
         log::info!("crawl year {year}");
-        update_tickets_with_year(year).await?;
+        crawl_year(year, &mut report).await.inspect_err(|e| {
+            if e.is::<progress::OperationCancelled>() {
+                log::info!(
+                    "Crawl cancelled in year {year}, {} periods inserted until then are kept",
+                    report.inserted.len()
+                );
+            }
+        })?;
     }
-    Ok(())
+    Ok(report)
 }
 
 pub async fn update_this_year_ticket() -> anyhow::Result<()> {
-    let year = chrono::Utc::now().year() as usize;
-    update_tickets_with_year(year).await?;
+    let year = crate::clock::now().year() as usize;
+    update_tickets_with_year(year, false).await?;
     Ok(())
 }
 
-pub async fn update_tickets_with_year(year: usize) -> anyhow::Result<()> {
+/// Fetch the tickets of `year` missing from the database, in a dry run only
+/// report what would be inserted and which stored tickets the provider contradicts
+pub async fn update_tickets_with_year(year: usize, dry_run: bool) -> anyhow::Result<CrawlReport> {
+    let _timer = metrics::time("update_tickets_with_year");
+    let mut report = CrawlReport::new(dry_run);
+    crawl_year(year, &mut report).await?;
+    Ok(report)
+}
+
+/// [`update_tickets_with_year`] adding to `report`, which keeps the periods
+/// inserted before a failure
+async fn crawl_year(year: usize, report: &mut CrawlReport) -> anyhow::Result<()> {
     // Get existing periods for this year from database
     let existing_periods_7digit = get_existing_periods_for_year(year)?;
+    let schedule = super::get_config().await?.draw_schedule(SSQ)?;
+    let known = existing_periods_7digit
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let missing = calendar::missing_periods(&schedule, year as i32, &known, crate::clock::now());
+    let mut crawl = YearCrawlProgress::new(year, missing.len());
+    report.missing.extend(missing);
 
     if let Some(latest_period) = existing_periods_7digit.last() {
         log::info!(
@@ -39,33 +126,59 @@ pub async fn update_tickets_with_year(year: usize) -> anyhow::Result<()> {
         );
 
         // Fill gaps in existing data
-        update_missing_periods(&existing_periods_7digit).await?;
+        let latest_period = *latest_period;
+        update_missing_periods(latest_period, report, &mut crawl).await?;
 
         // Continue from the latest period
-        let latest_period = *latest_period;
         log::info!("Latest period: {latest_period}");
 
-        update_tickets_after_period(latest_period + 1).await?;
+        update_tickets_after_period(latest_period + 1, report, &mut crawl).await?;
     } else {
         log::info!("No existing data for year {year}, starting from period 001");
-        update_year_from_start(year).await?;
+        update_year_from_start(year, report, &mut crawl).await?;
     }
 
     Ok(())
 }
 
+/// The drawn tickets of `page`, only those whose period starts with `period_prefix` if given
+pub async fn get_tickets(
+    period_prefix: Option<String>,
+    page: PageRequest,
+) -> anyhow::Result<Page<Ticket>> {
+    use crate::db::tickets;
+    let Some(prefix) = period_prefix.filter(|prefix| !prefix.is_empty()) else {
+        return tickets::get_all_tickets(page);
+    };
+    // periods are digits only, anything else would be a LIKE wildcard
+    if !prefix.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(Page {
+            items: Vec::new(),
+            total: 0,
+            offset: page.offset,
+        });
+    }
+    tickets::get_tickets_by_period_prefix(&prefix, page)
+}
+
 /// Request and insert latest tickets
 /// Return the latest ticket
+#[tracing::instrument(skip_all)]
 pub async fn update_latest_ticket() -> anyhow::Result<Ticket> {
-    use crate::api::MXNZP_PROVIDER;
+    use crate::api::LOTTERY_PROVIDER;
     use crate::db::tickets;
 
-    let request_latest_ticket = MXNZP_PROVIDER
+    let _timer = metrics::time("update_latest_ticket");
+    let data = LOTTERY_PROVIDER
         .get_latest_lottery()
-        .await?
-        .data
+        .await
+        .inspect_err(publish_api_failure)?
+        .data;
+    save_draw_detail(data.as_ref().and_then(|t| t.draw_detail()));
+    let request_latest_ticket = data
         .and_then(|t| Ticket::try_from(t).ok())
         .ok_or_else(|| anyhow::anyhow!("Failed to get latest ticket from API"))?;
+    metrics::increment(metrics::PERIODS_CRAWLED, 1);
 
     let query_tickets = tickets::get_ticket_by_period(&request_latest_ticket.period)?;
 
@@ -74,11 +187,13 @@ pub async fn update_latest_ticket() -> anyhow::Result<Ticket> {
             log::info!("Latest ticket is up to date");
             Ok(request_latest_ticket)
         } else {
-            anyhow::bail!(
-                "Latest ticket mismatch - database: {}, API: {}",
-                query_ticket,
-                request_latest_ticket
-            );
+            Err(TicketMismatch {
+                period: request_latest_ticket.period.clone(),
+                against: "database".to_owned(),
+                stored: query_ticket.to_string(),
+                fetched: request_latest_ticket.to_string(),
+            }
+            .into())
         }
     } else {
         tickets::insert_ticket(&request_latest_ticket)?;
@@ -90,38 +205,52 @@ pub async fn update_latest_ticket() -> anyhow::Result<Ticket> {
     }
 }
 
+/// The provider has no draw for a period, it is not drawn yet or never will be
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Period {period} is not drawn yet")]
+pub struct PeriodNotDrawn {
+    pub period: String,
+}
+
+/// Whether the provider may answer the request that failed with `error` when asked again
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<ProviderError>()
+            .is_some_and(ProviderError::is_transient)
+    })
+}
+
 /// Update tickets table by period
 /// Return `true` if ticket is inserted, `false` if ticket is up to date
 /// period is made up of 2-digit year and 3-digit number, e.g. 23001, 23002, 23003, ...
-pub async fn update_tickets_by_period(period: &str) -> anyhow::Result<bool> {
-    use crate::api::MXNZP_PROVIDER;
-    use crate::api::ProviderResponse as _;
+///
+/// Fails with [`PeriodNotDrawn`] when the provider has no such draw and with
+/// [`TicketMismatch`] when it contradicts the stored ticket or the ticket log.
+///
+/// A `dry_run` writes nothing and returns whether the ticket would be inserted.
+pub async fn update_tickets_by_period(period: &str, dry_run: bool) -> anyhow::Result<bool> {
     use crate::db::tickets;
 
-    // Check if period is longer than 5 digits and truncate if necessary
-    let period = if period.len() > 5 {
-        let period_5digit = &period[period.len() - 5..];
-        log::debug!(
-            "Period {period} is longer than 5 digits, truncating to last 5 digits {period_5digit}"
-        );
-        period_5digit
-    } else {
-        period
-    };
-
-    if period.len() != 5 {
-        anyhow::bail!("MXNZP api request param period must be 5 characters long {period}");
+    let (request_ticket, detail) = request_ticket(period).await?;
+    if !dry_run {
+        save_draw_detail(detail);
     }
-
-    let request_ticket = MXNZP_PROVIDER
-        .get_specified_lottery(period)
-        .await?
-        .get_data()
-        .and_then(|t| Ticket::try_from(t).ok())
-        .ok_or_else(|| anyhow::anyhow!("Failed to get ticket for period {period} from API"))?;
+    // stored tickets and the ticket log name periods with all 7 digits
+    let period = request_ticket.period.as_str();
 
     if !check_ticket_in_log_db(period, &request_ticket).await? {
-        anyhow::bail!("Ticket for period {period} does not match in log database");
+        let logged = crate::db::ticket_log::get_record_by_code(period)?
+            .map(|log| log.to_dball().map(|dball| dball.to_string()))
+            .transpose()?
+            .unwrap_or_default();
+        return Err(TicketMismatch {
+            period: period.to_owned(),
+            against: "ticket log".to_owned(),
+            stored: logged,
+            fetched: request_ticket.to_string(),
+        }
+        .into());
     }
 
     if let Some(t) = tickets::get_ticket_by_period(period)? {
@@ -129,10 +258,17 @@ pub async fn update_tickets_by_period(period: &str) -> anyhow::Result<bool> {
             log::debug!("Ticket for period {period} is up to date");
             Ok(false)
         } else {
-            anyhow::bail!(
-                "Ticket mismatch for period {period} - database: {t}, API: {request_ticket}"
-            );
+            Err(TicketMismatch {
+                period: period.to_owned(),
+                against: "database".to_owned(),
+                stored: t.to_string(),
+                fetched: request_ticket.to_string(),
+            }
+            .into())
         }
+    } else if dry_run {
+        log::info!("Would insert new ticket for period {period}");
+        Ok(true)
     } else {
         log::info!("Inserting new ticket for period {period}");
         tickets::insert_ticket(&request_ticket)?;
@@ -141,6 +277,70 @@ pub async fn update_tickets_by_period(period: &str) -> anyhow::Result<bool> {
     }
 }
 
+/// The ticket the provider has for `period`, of 5 or 7 digits, and the details of its draw
+///
+/// A provider that is unreachable or overloaded is asked again up to
+/// `crawl.max_attempts` times, waiting `crawl.backoff_ms` doubled after every
+/// attempt. Fails with [`PeriodNotDrawn`] when the provider has no such draw.
+///
+/// A request in flight or waiting to be retried stops as soon as the current
+/// operation is cancelled.
+pub(super) async fn request_ticket(period: &str) -> anyhow::Result<(Ticket, Option<DrawDetail>)> {
+    let crawl = super::get_config().await?.crawl;
+    let mut delay = Duration::from_millis(crawl.backoff_ms);
+    let mut attempts = 1;
+    progress::cancellable(async {
+        loop {
+            match fetch_ticket(period).await {
+                Err(e) if attempts < crawl.max_attempts && is_transient(&e) => {
+                    log::debug!(
+                        "Attempt {attempts} of {} at period {period} failed, retrying in {delay:?}: {e:#}",
+                        crawl.max_attempts
+                    );
+                    crate::clock::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    })
+    .await
+}
+
+async fn fetch_ticket(period: &str) -> anyhow::Result<(Ticket, Option<DrawDetail>)> {
+    use crate::api::LOTTERY_PROVIDER;
+    use crate::api::ProviderResponse as _;
+
+    // Check if period is longer than 5 digits and truncate if necessary
+    let period = if period.len() > 5 {
+        let period_5digit = &period[period.len() - 5..];
+        log::debug!(
+            "Period {period} is longer than 5 digits, truncating to last 5 digits {period_5digit}"
+        );
+        period_5digit
+    } else {
+        period
+    };
+
+    if period.len() != 5 {
+        anyhow::bail!("MXNZP api request param period must be 5 characters long {period}");
+    }
+
+    let _timer = metrics::time("fetch_ticket");
+    let response = LOTTERY_PROVIDER
+        .get_specified_lottery(period)
+        .await
+        .inspect_err(publish_api_failure)?;
+    let data = response.get_data().ok_or_else(|| PeriodNotDrawn {
+        period: period.to_owned(),
+    })?;
+    let ticket = Ticket::try_from(data)
+        .map_err(|e| anyhow::anyhow!("Failed to get ticket for period {period} from API: {e}"))?;
+    metrics::increment(metrics::PERIODS_CRAWLED, 1);
+    Ok((ticket, data.draw_detail()))
+}
+
 /// Check if the ticket exists in the log database
 /// Returns `true` if the ticket matches the log database or not found
 /// Returns `false` if the ticket does not match
@@ -162,7 +362,7 @@ pub async fn check_ticket_in_log_db(period: &str, ticket: &Ticket) -> anyhow::Re
 fn get_existing_periods_for_year(year: usize) -> anyhow::Result<Vec<usize>> {
     use crate::db::tickets;
 
-    let tickets = tickets::get_all_tickets()?;
+    let tickets = tickets::get_all_tickets(PageRequest::default())?.items;
 
     let mut periods_7digit: Vec<usize> = tickets
         .iter()
@@ -187,78 +387,331 @@ fn get_existing_periods_for_year(year: usize) -> anyhow::Result<Vec<usize>> {
 }
 
 /// Update tickets for a year starting from period 1
-async fn update_year_from_start(year: usize) -> anyhow::Result<()> {
-    let start_period = year % YEAR_MODULO * 1000 + 1;
-    update_tickets_after_period(start_period).await
+async fn update_year_from_start(
+    year: usize,
+    report: &mut CrawlReport,
+    crawl: &mut YearCrawlProgress,
+) -> anyhow::Result<()> {
+    let start_period = year * 1000 + 1;
+    update_tickets_after_period(start_period, report, crawl).await
 }
 
-/// Update tickets for a year starting from a specific period number
-async fn update_tickets_after_period(start_period_5digit: usize) -> anyhow::Result<()> {
-    let mut period_num = start_period_5digit;
-    let mut consecutive_failures = 0;
-    const MAX_CONSECUTIVE_FAILURES: usize = 3;
+/// Periods of a year crawl handled so far, published as
+/// [`DaemonEvent::CrawlProgress`] after each one and once more when dropped
+struct YearCrawlProgress {
+    year: usize,
+    period: String,
+    total: usize,
+    inserted: usize,
+    skipped: usize,
+    failed: usize,
+}
 
-    loop {
-        let period = format!("{period_num:05}");
+impl YearCrawlProgress {
+    fn new(year: usize, total: usize) -> Self {
+        Self {
+            year,
+            period: String::new(),
+            total,
+            inserted: 0,
+            skipped: 0,
+            failed: 0,
+        }
+    }
 
-        match update_tickets_by_period(&period).await {
-            Ok(_) => (),
-            Err(e) => {
-                log::warn!("Failed to update period {period}: {e}");
-                consecutive_failures += 1;
+    /// Count the `result` of fetching `period`, a cancelled fetch is not counted
+    fn note(&mut self, period: &str, result: &anyhow::Result<bool>) {
+        match result {
+            Ok(true) => self.inserted += 1,
+            Ok(false) => self.skipped += 1,
+            Err(e) if e.is::<progress::OperationCancelled>() => return,
+            Err(e) if e.is::<PeriodNotDrawn>() || e.is::<TicketMismatch>() => self.skipped += 1,
+            Err(_) => self.failed += 1,
+        }
+        period.clone_into(&mut self.period);
+        self.publish(false);
+    }
 
-                if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
-                    let year = period_num / 1000;
-                    log::info!(
-                        "Stopping updates for year {year} after {MAX_CONSECUTIVE_FAILURES} consecutive failures"
-                    );
-                    break;
-                }
+    fn publish(&self, finished: bool) {
+        events::publish(DaemonEvent::CrawlProgress {
+            year: self.year,
+            period: self.period.clone(),
+            total: self.total,
+            inserted: self.inserted,
+            skipped: self.skipped,
+            failed: self.failed,
+            finished,
+        });
+    }
+}
+
+impl Drop for YearCrawlProgress {
+    fn drop(&mut self) {
+        self.publish(true);
+    }
+}
+
+/// Note a fetched period in `report`, mismatches count as handled
+fn record(
+    report: &mut CrawlReport,
+    period: &str,
+    result: anyhow::Result<bool>,
+) -> anyhow::Result<()> {
+    match result {
+        Ok(inserted) => {
+            if inserted {
+                report.inserted.push(period.to_owned());
             }
+            Ok(())
         }
-
-        period_num += 1;
+        Err(e) => match e.downcast::<TicketMismatch>() {
+            Ok(mismatch) => {
+                log::warn!("{mismatch}");
+                report.mismatches.push(mismatch);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        },
     }
+}
 
-    Ok(())
+/// Periods in a row not drawn or failed for good after which a year is over
+const MAX_CONSECUTIVE_FAILURES: usize = 3;
+
+/// Outcome of a wave of periods fetched together by [`update_tickets_after_period`]
+#[derive(Debug, PartialEq, Eq)]
+enum Wave {
+    /// Fetch the next wave, `failed` when a period of this one failed
+    Done { failed: bool },
+    /// `MAX_CONSECUTIVE_FAILURES` periods in a row were not drawn or failed for good
+    YearOver,
 }
 
-/// Fill missing periods in the existing data for a specific year
-async fn update_missing_periods(existing_periods_7digit: &[usize]) -> anyhow::Result<()> {
-    if existing_periods_7digit.is_empty() {
-        return Ok(());
+/// Record the `results` of a wave of `periods`, judged in period order as if
+/// fetched one after another
+///
+/// Every result is recorded, even past a period stopping the crawl, as the
+/// later periods of the wave are written already. The first error stopping
+/// it is returned afterwards, a cancellation or an unreachable provider
+fn record_wave(
+    report: &mut CrawlReport,
+    crawl: &mut YearCrawlProgress,
+    periods: &[String],
+    results: Vec<anyhow::Result<bool>>,
+    consecutive_failures: &mut usize,
+) -> anyhow::Result<Wave> {
+    let mut outcome = Ok(Wave::Done { failed: false });
+    for (period, result) in periods.iter().zip(results) {
+        crawl.note(period, &result);
+        let recorded = record(report, period, result);
+        if !matches!(outcome, Ok(Wave::Done { .. })) {
+            if let Err(e) = recorded {
+                log::debug!("Failed to update period {period} after the crawl stopped: {e:#}");
+            }
+            continue;
+        }
+        match recorded {
+            Ok(()) => *consecutive_failures = 0,
+            // not a sign of the end of the year either
+            Err(e) if e.is::<progress::OperationCancelled>() => outcome = Err(e),
+            // an unreachable provider says nothing about where the year ends
+            Err(e) if is_transient(&e) => {
+                outcome = Err(e.context(format!("Crawling stopped at period {period}")));
+            }
+            Err(e) => {
+                if e.is::<PeriodNotDrawn>() {
+                    log::debug!("{e}");
+                } else {
+                    log::warn!("Failed to update period {period}: {e:#}");
+                }
+                *consecutive_failures += 1;
+                outcome = Ok(if *consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                    Wave::YearOver
+                } else {
+                    Wave::Done { failed: true }
+                });
+            }
+        }
     }
+    outcome
+}
 
-    let min_period = *existing_periods_7digit
-        .iter()
-        .min()
-        .ok_or_else(|| anyhow::anyhow!("existing_periods should not be empty at this point"))?;
-    let max_period = *existing_periods_7digit
+/// Update tickets for a year starting from a specific period number
+///
+/// Fetches `crawl.concurrency` periods at a time, halves that after a failure
+/// and grows it back one by one after waves without any, stops once
+/// `MAX_CONSECUTIVE_FAILURES` periods in a row are not drawn or failed for
+/// good, and with an error once the provider stays unreachable
+async fn update_tickets_after_period(
+    start_period: usize,
+    report: &mut CrawlReport,
+    crawl: &mut YearCrawlProgress,
+) -> anyhow::Result<()> {
+    let max_concurrency = super::get_config()
+        .await?
+        .crawl
+        .concurrency
+        .clamp(1, CrawlConfig::MAX_CONCURRENCY);
+    let mut concurrency = max_concurrency;
+    let mut period_num = start_period;
+    let mut consecutive_failures = 0;
+
+    loop {
+        progress::ensure_not_cancelled()?;
+        let periods = (period_num..period_num + concurrency)
+            .map(|period_num| format!("{period_num:05}"))
+            .collect::<Vec<_>>();
+        progress::report(
+            period_num - start_period,
+            None,
+            format!(
+                "Fetching periods {} to {}",
+                periods[0],
+                periods[periods.len() - 1]
+            ),
+        );
+
+        let results = join_all(
+            periods
+                .iter()
+                .map(|period| update_tickets_by_period(period, report.dry_run)),
+        )
+        .await;
+        let failed = match record_wave(report, crawl, &periods, results, &mut consecutive_failures)?
+        {
+            Wave::Done { failed } => failed,
+            Wave::YearOver => {
+                let year = period_num / 1000;
+                log::info!(
+                    "Stopping updates for year {year} after {MAX_CONSECUTIVE_FAILURES} consecutive failures"
+                );
+                return Ok(());
+            }
+        };
+
+        period_num += periods.len();
+        concurrency = if failed {
+            (concurrency / 2).max(1)
+        } else {
+            (concurrency + 1).min(max_concurrency)
+        };
+    }
+}
+
+/// Fill the periods of `report.missing` before `latest_period`, those after it
+/// are fetched going on from it
+async fn update_missing_periods(
+    latest_period: usize,
+    report: &mut CrawlReport,
+    crawl: &mut YearCrawlProgress,
+) -> anyhow::Result<()> {
+    let missing = report
+        .missing
         .iter()
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("existing_periods should not be empty at this point"))?;
-
-    log::debug!("Filling gaps between periods {min_period} and {max_period}");
-
-    for period_num in min_period..=max_period {
-        if !existing_periods_7digit.contains(&period_num) {
-            let period = (period_num % 100000).to_string();
-            log::info!("Attempting to fill missing period: {period}");
-
-            match update_tickets_by_period(&period).await {
-                Ok(inserted) => {
-                    if inserted {
-                        log::info!("Successfully filled missing period {period}");
-                    } else {
-                        log::warn!("Period {period} already exists (race condition?)");
-                    }
-                }
-                Err(e) => {
-                    log::warn!("Failed to fill missing period {period}: {e}");
-                }
+        .filter(|period| {
+            period
+                .parse::<usize>()
+                .is_ok_and(|period| period < latest_period)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    log::debug!("Filling {} missing periods", missing.len());
+
+    for (step, period) in missing.iter().enumerate() {
+        progress::ensure_not_cancelled()?;
+        progress::report(
+            step,
+            Some(missing.len()),
+            format!("Filling missing period {period}"),
+        );
+        log::info!("Attempting to fill missing period: {period}");
+
+        let result = update_tickets_by_period(period, report.dry_run).await;
+        crawl.note(period, &result);
+        if let Err(e) = record(report, period, result) {
+            if e.is::<progress::OperationCancelled>() {
+                return Err(e);
             }
+            log::warn!("Failed to fill missing period {period}: {e}");
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_year_crawl_progress() -> anyhow::Result<()> {
+        let mut receiver = events::subscribe_events();
+        let mut crawl = YearCrawlProgress::new(2099, 4);
+        crawl.note("2099001", &Ok(true));
+        crawl.note("2099002", &Ok(false));
+        crawl.note(
+            "2099003",
+            &Err(PeriodNotDrawn {
+                period: "99003".to_owned(),
+            }
+            .into()),
+        );
+        crawl.note("2099004", &Err(anyhow::anyhow!("Unreadable response")));
+        crawl.note("2099005", &Err(progress::OperationCancelled.into()));
+        drop(crawl);
+
+        let expected = DaemonEvent::CrawlProgress {
+            year: 2099,
+            period: "2099004".to_owned(),
+            total: 4,
+            inserted: 1,
+            skipped: 2,
+            failed: 1,
+            finished: true,
+        };
+        // other tests may publish at the same time
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while receiver.recv().await? != expected {}
+            anyhow::Ok(())
+        })
+        .await?
+    }
+
+    #[test]
+    fn test_wave_failing_in_the_middle() {
+        let mut report = CrawlReport::new(false);
+        let mut crawl = YearCrawlProgress::new(2099, 3);
+        let mut consecutive_failures = 0;
+        let periods = ["99001", "99002", "99003"].map(str::to_owned);
+        let unreachable = ProviderError::Status(reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        let results = vec![Ok(true), Err(unreachable.into()), Ok(true)];
+
+        let stopped = record_wave(
+            &mut report,
+            &mut crawl,
+            &periods,
+            results,
+            &mut consecutive_failures,
+        );
+        assert!(stopped.is_err_and(|e| is_transient(&e)));
+        // the period written after the failed one is recorded too
+        assert_eq!(report.inserted, ["99001", "99003"]);
+
+        let results = vec![
+            Ok(false),
+            Err(anyhow::anyhow!("Unreadable response")),
+            Ok(true),
+        ];
+        assert!(matches!(
+            record_wave(
+                &mut report,
+                &mut crawl,
+                &periods,
+                results,
+                &mut consecutive_failures
+            ),
+            Ok(Wave::Done { failed: true })
+        ));
+        assert_eq!(consecutive_failures, 0);
+    }
+}
@@ -0,0 +1,590 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    crate::db::{ticket_log, tickets},
+    crate::models::{NewTicketLog, PageRequest, Ticket},
+    dball_combora::dball::DBall,
+    std::collections::HashMap,
+};
+
+/// Archive draw whose numbers differ from the ones already recorded, left untouched
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TicketLogConflict {
+    pub period: String,
+    /// Table holding the recorded numbers, `tickets` or `ticket_log`
+    pub table: String,
+    /// Numbers in the archive, e.g. `03 08 12 19 26 31 + 05`
+    pub archive: String,
+    pub recorded: String,
+}
+
+/// Outcome of [`sync_from_archive`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TicketLogSyncReport {
+    /// Draw rows in the archive, including the invalid ones
+    pub rows: usize,
+    pub inserted: usize,
+    /// Draws already in `ticket_log` with the same numbers, refreshed from the archive
+    pub updated: usize,
+    pub conflicts: Vec<TicketLogConflict>,
+    /// Rows that could not be read, as `line N: reason`
+    pub invalid_rows: Vec<String>,
+}
+
+/// Outcome of [`import_tickets_from_archive`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TicketImportReport {
+    /// Draw rows in the archive, including the invalid ones
+    pub rows: usize,
+    pub inserted: usize,
+    /// Draws already in `tickets` with the same numbers
+    pub skipped: usize,
+    pub conflicts: Vec<TicketLogConflict>,
+    /// Rows that could not be read or fail [`Ticket::check`], as `line N: reason`
+    /// or `period: reason`
+    pub invalid_rows: Vec<String>,
+}
+
+/// Chinese numerals of the prize levels, as the official archive names its columns
+#[cfg(not(target_arch = "wasm32"))]
+const PRIZE_LEVELS: [&str; 6] = ["一", "二", "三", "四", "五", "六"];
+
+/// Fill `ticket_log` from the published results archive at a path or `http(s)` URL
+///
+/// The archive is CSV or the tab separated text the lottery site serves as `.xls`,
+/// with a header row naming the columns. Draws whose numbers disagree with `tickets`
+/// or `ticket_log` are reported as conflicts and not written, every other draw is
+/// inserted or refreshed in one transaction
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument]
+pub async fn sync_from_archive(source: &str) -> anyhow::Result<TicketLogSyncReport> {
+    let archive = read_archive(source).await?;
+    let Archive {
+        records,
+        invalid_rows,
+        rows,
+    } = parse_archive(&archive)?;
+
+    let drawn = tickets::get_all_tickets(PageRequest::default())?
+        .items
+        .into_iter()
+        .map(|ticket| Ok((ticket.period.clone(), ticket.to_dball()?)))
+        .collect::<anyhow::Result<HashMap<_, _>>>()?;
+    let logged = ticket_log::get_all_records()?
+        .into_iter()
+        .map(|record| (record.code.clone(), record))
+        .collect::<HashMap<_, _>>();
+
+    let mut report = TicketLogSyncReport {
+        rows,
+        invalid_rows,
+        ..TicketLogSyncReport::default()
+    };
+    let mut accepted = Vec::with_capacity(records.len());
+    for (record, archived) in records {
+        let conflict = |table: &str, recorded: &DBall| TicketLogConflict {
+            period: record.code.clone(),
+            table: table.to_owned(),
+            archive: numbers_text(&archived),
+            recorded: numbers_text(recorded),
+        };
+        if let Some(ticket) = drawn.get(&record.code)
+            && *ticket != archived
+        {
+            report.conflicts.push(conflict("tickets", ticket));
+            continue;
+        }
+        match logged.get(&record.code).map(|log| log.to_dball()) {
+            Some(Ok(log)) if log != archived => {
+                report.conflicts.push(conflict("ticket_log", &log));
+                continue;
+            }
+            Some(_) => report.updated += 1,
+            None => report.inserted += 1,
+        }
+        accepted.push(record);
+    }
+
+    ticket_log::upsert_records(&accepted)?;
+    log::info!(
+        "Synced ticket_log from {source}: {} inserted, {} updated, {} conflicts, {} invalid rows",
+        report.inserted,
+        report.updated,
+        report.conflicts.len(),
+        report.invalid_rows.len()
+    );
+    Ok(report)
+}
+
+/// Fill `tickets` from the published results archive at a path or `http(s)` URL,
+/// an offline alternative to crawling the draws year by year
+///
+/// Reads the same archives as [`sync_from_archive`], every draw needs its date
+/// and is taken as drawn at 21:15 of it. Draws already in `tickets` are skipped,
+/// or reported as conflicts when their numbers differ, the others are inserted
+/// in one transaction
+#[cfg(not(target_arch = "wasm32"))]
+#[tracing::instrument]
+pub async fn import_tickets_from_archive(source: &str) -> anyhow::Result<TicketImportReport> {
+    let archive = read_archive(source).await?;
+    let Archive {
+        records,
+        invalid_rows,
+        rows,
+    } = parse_archive(&archive)?;
+
+    let drawn = tickets::get_all_tickets(PageRequest::default())?
+        .items
+        .into_iter()
+        .map(|ticket| Ok((ticket.period.clone(), ticket.to_dball()?)))
+        .collect::<anyhow::Result<HashMap<_, _>>>()?;
+    let draw_time = chrono::NaiveTime::from_hms_opt(21, 15, 0).expect("Valid draw time");
+
+    let mut report = TicketImportReport {
+        rows,
+        invalid_rows,
+        ..TicketImportReport::default()
+    };
+    let mut accepted = Vec::with_capacity(records.len());
+    for (record, archived) in records {
+        if let Some(ticket) = drawn.get(&record.code) {
+            if *ticket == archived {
+                report.skipped += 1;
+            } else {
+                report.conflicts.push(TicketLogConflict {
+                    period: record.code.clone(),
+                    table: "tickets".to_owned(),
+                    archive: numbers_text(&archived),
+                    recorded: numbers_text(ticket),
+                });
+            }
+            continue;
+        }
+        let Some(date) = record.kj_date else {
+            report
+                .invalid_rows
+                .push(format!("{}: missing date", record.code));
+            continue;
+        };
+        let reds = archived.rball.map(i32::from);
+        match Ticket::with_datetime(
+            record.code.clone(),
+            date.and_time(draw_time),
+            &reds,
+            i32::from(archived.bball),
+        ) {
+            Ok(ticket) => accepted.push(ticket),
+            Err(e) => report.invalid_rows.push(format!("{}: {e}", record.code)),
+        }
+    }
+
+    report.inserted = tickets::insert_tickets_batch(&accepted)?;
+    log::info!(
+        "Imported tickets from {source}: {} inserted, {} skipped, {} conflicts, {} invalid rows",
+        report.inserted,
+        report.skipped,
+        report.conflicts.len(),
+        report.invalid_rows.len()
+    );
+    Ok(report)
+}
+
+/// Text of the archive at `source`, binary spreadsheets are rejected
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_archive(source: &str) -> anyhow::Result<String> {
+    let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source)
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec()
+    } else {
+        tokio::fs::read(source)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read archive {source}: {e}"))?
+    };
+
+    // OLE2 (.xls) and zip (.xlsx) signatures
+    if bytes.starts_with(&[0xD0, 0xCF, 0x11, 0xE0]) || bytes.starts_with(b"PK\x03\x04") {
+        anyhow::bail!("{source} is a binary spreadsheet, save it as CSV first");
+    }
+    let text =
+        String::from_utf8(bytes).map_err(|e| anyhow::anyhow!("{source} is not UTF-8 text: {e}"))?;
+    Ok(text.trim_start_matches('\u{feff}').to_owned())
+}
+
+/// Positions of the columns the archive has
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default)]
+struct Columns {
+    code: Option<usize>,
+    date: Option<usize>,
+    weekday: Option<usize>,
+    reds: [Option<usize>; 6],
+    /// All red balls in one column
+    red_list: Option<usize>,
+    blue: Option<usize>,
+    total_sales: Option<usize>,
+    jackpot: Option<usize>,
+    prize_num: [Option<usize>; 6],
+    prize_money: [Option<usize>; 6],
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Columns {
+    /// Find the columns by their header, in English, as `ticket_log` names them, or in Chinese
+    fn from_header(header: &[String]) -> anyhow::Result<Self> {
+        let mut columns = Self::default();
+        for (index, name) in header.iter().enumerate() {
+            // `总销售额(元)` is `总销售额`, `total_sales` is `totalsales`
+            let name = name
+                .split(['(', '（'])
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+                .replace([' ', '_', '-'], "");
+            let slot = match name.as_str() {
+                "code" | "period" | "issue" | "期号" => &mut columns.code,
+                "kjdate" | "date" | "开奖日期" => &mut columns.date,
+                "xq" | "weekday" | "星期" => &mut columns.weekday,
+                "reds" | "红球" | "红球号码" => &mut columns.red_list,
+                "number7" | "blue" | "蓝球" | "蓝球号码" => &mut columns.blue,
+                "totalsales" | "sales" | "销售额" | "总销售额" => &mut columns.total_sales,
+                "jackpot" | "pool" | "奖池" | "奖池金额" => &mut columns.jackpot,
+                name => match Self::numbered(name) {
+                    Some((Numbered::Red, level)) => &mut columns.reds[level],
+                    Some((Numbered::PrizeNum, level)) => &mut columns.prize_num[level],
+                    Some((Numbered::PrizeMoney, level)) => &mut columns.prize_money[level],
+                    None => continue,
+                },
+            };
+            slot.get_or_insert(index);
+        }
+
+        if columns.code.is_none() {
+            anyhow::bail!("The archive has no period column");
+        }
+        if columns.red_list.is_none() && columns.reds.iter().any(Option::is_none) {
+            anyhow::bail!("The archive has no red ball columns");
+        }
+        if columns.blue.is_none() {
+            anyhow::bail!("The archive has no blue ball column");
+        }
+        Ok(columns)
+    }
+
+    /// Column of one of six red balls or prize levels, with its zero based position
+    fn numbered(name: &str) -> Option<(Numbered, usize)> {
+        let digit = |prefix: &str, suffix: &str| {
+            name.strip_prefix(prefix)?
+                .strip_suffix(suffix)?
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=6).contains(n))
+                .map(|n| n - 1)
+        };
+        let chinese = |suffixes: &[&str]| {
+            PRIZE_LEVELS.iter().position(|level| {
+                name.strip_prefix(level)
+                    .is_some_and(|rest| suffixes.contains(&rest))
+            })
+        };
+
+        if let Some(n) = digit("number", "")
+            .or_else(|| digit("red", ""))
+            .or_else(|| digit("红球", ""))
+        {
+            Some((Numbered::Red, n))
+        } else if let Some(n) = digit("prize", "num").or_else(|| chinese(&["等奖注数"])) {
+            Some((Numbered::PrizeNum, n))
+        } else {
+            digit("prize", "money")
+                .or_else(|| chinese(&["等奖奖金", "等奖金额"]))
+                .map(|n| (Numbered::PrizeMoney, n))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+enum Numbered {
+    Red,
+    PrizeNum,
+    PrizeMoney,
+}
+
+/// Rows of a parsed archive
+#[cfg(not(target_arch = "wasm32"))]
+struct Archive {
+    /// Valid draws with their numbers
+    records: Vec<(NewTicketLog, DBall)>,
+    invalid_rows: Vec<String>,
+    rows: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_archive(text: &str) -> anyhow::Result<Archive> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("The archive is empty"))?;
+    let delimiter = if header.contains('\t') { '\t' } else { ',' };
+    let columns = Columns::from_header(&split_fields(header, delimiter))?;
+
+    let mut archive = Archive {
+        records: Vec::new(),
+        invalid_rows: Vec::new(),
+        rows: 0,
+    };
+    for (index, line) in lines {
+        archive.rows += 1;
+        match parse_row(&columns, &split_fields(line, delimiter)) {
+            Ok(record) => archive.records.push(record),
+            Err(e) => archive
+                .invalid_rows
+                .push(format!("line {}: {e}", index + 1)),
+        }
+    }
+    Ok(archive)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_row(columns: &Columns, fields: &[String]) -> anyhow::Result<(NewTicketLog, DBall)> {
+    let field = |column: Option<usize>| {
+        column
+            .and_then(|column| fields.get(column))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty() && *value != "-")
+    };
+    let ball = |column: Option<usize>, name: &str| -> anyhow::Result<u8> {
+        let value = field(column).ok_or_else(|| anyhow::anyhow!("missing {name}"))?;
+        value
+            .parse()
+            .ok()
+            .ok_or_else(|| anyhow::anyhow!("`{value}` is not a {name}"))
+    };
+    let amount = |column: Option<usize>| -> anyhow::Result<Option<i32>> {
+        let Some(value) = field(column) else {
+            return Ok(None);
+        };
+        value
+            .replace([',', '¥', ' '], "")
+            .parse()
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("`{value}` is not an amount: {e}"))
+    };
+
+    let code = field(columns.code).ok_or_else(|| anyhow::anyhow!("missing period"))?;
+    if code.len() != 7 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        anyhow::bail!("`{code}` is not a 7-digit period");
+    }
+    let kj_date = field(columns.date)
+        .map(|date| {
+            // `2025-08-03(日)`, the weekday is its own column
+            let date = date.get(..10).unwrap_or(date);
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .or_else(|_| chrono::NaiveDate::parse_from_str(date, "%Y/%m/%d"))
+                .map_err(|e| anyhow::anyhow!("`{date}` is not a date: {e}"))
+        })
+        .transpose()?;
+
+    let mut reds = [0; 6];
+    match field(columns.red_list) {
+        Some(list) => {
+            let balls = list
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|ball| !ball.is_empty())
+                .map(|ball| ball.parse::<u8>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| anyhow::anyhow!("`{list}` are not red balls: {e}"))?;
+            reds = balls.try_into().map_err(|balls: Vec<u8>| {
+                anyhow::anyhow!("{} red balls instead of 6", balls.len())
+            })?;
+        }
+        None => {
+            for (red, column) in reds.iter_mut().zip(columns.reds) {
+                *red = ball(column, "red ball")?;
+            }
+        }
+    }
+    let blue = ball(columns.blue, "blue ball")?;
+    let dball = DBall::new_one(reds, blue)?;
+
+    let mut prizes = [(None, None); 6];
+    for (prize, (num, money)) in prizes
+        .iter_mut()
+        .zip(columns.prize_num.iter().zip(&columns.prize_money))
+    {
+        *prize = (amount(*num)?, amount(*money)?);
+    }
+    let numbers = dball
+        .rball
+        .iter()
+        .chain([&dball.bball])
+        .map(|&n| i32::from(n))
+        .collect::<Vec<_>>();
+
+    let record = NewTicketLog {
+        code: code.to_owned(),
+        kj_date,
+        xq: field(columns.weekday).map(ToOwned::to_owned),
+        number1: Some(numbers[0]),
+        number2: Some(numbers[1]),
+        number3: Some(numbers[2]),
+        number4: Some(numbers[3]),
+        number5: Some(numbers[4]),
+        number6: Some(numbers[5]),
+        number7: Some(numbers[6]),
+        jsondata: serde_json::to_string(&numbers).ok(),
+        total_sales: amount(columns.total_sales)?,
+        jackpot: amount(columns.jackpot)?,
+        prize1_num: prizes[0].0,
+        prize1_money: prizes[0].1,
+        prize2_num: prizes[1].0,
+        prize2_money: prizes[1].1,
+        prize3_num: prizes[2].0,
+        prize3_money: prizes[2].1,
+        prize4_num: prizes[3].0,
+        prize4_money: prizes[3].1,
+        prize5_num: prizes[4].0,
+        prize5_money: prizes[4].1,
+        prize6_num: prizes[5].0,
+        prize6_money: prizes[5].1,
+    };
+    Ok((record, dball))
+}
+
+/// Fields of one line, double quoted fields may hold the delimiter and `""` quotes
+#[cfg(not(target_arch = "wasm32"))]
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn numbers_text(dball: &DBall) -> String {
+    let reds = dball.rball.map(|red| format!("{red:02}")).join(" ");
+    format!("{reds} + {:02}", dball.bball)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_archive() -> anyhow::Result<()> {
+        let archive = "期号,开奖日期,红球,蓝球,总销售额(元),奖池(元),一等奖注数,一等奖奖金(元)\n\
+            2025088,2025-07-31(四),\"06,11,16,20,29,33\",16,\"390,123,456\",1912345678,4,6512345\n\
+            2025087,2025-07-29(二),01 05 17 23 26,12,,,,\n\
+            \n\
+            2025086,2025-07-27(日),08 09 19 24 27 33,3,-,-,-,-\n";
+        let Archive {
+            records,
+            invalid_rows,
+            rows,
+        } = parse_archive(archive)?;
+        assert_eq!(rows, 3);
+        assert_eq!(records.len(), 2);
+        assert_eq!(invalid_rows.len(), 1);
+        assert!(invalid_rows[0].starts_with("line 3:"), "{invalid_rows:?}");
+
+        let (record, dball) = &records[0];
+        assert_eq!(record.code, "2025088");
+        assert_eq!(record.kj_date, chrono::NaiveDate::from_ymd_opt(2025, 7, 31));
+        assert_eq!(dball.rball, [6, 11, 16, 20, 29, 33]);
+        assert_eq!(record.number7, Some(16));
+        assert_eq!(record.total_sales, Some(390_123_456));
+        assert_eq!(record.prize1_money, Some(6_512_345));
+        assert_eq!(records[1].0.jackpot, None);
+
+        let tab_separated = "code\tkj_date\tnumber1\tnumber2\tnumber3\tnumber4\tnumber5\tnumber6\tnumber7\n\
+            2003001\t2003-02-23\t10\t11\t12\t13\t26\t28\t11\n";
+        let archive = parse_archive(tab_separated)?;
+        assert!(
+            archive.invalid_rows.is_empty(),
+            "{:?}",
+            archive.invalid_rows
+        );
+        assert_eq!(archive.records[0].1.bball, 11);
+
+        assert!(parse_archive("date,reds\n2025-01-01,1 2 3 4 5 6\n").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_archive() -> anyhow::Result<()> {
+        // 2025084 agrees with the fixture, 2025085 is not logged yet,
+        // 2025086 differs from the drawn ticket and 2099001 is not drawn
+        let archive = "code,kj_date,red1,red2,red3,red4,red5,red6,blue,jackpot\n\
+            2025084,2025-07-22,2,6,13,16,28,31,11,1876543210\n\
+            2025085,2025-07-24,4,10,15,21,25,29,7,1890000000\n\
+            2025086,2025-07-27,8,9,19,24,27,32,3,1912345678\n\
+            2099001,2099-01-01,1,2,3,4,5,6,1,\n";
+        let path = std::env::temp_dir().join(format!("dball-archive-{}.csv", std::process::id()));
+        std::fs::write(&path, archive)?;
+        let report = sync_from_archive(&path.display().to_string()).await;
+        std::fs::remove_file(&path)?;
+        let report = report?;
+
+        assert_eq!(report.rows, 4);
+        assert_eq!(report.updated, 1);
+        assert_eq!(report.inserted, 2);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].period, "2025086");
+        assert_eq!(report.conflicts[0].table, "tickets");
+        assert_eq!(report.conflicts[0].recorded, "08 09 19 24 27 33 + 03");
+
+        let inserted = ticket_log::get_record_by_code("2025085")?.expect("Synced record");
+        assert_eq!(inserted.jackpot, Some(1_890_000_000));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_tickets_from_archive() -> anyhow::Result<()> {
+        // 2025084 is drawn with the same numbers, 2025086 with others,
+        // 2024001 is new, 2024002 has no date and 2024003 a duplicate red
+        let archive = "code,kj_date,red1,red2,red3,red4,red5,red6,blue\n\
+            2025084,2025-07-22,2,6,13,16,28,31,11\n\
+            2025086,2025-07-27,8,9,19,24,27,32,3\n\
+            2024001,2024-01-02,3,9,14,20,27,31,5\n\
+            2024002,,1,2,3,4,5,6,1\n\
+            2024003,2024-01-04,1,1,3,4,5,6,1\n";
+        let path = std::env::temp_dir().join(format!("dball-tickets-{}.csv", std::process::id()));
+        std::fs::write(&path, archive)?;
+        let report = import_tickets_from_archive(&path.display().to_string()).await;
+        std::fs::remove_file(&path)?;
+        let report = report?;
+
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.inserted, 1);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].period, "2025086");
+        assert_eq!(report.invalid_rows.len(), 2, "{:?}", report.invalid_rows);
+
+        let imported = tickets::get_ticket_by_period("2024001")?.expect("Imported ticket");
+        assert_eq!(imported.formatted_time(), "2024-01-02 21:15:00");
+        assert_eq!(imported.format_numbers(), "03 09 14 20 27 31 + 05");
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! Offline daemon with a time that tests control
+//!
+//! Under the `simulation` feature the draws come from
+//! [`FixtureProvider`](crate::api::FixtureProvider) instead of the lottery API,
+//! the data lives in a fixture database seeded for each process, and
+//! [`crate::clock`] reads [`CLOCK`]. The clock starts right after the last
+//! fixture draw and runs [`DEFAULT_SPEED`] times faster than real time, so the
+//! daemon's state refresh and a test's `advance` to the next draw take seconds
+
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::Instant;
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+
+use crate::db::testing::{FIXTURE_TIME, seed_fixture};
+
+/// Speed of the clock when `DBALL_SIMULATION_SPEED` is not set
+pub const DEFAULT_SPEED: u32 = 60;
+
+/// HTTP address of the daemon unless `DBALL_HTTP_BIND` is set, any free loopback port
+pub const HTTP_BIND: &str = "127.0.0.1:0";
+
+/// Clock of the simulation, shared by the daemon and the tests driving it
+pub static CLOCK: LazyLock<SimulatedClock> = LazyLock::new(|| {
+    let speed = std::env::var("DBALL_SIMULATION_SPEED")
+        .ok()
+        .and_then(|speed| speed.trim().parse().ok())
+        .unwrap_or(DEFAULT_SPEED);
+    let start = NaiveDateTime::parse_from_str(FIXTURE_TIME, "%Y-%m-%d %H:%M:%S")
+        .expect("FIXTURE_TIME is a valid time")
+        .and_utc();
+    SimulatedClock::new(start, speed)
+});
+
+static DATABASE: LazyLock<PathBuf> = LazyLock::new(|| {
+    let path = std::env::temp_dir().join(format!("dball-simulation-{}.db", std::process::id()));
+    seed_fixture(&path).expect("Failed to seed the simulation database");
+    path
+});
+
+/// Time running `speed` times faster than real time, which can also jump ahead
+#[derive(Debug)]
+pub struct SimulatedClock {
+    start: DateTime<Utc>,
+    started: Instant,
+    speed: u32,
+    skipped: Mutex<TimeDelta>,
+}
+
+impl SimulatedClock {
+    /// Clock showing `start` now, a `speed` of 0 counts as 1
+    pub fn new(start: DateTime<Utc>, speed: u32) -> Self {
+        Self {
+            start,
+            started: Instant::now(),
+            speed: speed.max(1),
+            skipped: Mutex::new(TimeDelta::zero()),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        let elapsed = TimeDelta::from_std(self.started.elapsed() * self.speed).unwrap_or_default();
+        self.start + elapsed + *self.skipped.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Jump `by` ahead, the clock never goes back
+    pub fn advance(&self, by: TimeDelta) {
+        if by > TimeDelta::zero() {
+            *self.skipped.lock().unwrap_or_else(PoisonError::into_inner) += by;
+        }
+    }
+
+    /// Jump ahead to `time`, nothing happens when it has already passed
+    pub fn advance_to(&self, time: DateTime<Utc>) {
+        self.advance(time - self.now());
+    }
+
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    /// Real time passing while the clock moves on by `duration`
+    pub fn real_duration(&self, duration: std::time::Duration) -> std::time::Duration {
+        duration / self.speed
+    }
+}
+
+/// Fixture database of this process, seeded on first use
+pub fn database_path() -> &'static Path {
+    &DATABASE
+}
+
+/// `path` with `-simulation` added to its file name, so a simulated daemon
+/// never takes the socket or lock of a real one
+pub fn scoped_path(path: &str) -> String {
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if !extension.contains(['/', '\\']) => {
+            format!("{stem}-simulation.{extension}")
+        }
+        _ => format!("{path}-simulation"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_advance() {
+        let start = DateTime::<Utc>::from_timestamp(1_754_035_200, 0).expect("Valid timestamp");
+        let clock = SimulatedClock::new(start, 1);
+
+        clock.advance(TimeDelta::days(2));
+        let now = clock.now();
+        assert!(now >= start + TimeDelta::days(2));
+
+        clock.advance(TimeDelta::days(-1));
+        clock.advance_to(start);
+        assert!(clock.now() >= now);
+    }
+
+    #[test]
+    fn test_scoped_path() {
+        assert_eq!(
+            scoped_path("/tmp/dball-daemon.sock"),
+            "/tmp/dball-daemon-simulation.sock"
+        );
+        assert_eq!(
+            scoped_path(r"\\.\pipe\dball-daemon"),
+            r"\\.\pipe\dball-daemon-simulation"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Tracing spans of the request path, from the IPC and HTTP handlers down to
+//! the generator, the database and the lottery API
+//!
+//! Logs keep going through `log`, the spans are only collected when the `otel`
+//! feature is enabled and an OTLP endpoint is configured with the standard
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`.
+
+/// Name the spans are reported under, unless `OTEL_SERVICE_NAME` is set
+#[cfg(feature = "otel")]
+const SERVICE_NAME: &str = "dball-daemon";
+
+const ENDPOINT_VARS: [&str; 2] = [
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+];
+
+/// OTLP endpoint from the environment, blank values count as unset
+fn configured_endpoint(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    ENDPOINT_VARS
+        .iter()
+        .filter_map(|key| var(key))
+        .map(|value| value.trim().to_owned())
+        .find(|value| !value.is_empty())
+}
+
+/// Flushes the spans not exported yet when dropped, keep it until shutdown
+#[must_use = "spans are only flushed when the guard is dropped"]
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            log::warn!("Failed to flush trace spans: {e}");
+        }
+    }
+}
+
+/// Start exporting spans when an OTLP endpoint is configured
+#[cfg(feature = "otel")]
+pub fn init() -> anyhow::Result<TelemetryGuard> {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig as _;
+    use tracing_subscriber::layer::SubscriberExt as _;
+    use tracing_subscriber::util::SubscriberInitExt as _;
+
+    let Some(endpoint) = configured_endpoint(|key| std::env::var(key).ok()) else {
+        log::debug!("No OTLP endpoint configured, not exporting trace spans");
+        return Ok(TelemetryGuard { provider: None });
+    };
+
+    // the exporter reads the endpoint, headers and timeout from the environment itself
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+        .build()?;
+    let mut resource = opentelemetry_sdk::Resource::builder();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+        resource = resource.with_service_name(SERVICE_NAME);
+    }
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("dball"));
+    tracing_subscriber::registry().with(layer).try_init()?;
+
+    log::info!("Exporting trace spans to {endpoint}");
+    Ok(TelemetryGuard {
+        provider: Some(provider),
+    })
+}
+
+/// Spans are not exported without the `otel` feature, only warn when an endpoint is set
+#[cfg(not(feature = "otel"))]
+pub fn init() -> anyhow::Result<TelemetryGuard> {
+    if let Some(endpoint) = configured_endpoint(|key| std::env::var(key).ok()) {
+        log::warn!("OTLP endpoint {endpoint} is ignored, dball-client was built without `otel`");
+    }
+    Ok(TelemetryGuard {})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_endpoint_prefers_traces_endpoint() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| (*value).to_owned())
+            }
+        };
+
+        assert_eq!(configured_endpoint(env(&[])), None);
+        assert_eq!(
+            configured_endpoint(env(&[(
+                "OTEL_EXPORTER_OTLP_ENDPOINT",
+                " http://otel:4318 "
+            )])),
+            Some("http://otel:4318".to_owned())
+        );
+        assert_eq!(
+            configured_endpoint(env(&[
+                ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://otel:4318"),
+                (
+                    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+                    "http://traces:4318/v1/traces"
+                ),
+            ])),
+            Some("http://traces:4318/v1/traces".to_owned())
+        );
+        assert_eq!(
+            configured_endpoint(env(&[
+                ("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "  "),
+                ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://otel:4318"),
+            ])),
+            Some("http://otel:4318".to_owned())
+        );
+    }
+}
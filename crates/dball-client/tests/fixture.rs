@@ -0,0 +1,44 @@
+use std::process::Command;
+
+use dball_client::db::testing::{FIXTURE_DRAWS, FIXTURE_SPOTS, seed_fixture};
+
+/// Run `dball-cli` against the fixture database at `name` in the temp dir
+fn cli(name: &str, args: &[&str]) -> anyhow::Result<String> {
+    let path = std::env::temp_dir().join(name);
+    seed_fixture(&path)?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dball-cli"))
+        .args(args)
+        .env("DATABASE_URL", &path)
+        .output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "dball-cli {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn export_fixture_tickets() -> anyhow::Result<()> {
+    let csv = cli("dball-fixture-tickets.db", &["db", "export", "tickets"])?;
+    for (period, ..) in FIXTURE_DRAWS {
+        assert!(csv.contains(period), "period {period} missing from {csv}");
+    }
+    Ok(())
+}
+
+#[test]
+fn export_fixture_spots() -> anyhow::Result<()> {
+    let csv = cli(
+        "dball-fixture-spots.db",
+        &["db", "export", "spots", "--period", "2025088"],
+    )?;
+    let expected = FIXTURE_SPOTS
+        .iter()
+        .filter(|(period, ..)| *period == "2025088")
+        .count();
+    // one header line, then a line per spot
+    assert_eq!(csv.lines().count(), expected + 1, "{csv}");
+    Ok(())
+}
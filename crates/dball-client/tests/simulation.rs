@@ -0,0 +1,142 @@
+//! A whole draw cycle through the daemon and IPC, offline and in a few seconds
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use dball_client::api::UPCOMING_DRAWS;
+use dball_client::daemon::DaemonService;
+use dball_client::ipc::client::IpcClient;
+use dball_client::ipc::error::RpcError;
+use dball_client::ipc::protocol::{AppState, RpcService};
+use dball_client::models::{CrawlReport, ManualSpot, Page, PageRequest, Spot, SpotQuery, Ticket};
+use dball_client::simulation::CLOCK;
+
+/// Send a request answered with a `Result`
+async fn request<R>(client: &IpcClient, service: RpcService) -> anyhow::Result<R>
+where
+    for<'de> R: serde::Deserialize<'de>,
+{
+    let response = client.send_rpc_request(service).await?;
+    Ok(serde_json::from_value::<Result<R, RpcError>>(response)??)
+}
+
+async fn current_state(client: &IpcClient) -> anyhow::Result<AppState> {
+    let response = client.send_rpc_request(RpcService::GetCurrentState).await?;
+    Ok(serde_json::from_value(response)?)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulated_draw_cycle() -> anyhow::Result<()> {
+    let mut daemon = DaemonService::new().await?;
+    daemon.start().await?;
+    let daemon = Arc::new(daemon);
+    let runner = tokio::spawn({
+        let daemon = daemon.clone();
+        async move { daemon.run().await }
+    });
+
+    let mut client = IpcClient::new();
+    let mut attempts = 0;
+    while let Err(e) = client.connect().await {
+        attempts += 1;
+        anyhow::ensure!(attempts < 50, "Daemon never came up: {e}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let state = current_state(&client).await?;
+    assert_eq!(state.current_period, "2025088");
+    assert_eq!(state.next_period, "2025089");
+
+    // the numbers of the coming draw, then numbers matching none of them
+    let (period, time, reds, blue) = UPCOMING_DRAWS[0];
+    let jackpot = ManualSpot {
+        reds: reds.map(|red| red as u8),
+        blue: blue as u8,
+        magnification: 1,
+    };
+    let blank = ManualSpot {
+        reds: [1, 2, 3, 5, 6, 7],
+        blue: 1,
+        magnification: 1,
+    };
+    for spot in [jackpot, blank] {
+        let spot_period = request::<String>(&client, RpcService::AddManualSpot(spot)).await?;
+        assert_eq!(spot_period, period);
+    }
+
+    // nothing to settle before the draw
+    let next_draw = state.next_draw_time.expect("Next draw time");
+    assert!(CLOCK.now() < next_draw);
+    request::<Vec<Spot>>(&client, RpcService::UpdateAllUnprizeSpots).await?;
+    let query = SpotQuery {
+        period_prefix: Some(period.to_owned()),
+        ..SpotQuery::default()
+    };
+    let drawn = request::<Vec<Spot>>(&client, RpcService::QuerySpots(query.clone())).await?;
+    assert!(drawn.iter().all(|spot| spot.prize_status.is_none()));
+
+    CLOCK.advance_to(next_draw);
+    // a dry run finds the new draw without saving it
+    let preview =
+        request::<CrawlReport>(&client, RpcService::UpdateTicketsWithYear(2025, true)).await?;
+    assert!(preview.dry_run);
+    assert!(preview.inserted.iter().any(|inserted| inserted == period));
+    let tickets = request::<Page<Ticket>>(
+        &client,
+        RpcService::GetTickets(Some(period.to_owned()), PageRequest::default()),
+    )
+    .await?;
+    assert_eq!(tickets.total, 0);
+
+    request::<Vec<Spot>>(&client, RpcService::UpdateAllUnprizeSpots).await?;
+    let drawn = request::<Vec<Spot>>(&client, RpcService::QuerySpots(query.clone())).await?;
+    let prizes = drawn
+        .iter()
+        .map(|spot| spot.prize_status)
+        .collect::<Vec<_>>();
+    assert_eq!(prizes.len(), 2);
+    assert!(prizes.contains(&Some(0)), "{prizes:?}");
+    assert!(
+        prizes
+            .iter()
+            .any(|prize| prize.is_some_and(|prize| prize > 0))
+    );
+
+    // the same spots again, one page at a time
+    let (chunks, mut received) = tokio::sync::mpsc::unbounded_channel();
+    let response = client
+        .send_rpc_request_chunked(RpcService::StreamSpots(query), chunks)
+        .await?;
+    let total = serde_json::from_value::<Result<usize, RpcError>>(response)??;
+    let mut streamed = Vec::new();
+    while let Ok(page) = received.try_recv() {
+        streamed.extend(serde_json::from_value::<Vec<Spot>>(page)?);
+    }
+    assert_eq!(total, drawn.len());
+    assert_eq!(streamed.len(), drawn.len());
+
+    let tickets = request::<Page<Ticket>>(
+        &client,
+        RpcService::GetTickets(Some(period.to_owned()), PageRequest::default()),
+    )
+    .await?
+    .items;
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(tickets[0].time.to_string(), time);
+
+    // the accelerated refresh moves the countdown on to the following draw
+    let mut attempts = 0;
+    while current_state(&client)
+        .await?
+        .next_draw_time
+        .is_none_or(|time| time <= next_draw)
+    {
+        attempts += 1;
+        anyhow::ensure!(attempts < 50, "State never refreshed past {next_draw}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    daemon.shutdown().await?;
+    runner.await??;
+    Ok(())
+}
@@ -3,14 +3,14 @@ fn main() -> anyhow::Result<()> {
         .filter_level(log::LevelFilter::Debug)
         .init();
 
-    use dball_combora::dball::DBallBatch;
+    use dball_combora::dball::DEFAULT_BATCH_SIZE;
     use dball_combora::generator::Generator;
 
     let bluemorn = Generator::create_generator(Generator::BlueMorn);
-    let tickets = bluemorn.generate_batch()?;
-    let sims = DBallBatch(tickets.to_vec()).cosine_similarity();
+    let tickets = bluemorn.generate_batch(DEFAULT_BATCH_SIZE)?;
+    let sims = tickets.cosine_similarity();
     println!("Cosine similarities: {sims:?}");
-    println!("Generated tickets:\n{}", DBallBatch(tickets.to_vec()));
+    println!("Generated tickets:\n{tickets}");
 
     Ok(())
 }
@@ -0,0 +1,257 @@
+//! Replay a generator over past draws and count what its spots would have won
+
+use serde::{Deserialize, Serialize};
+
+use crate::dball::{COST_PER_TICKET, DBall, DEFAULT_BATCH_SIZE, Reward};
+use crate::generator::Generator;
+use crate::generator::frequency::Frequency;
+
+/// Winning numbers of a past draw and its period, e.g. `2025088`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PastDraw {
+    pub period: u64,
+    pub winning: DBall,
+}
+
+/// Generator, seed and budget replayed over past draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktestRun {
+    pub generator: Generator,
+    pub seed: u64,
+    /// Yuan spent per period, one batch of [`DEFAULT_BATCH_SIZE`] spots per period when `None`
+    pub budget: Option<u32>,
+}
+
+impl BacktestRun {
+    /// Spots bought each period, every spot costs the price of a single bet
+    pub fn spots_per_period(&self) -> usize {
+        match self.budget {
+            Some(budget) => budget as usize / COST_PER_TICKET,
+            None => DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+/// Outcome of one run, comparable across generators, seeds and budgets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BacktestResult {
+    pub generator: String,
+    pub seed: u64,
+    pub budget: Option<u32>,
+    pub periods: usize,
+    pub spots: usize,
+    pub cost: u64,
+    pub winnings: u64,
+    /// Spots that won each tier, first prize first
+    pub prizes: [usize; 6],
+}
+
+impl BacktestResult {
+    /// Totals of `run` over the `outcomes` of its draws
+    pub fn from_outcomes(run: &BacktestRun, outcomes: &[PeriodOutcome]) -> Self {
+        let mut result = Self {
+            generator: run.generator.name().to_owned(),
+            seed: run.seed,
+            budget: run.budget,
+            periods: 0,
+            spots: 0,
+            cost: 0,
+            winnings: 0,
+            prizes: [0; 6],
+        };
+        for outcome in outcomes {
+            result.periods += 1;
+            result.spots += outcome.spots;
+            result.cost += outcome.cost;
+            result.winnings += outcome.winnings;
+            for (total, won) in result.prizes.iter_mut().zip(outcome.prizes) {
+                *total += won;
+            }
+        }
+        result
+    }
+
+    pub fn net(&self) -> i64 {
+        self.winnings as i64 - self.cost as i64
+    }
+
+    /// Net return per yuan spent, 0 when nothing was spent
+    pub fn roi(&self) -> f64 {
+        if self.cost == 0 {
+            return 0.0;
+        }
+        self.net() as f64 / self.cost as f64
+    }
+}
+
+/// What the spots of a run cost and won in one past draw
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodOutcome {
+    pub period: u64,
+    pub spots: usize,
+    pub cost: u64,
+    pub winnings: u64,
+    /// Spots that won each tier, first prize first
+    pub prizes: [usize; 6],
+}
+
+/// Seed of a period's `batch`th batch, runs over the same periods differ only by their seed
+fn batch_seed(seed: u64, period: u64, batch: usize) -> u64 {
+    seed ^ period.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (batch as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9)
+}
+
+fn prize_tier(reward: &Reward) -> Option<usize> {
+    match reward {
+        Reward::FirstPrize => Some(0),
+        Reward::SecondPrize => Some(1),
+        Reward::ThirdPrize => Some(2),
+        Reward::FourthPrize => Some(3),
+        Reward::FifthPrize => Some(4),
+        Reward::SixthPrize => Some(5),
+        Reward::NoWin => None,
+    }
+}
+
+/// Generate the spots of `run` for every draw, oldest first, and check them against it
+pub fn replay(run: &BacktestRun, draws: &[PastDraw]) -> BacktestResult {
+    BacktestResult::from_outcomes(run, &replay_periods(run, draws))
+}
+
+/// [`replay`] draw by draw, one outcome per draw in the same order
+///
+/// Generators using history learn from the replayed draws before each period,
+/// up to [`Frequency::DEFAULT_WINDOW`] of them
+pub fn replay_periods(run: &BacktestRun, draws: &[PastDraw]) -> Vec<PeriodOutcome> {
+    let mut generator = Generator::create_generator(run.generator);
+    let per_period = run.spots_per_period();
+
+    let mut outcomes = Vec::with_capacity(draws.len());
+    for (replayed, draw) in draws.iter().enumerate() {
+        if let Some(aware) = generator.history_aware() {
+            let history = draws[replayed.saturating_sub(Frequency::DEFAULT_WINDOW)..replayed]
+                .iter()
+                .map(|draw| draw.winning)
+                .collect::<Vec<_>>();
+            aware.with_history(&history);
+        }
+
+        let mut outcome = PeriodOutcome {
+            period: draw.period,
+            spots: 0,
+            cost: 0,
+            winnings: 0,
+            prizes: [0; 6],
+        };
+        let spots = (0..per_period.div_ceil(DEFAULT_BATCH_SIZE))
+            .flat_map(|batch| {
+                let seed = batch_seed(run.seed, draw.period, batch);
+                generator.generate_seeded_batch(seed, DEFAULT_BATCH_SIZE).0
+            })
+            .take(per_period);
+        for spot in spots {
+            let reward = spot.check_prize(&draw.winning);
+            outcome.spots += 1;
+            outcome.cost += spot.cost() as u64;
+            outcome.winnings += u64::from(reward.prize_amount()) * spot.magnification as u64;
+            if let Some(tier) = prize_tier(&reward) {
+                outcome.prizes[tier] += 1;
+            }
+        }
+        outcomes.push(outcome);
+    }
+    outcomes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws() -> Vec<PastDraw> {
+        [
+            (2025001, [1, 5, 9, 14, 22, 30], 3),
+            (2025002, [2, 8, 11, 19, 27, 33], 12),
+            (2025003, [4, 6, 13, 17, 25, 31], 7),
+        ]
+        .into_iter()
+        .map(|(period, reds, blue)| PastDraw {
+            period,
+            winning: DBall::new_one(reds, blue).expect("Valid draw"),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        for generator in Generator::ALL {
+            let run = BacktestRun {
+                generator,
+                seed: 42,
+                budget: None,
+            };
+            assert_eq!(replay(&run, &draws()), replay(&run, &draws()));
+        }
+    }
+
+    #[test]
+    fn test_replay_spends_the_budget() {
+        let run = BacktestRun {
+            generator: Generator::BlueMorn,
+            seed: 7,
+            budget: Some(15),
+        };
+        let result = replay(&run, &draws());
+        assert_eq!(result.periods, 3);
+        assert_eq!(result.spots, 3 * 7);
+        assert_eq!(result.cost, 3 * 14);
+        assert!(
+            result.prizes.iter().sum::<usize>() <= result.spots,
+            "more prizes than spots"
+        );
+        assert_eq!(result.net(), result.winnings as i64 - 42);
+    }
+
+    #[test]
+    fn test_replay_counts_prizes() {
+        let mut draws = draws();
+        let run = BacktestRun {
+            generator: Generator::BlueMorn,
+            seed: 1,
+            budget: None,
+        };
+        // drawing the first spot of the run wins it the first prize
+        let seed = batch_seed(run.seed, draws[0].period, 0);
+        draws[0].winning = Generator::create_generator(run.generator)
+            .generate_seeded_batch(seed, 5)
+            .0[0];
+        let result = replay(&run, &draws[..1]);
+        assert!(result.prizes[0] >= 1, "{result:?}");
+        assert!(result.roi() > 0.0);
+    }
+
+    #[test]
+    fn test_replay_periods_add_up() {
+        let run = BacktestRun {
+            generator: Generator::BlueMorn,
+            seed: 3,
+            budget: Some(10),
+        };
+        let outcomes = replay_periods(&run, &draws());
+        let result = replay(&run, &draws());
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|outcome| outcome.period)
+                .collect::<Vec<_>>(),
+            [2025001, 2025002, 2025003]
+        );
+        assert_eq!(
+            outcomes.iter().map(|outcome| outcome.cost).sum::<u64>(),
+            result.cost
+        );
+        assert_eq!(
+            outcomes.iter().map(|outcome| outcome.winnings).sum::<u64>(),
+            result.winnings
+        );
+    }
+}
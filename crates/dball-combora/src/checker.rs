@@ -1,6 +1,11 @@
 use crate::dball::{DBall, DBallBatch};
-use std::collections::{HashMap, HashSet};
+use crate::game::{Game, GameRules};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DBallChecker {
     AllSingleDigits,
     AllEvenOrOdd,
@@ -13,152 +18,664 @@ pub enum DBallChecker {
     BatchBlueBallDuplicate,
     BatchBlueBallDistribution,
     BatchHighCosineSimilarity,
+    LongConsecutiveRun,
+    BatchConsecutiveRuns,
+    ZoneImbalance,
+    BatchZoneSkew,
+}
+
+/// How much a failed check speaks against a combination or batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Minor,
+    Major,
+    Critical,
+}
+
+impl DBallChecker {
+    /// Why the combination or batch is unlikely to win
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::AllSingleDigits => "All red balls are single digits",
+            Self::AllEvenOrOdd => "Red balls are all even or all odd",
+            Self::RedConflictsWithBlue => "The blue ball repeats a red ball",
+            Self::SumExtreme => "The sum of the red balls is extreme",
+            Self::RangeExtreme => "The red balls are spread too narrow or too wide",
+            Self::BatchRBallSumExtreme => "The red ball sums of the batch are extreme",
+            Self::BatchHasDuplicateCombinations => "The batch repeats a combination",
+            Self::BatchTopRedNumberFrequencies => "The batch leans on a few red balls",
+            Self::BatchBlueBallDuplicate => "The batch repeats blue balls",
+            Self::BatchBlueBallDistribution => "The blue balls of the batch are unevenly spread",
+            Self::BatchHighCosineSimilarity => "The combinations of the batch are too similar",
+            Self::LongConsecutiveRun => "Several red balls follow each other",
+            Self::BatchConsecutiveRuns => "Many combinations of the batch have red balls in a row",
+            Self::ZoneImbalance => "The red balls leave a low, middle or high zone empty",
+            Self::BatchZoneSkew => "The red balls of the batch crowd into some zones",
+        }
+    }
+
+    /// Grouped by how rarely winning numbers fail the check
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::BatchHasDuplicateCombinations
+            | Self::BatchTopRedNumberFrequencies
+            | Self::BatchBlueBallDuplicate => Severity::Critical,
+            Self::AllSingleDigits
+            | Self::RedConflictsWithBlue
+            | Self::SumExtreme
+            | Self::BatchBlueBallDistribution
+            | Self::BatchHighCosineSimilarity => Severity::Major,
+            Self::AllEvenOrOdd
+            | Self::RangeExtreme
+            | Self::BatchRBallSumExtreme
+            | Self::LongConsecutiveRun
+            | Self::BatchConsecutiveRuns
+            | Self::ZoneImbalance
+            | Self::BatchZoneSkew => Severity::Minor,
+        }
+    }
+}
+
+/// A failed check with the values failing it, e.g. `sum 112 outside 65-95`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckDetail {
+    pub check: DBallChecker,
+    pub severity: Severity,
+    pub explanation: String,
+    /// Index of the failing combination in its batch, `None` for checks of the whole batch
+    pub combination: Option<usize>,
+}
+
+impl CheckDetail {
+    fn new(check: DBallChecker, explanation: String) -> Self {
+        Self {
+            check,
+            severity: check.severity(),
+            explanation,
+            combination: None,
+        }
+    }
+}
+
+impl fmt::Display for CheckDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(index) = self.combination {
+            write!(f, "#{} ", index + 1)?;
+        }
+        write!(f, "{}: {}", self.check.description(), self.explanation)
+    }
+}
+
+/// Combination as its sorted front (red) and back (blue) numbers, any game
+pub type Numbers<'a> = (&'a [u8], &'a [u8]);
+
+/// Checks of a single combination of a game with `rules`
+pub fn evaluate_combination(rules: &GameRules, numbers: Numbers<'_>) -> Vec<DBallChecker> {
+    checks(evaluate_combination_detailed(rules, numbers))
+}
+
+/// [`evaluate_combination`] explaining every failed check
+pub fn evaluate_combination_detailed(
+    rules: &GameRules,
+    (front, back): Numbers<'_>,
+) -> Vec<CheckDetail> {
+    [
+        all_single_digits(front),
+        all_even_or_odd(front),
+        front_conflicts_with_back(front, back),
+        range_extreme(rules, front),
+        long_consecutive_run(front),
+        zone_imbalance(rules, front),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Checks of a batch of combinations of a game with `rules`
+pub fn evaluate_batch(rules: &GameRules, batch: &[Numbers<'_>]) -> Vec<DBallChecker> {
+    checks(evaluate_batch_detailed(rules, batch))
+}
+
+/// [`evaluate_batch`] explaining every failed check
+pub fn evaluate_batch_detailed(rules: &GameRules, batch: &[Numbers<'_>]) -> Vec<CheckDetail> {
+    [
+        duplicate_combinations(batch),
+        top_front_number_frequencies(batch, 5),
+        back_distribution(rules, batch),
+        duplicate_back(batch),
+        high_cosine_similarity(rules, batch),
+        batch_consecutive_runs(batch),
+        batch_zone_skew(rules, batch),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn checks(details: Vec<CheckDetail>) -> Vec<DBallChecker> {
+    details.into_iter().map(|detail| detail.check).collect()
+}
+
+fn join(numbers: &[u8]) -> String {
+    numbers
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bounds<T: fmt::Display>(range: &RangeInclusive<T>) -> String {
+    format!("{}-{}", range.start(), range.end())
+}
+
+fn all_single_digits(front: &[u8]) -> Option<CheckDetail> {
+    front.iter().all(|&n| n < 10).then(|| {
+        CheckDetail::new(
+            DBallChecker::AllSingleDigits,
+            format!("reds {} are all below 10", join(front)),
+        )
+    })
+}
+
+fn all_even_or_odd(front: &[u8]) -> Option<CheckDetail> {
+    let parity = if front.iter().all(|&n| n % 2 == 0) {
+        "even"
+    } else if front.iter().all(|&n| n % 2 == 1) {
+        "odd"
+    } else {
+        return None;
+    };
+    Some(CheckDetail::new(
+        DBallChecker::AllEvenOrOdd,
+        format!("reds {} are all {parity}", join(front)),
+    ))
+}
+
+fn front_conflicts_with_back(front: &[u8], back: &[u8]) -> Option<CheckDetail> {
+    back.iter().find(|n| front.contains(n)).map(|n| {
+        CheckDetail::new(
+            DBallChecker::RedConflictsWithBlue,
+            format!("blue {n} is also a red"),
+        )
+    })
+}
+
+fn front_sum(front: &[u8]) -> usize {
+    front.iter().map(|&n| usize::from(n)).sum()
+}
+
+fn sum_extreme(rules: &GameRules, front: &[u8]) -> Option<CheckDetail> {
+    let sum = front_sum(front);
+    (!rules.bounds.sum.contains(&sum)).then(|| {
+        CheckDetail::new(
+            DBallChecker::SumExtreme,
+            format!("sum {sum} outside {}", bounds(&rules.bounds.sum)),
+        )
+    })
+}
+
+fn range_extreme(rules: &GameRules, front: &[u8]) -> Option<CheckDetail> {
+    let min = front.iter().min().copied().unwrap_or(0);
+    let max = front.iter().max().copied().unwrap_or(rules.front.max);
+    let gap = max - min;
+    (!rules.bounds.gap.contains(&gap)).then(|| {
+        CheckDetail::new(
+            DBallChecker::RangeExtreme,
+            format!(
+                "gap {gap} from {min} to {max} outside {}",
+                bounds(&rules.bounds.gap)
+            ),
+        )
+    })
+}
+
+/// Consecutive front numbers from this many on count as a long run
+pub const MIN_RUN: usize = 3;
+
+/// Longest run of consecutive numbers in sorted `front`, the first one of the longest
+pub(crate) fn longest_run(front: &[u8]) -> &[u8] {
+    let mut longest = &front[..front.len().min(1)];
+    let mut start = 0;
+    for end in 1..=front.len() {
+        if end == front.len() || front[end] != front[end - 1] + 1 {
+            if end - start > longest.len() {
+                longest = &front[start..end];
+            }
+            start = end;
+        }
+    }
+    longest
+}
+
+fn long_consecutive_run(front: &[u8]) -> Option<CheckDetail> {
+    let run = longest_run(front);
+    (run.len() >= MIN_RUN).then(|| {
+        CheckDetail::new(
+            DBallChecker::LongConsecutiveRun,
+            format!("reds {} are in a row", join(run)),
+        )
+    })
+}
+
+fn batch_consecutive_runs(batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let runs = batch
+        .iter()
+        .filter(|(front, _)| longest_run(front).len() >= MIN_RUN)
+        .count();
+    (runs > fives(batch)).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchConsecutiveRuns,
+            format!(
+                "{runs} of {} spots have {MIN_RUN} or more reds in a row",
+                batch.len()
+            ),
+        )
+    })
+}
+
+/// The front numbers split into low, middle and high zones of equal size
+pub const ZONES: usize = 3;
+
+/// Zone of front number `n`, 0 for the low one
+pub(crate) fn zone(rules: &GameRules, n: u8) -> usize {
+    usize::from(n.saturating_sub(1)) * ZONES / usize::from(rules.front.max)
+}
+
+fn zone_counts(rules: &GameRules, front: &[u8]) -> [usize; ZONES] {
+    let mut counts = [0; ZONES];
+    for &n in front {
+        counts[zone(rules, n)] += 1;
+    }
+    counts
+}
+
+fn zone_imbalance(rules: &GameRules, front: &[u8]) -> Option<CheckDetail> {
+    let counts = zone_counts(rules, front);
+    counts.contains(&0).then(|| {
+        CheckDetail::new(
+            DBallChecker::ZoneImbalance,
+            format!("low, middle and high zones hold {counts:?} reds"),
+        )
+    })
+}
+
+fn batch_zone_skew(rules: &GameRules, batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let mut counts = [0; ZONES];
+    for (front, _) in batch {
+        for (total, count) in counts.iter_mut().zip(zone_counts(rules, front)) {
+            *total += count;
+        }
+    }
+    let most = counts.iter().max().copied().unwrap_or(0);
+    let least = counts.iter().min().copied().unwrap_or(0);
+    (most - least > 8 * fives(batch)).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchZoneSkew,
+            format!("low, middle and high zones hold {counts:?} reds"),
+        )
+    })
+}
+
+fn batch_sum_extreme(rules: &GameRules, batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let sum = batch
+        .iter()
+        .map(|(front, _)| front_sum(front))
+        .sum::<usize>();
+    let single = &rules.bounds.sum;
+    let total = single.start() * batch.len()..=single.end() * batch.len();
+    (!total.contains(&sum)).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchRBallSumExtreme,
+            format!("total sum {sum} outside {}", bounds(&total)),
+        )
+    })
+}
+
+fn duplicate_combinations(batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let mut seen = HashMap::new();
+    batch.iter().enumerate().find_map(|(index, combination)| {
+        seen.insert(combination, index).map(|first| {
+            CheckDetail::new(
+                DBallChecker::BatchHasDuplicateCombinations,
+                format!("#{} repeats #{}", index + 1, first + 1),
+            )
+        })
+    })
+}
+
+/// Tolerances of the batch checks are set for 5 spots and grow with every 5 more
+fn fives(batch: &[Numbers<'_>]) -> usize {
+    batch.len().div_ceil(5).max(1)
+}
+
+fn top_front_number_frequencies(batch: &[Numbers<'_>], top_n: usize) -> Option<CheckDetail> {
+    let mut freq = HashMap::new();
+    for (front, _) in batch {
+        for &n in *front {
+            *freq.entry(n).or_insert(0) += 1;
+        }
+    }
+    let mut freq_vec: Vec<_> = freq.into_iter().collect();
+    freq_vec.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let vec = freq_vec
+        .into_iter()
+        .take(top_n)
+        .collect::<Vec<(u8, usize)>>();
+    let (Some((first, count_first)), Some((_, count_last))) = (vec.first(), vec.last()) else {
+        return None;
+    };
+    let fives = fives(batch);
+    ((count_first - count_last).ge(&(3 * fives)) || (count_first.gt(&(2 * fives)))).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchTopRedNumberFrequencies,
+            format!(
+                "red {first} appears {count_first} times, the top {} {count_last} to {count_first} times",
+                vec.len()
+            ),
+        )
+    })
+}
+
+fn back_distribution(rules: &GameRules, batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let backs = batch.iter().flat_map(|(_, back)| back.iter());
+    let avg = backs.clone().map(|&n| f64::from(n)).sum::<f64>() / backs.count() as f64;
+    let average = &rules.bounds.back_average;
+    (!average.contains(&avg)).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchBlueBallDistribution,
+            format!("blue average {avg:.2} outside {}", bounds(average)),
+        )
+    })
+}
+
+fn duplicate_back(batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let mut counts = HashMap::new();
+    for (_, back) in batch {
+        *counts.entry(*back).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count > fives(batch))
+        .max_by_key(|&(back, count)| (count, std::cmp::Reverse(back)))
+        .map(|(back, count)| {
+            CheckDetail::new(
+                DBallChecker::BatchBlueBallDuplicate,
+                format!("blue {} appears {count} times", join(back)),
+            )
+        })
+}
+
+/// Front numbers then back numbers as one-hot vector
+fn to_vector(rules: &GameRules, (front, back): Numbers<'_>) -> Vec<f32> {
+    let front_len = usize::from(rules.front.max);
+    let mut vec = vec![0.0f32; front_len + usize::from(rules.back.max)];
+    for &num in front {
+        vec[usize::from(num) - 1] = 1.0;
+    }
+    for &num in back {
+        vec[front_len + usize::from(num) - 1] = 1.0;
+    }
+    vec
+}
+
+/// Cosine similarity of every pair of combinations in `batch`
+pub fn cosine_similarity(rules: &GameRules, batch: &[Numbers<'_>]) -> Vec<f32> {
+    fn calc_cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+        let dot = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f32>();
+        let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+        if norm_a == 0.0 || norm_b == 0.0 {
+            0.0
+        } else {
+            dot / (norm_a * norm_b)
+        }
+    }
+
+    let vectors: Vec<_> = batch
+        .iter()
+        .map(|numbers| to_vector(rules, *numbers))
+        .collect();
+    let len = vectors.len();
+    let mut sims = Vec::new();
+
+    for i in 0..len {
+        for j in i + 1..len {
+            sims.push(calc_cosine_similarity(&vectors[i], &vectors[j]));
+        }
+    }
+    sims
+}
+
+fn high_cosine_similarity(rules: &GameRules, batch: &[Numbers<'_>]) -> Option<CheckDetail> {
+    let sims = cosine_similarity(rules, batch);
+
+    // at least half of the pairs share no number
+    let disjoint = sims.iter().filter(|&&t| t == 0.0).count();
+    let highest = sims.iter().copied().fold(0.0, f32::max);
+    (disjoint * 2 < sims.len() || highest > 0.3).then(|| {
+        CheckDetail::new(
+            DBallChecker::BatchHighCosineSimilarity,
+            format!(
+                "{disjoint} of {} pairs share no number, highest similarity {highest:.2}",
+                sims.len()
+            ),
+        )
+    })
 }
 
 impl DBall {
+    fn numbers(&self) -> Numbers<'_> {
+        (&self.rball, std::slice::from_ref(&self.bball))
+    }
+
     pub fn is_all_single_digits(&self) -> Option<DBallChecker> {
-        self.rball
-            .iter()
-            .all(|&n| n < 10)
-            .then_some(DBallChecker::AllSingleDigits)
+        all_single_digits(&self.rball).map(|detail| detail.check)
     }
 
     pub fn is_all_even_or_odd(&self) -> Option<DBallChecker> {
-        (self.rball.iter().all(|&n| n % 2 == 0) || self.rball.iter().all(|&n| n % 2 == 1))
-            .then_some(DBallChecker::AllEvenOrOdd)
+        all_even_or_odd(&self.rball).map(|detail| detail.check)
     }
 
     pub fn red_conflicts_with_blue(&self) -> Option<DBallChecker> {
-        self.rball
-            .contains(&self.bball)
-            .then_some(DBallChecker::RedConflictsWithBlue)
+        front_conflicts_with_back(&self.rball, &[self.bball]).map(|detail| detail.check)
     }
 
     pub fn sum_extreme(&self) -> Option<DBallChecker> {
-        const SUM_EXTREME_MIN: u8 = 13 * 5;
-        const SUM_EXTREME_MAX: u8 = 19 * 5;
-        let sum = self.rball.iter().copied().sum::<u8>();
-        (!(SUM_EXTREME_MIN..=SUM_EXTREME_MAX).contains(&sum)).then_some(DBallChecker::SumExtreme)
+        sum_extreme(Game::Ssq.rules(), &self.rball).map(|detail| detail.check)
     }
 
     pub fn is_range_extreme(&self) -> Option<DBallChecker> {
-        const MIN_GAP: u8 = 8;
-        const MAX_GAP: u8 = 25;
-        let min = *self.rball.iter().min().unwrap_or(&0);
-        let max = *self.rball.iter().max().unwrap_or(&33);
-        let gap = max - min;
-        (!((MIN_GAP)..=(MAX_GAP)).contains(&gap)).then_some(DBallChecker::RangeExtreme)
+        range_extreme(Game::Ssq.rules(), &self.rball).map(|detail| detail.check)
+    }
+
+    pub fn zone_imbalance(&self) -> Option<DBallChecker> {
+        zone_imbalance(Game::Ssq.rules(), &self.rball).map(|detail| detail.check)
+    }
+
+    pub fn has_long_consecutive_run(&self) -> Option<DBallChecker> {
+        long_consecutive_run(&self.rball).map(|detail| detail.check)
     }
 
     pub fn evaluate(&self) -> Vec<DBallChecker> {
-        let mut checks = Vec::new();
-        if let Some(check) = self.is_all_single_digits() {
-            checks.push(check);
-        }
-        if let Some(check) = self.is_all_even_or_odd() {
-            checks.push(check);
-        }
-        if let Some(check) = self.red_conflicts_with_blue() {
-            checks.push(check);
-        }
-        if let Some(check) = self.is_range_extreme() {
-            checks.push(check);
-        }
-        checks
+        evaluate_combination(Game::Ssq.rules(), self.numbers())
+    }
+
+    /// [`Self::evaluate`] explaining every failed check
+    pub fn evaluate_detailed(&self) -> Vec<CheckDetail> {
+        evaluate_combination_detailed(Game::Ssq.rules(), self.numbers())
     }
 }
 
 impl DBallBatch {
+    pub(crate) fn numbers(&self) -> Vec<Numbers<'_>> {
+        self.0.iter().map(DBall::numbers).collect()
+    }
+
     pub fn batch_sum_extreme(&self) -> Option<DBallChecker> {
-        const SUM_EXTREME_MIN: usize = 13 * 5 * 5;
-        const SUM_EXTREME_MAX: usize = 19 * 5 * 5;
-        let sum = self
-            .0
-            .iter()
-            .map(|b| b.rball.iter().map(|n| *n as usize).sum::<usize>())
-            .sum::<usize>();
-        (!(SUM_EXTREME_MIN..=SUM_EXTREME_MAX).contains(&sum))
-            .then_some(DBallChecker::BatchRBallSumExtreme)
+        batch_sum_extreme(Game::Ssq.rules(), &self.numbers()).map(|detail| detail.check)
     }
 
     pub fn has_duplicate_combinations(&self) -> Option<DBallChecker> {
-        let mut seen = HashSet::new();
-        for ball in &self.0 {
-            let mut key = ball.rball.to_vec();
-            key.sort_unstable();
-            key.push(ball.bball); // Include blue ball in combination uniqueness
-            if !seen.insert(key) {
-                return Some(DBallChecker::BatchHasDuplicateCombinations);
-            }
-        }
-        None
+        duplicate_combinations(&self.numbers()).map(|detail| detail.check)
     }
 
     pub fn top_red_number_frequencies(&self, top_n: usize) -> Option<DBallChecker> {
-        let mut freq = HashMap::new();
-        for ball in &self.0 {
-            for &n in &ball.rball {
-                *freq.entry(n).or_insert(0) += 1;
-            }
-        }
-        let mut freq_vec: Vec<_> = freq.into_iter().collect();
-        freq_vec.sort_by(|a, b| b.1.cmp(&a.1));
-        let vec = freq_vec
-            .into_iter()
-            .take(top_n)
-            .collect::<Vec<(u8, usize)>>();
-        if let (Some((_, count_first)), Some((_, count_last))) = (vec.first(), vec.last()) {
-            ((count_first - count_last).ge(&3) || (count_first.gt(&2)))
-                .then_some(DBallChecker::BatchTopRedNumberFrequencies)
-        } else {
-            None
-        }
+        top_front_number_frequencies(&self.numbers(), top_n).map(|detail| detail.check)
     }
 
     pub fn blue_ball_distribution(&self) -> Option<DBallChecker> {
-        let avg = self.0.iter().map(|b| b.bball).sum::<u8>() as f64 / self.0.len() as f64;
-        if !(6.0..=10.0).contains(&avg) {
-            return Some(DBallChecker::BatchBlueBallDistribution);
-        }
-        None
+        back_distribution(Game::Ssq.rules(), &self.numbers()).map(|detail| detail.check)
     }
 
     pub fn duplicate_bball(&self) -> Option<DBallChecker> {
-        let mut bball_count = HashMap::new();
-        for ball in &self.0 {
-            *bball_count.entry(ball.bball).or_insert(0) += 1;
-        }
-        if bball_count.values().any(|&count| count > 1) {
-            Some(DBallChecker::BatchBlueBallDuplicate)
-        } else {
-            None
-        }
+        duplicate_back(&self.numbers()).map(|detail| detail.check)
     }
 
     pub fn has_high_cosine_similarity(&self) -> Option<DBallChecker> {
-        let sims = self.cosine_similarity();
+        high_cosine_similarity(Game::Ssq.rules(), &self.numbers()).map(|detail| detail.check)
+    }
 
-        (sims.iter().filter(|&&t| t == 0.0).count().le(&4) || sims.iter().any(|&sim| sim > 0.3))
-            .then_some(DBallChecker::BatchHighCosineSimilarity)
+    pub fn zone_skew(&self) -> Option<DBallChecker> {
+        batch_zone_skew(Game::Ssq.rules(), &self.numbers()).map(|detail| detail.check)
+    }
+
+    pub fn consecutive_runs(&self) -> Option<DBallChecker> {
+        batch_consecutive_runs(&self.numbers()).map(|detail| detail.check)
     }
 
     pub fn evaluate(&self) -> Vec<DBallChecker> {
-        let mut checks = Vec::new();
-        if let Some(check) = self.has_duplicate_combinations() {
-            checks.push(check);
-        }
-        if let Some(check) = self.top_red_number_frequencies(5) {
-            checks.push(check);
-        }
-        if let Some(check) = self.blue_ball_distribution() {
-            checks.push(check);
-        }
-        if let Some(check) = self.duplicate_bball() {
-            checks.push(check);
-        }
-        if let Some(check) = self.has_high_cosine_similarity() {
-            checks.push(check);
+        evaluate_batch(Game::Ssq.rules(), &self.numbers())
+    }
+
+    /// Why the batch would be rejected or flagged, its own checks and those
+    /// of every spot, worst first
+    pub fn evaluate_detailed(&self) -> Vec<CheckDetail> {
+        let rules = Game::Ssq.rules();
+        let numbers = self.numbers();
+        let mut details = evaluate_batch_detailed(rules, &numbers);
+        for (index, spot) in numbers.iter().enumerate() {
+            details.extend(
+                evaluate_combination_detailed(rules, *spot)
+                    .into_iter()
+                    .map(|detail| CheckDetail {
+                        combination: Some(index),
+                        ..detail
+                    }),
+            );
         }
-        checks
+        details.sort_by_key(|detail| std::cmp::Reverse(detail.severity));
+        details
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combination_details() -> anyhow::Result<()> {
+        let dball = DBall::new_one([2, 4, 6, 8, 10, 12], 4)?;
+        let details = dball.evaluate_detailed();
+        assert_eq!(checks(details.clone()), dball.evaluate());
+        assert_eq!(
+            details
+                .iter()
+                .map(|detail| detail.explanation.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "reds 2 4 6 8 10 12 are all even",
+                "blue 4 is also a red",
+                "low, middle and high zones hold [5, 1, 0] reds",
+            ]
+        );
+        assert_eq!(
+            sum_extreme(Game::Ssq.rules(), &dball.rball).map(|detail| detail.explanation),
+            Some("sum 42 outside 65-95".to_owned())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_consecutive_runs() -> anyhow::Result<()> {
+        assert_eq!(longest_run(&[2, 5, 6, 7, 20, 21]), [5, 6, 7]);
+        assert_eq!(longest_run(&[1, 3, 5]), [1]);
+        assert!(longest_run(&[]).is_empty());
+
+        let run = DBall::new_one([4, 11, 12, 13, 25, 30], 7)?;
+        assert_eq!(
+            run.has_long_consecutive_run(),
+            Some(DBallChecker::LongConsecutiveRun)
+        );
+        assert_eq!(
+            long_consecutive_run(&run.rball).map(|detail| detail.explanation),
+            Some("reds 11 12 13 are in a row".to_owned())
+        );
+        let pairs = DBall::new_one([4, 11, 12, 20, 21, 30], 7)?;
+        assert_eq!(pairs.has_long_consecutive_run(), None);
+
+        let batch = DBallBatch(vec![run, pairs, run, pairs, pairs]);
+        assert_eq!(
+            batch.consecutive_runs(),
+            Some(DBallChecker::BatchConsecutiveRuns)
+        );
+        let batch = DBallBatch(vec![run, pairs, pairs, pairs, pairs]);
+        assert_eq!(batch.consecutive_runs(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_zone_balance() -> anyhow::Result<()> {
+        let rules = Game::Ssq.rules();
+        assert_eq!(
+            [1, 11, 12, 22, 23, 33].map(|n| zone(rules, n)),
+            [0, 0, 1, 1, 2, 2]
+        );
+
+        let low_and_middle = DBall::new_one([2, 5, 9, 13, 18, 21], 7)?;
+        assert_eq!(
+            low_and_middle.zone_imbalance(),
+            Some(DBallChecker::ZoneImbalance)
+        );
+        assert_eq!(
+            zone_imbalance(rules, &low_and_middle.rball).map(|detail| detail.explanation),
+            Some("low, middle and high zones hold [3, 3, 0] reds".to_owned())
+        );
+        let spread = DBall::new_one([3, 8, 14, 19, 26, 31], 7)?;
+        assert_eq!(spread.zone_imbalance(), None);
+
+        let skewed = DBallBatch(vec![low_and_middle; 5]);
+        assert_eq!(skewed.zone_skew(), Some(DBallChecker::BatchZoneSkew));
+        let balanced = DBallBatch(vec![low_and_middle, spread, spread, spread, spread]);
+        assert_eq!(balanced.zone_skew(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_details() -> anyhow::Result<()> {
+        let dball = DBall::new_one([3, 11, 17, 24, 26, 28], 9)?;
+        let batch = DBallBatch(vec![dball; 5]);
+        let details = batch.evaluate_detailed();
+        assert!(details.iter().all(|detail| detail.combination.is_none()));
+        assert_eq!(details[0].severity, Severity::Critical);
+        assert!(
+            details.iter().any(
+                |detail| detail.to_string() == "The batch repeats a combination: #2 repeats #1"
+            ),
+            "{details:?}"
+        );
+        assert!(
+            details
+                .iter()
+                .any(|detail| detail.explanation == "blue 9 appears 5 times")
+        );
+
+        let mut checks = checks(details);
+        let mut expected = batch.evaluate();
+        checks.sort_by_key(DBallChecker::description);
+        expected.sort_by_key(DBallChecker::description);
+        assert_eq!(checks, expected);
+        Ok(())
     }
 }
@@ -1,6 +1,19 @@
 mod bits;
+mod builder;
 pub mod check;
+mod complex;
+mod coverage;
+mod dantuo;
 mod def;
+mod prize;
+mod summary;
+mod text;
 
 pub use bits::DBallBit;
-pub use def::{DBall, DBallBatch, DBallError, Reward};
+pub use builder::DBallBuilder;
+pub use complex::ComplexBet;
+pub use dantuo::DantuoBet;
+pub use def::{COST_PER_TICKET, DBall, DBallBatch, DBallError, DEFAULT_BATCH_SIZE, Reward};
+pub use prize::PrizeTable;
+pub use summary::{BatchSummary, Spread};
+pub use text::CSV_HEADER;
@@ -1,7 +1,8 @@
-use super::DBall;
+use super::{DBall, Reward};
 
 const RED_BASE: u32 = 0; // Red ball starting bit index
 const BLUE_BASE: u32 = 33; // Blue ball starting bit index
+const RED_MASK: u64 = (1 << BLUE_BASE) - 1; // Bits of the red balls
 const TOTAL_ONES: u32 = 7; // Fixed 7 ones in each one-hot encoding
 
 /// one-hot bits display for dball
@@ -55,6 +56,15 @@ impl DBallBit {
         (self.bits & other.bits).count_ones()
     }
 
+    /// Prize level against the winning ticket, the same as [`DBall::check_prize`]
+    /// with the matches counted from a single AND of the bits
+    pub fn check_prize(&self, winning_ticket: &Self) -> Reward {
+        let shared = self.bits & winning_ticket.bits;
+        let red_matches = (shared & RED_MASK).count_ones() as usize;
+        let blue_matches = shared & !RED_MASK != 0;
+        Reward::from_matches(red_matches, blue_matches)
+    }
+
     /// expose bits for serialization/debugging
     pub fn bits(&self) -> u64 {
         self.bits
@@ -113,4 +123,35 @@ mod tests {
         assert_eq!(aa.euclidean_distance(&dd), (ham as f64).sqrt());
         assert!((aa.cosine_distance(&dd) - (1.0 - shared_one as f64 / 7.0)).abs() < 1e-12);
     }
+
+    #[test]
+    fn test_check_prize_matches_array_check() {
+        let winning_ticket = DBall {
+            rball: [1, 5, 8, 12, 25, 30],
+            bball: 6,
+            magnification: 1,
+        };
+        let winning_bits = DBallBit::from_dball(&winning_ticket);
+        let tickets = [
+            ([1, 5, 8, 12, 25, 30], 6),
+            ([1, 5, 8, 12, 25, 30], 7),
+            ([1, 5, 8, 12, 25, 31], 6),
+            ([1, 5, 8, 12, 20, 31], 6),
+            ([1, 5, 8, 20, 21, 22], 7),
+            ([2, 3, 4, 20, 21, 22], 6),
+            ([2, 3, 4, 20, 21, 22], 7),
+        ];
+        for (rball, bball) in tickets {
+            let ticket = DBall {
+                rball,
+                bball,
+                magnification: 1,
+            };
+            assert_eq!(
+                DBallBit::from_dball(&ticket).check_prize(&winning_bits),
+                ticket.check_prize(&winning_ticket),
+                "{rball:?} + {bball}"
+            );
+        }
+    }
 }
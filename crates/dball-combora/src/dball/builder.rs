@@ -0,0 +1,116 @@
+use rand::seq::SliceRandom as _;
+
+use super::{DBall, DBallError};
+
+/// Builds a [`DBall`] step by step, validated by [`Self::build`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DBallBuilder {
+    reds: Vec<u8>,
+    blue: Option<u8>,
+    magnification: usize,
+}
+
+impl Default for DBallBuilder {
+    fn default() -> Self {
+        Self {
+            reds: Vec::new(),
+            blue: None,
+            magnification: 1,
+        }
+    }
+}
+
+impl DBall {
+    pub fn builder() -> DBallBuilder {
+        DBallBuilder::default()
+    }
+}
+
+impl DBallBuilder {
+    /// Red balls in any order, replacing the ones set before
+    pub fn reds(mut self, reds: impl Into<Vec<u8>>) -> Self {
+        self.reds = reds.into();
+        self
+    }
+
+    /// One more red ball
+    pub fn red(mut self, red: u8) -> Self {
+        self.reds.push(red);
+        self
+    }
+
+    pub fn blue(mut self, blue: u8) -> Self {
+        self.blue = Some(blue);
+        self
+    }
+
+    /// Times the bet is bought, 1 unless set
+    pub fn magnification(mut self, magnification: usize) -> Self {
+        self.magnification = magnification;
+        self
+    }
+
+    /// Pick the missing reds from the ones not set yet and the blue if unset
+    pub fn random_fill(mut self, rng: &mut impl rand::Rng) -> Self {
+        let free = (1..=33)
+            .filter(|red| !self.reds.contains(red))
+            .collect::<Vec<u8>>();
+        let missing = 6_usize.saturating_sub(self.reds.len());
+        self.reds.extend(free.choose_multiple(rng, missing));
+        if self.blue.is_none() {
+            self.blue = Some(rng.gen_range(1..=16));
+        }
+        self
+    }
+
+    /// The `DBall`, validated like [`DBall::new`], an unset blue is out of range
+    pub fn build(self) -> Result<DBall, DBallError> {
+        DBall::new(self.reds, self.blue.unwrap_or(0), self.magnification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng as _;
+
+    #[test]
+    fn test_builder_validates() {
+        let dball = DBall::builder()
+            .reds([33, 1, 12, 7, 25])
+            .red(18)
+            .blue(16)
+            .build();
+        assert_eq!(dball, DBall::new_one([1, 7, 12, 18, 25, 33], 16));
+        assert_eq!(
+            DBall::builder().reds([1, 2, 3, 4, 5, 6]).build(),
+            Err(DBallError::InvalidBBall(0))
+        );
+        assert_eq!(
+            DBall::builder().reds([1, 2, 3]).blue(4).build(),
+            Err(DBallError::InvalidRBallCount(3))
+        );
+    }
+
+    #[test]
+    fn test_random_fill_keeps_picked_balls() -> anyhow::Result<()> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        for _ in 0..100 {
+            let dball = DBall::builder()
+                .reds([5, 30])
+                .magnification(3)
+                .random_fill(&mut rng)
+                .build()?;
+            assert!(dball.rball.contains(&5) && dball.rball.contains(&30));
+            assert_eq!(dball.magnification, 3);
+        }
+
+        let full = DBall::builder()
+            .reds([1, 2, 3, 4, 5, 6])
+            .blue(7)
+            .random_fill(&mut rng)
+            .build()?;
+        assert_eq!(full, DBall::new_one([1, 2, 3, 4, 5, 6], 7)?);
+        Ok(())
+    }
+}
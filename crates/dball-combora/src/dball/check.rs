@@ -1,5 +1,20 @@
 use crate::dball::{DBall, Reward};
 
+impl Reward {
+    /// Prize level of a ticket matching `red_matches` red balls and the blue ball if `blue_matches`
+    pub fn from_matches(red_matches: usize, blue_matches: bool) -> Self {
+        match (red_matches, blue_matches) {
+            (6, true) => Self::FirstPrize,
+            (6, false) => Self::SecondPrize,
+            (5, true) => Self::ThirdPrize,
+            (5, false) | (4, true) => Self::FourthPrize,
+            (4, false) | (3, true) => Self::FifthPrize,
+            (_, true) => Self::SixthPrize,
+            _ => Self::NoWin,
+        }
+    }
+}
+
 impl DBall {
     /// Check prize level
     ///
@@ -21,15 +36,7 @@ impl DBall {
         let blue_matches = self.bball == winning_ticket.bball;
 
         // Determine prize based on matches
-        match (red_matches, blue_matches) {
-            (6, true) => Reward::FirstPrize,
-            (6, false) => Reward::SecondPrize,
-            (5, true) => Reward::ThirdPrize,
-            (5, false) | (4, true) => Reward::FourthPrize,
-            (4, false) | (3, true) => Reward::FifthPrize,
-            (_, true) => Reward::SixthPrize,
-            _ => Reward::NoWin,
-        }
+        Reward::from_matches(red_matches, blue_matches)
     }
 
     /// Check multiple tickets against a winning ticket
@@ -228,9 +235,23 @@ mod tests {
         assert_eq!(ticket1.bball, ticket2.bball);
     }
 
+    #[test]
+    fn test_generator_seeded_batch() {
+        use crate::generator::RandomGenerator as _;
+
+        let batch1 = BlueMorn::default().generate_seeded_batch(2024, 5);
+        let batch2 = BlueMorn::default().generate_seeded_batch(2024, 5);
+        assert_eq!(batch1, batch2);
+        assert_ne!(batch1, BlueMorn::default().generate_seeded_batch(2025, 5));
+        assert_eq!(
+            BlueMorn::default().generate_seeded_batch(2024, 3).0.len(),
+            3
+        );
+    }
+
     #[test]
     fn test_generator_red_range() {
-        let result = BlueMorn::generate_with_red_range(1, 10, Some(5));
+        let result = BlueMorn::default().generate_with_red_range(1, 10, Some(5));
         assert!(result.is_ok());
 
         let ticket = result.expect("Valid limited ticket");
@@ -242,7 +263,7 @@ mod tests {
 
     #[test]
     fn test_generator_red_range_invalid() {
-        let result = BlueMorn::generate_with_red_range(1, 5, Some(5));
+        let result = BlueMorn::default().generate_with_red_range(1, 5, Some(5));
         assert!(result.is_err());
         assert_eq!(
             result,
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use super::{COST_PER_TICKET, DBall, DBallError, Reward};
+use crate::checker::{CheckDetail, DBallChecker};
+
+/// 复式 bet, more than 6 reds or more than one blue bought as every simple bet they make up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplexBet {
+    /// Sorted, 6 to [`Self::MAX_RBALLS`]
+    pub rball: Vec<u8>,
+    /// Sorted, 1 to 16
+    pub bball: Vec<u8>,
+    pub magnification: usize,
+}
+
+impl ComplexBet {
+    pub const MAX_RBALLS: usize = 20;
+
+    pub fn new(
+        rball: impl Into<Vec<u8>>,
+        bball: impl Into<Vec<u8>>,
+        magnification: usize,
+    ) -> Result<Self, DBallError> {
+        let mut rball = rball.into();
+        let mut bball = bball.into();
+        if !(6..=Self::MAX_RBALLS).contains(&rball.len()) || !(1..=16).contains(&bball.len()) {
+            return Err(DBallError::InvalidComplexCount {
+                reds: rball.len(),
+                blues: bball.len(),
+            });
+        }
+        if let Some(&red) = rball.iter().find(|red| !(1..=33).contains(*red)) {
+            return Err(DBallError::RBallOutOfRange(red));
+        }
+        if let Some(&blue) = bball.iter().find(|blue| !(1..=16).contains(*blue)) {
+            return Err(DBallError::InvalidBBall(blue));
+        }
+        rball.sort_unstable();
+        bball.sort_unstable();
+        if rball.windows(2).any(|w| w[0] == w[1]) {
+            return Err(DBallError::RBallDuplicate);
+        }
+        if bball.windows(2).any(|w| w[0] == w[1]) {
+            return Err(DBallError::BBallDuplicate);
+        }
+        Ok(Self {
+            rball,
+            bball,
+            magnification,
+        })
+    }
+
+    /// Whether the bet is a single simple bet
+    pub fn is_simple(&self) -> bool {
+        self.rball.len() == 6 && self.bball.len() == 1
+    }
+
+    /// Simple bets the complex bet covers, C(reds, 6) for every blue
+    pub fn bets(&self) -> usize {
+        let reds = self.rball.len();
+        // C(n, 6) multiplied out one factor at a time stays exact
+        let red_bets = (0..6).fold(1, |bets, i| bets * (reds - i) / (i + 1));
+        red_bets * self.bball.len()
+    }
+
+    pub fn cost(&self) -> usize {
+        self.bets() * self.magnification * COST_PER_TICKET
+    }
+
+    /// Every simple bet of the complex bet, with its magnification
+    pub fn expand(&self) -> Vec<DBall> {
+        let mut bets = Vec::with_capacity(self.bets());
+        let mut picked = [0, 1, 2, 3, 4, 5];
+        let reds = self.rball.len();
+        loop {
+            let rball = picked.map(|i| self.rball[i]);
+            bets.extend(self.bball.iter().map(|&bball| DBall {
+                rball,
+                bball,
+                magnification: self.magnification,
+            }));
+
+            // next 6 of the reds in lexicographic order
+            let Some(i) = (0..6).rev().find(|&i| picked[i] < reds - 6 + i) else {
+                return bets;
+            };
+            picked[i] += 1;
+            for j in i + 1..6 {
+                picked[j] = picked[j - 1] + 1;
+            }
+        }
+    }
+
+    /// Prizes of the simple bets winning in the draw of `winning`
+    pub fn check_prizes(&self, winning: &DBall) -> Vec<Reward> {
+        self.expand()
+            .iter()
+            .map(|bet| bet.check_prize(winning))
+            .filter(|reward| *reward != Reward::NoWin)
+            .collect()
+    }
+
+    /// Checks failed by some simple bet, each once
+    pub fn evaluate(&self) -> Vec<DBallChecker> {
+        let mut checks = Vec::new();
+        for bet in self.expand() {
+            for check in bet.evaluate() {
+                if !checks.contains(&check) {
+                    checks.push(check);
+                }
+            }
+        }
+        checks
+    }
+
+    /// Failed checks of every simple bet, the combination is its index in [`Self::expand`]
+    ///
+    /// Batch checks are left out, the bets share their numbers by design
+    pub fn evaluate_detailed(&self) -> Vec<CheckDetail> {
+        self.expand()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, bet)| {
+                bet.evaluate_detailed()
+                    .into_iter()
+                    .map(move |detail| CheckDetail {
+                        combination: Some(index),
+                        ..detail
+                    })
+            })
+            .collect()
+    }
+}
+
+impl From<DBall> for ComplexBet {
+    fn from(dball: DBall) -> Self {
+        Self {
+            rball: dball.rball.to_vec(),
+            bball: vec![dball.bball],
+            magnification: dball.magnification,
+        }
+    }
+}
+
+impl Display for ComplexBet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use console::style;
+
+        let join = |numbers: &[u8]| {
+            numbers
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        write!(
+            f,
+            "{} {}",
+            style(join(&self.rball)).red().bold(),
+            style(join(&self.bball)).blue().bold()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complex_cost() -> anyhow::Result<()> {
+        let bet = ComplexBet::new([1, 5, 9, 14, 22, 30, 31], [3], 1)?;
+        assert_eq!(bet.bets(), 7);
+        assert_eq!(bet.cost(), 14);
+
+        let wide = ComplexBet::new((1..=20).collect::<Vec<_>>(), [3, 9], 2)?;
+        assert_eq!(wide.bets(), 38760 * 2);
+        assert_eq!(wide.cost(), 38760 * 2 * 2 * COST_PER_TICKET);
+
+        let simple = ComplexBet::from(DBall::new_one([1, 5, 9, 14, 22, 30], 3)?);
+        assert!(simple.is_simple());
+        assert_eq!(simple.cost(), COST_PER_TICKET);
+
+        assert_eq!(
+            ComplexBet::new((1..=21).collect::<Vec<_>>(), [3], 1),
+            Err(DBallError::InvalidComplexCount { reds: 21, blues: 1 })
+        );
+        assert_eq!(
+            ComplexBet::new([1, 5, 9, 14, 22, 30, 31], [3, 3], 1),
+            Err(DBallError::BBallDuplicate)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_and_check() -> anyhow::Result<()> {
+        let bet = ComplexBet::new([30, 1, 5, 9, 14, 22, 31], [3, 7], 1)?;
+        let bets = bet.expand();
+        assert_eq!(bets.len(), bet.bets());
+        assert_eq!(bets[0], DBall::new_one([1, 5, 9, 14, 22, 30], 3)?);
+        assert_eq!(bets[13], DBall::new_one([5, 9, 14, 22, 30, 31], 7)?);
+        let mut unique = bets.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), bets.len());
+
+        // the winning reds are 6 of the 7, the blue is one of the 2
+        let winning = DBall::new_one([1, 5, 9, 14, 22, 30], 7)?;
+        let prizes = bet.check_prizes(&winning);
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::FirstPrize).count(),
+            1
+        );
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::SecondPrize).count(),
+            1
+        );
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::ThirdPrize).count(),
+            6
+        );
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::FourthPrize).count(),
+            6
+        );
+
+        let checks = bet.evaluate_detailed();
+        assert!(
+            checks
+                .iter()
+                .all(|detail| detail.combination.is_some_and(|i| i < bets.len()))
+        );
+        Ok(())
+    }
+}
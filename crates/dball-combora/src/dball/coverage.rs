@@ -0,0 +1,105 @@
+use super::{DBallBatch, DBallBit};
+
+impl DBallBatch {
+    /// Distinct red and blue numbers the batch covers, up to 33 + 16
+    pub fn coverage(&self) -> u32 {
+        self.0
+            .iter()
+            .fold(0, |covered, dball| {
+                covered | DBallBit::from_dball(dball).bits()
+            })
+            .count_ones()
+    }
+
+    /// Numbers every pair of spots shares, summed over the pairs
+    pub fn pairwise_overlap(&self) -> u32 {
+        let bits = self.0.iter().map(DBallBit::from_dball).collect::<Vec<_>>();
+        bits.iter()
+            .enumerate()
+            .flat_map(|(i, a)| bits[i + 1..].iter().map(|b| a.shared_ones(b)))
+            .sum()
+    }
+
+    /// Pick `n` of the batch's spots covering as many numbers as possible
+    ///
+    /// Greedy set cover: each step takes the spot adding the most uncovered
+    /// numbers, then the one sharing the fewest numbers with the spots taken,
+    /// then the earliest. Picked spots keep their order in the batch, so the
+    /// candidates may come from batches that passed the similarity checks
+    pub fn optimize_coverage(&self, n: usize) -> Self {
+        if n >= self.0.len() {
+            return self.clone();
+        }
+
+        let bits = self.0.iter().map(DBallBit::from_dball).collect::<Vec<_>>();
+        let mut picked = Vec::with_capacity(n);
+        let mut covered = 0_u64;
+        for _ in 0..n {
+            let Some(best) = (0..bits.len())
+                .filter(|i| !picked.contains(i))
+                .max_by_key(|&i| {
+                    let gain = (bits[i].bits() & !covered).count_ones();
+                    let overlap = picked
+                        .iter()
+                        .map(|&j: &usize| bits[i].shared_ones(&bits[j]))
+                        .sum::<u32>();
+                    (gain, std::cmp::Reverse(overlap), std::cmp::Reverse(i))
+                })
+            else {
+                break;
+            };
+            covered |= bits[best].bits();
+            picked.push(best);
+        }
+
+        picked.sort_unstable();
+        Self(picked.into_iter().map(|i| self.0[i]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dball::DBall;
+
+    #[test]
+    fn test_optimize_coverage() -> anyhow::Result<()> {
+        let candidates = DBallBatch(vec![
+            DBall::new_one([1, 2, 3, 4, 5, 6], 1)?,
+            DBall::new_one([1, 2, 3, 4, 5, 7], 1)?,
+            DBall::new_one([8, 9, 10, 11, 12, 13], 2)?,
+            DBall::new_one([1, 2, 8, 9, 14, 15], 2)?,
+            DBall::new_one([16, 17, 18, 19, 20, 21], 3)?,
+        ]);
+        let optimized = candidates.optimize_coverage(3);
+        assert_eq!(
+            optimized.0,
+            [candidates.0[0], candidates.0[2], candidates.0[4]]
+        );
+        assert_eq!(optimized.coverage(), 21);
+        assert_eq!(optimized.pairwise_overlap(), 0);
+        assert!(candidates.pairwise_overlap() > 0);
+
+        assert_eq!(candidates.optimize_coverage(9), candidates);
+        assert!(candidates.optimize_coverage(0).0.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_generated_batches() {
+        use crate::generator::RandomGenerator as _;
+        use crate::generator::bluemorn::BlueMorn;
+
+        // candidates from batches that passed the checks already
+        let candidates = DBallBatch(
+            (0..3)
+                .flat_map(|seed| BlueMorn::default().generate_seeded_batch(seed, 4).0)
+                .collect(),
+        );
+        let optimized = candidates.optimize_coverage(4);
+        assert_eq!(optimized.0.len(), 4);
+        assert!(optimized.0.iter().all(|dball| candidates.0.contains(dball)));
+        let first = DBallBatch(candidates.0[..4].to_vec());
+        assert!(optimized.coverage() >= first.coverage());
+    }
+}
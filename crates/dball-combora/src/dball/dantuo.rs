@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use super::{COST_PER_TICKET, DBall, DBallError, PrizeTable, Reward};
+
+/// 胆拖 bet, the banker reds are in every simple bet and the drag reds fill
+/// the rest of the 6 in every possible way, for every blue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DantuoBet {
+    /// Sorted, 1 to [`Self::MAX_BANKERS`]
+    pub bankers: Vec<u8>,
+    /// Sorted, none of the bankers, more than 6 reds with the bankers
+    pub drags: Vec<u8>,
+    /// Sorted, 1 to 16
+    pub bball: Vec<u8>,
+    pub magnification: usize,
+}
+
+impl DantuoBet {
+    pub const MAX_BANKERS: usize = 5;
+
+    pub fn new(
+        bankers: impl Into<Vec<u8>>,
+        drags: impl Into<Vec<u8>>,
+        bball: impl Into<Vec<u8>>,
+        magnification: usize,
+    ) -> Result<Self, DBallError> {
+        let mut bankers = bankers.into();
+        let mut drags = drags.into();
+        let mut bball = bball.into();
+        if !(1..=Self::MAX_BANKERS).contains(&bankers.len())
+            || bankers.len() + drags.len() <= 6
+            || !(1..=16).contains(&bball.len())
+        {
+            return Err(DBallError::InvalidDantuoCount {
+                bankers: bankers.len(),
+                drags: drags.len(),
+                blues: bball.len(),
+            });
+        }
+        if let Some(&red) = bankers
+            .iter()
+            .chain(&drags)
+            .find(|red| !(1..=33).contains(*red))
+        {
+            return Err(DBallError::RBallOutOfRange(red));
+        }
+        if let Some(&blue) = bball.iter().find(|blue| !(1..=16).contains(*blue)) {
+            return Err(DBallError::InvalidBBall(blue));
+        }
+        bankers.sort_unstable();
+        drags.sort_unstable();
+        bball.sort_unstable();
+        if bankers.windows(2).any(|w| w[0] == w[1])
+            || drags.windows(2).any(|w| w[0] == w[1])
+            || bankers.iter().any(|banker| drags.contains(banker))
+        {
+            return Err(DBallError::RBallDuplicate);
+        }
+        if bball.windows(2).any(|w| w[0] == w[1]) {
+            return Err(DBallError::BBallDuplicate);
+        }
+        Ok(Self {
+            bankers,
+            drags,
+            bball,
+            magnification,
+        })
+    }
+
+    /// Simple bets the 胆拖 bet covers, C(drags, 6 - bankers) for every blue
+    pub fn bets(&self) -> usize {
+        let drags = self.drags.len();
+        let picked = 6 - self.bankers.len();
+        let red_bets = (0..picked).fold(1, |bets, i| bets * (drags - i) / (i + 1));
+        red_bets * self.bball.len()
+    }
+
+    pub fn cost(&self) -> usize {
+        self.bets() * self.magnification * COST_PER_TICKET
+    }
+
+    /// Every simple bet of the 胆拖 bet, with its magnification
+    pub fn expand(&self) -> Vec<DBall> {
+        let mut bets = Vec::with_capacity(self.bets());
+        let picked = 6 - self.bankers.len();
+        let drags = self.drags.len();
+        let mut indices = (0..picked).collect::<Vec<_>>();
+        loop {
+            let mut rball = [0; 6];
+            rball[..self.bankers.len()].copy_from_slice(&self.bankers);
+            for (slot, &i) in rball[self.bankers.len()..].iter_mut().zip(&indices) {
+                *slot = self.drags[i];
+            }
+            rball.sort_unstable();
+            bets.extend(self.bball.iter().map(|&bball| DBall {
+                rball,
+                bball,
+                magnification: self.magnification,
+            }));
+
+            // next `picked` of the drags in lexicographic order
+            let Some(i) = (0..picked).rev().find(|&i| indices[i] < drags - picked + i) else {
+                return bets;
+            };
+            indices[i] += 1;
+            for j in i + 1..picked {
+                indices[j] = indices[j - 1] + 1;
+            }
+        }
+    }
+
+    /// Prizes of the simple bets winning in the draw of `winning`
+    pub fn check_prizes(&self, winning: &DBall) -> Vec<Reward> {
+        self.expand()
+            .iter()
+            .map(|bet| bet.check_prize(winning))
+            .filter(|reward| *reward != Reward::NoWin)
+            .collect()
+    }
+
+    /// Total of [`DBall::check_prize_amount`] over every bet covered
+    pub fn check_prize_amount(&self, winning: &DBall, table: &PrizeTable) -> u32 {
+        self.expand()
+            .iter()
+            .map(|bet| bet.check_prize_amount(winning, table))
+            .fold(0, u32::saturating_add)
+    }
+}
+
+impl Display for DantuoBet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use console::style;
+
+        let join = |numbers: &[u8]| {
+            numbers
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        write!(
+            f,
+            "({}) {} {}",
+            style(join(&self.bankers)).red().bold(),
+            style(join(&self.drags)).red(),
+            style(join(&self.bball)).blue().bold()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dantuo_validation() {
+        assert_eq!(
+            DantuoBet::new([1, 2, 3, 4, 5, 6], [7, 8], [3], 1),
+            Err(DBallError::InvalidDantuoCount {
+                bankers: 6,
+                drags: 2,
+                blues: 1
+            })
+        );
+        assert_eq!(
+            DantuoBet::new([1, 2], [3, 4, 5, 6], [3], 1),
+            Err(DBallError::InvalidDantuoCount {
+                bankers: 2,
+                drags: 4,
+                blues: 1
+            })
+        );
+        assert_eq!(
+            DantuoBet::new([1, 2], [2, 4, 5, 6, 7], [3], 1),
+            Err(DBallError::RBallDuplicate)
+        );
+        assert_eq!(
+            DantuoBet::new([1, 34], [3, 4, 5, 6, 7], [3], 1),
+            Err(DBallError::RBallOutOfRange(34))
+        );
+    }
+
+    #[test]
+    fn test_dantuo_expand_and_check() -> anyhow::Result<()> {
+        let bet = DantuoBet::new([9, 1], [30, 5, 14, 22, 31], [3, 7], 2)?;
+        // C(5, 4) for each of the 2 blues
+        assert_eq!(bet.bets(), 10);
+        assert_eq!(bet.cost(), 10 * 2 * COST_PER_TICKET);
+
+        let bets = bet.expand();
+        assert_eq!(bets.len(), bet.bets());
+        assert_eq!(bets[0], DBall::new([1, 5, 9, 14, 22, 30], 3, 2)?);
+        assert!(
+            bets.iter()
+                .all(|dball| dball.rball.contains(&1) && dball.rball.contains(&9))
+        );
+        let mut unique = bets.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), bets.len());
+
+        let winning = DBall::new_one([1, 5, 9, 14, 22, 30], 7)?;
+        let prizes = bet.check_prizes(&winning);
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::FirstPrize).count(),
+            1
+        );
+        assert_eq!(
+            prizes.iter().filter(|r| **r == Reward::SecondPrize).count(),
+            1
+        );
+        // every prize paid twice, the bet is magnified by 2
+        let table = PrizeTable::default();
+        assert_eq!(
+            bet.check_prize_amount(&winning, &table),
+            2 * prizes.iter().map(Reward::prize_amount).sum::<u32>()
+        );
+        Ok(())
+    }
+}
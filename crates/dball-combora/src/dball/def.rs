@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-const COST_PER_TICKET: usize = 2;
+use crate::game::Game;
+
+/// Price of a single bet in yuan
+pub const COST_PER_TICKET: usize = 2;
+
+/// Spots generated together when nothing else is asked for
+pub const DEFAULT_BATCH_SIZE: usize = 5;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DBall {
@@ -38,35 +44,8 @@ impl Display for DBall {
 pub struct DBallBatch(pub Vec<DBall>);
 
 impl DBallBatch {
-    pub fn to_batch(self) -> anyhow::Result<[DBall; 5]> {
-        self.0.try_into().map_err(|e| {
-            anyhow::anyhow!("Failed to convert Vec<DBall> to [DBall; 5]:\n{}", Self(e))
-        })
-    }
-
     pub fn cosine_similarity(&self) -> Vec<f32> {
-        fn calc_cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-            let dot = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f32>();
-            let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-            let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-            if norm_a == 0.0 || norm_b == 0.0 {
-                0.0
-            } else {
-                dot / (norm_a * norm_b)
-            }
-        }
-
-        let vectors: Vec<_> = self.0.iter().map(DBall::to_vector).collect();
-        let len = vectors.len();
-        let mut sims = Vec::new();
-
-        for i in 0..len {
-            for j in i + 1..len {
-                sims.push(calc_cosine_similarity(&vectors[i], &vectors[j]));
-            }
-        }
-        sims
+        crate::checker::cosine_similarity(Game::Ssq.rules(), &self.numbers())
     }
 }
 
@@ -91,6 +70,24 @@ pub enum DBallError {
     InvalidRBallRange((u8, u8)),
     RBallOutOfRange(u8),
     RBallDuplicate,
+    /// A ball is both required and excluded by the generation constraints
+    ConflictingConstraint(u8),
+    /// The generation constraints leave too few balls to pick from
+    NotEnoughCandidates,
+    BBallDuplicate,
+    /// A complex bet takes 6 to 20 reds and 1 to 16 blues
+    InvalidComplexCount {
+        reds: usize,
+        blues: usize,
+    },
+    /// Text that is not ball numbers, e.g. a pasted ticket with a typo
+    InvalidFormat(String),
+    /// A 胆拖 bet takes 1-5 bankers, more than 6 reds with its drags and 1-16 blues
+    InvalidDantuoCount {
+        bankers: usize,
+        drags: usize,
+        blues: usize,
+    },
 }
 
 impl Display for DBallError {
@@ -112,10 +109,39 @@ impl Display for DBallError {
                     "Red ball range must at least contain 6 numbers, but got: {min}-{max}",
                 )
             }
+            Self::ConflictingConstraint(ball) => {
+                write!(f, "Ball {ball} is both required and excluded")
+            }
+            Self::NotEnoughCandidates => {
+                write!(
+                    f,
+                    "Constraints leave fewer than 6 red balls or no blue ball to pick from"
+                )
+            }
+            Self::BBallDuplicate => write!(f, "Duplicate blue balls found"),
+            Self::InvalidComplexCount { reds, blues } => {
+                write!(
+                    f,
+                    "A complex bet takes 6-20 red and 1-16 blue balls, got {reds} and {blues}"
+                )
+            }
+            Self::InvalidFormat(text) => write!(f, "Cannot read ball numbers from \"{text}\""),
+            Self::InvalidDantuoCount {
+                bankers,
+                drags,
+                blues,
+            } => {
+                write!(
+                    f,
+                    "A dantuo bet takes 1-5 bankers, more than 6 reds with the drags and 1-16 blue balls, got {bankers}, {drags} and {blues}"
+                )
+            }
         }
     }
 }
 
+impl std::error::Error for DBallError {}
+
 impl DBall {
     pub fn new(
         rball: impl AsMut<[u8]>,
@@ -212,7 +238,7 @@ impl TryFrom<i32> for Reward {
 }
 
 impl Reward {
-    /// get the prize amount
+    /// get the prize amount, the first two tiers as usually paid, see [`super::PrizeTable`]
     pub fn prize_amount(&self) -> u32 {
         match self {
             Self::FirstPrize => 4_500_000,
@@ -238,6 +264,19 @@ impl Reward {
         }
     }
 
+    /// Prize tier from 1 for the first prize to 6, `None` without a win
+    pub fn tier(&self) -> Option<u8> {
+        match self {
+            Self::FirstPrize => Some(1),
+            Self::SecondPrize => Some(2),
+            Self::ThirdPrize => Some(3),
+            Self::FourthPrize => Some(4),
+            Self::FifthPrize => Some(5),
+            Self::SixthPrize => Some(6),
+            Self::NoWin => None,
+        }
+    }
+
     /// Convert reward to i32 value for database storage
     pub fn to_i32(&self) -> i32 {
         self.prize_amount() as i32
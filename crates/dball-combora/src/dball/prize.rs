@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ComplexBet, DBall, Reward};
+
+/// Prize amounts of one period in yuan
+///
+/// The first and second prizes float with the pool and the sales of the
+/// period, the other tiers are fixed, see [`Reward::prize_amount`]. The
+/// default table holds the usual amounts of the floating tiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrizeTable {
+    pub first: u32,
+    pub second: u32,
+}
+
+impl Default for PrizeTable {
+    fn default() -> Self {
+        Self {
+            first: Reward::FirstPrize.prize_amount(),
+            second: Reward::SecondPrize.prize_amount(),
+        }
+    }
+}
+
+impl PrizeTable {
+    /// Table of a period paying `first` and `second`, unknown amounts keep the defaults
+    pub fn new(first: Option<u32>, second: Option<u32>) -> Self {
+        let default = Self::default();
+        Self {
+            first: first.unwrap_or(default.first),
+            second: second.unwrap_or(default.second),
+        }
+    }
+
+    /// Amount a single bet winning `reward` is paid in this period, once
+    /// without its magnification
+    pub fn amount(&self, reward: &Reward) -> u32 {
+        match reward {
+            Reward::FirstPrize => self.first,
+            Reward::SecondPrize => self.second,
+            _ => reward.prize_amount(),
+        }
+    }
+}
+
+impl DBall {
+    /// Amount a single bet of `self` wins against `winning` with the prizes of
+    /// `table`, paid once per magnification
+    pub fn check_prize_amount(&self, winning: &Self, table: &PrizeTable) -> u32 {
+        let magnification = u32::try_from(self.magnification).unwrap_or(u32::MAX);
+        table
+            .amount(&self.check_prize(winning))
+            .saturating_mul(magnification)
+    }
+}
+
+impl ComplexBet {
+    /// Total of [`DBall::check_prize_amount`] over every bet covered
+    pub fn check_prize_amount(&self, winning: &DBall, table: &PrizeTable) -> u32 {
+        self.expand()
+            .iter()
+            .map(|bet| bet.check_prize_amount(winning, table))
+            .fold(0, u32::saturating_add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floating_prizes() -> anyhow::Result<()> {
+        let winning = DBall::new_one([3, 8, 14, 21, 27, 32], 9)?;
+        let table = PrizeTable::new(Some(7_312_640), None);
+        assert_eq!(winning.check_prize_amount(&winning, &table), 7_312_640);
+        assert_eq!(
+            winning.check_prize_amount(&winning, &PrizeTable::default()),
+            4_500_000
+        );
+
+        let second = DBall::new_one([3, 8, 14, 21, 27, 32], 10)?;
+        assert_eq!(second.check_prize_amount(&winning, &table), 150_000);
+        let fifth = DBall::new_one([3, 8, 14, 1, 2, 4], 9)?;
+        assert_eq!(fifth.check_prize_amount(&winning, &table), 10);
+
+        // one first prize and the second prize of every other blue
+        let bet = ComplexBet::new(winning.rball, [9, 10, 11], 1)?;
+        assert_eq!(
+            bet.check_prize_amount(&winning, &table),
+            7_312_640 + 2 * 150_000
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_magnified_prizes() -> anyhow::Result<()> {
+        let winning = DBall::new_one([3, 8, 14, 21, 27, 32], 9)?;
+        let table = PrizeTable::default();
+        let fifth = DBall::new(vec![3, 8, 14, 1, 2, 4], 9, 5)?;
+        assert_eq!(fifth.check_prize_amount(&winning, &table), 5 * 10);
+
+        let bet = ComplexBet::new(winning.rball, [9, 10], 3)?;
+        assert_eq!(
+            bet.check_prize_amount(&winning, &table),
+            3 * (4_500_000 + 150_000)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use super::DBallBatch;
+use crate::checker::{self, ZONES};
+use crate::game::Game;
+
+/// Smallest, largest and mean of some numbers, all 0 when there are none
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Spread {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Spread {
+    fn of(values: impl IntoIterator<Item = f64>) -> Self {
+        let values = values.into_iter().collect::<Vec<_>>();
+        if values.is_empty() {
+            return Self::default();
+        }
+        Self {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+        }
+    }
+}
+
+/// Statistics of a batch, see [`DBallBatch::summary`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub spots: usize,
+    /// Sum of the reds of each spot
+    pub sum: Spread,
+    pub odd_reds: usize,
+    pub even_reds: usize,
+    /// Reds in the low, middle and high zones
+    pub zones: [usize; ZONES],
+    pub distinct_blues: usize,
+    pub blue: Spread,
+    /// Cosine similarity of every pair of spots
+    pub similarity: Spread,
+}
+
+impl DBallBatch {
+    /// Statistics shown next to a batch, so displaying it computes nothing else
+    pub fn summary(&self) -> BatchSummary {
+        let rules = Game::Ssq.rules();
+        let reds = || self.0.iter().flat_map(|dball| dball.rball);
+        let odd_reds = reds().filter(|red| red % 2 == 1).count();
+        let mut zones = [0; ZONES];
+        for red in reds() {
+            zones[checker::zone(rules, red)] += 1;
+        }
+        let mut blues = self.0.iter().map(|dball| dball.bball).collect::<Vec<_>>();
+        blues.sort_unstable();
+        blues.dedup();
+
+        BatchSummary {
+            spots: self.0.len(),
+            sum: Spread::of(self.0.iter().map(|dball| {
+                f64::from(dball.rball.iter().map(|&red| u32::from(red)).sum::<u32>())
+            })),
+            odd_reds,
+            even_reds: reds().count() - odd_reds,
+            zones,
+            distinct_blues: blues.len(),
+            blue: Spread::of(self.0.iter().map(|dball| f64::from(dball.bball))),
+            similarity: Spread::of(self.cosine_similarity().into_iter().map(f64::from)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dball::DBall;
+
+    #[test]
+    fn test_batch_summary() -> anyhow::Result<()> {
+        let batch = DBallBatch(vec![
+            DBall::new_one([1, 2, 3, 4, 5, 6], 3)?,
+            DBall::new_one([12, 15, 20, 23, 28, 33], 3)?,
+            DBall::new_one([1, 2, 3, 4, 5, 6], 11)?,
+        ]);
+        let summary = batch.summary();
+        assert_eq!(summary.spots, 3);
+        assert_eq!(
+            summary.sum,
+            Spread {
+                min: 21.0,
+                max: 131.0,
+                mean: 173.0 / 3.0
+            }
+        );
+        assert_eq!((summary.odd_reds, summary.even_reds), (9, 9));
+        assert_eq!(summary.zones, [12, 3, 3]);
+        assert_eq!(summary.distinct_blues, 2);
+        assert_eq!((summary.blue.min, summary.blue.max), (3.0, 11.0));
+        assert!(summary.similarity.max > summary.similarity.min);
+
+        assert_eq!(DBallBatch(Vec::new()).summary(), BatchSummary::default());
+        Ok(())
+    }
+}
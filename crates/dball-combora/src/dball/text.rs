@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use super::{DBall, DBallBatch, DBallError};
+
+/// Header of [`DBallBatch::to_csv`]
+pub const CSV_HEADER: &str = "red1,red2,red3,red4,red5,red6,blue,magnification";
+
+fn parse_number<T: FromStr>(number: &str) -> Result<T, DBallError> {
+    number
+        .trim()
+        .parse()
+        .map_err(|_e| DBallError::InvalidFormat(number.trim().to_owned()))
+}
+
+impl DBall {
+    /// Plain text like `02,06,07,13,16,28+11`, followed by ` x2` when bought more
+    /// than once, which [`DBall::from_str`] reads back
+    pub fn to_text(&self) -> String {
+        let reds = self.rball.map(|red| format!("{red:02}")).join(",");
+        match self.magnification {
+            1 => format!("{reds}+{:02}", self.bball),
+            magnification => format!("{reds}+{:02} x{magnification}", self.bball),
+        }
+    }
+
+    /// Row of [`DBallBatch::to_csv`]
+    pub fn to_csv(&self) -> String {
+        let reds = self.rball.map(|red| red.to_string()).join(",");
+        format!("{reds},{},{}", self.bball, self.magnification)
+    }
+
+    /// Row of 6 reds, the blue and optionally the magnification
+    pub fn from_csv(row: &str) -> Result<Self, DBallError> {
+        let fields = row.split(',').collect::<Vec<_>>();
+        let (numbers, magnification) = match fields.as_slice() {
+            [numbers @ .., magnification] if fields.len() == 8 => {
+                (numbers, parse_number(magnification)?)
+            }
+            numbers if fields.len() == 7 => (numbers, 1),
+            _ => return Err(DBallError::InvalidFormat(row.to_owned())),
+        };
+        let mut numbers = numbers
+            .iter()
+            .map(|number| parse_number(number))
+            .collect::<Result<Vec<u8>, _>>()?;
+        let blue = numbers.pop().unwrap_or_default();
+        Self::new(numbers, blue, magnification)
+    }
+}
+
+/// Reds separated by commas or spaces, then `+` and the blue, e.g.
+/// `02,06,07,13,16,28+11` or `2 6 7 13 16 28 + 11`
+///
+/// Without a `+` the last of 7 numbers is the blue. A trailing `x2`, `×2` or `*2`
+/// buys the numbers twice
+impl FromStr for DBall {
+    type Err = DBallError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (numbers, magnification) = match text.rsplit_once(['x', 'X', '×', '*']) {
+            Some((numbers, magnification)) => (numbers, parse_number(magnification)?),
+            None => (text, 1),
+        };
+        let (reds, blue) = match numbers.split_once('+') {
+            Some((reds, blue)) => (reds, Some(parse_number(blue)?)),
+            None => (numbers, None),
+        };
+        let mut reds = reds
+            .split([',', ' ', '\t'])
+            .filter(|red| !red.is_empty())
+            .map(parse_number)
+            .collect::<Result<Vec<u8>, _>>()?;
+        let blue = match blue {
+            Some(blue) => blue,
+            None if reds.len() == 7 => reds.pop().unwrap_or_default(),
+            None => return Err(DBallError::InvalidFormat(text.to_owned())),
+        };
+        Self::new(reds, blue, magnification)
+    }
+}
+
+impl DBallBatch {
+    /// One line of [`DBall::to_text`] per spot
+    pub fn to_text(&self) -> String {
+        self.0
+            .iter()
+            .map(|dball| format!("{}\n", dball.to_text()))
+            .collect()
+    }
+
+    /// [`CSV_HEADER`] and a row per spot
+    pub fn to_csv(&self) -> String {
+        std::iter::once(CSV_HEADER.to_owned())
+            .chain(self.0.iter().map(DBall::to_csv))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    }
+
+    /// Rows of [`Self::to_csv`], the header and blank lines are skipped
+    pub fn from_csv(csv: &str) -> Result<Self, DBallError> {
+        csv.lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty() && *row != CSV_HEADER)
+            .map(DBall::from_csv)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// A spot per line in the format of [`DBall::from_str`], blank lines and lines
+/// starting with `#` are skipped
+impl FromStr for DBallBatch {
+    type Err = DBallError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(DBall::from_str)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text() -> anyhow::Result<()> {
+        let dball = DBall::new_one([2, 6, 7, 13, 16, 28], 11)?;
+        assert_eq!("02,06,07,13,16,28+11".parse::<DBall>()?, dball);
+        assert_eq!(" 28 2 6 7 13 16 + 11 ".parse::<DBall>()?, dball);
+        assert_eq!("2,6,7,13,16,28,11".parse::<DBall>()?, dball);
+        assert_eq!(dball.to_text().parse::<DBall>()?, dball);
+
+        let doubled = DBall::new([2, 6, 7, 13, 16, 28], 11, 2)?;
+        assert_eq!(doubled.to_text(), "02,06,07,13,16,28+11 x2");
+        assert_eq!("02,06,07,13,16,28+11 ×2".parse::<DBall>()?, doubled);
+
+        assert_eq!(
+            "02,06,07,13,16,2a+11".parse::<DBall>(),
+            Err(DBallError::InvalidFormat("2a".to_owned()))
+        );
+        assert_eq!(
+            "02,06,07,13,16+11".parse::<DBall>(),
+            Err(DBallError::InvalidRBallCount(5))
+        );
+        assert_eq!(
+            "02,06,07,13,16".parse::<DBall>(),
+            Err(DBallError::InvalidFormat("02,06,07,13,16".to_owned()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_text_and_csv() -> anyhow::Result<()> {
+        let batch = DBallBatch(vec![
+            DBall::new_one([2, 6, 7, 13, 16, 28], 11)?,
+            DBall::new([1, 9, 14, 22, 30, 33], 4, 3)?,
+        ]);
+        assert_eq!(batch.to_text().parse::<DBallBatch>()?, batch);
+        assert_eq!(DBallBatch::from_csv(&batch.to_csv())?, batch);
+        assert_eq!(
+            batch.to_csv(),
+            format!("{CSV_HEADER}\n2,6,7,13,16,28,11,1\n1,9,14,22,30,33,4,3\n")
+        );
+
+        let pasted = "# ticket of the week\n\n02,06,07,13,16,28+11\n01 09 14 22 30 33 + 04 x3\n";
+        assert_eq!(pasted.parse::<DBallBatch>()?, batch);
+        assert_eq!(
+            DBallBatch::from_csv("2,6,7,13,16,28,11\n1,9,14,22,30,33,4,3")?,
+            batch
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,436 @@
+//! Lottery games the crate knows, each with its own numbers, checks and prizes
+//!
+//! [`DBall`] stays the 双色球 combination the rest of the workspace uses, a
+//! [`Combination`] holds the numbers of any [`Game`]
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use rand::seq::SliceRandom as _;
+use serde::{Deserialize, Serialize};
+
+use crate::checker::{self, DBallChecker};
+use crate::dball::{COST_PER_TICKET, DBall};
+use crate::generator::bluemorn::BlueMorn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Game {
+    /// 双色球, 6 reds of 1-33 and 1 blue of 1-16
+    Ssq,
+    /// 大乐透, 5 front numbers of 1-35 and 2 back numbers of 1-12
+    Dlt,
+}
+
+/// `count` distinct numbers out of `1..=max`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zone {
+    pub count: usize,
+    pub max: u8,
+}
+
+/// Thresholds of the checks, combinations outside them are unlikely to win
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckBounds {
+    /// Sum of the front numbers
+    pub sum: RangeInclusive<usize>,
+    /// Largest minus smallest front number
+    pub gap: RangeInclusive<u8>,
+    /// Average back number of a batch
+    pub back_average: RangeInclusive<f64>,
+}
+
+/// A prize level, won by any of its `(front, back)` match counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrizeTier {
+    /// 1 for the first prize
+    pub level: u8,
+    pub matches: &'static [(usize, usize)],
+    /// Yuan per bet, a typical amount for the floating top prizes
+    pub amount: u32,
+}
+
+/// Numbers, checks and prizes of a game
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRules {
+    pub front: Zone,
+    pub back: Zone,
+    pub bounds: CheckBounds,
+    /// Highest prize first
+    pub tiers: &'static [PrizeTier],
+}
+
+impl GameRules {
+    /// Prize won by `front` and `back` matching numbers, `None` when nothing is won
+    pub fn prize(&self, front: usize, back: usize) -> Option<&PrizeTier> {
+        self.tiers
+            .iter()
+            .find(|tier| tier.matches.contains(&(front, back)))
+    }
+}
+
+static SSQ: GameRules = GameRules {
+    front: Zone { count: 6, max: 33 },
+    back: Zone { count: 1, max: 16 },
+    bounds: CheckBounds {
+        sum: 13 * 5..=19 * 5,
+        gap: 8..=25,
+        back_average: 6.0..=10.0,
+    },
+    tiers: &[
+        PrizeTier {
+            level: 1,
+            matches: &[(6, 1)],
+            amount: 4_500_000,
+        },
+        PrizeTier {
+            level: 2,
+            matches: &[(6, 0)],
+            amount: 150_000,
+        },
+        PrizeTier {
+            level: 3,
+            matches: &[(5, 1)],
+            amount: 3_000,
+        },
+        PrizeTier {
+            level: 4,
+            matches: &[(5, 0), (4, 1)],
+            amount: 200,
+        },
+        PrizeTier {
+            level: 5,
+            matches: &[(4, 0), (3, 1)],
+            amount: 10,
+        },
+        PrizeTier {
+            level: 6,
+            matches: &[(2, 1), (1, 1), (0, 1)],
+            amount: 5,
+        },
+    ],
+};
+
+static DLT: GameRules = GameRules {
+    front: Zone { count: 5, max: 35 },
+    back: Zone { count: 2, max: 12 },
+    bounds: CheckBounds {
+        sum: 70..=110,
+        gap: 8..=28,
+        back_average: 4.5..=8.5,
+    },
+    tiers: &[
+        PrizeTier {
+            level: 1,
+            matches: &[(5, 2)],
+            amount: 10_000_000,
+        },
+        PrizeTier {
+            level: 2,
+            matches: &[(5, 1)],
+            amount: 200_000,
+        },
+        PrizeTier {
+            level: 3,
+            matches: &[(5, 0)],
+            amount: 10_000,
+        },
+        PrizeTier {
+            level: 4,
+            matches: &[(4, 2)],
+            amount: 3_000,
+        },
+        PrizeTier {
+            level: 5,
+            matches: &[(4, 1)],
+            amount: 300,
+        },
+        PrizeTier {
+            level: 6,
+            matches: &[(3, 2)],
+            amount: 200,
+        },
+        PrizeTier {
+            level: 7,
+            matches: &[(4, 0)],
+            amount: 100,
+        },
+        PrizeTier {
+            level: 8,
+            matches: &[(3, 1), (2, 2)],
+            amount: 15,
+        },
+        PrizeTier {
+            level: 9,
+            matches: &[(3, 0), (2, 1), (1, 2), (0, 2)],
+            amount: 5,
+        },
+    ],
+};
+
+impl Game {
+    pub const ALL: [Self; 2] = [Self::Ssq, Self::Dlt];
+
+    /// Code of the game as the lottery APIs name it
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Ssq => "ssq",
+            Self::Dlt => "dlt",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|game| game.code() == code)
+    }
+
+    pub fn rules(&self) -> &'static GameRules {
+        match self {
+            Self::Ssq => &SSQ,
+            Self::Dlt => &DLT,
+        }
+    }
+
+    /// Batch of `size` random combinations kept with the score of its checks as
+    /// the probability, like [`BlueMorn`] does for 双色球
+    pub fn generate_seeded_batch(self, seed: u64, size: usize) -> Vec<Combination> {
+        use rand::{Rng as _, SeedableRng as _};
+        let rules = self.rules();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let pick = |zone: Zone, rng: &mut rand::rngs::StdRng| {
+            let numbers = (1..=zone.max).collect::<Vec<_>>();
+            let mut picked = numbers
+                .choose_multiple(rng, zone.count)
+                .copied()
+                .collect::<Vec<_>>();
+            picked.sort_unstable();
+            picked
+        };
+        loop {
+            let batch = (0..size)
+                .map(|_| Combination {
+                    game: self,
+                    front: pick(rules.front, &mut rng),
+                    back: pick(rules.back, &mut rng),
+                    magnification: 1,
+                })
+                .collect::<Vec<_>>();
+            let score = BlueMorn::score(&Combination::evaluate_batch(&batch));
+            if rng.gen_bool(score.clamp(0.0, 1.0)) {
+                return batch;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CombinationError {
+    Count { expected: usize, got: usize },
+    OutOfRange { number: u8, max: u8 },
+    Duplicate(u8),
+}
+
+impl Display for CombinationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count { expected, got } => {
+                write!(
+                    f,
+                    "Invalid count of numbers: expected {expected}, got {got}"
+                )
+            }
+            Self::OutOfRange { number, max } => {
+                write!(f, "Number {number} is out of range (1-{max})")
+            }
+            Self::Duplicate(number) => write!(f, "Duplicate number {number}"),
+        }
+    }
+}
+
+impl std::error::Error for CombinationError {}
+
+/// Numbers of one bet of any game, both zones sorted
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Combination {
+    pub game: Game,
+    pub front: Vec<u8>,
+    pub back: Vec<u8>,
+    pub magnification: usize,
+}
+
+impl Combination {
+    pub fn new(
+        game: Game,
+        front: impl Into<Vec<u8>>,
+        back: impl Into<Vec<u8>>,
+        magnification: usize,
+    ) -> Result<Self, CombinationError> {
+        let rules = game.rules();
+        let zone = |numbers: Vec<u8>, zone: Zone| {
+            if numbers.len() != zone.count {
+                return Err(CombinationError::Count {
+                    expected: zone.count,
+                    got: numbers.len(),
+                });
+            }
+            if let Some(&number) = numbers.iter().find(|n| !(1..=zone.max).contains(*n)) {
+                return Err(CombinationError::OutOfRange {
+                    number,
+                    max: zone.max,
+                });
+            }
+            let mut numbers = numbers;
+            numbers.sort_unstable();
+            match numbers.windows(2).find(|w| w[0] == w[1]) {
+                Some(w) => Err(CombinationError::Duplicate(w[0])),
+                None => Ok(numbers),
+            }
+        };
+        Ok(Self {
+            game,
+            front: zone(front.into(), rules.front)?,
+            back: zone(back.into(), rules.back)?,
+            magnification,
+        })
+    }
+
+    pub fn cost(&self) -> usize {
+        self.magnification * COST_PER_TICKET
+    }
+
+    /// Prize the combination wins in the draw of `winning`, `None` without a
+    /// prize or for a draw of another game
+    pub fn check_prize(&self, winning: &Self) -> Option<&'static PrizeTier> {
+        if self.game != winning.game {
+            return None;
+        }
+        let matches =
+            |ours: &[u8], theirs: &[u8]| ours.iter().filter(|n| theirs.contains(n)).count();
+        self.game.rules().prize(
+            matches(&self.front, &winning.front),
+            matches(&self.back, &winning.back),
+        )
+    }
+
+    pub fn evaluate(&self) -> Vec<DBallChecker> {
+        checker::evaluate_combination(self.game.rules(), (&self.front, &self.back))
+    }
+
+    /// Checks of every combination of `batch` and of the batch itself, which must be of one game
+    pub fn evaluate_batch(batch: &[Self]) -> Vec<DBallChecker> {
+        let Some(first) = batch.first() else {
+            return Vec::new();
+        };
+        let rules = first.game.rules();
+        let numbers = batch
+            .iter()
+            .map(|combination| (combination.front.as_slice(), combination.back.as_slice()))
+            .collect::<Vec<_>>();
+        let mut checks = checker::evaluate_batch(rules, &numbers);
+        for combination in &numbers {
+            checks.extend(checker::evaluate_combination(rules, *combination));
+        }
+        checks
+    }
+}
+
+impl From<DBall> for Combination {
+    fn from(dball: DBall) -> Self {
+        Self {
+            game: Game::Ssq,
+            front: dball.rball.to_vec(),
+            back: vec![dball.bball],
+            magnification: dball.magnification,
+        }
+    }
+}
+
+impl Display for Combination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use console::style;
+
+        let join = |numbers: &[u8]| {
+            numbers
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        write!(
+            f,
+            "{} {}",
+            style(join(&self.front)).red().bold(),
+            style(join(&self.back)).blue().bold()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssq_rules_match_reward() -> anyhow::Result<()> {
+        let winning = DBall::new_one([1, 2, 3, 4, 5, 6], 7)?;
+        for reds in 0..=6_u8 {
+            for blue in [7, 8] {
+                let mut rball = (1..=reds).chain(20..26 - reds).collect::<Vec<_>>();
+                rball.truncate(6);
+                let dball = DBall::new_one(rball, blue)?;
+                let reward = dball.check_prize(&winning);
+                let tier = Combination::from(dball).check_prize(&winning.into());
+                assert_eq!(
+                    tier.map_or(0, |tier| tier.amount),
+                    reward.prize_amount(),
+                    "{dball} {reward:?}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dlt_combination() -> anyhow::Result<()> {
+        let winning = Combination::new(Game::Dlt, [35, 1, 12, 20, 7], [12, 3], 1)?;
+        assert_eq!(winning.front, vec![1, 7, 12, 20, 35]);
+
+        let four_and_one = Combination::new(Game::Dlt, [1, 7, 12, 20, 21], [3, 4], 1)?;
+        assert_eq!(
+            four_and_one.check_prize(&winning).map(|tier| tier.level),
+            Some(5)
+        );
+        let none = Combination::new(Game::Dlt, [2, 8, 13, 21, 34], [1, 2], 1)?;
+        assert_eq!(none.check_prize(&winning), None);
+
+        assert_eq!(
+            Combination::new(Game::Dlt, [1, 2, 3, 4, 5, 6], [1, 2], 1),
+            Err(CombinationError::Count {
+                expected: 5,
+                got: 6
+            })
+        );
+        assert_eq!(
+            Combination::new(Game::Dlt, [1, 2, 3, 4, 5], [1, 13], 1),
+            Err(CombinationError::OutOfRange {
+                number: 13,
+                max: 12
+            })
+        );
+        assert_eq!(
+            Combination::new(Game::Dlt, [1, 2, 3, 4, 5], [6, 6], 1),
+            Err(CombinationError::Duplicate(6))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dlt_seeded_batch() {
+        let batch = Game::Dlt.generate_seeded_batch(3, 5);
+        assert_eq!(batch, Game::Dlt.generate_seeded_batch(3, 5));
+        for combination in &batch {
+            assert_eq!(combination.game, Game::Dlt);
+            assert_eq!(combination.front.len(), 5);
+            assert_eq!(combination.back.len(), 2);
+            assert!(combination.front.iter().all(|n| (1..=35).contains(n)));
+            assert!(combination.back.iter().all(|n| (1..=12).contains(n)));
+        }
+    }
+}
@@ -1,9 +1,18 @@
 use crate::checker::DBallChecker;
-use crate::dball::{DBall, DBallBatch, DBallError};
+use crate::dball::{ComplexBet, DBall, DBallBatch, DBallError};
+use rng::{RngBackend, SeededRng};
 use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Generator {
     BlueMorn,
+    /// Weighs the numbers by their past frequency, see [`frequency::Frequency`]
+    Frequency,
+    /// Follows the transitions between consecutive past draws, see [`markov::Markov`]
+    Markov,
+    /// Refines a batch one ball at a time by simulated annealing, see [`anneal::Anneal`]
+    Anneal,
 }
 
 impl AsRef<Self> for Generator {
@@ -13,17 +22,154 @@ impl AsRef<Self> for Generator {
 }
 
 impl Generator {
+    pub const ALL: [Self; 4] = [Self::BlueMorn, Self::Frequency, Self::Markov, Self::Anneal];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BlueMorn => "BlueMorn",
+            Self::Frequency => "Frequency",
+            Self::Markov => "Markov",
+            Self::Anneal => "Anneal",
+        }
+    }
+
+    /// Whether the generator learns from past draws, see [`HistoryAware`]
+    pub fn uses_history(&self) -> bool {
+        matches!(self, Self::Frequency | Self::Markov)
+    }
+
+    /// Generator called `name`, ignoring case and surrounding whitespace
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|g| g.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Generator without any history, [`Self::Frequency`] and [`Self::Markov`] then pick uniformly
     pub fn create_generator(generator: impl AsRef<Self>) -> Box<dyn RandomGenerator> {
-        match generator.as_ref() {
-            Self::BlueMorn => Box::new(bluemorn::BlueMorn),
+        Self::create_generator_with_history(generator, &[], frequency::Frequency::DEFAULT_BIAS)
+    }
+
+    /// Generator learning from `history`, the winning numbers of past draws oldest first
+    ///
+    /// `bias` leans [`Self::Frequency`] towards hot numbers when positive and
+    /// cold ones when negative, generators without history ignore both
+    pub fn create_generator_with_history(
+        generator: impl AsRef<Self>,
+        history: &[DBall],
+        bias: f64,
+    ) -> Box<dyn RandomGenerator> {
+        let mut created: Box<dyn RandomGenerator> = match generator.as_ref() {
+            Self::BlueMorn => Box::new(bluemorn::BlueMorn::default()),
+            Self::Frequency => Box::new(frequency::Frequency::new(&[], bias)),
+            Self::Markov => Box::new(markov::Markov::new(&[])),
+            Self::Anneal => Box::new(anneal::Anneal::default()),
+        };
+        if let Some(aware) = created.history_aware() {
+            aware.with_history(history);
+        }
+        created
+    }
+}
+
+/// `1..=N` as an array
+const fn numbers<const N: usize>() -> [u8; N] {
+    let mut numbers = [0; N];
+    let mut i = 0;
+    while i < N {
+        numbers[i] = i as u8 + 1;
+        i += 1;
+    }
+    numbers
+}
+
+/// Batch of `size` spots from `pick` kept with its score as the probability, like [`bluemorn::BlueMorn`] does
+fn accept_seeded_batch(
+    generator: &impl RandomGenerator,
+    backend: RngBackend,
+    seed: u64,
+    size: usize,
+    mut pick: impl FnMut(&mut SeededRng) -> DBall,
+) -> DBallBatch {
+    use rand::Rng as _;
+    let mut rng = backend.seeded(seed);
+    loop {
+        let batch = DBallBatch((0..size).map(|_| pick(&mut rng)).collect());
+        let score = generator.evaluate_batch(&batch);
+        if rng.gen_bool(score.clamp(0.0, 1.0)) {
+            return batch;
         }
     }
 }
 
+/// Statistics of a running batch generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationProgress {
+    /// Candidate batches evaluated so far
+    pub tries: usize,
+    /// Highest score of those candidates
+    pub best_score: f64,
+    /// Seed reproducing the batch with [`RandomGenerator::generate_seeded_batch`],
+    /// set on the report of the finished batch when a single seed drew it
+    pub seed: Option<u64>,
+}
+
+/// Generators conditioned on past draws, e.g. leaning away from the numbers
+/// drawn in the latest periods
+pub trait HistoryAware {
+    /// Learn from `draws`, the winning numbers of past periods oldest first,
+    /// replacing whatever was learned before
+    fn with_history(&mut self, draws: &[DBall]);
+}
+
 pub trait RandomGenerator {
-    fn generate_batch(&self) -> anyhow::Result<[DBall; 5]>;
+    /// Batch of `size` spots, see [`crate::dball::DEFAULT_BATCH_SIZE`]
+    ///
+    /// Draws a random seed for [`Self::generate_seeded_batch`] and logs it, so
+    /// the batch can be reproduced
+    fn generate_batch(&self, size: usize) -> anyhow::Result<DBallBatch> {
+        let seed = rand::random();
+        log::debug!("Generating a batch of {size} from seed {seed}");
+        Ok(self.generate_seeded_batch(seed, size))
+    }
+
+    /// [`Self::generate_batch`] passing its statistics to `on_progress` as it
+    /// goes and giving up once `cancel` is set, generators finishing quickly
+    /// ignore `cancel` and only report the finished batch
+    fn generate_tracked_batch(
+        &self,
+        size: usize,
+        _cancel: &AtomicBool,
+        on_progress: &(dyn Fn(GenerationProgress) + Sync),
+    ) -> anyhow::Result<DBallBatch> {
+        let seed = rand::random();
+        log::debug!("Generating a batch of {size} from seed {seed}");
+        let batch = self.generate_seeded_batch(seed, size);
+        on_progress(GenerationProgress {
+            tries: 1,
+            best_score: self.evaluate_batch(&batch),
+            seed: Some(seed),
+        });
+        Ok(batch)
+    }
+
+    /// Batch of `size` spots drawn from `seed` alone, the same seed always gives the same batch
+    fn generate_seeded_batch(&self, seed: u64, size: usize) -> DBallBatch;
 
     fn evaluate_batch(&self, batch: &DBallBatch) -> f64;
+
+    /// Draw the random numbers from `rng` from now on, [`RngBackend::Std`] until then
+    fn set_rng(&mut self, rng: RngBackend);
+
+    /// The generator as [`HistoryAware`], `None` when it ignores past draws
+    fn history_aware(&mut self) -> Option<&mut dyn HistoryAware> {
+        None
+    }
 }
 
+pub mod anneal;
 pub mod bluemorn;
+pub mod constraints;
+pub mod frequency;
+pub mod markov;
+pub mod rng;
@@ -0,0 +1,161 @@
+use rand::Rng as _;
+use rand::seq::SliceRandom as _;
+
+use super::bluemorn::BlueMorn;
+use super::rng::RngBackend;
+use super::{DBall, DBallBatch, RandomGenerator, numbers};
+
+/// Refines one batch by simulated annealing instead of drawing new ones
+///
+/// Every step replaces a single ball of a random spot. A change raising the
+/// score is always taken, one lowering it by `delta` with the probability
+/// `exp(-delta / temperature)`, and the temperature cools down step by step.
+/// The batch is kept with its score as the probability after each step, like
+/// [`BlueMorn`] does with whole batches, or the best one seen after
+/// [`Self::steps`]. Scores are those of [`BlueMorn`]
+pub struct Anneal {
+    /// Temperature of the first step
+    pub temperature: f64,
+    /// Factor the temperature is multiplied with after every step
+    pub cooling: f64,
+    /// Steps before giving up and taking the best batch
+    pub steps: usize,
+    /// Where the random numbers come from
+    pub rng: RngBackend,
+}
+
+impl Default for Anneal {
+    fn default() -> Self {
+        Self {
+            temperature: Self::DEFAULT_TEMPERATURE,
+            cooling: Self::DEFAULT_COOLING,
+            steps: Self::DEFAULT_STEPS,
+            rng: RngBackend::default(),
+        }
+    }
+}
+
+impl Anneal {
+    pub const DEFAULT_TEMPERATURE: f64 = 0.1;
+    pub const DEFAULT_COOLING: f64 = 0.99;
+    pub const DEFAULT_STEPS: usize = 2000;
+
+    /// `ball` with one of its reds or its blue replaced by a number it does not hold yet
+    fn perturb(ball: &DBall, rng: &mut impl rand::Rng) -> DBall {
+        const REDS: [u8; 33] = numbers();
+        const BLUES: [u8; 16] = numbers();
+        loop {
+            let mut reds = ball.rball;
+            let mut blue = ball.bball;
+            match rng.gen_range(0..=6) {
+                6 => blue = *BLUES.choose(rng).unwrap_or(&ball.bball),
+                red => {
+                    let free = REDS
+                        .iter()
+                        .filter(|number| !ball.rball.contains(number))
+                        .collect::<Vec<_>>();
+                    reds[red] = free.choose(rng).map_or(reds[red], |number| **number);
+                }
+            }
+            if let Ok(perturbed) = DBall::builder()
+                .reds(reds)
+                .blue(blue)
+                .magnification(ball.magnification)
+                .build()
+                && perturbed != *ball
+            {
+                return perturbed;
+            }
+        }
+    }
+}
+
+impl RandomGenerator for Anneal {
+    fn generate_seeded_batch(&self, seed: u64, size: usize) -> DBallBatch {
+        let generator = BlueMorn { rng: self.rng };
+        let mut rng = self.rng.seeded(seed);
+        let mut batch = DBallBatch(
+            (0..size)
+                .map(|_| generator.generate_seeded(rng.r#gen()))
+                .collect(),
+        );
+        let mut score = self.evaluate_batch(&batch);
+        let mut best = (batch.clone(), score);
+        let mut temperature = self.temperature;
+
+        for _ in 0..self.steps {
+            if batch.0.is_empty() || rng.gen_bool(score.clamp(0.0, 1.0)) {
+                return batch;
+            }
+            let spot = rng.gen_range(0..batch.0.len());
+
+            let mut candidate = batch.clone();
+            candidate.0[spot] = Self::perturb(&batch.0[spot], &mut rng);
+            let candidate_score = self.evaluate_batch(&candidate);
+            let delta = candidate_score - score;
+            if delta >= 0.0 || rng.gen_bool((delta / temperature.max(f64::MIN_POSITIVE)).exp()) {
+                batch = candidate;
+                score = candidate_score;
+                if score > best.1 {
+                    best = (batch.clone(), score);
+                }
+            }
+            temperature *= self.cooling;
+        }
+        log::debug!(
+            "Annealing gave up after {} steps, best score {}",
+            self.steps,
+            best.1
+        );
+        best.0
+    }
+
+    fn evaluate_batch(&self, batch: &DBallBatch) -> f64 {
+        BlueMorn::default().evaluate_batch(batch)
+    }
+
+    fn set_rng(&mut self, rng: RngBackend) {
+        self.rng = rng;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng as _;
+
+    #[test]
+    fn test_perturb_changes_one_ball() -> anyhow::Result<()> {
+        let ball = DBall::new_one([3, 8, 14, 21, 27, 32], 9)?;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let perturbed = Anneal::perturb(&ball, &mut rng);
+            let kept = perturbed
+                .rball
+                .iter()
+                .filter(|red| ball.rball.contains(red))
+                .count();
+            let changed = 6 - kept + usize::from(perturbed.bball != ball.bball);
+            assert_eq!(changed, 1, "{ball} became {perturbed}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_batch() {
+        let generator = Anneal::default();
+        let batch = generator.generate_seeded_batch(17, 5);
+        assert_eq!(batch.0.len(), 5);
+        assert_eq!(batch, generator.generate_seeded_batch(17, 5));
+    }
+
+    #[test]
+    fn test_annealing_without_steps() {
+        let generator = Anneal {
+            steps: 0,
+            ..Anneal::default()
+        };
+        assert_eq!(generator.generate_seeded_batch(3, 0).0.len(), 0);
+        assert_eq!(generator.generate_seeded_batch(3, 4).0.len(), 4);
+    }
+}
@@ -1,24 +1,115 @@
-use std::sync::atomic::AtomicBool;
-
-use super::{DBall, DBallBatch, DBallChecker, DBallError, HashSet, RandomGenerator};
-pub struct BlueMorn;
+use rand::RngCore as _;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use super::constraints::GenerationConstraints;
+use super::rng::RngBackend;
+use super::{ComplexBet, numbers};
+use super::{
+    DBall, DBallBatch, DBallChecker, DBallError, GenerationProgress, HashSet, RandomGenerator,
+};
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlueMorn {
+    /// Where the random numbers come from
+    pub rng: RngBackend,
+}
 
 impl RandomGenerator for BlueMorn {
-    fn generate_batch(&self) -> anyhow::Result<[DBall; 5]> {
-        const THREAD_COUNT: usize = 10;
-        let batch = self.multi_thread_generate(THREAD_COUNT)?;
-        batch.to_batch()
+    fn generate_batch(&self, size: usize) -> anyhow::Result<DBallBatch> {
+        self.generate_batch_with(size, &GenerationConstraints::default())
+    }
+
+    fn generate_tracked_batch(
+        &self,
+        size: usize,
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(GenerationProgress) + Sync),
+    ) -> anyhow::Result<DBallBatch> {
+        self.generate_tracked_batch_with(
+            size,
+            &GenerationConstraints::default(),
+            cancel,
+            on_progress,
+        )
+    }
+
+    /// The batch [`Self::generate_batch`] gives when it draws `seed`
+    fn generate_seeded_batch(&self, seed: u64, size: usize) -> DBallBatch {
+        self.seeded_batch(seed, size, &GenerationConstraints::default())
     }
 
     fn evaluate_batch(&self, batch: &DBallBatch) -> f64 {
-        let mut score = 1.0;
+        self.evaluate_batch_with(batch, &GenerationConstraints::default())
+    }
+
+    fn set_rng(&mut self, rng: RngBackend) {
+        self.rng = rng;
+    }
+}
+
+impl BlueMorn {
+    /// [`RandomGenerator::generate_batch`] of spots meeting `constraints`
+    pub fn generate_batch_with(
+        &self,
+        size: usize,
+        constraints: &GenerationConstraints,
+    ) -> anyhow::Result<DBallBatch> {
+        self.generate_tracked_batch_with(size, constraints, &AtomicBool::new(false), &|_| {})
+    }
+
+    /// [`RandomGenerator::generate_tracked_batch`] of spots meeting `constraints`
+    pub fn generate_tracked_batch_with(
+        &self,
+        size: usize,
+        constraints: &GenerationConstraints,
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(GenerationProgress) + Sync),
+    ) -> anyhow::Result<DBallBatch> {
+        constraints.validate()?;
+        let seed = rand::random();
+        log::debug!("Generating a batch of {size} from seed {seed}");
+        self.parallel_generate(seed, size, constraints, cancel, on_progress)
+    }
+
+    /// [`RandomGenerator::generate_seeded_batch`] of spots meeting `constraints`
+    pub fn generate_seeded_batch_with(
+        &self,
+        seed: u64,
+        size: usize,
+        constraints: &GenerationConstraints,
+    ) -> Result<DBallBatch, DBallError> {
+        constraints.validate()?;
+        Ok(self.seeded_batch(seed, size, constraints))
+    }
+
+    fn seeded_batch(
+        &self,
+        seed: u64,
+        size: usize,
+        constraints: &GenerationConstraints,
+    ) -> DBallBatch {
+        self.parallel_generate(seed, size, constraints, &AtomicBool::new(false), &|_| {})
+            .expect("Batch generation only stops early when cancelled")
+    }
+
+    /// [`RandomGenerator::evaluate_batch`] without the checks `constraints` make unavoidable
+    pub fn evaluate_batch_with(
+        &self,
+        batch: &DBallBatch,
+        constraints: &GenerationConstraints,
+    ) -> f64 {
         let mut checks = batch.evaluate();
         batch.0.iter().for_each(|ball| {
             checks.extend(ball.evaluate());
         });
+        checks.retain(|check| !constraints.makes_unavoidable(*check));
+        Self::score(&checks)
+    }
 
+    /// Chance of keeping a batch that failed `checks`, 1 when it passed them all
+    pub fn score(checks: &[DBallChecker]) -> f64 {
+        let mut score = 1.0;
         #[expect(clippy::match_same_arms)]
-        for e in &checks {
+        for e in checks {
             match e {
                 DBallChecker::AllSingleDigits => score *= 0.1004,
                 DBallChecker::AllEvenOrOdd => score *= 0.2003,
@@ -31,18 +122,16 @@ impl RandomGenerator for BlueMorn {
                 DBallChecker::BatchBlueBallDistribution => score *= 0.0921,
                 DBallChecker::BatchBlueBallDuplicate => score *= 0.0321,
                 DBallChecker::BatchHighCosineSimilarity => score *= 0.0830,
+                DBallChecker::LongConsecutiveRun => score *= 0.3544,
+                DBallChecker::BatchConsecutiveRuns => score *= 0.3544,
+                DBallChecker::ZoneImbalance => score *= 0.3544,
+                DBallChecker::BatchZoneSkew => score *= 0.2003,
             }
         }
         score
     }
 }
 
-/// Generate a random number with a simple linear congruential generator
-fn simple_random(seed: &mut u64) -> u64 {
-    *seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-    *seed
-}
-
 /// Get current time as seed
 fn get_time_seed() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -58,47 +147,196 @@ impl BlueMorn {
         Self::generate_with_seed(get_time_seed())
     }
 
-    /// Generate a random ticket with a specific seed
+    /// Generate a random ticket with a specific seed, from the default [`RngBackend`]
     pub fn generate_with_seed(initial_seed: u64) -> DBall {
-        let mut seed = initial_seed;
+        Self::default().generate_seeded(initial_seed)
+    }
+
+    /// Generate a random ticket with a specific seed, from [`Self::rng`]
+    pub fn generate_seeded(&self, initial_seed: u64) -> DBall {
+        const REDS: [u8; 33] = numbers();
+        const BLUES: [u8; 16] = numbers();
+        Self::pick(&mut self.rng.seeded(initial_seed), &REDS, 0, &BLUES)
+    }
+
+    /// Ticket of the first `included` of `reds`, others of them and one of `blues`
+    fn pick(rng: &mut impl rand::RngCore, reds: &[u8], included: usize, blues: &[u8]) -> DBall {
         loop {
-            // Generate 6 red balls without duplicates (1-33)
-            let mut rball = HashSet::new();
+            let mut rball = reds[..included].iter().copied().collect::<HashSet<_>>();
+            let free = &reds[included..];
             while rball.len() < 6 {
-                seed = simple_random(&mut seed);
-                let red_ball = ((seed % 33) + 1) as u8;
-                rball.insert(red_ball);
+                rball.insert(free[(rng.next_u64() % free.len() as u64) as usize]);
             }
 
-            // Generate a blue ball (1-16)
-            seed = simple_random(&mut seed);
-            let bball = ((seed % 16) + 1) as u8;
+            let bball = blues[(rng.next_u64() % blues.len() as u64) as usize];
 
             let mut rball_vec: Vec<u8> = rball.into_iter().collect();
-            rball_vec.sort_unstable();
-
             // Try to create the ticket using the check method
             if let Ok(ticket) = DBall::new_one(&mut rball_vec[..], bball) {
                 return ticket;
             }
-            // If creation fails, retry with a new seed
-            seed = simple_random(&mut seed);
+            // If creation fails, retry with the next number
+            rng.next_u64();
+        }
+    }
+
+    /// Generate a random ticket meeting `constraints`, which must be valid
+    fn generate_constrained(
+        &self,
+        initial_seed: u64,
+        constraints: &GenerationConstraints,
+    ) -> DBall {
+        if constraints.is_empty() {
+            return self.generate_seeded(initial_seed);
+        }
+
+        Self::pick(
+            &mut self.rng.seeded(initial_seed),
+            &constraints.red_candidates(),
+            constraints.include_reds.len().min(6),
+            &constraints.blue_candidates(),
+        )
+    }
+
+    /// Random 复式 bet of `reds` reds and `blues` blues
+    pub fn generate_complex(&self, reds: usize, blues: usize) -> Result<ComplexBet, DBallError> {
+        self.generate_complex_with_seed(rand::random(), reds, blues)
+    }
+
+    /// 复式 bet drawn from `seed` alone with [`Self::rng`], kept with the mean
+    /// score of its simple bets as the probability
+    pub fn generate_complex_with_seed(
+        &self,
+        seed: u64,
+        reds: usize,
+        blues: usize,
+    ) -> Result<ComplexBet, DBallError> {
+        Ok(self
+            .complex_from_seed(seed, reds, blues, &AtomicBool::new(false), &|_| {})?
+            .expect("Complex generation only stops early when cancelled"))
+    }
+
+    /// [`Self::generate_complex_with_seed`] reporting progress like
+    /// [`RandomGenerator::generate_tracked_batch`] until `cancel` is set
+    pub fn generate_tracked_complex(
+        &self,
+        seed: u64,
+        reds: usize,
+        blues: usize,
+        cancel: &AtomicBool,
+        on_progress: &dyn Fn(GenerationProgress),
+    ) -> anyhow::Result<ComplexBet> {
+        self.complex_from_seed(seed, reds, blues, cancel, on_progress)?
+            .ok_or_else(|| anyhow::anyhow!("complex generation cancelled"))
+    }
+
+    /// Simple bets of a 复式 bet scored on each try at most, a random sample of
+    /// them for larger bets, which cover up to C(20, 6) × 16 of them
+    const COMPLEX_SCORE_SAMPLE: usize = 64;
+
+    /// Try 复式 bets drawn from `seed` until one is kept, `None` once `cancel` is set
+    ///
+    /// Progress is reported after the first try and every [`Self::PROGRESS_INTERVAL`] tries
+    fn complex_from_seed(
+        &self,
+        seed: u64,
+        reds: usize,
+        blues: usize,
+        cancel: &AtomicBool,
+        on_progress: &dyn Fn(GenerationProgress),
+    ) -> Result<Option<ComplexBet>, DBallError> {
+        use rand::Rng as _;
+        use rand::seq::SliceRandom as _;
+        const REDS: [u8; 33] = numbers();
+        const BLUES: [u8; 16] = numbers();
+
+        if !(6..=ComplexBet::MAX_RBALLS).contains(&reds) || !(1..=16).contains(&blues) {
+            return Err(DBallError::InvalidComplexCount { reds, blues });
+        }
+        let mut rng = self.rng.seeded(seed);
+        let mut tries = 0_usize;
+        let mut best_score = 0.0_f64;
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                log::debug!("Complex generation cancelled after {tries} tries");
+                return Ok(None);
+            }
+            let bet = ComplexBet::new(
+                REDS.choose_multiple(&mut rng, reds)
+                    .copied()
+                    .collect::<Vec<_>>(),
+                BLUES
+                    .choose_multiple(&mut rng, blues)
+                    .copied()
+                    .collect::<Vec<_>>(),
+                1,
+            )?;
+            let score = Self::complex_score(&mut rng, &bet);
+            tries += 1;
+            best_score = best_score.max(score);
+            if tries == 1 || tries.is_multiple_of(Self::PROGRESS_INTERVAL) {
+                on_progress(GenerationProgress {
+                    tries,
+                    best_score,
+                    seed: None,
+                });
+            }
+            if rng.gen_bool(score.clamp(0.0, 1.0)) {
+                log::info!("Generated complex bet with score {score} after {tries} tries");
+                on_progress(GenerationProgress {
+                    tries,
+                    best_score,
+                    seed: Some(seed),
+                });
+                return Ok(Some(bet));
+            }
         }
     }
 
+    /// Mean score of the simple bets of `bet`, of [`Self::COMPLEX_SCORE_SAMPLE`]
+    /// random ones when it covers more
+    fn complex_score(rng: &mut impl rand::Rng, bet: &ComplexBet) -> f64 {
+        let bets = if bet.bets() <= Self::COMPLEX_SCORE_SAMPLE {
+            bet.expand()
+        } else {
+            (0..Self::COMPLEX_SCORE_SAMPLE)
+                .map(|_| {
+                    let mut picked = rand::seq::index::sample(rng, bet.rball.len(), 6).into_vec();
+                    picked.sort_unstable();
+                    DBall {
+                        rball: std::array::from_fn(|i| bet.rball[picked[i]]),
+                        bball: bet.bball[rng.gen_range(0..bet.bball.len())],
+                        magnification: bet.magnification,
+                    }
+                })
+                .collect()
+        };
+        bets.iter()
+            .map(|bet| Self::score(&bet.evaluate()))
+            .sum::<f64>()
+            / bets.len() as f64
+    }
+
     /// Generate multiple random tickets
     pub fn generate_multiple(&self, count: usize) -> Vec<DBall> {
-        let mut seed = get_time_seed();
+        self.generate_multiple_with(count, &GenerationConstraints::default())
+    }
+
+    /// Generate multiple random tickets meeting `constraints`, which must be valid
+    fn generate_multiple_with(
+        &self,
+        count: usize,
+        constraints: &GenerationConstraints,
+    ) -> Vec<DBall> {
+        let mut rng = self.rng.seeded(get_time_seed());
         (0..count)
-            .map(|_| {
-                seed = simple_random(&mut seed);
-                Self::generate_with_seed(seed)
-            })
+            .map(|_| self.generate_constrained(rng.next_u64(), constraints))
             .collect()
     }
 
-    /// Generate a random ticket with a specific red ball range
+    /// Generate a random ticket with a specific red ball range, from [`Self::rng`]
     pub fn generate_with_red_range(
+        &self,
         min_red: u8,
         max_red: u8,
         bball: Option<u8>,
@@ -106,143 +344,254 @@ impl BlueMorn {
         if min_red < 1 || max_red > 33 || min_red > max_red || max_red - min_red + 1 < 6 {
             return Err(DBallError::InvalidRBallRange((min_red, max_red)));
         }
+        let blues = match bball {
+            Some(blue) if !(1..=16).contains(&blue) => return Err(DBallError::InvalidBBall(blue)),
+            Some(blue) => vec![blue],
+            None => numbers::<16>().to_vec(),
+        };
+
+        let reds = (min_red..=max_red).collect::<Vec<_>>();
+        let mut rng = self.rng.seeded(get_time_seed());
+        Ok(Self::pick(&mut rng, &reds, 0, &blues))
+    }
 
-        let mut seed = get_time_seed();
-        let range_size = max_red - min_red + 1;
-
-        loop {
-            let mut rball = HashSet::new();
-            while rball.len() < 6 {
-                seed = simple_random(&mut seed);
-                let red_ball = min_red + ((seed % range_size as u64) as u8);
-                rball.insert(red_ball);
-            }
-
-            let blue = if let Some(blue) = bball {
-                if !(1..=16).contains(&blue) {
-                    return Err(DBallError::InvalidBBall(blue));
-                }
-                blue
-            } else {
-                seed = simple_random(&mut seed);
-                ((seed % 16) + 1) as u8
-            };
-
-            let mut rball_vec: Vec<u8> = rball.into_iter().collect();
-            rball_vec.sort_unstable();
-
-            // Try to create the ticket using the check method
-            if let Ok(ticket) = DBall::new_one(&mut rball_vec[..], blue) {
-                return Ok(ticket);
+    /// Tries between two progress reports of [`Self::generate_tracked_batch_with`]
+    pub const PROGRESS_INTERVAL: usize = 64;
+
+    /// Candidates [`Self::parallel_generate`] tries at once
+    const CANDIDATES_PER_ROUND: u64 = 64;
+
+    /// Batch of `size` spots, each picked from a large random draw, not scored yet
+    fn candidate_batch(
+        &self,
+        rng: &mut impl rand::Rng,
+        size: usize,
+        constraints: &GenerationConstraints,
+    ) -> DBallBatch {
+        let mut selected_tickets = Vec::with_capacity(size);
+        while selected_tickets.len() < size {
+            // one in ten draws is picked from, any ticket of it as likely as the others
+            if rng.gen_bool(0.1004) {
+                selected_tickets.push(self.generate_constrained(rng.next_u64(), constraints));
             }
-            // If creation fails, retry with a new seed
-            seed = simple_random(&mut seed);
         }
+        DBallBatch(selected_tickets)
     }
 
-    fn generate_dball_batch(&self, stop: &AtomicBool) -> Option<DBallBatch> {
-        const ITER_CHECK: usize = 0xFF;
-        use rand::Rng as _;
-        let mut rng = rand::thread_rng();
-        let mut try_count = 0;
-        let mut selected_tickets = Vec::new();
-        let mut iter: usize = 0;
-
-        loop {
-            iter += 1;
-            while selected_tickets.len() < 5 {
-                let tickets = self.generate_multiple(3544);
-
-                let should_pick = rng.gen_bool(0.1004);
+    /// Seed of the candidate at `index` of a batch drawn from `seed`
+    const fn candidate_seed(seed: u64, index: u64) -> u64 {
+        seed ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
 
-                if should_pick && !tickets.is_empty() {
-                    let random_index = rng.gen_range(0..tickets.len());
-                    let selected = tickets[random_index];
-                    selected_tickets.push(selected);
-                }
-            }
-            let batch = DBallBatch(selected_tickets.clone());
-            let score = self.evaluate_batch(&batch);
-            try_count += 1;
-            if rng.gen_bool(score) {
-                if stop.load(std::sync::atomic::Ordering::Relaxed) {
-                    log::debug!("Received stop signal, exiting batch generation");
-                    return None;
-                }
-                log::info!("Generated batch with score {score} after {try_count} tries",);
-                return Some(batch);
-            } else {
-                log::debug!("Batch with {score} failed, retrying...");
-                selected_tickets.clear();
+    /// Try candidate batches drawn from `seed` on every core until one is
+    /// kept with its score as the probability, or `cancel` is set
+    ///
+    /// Candidates are tried in rounds and the first one kept wins, so `seed`
+    /// always gives the same batch however the tries are spread on the cores.
+    /// Progress is reported after the first try and every [`Self::PROGRESS_INTERVAL`] tries
+    fn parallel_generate(
+        &self,
+        seed: u64,
+        size: usize,
+        constraints: &GenerationConstraints,
+        cancel: &AtomicBool,
+        on_progress: &(dyn Fn(GenerationProgress) + Sync),
+    ) -> anyhow::Result<DBallBatch> {
+        use rand::Rng as _;
+        use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+
+        let tries = AtomicUsize::new(0);
+        // scores are never negative, so their bits order like the scores themselves
+        let best_score = AtomicU64::new(0.0_f64.to_bits());
+        for round in 0.. {
+            let first = round * Self::CANDIDATES_PER_ROUND;
+            let kept = (first..first + Self::CANDIDATES_PER_ROUND)
+                .into_par_iter()
+                .find_map_first(|index| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let mut rng = self.rng.seeded(Self::candidate_seed(seed, index));
+                    let batch = self.candidate_batch(&mut rng, size, constraints);
+                    let score = self.evaluate_batch_with(&batch, constraints);
+                    let tries = tries.fetch_add(1, Ordering::Relaxed) + 1;
+                    let best_score =
+                        best_score.fetch_max(score.max(0.0).to_bits(), Ordering::Relaxed);
+                    if tries == 1 || tries.is_multiple_of(Self::PROGRESS_INTERVAL) {
+                        on_progress(GenerationProgress {
+                            tries,
+                            best_score: f64::from_bits(best_score).max(score),
+                            seed: None,
+                        });
+                    }
+                    if rng.gen_bool(score.clamp(0.0, 1.0)) {
+                        Some((batch, score))
+                    } else {
+                        log::debug!("Batch with {score} failed, retrying...");
+                        None
+                    }
+                });
+
+            // a candidate kept while cancelling may not be the first one
+            if cancel.load(Ordering::Relaxed) {
+                break;
             }
-
-            if (iter & ITER_CHECK) == 0 && stop.load(std::sync::atomic::Ordering::Relaxed) {
-                log::debug!("Stopping batch generation after {iter} iterations");
-                return None;
-            } else {
-                iter = 0;
+            if let Some((batch, score)) = kept {
+                let tries = tries.load(Ordering::Relaxed);
+                log::info!("Generated batch with score {score} after {tries} tries");
+                on_progress(GenerationProgress {
+                    tries,
+                    best_score: f64::from_bits(best_score.load(Ordering::Relaxed)).max(score),
+                    seed: Some(seed),
+                });
+                return Ok(batch);
             }
         }
-    }
-
-    #[expect(clippy::unused_self)]
-    fn multi_thread_generate(&self, thread_count: usize) -> anyhow::Result<DBallBatch> {
-        use std::sync::{Arc, mpsc};
-        use std::thread::{self, JoinHandle};
-
-        // Create a channel to receive results from threads
-        let (tx, rx) = mpsc::channel();
 
-        // Store thread handles so we can terminate them
-        let mut handles: Vec<JoinHandle<()>> = Vec::with_capacity(thread_count);
-
-        let stop = Arc::new(AtomicBool::new(false));
-
-        // Spawn threads to generate batches concurrently
-        for i in 0..thread_count {
-            let tx_clone = tx.clone();
-            let stop_clone = Arc::clone(&stop);
+        log::debug!(
+            "Batch generation cancelled after {} tries",
+            tries.load(Ordering::Relaxed)
+        );
+        anyhow::bail!("batch generation cancelled");
+    }
+}
 
-            // No reference to self escapes; use BlueMorn directly
-            let handle = thread::spawn(move || {
-                log::debug!("Thread {i} starting batch generation");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancelled_generation() {
+        let cancel = AtomicBool::new(true);
+        assert!(
+            BlueMorn::default()
+                .generate_tracked_batch(5, &cancel, &|_| {})
+                .is_err()
+        );
+    }
 
-                // Generate batch (this is a blocking operation until success)
-                let generator = Self;
-                let tickets = generator.generate_dball_batch(&stop_clone);
+    #[test]
+    fn test_legacy_rng() -> anyhow::Result<()> {
+        let legacy = BlueMorn {
+            rng: RngBackend::Lcg,
+        };
+        // what the hand-rolled generator gave before there were backends
+        assert_eq!(
+            legacy.generate_seeded(12345),
+            DBall::new_one([18, 19, 22, 28, 29, 33], 9)?
+        );
+        assert_eq!(
+            legacy.generate_seeded_batch(7, 3),
+            legacy.generate_seeded_batch(7, 3)
+        );
+        assert_ne!(
+            legacy.generate_seeded_batch(7, 3),
+            BlueMorn::default().generate_seeded_batch(7, 3)
+        );
+        // complex bets draw from the chosen backend too
+        assert_ne!(
+            legacy.generate_complex_with_seed(7, 8, 2)?,
+            BlueMorn::default().generate_complex_with_seed(7, 8, 2)?
+        );
+        Ok(())
+    }
 
-                log::info!("Thread {i} successfully generated batch!");
-                // Try to send the result - if channel is closed, just exit
-                if tx_clone.send((i, tickets)).is_err() {
-                    log::debug!("Thread {i} - channel closed, exiting");
+    #[test]
+    fn test_reproduced_batch() {
+        let generator = BlueMorn::default();
+        let seed = std::sync::Mutex::new(None);
+        let batch = generator
+            .generate_tracked_batch(3, &AtomicBool::new(false), &|progress| {
+                if progress.seed.is_some() {
+                    *seed
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner) = progress.seed;
                 }
-            });
+            })
+            .expect("Generation is not cancelled");
+        let seed = seed
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .expect("The finished batch reports its seed");
+        assert_eq!(generator.generate_seeded_batch(seed, 3), batch);
+        assert_eq!(
+            generator.generate_seeded_batch(seed, 3),
+            generator.generate_seeded_batch(seed, 3)
+        );
+    }
 
-            handles.push(handle);
-        }
+    #[test]
+    fn test_complex_generation() -> anyhow::Result<()> {
+        let generator = BlueMorn::default();
+        let bet = generator.generate_complex_with_seed(12, 8, 2)?;
+        assert_eq!((bet.rball.len(), bet.bball.len()), (8, 2));
+        assert_eq!(bet.bets(), 28 * 2);
+        assert_eq!(bet, generator.generate_complex_with_seed(12, 8, 2)?);
+        assert_eq!(
+            generator.generate_complex_with_seed(12, 21, 1),
+            Err(DBallError::InvalidComplexCount { reds: 21, blues: 1 })
+        );
+
+        // the largest bets only score a sample of their simple bets on each try
+        let largest = generator.generate_complex_with_seed(12, ComplexBet::MAX_RBALLS, 16)?;
+        assert_eq!(largest.bets(), 38_760 * 16);
+        assert_eq!(
+            largest,
+            generator.generate_complex_with_seed(12, ComplexBet::MAX_RBALLS, 16)?
+        );
+        Ok(())
+    }
 
-        // Drop the original sender immediately after spawning all threads
-        // This ensures rx.recv() can detect when all worker threads are done
-        drop(tx);
-
-        // Wait for the first successful result
-        if let Ok((thread_id, Some(tickets))) = rx.recv() {
-            log::info!("Received result from thread {thread_id}, terminating other threads");
-            // Stop all other threads
-            stop.store(true, std::sync::atomic::Ordering::Relaxed);
-
-            // Consume and discard any additional results that might come in
-            while let Ok((discarded_thread_id, _)) = rx.try_recv() {
-                log::debug!(
-                    "Discarded result from thread {discarded_thread_id} (already have result)"
-                );
-            }
+    #[test]
+    fn test_tracked_complex_generation() -> anyhow::Result<()> {
+        let generator = BlueMorn::default();
+        let reports = std::sync::Mutex::new(Vec::new());
+        let bet =
+            generator.generate_tracked_complex(3, 10, 2, &AtomicBool::new(false), &|progress| {
+                reports
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .push(progress);
+            })?;
+        assert_eq!(bet, generator.generate_complex_with_seed(3, 10, 2)?);
+        let reports = reports
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert_eq!(reports.first().map(|progress| progress.tries), Some(1));
+        assert_eq!(reports.last().and_then(|progress| progress.seed), Some(3));
+
+        let cancel = AtomicBool::new(true);
+        assert!(
+            generator
+                .generate_tracked_complex(3, 10, 2, &cancel, &|_| {})
+                .is_err()
+        );
+        Ok(())
+    }
 
-            Ok(tickets)
-        } else {
-            // All threads finished without success this should never happen
-            stop.store(true, std::sync::atomic::Ordering::Relaxed);
-            anyhow::bail!("batch generation attempts failed");
+    #[test]
+    fn test_progress_of_generation() {
+        let cancel = AtomicBool::new(false);
+        let reports = std::sync::Mutex::new(Vec::new());
+        // cancelling at the first report, unless its candidate is kept right away
+        let generated = BlueMorn::default().generate_tracked_batch(5, &cancel, &|progress| {
+            cancel.store(true, Ordering::Relaxed);
+            reports
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(progress);
+        });
+        if let Ok(batch) = generated {
+            assert_eq!(batch.0.len(), 5);
         }
+        let reports = reports
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert!(reports.iter().any(|progress| progress.tries == 1));
+        assert!(
+            reports
+                .iter()
+                .all(|progress| (0.0..=1.0).contains(&progress.best_score))
+        );
     }
 }
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DBall, DBallChecker, DBallError};
+use crate::checker;
+use crate::game::Game;
+
+/// Numbers every generated spot must or must not contain
+///
+/// The default has no constraints. Checks a constraint makes impossible to pass,
+/// e.g. repeating the blue ball when it is fixed, no longer count against a batch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationConstraints {
+    /// Reds every spot contains, e.g. favourite numbers
+    pub include_reds: Vec<u8>,
+    /// Reds no spot contains, e.g. the recently drawn ones
+    pub exclude_reds: Vec<u8>,
+    /// Blue of every spot
+    pub fixed_blue: Option<u8>,
+    /// Blues no spot gets
+    pub exclude_blues: Vec<u8>,
+}
+
+impl GenerationConstraints {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether spots can be generated at all, every number must be a valid ball
+    /// and no number both required and excluded
+    pub fn validate(&self) -> Result<(), DBallError> {
+        if let Some(&red) = self
+            .include_reds
+            .iter()
+            .chain(&self.exclude_reds)
+            .find(|red| !(1..=33).contains(*red))
+        {
+            return Err(DBallError::RBallOutOfRange(red));
+        }
+        if let Some(&blue) = self
+            .fixed_blue
+            .iter()
+            .chain(&self.exclude_blues)
+            .find(|blue| !(1..=16).contains(*blue))
+        {
+            return Err(DBallError::InvalidBBall(blue));
+        }
+        if self.include_reds.len() > 6 {
+            return Err(DBallError::InvalidRBallCount(self.include_reds.len()));
+        }
+        if let Some(&red) = self
+            .include_reds
+            .iter()
+            .find(|red| self.exclude_reds.contains(red))
+        {
+            return Err(DBallError::ConflictingConstraint(red));
+        }
+        if let Some(blue) = self
+            .fixed_blue
+            .filter(|blue| self.exclude_blues.contains(blue))
+        {
+            return Err(DBallError::ConflictingConstraint(blue));
+        }
+        if self.red_candidates().len() < 6 || self.blue_candidates().is_empty() {
+            return Err(DBallError::NotEnoughCandidates);
+        }
+        Ok(())
+    }
+
+    /// Reds a spot may contain, the included ones first
+    pub(super) fn red_candidates(&self) -> Vec<u8> {
+        let mut reds = self.include_reds.clone();
+        reds.sort_unstable();
+        reds.dedup();
+        reds.extend(
+            (1..=33)
+                .filter(|red| !self.include_reds.contains(red) && !self.exclude_reds.contains(red)),
+        );
+        reds
+    }
+
+    /// Blues a spot may get
+    pub(super) fn blue_candidates(&self) -> Vec<u8> {
+        match self.fixed_blue {
+            Some(blue) => vec![blue],
+            None => (1..=16)
+                .filter(|blue| !self.exclude_blues.contains(blue))
+                .collect(),
+        }
+    }
+
+    /// Whether `dball` meets every constraint
+    pub fn allows(&self, dball: &DBall) -> bool {
+        self.include_reds
+            .iter()
+            .all(|red| dball.rball.contains(red))
+            && !self
+                .exclude_reds
+                .iter()
+                .any(|red| dball.rball.contains(red))
+            && self.fixed_blue.is_none_or(|blue| blue == dball.bball)
+            && !self.exclude_blues.contains(&dball.bball)
+    }
+
+    /// Zones without an included red
+    fn uncovered_zones(&self) -> Vec<usize> {
+        (0..checker::ZONES)
+            .filter(|&zone| !self.include_reds.iter().any(|&red| zone_of(red) == zone))
+            .collect()
+    }
+
+    /// Whether `check` fails for every batch meeting the constraints, such
+    /// checks are left out of the evaluation
+    pub fn makes_unavoidable(&self, check: DBallChecker) -> bool {
+        let reds = self.red_candidates().len();
+        let blues = self.blue_candidates();
+        match check {
+            DBallChecker::BatchBlueBallDuplicate => blues.len() < 5,
+            DBallChecker::BatchBlueBallDistribution => {
+                blues.iter().all(|&blue| blue < 6) || blues.iter().all(|&blue| blue > 10)
+            }
+            DBallChecker::RedConflictsWithBlue => self
+                .fixed_blue
+                .is_some_and(|blue| self.include_reds.contains(&blue)),
+            // the 30 reds of a batch repeat some red 3 times
+            DBallChecker::BatchTopRedNumberFrequencies => {
+                !self.include_reds.is_empty() || reds < 15
+            }
+            // every pair of spots shares a number, or too many pairs do
+            DBallChecker::BatchHighCosineSimilarity => {
+                !self.include_reds.is_empty() || self.fixed_blue.is_some() || reds < 18
+            }
+            // the included reds are in a row themselves
+            DBallChecker::LongConsecutiveRun | DBallChecker::BatchConsecutiveRuns => {
+                let mut included = self.include_reds.clone();
+                included.sort_unstable();
+                included.dedup();
+                checker::longest_run(&included).len() >= checker::MIN_RUN
+            }
+            // no red is left for some zone
+            DBallChecker::ZoneImbalance => {
+                let free = 6 - self.include_reds.len().min(6);
+                let uncovered = self.uncovered_zones();
+                uncovered.len() > free
+                    || uncovered.iter().any(|&zone| {
+                        !self
+                            .red_candidates()
+                            .iter()
+                            .any(|&red| zone_of(red) == zone)
+                    })
+            }
+            DBallChecker::BatchZoneSkew => (0..checker::ZONES).any(|zone| {
+                !self
+                    .red_candidates()
+                    .iter()
+                    .any(|&red| zone_of(red) == zone)
+            }),
+            _ => false,
+        }
+    }
+}
+
+fn zone_of(red: u8) -> usize {
+    checker::zone(Game::Ssq.rules(), red)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::bluemorn::BlueMorn;
+
+    #[test]
+    fn test_validate_constraints() {
+        assert_eq!(GenerationConstraints::default().validate(), Ok(()));
+
+        let conflict = GenerationConstraints {
+            include_reds: vec![3, 8],
+            exclude_reds: vec![8],
+            ..GenerationConstraints::default()
+        };
+        assert_eq!(
+            conflict.validate(),
+            Err(DBallError::ConflictingConstraint(8))
+        );
+
+        let blue = GenerationConstraints {
+            fixed_blue: Some(17),
+            ..GenerationConstraints::default()
+        };
+        assert_eq!(blue.validate(), Err(DBallError::InvalidBBall(17)));
+
+        let exhausted = GenerationConstraints {
+            exclude_reds: (1..=28).collect(),
+            ..GenerationConstraints::default()
+        };
+        assert_eq!(exhausted.validate(), Err(DBallError::NotEnoughCandidates));
+    }
+
+    #[test]
+    fn test_unavoidable_checks() {
+        let no_high = GenerationConstraints {
+            exclude_reds: (23..=33).collect(),
+            ..GenerationConstraints::default()
+        };
+        assert!(no_high.makes_unavoidable(DBallChecker::ZoneImbalance));
+        assert!(no_high.makes_unavoidable(DBallChecker::BatchZoneSkew));
+
+        let low_five = GenerationConstraints {
+            include_reds: vec![1, 3, 5, 7, 9],
+            ..GenerationConstraints::default()
+        };
+        assert!(low_five.makes_unavoidable(DBallChecker::ZoneImbalance));
+        assert!(!low_five.makes_unavoidable(DBallChecker::BatchZoneSkew));
+        assert!(!GenerationConstraints::default().makes_unavoidable(DBallChecker::ZoneImbalance));
+    }
+
+    #[test]
+    fn test_constrained_batch() -> anyhow::Result<()> {
+        let constraints = GenerationConstraints {
+            include_reds: vec![7, 21],
+            exclude_reds: vec![1, 2, 3, 4, 5, 6],
+            fixed_blue: Some(9),
+            exclude_blues: Vec::new(),
+        };
+        let batch = BlueMorn::default().generate_seeded_batch_with(4, 5, &constraints)?;
+        assert!(
+            batch.0.iter().all(|dball| constraints.allows(dball)),
+            "{batch:?}"
+        );
+        assert_eq!(
+            batch,
+            BlueMorn::default().generate_seeded_batch_with(4, 5, &constraints)?
+        );
+
+        // the numbers of the latest draw
+        let banned = GenerationConstraints {
+            exclude_reds: vec![2, 9, 14, 22, 27, 31],
+            exclude_blues: vec![12],
+            ..GenerationConstraints::default()
+        };
+        let batch = BlueMorn::default().generate_seeded_batch_with(4, 6, &banned)?;
+        assert_eq!(batch.0.len(), 6);
+        assert!(
+            batch.0.iter().all(|dball| banned.allows(dball)),
+            "{batch:?}"
+        );
+        Ok(())
+    }
+}
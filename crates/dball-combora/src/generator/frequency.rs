@@ -0,0 +1,170 @@
+use rand::seq::SliceRandom as _;
+
+use super::bluemorn::BlueMorn;
+use super::rng::RngBackend;
+use super::{DBall, DBallBatch, HistoryAware, RandomGenerator, accept_seeded_batch, numbers};
+
+/// Picks numbers weighted by how often they were drawn in the past
+///
+/// A number drawn `n` times weighs `(n + 1) ^ bias`: a `bias` of 1 picks in
+/// proportion to the frequency, 0 ignores the history and a negative bias
+/// favours the cold numbers. Batches go through the checks of [`BlueMorn`]
+pub struct Frequency {
+    bias: f64,
+    red_weights: [f64; 33],
+    blue_weights: [f64; 16],
+    rng: RngBackend,
+}
+
+impl Frequency {
+    /// Bias picking in proportion to the frequency
+    pub const DEFAULT_BIAS: f64 = 1.0;
+
+    /// Latest draws learned from when nothing else is asked for
+    pub const DEFAULT_WINDOW: usize = 100;
+
+    /// Weights learned from `history`, the winning numbers of past draws
+    pub fn new(history: &[DBall], bias: f64) -> Self {
+        let mut frequency = Self {
+            bias,
+            red_weights: [1.0; 33],
+            blue_weights: [1.0; 16],
+            rng: RngBackend::default(),
+        };
+        frequency.with_history(history);
+        frequency
+    }
+}
+
+impl HistoryAware for Frequency {
+    /// Weigh the numbers by their frequency in `history` with the bias of the generator
+    fn with_history(&mut self, history: &[DBall]) {
+        let mut red_counts = [0_u32; 33];
+        let mut blue_counts = [0_u32; 16];
+        for draw in history {
+            for red in draw.rball {
+                if let Some(count) = red_counts.get_mut(usize::from(red).wrapping_sub(1)) {
+                    *count += 1;
+                }
+            }
+            if let Some(count) = blue_counts.get_mut(usize::from(draw.bball).wrapping_sub(1)) {
+                *count += 1;
+            }
+        }
+
+        let weight = |count: u32| f64::from(count + 1).powf(self.bias);
+        self.red_weights = red_counts.map(weight);
+        self.blue_weights = blue_counts.map(weight);
+    }
+}
+
+impl Frequency {
+    fn pick(&self, rng: &mut impl rand::Rng) -> DBall {
+        const REDS: [u8; 33] = numbers();
+        const BLUES: [u8; 16] = numbers();
+        let weight = |weights: &[f64], number: &u8| weights[usize::from(*number) - 1];
+        loop {
+            let mut reds = REDS
+                .choose_multiple_weighted(rng, 6, |red| weight(&self.red_weights, red))
+                .map(|reds| reds.copied().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let blue = BLUES
+                .choose_weighted(rng, |blue| weight(&self.blue_weights, blue))
+                .copied()
+                .unwrap_or(1);
+            reds.sort_unstable();
+            if let Ok(dball) = DBall::new_one(reds, blue) {
+                return dball;
+            }
+        }
+    }
+}
+
+impl RandomGenerator for Frequency {
+    fn generate_seeded_batch(&self, seed: u64, size: usize) -> DBallBatch {
+        accept_seeded_batch(self, self.rng, seed, size, |rng| self.pick(rng))
+    }
+
+    fn evaluate_batch(&self, batch: &DBallBatch) -> f64 {
+        BlueMorn::default().evaluate_batch(batch)
+    }
+
+    fn set_rng(&mut self, rng: RngBackend) {
+        self.rng = rng;
+    }
+
+    fn history_aware(&mut self) -> Option<&mut dyn HistoryAware> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+    use rand::SeedableRng as _;
+
+    #[test]
+    fn test_frequency_weights() -> anyhow::Result<()> {
+        let history = vec![DBall::new_one([1, 2, 3, 4, 5, 6], 16)?; 9];
+        let hot = Frequency::new(&history, 1.0);
+        assert_eq!(hot.red_weights[0], 10.0);
+        assert_eq!(hot.red_weights[32], 1.0);
+        assert_eq!(hot.blue_weights[15], 10.0);
+
+        let cold = Frequency::new(&history, -1.0);
+        assert!(cold.red_weights[0] < cold.red_weights[32]);
+
+        let uniform = Frequency::new(&history, 0.0);
+        assert!(uniform.red_weights.iter().all(|weight| *weight == 1.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_history() -> anyhow::Result<()> {
+        let history = vec![DBall::new_one([1, 2, 3, 4, 5, 6], 16)?; 9];
+        let mut generator = Frequency::new(&[], -1.0);
+        generator.with_history(&history);
+        assert_eq!(
+            generator.red_weights,
+            Frequency::new(&history, -1.0).red_weights
+        );
+        // learning again forgets the earlier history
+        generator.with_history(&[]);
+        assert!(generator.red_weights.iter().all(|weight| *weight == 1.0));
+
+        for kind in Generator::ALL {
+            let mut created = Generator::create_generator(kind);
+            assert_eq!(created.history_aware().is_some(), kind.uses_history());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_batch() {
+        let history = (0..100)
+            .map(BlueMorn::generate_with_seed)
+            .collect::<Vec<_>>();
+        let generator = Frequency::new(&history, Frequency::DEFAULT_BIAS);
+        assert_eq!(
+            generator.generate_seeded_batch(11, 5),
+            generator.generate_seeded_batch(11, 5)
+        );
+    }
+
+    #[test]
+    fn test_pick_favours_hot_numbers() -> anyhow::Result<()> {
+        // varied reds, but the blue is always 7
+        let history = (0..50)
+            .map(|seed| DBall::new_one(BlueMorn::generate_with_seed(seed).rball, 7))
+            .collect::<Result<Vec<_>, _>>()?;
+        let generator = Frequency::new(&history, Frequency::DEFAULT_BIAS);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let blues = (0..100)
+            .filter(|_| generator.pick(&mut rng).bball == 7)
+            .count();
+        // 6 of 100 when every blue is as likely, 77 in proportion to the frequency
+        assert!(blues > 50, "hot blue picked {blues} of 100 times");
+        Ok(())
+    }
+}
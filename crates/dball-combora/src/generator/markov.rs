@@ -0,0 +1,228 @@
+use rand::distributions::{Distribution as _, WeightedIndex};
+use rand::seq::SliceRandom as _;
+
+use super::bluemorn::BlueMorn;
+use super::rng::RngBackend;
+use super::{DBall, DBallBatch, HistoryAware, RandomGenerator, accept_seeded_batch, numbers};
+
+/// Samples spots from a transition model over consecutive past draws
+///
+/// A red weighs how often it followed the reds of the latest draw, times how
+/// often it was drawn together with the reds picked so far. The blue weighs how
+/// often it followed the latest blue. Every count starts at 1, so numbers never
+/// seen still come up and without history all numbers are equally likely.
+/// Batches go through the checks of [`BlueMorn`]
+pub struct Markov {
+    /// `red_transitions[i][j]` counts red `j + 1` drawn right after red `i + 1`
+    red_transitions: [[f64; 33]; 33],
+    /// `red_pairs[i][j]` counts reds `i + 1` and `j + 1` drawn together
+    red_pairs: [[f64; 33]; 33],
+    /// `blue_transitions[i][j]` counts blue `j + 1` drawn right after blue `i + 1`
+    blue_transitions: [[f64; 16]; 16],
+    /// Latest draw, the state the spots follow
+    last: Option<DBall>,
+    rng: RngBackend,
+}
+
+impl Markov {
+    /// Model of `history`, the winning numbers of past draws oldest first
+    pub fn new(history: &[DBall]) -> Self {
+        let mut markov = Self {
+            red_transitions: [[1.0; 33]; 33],
+            red_pairs: [[1.0; 33]; 33],
+            blue_transitions: [[1.0; 16]; 16],
+            last: None,
+            rng: RngBackend::default(),
+        };
+        markov.with_history(history);
+        markov
+    }
+
+    /// Weight of `red` following the latest draw next to the `picked` reds
+    fn red_weight(&self, red: u8, picked: &[u8]) -> f64 {
+        let mean = |weights: Vec<f64>| {
+            if weights.is_empty() {
+                1.0
+            } else {
+                weights.iter().sum::<f64>() / weights.len() as f64
+            }
+        };
+        let transition = self.last.as_ref().map_or(1.0, |last| {
+            mean(
+                last.rball
+                    .iter()
+                    .map(|from| self.red_transitions[index(*from)][index(red)])
+                    .collect(),
+            )
+        });
+        let pairs = mean(
+            picked
+                .iter()
+                .map(|other| self.red_pairs[index(*other)][index(red)])
+                .collect(),
+        );
+        transition * pairs
+    }
+
+    fn pick(&self, rng: &mut impl rand::Rng) -> DBall {
+        const REDS: [u8; 33] = numbers();
+        const BLUES: [u8; 16] = numbers();
+        loop {
+            let mut reds = Vec::with_capacity(6);
+            while reds.len() < 6 {
+                let weights = REDS.map(|red| {
+                    if reds.contains(&red) {
+                        0.0
+                    } else {
+                        self.red_weight(red, &reds)
+                    }
+                });
+                let Ok(distribution) = WeightedIndex::new(weights) else {
+                    break;
+                };
+                reds.push(REDS[distribution.sample(rng)]);
+            }
+            reds.sort_unstable();
+
+            let blue = BLUES
+                .choose_weighted(rng, |blue| {
+                    self.last.as_ref().map_or(1.0, |last| {
+                        self.blue_transitions[index(last.bball)][index(*blue)]
+                    })
+                })
+                .copied()
+                .unwrap_or(1);
+            if let Ok(dball) = DBall::new_one(reds, blue) {
+                return dball;
+            }
+        }
+    }
+}
+
+/// Row of a valid ball number in the tables
+fn index(number: u8) -> usize {
+    usize::from(number).saturating_sub(1)
+}
+
+impl HistoryAware for Markov {
+    /// Count the transitions and pairs of `history` from scratch
+    fn with_history(&mut self, history: &[DBall]) {
+        self.red_transitions = [[1.0; 33]; 33];
+        self.red_pairs = [[1.0; 33]; 33];
+        self.blue_transitions = [[1.0; 16]; 16];
+        self.last = history.last().copied();
+
+        for draw in history {
+            for a in draw.rball {
+                for b in draw.rball {
+                    if a != b {
+                        self.red_pairs[index(a)][index(b)] += 1.0;
+                    }
+                }
+            }
+        }
+        for draws in history.windows(2) {
+            let [previous, next] = draws else {
+                continue;
+            };
+            for from in previous.rball {
+                for to in next.rball {
+                    self.red_transitions[index(from)][index(to)] += 1.0;
+                }
+            }
+            self.blue_transitions[index(previous.bball)][index(next.bball)] += 1.0;
+        }
+    }
+}
+
+impl RandomGenerator for Markov {
+    fn generate_seeded_batch(&self, seed: u64, size: usize) -> DBallBatch {
+        accept_seeded_batch(self, self.rng, seed, size, |rng| self.pick(rng))
+    }
+
+    fn evaluate_batch(&self, batch: &DBallBatch) -> f64 {
+        BlueMorn::default().evaluate_batch(batch)
+    }
+
+    fn set_rng(&mut self, rng: RngBackend) {
+        self.rng = rng;
+    }
+
+    fn history_aware(&mut self) -> Option<&mut dyn HistoryAware> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng as _;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn test_markov_transitions() -> anyhow::Result<()> {
+        let low = DBall::new_one([1, 2, 3, 4, 5, 6], 1)?;
+        let high = DBall::new_one([28, 29, 30, 31, 32, 33], 16)?;
+        let history = [low, high, low, high];
+        let markov = Markov::new(&history);
+        assert_eq!(markov.red_transitions[32][0], 2.0);
+        assert_eq!(markov.red_transitions[0][0], 1.0);
+        assert_eq!(markov.red_pairs[0][5], 3.0);
+        assert_eq!(markov.blue_transitions[15][0], 2.0);
+
+        // the latest draw was high, low numbers follow it
+        assert!(markov.red_weight(1, &[]) > markov.red_weight(33, &[]));
+        assert!(markov.red_weight(2, &[1]) > markov.red_weight(20, &[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_batch() -> anyhow::Result<()> {
+        let history = (0..100)
+            .map(BlueMorn::generate_with_seed)
+            .collect::<Vec<_>>();
+        let markov = Markov::new(&history);
+        assert_eq!(
+            markov.generate_seeded_batch(5, 3),
+            markov.generate_seeded_batch(5, 3)
+        );
+        let batch = Markov::new(&[]).generate_batch(6)?;
+        assert_eq!(batch.0.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tracked_batch_reports_its_seed() -> anyhow::Result<()> {
+        let markov = Markov::new(&[]);
+        let reported = std::sync::Mutex::new(None);
+        let batch = markov.generate_tracked_batch(4, &AtomicBool::new(false), &|stats| {
+            *reported
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = stats.seed;
+        })?;
+        let seed = reported
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .ok_or(anyhow::anyhow!("No seed reported"))?;
+        assert_eq!(markov.generate_seeded_batch(seed, 4), batch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pick_follows_the_latest_draw() -> anyhow::Result<()> {
+        let history = (0..40)
+            .map(|i| {
+                let draw = BlueMorn::generate_with_seed(i);
+                DBall::new_one(draw.rball, if i % 2 == 0 { 3 } else { 9 })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let markov = Markov::new(&history);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(8);
+        // 9 was drawn last and 3 always came after it
+        let blues = (0..100)
+            .filter(|_| markov.pick(&mut rng).bball == 3)
+            .count();
+        assert!(blues > 30, "blue 3 picked {blues} of 100 times");
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use rand::SeedableRng as _;
+
+/// Source of the random numbers of the generators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RngBackend {
+    /// [`rand::rngs::StdRng`], a `ChaCha` stream cipher
+    #[default]
+    Std,
+    /// The linear congruential generator [`crate::generator::bluemorn::BlueMorn`]
+    /// used to pick its spots with, statistically weak but giving the same
+    /// spots for the same seeds as before
+    Lcg,
+}
+
+impl RngBackend {
+    pub const ALL: [Self; 2] = [Self::Std, Self::Lcg];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Std => "Std",
+            Self::Lcg => "Lcg",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rng| rng.name() == name)
+    }
+
+    /// Generator of this backend drawing everything from `seed`
+    pub fn seeded(self, seed: u64) -> SeededRng {
+        match self {
+            Self::Std => SeededRng::Std(Box::new(rand::rngs::StdRng::seed_from_u64(seed))),
+            Self::Lcg => SeededRng::Lcg(Lcg(seed)),
+        }
+    }
+}
+
+/// Random number generator of a [`RngBackend`]
+#[derive(Debug, Clone)]
+pub enum SeededRng {
+    Std(Box<rand::rngs::StdRng>),
+    Lcg(Lcg),
+}
+
+impl rand::RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Std(rng) => rng.next_u32(),
+            Self::Lcg(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Std(rng) => rng.next_u64(),
+            Self::Lcg(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Std(rng) => rng.fill_bytes(dest),
+            Self::Lcg(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Std(rng) => rng.try_fill_bytes(dest),
+            Self::Lcg(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Linear congruential generator, every number is the state after the step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lcg(pub u64);
+
+impl rand::RngCore for Lcg {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore as _;
+
+    #[test]
+    fn test_lcg_sequence() {
+        let mut rng = RngBackend::Lcg.seeded(1);
+        assert_eq!(rng.next_u64(), 1103515245 + 12345);
+        assert_eq!(
+            rng.next_u64(),
+            1103527590_u64.wrapping_mul(1103515245).wrapping_add(12345)
+        );
+    }
+
+    #[test]
+    fn test_backends() {
+        for backend in RngBackend::ALL {
+            assert_eq!(RngBackend::from_name(backend.name()), Some(backend));
+            let mut bytes = [0; 11];
+            backend.seeded(7).fill_bytes(&mut bytes);
+            assert_ne!(bytes, [0; 11]);
+            assert_eq!(backend.seeded(7).next_u64(), backend.seeded(7).next_u64());
+        }
+        assert_eq!(RngBackend::from_name("ChaCha"), None);
+    }
+}
@@ -2,8 +2,10 @@ pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
 
+pub mod backtest;
 pub mod checker;
 pub mod dball;
+pub mod game;
 pub mod generator;
 
 #[cfg(test)]
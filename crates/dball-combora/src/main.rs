@@ -1,3 +1,4 @@
+use dball_combora::dball::DEFAULT_BATCH_SIZE;
 use dball_combora::generator::Generator;
 
 fn main() -> anyhow::Result<()> {
@@ -7,8 +8,8 @@ fn main() -> anyhow::Result<()> {
 
     log::info!("Running in terminal mode with concurrent batch generation (10 threads).");
     let generator = Generator::create_generator(Generator::BlueMorn);
-    let tickets = generator.generate_batch()?;
-    for ticket in tickets {
+    let tickets = generator.generate_batch(DEFAULT_BATCH_SIZE)?;
+    for ticket in tickets.0 {
         log::info!("Ticket: {ticket}");
     }
 
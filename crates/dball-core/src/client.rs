@@ -0,0 +1,276 @@
+use dball_client::ipc::client::{DaemonTransport, HttpTransport, RpcTransport};
+use dball_client::ipc::protocol::RpcService;
+
+use crate::{
+    BatchSummary, DbHealth, DballConfig, DuplicateSpots, ManualSpot, MetricsSnapshot, Page,
+    PageRequest, PeriodEvaluation, PeriodicReport, PlannedBatch, RecommendRequest, Recommendation,
+    RoiAnalytics, RoiReport, RpcError, RpcErrorCode, Spot, SpotFilter, SpotQuery,
+    StrategySimulation, TagCount, Ticket, TransportMode,
+};
+
+/// Typed client of the running daemon
+///
+/// Every call goes through the daemon, which owns the database, so several
+/// tools can use it at the same time as the desktop and terminal apps
+pub struct DballClient<T = DaemonTransport> {
+    transport: T,
+}
+
+impl DballClient {
+    /// Connect over the Unix socket, falling back to the HTTP API configured
+    /// by the `DBALL_HTTP_*` variables when the socket is not available
+    pub async fn connect() -> Result<Self, RpcError> {
+        Self::connect_with(TransportMode::Auto, HttpTransport::from_env()).await
+    }
+
+    /// Connect to the HTTP API at `base_url`, e.g. `http://127.0.0.1:8081`
+    pub async fn connect_http(
+        base_url: impl Into<String>,
+        token: Option<String>,
+    ) -> Result<Self, RpcError> {
+        let http = HttpTransport::new(base_url).with_token(token);
+        Self::connect_with(TransportMode::Http, http).await
+    }
+
+    async fn connect_with(mode: TransportMode, http: HttpTransport) -> Result<Self, RpcError> {
+        DaemonTransport::connect(mode, http)
+            .await
+            .map(Self::with_transport)
+            .map_err(|e| transport_error(&e))
+    }
+}
+
+impl<T: RpcTransport> DballClient<T> {
+    /// Use an already connected transport
+    pub fn with_transport(transport: T) -> Self {
+        Self { transport }
+    }
+
+    /// Generate a batch for the next period, returning every unsettled spot of that period
+    pub async fn generate(&self) -> Result<Vec<Spot>, RpcError> {
+        self.request::<()>(RpcService::GenerateBatchSpots(None))
+            .await?;
+        self.unprized_spots().await
+    }
+
+    /// Like [`Self::generate`] with the generator named `strategy` instead of the configured one
+    pub async fn generate_with(&self, strategy: &str) -> Result<Vec<Spot>, RpcError> {
+        self.request::<()>(RpcService::GenerateBatchSpots(Some(strategy.to_owned())))
+            .await?;
+        self.unprized_spots().await
+    }
+
+    /// Generate a batch for each of the next `periods` draws, tagged by their period
+    pub async fn generate_ahead(
+        &self,
+        periods: usize,
+        strategy: Option<&str>,
+    ) -> Result<Vec<PlannedBatch>, RpcError> {
+        let service = RpcService::GenerateSpotsForPeriods(periods, strategy.map(str::to_owned));
+        self.request(service).await
+    }
+
+    /// The best batches of the candidates the daemon generates, nothing is saved
+    pub async fn recommend(
+        &self,
+        request: RecommendRequest,
+    ) -> Result<Vec<Recommendation>, RpcError> {
+        self.request(RpcService::Recommend(request)).await
+    }
+
+    /// What the generator named `strategy`, or the configured one, would have
+    /// earned over the draws of `year`
+    pub async fn simulate(
+        &self,
+        year: i32,
+        strategy: Option<&str>,
+    ) -> Result<StrategySimulation, RpcError> {
+        let service = RpcService::SimulateStrategy(year, strategy.map(str::to_owned));
+        self.request(service).await
+    }
+
+    /// Save numbers picked by hand for the next period, returning that period
+    pub async fn add_manual_spot(&self, spot: ManualSpot) -> Result<String, RpcError> {
+        self.request(RpcService::AddManualSpot(spot)).await
+    }
+
+    /// Fetch the latest draw from the lottery API and store it
+    pub async fn update_latest_ticket(&self) -> Result<Ticket, RpcError> {
+        self.request(RpcService::UpdateLatestTicket).await
+    }
+
+    /// Check every unsettled spot against the drawn tickets, returning the prized ones
+    pub async fn settle(&self) -> Result<Vec<Spot>, RpcError> {
+        self.request(RpcService::UpdateAllUnprizeSpots).await
+    }
+
+    /// Settle the spots of `period` against its draw, fetched when missing,
+    /// returning the cost and winnings of the period
+    pub async fn evaluate_period(&self, period: &str) -> Result<PeriodEvaluation, RpcError> {
+        self.request(RpcService::EvaluatePeriod(period.to_owned()))
+            .await
+    }
+
+    /// Cost and winnings of the settled spots, newest period first
+    pub async fn report(&self) -> Result<RoiReport, RpcError> {
+        self.request(RpcService::GetRoiReport).await
+    }
+
+    /// Spend, returns and wins of the settled spots drawn in `year`, or in
+    /// `month` of it
+    pub async fn periodic_report(
+        &self,
+        year: i32,
+        month: Option<u32>,
+    ) -> Result<PeriodicReport, RpcError> {
+        self.request(RpcService::GetPeriodicReport(year, month))
+            .await
+    }
+
+    /// Cumulative cost and winnings per period, with the win rate and the
+    /// break-even projection over the latest `window` periods
+    pub async fn analytics(&self, window: usize) -> Result<RoiAnalytics, RpcError> {
+        self.request(RpcService::GetRoiAnalytics(window)).await
+    }
+
+    /// Unsettled spots of the next period
+    pub async fn unprized_spots(&self) -> Result<Vec<Spot>, RpcError> {
+        self.request(RpcService::GetUnprizeSpots).await
+    }
+
+    /// Statistics of the unsettled spots of the next period as one batch
+    pub async fn next_batch_summary(&self) -> Result<BatchSummary, RpcError> {
+        self.request(RpcService::GetNextBatchSummary).await
+    }
+
+    /// Spots matching `query`
+    pub async fn spots(&self, query: SpotQuery) -> Result<Vec<Spot>, RpcError> {
+        self.request(RpcService::QuerySpots(query)).await
+    }
+
+    /// Page of the drawn tickets, only the periods starting with `period_prefix` if given
+    pub async fn tickets(
+        &self,
+        period_prefix: Option<String>,
+        page: PageRequest,
+    ) -> Result<Page<Ticket>, RpcError> {
+        self.request(RpcService::GetTickets(period_prefix, page))
+            .await
+    }
+
+    /// Page of the spots checked against a draw
+    pub async fn prized_spots(&self, page: PageRequest) -> Result<Page<Spot>, RpcError> {
+        self.request(RpcService::GetPrizedSpots(page)).await
+    }
+
+    /// Page of the spots matching `filter`
+    pub async fn find_spots(
+        &self,
+        filter: SpotFilter,
+        page: PageRequest,
+    ) -> Result<Page<Spot>, RpcError> {
+        self.request(RpcService::FindSpots(filter, page)).await
+    }
+
+    /// Label the spots `ids` with `tag`, returning the number of spots newly tagged
+    pub async fn tag_spots(&self, ids: Vec<i32>, tag: String) -> Result<usize, RpcError> {
+        self.request(RpcService::TagSpots(ids, tag)).await
+    }
+
+    /// Every tag with the number of spots carrying it
+    pub async fn tags(&self) -> Result<Vec<TagCount>, RpcError> {
+        self.request(RpcService::GetTags).await
+    }
+
+    /// Live spots repeating another one of their period, grouped by period and numbers
+    pub async fn duplicate_spots(&self) -> Result<Vec<DuplicateSpots>, RpcError> {
+        self.request(RpcService::GetDuplicateSpots).await
+    }
+
+    /// Integrity check, sizes and row counts of the daemon's database
+    pub async fn db_health(&self) -> Result<DbHealth, RpcError> {
+        self.request(RpcService::GetDbHealth).await
+    }
+
+    /// Counters and operation durations of the daemon since it started
+    pub async fn metrics(&self) -> Result<MetricsSnapshot, RpcError> {
+        self.request(RpcService::GetMetrics).await
+    }
+
+    /// Contents of the daemon's `dball.toml`
+    pub async fn config(&self) -> Result<DballConfig, RpcError> {
+        self.request(RpcService::GetConfig).await
+    }
+
+    /// Send a request answered with a `Result`, flattening transport and service errors
+    async fn request<R>(&self, service: RpcService) -> Result<R, RpcError>
+    where
+        for<'de> R: serde::Deserialize<'de>,
+    {
+        let response = self
+            .transport
+            .send_rpc(service)
+            .await
+            .map_err(|e| transport_error(&e))?;
+        serde_json::from_value::<Result<R, RpcError>>(response).map_err(|e| {
+            RpcError::new(
+                RpcErrorCode::Internal,
+                format!("Unexpected response from the daemon: {e}"),
+            )
+        })?
+    }
+}
+
+fn transport_error(e: &anyhow::Error) -> RpcError {
+    e.downcast_ref::<RpcError>()
+        .cloned()
+        .unwrap_or_else(|| RpcError::new(RpcErrorCode::Transport, format!("{e:#}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    /// Answers every request with the response of its service name
+    struct Canned(Vec<(&'static str, Value)>);
+
+    impl RpcTransport for Canned {
+        fn name(&self) -> &'static str {
+            "canned"
+        }
+
+        async fn send_rpc(&self, service: RpcService) -> anyhow::Result<Value> {
+            let name = format!("{service:?}");
+            self.0
+                .iter()
+                .find(|(prefix, _)| name.starts_with(prefix))
+                .map(|(_, response)| response.clone())
+                .ok_or_else(|| anyhow::anyhow!("No response for {name}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report() {
+        let client = DballClient::with_transport(Canned(vec![(
+            "GetRoiReport",
+            json!({ "Ok": { "periods": [], "total_cost": 20, "total_winnings": 5 } }),
+        )]));
+        let report = client.report().await.expect("Report");
+        assert_eq!(report.total_cost, 20);
+        assert_eq!(report.total_winnings, 5);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let client = DballClient::with_transport(Canned(vec![(
+            "GetConfig",
+            json!({ "Err": RpcError::new(RpcErrorCode::Internal, "no dball.toml") }),
+        )]));
+        let error = client.config().await.expect_err("Service error");
+        assert_eq!(error.code, RpcErrorCode::Internal);
+
+        let error = client.report().await.expect_err("Transport error");
+        assert_eq!(error.code, RpcErrorCode::Transport);
+    }
+}
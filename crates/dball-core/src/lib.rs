@@ -0,0 +1,43 @@
+//! Stable facade over dball for tools built on top of it
+//!
+//! Everything reachable from this crate follows semver: a breaking change to
+//! any item re-exported here bumps the major version. `dball-client` and
+//! `dball-combora` stay internal and may change in any release, so embedders
+//! should depend on this crate alone.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), dball_core::RpcError> {
+//! let client = dball_core::DballClient::connect().await?;
+//! let spots = client.generate().await?;
+//! println!("{} spots for the next period", spots.len());
+//! let report = client.report().await?;
+//! println!("Spent {} yuan, won {}", report.total_cost, report.total_winnings);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+mod client;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use client::DballClient;
+
+pub use dball_client::config::{BudgetConfig, DballConfig, DuplicatePolicy, GeneratorConfig};
+pub use dball_client::ipc::client::TransportMode;
+pub use dball_client::ipc::error::{RpcError, RpcErrorCode};
+pub use dball_client::metrics::{DurationHistogram, MetricsSnapshot};
+pub use dball_client::models::{
+    DbHealth, DuplicateSpots, ManualSpot, Page, PageRequest, RecommendRequest, SortOrder, Spot,
+    SpotFilter, SpotQuery, SpotScope, TagCount, Ticket,
+};
+pub use dball_client::service::{
+    BacktestResult, BreakEvenProjection, PeriodEvaluation, PeriodNet, PeriodRoi, PeriodicReport,
+    PlannedBatch, Recommendation, RoiAnalytics, RoiPoint, RoiReport, SimulatedWeek,
+    StrategySimulation,
+};
+pub use dball_combora::checker::{CheckDetail, DBallChecker, Severity};
+pub use dball_combora::dball::{
+    BatchSummary, COST_PER_TICKET, DBall, DBallBuilder, PrizeTable, Reward, Spread,
+};
+pub use dball_combora::generator::rng::RngBackend;
+pub use dball_combora::generator::{Generator, HistoryAware, RandomGenerator};
@@ -0,0 +1,3 @@
+-- Remove note and purchased fields
+ALTER TABLE spot DROP COLUMN purchased;
+ALTER TABLE spot DROP COLUMN note;
@@ -0,0 +1,3 @@
+-- Add free text note and purchased flag to spot table
+ALTER TABLE spot ADD COLUMN note TEXT;
+ALTER TABLE spot ADD COLUMN purchased BOOLEAN NOT NULL DEFAULT FALSE;
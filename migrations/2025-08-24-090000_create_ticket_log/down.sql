@@ -0,0 +1,2 @@
+-- Drop the ticket log table
+DROP TABLE IF EXISTS ticket_log;
@@ -0,0 +1,29 @@
+-- Draw details crawled from the official site, databases created before this
+-- migration already have the table
+CREATE TABLE IF NOT EXISTS ticket_log (
+    code TEXT PRIMARY KEY NOT NULL,
+    kj_date DATE,
+    xq TEXT,
+    number1 INTEGER,
+    number2 INTEGER,
+    number3 INTEGER,
+    number4 INTEGER,
+    number5 INTEGER,
+    number6 INTEGER,
+    number7 INTEGER,
+    jsondata TEXT,
+    total_sales INTEGER,
+    jackpot INTEGER,
+    prize1_num INTEGER,
+    prize1_money INTEGER,
+    prize2_num INTEGER,
+    prize2_money INTEGER,
+    prize3_num INTEGER,
+    prize3_money INTEGER,
+    prize4_num INTEGER,
+    prize4_money INTEGER,
+    prize5_num INTEGER,
+    prize5_money INTEGER,
+    prize6_num INTEGER,
+    prize6_money INTEGER
+);
@@ -0,0 +1,3 @@
+-- Remove the extra numbers of complex bets
+ALTER TABLE spot DROP COLUMN extra_blues;
+ALTER TABLE spot DROP COLUMN extra_reds;
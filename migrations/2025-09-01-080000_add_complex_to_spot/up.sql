@@ -0,0 +1,3 @@
+-- Reds and blues of complex bets beyond the first 6 reds and the first blue
+ALTER TABLE spot ADD COLUMN extra_reds TEXT;
+ALTER TABLE spot ADD COLUMN extra_blues TEXT;
@@ -0,0 +1,2 @@
+-- Remove the bankers of dantuo bets
+ALTER TABLE spot DROP COLUMN bankers;
@@ -0,0 +1,2 @@
+-- Bankers of dantuo bets, the other reds of the spot are its drags
+ALTER TABLE spot ADD COLUMN bankers TEXT;
@@ -0,0 +1,2 @@
+DROP TABLE purchase_spots;
+DROP TABLE purchases;
@@ -0,0 +1,17 @@
+-- Tickets actually bought at a store, with what they cost
+CREATE TABLE purchases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    purchase_date DATE NOT NULL,
+    store TEXT,
+    -- Yuan paid, may differ from the price of the spots
+    cost INTEGER NOT NULL,
+    note TEXT,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Spots bought with each purchase
+CREATE TABLE purchase_spots (
+    purchase_id INTEGER NOT NULL REFERENCES purchases(id) ON DELETE CASCADE,
+    spot_id INTEGER NOT NULL REFERENCES spot(id),
+    PRIMARY KEY (purchase_id, spot_id)
+);
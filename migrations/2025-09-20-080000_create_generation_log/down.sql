@@ -0,0 +1,2 @@
+DROP TABLE generation_log_spots;
+DROP TABLE generation_log;
@@ -0,0 +1,19 @@
+-- Every batch of generated spots, with what the generator did to pick it
+CREATE TABLE generation_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    period TEXT NOT NULL,
+    generator TEXT NOT NULL,
+    -- Decimal u64 seed reproducing the batch, NULL when no single seed drew it
+    seed TEXT,
+    score DOUBLE NOT NULL,
+    attempts INTEGER NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Spots created from each generated batch
+CREATE TABLE generation_log_spots (
+    generation_id INTEGER NOT NULL REFERENCES generation_log(id) ON DELETE CASCADE,
+    spot_id INTEGER NOT NULL REFERENCES spot(id) ON DELETE CASCADE,
+    PRIMARY KEY (generation_id, spot_id)
+);
@@ -0,0 +1 @@
+DROP TABLE spot_deprecations;
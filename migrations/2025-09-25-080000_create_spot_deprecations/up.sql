@@ -0,0 +1,12 @@
+-- Every time a spot was deprecated or restored, by whom and why
+CREATE TABLE spot_deprecations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    spot_id INTEGER NOT NULL REFERENCES spot(id) ON DELETE CASCADE,
+    -- TRUE when the spot was deprecated, FALSE when it was restored
+    deprecated BOOLEAN NOT NULL,
+    actor TEXT NOT NULL,
+    reason TEXT,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX spot_deprecations_spot_id ON spot_deprecations (spot_id);
@@ -0,0 +1,2 @@
+DROP TABLE spot_tags;
+DROP TABLE tags;
@@ -0,0 +1,15 @@
+-- Labels given to spots, e.g. `birthday numbers` or `machine pick`
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Tags carried by each spot
+CREATE TABLE spot_tags (
+    spot_id INTEGER NOT NULL REFERENCES spot(id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (spot_id, tag_id)
+);
+
+CREATE INDEX spot_tags_tag_id ON spot_tags (tag_id);
@@ -0,0 +1 @@
+DROP TABLE draw_details;
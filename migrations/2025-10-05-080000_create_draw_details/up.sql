@@ -0,0 +1,22 @@
+-- Pool, sales and winners of each draw as answered by the lottery provider
+CREATE TABLE draw_details (
+    period TEXT PRIMARY KEY NOT NULL,
+    -- Yuan left in the jackpot pool after the draw
+    pool BIGINT,
+    -- Yuan of tickets sold for the period
+    sales BIGINT,
+    -- Winning bets and yuan paid per bet of each tier, first to sixth
+    prize1_winners INTEGER,
+    prize1_amount INTEGER,
+    prize2_winners INTEGER,
+    prize2_amount INTEGER,
+    prize3_winners INTEGER,
+    prize3_amount INTEGER,
+    prize4_winners INTEGER,
+    prize4_amount INTEGER,
+    prize5_winners INTEGER,
+    prize5_amount INTEGER,
+    prize6_winners INTEGER,
+    prize6_amount INTEGER,
+    fetched_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,5 @@
+DROP INDEX purchases_profile_id;
+DROP INDEX spot_profile_id;
+ALTER TABLE purchases DROP COLUMN profile_id;
+ALTER TABLE spot DROP COLUMN profile_id;
+DROP TABLE profiles;
@@ -0,0 +1,17 @@
+-- People sharing one daemon, each seeing only their own spots and purchases
+CREATE TABLE profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Spots and purchases recorded before profiles belong to the default one
+INSERT INTO profiles (id, name) VALUES (1, 'default');
+
+-- SQLite cannot add a column referencing another table with a default, the
+-- services only ever write ids of existing profiles
+ALTER TABLE spot ADD COLUMN profile_id INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE purchases ADD COLUMN profile_id INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX spot_profile_id ON spot (profile_id);
+CREATE INDEX purchases_profile_id ON purchases (profile_id);
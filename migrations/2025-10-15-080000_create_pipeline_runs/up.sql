@@ -0,0 +1,16 @@
+-- Every run of the pipeline the daemon starts after a draw: update the
+-- latest ticket, settle the unprized spots, generate the next batch
+CREATE TABLE pipeline_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Draw the run followed
+    draw_time TIMESTAMP NOT NULL,
+    started_time TIMESTAMP NOT NULL,
+    finished_time TIMESTAMP NOT NULL,
+    -- Steps that finished, in order, before the run stopped or ended
+    completed_steps INTEGER NOT NULL,
+    -- Attempts made over all steps, retries included
+    attempts INTEGER NOT NULL,
+    -- Name of the step that gave up and its last error, NULL for a successful run
+    failed_step TEXT,
+    error TEXT
+);
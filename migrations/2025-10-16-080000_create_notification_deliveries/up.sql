@@ -0,0 +1,16 @@
+-- Every notification the daemon sent out or gave up on, one row per target
+CREATE TABLE notification_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- How it was sent, e.g. `webhook`
+    channel TEXT NOT NULL,
+    -- Where it was sent, e.g. the webhook URL
+    target TEXT NOT NULL,
+    -- Period whose prizes were announced
+    period TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    -- Attempts made, retries included
+    attempts INTEGER NOT NULL,
+    -- Last error, NULL once delivered
+    error TEXT,
+    sent_time TIMESTAMP NOT NULL
+);
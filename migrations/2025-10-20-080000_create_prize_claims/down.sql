@@ -0,0 +1 @@
+DROP TABLE prize_claims;
@@ -0,0 +1,13 @@
+-- Prizes collected for winning spots, with what was actually paid out
+CREATE TABLE prize_claims (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    spot_id INTEGER NOT NULL UNIQUE REFERENCES spot(id),
+    claim_date DATE NOT NULL,
+    -- Yuan paid out, the floating first and second tiers after tax
+    amount INTEGER NOT NULL,
+    note TEXT,
+    created_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    profile_id INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE INDEX prize_claims_profile_id ON prize_claims (profile_id);
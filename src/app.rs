@@ -1,3 +1,5 @@
+mod heat;
+mod i18n;
 #[cfg(feature = "terminal")]
 pub mod terminal;
 
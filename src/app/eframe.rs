@@ -1,106 +1,291 @@
-/// We derive Deserialize/Serialize so we can persist app state on shutdown.
+mod daemon;
+mod dashboard;
+#[cfg(not(target_arch = "wasm32"))]
+mod fonts;
+mod history;
+#[cfg(not(target_arch = "wasm32"))]
+mod notification;
+mod picker;
+mod preferences;
+#[cfg(not(target_arch = "wasm32"))]
+mod reminder;
+mod roi;
+mod stats;
+mod strings;
+#[cfg(not(target_arch = "wasm32"))]
+mod tray;
 
-#[derive(serde::Deserialize, serde::Serialize)]
-#[serde(default)] // if we add new fields, give them default values when deserializing old state
-pub struct TemplateApp {
-    // Example stuff:
-    label: String,
+use std::time::Duration;
 
-    #[serde(skip)] // This how you opt-out of serialization of a field
-    value: f32,
+use super::i18n;
+use daemon::{Command, Connection, DaemonLink};
+use history::HistoryPanel;
+use picker::NumberPicker;
+use preferences::{Applied, Preferences, PreferencesDialog};
+#[cfg(not(target_arch = "wasm32"))]
+use reminder::DrawReminder;
+use roi::RoiPanel;
+use stats::StatsPanel;
+use strings::Msg;
+#[cfg(not(target_arch = "wasm32"))]
+use tray::{Tray, TrayAction};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum View {
+    Dashboard,
+    Pick,
+    Stats,
+    Roi,
+    History,
+}
+
+/// Desktop dashboard, talks to the daemon over its Unix socket or HTTP API
+pub struct DballApp {
+    /// `Err` when the runtime driving the connection could not start
+    daemon: Result<DaemonLink, String>,
+    preferences: Preferences,
+    preferences_dialog: PreferencesDialog,
+    /// UI time of the last periodic refresh, in seconds
+    last_refresh: f64,
+    view: View,
+    picker: NumberPicker,
+    stats: StatsPanel,
+    roi: RoiPanel,
+    history: HistoryPanel,
+    #[cfg(not(target_arch = "wasm32"))]
+    desktop: Desktop,
 }
 
-impl Default for TemplateApp {
-    fn default() -> Self {
+/// Tray icon and draw reminder of the native app
+#[cfg(not(target_arch = "wasm32"))]
+struct Desktop {
+    /// `None` when the desktop has no tray to add the icon to
+    tray: Option<Tray>,
+    reminder: DrawReminder,
+}
+
+impl DballApp {
+    /// Called once before the first frame.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let preferences: Preferences = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, preferences::STORAGE_KEY))
+            .unwrap_or_default();
+        cc.egui_ctx.set_theme(preferences.theme);
+        #[cfg(not(target_arch = "wasm32"))]
+        fonts::install_cjk_fallback(&cc.egui_ctx);
+        let daemon = DaemonLink::start(cc.egui_ctx.clone(), &preferences).map_err(|e| {
+            log::error!("Failed to start daemon connection: {e}");
+            e.to_string()
+        });
         Self {
-            // Example stuff:
-            label: "Hello World!".to_owned(),
-            value: 2.7,
+            daemon,
+            preferences,
+            preferences_dialog: PreferencesDialog::default(),
+            last_refresh: 0.0,
+            view: View::Dashboard,
+            picker: NumberPicker::default(),
+            stats: StatsPanel::default(),
+            roi: RoiPanel::default(),
+            history: HistoryPanel::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            desktop: Desktop {
+                tray: Tray::new(&cc.egui_ctx)
+                    .map_err(|e| log::warn!("Failed to add tray icon: {e}"))
+                    .ok(),
+                reminder: DrawReminder::default(),
+            },
         }
     }
 }
 
-impl TemplateApp {
-    /// Called once before the first frame.
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // This is also where you can customize the look and feel of egui using
-        // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
-
-        // Load previous app state (if any).
-        // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
-        } else {
-            Default::default()
+impl DballApp {
+    /// Apply the preferences without a restart, reconnecting only when the connection changed
+    fn apply_preferences(&mut self, ctx: &egui::Context, applied: Applied) {
+        let Applied {
+            preferences,
+            budget,
+            locale,
+        } = applied;
+        ctx.set_theme(preferences.theme);
+        if let Some(locale) = locale {
+            i18n::set_locale(locale);
+        }
+        if let Ok(daemon) = &mut self.daemon {
+            if let Some(budget) = budget {
+                daemon.send(Command::SaveBudget(budget));
+            }
+            if let Some(locale) = locale {
+                // keeps the loaded config from switching back until the save is done
+                if let Some(Ok(config)) = &mut daemon.snapshot().config {
+                    config.ui.locale = locale;
+                }
+                daemon.send(Command::SaveLocale(locale));
+            }
+            if preferences.connection_differs(&self.preferences) {
+                log::info!(
+                    "Reconnecting over {:?} with {}",
+                    preferences.transport,
+                    preferences.http_url
+                );
+                daemon.reconnect(&preferences);
+                self.stats.reload();
+                self.roi.reload();
+                self.history.reload();
+            }
+        }
+        self.preferences = preferences;
+    }
+
+    /// Whether the refresh interval passed since the last periodic refresh
+    fn refresh_due(&mut self, ctx: &egui::Context) -> bool {
+        let now = ctx.input(|input| input.time);
+        if now - self.last_refresh < self.preferences.refresh_secs as f64 {
+            return false;
+        }
+        self.last_refresh = now;
+        true
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Desktop {
+    /// Keep the tray and the draw reminder up to date, notify of new alerts
+    /// and run what was picked from the tray, returns whether the dashboard was asked for
+    fn update(
+        &mut self,
+        ctx: &egui::Context,
+        daemon: &DaemonLink,
+        preferences: &Preferences,
+    ) -> bool {
+        {
+            let mut snapshot = daemon.snapshot();
+            for alert in std::mem::take(&mut snapshot.alerts) {
+                if preferences.notifications {
+                    notification::notify(&alert.summary, &alert.body);
+                }
+            }
+            if let Some(state) = &snapshot.state
+                && preferences.draw_reminder
+            {
+                self.reminder.check(state, chrono::Utc::now());
+            }
+            if let Some(tray) = &mut self.tray {
+                tray.show_countdown(snapshot.state.as_ref());
+            }
+        }
+
+        let Some(tray) = &self.tray else {
+            return false;
+        };
+        let mut open_dashboard = false;
+        for action in tray.actions() {
+            match action {
+                TrayAction::OpenDashboard => {
+                    open_dashboard = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                }
+                TrayAction::Generate => daemon.send(Command::Generate),
+                TrayAction::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            }
         }
+        open_dashboard
     }
 }
 
-impl eframe::App for TemplateApp {
-    /// Called by the framework to save state before shutdown.
+impl eframe::App for DballApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+        eframe::set_value(storage, preferences::STORAGE_KEY, &self.preferences);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
-        // For inspiration and more examples, go to https://emilk.github.io/egui
+        // keeps the draw countdown ticking
+        ctx.request_repaint_after(Duration::from_secs(1));
 
-        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            // The top panel is often a good place for a menu bar:
+        let applied = {
+            let snapshot = self.daemon.as_ref().ok().map(DaemonLink::snapshot);
+            let config = snapshot
+                .as_ref()
+                .and_then(|snapshot| snapshot.config.as_ref());
+            if let Some(Ok(config)) = config {
+                i18n::set_locale(config.ui.locale);
+            }
+            self.preferences_dialog.show(ctx, config)
+        };
+        if let Some(applied) = applied {
+            self.apply_preferences(ctx, applied);
+        }
 
+        let refresh_due = self.refresh_due(ctx);
+        let daemon = match &self.daemon {
+            Ok(daemon) => daemon,
+            Err(e) => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.colored_label(egui::Color32::RED, Msg::FailedToStart.fill(&[("error", e)]));
+                });
+                return;
+            }
+        };
+        // reload the spots while idle, the daemon state is pushed or polled by the worker
+        if refresh_due {
+            let idle = {
+                let snapshot = daemon.snapshot();
+                snapshot.running.is_none()
+                    && matches!(snapshot.connection, Connection::Connected(_))
+            };
+            if idle {
+                daemon.send(Command::Refresh);
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.desktop.update(ctx, daemon, &self.preferences) {
+            self.view = View::Dashboard;
+        }
+        // the browser raises no notifications, so alerts are dropped
+        #[cfg(target_arch = "wasm32")]
+        daemon.snapshot().alerts.clear();
+
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
-                ui.menu_button("File", |ui| {
-                    if ui.button("Quit").clicked() {
+                ui.menu_button(Msg::MenuFile.text(), |ui| {
+                    if ui.button(Msg::MenuPreferences.text()).clicked() {
+                        self.preferences_dialog.open(&self.preferences);
+                        daemon.send(Command::LoadConfig);
+                    }
+                    if ui.button(Msg::MenuQuit.text()).clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                 });
                 ui.add_space(16.0);
-
-                egui::widgets::global_theme_preference_buttons(ui);
+                for (view, label) in [
+                    (View::Dashboard, Msg::ViewDashboard),
+                    (View::Pick, Msg::ViewPick),
+                    (View::Stats, Msg::ViewStats),
+                    (View::Roi, Msg::ViewRoi),
+                    (View::History, Msg::ViewHistory),
+                ] {
+                    ui.selectable_value(&mut self.view, view, label.text());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    dashboard::connection_label(ui, &daemon.snapshot().connection);
+                });
             });
         });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // The central panel the region left after adding TopPanel's and SidePanel's
-            ui.heading("eframe template");
-
-            ui.horizontal(|ui| {
-                ui.label("Write something: ");
-                ui.text_edit_singleline(&mut self.label);
-            });
-
-            ui.add(egui::Slider::new(&mut self.value, 0.0..=10.0).text("value"));
-            if ui.button("Increment").clicked() {
-                self.value += 1.0;
-            }
-
-            ui.separator();
-
-            ui.add(egui::github_link_file!(
-                "https://github.com/emilk/eframe_template/blob/main/",
-                "Source code."
-            ));
-
-            ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
-                powered_by_egui_and_eframe(ui);
-                egui::warn_if_debug_build(ui);
-            });
-        });
+        let command = egui::CentralPanel::default()
+            .show(ctx, |ui| match self.view {
+                View::Dashboard => dashboard::show(ui, &daemon.snapshot()),
+                View::Pick => self.picker.show(ui, &daemon.snapshot()),
+                View::Stats => self.stats.show(ui, &daemon.snapshot()),
+                View::Roi => self.roi.show(ui, &daemon.snapshot()),
+                View::History => self.history.show(ui, &daemon.snapshot()),
+            })
+            .inner;
+        if let Some(command) = command {
+            daemon.send(command);
+        }
     }
 }
-
-fn powered_by_egui_and_eframe(ui: &mut egui::Ui) {
-    ui.horizontal(|ui| {
-        ui.spacing_mut().item_spacing.x = 0.0;
-        ui.label("Powered by ");
-        ui.hyperlink_to("egui", "https://github.com/emilk/egui");
-        ui.label(" and ");
-        ui.hyperlink_to(
-            "eframe",
-            "https://github.com/emilk/egui/tree/master/crates/eframe",
-        );
-        ui.label(".");
-    });
-}
@@ -0,0 +1,636 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+#[cfg(target_arch = "wasm32")]
+use dball_client::ipc::client::HttpTransport;
+#[cfg(not(target_arch = "wasm32"))]
+use dball_client::ipc::client::{DaemonTransport, StateSubscriber};
+use dball_client::{
+    config::{BudgetConfig, ConfigIssue, DballConfig, Locale},
+    ipc::{AppState, RpcError, RpcErrorCode, RpcService, client::RpcTransport},
+    models::{ExportRequest, ManualSpot, Page, PageRequest, Spot, SpotQuery, Ticket},
+    service::{NumberFrequencies, RoiReport},
+};
+use dball_combora::dball::Reward;
+use futures_util::future::{AbortHandle, abortable};
+use tokio::sync::mpsc;
+
+use super::preferences::Preferences;
+use super::strings::Msg;
+
+/// Delay between attempts to reach the daemon
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Request of the dashboard, handled one at a time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Command {
+    Refresh,
+    Generate,
+    Settle,
+    Deprecate,
+    /// Save numbers picked by hand
+    AddManualSpot(ManualSpot),
+    /// Number statistics over the given lookback window
+    LoadStats(usize),
+    LoadRoi,
+    /// The daemon's `dball.toml`, for the budget and language in the preferences
+    LoadConfig,
+    /// Replace the budget in the daemon's `dball.toml`
+    SaveBudget(BudgetConfig),
+    /// Replace the UI language in the daemon's `dball.toml`
+    SaveLocale(Locale),
+    /// Drawn tickets and all spots, of the periods starting with the prefix if given
+    LoadHistory(Option<String>),
+    /// Save the exported CSV into the working directory
+    ExportCsv(ExportRequest),
+}
+
+impl Command {
+    fn msg(&self) -> Msg {
+        match self {
+            Self::Refresh => Msg::Refreshing,
+            Self::Generate => Msg::GeneratingSpots,
+            Self::Settle => Msg::SettlingSpots,
+            Self::Deprecate => Msg::DeprecatingBatch,
+            Self::AddManualSpot(_) => Msg::SavingManualSpot,
+            Self::LoadStats(_) => Msg::LoadingStats,
+            Self::LoadRoi => Msg::LoadingRoi,
+            Self::LoadConfig => Msg::LoadingConfig,
+            Self::SaveBudget(_) => Msg::SavingBudget,
+            Self::SaveLocale(_) => Msg::SavingLanguage,
+            Self::LoadHistory(_) => Msg::LoadingHistory,
+            Self::ExportCsv(_) => Msg::ExportingCsv,
+        }
+    }
+
+    /// What is being done, in the current language
+    pub(crate) fn label(&self) -> &'static str {
+        self.msg().text()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) enum Connection {
+    #[default]
+    Connecting,
+    /// Connected over the described transport
+    Connected(String),
+    Failed(String),
+}
+
+/// Event worth an OS notification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Alert {
+    pub(crate) summary: String,
+    pub(crate) body: String,
+}
+
+impl Alert {
+    /// Alert of a settled spot that won one of the top four tiers
+    fn big_prize(spot: &Spot) -> Option<Self> {
+        let reward = spot.reward_level().ok().flatten()?;
+        if !matches!(
+            reward,
+            Reward::FirstPrize | Reward::SecondPrize | Reward::ThirdPrize | Reward::FourthPrize
+        ) {
+            return None;
+        }
+        let join = |numbers: Vec<i32>| {
+            numbers
+                .iter()
+                .map(|number| format!("{number:02}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let numbers = format!(
+            "{} + {}",
+            join(spot.red_numbers()),
+            join(spot.blue_numbers())
+        );
+        Some(Self {
+            summary: Msg::PrizeWonSummary.fill(&[("tier", &reward.description())]),
+            body: Msg::PrizeWonBody.fill(&[
+                ("period", &spot.period),
+                ("numbers", &numbers),
+                ("times", &spot.magnification),
+                ("amount", &reward.prize_amount()),
+            ]),
+        })
+    }
+}
+
+/// Drawn tickets with the spots of the same periods
+pub(crate) struct History {
+    pub(crate) tickets: Vec<Ticket>,
+    pub(crate) spots: Vec<Spot>,
+}
+
+/// What the dashboard draws, written by the worker and read every frame
+#[derive(Default)]
+pub(crate) struct Snapshot {
+    pub(crate) connection: Connection,
+    /// Latest state pushed by the daemon
+    pub(crate) state: Option<AppState>,
+    pub(crate) spots: Option<Result<Vec<Spot>, RpcError>>,
+    pub(crate) stats: Option<Result<NumberFrequencies, RpcError>>,
+    pub(crate) roi: Option<Result<RoiReport, RpcError>>,
+    pub(crate) config: Option<Result<DballConfig, RpcError>>,
+    pub(crate) history: Option<Result<History, RpcError>>,
+    /// File the last export was saved to
+    pub(crate) export: Option<Result<String, RpcError>>,
+    /// Command being handled
+    pub(crate) running: Option<Command>,
+    /// Outcome of the last command, `Err` when it failed
+    pub(crate) outcome: Option<Result<String, RpcError>>,
+    /// Alerts not notified of yet, taken by the app every frame
+    pub(crate) alerts: Vec<Alert>,
+}
+
+/// Natively the daemon is reached over its socket or HTTP, in the browser only over HTTP
+#[cfg(not(target_arch = "wasm32"))]
+type Transport = DaemonTransport;
+#[cfg(target_arch = "wasm32")]
+type Transport = HttpTransport;
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Connection to the daemon, driven by a tokio runtime next to the UI thread
+/// or by the browser's event loop on the web
+pub(crate) struct DaemonLink {
+    #[cfg(not(target_arch = "wasm32"))]
+    runtime: tokio::runtime::Runtime,
+    ctx: egui::Context,
+    worker: AbortHandle,
+    commands: mpsc::UnboundedSender<Command>,
+    snapshot: Arc<Mutex<Snapshot>>,
+}
+
+impl DaemonLink {
+    /// Start connecting to the daemon, `ctx` is repainted whenever the snapshot changes
+    #[cfg_attr(
+        target_arch = "wasm32",
+        expect(
+            clippy::unnecessary_wraps,
+            reason = "only the native runtime can fail to start"
+        )
+    )]
+    pub(crate) fn start(ctx: egui::Context, preferences: &Preferences) -> anyhow::Result<Self> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let mut link = Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            runtime: tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .enable_all()
+                .build()?,
+            ctx,
+            worker: AbortHandle::new_pair().0,
+            commands,
+            snapshot: Arc::new(Mutex::new(Snapshot::default())),
+        };
+        link.worker = link.spawn_worker(preferences, receiver);
+        Ok(link)
+    }
+
+    /// Run a worker until it is aborted, the tasks it starts end with it
+    fn spawn_worker(
+        &self,
+        preferences: &Preferences,
+        receiver: mpsc::UnboundedReceiver<Command>,
+    ) -> AbortHandle {
+        let worker = Worker::new(self.ctx.clone(), self.snapshot.clone());
+        let (worker, handle) = abortable(worker.run(preferences.clone(), receiver));
+        #[cfg(not(target_arch = "wasm32"))]
+        self.runtime.spawn(worker);
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async {
+            // `Err` only tells that it was aborted
+            let _aborted = worker.await;
+        });
+        handle
+    }
+
+    /// Drop the current connection and connect again with `preferences`
+    ///
+    /// Everything loaded over the old connection is cleared, as it may be another daemon
+    pub(crate) fn reconnect(&mut self, preferences: &Preferences) {
+        self.worker.abort();
+        *self.snapshot() = Snapshot::default();
+        let (commands, receiver) = mpsc::unbounded_channel();
+        self.commands = commands;
+        self.worker = self.spawn_worker(preferences, receiver);
+    }
+
+    pub(crate) fn send(&self, command: Command) {
+        if let Err(mpsc::error::SendError(command)) = self.commands.send(command) {
+            log::error!("Daemon worker stopped, dropping {command:?}");
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> MutexGuard<'_, Snapshot> {
+        self.snapshot.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct Worker {
+    ctx: egui::Context,
+    snapshot: Arc<Mutex<Snapshot>>,
+}
+
+impl Worker {
+    fn new(ctx: egui::Context, snapshot: Arc<Mutex<Snapshot>>) -> Self {
+        Self { ctx, snapshot }
+    }
+
+    fn update(&self, update: impl FnOnce(&mut Snapshot)) {
+        update(&mut self.snapshot.lock().unwrap_or_else(PoisonError::into_inner));
+        self.ctx.request_repaint();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn try_connect(preferences: &Preferences) -> anyhow::Result<(Transport, String)> {
+        let http = preferences.http_transport();
+        let transport = DaemonTransport::connect(preferences.transport, http).await?;
+        let description = match &transport {
+            DaemonTransport::Ipc(_) => "Unix socket".to_owned(),
+            DaemonTransport::Http(http) => http.base_url().to_owned(),
+        };
+        Ok((transport, description))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn try_connect(preferences: &Preferences) -> anyhow::Result<(Transport, String)> {
+        let http = preferences.http_transport();
+        http.check_health().await?;
+        let description = http.base_url().to_owned();
+        Ok((http, description))
+    }
+
+    async fn connect(&self, preferences: &Preferences) -> Transport {
+        loop {
+            match Self::try_connect(preferences).await {
+                Ok((transport, description)) => {
+                    self.update(|snapshot| {
+                        snapshot.connection = Connection::Connected(description);
+                    });
+                    return transport;
+                }
+                Err(e) => {
+                    log::warn!("Failed to connect to daemon: {e}");
+                    self.update(|snapshot| snapshot.connection = Connection::Failed(e.to_string()));
+                    sleep(RECONNECT_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    fn set_state(&self, state: Option<AppState>) {
+        self.update(|snapshot| snapshot.state = state);
+    }
+
+    /// Poll the daemon state every `interval`, for transports that can't push it
+    #[expect(clippy::infinite_loop, reason = "polls until the worker is aborted")]
+    async fn poll_state(&self, transport: &impl RpcTransport, interval: Duration) {
+        loop {
+            let state = transport
+                .send_rpc(RpcService::GetCurrentState)
+                .await
+                .and_then(|value| {
+                    serde_json::from_value::<AppState>(value).map_err(anyhow::Error::from)
+                });
+            match state {
+                Ok(state) => self.set_state(Some(state)),
+                Err(e) => log::warn!("Failed to poll daemon state: {e}"),
+            }
+            sleep(interval).await;
+        }
+    }
+
+    /// Mirror the daemon state into the snapshot, pushed over the socket or polled over HTTP
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn follow_state(&self, transport: &Transport, interval: Duration) {
+        let client = match transport {
+            DaemonTransport::Ipc(client) => client,
+            DaemonTransport::Http(http) => return self.poll_state(http, interval).await,
+        };
+        let subscriber = StateSubscriber::new();
+        if let Err(e) = subscriber.start_subscription(client).await {
+            log::error!("Failed to subscribe to daemon state: {e}");
+            return;
+        }
+        // the subscription ends when the subscriber is dropped with the worker
+        let mut changes = subscriber.subscribe_to_changes();
+        while changes.changed().await.is_ok() {
+            let state = changes.borrow_and_update().clone();
+            self.set_state(state);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn follow_state(&self, transport: &Transport, interval: Duration) {
+        self.poll_state(transport, interval).await;
+    }
+
+    async fn run(self, preferences: Preferences, mut commands: mpsc::UnboundedReceiver<Command>) {
+        let transport = self.connect(&preferences).await;
+        let handle_commands = async {
+            // the config carries the UI language
+            self.handle(&transport, Command::LoadConfig).await;
+            self.handle(&transport, Command::Refresh).await;
+            while let Some(command) = commands.recv().await {
+                self.handle(&transport, command).await;
+            }
+        };
+        futures_util::join!(
+            self.follow_state(&transport, preferences.refresh_interval()),
+            handle_commands
+        );
+    }
+
+    /// Save an edit of the daemon's config, `done` is the outcome shown once saved
+    async fn save_config(
+        &self,
+        client: &Transport,
+        edit: impl FnOnce(&mut DballConfig),
+        done: Msg,
+    ) {
+        let saved = save_config(client, edit).await;
+        if let Err(e) = &saved {
+            log::error!("Saving config failed: {e}");
+        }
+        self.update(|snapshot| {
+            snapshot.running = None;
+            match saved {
+                Ok(config) => {
+                    snapshot.config = Some(Ok(config));
+                    snapshot.outcome = Some(Ok(done.text().to_owned()));
+                }
+                Err(e) => snapshot.outcome = Some(Err(e)),
+            }
+        });
+    }
+
+    async fn load_stats(&self, client: &Transport, window: usize) {
+        let stats =
+            request::<NumberFrequencies>(client, RpcService::GetNumberFrequencies(Some(window)))
+                .await;
+        self.update(|snapshot| {
+            snapshot.running = None;
+            snapshot.stats = Some(stats);
+        });
+    }
+
+    async fn load_roi(&self, client: &Transport) {
+        let roi = request::<RoiReport>(client, RpcService::GetRoiReport).await;
+        self.update(|snapshot| {
+            snapshot.running = None;
+            snapshot.roi = Some(roi);
+        });
+    }
+
+    async fn load_history(&self, client: &Transport, period_prefix: Option<String>) {
+        let history = load_history(client, period_prefix).await;
+        self.update(|snapshot| {
+            snapshot.running = None;
+            snapshot.history = Some(history);
+        });
+    }
+
+    async fn export_csv(&self, client: &Transport, export: ExportRequest) {
+        let file = format!(
+            "dball-{}-{}.csv",
+            export.table.name(),
+            export.period_prefix.as_deref().unwrap_or("all")
+        );
+        let saved = export_csv(client, export, &file).await;
+        if let Err(e) = &saved {
+            log::error!("Exporting CSV failed: {e}");
+        }
+        self.update(|snapshot| {
+            snapshot.running = None;
+            snapshot.export = Some(saved.map(|()| file));
+        });
+    }
+
+    async fn handle(&self, client: &Transport, command: Command) {
+        log::info!("{}...", command.msg().text_in(Locale::En));
+        self.update(|snapshot| snapshot.running = Some(command.clone()));
+
+        match command {
+            Command::LoadStats(window) => {
+                self.load_stats(client, window).await;
+                return;
+            }
+            Command::LoadRoi => {
+                self.load_roi(client).await;
+                return;
+            }
+            Command::LoadConfig => {
+                let config = request::<DballConfig>(client, RpcService::GetConfig).await;
+                self.update(|snapshot| {
+                    snapshot.running = None;
+                    snapshot.config = Some(config);
+                });
+                return;
+            }
+            Command::SaveBudget(budget) => {
+                self.save_config(client, |config| config.budget = budget, Msg::SavedBudget)
+                    .await;
+                return;
+            }
+            Command::SaveLocale(locale) => {
+                self.save_config(
+                    client,
+                    |config| config.ui.locale = locale,
+                    Msg::SavedLanguage,
+                )
+                .await;
+                return;
+            }
+            Command::LoadHistory(period_prefix) => {
+                self.load_history(client, period_prefix).await;
+                return;
+            }
+            Command::ExportCsv(export) => {
+                self.export_csv(client, export).await;
+                return;
+            }
+            Command::Refresh
+            | Command::Generate
+            | Command::Settle
+            | Command::Deprecate
+            | Command::AddManualSpot(_) => {}
+        }
+
+        let mut alerts = Vec::new();
+        let outcome = match command {
+            Command::Refresh
+            | Command::LoadStats(_)
+            | Command::LoadRoi
+            | Command::LoadConfig
+            | Command::SaveBudget(_)
+            | Command::SaveLocale(_)
+            | Command::LoadHistory(_)
+            | Command::ExportCsv(_) => Ok(None),
+            Command::Generate => request::<()>(client, RpcService::GenerateBatchSpots(None))
+                .await
+                .map(|()| Some(Msg::Generated.text().to_owned())),
+            Command::Settle => request::<Vec<Spot>>(client, RpcService::UpdateAllUnprizeSpots)
+                .await
+                .map(|spots| {
+                    alerts.extend(spots.iter().filter_map(Alert::big_prize));
+                    Some(Msg::Settled.fill(&[("count", &spots.len())]))
+                }),
+            Command::AddManualSpot(manual) => {
+                request::<String>(client, RpcService::AddManualSpot(manual))
+                    .await
+                    .map(|period| Some(Msg::SavedManualSpot.fill(&[("period", &period)])))
+            }
+            Command::Deprecate => {
+                request::<usize>(client, RpcService::DeprecatedLastBatchUnprizedSpot)
+                    .await
+                    .map(|count| Some(Msg::Deprecated.fill(&[("count", &count)])))
+            }
+        };
+        if let Err(e) = &outcome {
+            log::error!("{} failed: {e}", command.msg().text_in(Locale::En));
+            if command == Command::Settle {
+                alerts.push(Alert {
+                    summary: Msg::SettlementFailed.text().to_owned(),
+                    body: e.to_string(),
+                });
+            }
+        }
+
+        let spots = request::<Vec<Spot>>(client, RpcService::GetUnprizeSpots).await;
+        self.update(|snapshot| {
+            snapshot.running = None;
+            snapshot.spots = Some(spots);
+            snapshot.alerts.extend(alerts);
+            match outcome {
+                Ok(Some(message)) => snapshot.outcome = Some(Ok(message)),
+                Ok(None) => {}
+                Err(e) => snapshot.outcome = Some(Err(e)),
+            }
+        });
+    }
+}
+
+/// Apply `edit` to the daemon's current config, returns the saved config
+async fn save_config(
+    client: &impl RpcTransport,
+    edit: impl FnOnce(&mut DballConfig),
+) -> Result<DballConfig, RpcError> {
+    let mut config = request::<DballConfig>(client, RpcService::GetConfig).await?;
+    edit(&mut config);
+    let issues =
+        request::<Vec<ConfigIssue>>(client, RpcService::UpdateConfig(Box::new(config.clone())))
+            .await?;
+    if !issues.is_empty() {
+        let message = issues
+            .iter()
+            .map(|issue| format!("{}: {}", issue.field, issue.message))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(RpcError::new(RpcErrorCode::InvalidRequest, message));
+    }
+    Ok(config)
+}
+
+/// Tickets and spots of the periods starting with `period_prefix`, or of all periods
+async fn load_history(
+    client: &impl RpcTransport,
+    period_prefix: Option<String>,
+) -> Result<History, RpcError> {
+    let tickets = request::<Page<Ticket>>(
+        client,
+        RpcService::GetTickets(period_prefix.clone(), PageRequest::default()),
+    )
+    .await?
+    .items;
+    let query = SpotQuery {
+        period_prefix,
+        ..SpotQuery::default()
+    };
+    let spots = request::<Vec<Spot>>(client, RpcService::QuerySpots(query)).await?;
+    Ok(History { tickets, spots })
+}
+
+/// Export a table and write it to `file`
+#[cfg(not(target_arch = "wasm32"))]
+async fn export_csv(
+    client: &impl RpcTransport,
+    export: ExportRequest,
+    file: &str,
+) -> Result<(), RpcError> {
+    let csv = request::<String>(client, RpcService::ExportCsv(export)).await?;
+    tokio::fs::write(file, csv).await.map_err(|e| {
+        RpcError::new(
+            RpcErrorCode::Internal,
+            format!("Failed to write {file}: {e}"),
+        )
+    })
+}
+
+/// Export a table and hand it to the browser as a download named `file`
+#[cfg(target_arch = "wasm32")]
+async fn export_csv(
+    client: &impl RpcTransport,
+    export: ExportRequest,
+    file: &str,
+) -> Result<(), RpcError> {
+    let csv = request::<String>(client, RpcService::ExportCsv(export)).await?;
+    download(file, &csv).map_err(|e| {
+        RpcError::new(
+            RpcErrorCode::Internal,
+            format!("Failed to download {file}: {e:?}"),
+        )
+    })
+}
+
+/// Let the browser save `contents` as `file`, through a temporary link to a blob
+#[cfg(target_arch = "wasm32")]
+fn download(file: &str, contents: &str) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast as _;
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/csv");
+    let parts = js_sys::Array::of1(&contents.into());
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let link = document
+        .create_element("a")?
+        .dyn_into::<web_sys::HtmlAnchorElement>()?;
+    link.set_href(&url);
+    link.set_download(file);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Send a request answered with a `Result`, flattening transport and service errors
+async fn request<T>(client: &impl RpcTransport, service: RpcService) -> Result<T, RpcError>
+where
+    for<'de> T: serde::Deserialize<'de>,
+{
+    let response = client.send_rpc(service).await.map_err(|e| {
+        e.downcast_ref::<RpcError>()
+            .cloned()
+            .unwrap_or_else(|| RpcError::new(RpcErrorCode::Transport, format!("{e:#}")))
+    })?;
+    serde_json::from_value::<Result<T, RpcError>>(response).map_err(|e| {
+        RpcError::new(
+            RpcErrorCode::Internal,
+            format!("Unexpected response from the daemon: {e}"),
+        )
+    })?
+}
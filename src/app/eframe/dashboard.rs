@@ -0,0 +1,211 @@
+use chrono::{TimeDelta, Utc};
+use dball_client::{ipc::AppState, models::Spot};
+use egui::{Color32, RichText};
+
+use super::daemon::{Command, Connection, Snapshot};
+use super::strings::Msg;
+use crate::app::i18n;
+
+pub(super) const RED_BALL: Color32 = Color32::from_rgb(0xd9, 0x3a, 0x3a);
+pub(super) const BLUE_BALL: Color32 = Color32::from_rgb(0x2f, 0x6f, 0xd6);
+
+pub(super) fn format_countdown(remaining: TimeDelta) -> String {
+    let secs = remaining.num_seconds();
+    if secs <= 0 {
+        return Msg::DrawingNow.text().to_owned();
+    }
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    );
+    if days > 0 {
+        Msg::CountdownDays.fill(&[("days", &days), ("clock", &clock)])
+    } else {
+        clock
+    }
+}
+
+pub(super) fn balls(ui: &mut egui::Ui, reds: &[i32], blues: &[i32]) {
+    ui.horizontal(|ui| {
+        for red in reds {
+            ui.label(RichText::new(format!("{red:02}")).strong().color(RED_BALL));
+        }
+        for blue in blues {
+            ui.label(
+                RichText::new(format!("{blue:02}"))
+                    .strong()
+                    .color(BLUE_BALL),
+            );
+        }
+    });
+}
+
+pub(super) fn connection_label(ui: &mut egui::Ui, connection: &Connection) {
+    let (text, color) = match connection {
+        Connection::Connecting => (Msg::Connecting.text().to_owned(), Color32::YELLOW),
+        Connection::Connected(over) => (
+            Msg::ConnectedOver.fill(&[("transport", over)]),
+            Color32::GREEN,
+        ),
+        Connection::Failed(e) => (Msg::Unreachable.fill(&[("error", e)]), Color32::RED),
+    };
+    ui.label(RichText::new(text).color(color));
+}
+
+fn overview(ui: &mut egui::Ui, state: &AppState) {
+    egui::Grid::new("overview")
+        .num_columns(2)
+        .spacing([24.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(Msg::CurrentPeriod.text());
+            ui.label(RichText::new(&state.current_period).strong());
+            ui.end_row();
+
+            ui.label(Msg::NextPeriod.text());
+            ui.label(RichText::new(&state.next_period).strong());
+            ui.end_row();
+
+            ui.label(Msg::DrawIn.text());
+            let countdown = state.next_draw_time.map_or_else(
+                || "-".to_owned(),
+                |time| format_countdown(time - Utc::now()),
+            );
+            ui.label(RichText::new(countdown).strong().color(Color32::YELLOW));
+            ui.end_row();
+
+            ui.label(Msg::LastResult.text());
+            match &state.latest_ticket {
+                Some(ticket) => {
+                    let reds = ticket.rball.map(|red| red as i32);
+                    balls(ui, &reds, &[i32::from(ticket.bball)]);
+                }
+                None => {
+                    ui.label("-");
+                }
+            }
+            ui.end_row();
+
+            ui.label(Msg::UnprizedSpots.text());
+            ui.label(state.unprize_spots_count.to_string());
+            ui.end_row();
+
+            let net = state.total_return - state.total_investment;
+            ui.label(Msg::InvestedReturned.text());
+            ui.label(format!(
+                "¥{:.0} / ¥{:.0} ({net:+.0})",
+                state.total_investment, state.total_return
+            ));
+            ui.end_row();
+
+            ui.label(Msg::Claimed.text());
+            ui.label(format!("¥{:.0}", state.claimed_return));
+            ui.end_row();
+        });
+}
+
+/// Action buttons, disabled while a command runs or the daemon is unreachable
+fn actions(ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+    let enabled =
+        snapshot.running.is_none() && matches!(snapshot.connection, Connection::Connected(_));
+    let mut clicked = None;
+    ui.horizontal(|ui| {
+        for (command, text) in [
+            (Command::Generate, Msg::Generate),
+            (Command::Settle, Msg::Settle),
+            (Command::Deprecate, Msg::DeprecateBatch),
+            (Command::Refresh, Msg::Refresh),
+        ] {
+            if ui
+                .add_enabled(enabled, egui::Button::new(text.text()))
+                .clicked()
+            {
+                clicked = Some(command);
+            }
+        }
+        if let Some(running) = &snapshot.running {
+            ui.spinner();
+            ui.label(running.label());
+        }
+    });
+
+    match &snapshot.outcome {
+        Some(Ok(message)) => {
+            ui.label(RichText::new(message).color(Color32::GREEN));
+        }
+        Some(Err(error)) => {
+            ui.label(
+                RichText::new(format!("{}: {}", error.code.as_str(), error.message))
+                    .color(Color32::RED),
+            );
+            if let Some(hint) = i18n::retry_hint(error.code) {
+                ui.label(RichText::new(hint).weak());
+            }
+        }
+        None => {}
+    }
+    clicked
+}
+
+fn spot_table(ui: &mut egui::Ui, spots: &[Spot]) {
+    if spots.is_empty() {
+        ui.label(Msg::NoUnprizedSpots.text());
+        return;
+    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("unprized_spots")
+            .num_columns(4)
+            .striped(true)
+            .spacing([16.0, 4.0])
+            .show(ui, |ui| {
+                for header in [Msg::Period, Msg::Numbers, Msg::Times, Msg::Created] {
+                    ui.label(RichText::new(header.text()).strong());
+                }
+                ui.end_row();
+
+                for spot in spots {
+                    ui.label(&spot.period);
+                    balls(ui, &spot.red_numbers(), &spot.blue_numbers());
+                    ui.label(format!("x{}", spot.magnification));
+                    ui.label(spot.formatted_created_time());
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+/// Draw the dashboard, returns the command of the clicked button
+pub(super) fn show(ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+    ui.heading(Msg::ViewDashboard.text());
+    ui.add_space(8.0);
+
+    match &snapshot.state {
+        Some(state) => overview(ui, state),
+        None => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(Msg::WaitingForState.text());
+            });
+        }
+    }
+
+    ui.add_space(8.0);
+    let command = actions(ui, snapshot);
+    ui.separator();
+
+    ui.label(RichText::new(Msg::UnprizedSpots.text()).strong());
+    match &snapshot.spots {
+        Some(Ok(spots)) => spot_table(ui, spots),
+        Some(Err(error)) => {
+            ui.label(
+                RichText::new(Msg::FailedToLoadSpots.fill(&[("error", error)])).color(Color32::RED),
+            );
+        }
+        None => {
+            ui.spinner();
+        }
+    }
+    command
+}
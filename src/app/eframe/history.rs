@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use dball_client::models::{ExportRequest, ExportTable, Spot, Ticket};
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+
+use super::daemon::{Command, History, Snapshot};
+use super::dashboard::balls;
+use super::strings::Msg;
+
+const WIN_COLOR: Color32 = Color32::from_rgb(0x3a, 0xa8, 0x5c);
+const ROW_HEIGHT: f32 = 20.0;
+
+/// Column the rows are ordered by
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Period,
+    /// Draw time of tickets, creation time of spots
+    Time,
+    Blue,
+    /// Prize amount of spots, magnification included
+    Prize,
+}
+
+/// Prize amount a settled spot won, 0 when it didn't or isn't settled
+///
+/// `prize_status` holds the total the spot won, every bet of a complex spot and
+/// its magnification included
+fn prize_amount(spot: &Spot) -> u64 {
+    spot.prize_status.unwrap_or(0).max(0) as u64
+}
+
+fn is_winning(spot: &Spot) -> bool {
+    spot.prize_status.is_some_and(|level| level > 0)
+}
+
+/// Drawn tickets and spots, sortable by clicking the column headers
+pub(super) struct HistoryPanel {
+    table: ExportTable,
+    sort: SortKey,
+    descending: bool,
+    /// Period prefix being typed
+    filter: String,
+    /// Period prefix the shown rows were loaded with
+    applied: Option<String>,
+    requested: bool,
+}
+
+impl Default for HistoryPanel {
+    fn default() -> Self {
+        Self {
+            table: ExportTable::Tickets,
+            sort: SortKey::Period,
+            descending: true,
+            filter: String::new(),
+            applied: None,
+            requested: false,
+        }
+    }
+}
+
+impl HistoryPanel {
+    /// Load the history again on the next frame
+    pub(super) fn reload(&mut self) {
+        self.requested = false;
+    }
+
+    fn apply_filter(&mut self) {
+        let prefix = self.filter.trim();
+        self.applied = (!prefix.is_empty()).then(|| prefix.to_owned());
+        self.reload();
+    }
+
+    fn ordering(&self, ordering: Ordering) -> Ordering {
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    fn sorted_tickets<'a>(&self, tickets: &'a [Ticket]) -> Vec<&'a Ticket> {
+        let mut tickets = tickets.iter().collect::<Vec<_>>();
+        tickets.sort_by(|a, b| {
+            self.ordering(match self.sort {
+                SortKey::Period | SortKey::Prize => a.period.cmp(&b.period),
+                SortKey::Time => a.time.cmp(&b.time),
+                SortKey::Blue => a.blue.cmp(&b.blue),
+            })
+        });
+        tickets
+    }
+
+    fn sorted_spots<'a>(&self, spots: &'a [Spot]) -> Vec<&'a Spot> {
+        let mut spots = spots.iter().collect::<Vec<_>>();
+        spots.sort_by(|a, b| {
+            self.ordering(match self.sort {
+                SortKey::Period => a.period.cmp(&b.period),
+                SortKey::Time => a.created_time.cmp(&b.created_time),
+                SortKey::Blue => a.blue.cmp(&b.blue),
+                SortKey::Prize => prize_amount(a).cmp(&prize_amount(b)),
+            })
+        });
+        spots
+    }
+
+    /// Header that sorts by `key` when clicked, clicking it again flips the direction
+    fn sort_header(&mut self, ui: &mut egui::Ui, label: &str, key: SortKey) {
+        let text = match (self.sort == key, self.descending) {
+            (true, true) => format!("{label} ⏷"),
+            (true, false) => format!("{label} ⏶"),
+            (false, _) => label.to_owned(),
+        };
+        if ui
+            .add(egui::Button::new(RichText::new(text).strong()).frame(false))
+            .clicked()
+        {
+            if self.sort == key {
+                self.descending = !self.descending;
+            } else {
+                self.sort = key;
+                self.descending = key != SortKey::Blue;
+            }
+        }
+    }
+
+    fn tickets_table(&mut self, ui: &mut egui::Ui, history: &History) {
+        let winning_periods = history
+            .spots
+            .iter()
+            .filter(|spot| is_winning(spot))
+            .map(|spot| spot.period.as_str())
+            .collect::<HashSet<_>>();
+        let tickets = self.sorted_tickets(&history.tickets);
+        TableBuilder::new(ui)
+            .id_salt("history_tickets")
+            .striped(true)
+            .column(Column::auto().at_least(80.0))
+            .column(Column::auto().at_least(140.0))
+            .column(Column::auto().at_least(200.0))
+            .column(Column::remainder())
+            .header(ROW_HEIGHT, |mut header| {
+                header.col(|ui| self.sort_header(ui, Msg::Period.text(), SortKey::Period));
+                header.col(|ui| self.sort_header(ui, Msg::Drawn.text(), SortKey::Time));
+                header.col(|ui| self.sort_header(ui, Msg::Numbers.text(), SortKey::Blue));
+                header.col(|ui| {
+                    ui.strong(Msg::Won.text());
+                });
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, tickets.len(), |mut row| {
+                    let ticket = tickets[row.index()];
+                    let won = winning_periods.contains(ticket.period.as_str());
+                    row.col(|ui| {
+                        let period = RichText::new(&ticket.period);
+                        ui.label(if won { period.color(WIN_COLOR) } else { period });
+                    });
+                    row.col(|ui| {
+                        ui.label(ticket.formatted_time());
+                    });
+                    row.col(|ui| balls(ui, &ticket.red_numbers(), &[ticket.blue]));
+                    row.col(|ui| {
+                        if won {
+                            ui.label(RichText::new("✔").color(WIN_COLOR));
+                        }
+                    });
+                });
+            });
+    }
+
+    fn spots_table(&mut self, ui: &mut egui::Ui, history: &History) {
+        let spots = self.sorted_spots(&history.spots);
+        TableBuilder::new(ui)
+            .id_salt("history_spots")
+            .striped(true)
+            .column(Column::auto().at_least(80.0))
+            .column(Column::auto().at_least(140.0))
+            .column(Column::auto().at_least(200.0))
+            .column(Column::auto().at_least(40.0))
+            .column(Column::auto().at_least(80.0))
+            .column(Column::remainder())
+            .header(ROW_HEIGHT, |mut header| {
+                header.col(|ui| self.sort_header(ui, Msg::Period.text(), SortKey::Period));
+                header.col(|ui| self.sort_header(ui, Msg::Created.text(), SortKey::Time));
+                header.col(|ui| self.sort_header(ui, Msg::Numbers.text(), SortKey::Blue));
+                header.col(|ui| {
+                    ui.strong("×");
+                });
+                header.col(|ui| self.sort_header(ui, Msg::Prize.text(), SortKey::Prize));
+                header.col(|ui| {
+                    ui.strong(Msg::Note.text());
+                });
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, spots.len(), |mut row| {
+                    let spot = spots[row.index()];
+                    let won = is_winning(spot);
+                    row.col(|ui| {
+                        let period = RichText::new(&spot.period);
+                        ui.label(match (won, spot.deprecated) {
+                            (true, _) => period.color(WIN_COLOR),
+                            (false, true) => period.strikethrough().weak(),
+                            (false, false) => period,
+                        });
+                    });
+                    row.col(|ui| {
+                        ui.label(spot.formatted_created_time());
+                    });
+                    row.col(|ui| balls(ui, &spot.red_numbers(), &spot.blue_numbers()));
+                    row.col(|ui| {
+                        ui.label(spot.magnification.to_string());
+                    });
+                    row.col(|ui| {
+                        // complex spots win several tiers at once, only the total is shown
+                        let tier = spot
+                            .reward_level()
+                            .ok()
+                            .flatten()
+                            .filter(|_| !spot.is_complex());
+                        match (spot.prize_status, tier) {
+                            (Some(_), Some(reward)) if won => ui.label(
+                                RichText::new(format!(
+                                    "{} ¥{}",
+                                    reward.description(),
+                                    prize_amount(spot)
+                                ))
+                                .color(WIN_COLOR),
+                            ),
+                            (Some(_), None) if won => ui.label(
+                                RichText::new(format!("¥{}", prize_amount(spot))).color(WIN_COLOR),
+                            ),
+                            (Some(_), _) => ui.label("—"),
+                            (None, _) => ui.label(RichText::new(Msg::Pending.text()).weak()),
+                        };
+                    });
+                    row.col(|ui| {
+                        ui.label(spot.note.as_deref().unwrap_or_default());
+                    });
+                });
+            });
+    }
+
+    /// Draw the panel, returns the request for the history when shown first or filtered,
+    /// or the export when asked for
+    pub(super) fn show(&mut self, ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+        let mut command = None;
+        ui.horizontal(|ui| {
+            ui.heading(Msg::HistoryTitle.text());
+            ui.add_space(16.0);
+            ui.selectable_value(&mut self.table, ExportTable::Tickets, Msg::Tickets.text());
+            ui.selectable_value(&mut self.table, ExportTable::Spots, Msg::Spots.text());
+            ui.add_space(16.0);
+            ui.label(Msg::Period.text());
+            let filter = ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text(Msg::PeriodHint.text())
+                    .desired_width(80.0),
+            );
+            let entered =
+                filter.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+            if ui.button(Msg::Apply.text()).clicked() || entered {
+                self.apply_filter();
+            }
+            ui.add_enabled_ui(snapshot.running.is_none(), |ui| {
+                if ui.button(Msg::ExportCsv.text()).clicked() {
+                    command = Some(Command::ExportCsv(ExportRequest {
+                        table: self.table,
+                        period_prefix: self.applied.clone(),
+                    }));
+                }
+            });
+        });
+        if let Some(Command::ExportCsv(_)) = &snapshot.running {
+            ui.spinner();
+        }
+        match &snapshot.export {
+            Some(Ok(file)) => {
+                ui.label(RichText::new(Msg::Saved.fill(&[("file", file)])).color(WIN_COLOR));
+            }
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(Msg::ExportFailed.fill(&[("error", error)])).color(Color32::RED),
+                );
+            }
+            None => {}
+        }
+        ui.add_space(8.0);
+
+        match &snapshot.history {
+            Some(Ok(history)) => match self.table {
+                ExportTable::Tickets => self.tickets_table(ui, history),
+                ExportTable::Spots => self.spots_table(ui, history),
+            },
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(Msg::FailedToLoadHistory.fill(&[("error", error)]))
+                        .color(Color32::RED),
+                );
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+
+        if command.is_some() || self.requested || snapshot.running.is_some() {
+            return command;
+        }
+        self.requested = true;
+        Some(Command::LoadHistory(self.applied.clone()))
+    }
+}
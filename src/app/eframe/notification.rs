@@ -0,0 +1,14 @@
+use notify_rust::Notification;
+
+/// Raise an OS notification, failures are only logged as the desktop may have no notification server
+pub(super) fn notify(summary: &str, body: &str) {
+    log::info!("Notifying: {summary}: {body}");
+    if let Err(e) = Notification::new()
+        .appname("dball")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification `{summary}`: {e}");
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+use dball_client::models::ManualSpot;
+use egui::{Color32, RichText, Sense, Stroke, Vec2};
+
+use super::daemon::{Command, Connection, Snapshot};
+use super::dashboard::{BLUE_BALL, RED_BALL};
+use super::strings::Msg;
+
+const RED_COUNT: u8 = 33;
+const BLUE_COUNT: u8 = 16;
+const REDS_TO_PICK: usize = 6;
+const RED_PER_ROW: u8 = 11;
+const BLUE_PER_ROW: u8 = 8;
+const CHIP_SIZE: Vec2 = Vec2::splat(34.0);
+
+/// Toggleable ball drawn as a circle, filled while selected
+fn chip(ui: &mut egui::Ui, number: u8, selected: bool, color: Color32) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(CHIP_SIZE, Sense::click());
+    let painter = ui.painter();
+    let radius = rect.width() / 2.0 - 2.0;
+    let text_color = if selected {
+        painter.circle_filled(rect.center(), radius, color);
+        Color32::WHITE
+    } else {
+        let width = if response.hovered() { 2.5 } else { 1.5 };
+        painter.circle_stroke(rect.center(), radius, Stroke::new(width, color));
+        ui.visuals().text_color()
+    };
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        format!("{number:02}"),
+        egui::FontId::monospace(14.0),
+        text_color,
+    );
+    response.on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
+/// Red and blue balls to pick a spot by hand
+pub(super) struct NumberPicker {
+    reds: BTreeSet<u8>,
+    blue: Option<u8>,
+    magnification: usize,
+}
+
+impl Default for NumberPicker {
+    fn default() -> Self {
+        Self {
+            reds: BTreeSet::new(),
+            blue: None,
+            magnification: 1,
+        }
+    }
+}
+
+impl NumberPicker {
+    /// The picked spot, or why it can't be submitted yet
+    fn spot(&self) -> Result<ManualSpot, String> {
+        let reds: [u8; REDS_TO_PICK] = self
+            .reds
+            .iter()
+            .copied()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|reds: Vec<u8>| {
+                Msg::PickReds.fill(&[("count", &REDS_TO_PICK), ("picked", &reds.len())])
+            })?;
+        let blue = self.blue.ok_or_else(|| Msg::PickBlue.text().to_owned())?;
+        Ok(ManualSpot {
+            reds,
+            blue,
+            magnification: self.magnification,
+        })
+    }
+
+    fn balls(&mut self, ui: &mut egui::Ui) {
+        for row in (1..=RED_COUNT)
+            .collect::<Vec<_>>()
+            .chunks(RED_PER_ROW.into())
+        {
+            ui.horizontal(|ui| {
+                for &number in row {
+                    let selected = self.reds.contains(&number);
+                    if chip(ui, number, selected, RED_BALL).clicked() && !self.reds.remove(&number)
+                    {
+                        self.reds.insert(number);
+                    }
+                }
+            });
+        }
+        ui.add_space(8.0);
+        for row in (1..=BLUE_COUNT)
+            .collect::<Vec<_>>()
+            .chunks(BLUE_PER_ROW.into())
+        {
+            ui.horizontal(|ui| {
+                for &number in row {
+                    let selected = self.blue == Some(number);
+                    if chip(ui, number, selected, BLUE_BALL).clicked() {
+                        self.blue = (!selected).then_some(number);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Validation result and the checker warnings of a complete pick
+    fn validation(ui: &mut egui::Ui, spot: &Result<ManualSpot, String>) {
+        let dball = match spot {
+            Ok(spot) => spot.to_dball().map_err(|e| e.to_string()),
+            Err(problem) => Err(problem.clone()),
+        };
+        match dball {
+            Ok(dball) => {
+                ui.label(RichText::new(format!("✔ {dball}")).color(Color32::GREEN));
+                for warning in dball.evaluate_detailed() {
+                    ui.label(RichText::new(format!("⚠ {warning}")).color(Color32::YELLOW));
+                }
+            }
+            Err(problem) => {
+                ui.label(RichText::new(problem).weak());
+            }
+        }
+    }
+
+    /// Draw the picker, returns the command saving the spot once submitted
+    pub(super) fn show(&mut self, ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+        ui.heading(Msg::PickTitle.text());
+        ui.add_space(8.0);
+
+        self.balls(ui);
+        ui.add_space(8.0);
+
+        let spot = self.spot();
+        Self::validation(ui, &spot);
+        ui.add_space(8.0);
+
+        let enabled = spot.is_ok()
+            && snapshot.running.is_none()
+            && matches!(snapshot.connection, Connection::Connected(_));
+        let mut command = None;
+        ui.horizontal(|ui| {
+            ui.label(Msg::Times.text());
+            ui.add(egui::DragValue::new(&mut self.magnification).range(1..=99));
+            if ui
+                .add_enabled(enabled, egui::Button::new(Msg::Submit.text()))
+                .clicked()
+            {
+                command = spot.ok().map(Command::AddManualSpot);
+            }
+            if ui.button(Msg::Clear.text()).clicked() {
+                self.reds.clear();
+                self.blue = None;
+            }
+            if let Some(running) = &snapshot.running {
+                ui.spinner();
+                ui.label(running.label());
+            }
+        });
+
+        match &snapshot.outcome {
+            Some(Ok(message)) => {
+                ui.label(RichText::new(message).color(Color32::GREEN));
+            }
+            Some(Err(error)) => {
+                ui.label(RichText::new(error.to_string()).color(Color32::RED));
+            }
+            None => {}
+        }
+        command
+    }
+}
@@ -0,0 +1,345 @@
+use std::time::Duration;
+
+use dball_client::{
+    config::{BudgetConfig, DballConfig, Locale},
+    ipc::{
+        RpcError,
+        client::{HttpTransport, TransportMode},
+    },
+};
+use egui::{Color32, RichText, ThemePreference};
+use serde::{Deserialize, Serialize};
+
+use super::strings::Msg;
+
+/// Storage key of the preferences, restored on the next start
+pub(super) const STORAGE_KEY: &str = "preferences";
+
+/// Settings of the dashboard, kept in eframe's storage
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Preferences {
+    pub(crate) transport: TransportMode,
+    /// Base URL of the daemon's HTTP server, which may run on another machine
+    pub(crate) http_url: String,
+    /// Bearer token of the daemon's HTTP API, blank when it requires none
+    pub(crate) auth_token: String,
+    pub(crate) theme: ThemePreference,
+    /// Seconds between refreshes of the spots, and of the state when it is polled
+    pub(crate) refresh_secs: u64,
+    /// Notify 30 minutes before each draw, only on the desktop
+    pub(crate) draw_reminder: bool,
+    /// Notify of top tier prizes and failed settlements, only on the desktop
+    pub(crate) notifications: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            transport: TransportMode::Auto,
+            http_url: default_http_url(),
+            auth_token: String::new(),
+            theme: ThemePreference::System,
+            refresh_secs: 30,
+            draw_reminder: true,
+            notifications: true,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_http_url() -> String {
+    HttpTransport::from_env().base_url().to_owned()
+}
+
+/// The web build is served by the daemon, so its API is on the page's origin
+#[cfg(target_arch = "wasm32")]
+fn default_http_url() -> String {
+    web_sys::window()
+        .and_then(|window| window.location().origin().ok())
+        .unwrap_or_default()
+}
+
+impl Preferences {
+    pub(crate) fn http_transport(&self) -> HttpTransport {
+        HttpTransport::new(self.http_url.as_str()).with_token(Some(self.auth_token.clone()))
+    }
+
+    pub(crate) fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_secs)
+    }
+
+    /// Whether applying `other` needs a new connection to the daemon
+    pub(super) fn connection_differs(&self, other: &Self) -> bool {
+        self.transport != other.transport
+            || self.http_url != other.http_url
+            || self.auth_token != other.auth_token
+            || self.refresh_secs != other.refresh_secs
+    }
+
+    /// Why the preferences can't be applied, if they can't
+    fn problem(&self) -> Option<Msg> {
+        let url = self.http_url.trim();
+        if self.transport != TransportMode::Ipc
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            return Some(Msg::UrlScheme);
+        }
+        None
+    }
+}
+
+/// Budget of the daemon's config as typed, blank for unlimited
+struct BudgetDraft {
+    per_period: String,
+    per_week: String,
+    per_month: String,
+}
+
+impl BudgetDraft {
+    fn new(config: &DballConfig) -> Self {
+        Self {
+            per_period: config.field_value("budget.per_period").unwrap_or_default(),
+            per_week: config.field_value("budget.per_week").unwrap_or_default(),
+            per_month: config.field_value("budget.per_month").unwrap_or_default(),
+        }
+    }
+
+    /// The typed budget, checked the way the daemon checks it
+    fn parse(&self, config: &DballConfig) -> Result<BudgetConfig, String> {
+        let mut config = config.clone();
+        config
+            .set_field("budget.per_period", &self.per_period)
+            .and_then(|()| config.set_field("budget.per_week", &self.per_week))
+            .and_then(|()| config.set_field("budget.per_month", &self.per_month))
+            .map_err(|e| e.to_string())?;
+        match config
+            .validate()
+            .into_iter()
+            .find(|issue| issue.field.starts_with("budget."))
+        {
+            Some(issue) => Err(format!("{}: {}", issue.field, issue.message)),
+            None => Ok(config.budget),
+        }
+    }
+}
+
+/// What the window applied
+pub(super) struct Applied {
+    pub(super) preferences: Preferences,
+    /// New budget of the daemon, `None` when unchanged
+    pub(super) budget: Option<BudgetConfig>,
+    /// New UI language of the daemon's config, `None` when unchanged
+    pub(super) locale: Option<Locale>,
+}
+
+/// Window editing a copy of the preferences, applied all at once
+#[derive(Default)]
+pub(super) struct PreferencesDialog {
+    /// Edited copy, `Some` while the window is open
+    draft: Option<Preferences>,
+    /// Typed budget, `None` until the daemon's config has loaded
+    budget: Option<BudgetDraft>,
+    /// Picked language, `None` until the daemon's config has loaded
+    locale: Option<Locale>,
+}
+
+impl PreferencesDialog {
+    pub(super) fn open(&mut self, current: &Preferences) {
+        self.draft = Some(current.clone());
+        self.budget = None;
+        self.locale = None;
+    }
+
+    fn connection(ui: &mut egui::Ui, draft: &mut Preferences) {
+        ui.label(RichText::new(Msg::DaemonConnection.text()).strong());
+        // the browser can only reach the daemon over HTTP
+        if cfg!(not(target_arch = "wasm32")) {
+            for (mode, label) in [
+                (TransportMode::Auto, Msg::TransportAuto),
+                (TransportMode::Ipc, Msg::TransportIpc),
+                (TransportMode::Http, Msg::TransportHttp),
+            ] {
+                ui.radio_value(&mut draft.transport, mode, label.text());
+            }
+            ui.add_space(6.0);
+        }
+
+        ui.add_enabled_ui(draft.transport != TransportMode::Ipc, |ui| {
+            egui::Grid::new("http_preferences")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(Msg::HttpUrl.text());
+                    ui.text_edit_singleline(&mut draft.http_url);
+                    ui.end_row();
+
+                    ui.label(Msg::AuthToken.text());
+                    ui.add(
+                        egui::TextEdit::singleline(&mut draft.auth_token)
+                            .password(true)
+                            .hint_text(Msg::NoToken.text()),
+                    );
+                    ui.end_row();
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label(Msg::RefreshEvery.text());
+            ui.add(
+                egui::DragValue::new(&mut draft.refresh_secs)
+                    .range(1..=3600)
+                    .suffix(Msg::SecondsSuffix.text()),
+            );
+        });
+    }
+
+    fn theme(ui: &mut egui::Ui, theme: &mut ThemePreference) {
+        ui.label(RichText::new(Msg::Theme.text()).strong());
+        ui.horizontal(|ui| {
+            for (value, label) in [
+                (ThemePreference::System, Msg::ThemeSystem),
+                (ThemePreference::Dark, Msg::ThemeDark),
+                (ThemePreference::Light, Msg::ThemeLight),
+            ] {
+                ui.radio_value(theme, value, label.text());
+            }
+        });
+    }
+
+    /// Language picker once the config is loaded
+    fn language(&mut self, ui: &mut egui::Ui, config: &DballConfig) {
+        let locale = self.locale.get_or_insert(config.ui.locale);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(Msg::Language.text()).strong());
+            egui::ComboBox::from_id_salt("language")
+                .selected_text(locale.native_name())
+                .show_ui(ui, |ui| {
+                    for option in Locale::ALL {
+                        ui.selectable_value(locale, option, option.native_name());
+                    }
+                });
+        });
+    }
+
+    /// Budget fields once the config is loaded, returns the budget or why it's invalid
+    fn budget(&mut self, ui: &mut egui::Ui, config: &DballConfig) -> Result<BudgetConfig, String> {
+        ui.label(RichText::new(Msg::Budget.text()).strong());
+        let budget = self.budget.get_or_insert_with(|| BudgetDraft::new(config));
+        egui::Grid::new("budget_preferences")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(Msg::PerPeriod.text());
+                ui.text_edit_singleline(&mut budget.per_period);
+                ui.end_row();
+
+                ui.label(Msg::PerWeek.text());
+                ui.text_edit_singleline(&mut budget.per_week);
+                ui.end_row();
+
+                ui.label(Msg::PerMonth.text());
+                ui.text_edit_singleline(&mut budget.per_month);
+                ui.end_row();
+            });
+        budget.parse(config)
+    }
+
+    /// Fields kept in the daemon's config, returns the budget or why it's invalid
+    ///
+    /// `None` while the config loads or when it failed to
+    fn daemon_config(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: Option<&Result<DballConfig, RpcError>>,
+    ) -> Option<Result<BudgetConfig, String>> {
+        match config {
+            Some(Ok(config)) => {
+                self.language(ui, config);
+                ui.add_space(6.0);
+                Some(self.budget(ui, config))
+            }
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(Msg::FailedToLoadConfig.fill(&[("error", error)]))
+                        .color(Color32::RED),
+                );
+                None
+            }
+            None => {
+                ui.spinner();
+                None
+            }
+        }
+    }
+
+    /// Draw the window while open, returns what was applied
+    ///
+    /// `config` is the daemon's config the budget is edited in, `None` while it loads
+    pub(super) fn show(
+        &mut self,
+        ctx: &egui::Context,
+        config: Option<&Result<DballConfig, RpcError>>,
+    ) -> Option<Applied> {
+        let mut draft = self.draft.take()?;
+        let mut applied = None;
+        let mut close = false;
+
+        egui::Window::new(Msg::PreferencesTitle.text())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                Self::connection(ui, &mut draft);
+                ui.add_space(6.0);
+
+                Self::theme(ui, &mut draft.theme);
+                ui.add_space(6.0);
+
+                let budget = self.daemon_config(ui, config);
+                ui.add_space(6.0);
+
+                if cfg!(not(target_arch = "wasm32")) {
+                    ui.label(RichText::new(Msg::Notifications.text()).strong());
+                    ui.checkbox(&mut draft.draw_reminder, Msg::RemindBeforeDraw.text());
+                    ui.checkbox(&mut draft.notifications, Msg::NotifyPrizes.text());
+                    ui.add_space(6.0);
+                }
+
+                let problem = draft
+                    .problem()
+                    .map(|problem| problem.text().to_owned())
+                    .or_else(|| budget.as_ref().and_then(|budget| budget.clone().err()));
+                if let Some(problem) = &problem {
+                    ui.label(RichText::new(problem).color(Color32::RED));
+                }
+                ui.add_space(6.0);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(problem.is_none(), egui::Button::new(Msg::Apply.text()))
+                        .clicked()
+                    {
+                        draft.http_url = draft.http_url.trim().to_owned();
+                        draft.auth_token = draft.auth_token.trim().to_owned();
+                        // unchanged budgets and languages aren't written back
+                        let loaded = config.and_then(|config| config.as_ref().ok());
+                        applied = Some(Applied {
+                            preferences: draft.clone(),
+                            budget: budget.and_then(Result::ok).filter(|budget| {
+                                loaded.is_some_and(|config| config.budget != *budget)
+                            }),
+                            locale: self.locale.filter(|locale| {
+                                loaded.is_some_and(|config| config.ui.locale != *locale)
+                            }),
+                        });
+                    }
+                    if ui.button(Msg::Cancel.text()).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !close && applied.is_none() {
+            self.draft = Some(draft);
+        }
+        applied
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Local, TimeDelta, Utc};
+use dball_client::ipc::AppState;
+
+use super::notification::notify;
+use super::strings::Msg;
+
+/// How long before a draw the reminder pops up
+const REMIND_BEFORE: TimeDelta = TimeDelta::minutes(30);
+
+/// Desktop notification shown once per draw, shortly before it
+#[derive(Default)]
+pub(super) struct DrawReminder {
+    /// Draw that was last reminded of
+    reminded: Option<DateTime<Utc>>,
+}
+
+impl DrawReminder {
+    /// Notify when the next draw is within [`REMIND_BEFORE`] and wasn't reminded of yet
+    pub(super) fn check(&mut self, state: &AppState, now: DateTime<Utc>) {
+        let Some(draw) = state.next_draw_time else {
+            return;
+        };
+        if self.reminded == Some(draw) || now < draw - REMIND_BEFORE || now >= draw {
+            return;
+        }
+        self.reminded = Some(draw);
+
+        let minutes = (draw - now).num_minutes();
+        let body = Msg::DrawReminderBody.fill(&[
+            ("period", &state.next_period),
+            ("time", &draw.with_timezone(&Local).format("%H:%M")),
+        ]);
+        notify(
+            &Msg::DrawReminderSummary.fill(&[("minutes", &minutes)]),
+            &body,
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::path::PathBuf;
+
+use dball_client::service::RoiReport;
+use egui::{Color32, RichText};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+
+use super::daemon::{Command, Snapshot};
+use super::strings::Msg;
+
+const COST_COLOR: Color32 = Color32::from_rgb(0xd9, 0x3a, 0x3a);
+const WINNINGS_COLOR: Color32 = Color32::from_rgb(0x3a, 0xa8, 0x5c);
+const PLOT_HEIGHT: f32 = 180.0;
+
+/// Values plotted for one period, sums run from the oldest period
+struct PeriodPoint {
+    period: String,
+    cost: u64,
+    winnings: u64,
+    cumulative_cost: u64,
+    cumulative_winnings: u64,
+}
+
+impl PeriodPoint {
+    fn net(&self) -> i64 {
+        self.winnings as i64 - self.cost as i64
+    }
+}
+
+/// Oldest to newest series of the report, which lists the newest period first
+fn series(report: &RoiReport) -> Vec<PeriodPoint> {
+    let mut cumulative_cost = 0;
+    let mut cumulative_winnings = 0;
+    report
+        .periods
+        .iter()
+        .rev()
+        .map(|period| {
+            cumulative_cost += period.cost;
+            cumulative_winnings += period.winnings;
+            PeriodPoint {
+                period: period.period.clone(),
+                cost: period.cost,
+                winnings: period.winnings,
+                cumulative_cost,
+                cumulative_winnings,
+            }
+        })
+        .collect()
+}
+
+fn to_csv(points: &[PeriodPoint]) -> String {
+    let mut csv = "period,cost,winnings,net,cumulative_cost,cumulative_winnings\n".to_owned();
+    for point in points {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            point.period,
+            point.cost,
+            point.winnings,
+            point.net(),
+            point.cumulative_cost,
+            point.cumulative_winnings
+        ));
+    }
+    csv
+}
+
+/// Write the plotted series next to the working directory, named after its periods
+fn export_csv(points: &[PeriodPoint]) -> anyhow::Result<PathBuf> {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        anyhow::bail!(Msg::NothingToExport.text());
+    };
+    let path = PathBuf::from(format!("dball-roi-{}-{}.csv", first.period, last.period));
+    std::fs::write(&path, to_csv(points))?;
+    Ok(path)
+}
+
+/// Cumulative cost against winnings and the net of every period, over a selectable range
+#[derive(Default)]
+pub(super) struct RoiPanel {
+    /// Selected indices into the series, reset when the number of periods changes
+    range: Option<(usize, usize, usize)>,
+    requested: bool,
+    export: Option<Result<PathBuf, String>>,
+}
+
+impl RoiPanel {
+    /// First and last selected index for a series of `len` periods
+    fn selected(&mut self, len: usize) -> (usize, usize) {
+        match self.range {
+            Some((from, to, total)) if total == len => (from, to),
+            _ => {
+                self.range = Some((0, len - 1, len));
+                (0, len - 1)
+            }
+        }
+    }
+
+    fn range_selector(&mut self, ui: &mut egui::Ui, points: &[PeriodPoint]) -> (usize, usize) {
+        let last = points.len() - 1;
+        let (mut from, mut to) = self.selected(points.len());
+        ui.horizontal(|ui| {
+            let label = |idx: f64| points[(idx as usize).min(last)].period.clone();
+            ui.label(Msg::RangeFrom.text());
+            ui.add(egui::Slider::new(&mut from, 0..=last).custom_formatter(|v, _| label(v)));
+            ui.label(Msg::RangeTo.text());
+            ui.add(egui::Slider::new(&mut to, 0..=last).custom_formatter(|v, _| label(v)));
+        });
+        // dragging one end past the other moves both
+        if from > to {
+            (from, to) = (to, from);
+        }
+        self.range = Some((from, to, points.len()));
+        (from, to)
+    }
+
+    fn charts(&mut self, ui: &mut egui::Ui, report: &RoiReport) {
+        let points = series(report);
+        if points.is_empty() {
+            ui.label(Msg::NoSettledSpots.text());
+            return;
+        }
+
+        let (from, to) = self.range_selector(ui, &points);
+        let selected = &points[from..=to];
+        let period_label = |x: f64| {
+            let idx = x.round();
+            if idx < from as f64 || idx > to as f64 || (x - idx).abs() > f64::EPSILON {
+                return String::new();
+            }
+            points[idx as usize].period.clone()
+        };
+
+        ui.horizontal(|ui| {
+            if ui.button(Msg::ExportCsv.text()).clicked() {
+                self.export = Some(export_csv(selected).map_err(|e| e.to_string()));
+            }
+            match &self.export {
+                Some(Ok(path)) => {
+                    ui.label(
+                        RichText::new(Msg::Saved.fill(&[("file", &path.display())]))
+                            .color(Color32::GREEN),
+                    );
+                }
+                Some(Err(e)) => {
+                    ui.label(
+                        RichText::new(Msg::ExportFailed.fill(&[("error", e)])).color(Color32::RED),
+                    );
+                }
+                None => {}
+            }
+        });
+
+        ui.label(RichText::new(Msg::CumulativeTitle.text()).strong());
+        let cost = selected
+            .iter()
+            .enumerate()
+            .map(|(offset, point)| [(from + offset) as f64, point.cumulative_cost as f64])
+            .collect::<PlotPoints<'_>>();
+        let winnings = selected
+            .iter()
+            .enumerate()
+            .map(|(offset, point)| [(from + offset) as f64, point.cumulative_winnings as f64])
+            .collect::<PlotPoints<'_>>();
+        Plot::new("roi_cumulative")
+            .height(PLOT_HEIGHT)
+            .legend(Legend::default())
+            .x_axis_formatter(|mark, _| period_label(mark.value))
+            .show(ui, |plot| {
+                plot.line(Line::new(Msg::Cost.text(), cost).color(COST_COLOR));
+                plot.line(Line::new(Msg::Winnings.text(), winnings).color(WINNINGS_COLOR));
+            });
+
+        ui.label(RichText::new(Msg::NetPerPeriod.text()).strong());
+        let bars = selected
+            .iter()
+            .enumerate()
+            .map(|(offset, point)| {
+                let net = point.net();
+                let color = if net >= 0 { WINNINGS_COLOR } else { COST_COLOR };
+                Bar::new((from + offset) as f64, net as f64)
+                    .name(&point.period)
+                    .fill(color)
+            })
+            .collect();
+        Plot::new("roi_net")
+            .height(PLOT_HEIGHT)
+            .x_axis_formatter(|mark, _| period_label(mark.value))
+            .show(ui, |plot| {
+                plot.bar_chart(BarChart::new(Msg::Net.text(), bars));
+            });
+    }
+
+    /// Load the report again on the next frame
+    pub(super) fn reload(&mut self) {
+        self.requested = false;
+    }
+
+    /// Draw the panel, returns the request for the report when shown first or reloaded
+    pub(super) fn show(&mut self, ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+        ui.horizontal(|ui| {
+            ui.heading(Msg::RoiTitle.text());
+            if ui.button(Msg::Reload.text()).clicked() {
+                self.reload();
+            }
+        });
+        ui.add_space(8.0);
+
+        match &snapshot.roi {
+            Some(Ok(report)) => {
+                let net = report.total_winnings as i64 - report.total_cost as i64;
+                ui.label(Msg::RoiSummary.fill(&[
+                    ("cost", &report.total_cost),
+                    ("winnings", &report.total_winnings),
+                    ("net", &format!("{net:+}")),
+                ]));
+                self.charts(ui, report);
+            }
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(Msg::FailedToLoadRoi.fill(&[("error", error)]))
+                        .color(Color32::RED),
+                );
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+
+        if self.requested || snapshot.running.is_some() {
+            return None;
+        }
+        self.requested = true;
+        Some(Command::LoadRoi)
+    }
+}
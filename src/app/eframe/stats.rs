@@ -0,0 +1,191 @@
+use dball_client::service::{NumberFrequencies, NumberStat};
+use egui::{Color32, RichText, Sense, Vec2};
+
+use super::daemon::{Command, Snapshot};
+use super::strings::Msg;
+use crate::app::heat::{HeatMetric, heat_ratio, heat_rgb};
+
+const DEFAULT_WINDOW: usize = 100;
+const WINDOW_RANGE: std::ops::RangeInclusive<usize> = 10..=500;
+const RED_PER_ROW: usize = 11;
+const BLUE_PER_ROW: usize = 8;
+const CELL_SIZE: Vec2 = Vec2::new(36.0, 28.0);
+
+/// Heatmap of how often each number was drawn over a lookback window
+pub(super) struct StatsPanel {
+    window: usize,
+    metric: HeatMetric,
+    /// Window of the last request, `None` until the panel is first shown
+    requested: Option<usize>,
+}
+
+impl Default for StatsPanel {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_WINDOW,
+            metric: HeatMetric::Frequency,
+            requested: None,
+        }
+    }
+}
+
+/// [`heat_rgb`] as an egui color
+fn heat_color(ratio: f32) -> Color32 {
+    let (r, g, b) = heat_rgb(ratio);
+    Color32::from_rgb(r, g, b)
+}
+
+fn heat_cell(ui: &mut egui::Ui, stat: &NumberStat, ratio: f32, draws: usize) {
+    let (rect, response) = ui.allocate_exact_size(CELL_SIZE, Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 4.0, heat_color(ratio));
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        format!("{:02}", stat.number),
+        egui::FontId::monospace(14.0),
+        Color32::BLACK,
+    );
+
+    let share = if draws > 0 {
+        stat.frequency as f32 * 100.0 / draws as f32
+    } else {
+        0.0
+    };
+    response.on_hover_text(Msg::StatsCell.fill(&[
+        ("number", &format!("{:02}", stat.number)),
+        ("frequency", &stat.frequency),
+        ("draws", &draws),
+        ("share", &format!("{share:.1}")),
+        ("omission", &stat.omission),
+        ("rank", &stat.rank),
+    ]));
+}
+
+fn heatmap(
+    ui: &mut egui::Ui,
+    stats: &[NumberStat],
+    per_row: usize,
+    ratio: impl Fn(&NumberStat) -> f32,
+    draws: usize,
+) {
+    for row in stats.chunks(per_row) {
+        ui.horizontal(|ui| {
+            for stat in row {
+                heat_cell(ui, stat, ratio(stat), draws);
+            }
+        });
+    }
+}
+
+/// Cold to hot gradient under the heatmaps
+fn legend(ui: &mut egui::Ui, metric: HeatMetric) {
+    const STEPS: usize = 40;
+    ui.horizontal(|ui| {
+        let (cold, hot) = match metric {
+            HeatMetric::Frequency => (Msg::LegendRare, Msg::LegendFrequent),
+            HeatMetric::Omission => (Msg::LegendAbsent, Msg::LegendJustDrawn),
+        };
+        ui.label(cold.text());
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(200.0, 12.0), Sense::hover());
+        let step_width = rect.width() / STEPS as f32;
+        for step in 0..STEPS {
+            let left = rect.left() + step as f32 * step_width;
+            let cell = egui::Rect::from_min_size(
+                egui::pos2(left, rect.top()),
+                Vec2::new(step_width + 0.5, rect.height()),
+            );
+            ui.painter()
+                .rect_filled(cell, 0.0, heat_color(step as f32 / (STEPS - 1) as f32));
+        }
+        ui.label(hot.text());
+    });
+}
+
+impl StatsPanel {
+    fn heatmaps(&self, ui: &mut egui::Ui, stats: &NumberFrequencies) {
+        ui.label(
+            RichText::new(Msg::StatsDraws.fill(&[
+                ("draws", &stats.draws),
+                ("period", &stats.latest_period.as_deref().unwrap_or("-")),
+            ]))
+            .weak(),
+        );
+        ui.add_space(6.0);
+
+        let max_red = stats.max_red_frequency();
+        heatmap(
+            ui,
+            &stats.red,
+            RED_PER_ROW,
+            |stat| heat_ratio(stat, self.metric, max_red, stats.draws),
+            stats.draws,
+        );
+        ui.add_space(10.0);
+        let max_blue = stats.max_blue_frequency();
+        heatmap(
+            ui,
+            &stats.blue,
+            BLUE_PER_ROW,
+            |stat| heat_ratio(stat, self.metric, max_blue, stats.draws),
+            stats.draws,
+        );
+        ui.add_space(10.0);
+        legend(ui, self.metric);
+    }
+
+    /// Load the statistics again on the next frame
+    pub(super) fn reload(&mut self) {
+        self.requested = None;
+    }
+
+    /// Draw the panel, returns the request for statistics when the window changed
+    pub(super) fn show(&mut self, ui: &mut egui::Ui, snapshot: &Snapshot) -> Option<Command> {
+        ui.heading(Msg::StatsTitle.text());
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.label(Msg::StatsLookback.text());
+            ui.add(
+                egui::Slider::new(&mut self.window, WINDOW_RANGE)
+                    .suffix(Msg::StatsDrawsSuffix.text()),
+            );
+            ui.separator();
+            ui.selectable_value(
+                &mut self.metric,
+                HeatMetric::Frequency,
+                Msg::MetricFrequency.text(),
+            );
+            ui.selectable_value(
+                &mut self.metric,
+                HeatMetric::Omission,
+                Msg::MetricOmission.text(),
+            );
+        });
+        ui.add_space(8.0);
+
+        match &snapshot.stats {
+            Some(Ok(stats)) => self.heatmaps(ui, stats),
+            Some(Err(error)) => {
+                ui.label(
+                    RichText::new(Msg::FailedToLoadStats.fill(&[("error", error)]))
+                        .color(Color32::RED),
+                );
+                if ui.button(Msg::Retry.text()).clicked() {
+                    self.reload();
+                }
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+
+        // only reload once the slider is let go
+        let dragging = ui.ctx().dragged_id().is_some();
+        if dragging || snapshot.running.is_some() || self.requested == Some(self.window) {
+            return None;
+        }
+        self.requested = Some(self.window);
+        Some(Command::LoadStats(self.window))
+    }
+}
@@ -0,0 +1,160 @@
+//! Strings of the desktop and web GUI
+
+use crate::app::i18n::string_table;
+
+string_table! {
+    // Menu and views
+    MenuFile => "File", "文件";
+    MenuPreferences => "Preferences…", "偏好设置…";
+    MenuQuit => "Quit", "退出";
+    ViewDashboard => "Dashboard", "总览";
+    ViewPick => "Pick", "选号";
+    ViewStats => "Stats", "统计";
+    ViewRoi => "ROI", "收益";
+    ViewHistory => "History", "历史";
+    FailedToStart => "Failed to start: {$error}", "启动失败: {$error}";
+
+    // Shared
+    Period => "Period", "期号";
+    Numbers => "Numbers", "号码";
+    Times => "Times", "倍数";
+    Created => "Created", "创建时间";
+    Pending => "pending", "待开奖";
+    Apply => "Apply", "应用";
+    Retry => "Retry", "重试";
+    ExportCsv => "Export CSV", "导出 CSV";
+    Saved => "Saved {$file}", "已保存 {$file}";
+    ExportFailed => "Export failed: {$error}", "导出失败: {$error}";
+
+    // Commands
+    Refreshing => "Refreshing", "刷新中";
+    GeneratingSpots => "Generating spots", "正在生成号码";
+    SettlingSpots => "Settling spots", "正在结算号码";
+    DeprecatingBatch => "Deprecating last batch", "正在废弃上一批";
+    SavingManualSpot => "Saving manual spot", "正在保存手选号码";
+    LoadingStats => "Loading statistics", "正在加载统计";
+    LoadingRoi => "Loading ROI report", "正在加载收益报告";
+    LoadingConfig => "Loading daemon config", "正在加载守护进程配置";
+    SavingBudget => "Saving budget", "正在保存预算";
+    SavingLanguage => "Saving language", "正在保存语言";
+    LoadingHistory => "Loading history", "正在加载历史";
+    ExportingCsv => "Exporting CSV", "正在导出 CSV";
+    SavedBudget => "Saved budget", "预算已保存";
+    SavedLanguage => "Saved language", "语言已保存";
+    Generated => "Generated a new batch of spots", "已生成一批新号码";
+    Settled => "Settled {$count} spots", "已结算 {$count} 注";
+    SavedManualSpot => "Saved manual spot for period {$period}", "已保存第 {$period} 期的手选号码";
+    Deprecated => "Deprecated {$count} spots", "已废弃 {$count} 注";
+
+    // Notifications
+    PrizeWonSummary => "Prize {$tier} won!", "中奖 {$tier}！";
+    PrizeWonBody => "Period {$period}: {$numbers} x{$times} won ¥{$amount}", "第 {$period} 期: {$numbers} x{$times} 中奖 ¥{$amount}";
+    SettlementFailed => "Settlement failed", "结算失败";
+    #[cfg(not(target_arch = "wasm32"))]
+    DrawReminderSummary => "Draw in {$minutes} minutes", "{$minutes} 分钟后开奖";
+    #[cfg(not(target_arch = "wasm32"))]
+    DrawReminderBody => "Period {$period} draws at {$time}", "第 {$period} 期将于 {$time} 开奖";
+
+    // Tray
+    #[cfg(not(target_arch = "wasm32"))]
+    TrayOpen => "Open dashboard", "打开总览";
+    #[cfg(not(target_arch = "wasm32"))]
+    TrayGenerate => "Generate spots", "生成号码";
+    #[cfg(not(target_arch = "wasm32"))]
+    TrayTooltip => "dball: period {$period} draws in {$countdown}", "dball: 第 {$period} 期距开奖 {$countdown}";
+
+    // Dashboard
+    DrawingNow => "drawing now", "开奖中";
+    CountdownDays => "{$days}d {$clock}", "{$days}天 {$clock}";
+    Connecting => "◌ connecting", "◌ 连接中";
+    ConnectedOver => "● connected over {$transport}", "● 已通过 {$transport} 连接";
+    Unreachable => "○ daemon unreachable: {$error}", "○ 守护进程不可达: {$error}";
+    CurrentPeriod => "Current period", "本期";
+    NextPeriod => "Next period", "下期";
+    DrawIn => "Draw in", "距开奖";
+    LastResult => "Last result", "最新开奖";
+    UnprizedSpots => "Unprized spots", "待开奖号码";
+    InvestedReturned => "Invested / returned", "投入 / 回报";
+    Claimed => "Claimed", "已兑奖";
+    Generate => "Generate", "生成";
+    Settle => "Settle", "结算";
+    DeprecateBatch => "Deprecate last batch", "废弃上一批";
+    Refresh => "Refresh", "刷新";
+    NoUnprizedSpots => "No unprized spots, generate a batch for the next period", "暂无待开奖号码，请为下一期生成一批";
+    WaitingForState => "Waiting for the daemon state...", "等待守护进程状态...";
+    FailedToLoadSpots => "Failed to load spots: {$error}", "加载号码失败: {$error}";
+
+    // Picker
+    PickTitle => "Pick Numbers", "选号";
+    PickReds => "Pick exactly {$count} reds, {$picked} picked", "请恰好选择 {$count} 个红球，已选 {$picked} 个";
+    PickBlue => "Pick 1 blue", "请选择 1 个蓝球";
+    Submit => "Submit", "提交";
+    Clear => "Clear", "清空";
+
+    // Stats
+    StatsTitle => "Number Stats", "号码统计";
+    StatsLookback => "Lookback", "统计范围";
+    StatsDrawsSuffix => " draws", " 期";
+    MetricFrequency => "Frequency", "频次";
+    MetricOmission => "Omission", "遗漏";
+    StatsDraws => "{$draws} draws up to {$period}", "截至 {$period} 共 {$draws} 期";
+    StatsCell => "Number {$number}\nDrawn {$frequency} of {$draws} draws ({$share}%)\nLast seen {$omission} draws ago\nHot rank {$rank}", "号码 {$number}\n{$draws} 期中开出 {$frequency} 次 ({$share}%)\n已 {$omission} 期未开出\n冷热排名 {$rank}";
+    LegendRare => "rare", "冷";
+    LegendFrequent => "frequent", "热";
+    LegendAbsent => "long absent", "久未开出";
+    LegendJustDrawn => "just drawn", "刚开出";
+    FailedToLoadStats => "Failed to load statistics: {$error}", "加载统计失败: {$error}";
+
+    // ROI
+    RoiTitle => "ROI", "收益";
+    Reload => "Reload", "重新加载";
+    RoiSummary => "Cost ¥{$cost} | Win ¥{$winnings} | Net {$net}", "花费 ¥{$cost} | 奖金 ¥{$winnings} | 净收益 {$net}";
+    NoSettledSpots => "No settled spots yet", "暂无已结算号码";
+    NothingToExport => "Nothing to export", "没有可导出的数据";
+    RangeFrom => "From", "从";
+    RangeTo => "To", "到";
+    CumulativeTitle => "Cumulative cost vs winnings", "累计花费与奖金";
+    Cost => "Cost", "花费";
+    Winnings => "Winnings", "奖金";
+    NetPerPeriod => "Net per period", "每期净收益";
+    Net => "Net", "净收益";
+    FailedToLoadRoi => "Failed to load ROI report: {$error}", "加载收益报告失败: {$error}";
+
+    // History
+    HistoryTitle => "History", "历史";
+    Tickets => "Tickets", "开奖";
+    Spots => "Spots", "号码";
+    Drawn => "Drawn", "开奖时间";
+    Won => "Won", "中奖";
+    Prize => "Prize", "奖金";
+    Note => "Note", "备注";
+    PeriodHint => "e.g. 2025", "例如 2025";
+    FailedToLoadHistory => "Failed to load history: {$error}", "加载历史失败: {$error}";
+
+    // Preferences
+    PreferencesTitle => "Preferences", "偏好设置";
+    UrlScheme => "The URL must start with http:// or https://", "地址必须以 http:// 或 https:// 开头";
+    DaemonConnection => "Daemon connection", "守护进程连接";
+    TransportAuto => "Auto (Unix socket, then HTTP)", "自动 (先 Unix socket，再 HTTP)";
+    TransportIpc => "Unix socket", "Unix socket";
+    TransportHttp => "HTTP", "HTTP";
+    HttpUrl => "HTTP URL", "HTTP 地址";
+    AuthToken => "Auth token", "认证令牌";
+    NoToken => "none", "无";
+    RefreshEvery => "Refresh every", "刷新间隔";
+    SecondsSuffix => " s", " 秒";
+    Theme => "Theme", "主题";
+    ThemeSystem => "System", "跟随系统";
+    ThemeDark => "Dark", "深色";
+    ThemeLight => "Light", "浅色";
+    Language => "Language", "语言";
+    Budget => "Budget (¥, blank for unlimited)", "预算 (¥，留空为不限)";
+    FailedToLoadConfig => "Failed to load the daemon config: {$error}", "加载守护进程配置失败: {$error}";
+    PerPeriod => "Per period", "每期";
+    PerWeek => "Per week", "每周";
+    PerMonth => "Per month", "每月";
+    Notifications => "Notifications", "通知";
+    RemindBeforeDraw => "Remind me 30 minutes before each draw", "每次开奖前 30 分钟提醒我";
+    NotifyPrizes => "Notify of prizes up to tier 4 and failed settlements", "中得四等及以上奖项或结算失败时通知我";
+    Cancel => "Cancel", "取消";
+}
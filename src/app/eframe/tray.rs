@@ -0,0 +1,142 @@
+use std::sync::mpsc;
+
+use chrono::Utc;
+use dball_client::{config::Locale, ipc::AppState};
+use tray_icon::{
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+};
+
+use super::dashboard::format_countdown;
+use super::strings::Msg;
+use crate::app::i18n;
+
+const OPEN_ID: &str = "open";
+const GENERATE_ID: &str = "generate";
+const QUIT_ID: &str = "quit";
+
+/// Quick action picked from the tray
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TrayAction {
+    OpenDashboard,
+    Generate,
+    Quit,
+}
+
+impl TrayAction {
+    fn from_menu_id(id: &str) -> Option<Self> {
+        match id {
+            OPEN_ID => Some(Self::OpenDashboard),
+            GENERATE_ID => Some(Self::Generate),
+            QUIT_ID => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Tray icon with the next draw countdown as its tooltip
+pub(super) struct Tray {
+    icon: TrayIcon,
+    tooltip: String,
+    /// Menu items with their labels, relabelled when the language changes
+    items: Vec<(MenuItem, Msg)>,
+    /// Language the menu is labelled in
+    locale: Locale,
+    actions: mpsc::Receiver<TrayAction>,
+}
+
+impl Tray {
+    /// Add the icon to the tray, `ctx` is repainted whenever an action is picked
+    pub(super) fn new(ctx: &egui::Context) -> anyhow::Result<Self> {
+        let item = |id, label: Msg| (MenuItem::with_id(id, label.text(), true, None), label);
+        let items = vec![
+            item(OPEN_ID, Msg::TrayOpen),
+            item(GENERATE_ID, Msg::TrayGenerate),
+            item(QUIT_ID, Msg::MenuQuit),
+        ];
+        let menu = Menu::new();
+        menu.append_items(&[
+            &items[0].0,
+            &items[1].0,
+            &PredefinedMenuItem::separator(),
+            &items[2].0,
+        ])?;
+        let image =
+            eframe::icon_data::from_png_bytes(include_bytes!("../../../assets/icon-256.png"))?;
+        let tooltip = "dball".to_owned();
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_menu_on_left_click(false)
+            .with_tooltip(&tooltip)
+            .with_icon(Icon::from_rgba(image.rgba, image.width, image.height)?)
+            .build()?;
+
+        // the handlers run on the tray's own thread, so the UI is woken up even while idle
+        let (sender, actions) = mpsc::channel();
+        let menu_sender = sender.clone();
+        let menu_ctx = ctx.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some(action) = TrayAction::from_menu_id(event.id().as_ref())
+                && menu_sender.send(action).is_ok()
+            {
+                menu_ctx.request_repaint();
+            }
+        }));
+        let click_ctx = ctx.clone();
+        TrayIconEvent::set_event_handler(Some(move |event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+                && sender.send(TrayAction::OpenDashboard).is_ok()
+            {
+                click_ctx.request_repaint();
+            }
+        }));
+
+        Ok(Self {
+            icon,
+            tooltip,
+            items,
+            locale: i18n::locale(),
+            actions,
+        })
+    }
+
+    /// Actions picked since the last frame
+    pub(super) fn actions(&self) -> impl Iterator<Item = TrayAction> + '_ {
+        self.actions.try_iter()
+    }
+
+    /// Show the countdown to the next draw, only touching the tray when the text changes,
+    /// and relabel the menu when the language changed
+    pub(super) fn show_countdown(&mut self, state: Option<&AppState>) {
+        let locale = i18n::locale();
+        if locale != self.locale {
+            for (item, label) in &self.items {
+                item.set_text(label.text_in(locale));
+            }
+            self.locale = locale;
+        }
+
+        let tooltip = match state {
+            Some(AppState {
+                next_period,
+                next_draw_time: Some(time),
+                ..
+            }) => Msg::TrayTooltip.fill(&[
+                ("period", next_period),
+                ("countdown", &format_countdown(*time - Utc::now())),
+            ]),
+            Some(_) | None => "dball".to_owned(),
+        };
+        if tooltip == self.tooltip {
+            return;
+        }
+        if let Err(e) = self.icon.set_tooltip(Some(&tooltip)) {
+            log::warn!("Failed to update tray tooltip: {e}");
+        }
+        self.tooltip = tooltip;
+    }
+}
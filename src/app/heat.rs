@@ -0,0 +1,40 @@
+//! Heat scale of the number heatmaps of both frontends
+//!
+//! Each frontend turns the `(r, g, b)` of [`heat_rgb`] into its own color type.
+
+use dball_client::service::NumberStat;
+
+/// Statistic a heatmap colors the numbers by
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HeatMetric {
+    Frequency,
+    Omission,
+}
+
+/// Map a number's statistic to 0.0 (cold) ..= 1.0 (hot)
+pub(crate) fn heat_ratio(
+    stat: &NumberStat,
+    metric: HeatMetric,
+    max_frequency: usize,
+    draws: usize,
+) -> f32 {
+    match metric {
+        HeatMetric::Frequency if max_frequency > 0 => stat.frequency as f32 / max_frequency as f32,
+        // A long omission is cold, a number drawn last period is hot
+        HeatMetric::Omission if draws > 0 => 1.0 - (stat.omission as f32 / draws as f32),
+        HeatMetric::Frequency | HeatMetric::Omission => 0.0,
+    }
+}
+
+/// Blue (cold) -> yellow -> red (hot) color scale as `(r, g, b)`
+pub(crate) fn heat_rgb(ratio: f32) -> (u8, u8, u8) {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let (r, g, b) = if ratio < 0.5 {
+        let t = ratio * 2.0;
+        (70.0 + 185.0 * t, 110.0 + 110.0 * t, 200.0 - 160.0 * t)
+    } else {
+        let t = (ratio - 0.5) * 2.0;
+        (255.0, 220.0 - 170.0 * t, 40.0)
+    };
+    (r as u8, g as u8, b as u8)
+}
@@ -0,0 +1,107 @@
+//! Translated labels, messages and notification templates of both frontends
+//!
+//! Each frontend declares its strings with [`string_table!`], every key with an English
+//! and a Simplified Chinese text. The language is `ui.locale` of the daemon's `dball.toml`,
+//! applied with [`set_locale`] whenever the config is loaded.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dball_client::config::Locale;
+use dball_client::ipc::RpcErrorCode;
+
+/// Index into [`Locale::ALL`] of the current language
+static LOCALE: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn locale() -> Locale {
+    Locale::ALL
+        .get(LOCALE.load(Ordering::Relaxed))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Switch the language, the next frame or render picks it up
+pub(crate) fn set_locale(locale: Locale) {
+    let index = Locale::ALL
+        .iter()
+        .position(|known| *known == locale)
+        .unwrap_or_default();
+    if LOCALE.swap(index, Ordering::Relaxed) != index {
+        log::info!("UI language set to {}", locale.tag());
+    }
+}
+
+/// Replace each `{$name}` placeholder of `template` with its argument
+pub(crate) fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{${name}}}"), &value.to_string())
+        })
+}
+
+/// What the user can do about a request failing with `code`, in the current language,
+/// `None` when retrying is unlikely to help
+pub(crate) fn retry_hint(code: RpcErrorCode) -> Option<&'static str> {
+    let (en, zh) = match code {
+        RpcErrorCode::ProviderUnavailable => (
+            "The lottery API is unreachable or rate limited, retry in a minute",
+            "彩票接口无法访问或已限流，请一分钟后重试",
+        ),
+        RpcErrorCode::Busy => (
+            "The daemon is busy, retry once it finishes",
+            "守护进程正忙，请在其完成后重试",
+        ),
+        RpcErrorCode::Transport => (
+            "Check that the daemon is running, then retry",
+            "请确认守护进程正在运行后重试",
+        ),
+        RpcErrorCode::NotFound
+        | RpcErrorCode::InvalidRequest
+        | RpcErrorCode::Cancelled
+        | RpcErrorCode::Unauthorized
+        | RpcErrorCode::Internal => return None,
+    };
+    Some(match locale() {
+        Locale::En => en,
+        Locale::ZhCn => zh,
+    })
+}
+
+/// Declare a `Msg` key per string, with its English and Chinese text
+///
+/// Templates name their arguments `{$name}`, see [`fill`]. A key can be put behind a `cfg`
+/// attribute when only some targets use it.
+macro_rules! string_table {
+    ($($(#[$cfg:meta])* $key:ident => $en:literal, $zh:literal;)*) => {
+        /// Key of a translated string
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub(crate) enum Msg {
+            $($(#[$cfg])* $key,)*
+        }
+
+        impl Msg {
+            pub(crate) fn text_in(self, locale: dball_client::config::Locale) -> &'static str {
+                match locale {
+                    dball_client::config::Locale::En => match self {
+                        $($(#[$cfg])* Self::$key => $en,)*
+                    },
+                    dball_client::config::Locale::ZhCn => match self {
+                        $($(#[$cfg])* Self::$key => $zh,)*
+                    },
+                }
+            }
+
+            /// Text in the current language
+            pub(crate) fn text(self) -> &'static str {
+                self.text_in($crate::app::i18n::locale())
+            }
+
+            /// Template in the current language with its placeholders filled in
+            pub(crate) fn fill(self, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+                $crate::app::i18n::fill(self.text(), args)
+            }
+        }
+    };
+}
+
+pub(crate) use string_table;
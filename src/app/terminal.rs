@@ -1,17 +1,26 @@
+mod clipboard;
 mod component;
+mod input;
 mod ipc;
 mod layout;
+mod strings;
 
 use std::sync::LazyLock;
 
 use chrono::Utc;
+use dball_client::config::DballConfig;
+use dball_client::ipc::RpcService;
 use dball_client::ipc::client::StateSubscriber;
 use dball_client::ipc::protocol::{AppState as IpcAppState, GenerationStatus};
 use dball_combora::dball::DBall;
 use iocraft::prelude::*;
-use layout::MainLayout;
+use layout::{MainLayout, SettingsLayout};
 use tokio::sync::RwLock;
 
+use crate::app::i18n;
+
+pub use ipc::{TransportMode, connect_transport, set_transport_mode};
+
 fn create_default_app_state() -> IpcAppState {
     let mut app_state = IpcAppState {
         current_period: "2025084".to_owned(),
@@ -22,7 +31,9 @@ fn create_default_app_state() -> IpcAppState {
         pending_tickets: vec![],
         unprize_spots_count: 12,
         total_investment: 0.0,
+        theoretical_investment: 0.0,
         total_return: 0.0,
+        claimed_return: 0.0,
         api_status: dball_client::ipc::protocol::ApiStatusInfo {
             api_provider: "default".to_owned(),
             last_success: None,
@@ -33,6 +44,7 @@ fn create_default_app_state() -> IpcAppState {
         daemon_uptime: std::time::Duration::from_secs(0),
         generation_status: GenerationStatus::Idle,
         last_generation_time: None,
+        budget: Default::default(),
     };
 
     // Create a default DBall instance
@@ -42,14 +54,23 @@ fn create_default_app_state() -> IpcAppState {
     app_state
 }
 
+/// Latest state pushed by the daemon, `None` until the subscription delivers one
 static APP_UI_STATE: LazyLock<RwLock<Option<IpcAppState>>> =
-    LazyLock::new(|| RwLock::const_new(Some(create_default_app_state())));
+    LazyLock::new(|| RwLock::const_new(None));
 
 pub async fn get_app_ui_state() -> IpcAppState {
     let state = APP_UI_STATE.read().await;
     state.clone().unwrap_or_else(create_default_app_state)
 }
 
+/// Apply `ui.locale` of the daemon's config, the UI stays in English until it is known
+async fn load_locale() {
+    match ipc::send_rpc_request::<ipc::RpcResult<DballConfig>>(RpcService::GetConfig).await {
+        Ok(Ok(config)) => i18n::set_locale(config.ui.locale),
+        Err(e) | Ok(Err(e)) => log::warn!("Failed to load the UI language: {e}"),
+    }
+}
+
 #[component]
 pub fn DballApp(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
     let mut init_once = hooks.use_state(|| false);
@@ -63,11 +84,19 @@ pub fn DballApp(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
 
     let mut system = hooks.use_context_mut::<SystemContext>();
     let mut should_exit = hooks.use_state(|| false);
+    let mut show_settings = hooks.use_state(|| false);
+    let mut modal_synced = hooks.use_state(|| false);
+    let (width, height) = hooks.use_terminal_size();
 
     // Initialize IPC client and state subscriber
     hooks.use_future(async move {
+        load_locale().await;
+
         // Create state subscriber
         let subscriber = StateSubscriber::new();
+        if let Err(e) = ipc::start_state_subscription(&subscriber).await {
+            log::error!("Failed to start state subscription: {e}");
+        }
         // Listen to state changes
         let mut receiver = subscriber.subscribe_to_changes();
 
@@ -86,12 +115,31 @@ pub fn DballApp(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
     hooks.use_terminal_events({
         move |event| match event {
             TerminalEvent::Key(KeyEvent {
-                code: KeyCode::Char('q' | 'Q') | KeyCode::Esc,
+                code: KeyCode::Char('q' | 'Q'),
+                kind,
+                ..
+            }) if kind != KeyEventKind::Release && !input::input_captured() => {
+                should_exit.set(true);
+            }
+            // Esc cancels a running operation instead of quitting
+            TerminalEvent::Key(KeyEvent {
+                code: KeyCode::Esc,
                 kind,
                 ..
-            }) if kind != KeyEventKind::Release => {
+            }) if kind != KeyEventKind::Release
+                && !input::input_captured()
+                && !input::operation_running() =>
+            {
                 should_exit.set(true);
             }
+            // Press S to open settings
+            TerminalEvent::Key(KeyEvent {
+                code: KeyCode::Char('s' | 'S'),
+                kind,
+                ..
+            }) if kind != KeyEventKind::Release && !input::input_captured() => {
+                show_settings.set(true);
+            }
             _ => {}
         }
     });
@@ -100,9 +148,28 @@ pub fn DballApp(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
         system.exit();
     }
 
-    element! {
-
-            MainLayout()
+    // Synced while rendering so every key handler of one event sees the same capture
+    if modal_synced.get() != show_settings.get() {
+        input::set_modal_open(show_settings.get());
+        modal_synced.set(show_settings.get());
+    }
 
+    if show_settings.get() {
+        element! {
+            View(
+                width,
+                height,
+                background_color: Color::Black,
+                padding: 1,
+            ) {
+                SettingsLayout(on_close: move |()| show_settings.set(false))
+            }
+        }
+    } else {
+        element! {
+            View {
+                MainLayout()
+            }
+        }
     }
 }
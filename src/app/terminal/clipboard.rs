@@ -0,0 +1,24 @@
+use std::sync::{LazyLock, Mutex};
+
+use arboard::Clipboard;
+
+/// Kept alive for the whole session, on Linux the copied text is served by its owner
+static CLIPBOARD: LazyLock<Mutex<Option<Clipboard>>> = LazyLock::new(|| {
+    Mutex::new(
+        Clipboard::new()
+            .inspect_err(|e| log::warn!("System clipboard unavailable: {e}"))
+            .ok(),
+    )
+});
+
+pub(crate) fn copy_text(text: &str) -> anyhow::Result<()> {
+    let mut clipboard = CLIPBOARD
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Clipboard lock poisoned: {e}"))?;
+    let clipboard = clipboard
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("System clipboard unavailable"))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| anyhow::anyhow!("Failed to copy to clipboard: {e}"))
+}
@@ -1,10 +1,16 @@
 use dball_combora::dball::{DBall, DBallBatch};
 use iocraft::prelude::*;
 
-use crate::terminal::ipc::send_rpc_request;
+use crate::terminal::ipc::{RpcResult, send_rpc_request};
 
 pub(crate) mod dball;
+pub(crate) mod error_modal;
+pub(crate) mod progress;
+pub(crate) mod search;
 pub(crate) mod spot;
+pub(crate) mod spot_list;
+pub(crate) mod status_bar;
+pub(crate) mod toast;
 
 /// Status panel component
 #[component]
@@ -12,7 +18,7 @@ pub fn StatusPanel(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
     let mut latest_unprize_spots = hooks.use_state(|| DBallBatch(vec![]));
 
     hooks.use_future(async move {
-        match send_rpc_request::<Result<Vec<DBall>, String>>(
+        match send_rpc_request::<RpcResult<Vec<DBall>>>(
             dball_client::ipc::RpcService::GetUnprizeSpots,
         )
         .await
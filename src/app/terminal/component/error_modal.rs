@@ -0,0 +1,113 @@
+use dball_client::ipc::RpcError;
+use iocraft::prelude::*;
+
+use crate::app::i18n;
+use crate::terminal::{input, strings::Msg};
+
+const MODAL_WIDTH: u16 = 64;
+
+/// A failed request shown in the error modal, along with how the view sends it again
+#[derive(Clone)]
+pub(crate) struct ErrorDialog<R> {
+    failure: Option<(RpcError, R)>,
+    /// Whether this dialog currently holds the global input capture
+    capturing: bool,
+}
+
+impl<R> Default for ErrorDialog<R> {
+    fn default() -> Self {
+        Self {
+            failure: None,
+            capturing: false,
+        }
+    }
+}
+
+impl<R> ErrorDialog<R> {
+    pub(crate) fn show(&mut self, error: RpcError, retry: R) {
+        self.failure = Some((error, retry));
+    }
+
+    pub(crate) fn is_open(&self) -> bool {
+        self.failure.is_some()
+    }
+
+    pub(crate) fn error(&self) -> Option<RpcError> {
+        self.failure.as_ref().map(|(error, _)| error.clone())
+    }
+
+    /// Handle a key while open, `Enter` or `R` returns the request to retry and `Esc` dismisses
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> Option<R> {
+        match code {
+            KeyCode::Enter | KeyCode::Char('r' | 'R') => {
+                self.failure.take().map(|(_, retry)| retry)
+            }
+            KeyCode::Esc => {
+                self.failure = None;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn capture_outdated(&self, focused: bool) -> bool {
+        self.capturing != (focused && self.is_open())
+    }
+
+    /// Sync the global modal flag with the open state, only the focused view holds it
+    pub(crate) fn sync_capture(&mut self, focused: bool) {
+        let open = focused && self.is_open();
+        input::set_modal_open(open);
+        self.capturing = open;
+    }
+}
+
+#[derive(Default, Props)]
+pub struct ErrorModalProps {
+    pub error: Option<RpcError>,
+}
+
+/// Details of a failed request drawn over the view, with a retry suggestion for transient errors
+#[component]
+pub fn ErrorModal(
+    _hooks: Hooks<'_, '_>,
+    props: &ErrorModalProps,
+) -> impl Into<AnyElement<'static>> {
+    let Some(error) = &props.error else {
+        return element!(View).into_any();
+    };
+
+    let request_id = error.request_id.as_deref().unwrap_or("-").to_owned();
+    let (hint, hint_color) = match i18n::retry_hint(error.code) {
+        Some(hint) => (hint, Color::Yellow),
+        None => (Msg::HintNone.text(), Color::DarkGrey),
+    };
+
+    element! {
+        View(
+            position: Position::Absolute,
+            top: 4,
+            left: 2,
+            width: MODAL_WIDTH,
+            flex_direction: FlexDirection::Column,
+            border_style: BorderStyle::Double,
+            border_color: Color::Red,
+            background_color: Color::Black,
+            padding_left: 1,
+            padding_right: 1,
+        ) {
+            Text(content: Msg::RequestFailed.text(), color: Color::Red, weight: Weight::Bold)
+            Text(content: Msg::ErrorCode.fill(&[("code", &error.code.as_str())]), color: Color::White)
+            Text(content: Msg::ErrorMessage.fill(&[("message", &error.message)]), color: Color::White)
+            Text(content: Msg::ErrorRequest.fill(&[("id", &request_id)]), color: Color::DarkGrey)
+            View(margin_top: 1) {
+                Text(content: hint, color: hint_color)
+            }
+            View(margin_top: 1, gap: 2) {
+                Text(content: Msg::RetryButton.text(), color: Color::Green, weight: Weight::Bold)
+                Text(content: Msg::DismissButton.text(), color: Color::DarkGrey)
+            }
+        }
+    }
+    .into_any()
+}
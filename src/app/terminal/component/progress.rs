@@ -0,0 +1,226 @@
+use std::time::{Duration, Instant};
+
+use dball_client::{
+    ipc::RpcService,
+    service::{DaemonEvent, OperationProgress},
+};
+use iocraft::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::terminal::{
+    ipc::{self, RpcResult, send_rpc_request},
+    strings::Msg,
+};
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const BAR_WIDTH: usize = 20;
+
+/// A long-running request a view is waiting on
+#[derive(Clone)]
+pub(crate) struct RunningOperation {
+    pub(crate) label: String,
+    pub(crate) started: Instant,
+    /// Latest progress report of the daemon, `None` until the first one arrives
+    pub(crate) progress: Option<OperationProgress>,
+    pub(crate) cancelling: bool,
+}
+
+impl RunningOperation {
+    pub(crate) fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            started: Instant::now(),
+            progress: None,
+            cancelling: false,
+        }
+    }
+
+    /// Mark the operation as cancelling, returns the id to cancel with
+    ///
+    /// The id is known once the daemon reported progress, `None` before that or when already cancelling
+    pub(crate) fn start_cancel(&mut self) -> Option<String> {
+        if self.cancelling {
+            return None;
+        }
+        let id = self.progress.as_ref().map(|progress| progress.id.clone())?;
+        self.cancelling = true;
+        Some(id)
+    }
+}
+
+/// `[###---] step/total`, the step capped at a `total` above 0
+fn progress_bar(step: usize, total: usize) -> String {
+    let step = step.min(total);
+    let filled = step * BAR_WIDTH / total;
+    format!(
+        "[{}{}] {step}/{total}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+    )
+}
+
+fn progress_line(progress: &OperationProgress) -> String {
+    match progress.total {
+        Some(total) if total > 0 => {
+            format!(
+                "{} {}",
+                progress_bar(progress.step, total),
+                progress.message
+            )
+        }
+        _ => Msg::ProgressStep.fill(&[
+            ("step", &(progress.step + 1)),
+            ("message", &progress.message),
+        ]),
+    }
+}
+
+#[derive(Default, Props)]
+pub struct ProgressIndicatorProps {
+    pub operation: Option<RunningOperation>,
+}
+
+/// Spinner with elapsed time and the latest progress report, shown while an operation runs
+#[component]
+pub fn ProgressIndicator(
+    mut hooks: Hooks<'_, '_>,
+    props: &ProgressIndicatorProps,
+) -> impl Into<AnyElement<'static>> {
+    let mut frame = hooks.use_state(|| 0usize);
+
+    // Keeps spinning while the daemon is busy, a frozen spinner means a stuck client
+    hooks.use_future(async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            frame.set((frame.get() + 1) % SPINNER_FRAMES.len());
+        }
+    });
+
+    let Some(operation) = props.operation.as_ref() else {
+        return element! { View }.into_any();
+    };
+
+    let elapsed = operation.started.elapsed().as_secs();
+    let detail = operation
+        .progress
+        .as_ref()
+        .map(progress_line)
+        .unwrap_or_else(|| Msg::WaitingForDaemon.text().to_owned());
+    let (hint, hint_color) = if operation.cancelling {
+        (Msg::Cancelling.text(), Color::Red)
+    } else if operation.progress.is_some() {
+        (Msg::CancelHint.text(), Color::DarkGrey)
+    } else {
+        ("", Color::DarkGrey)
+    };
+
+    element! {
+        View(flex_direction: FlexDirection::Column) {
+            Text(
+                content: format!(
+                    "{} {} {}:{:02}",
+                    SPINNER_FRAMES[frame.get()],
+                    operation.label,
+                    elapsed / 60,
+                    elapsed % 60
+                ),
+                color: Color::Yellow,
+                weight: Weight::Bold,
+            )
+            Text(content: detail, color: Color::White)
+            Text(content: hint, color: hint_color)
+        }
+    }
+    .into_any()
+}
+
+/// Ask the daemon to cancel an operation, it stops at its next checkpoint
+pub(crate) async fn request_cancel(id: String) -> RpcResult<()> {
+    if send_rpc_request::<RpcResult<bool>>(RpcService::CancelOperation(id)).await?? {
+        log::info!("Cancel requested");
+    } else {
+        log::warn!("Operation finished before it could be cancelled");
+    }
+    Ok(())
+}
+
+/// Latest `CrawlProgress` event of the year crawl the daemon runs
+#[derive(Clone)]
+struct YearCrawl {
+    year: usize,
+    period: String,
+    total: usize,
+    inserted: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+/// Progress bar of the year crawl the daemon runs, whoever started it, hidden
+/// while none runs
+#[component]
+pub fn CrawlProgressBar(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+    let mut crawl = hooks.use_state(|| None::<YearCrawl>);
+
+    hooks.use_future(async move {
+        let mut receiver = ipc::subscribe_daemon_events().await;
+        loop {
+            match receiver.recv().await {
+                Ok(DaemonEvent::CrawlProgress {
+                    year,
+                    period,
+                    total,
+                    inserted,
+                    skipped,
+                    failed,
+                    finished,
+                }) => crawl.set((!finished).then_some(YearCrawl {
+                    year,
+                    period,
+                    total,
+                    inserted,
+                    skipped,
+                    failed,
+                })),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    log::debug!(
+                        "Missed {skipped} daemon events, waiting for the next crawl progress"
+                    );
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let Some(crawl) = crawl.read().clone() else {
+        return element! { View }.into_any();
+    };
+
+    let counts = Msg::CrawlCounts.fill(&[
+        ("inserted", &crawl.inserted),
+        ("skipped", &crawl.skipped),
+        ("failed", &crawl.failed),
+    ]);
+    // periods probed past the last draw may outnumber the missing ones
+    let handled = crawl.inserted + crawl.skipped + crawl.failed;
+    let detail = if crawl.total > 0 {
+        format!("{} {counts}", progress_bar(handled, crawl.total))
+    } else {
+        counts
+    };
+
+    element! {
+        View(flex_direction: FlexDirection::Column) {
+            Text(
+                content: Msg::CrawlingYear.fill(&[("year", &crawl.year), ("period", &crawl.period)]),
+                color: Color::Yellow,
+                weight: Weight::Bold,
+            )
+            Text(
+                content: detail,
+                color: if crawl.failed > 0 { Color::Red } else { Color::White },
+            )
+        }
+    }
+    .into_any()
+}
@@ -0,0 +1,116 @@
+use dball_client::models::SpotQuery;
+use iocraft::prelude::*;
+
+use crate::terminal::{input, strings::Msg};
+
+/// Search input state owned by a list view
+#[derive(Clone, Default)]
+pub(crate) struct SearchInput {
+    pub(crate) editing: bool,
+    pub(crate) text: String,
+    pub(crate) error: Option<String>,
+    /// Query currently applied to the list, along with the text it was parsed from
+    pub(crate) applied: Option<(String, SpotQuery)>,
+    /// Whether this input currently holds the global input capture
+    capturing: bool,
+}
+
+pub(crate) enum SearchAction {
+    None,
+    Apply(SpotQuery),
+    Clear,
+}
+
+impl SearchInput {
+    pub(crate) fn start(&mut self) {
+        self.editing = true;
+        self.error = None;
+        self.text = self
+            .applied
+            .as_ref()
+            .map(|(text, _)| text.clone())
+            .unwrap_or_default();
+    }
+
+    /// Handle a key while editing, `Enter` applies and `Esc` cancels
+    pub(crate) fn handle_key(&mut self, code: KeyCode) -> SearchAction {
+        match code {
+            KeyCode::Char(c) => {
+                self.text.push(c);
+                SearchAction::None
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+                SearchAction::None
+            }
+            KeyCode::Esc => {
+                self.editing = false;
+                self.error = None;
+                SearchAction::None
+            }
+            KeyCode::Enter => match self.text.parse::<SpotQuery>() {
+                Ok(query) if query.is_unfiltered() => {
+                    self.editing = false;
+                    self.applied = None;
+                    SearchAction::Clear
+                }
+                Ok(query) => {
+                    self.editing = false;
+                    self.applied = Some((self.text.trim().to_owned(), query.clone()));
+                    SearchAction::Apply(query)
+                }
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    SearchAction::None
+                }
+            },
+            _ => SearchAction::None,
+        }
+    }
+
+    pub(crate) fn query(&self) -> Option<SpotQuery> {
+        self.applied.as_ref().map(|(_, query)| query.clone())
+    }
+
+    pub(crate) fn capture_outdated(&self) -> bool {
+        self.capturing != self.editing
+    }
+
+    /// Sync the global input capture with the editing state,
+    /// called while rendering so every key handler of one event sees the same value
+    pub(crate) fn sync_capture(&mut self) {
+        input::set_text_captured(self.editing);
+        self.capturing = self.editing;
+    }
+}
+
+#[derive(Default, Props)]
+pub struct SearchBarProps {
+    pub editing: bool,
+    pub text: String,
+    pub error: Option<String>,
+    pub applied: Option<String>,
+}
+
+/// One line search bar, shows the input while editing and the applied filter otherwise
+#[component]
+pub fn SearchBar(_hooks: Hooks<'_, '_>, props: &SearchBarProps) -> impl Into<AnyElement<'static>> {
+    let (content, color) = if props.editing {
+        match &props.error {
+            Some(error) => (format!("/{} - {error}", props.text), Color::Red),
+            None => (format!("/{}_", props.text), Color::White),
+        }
+    } else {
+        match &props.applied {
+            Some(applied) => (
+                Msg::SearchFilter.fill(&[("filter", applied)]),
+                Color::Magenta,
+            ),
+            None => (Msg::SearchHint.text().to_owned(), Color::DarkGrey),
+        }
+    };
+
+    element! {
+        Text(content, color)
+    }
+}
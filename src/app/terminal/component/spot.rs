@@ -1,6 +1,8 @@
-use dball_client::models::Spot;
+use dball_client::models::{DEFAULT_PROFILE_ID, Spot};
 use iocraft::prelude::*;
 
+use crate::terminal::strings::Msg;
+
 #[derive(Props)]
 pub struct SpotProps {
     pub value: Spot,
@@ -25,6 +27,12 @@ impl Default for SpotProps {
                 magnification: 1,
                 prize_status: Some(0),
                 deprecated: false,
+                note: None,
+                purchased: false,
+                extra_reds: None,
+                extra_blues: None,
+                bankers: None,
+                profile_id: DEFAULT_PROFILE_ID,
                 created_time: now,
                 modified_time: now,
             },
@@ -37,25 +45,35 @@ impl Default for SpotProps {
 pub fn SpotComponent(_hooks: Hooks<'_, '_>, props: &SpotProps) -> impl Into<AnyElement<'static>> {
     let spot = &props.value;
 
-    let red_balls = [
-        spot.red1, spot.red2, spot.red3, spot.red4, spot.red5, spot.red6,
-    ];
-    let red_balls_str = red_balls
+    let red_balls_str = spot
+        .red_numbers()
         .iter()
         .map(|&ball| format!("{ball:02}"))
         .collect::<Vec<_>>()
         .join(",");
 
-    let blue_ball_str = format!("{:02}", spot.blue);
+    let blue_ball_str = spot
+        .blue_numbers()
+        .iter()
+        .map(|&ball| format!("{ball:02}"))
+        .collect::<Vec<_>>()
+        .join(",");
 
     let multiplier_str = format!("×{}", spot.magnification);
 
     let (status_text, status_color) = spot_status(spot);
+    let purchased_str = if spot.purchased { " $" } else { "" };
+    let note_str = spot
+        .note
+        .as_ref()
+        .map(|note| format!(" {note}"))
+        .unwrap_or_default();
 
     element! {
         View(
             flex_direction: FlexDirection::Row,
             align_items: AlignItems::Center,
+            background_color: if props.has_focus { Some(Color::DarkGrey) } else { None },
         ) {
             Text(content: format!("[{}]", spot.period), color: Color::Cyan)
             Text(content: " ", color: Color::White)
@@ -66,6 +84,8 @@ pub fn SpotComponent(_hooks: Hooks<'_, '_>, props: &SpotProps) -> impl Into<AnyE
             Text(content: multiplier_str, color: Color::Yellow)
             Text(content: " - ", color: Color::White)
             Text(content: status_text, color: status_color, weight: Weight::Bold)
+            Text(content: purchased_str, color: Color::Green, weight: Weight::Bold)
+            Text(content: note_str, color: Color::DarkGrey)
         }
     }
 }
@@ -74,7 +94,7 @@ pub(crate) fn spot_status(spot: &Spot) -> (String, Color) {
     if let Some(prize_status) = spot.prize_status {
         if prize_status > 0 {
             (
-                format!("hit#{prize_status}"),
+                Msg::SpotHit.fill(&[("status", &prize_status)]),
                 if spot.deprecated {
                     Color::DarkMagenta
                 } else {
@@ -83,7 +103,7 @@ pub(crate) fn spot_status(spot: &Spot) -> (String, Color) {
             )
         } else {
             (
-                "non-prize".to_owned(),
+                Msg::SpotNoPrize.text().to_owned(),
                 if spot.deprecated {
                     Color::White
                 } else {
@@ -92,6 +112,6 @@ pub(crate) fn spot_status(spot: &Spot) -> (String, Color) {
             )
         }
     } else {
-        ("pending".to_owned(), Color::Yellow)
+        (Msg::SpotPending.text().to_owned(), Color::Yellow)
     }
 }
@@ -0,0 +1,314 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use chrono::TimeDelta;
+use dball_client::models::Spot;
+use iocraft::prelude::*;
+
+use crate::terminal::strings::Msg;
+
+/// Spots of a period created further apart than this belong to different batches
+const BATCH_GAP: TimeDelta = TimeDelta::seconds(60);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum SpotSort {
+    #[default]
+    Created,
+    Period,
+    Prize,
+    Cost,
+}
+
+impl SpotSort {
+    fn next(self) -> Self {
+        match self {
+            Self::Created => Self::Period,
+            Self::Period => Self::Prize,
+            Self::Prize => Self::Cost,
+            Self::Cost => Self::Created,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Created => Msg::SortCreated,
+            Self::Period => Msg::SortPeriod,
+            Self::Prize => Msg::SortPrize,
+            Self::Cost => Msg::SortCost,
+        }
+        .text()
+    }
+
+    /// Ascending order, ties keep the order the daemon sent
+    fn compare(self, a: &Spot, b: &Spot) -> Ordering {
+        match self {
+            Self::Created => a.created_time.cmp(&b.created_time),
+            Self::Period => a.period.cmp(&b.period),
+            // `prize_status` is the prize amount, unsettled spots rank below losing ones
+            Self::Prize => a.prize_status.cmp(&b.prize_status),
+            Self::Cost => spot_cost(a).cmp(&spot_cost(b)),
+        }
+    }
+}
+
+fn spot_cost(spot: &Spot) -> usize {
+    spot.cost().unwrap_or(0)
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum SpotGrouping {
+    #[default]
+    None,
+    Batch,
+    Period,
+}
+
+impl SpotGrouping {
+    fn next(self) -> Self {
+        match self {
+            Self::None => Self::Batch,
+            Self::Batch => Self::Period,
+            Self::Period => Self::None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::None => Msg::GroupNone,
+            Self::Batch => Msg::GroupBatch,
+            Self::Period => Msg::GroupPeriod,
+        }
+        .text()
+    }
+}
+
+/// A line of a sorted and grouped spot list
+pub(crate) enum SpotRow<'a> {
+    Group {
+        key: String,
+        label: String,
+        spots: usize,
+        collapsed: bool,
+    },
+    Spot(&'a Spot),
+}
+
+/// Sorting, grouping and collapsed groups of a spot list, owned by the list view
+#[derive(Clone)]
+pub(crate) struct SpotListView {
+    pub(crate) sort: SpotSort,
+    pub(crate) descending: bool,
+    pub(crate) grouping: SpotGrouping,
+    collapsed: HashSet<String>,
+}
+
+impl Default for SpotListView {
+    fn default() -> Self {
+        Self {
+            sort: SpotSort::default(),
+            descending: true,
+            grouping: SpotGrouping::default(),
+            collapsed: HashSet::new(),
+        }
+    }
+}
+
+impl SpotListView {
+    /// Handle a list key, `O` cycles the sort key, `V` reverses it, `B` cycles the grouping
+    /// and `Space` collapses or expands `cursor_group`, the key of the header under the cursor
+    ///
+    /// Returns whether the key was handled
+    pub(crate) fn handle_key(&mut self, code: KeyCode, cursor_group: Option<&str>) -> bool {
+        match code {
+            KeyCode::Char('o' | 'O') => self.sort = self.sort.next(),
+            KeyCode::Char('v' | 'V') => self.descending = !self.descending,
+            KeyCode::Char('b' | 'B') => {
+                self.grouping = self.grouping.next();
+                self.collapsed.clear();
+            }
+            KeyCode::Char(' ') => {
+                let Some(key) = cursor_group else {
+                    return false;
+                };
+                if !self.collapsed.remove(key) {
+                    self.collapsed.insert(key.to_owned());
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub(crate) fn sort_label(&self) -> String {
+        let direction = if self.descending {
+            Msg::Descending
+        } else {
+            Msg::Ascending
+        };
+        Msg::SortLabel.fill(&[
+            ("key", &self.sort.label()),
+            ("direction", &direction.text()),
+        ])
+    }
+
+    /// One line summary of the current sorting and grouping
+    pub(crate) fn label(&self) -> String {
+        Msg::ViewLabel.fill(&[
+            ("sort", &self.sort_label()),
+            ("group", &self.grouping.label()),
+        ])
+    }
+
+    pub(crate) fn is_collapsed(&self, key: &str) -> bool {
+        self.collapsed.contains(key)
+    }
+
+    /// `spots` in sorted order, ignoring the grouping
+    pub(crate) fn sort_spots<'a>(&self, spots: &'a [Spot]) -> Vec<&'a Spot> {
+        self.sorted(spots)
+            .into_iter()
+            .map(|idx| &spots[idx])
+            .collect()
+    }
+
+    /// Indices of `spots` in sorted order
+    fn sorted(&self, spots: &[Spot]) -> Vec<usize> {
+        let mut sorted = (0..spots.len()).collect::<Vec<_>>();
+        sorted.sort_by(|&a, &b| {
+            let order = self.sort.compare(&spots[a], &spots[b]);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        sorted
+    }
+
+    /// Rows of `spots` in display order, spots of collapsed groups are left out
+    pub(crate) fn rows<'a>(&self, spots: &'a [Spot]) -> Vec<SpotRow<'a>> {
+        let sorted = self.sorted(spots);
+        let keys = match self.grouping {
+            SpotGrouping::None => {
+                return sorted
+                    .into_iter()
+                    .map(|idx| SpotRow::Spot(&spots[idx]))
+                    .collect();
+            }
+            SpotGrouping::Period => spots
+                .iter()
+                .map(|spot| {
+                    let label = Msg::PeriodGroup.fill(&[("period", &spot.period)]);
+                    (spot.period.clone(), label)
+                })
+                .collect::<Vec<_>>(),
+            SpotGrouping::Batch => batch_keys(spots),
+        };
+
+        // groups are ordered by their first spot in sorted order
+        let mut groups: Vec<(String, String, Vec<&Spot>)> = Vec::new();
+        let mut group_index = HashMap::new();
+        for idx in sorted {
+            let (key, label) = &keys[idx];
+            let group = *group_index.entry(key.clone()).or_insert_with(|| {
+                groups.push((key.clone(), label.clone(), Vec::new()));
+                groups.len() - 1
+            });
+            groups[group].2.push(&spots[idx]);
+        }
+
+        groups
+            .into_iter()
+            .flat_map(|(key, label, spots)| {
+                let collapsed = self.collapsed.contains(&key);
+                let header = SpotRow::Group {
+                    key,
+                    label,
+                    spots: spots.len(),
+                    collapsed,
+                };
+                let spots = if collapsed { Vec::new() } else { spots };
+                std::iter::once(header).chain(spots.into_iter().map(SpotRow::Spot))
+            })
+            .collect()
+    }
+}
+
+/// Key and label of the batch of every spot, in the order of `spots`
+///
+/// Spots carry no batch id, a batch is a run of spots of one period created close together
+fn batch_keys(spots: &[Spot]) -> Vec<(String, String)> {
+    let mut by_created = (0..spots.len()).collect::<Vec<_>>();
+    by_created.sort_by(|&a, &b| {
+        spots[a]
+            .period
+            .cmp(&spots[b].period)
+            .then(spots[a].created_time.cmp(&spots[b].created_time))
+    });
+
+    let mut keys = vec![(String::new(), String::new()); spots.len()];
+    let mut current: Option<(&Spot, (String, String))> = None;
+    let mut batch_number = 0;
+    for idx in by_created {
+        let spot = &spots[idx];
+        let batch = match current.take() {
+            Some((previous, batch))
+                if previous.period == spot.period
+                    && spot.created_time - previous.created_time <= BATCH_GAP =>
+            {
+                batch
+            }
+            previous => {
+                batch_number = match previous {
+                    Some((previous, _)) if previous.period == spot.period => batch_number + 1,
+                    _ => 1,
+                };
+                (
+                    format!("{}@{}", spot.period, spot.created_time),
+                    Msg::BatchGroup.fill(&[
+                        ("period", &spot.period),
+                        ("batch", &batch_number),
+                        ("time", &spot.formatted_created_time()),
+                    ]),
+                )
+            }
+        };
+        keys[idx] = batch.clone();
+        current = Some((spot, batch));
+    }
+    keys
+}
+
+#[derive(Default, Props)]
+pub struct SpotGroupHeaderProps {
+    pub label: String,
+    pub spots: usize,
+    pub collapsed: bool,
+    pub has_focus: bool,
+}
+
+/// Header line of a group of spots, `Space` on it collapses or expands the group
+#[component]
+pub fn SpotGroupHeader(
+    _hooks: Hooks<'_, '_>,
+    props: &SpotGroupHeaderProps,
+) -> impl Into<AnyElement<'static>> {
+    let marker = if props.collapsed { "▶" } else { "▼" };
+    let color = if props.has_focus {
+        Color::Yellow
+    } else {
+        Color::Cyan
+    };
+
+    element! {
+        Text(
+            content: Msg::GroupHeader.fill(&[
+                ("marker", &marker),
+                ("label", &props.label),
+                ("count", &props.spots),
+            ]),
+            color,
+            weight: Weight::Bold,
+        )
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{TimeDelta, Utc};
+use dball_client::ipc::{client::client::ClientState, protocol::AppState};
+use iocraft::prelude::*;
+
+use crate::terminal::{APP_UI_STATE, ipc::get_ipc_client_state, strings::Msg};
+
+fn format_countdown(remaining: TimeDelta) -> String {
+    let secs = remaining.num_seconds();
+    if secs <= 0 {
+        return Msg::DrawingNow.text().to_owned();
+    }
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    );
+    if days > 0 {
+        Msg::CountdownDays.fill(&[("days", &days), ("clock", &clock)])
+    } else {
+        clock
+    }
+}
+
+pub(crate) fn connection_label(state: Option<&ClientState>) -> (String, Color) {
+    match state {
+        Some(ClientState::Connected | ClientState::Authenticated | ClientState::Subscribed) => {
+            (Msg::Connected.text().to_owned(), Color::Green)
+        }
+        Some(ClientState::Connecting) | None => (Msg::Connecting.text().to_owned(), Color::Yellow),
+        Some(ClientState::Disconnected) => (Msg::Disconnected.text().to_owned(), Color::Red),
+        Some(ClientState::Error(e)) => (Msg::ConnectionError.fill(&[("error", e)]), Color::Red),
+    }
+}
+
+/// One line header with periods, draw countdown, unprized count and daemon connection,
+/// derived from the state the daemon pushes to the subscription
+#[component]
+pub fn StatusBar(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+    let mut app_state = hooks.use_state(|| None::<AppState>);
+    let mut connection = hooks.use_state(|| None::<ClientState>);
+    let mut now = hooks.use_state(Utc::now);
+
+    hooks.use_future(async move {
+        loop {
+            app_state.set(APP_UI_STATE.read().await.clone());
+            connection.set(Some(get_ipc_client_state().await));
+            now.set(Utc::now());
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+
+    let (connection_text, connection_color) = connection_label(connection.read().as_ref());
+
+    let details: Vec<AnyElement<'static>> = match &*app_state.read() {
+        Some(state) => {
+            let countdown = state
+                .next_draw_time
+                .map(|time| format_countdown(time - now.get()))
+                .unwrap_or_else(|| "--".to_owned());
+            vec![
+                element! {
+                    Text(
+                        content: Msg::StatusPeriods.fill(&[
+                            ("current", &state.current_period),
+                            ("next", &state.next_period),
+                        ]),
+                        color: Color::Cyan,
+                        weight: Weight::Bold,
+                    )
+                }
+                .into(),
+                element! {
+                    Text(
+                        content: Msg::StatusDrawIn.fill(&[("countdown", &countdown)]),
+                        color: Color::Yellow,
+                    )
+                }
+                .into(),
+                element! {
+                    Text(
+                        content: Msg::StatusUnprized
+                            .fill(&[("count", &state.unprize_spots_count)]),
+                        color: Color::White,
+                    )
+                }
+                .into(),
+            ]
+        }
+        None => vec![
+            element! {
+                Text(content: format!("{} ", Msg::WaitingForState.text()), color: Color::DarkGrey)
+            }
+            .into(),
+        ],
+    };
+
+    element! {
+        View(
+            height: 1,
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+        ) {
+            View(flex_direction: FlexDirection::Row) {
+                Fragment(children: details)
+            }
+            Text(content: connection_text, color: connection_color, weight: Weight::Bold)
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use dball_client::{
+    config::{ConfigIssue, DballConfig},
+    ipc::{RpcError, RpcErrorCode},
+    service::DaemonEvent,
+};
+use iocraft::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::terminal::{
+    input,
+    ipc::{self, RpcResult, send_rpc_request},
+    strings::Msg,
+};
+
+const TOAST_LIFETIME: Duration = Duration::from_secs(6);
+const MAX_TOASTS: usize = 4;
+const TOAST_WIDTH: u16 = 44;
+
+#[derive(Clone)]
+struct Toast {
+    text: String,
+    color: Color,
+    shown_at: Instant,
+}
+
+fn push_toast(toasts: &mut State<Vec<Toast>>, text: String, color: Color) {
+    let mut toasts = toasts.write();
+    toasts.push(Toast {
+        text,
+        color,
+        shown_at: Instant::now(),
+    });
+    let overflow = toasts.len().saturating_sub(MAX_TOASTS);
+    toasts.drain(..overflow);
+}
+
+/// Toast of `event`, `None` for the progress of a crawl until it finishes
+fn event_toast(event: DaemonEvent) -> Option<(String, Color)> {
+    let toast = match event {
+        DaemonEvent::DrawSettled { period, spots } => (
+            Msg::ToastDrawSettled.fill(&[("period", &period), ("spots", &spots)]),
+            Color::Cyan,
+        ),
+        DaemonEvent::PrizeWon {
+            period,
+            tier,
+            amount,
+        } => (
+            Msg::ToastPrizeWon.fill(&[("tier", &tier), ("period", &period), ("amount", &amount)]),
+            Color::Green,
+        ),
+        DaemonEvent::PeriodEvaluated {
+            period,
+            spots,
+            winnings,
+        } => (
+            Msg::ToastPeriodEvaluated.fill(&[
+                ("period", &period),
+                ("spots", &spots),
+                ("winnings", &winnings),
+            ]),
+            Color::Cyan,
+        ),
+        DaemonEvent::GenerationFinished { period, spots } => (
+            Msg::ToastGenerated.fill(&[("spots", &spots), ("period", &period)]),
+            Color::Yellow,
+        ),
+        DaemonEvent::ApiFailure { message } => (
+            Msg::ToastApiFailure.fill(&[("message", &message)]),
+            Color::Red,
+        ),
+        DaemonEvent::CrawlProgress {
+            year,
+            inserted,
+            failed,
+            finished: true,
+            ..
+        } => (
+            Msg::ToastCrawlFinished.fill(&[
+                ("year", &year),
+                ("inserted", &inserted),
+                ("failed", &failed),
+            ]),
+            if failed > 0 {
+                Color::Yellow
+            } else {
+                Color::Cyan
+            },
+        ),
+        DaemonEvent::CrawlProgress { .. } => return None,
+    };
+    Some(toast)
+}
+
+async fn load_do_not_disturb() -> RpcResult<bool> {
+    send_rpc_request::<RpcResult<DballConfig>>(dball_client::ipc::RpcService::GetConfig)
+        .await?
+        .map(|config| config.notification.do_not_disturb)
+}
+
+/// Persist the toggle to `dball.toml`, keeping the other settings as they are
+async fn save_do_not_disturb(enabled: bool) -> RpcResult<()> {
+    let mut config =
+        send_rpc_request::<RpcResult<DballConfig>>(dball_client::ipc::RpcService::GetConfig)
+            .await??;
+    config.notification.do_not_disturb = enabled;
+    let issues = send_rpc_request::<RpcResult<Vec<ConfigIssue>>>(
+        dball_client::ipc::RpcService::UpdateConfig(Box::new(config)),
+    )
+    .await??;
+    match issues.first() {
+        Some(issue) => Err(RpcError::new(
+            RpcErrorCode::InvalidRequest,
+            format!("{}: {}", issue.field, issue.message),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Transient notifications for the events pushed by the daemon, drawn over the top right corner
+///
+/// Press Z to toggle do-not-disturb, stored as `notification.do_not_disturb`
+#[component]
+pub fn ToastOverlay(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+    let mut toasts = hooks.use_state(Vec::<Toast>::new);
+    let mut do_not_disturb = hooks.use_state(|| false);
+
+    hooks.use_future(async move {
+        match load_do_not_disturb().await {
+            Ok(enabled) => do_not_disturb.set(enabled),
+            Err(e) => log::warn!("Failed to load do-not-disturb setting: {e}"),
+        }
+
+        let mut receiver = ipc::subscribe_daemon_events().await;
+        loop {
+            match receiver.recv().await {
+                Ok(event) if do_not_disturb.get() => {
+                    log::debug!("Do not disturb, toast suppressed: {event:?}");
+                }
+                Ok(event) => {
+                    if let Some((text, color)) = event_toast(event) {
+                        push_toast(&mut toasts, text, color);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("Missed {skipped} daemon events");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    hooks.use_future(async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let expired = toasts
+                .read()
+                .iter()
+                .any(|toast| toast.shown_at.elapsed() >= TOAST_LIFETIME);
+            if expired {
+                toasts
+                    .write()
+                    .retain(|toast| toast.shown_at.elapsed() < TOAST_LIFETIME);
+            }
+        }
+    });
+
+    let mut toggle_do_not_disturb = hooks.use_async_handler(move |enabled: bool| async move {
+        do_not_disturb.set(enabled);
+        let label = if enabled {
+            Msg::DoNotDisturbOn
+        } else {
+            Msg::DoNotDisturbOff
+        };
+        push_toast(&mut toasts, label.text().to_owned(), Color::White);
+        if let Err(e) = save_do_not_disturb(enabled).await {
+            log::error!("Failed to save do-not-disturb setting: {e}");
+            push_toast(
+                &mut toasts,
+                Msg::SaveFailed.fill(&[("error", &e)]),
+                Color::Red,
+            );
+        }
+    });
+
+    hooks.use_terminal_events(move |event| match event {
+        TerminalEvent::Key(KeyEvent {
+            code: KeyCode::Char('z' | 'Z'),
+            kind,
+            ..
+        }) if kind != KeyEventKind::Release && !input::input_captured() => {
+            toggle_do_not_disturb(!do_not_disturb.get());
+        }
+        _ => {}
+    });
+
+    let toast_elements = toasts
+        .read()
+        .iter()
+        .map(|toast| {
+            element! {
+                View(
+                    width: TOAST_WIDTH,
+                    border_style: BorderStyle::Round,
+                    border_color: toast.color,
+                    background_color: Color::Black,
+                    padding_left: 1,
+                    padding_right: 1,
+                ) {
+                    Text(content: toast.text.clone(), color: toast.color, weight: Weight::Bold)
+                }
+            }
+            .into()
+        })
+        .collect::<Vec<AnyElement<'static>>>();
+
+    element! {
+        View(
+            position: Position::Absolute,
+            top: 2,
+            right: 2,
+            flex_direction: FlexDirection::Column,
+        ) {
+            Fragment(children: toast_elements)
+        }
+    }
+}
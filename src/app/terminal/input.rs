@@ -0,0 +1,37 @@
+//! Keyboard capture shared by all views
+//!
+//! Every view receives every key event, views that take text input or cover the screen
+//! raise a flag here so global shortcuts ignore the keys meant for them
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Set while a text input such as a search bar is being edited
+static TEXT_CAPTURED: AtomicBool = AtomicBool::new(false);
+/// Set while a full screen view such as settings is open
+static MODAL_OPEN: AtomicBool = AtomicBool::new(false);
+/// Number of views waiting on a cancellable operation, `Esc` cancels instead of quitting
+static RUNNING_OPERATIONS: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn input_captured() -> bool {
+    TEXT_CAPTURED.load(Ordering::Relaxed) || MODAL_OPEN.load(Ordering::Relaxed)
+}
+
+pub(crate) fn set_text_captured(captured: bool) {
+    TEXT_CAPTURED.store(captured, Ordering::Relaxed);
+}
+
+pub(crate) fn set_modal_open(open: bool) {
+    MODAL_OPEN.store(open, Ordering::Relaxed);
+}
+
+pub(crate) fn operation_running() -> bool {
+    RUNNING_OPERATIONS.load(Ordering::Relaxed) > 0
+}
+
+pub(crate) fn set_operation_running(running: bool) {
+    if running {
+        RUNNING_OPERATIONS.fetch_add(1, Ordering::Relaxed);
+    } else {
+        RUNNING_OPERATIONS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -1,31 +1,174 @@
-use dball_client::ipc::{
-    RpcService,
-    client::{IpcClient, client::ClientState},
+use std::sync::OnceLock;
+
+use dball_client::{
+    ipc::{
+        AppState, RpcError, RpcErrorCode, RpcService,
+        client::{
+            DaemonTransport, HttpTransport, RpcTransport, StateSubscriber, client::ClientState,
+        },
+    },
+    service::{DaemonEvent, OperationProgress},
 };
 
-pub(crate) type RpcResult<T> = Result<T, String>;
+pub use dball_client::ipc::client::TransportMode;
+
+pub(crate) type RpcResult<T> = Result<T, RpcError>;
+
+/// Interval of polling the daemon state when it can't be subscribed to
+const HTTP_STATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+static TRANSPORT_MODE: OnceLock<TransportMode> = OnceLock::new();
+
+/// Choose the transport, must be called before the first request
+///
+/// Without it the mode is read from `DBALL_TRANSPORT`, defaulting to [`TransportMode::Auto`]
+pub fn set_transport_mode(mode: TransportMode) {
+    if TRANSPORT_MODE.set(mode).is_err() {
+        log::warn!("Transport mode already chosen, ignoring {mode:?}");
+    }
+}
+
+fn transport_mode() -> TransportMode {
+    *TRANSPORT_MODE.get_or_init(|| {
+        std::env::var("DBALL_TRANSPORT")
+            .ok()
+            .and_then(|name| TransportMode::from_name(&name))
+            .unwrap_or_default()
+    })
+}
 
-static IPC_CLIENT: async_lazy::Lazy<IpcClient> = async_lazy::Lazy::new(|| {
+static TRANSPORT: async_lazy::Lazy<RpcResult<DaemonTransport>> = async_lazy::Lazy::new(|| {
     Box::pin(async {
-        IpcClient::new_connected()
+        DaemonTransport::connect(transport_mode(), HttpTransport::from_env())
             .await
-            .expect("Failed to connect to IPC client")
+            .map_err(|e| {
+                RpcError::new(
+                    RpcErrorCode::Transport,
+                    format!("Failed to connect to daemon: {e}"),
+                )
+            })
     })
 });
 
-#[expect(unused)]
+async fn transport() -> RpcResult<&'static DaemonTransport> {
+    TRANSPORT.force().await.as_ref().map_err(Clone::clone)
+}
+
+/// Connect to the daemon ahead of rendering, returns the name of the transport in use
+pub async fn connect_transport() -> anyhow::Result<&'static str> {
+    transport()
+        .await
+        .map(RpcTransport::name)
+        .map_err(anyhow::Error::from)
+}
+
 pub async fn get_ipc_client_state() -> ClientState {
-    let client = IPC_CLIENT.force().await;
-    client.get_state().await
+    match transport().await {
+        Ok(DaemonTransport::Ipc(client)) => client.get_state().await,
+        // every request is a new connection, there is no state to track
+        Ok(DaemonTransport::Http(_)) => ClientState::Connected,
+        Err(e) => ClientState::Error(e.message),
+    }
+}
+
+/// Mirror the daemon state into `subscriber`, pushed over the socket or polled over HTTP
+pub async fn start_state_subscription(subscriber: &StateSubscriber) -> anyhow::Result<()> {
+    match transport().await? {
+        DaemonTransport::Ipc(client) => subscriber.start_subscription(client).await,
+        DaemonTransport::Http(http) => {
+            let subscriber = subscriber.clone();
+            tokio::spawn(async move {
+                loop {
+                    match http
+                        .send_rpc(RpcService::GetCurrentState)
+                        .await
+                        .and_then(|value| {
+                            serde_json::from_value::<AppState>(value).map_err(anyhow::Error::from)
+                        }) {
+                        Ok(state) => {
+                            if let Err(e) = subscriber.update_state(state).await {
+                                log::error!("Failed to update polled state: {e}");
+                            }
+                        }
+                        Err(e) => log::warn!("Failed to poll daemon state: {e}"),
+                    }
+                    tokio::time::sleep(HTTP_STATE_POLL_INTERVAL).await;
+                }
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Receive the events the daemon pushes from now on
+///
+/// Events are only pushed over the socket, over HTTP the receiver is closed right away
+pub async fn subscribe_daemon_events() -> tokio::sync::broadcast::Receiver<DaemonEvent> {
+    match transport().await {
+        Ok(DaemonTransport::Ipc(client)) => client.subscribe_daemon_events(),
+        Ok(DaemonTransport::Http(_)) | Err(_) => {
+            log::debug!("Daemon events are not available without the socket");
+            tokio::sync::broadcast::channel(1).1
+        }
+    }
+}
+
+/// Failure of the transport itself, the request may not have reached the daemon
+fn transport_error(error: &anyhow::Error) -> RpcError {
+    error
+        .downcast_ref::<RpcError>()
+        .cloned()
+        .unwrap_or_else(|| RpcError::new(RpcErrorCode::Transport, format!("{error:#}")))
+}
+
+fn decode_response<T>(response: serde_json::Value) -> RpcResult<T>
+where
+    for<'de> T: serde::Deserialize<'de>,
+{
+    serde_json::from_value::<T>(response).map_err(|e| {
+        RpcError::new(
+            RpcErrorCode::Internal,
+            format!("Unexpected response from the daemon: {e}"),
+        )
+    })
 }
 
 pub async fn send_rpc_request<T>(service: RpcService) -> RpcResult<T>
 where
     for<'de> T: serde::Deserialize<'de>,
 {
-    let client = IPC_CLIENT.force().await;
-    match client.send_rpc_request(service).await {
-        Ok(response) => serde_json::from_value::<T>(response).map_err(|e| e.to_string()),
-        Err(e) => Err(e.to_string()),
+    match transport().await?.send_rpc(service).await {
+        Ok(response) => decode_response(response),
+        Err(e) => Err(transport_error(&e)),
+    }
+}
+
+/// Send a long-running request, `on_progress` is called for every progress report until it completes
+///
+/// Progress is only reported over the socket, over HTTP this is a plain request
+pub async fn send_rpc_request_with_progress<T>(
+    service: RpcService,
+    mut on_progress: impl FnMut(OperationProgress),
+) -> RpcResult<T>
+where
+    for<'de> T: serde::Deserialize<'de>,
+{
+    let client = match transport().await? {
+        DaemonTransport::Ipc(client) => client,
+        DaemonTransport::Http(_) => return send_rpc_request(service).await,
+    };
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let request = client.send_rpc_request_with_progress(service, sender);
+    tokio::pin!(request);
+
+    let response = loop {
+        tokio::select! {
+            response = &mut request => break response,
+            Some(progress) = receiver.recv() => on_progress(progress),
+        }
+    };
+    match response {
+        Ok(response) => decode_response(response),
+        Err(e) => Err(transport_error(&e)),
     }
 }
@@ -1,65 +1,152 @@
 use iocraft::prelude::*;
 
+use crate::terminal::{
+    component::{status_bar::StatusBar, toast::ToastOverlay},
+    input,
+    strings::Msg,
+};
+
+mod daemon;
+mod dashboard;
 mod logs;
 mod middle;
 mod nextgen;
-mod open_status;
+mod report;
+mod settings;
 mod spot_history;
+mod stats;
 
 pub(crate) use logs::init_logger;
+pub(crate) use settings::SettingsLayout;
 
+/// Top-level tabs, every tab stays mounted so it keeps its state while hidden
 #[derive(Clone, Copy, PartialEq, Eq)]
-enum FocusPanel {
-    SpotHistory,
+enum Tab {
+    Dashboard,
+    NextGen,
+    History,
+    Stats,
+    Prized,
+    Daemon,
     Logs,
+    Report,
+}
+
+impl Tab {
+    const ALL: [Self; 8] = [
+        Self::Dashboard,
+        Self::NextGen,
+        Self::History,
+        Self::Stats,
+        Self::Prized,
+        Self::Daemon,
+        Self::Logs,
+        Self::Report,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Dashboard => Msg::TabDashboard,
+            Self::NextGen => Msg::TabNextGen,
+            Self::History => Msg::TabHistory,
+            Self::Stats => Msg::TabStats,
+            Self::Prized => Msg::TabPrized,
+            Self::Daemon => Msg::TabDaemon,
+            Self::Logs => Msg::TabLogs,
+            Self::Report => Msg::TabReport,
+        }
+        .text()
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Tab selected by its number key, `1` being the first tab
+    fn from_key(c: char) -> Option<Self> {
+        let number = c.to_digit(10)? as usize;
+        Self::ALL.get(number.checked_sub(1)?).copied()
+    }
+}
+
+fn tab_bar(active: Tab) -> Vec<AnyElement<'static>> {
+    Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(idx, tab)| {
+            let is_active = *tab == active;
+            element! {
+                View(
+                    margin_right: 1,
+                    background_color: if is_active { Color::Cyan } else { Color::Black },
+                ) {
+                    Text(
+                        content: format!(" {} {} ", idx + 1, tab.title()),
+                        color: if is_active { Color::Black } else { Color::Grey },
+                        weight: if is_active { Weight::Bold } else { Weight::Normal },
+                    )
+                }
+            }
+            .into()
+        })
+        .collect()
 }
 
 /// Main layout component
 #[component]
 pub fn MainLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
-    const LEFT_WIDTH: u16 = 52;
+    const STATUS_BAR_LINES: u16 = 1;
+    const TAB_BAR_LINES: u16 = 1;
     const BORDER_LINES: u16 = 2;
     const PANEL_PADDING: u16 = 2;
-    const SPOT_HISTORY_HEADER_LINES: u16 = 2;
+    const SPOT_HISTORY_HEADER_LINES: u16 = 4;
     const SPOT_HISTORY_MARGIN_LINES: u16 = 1;
     const LOGS_HEADER_LINES: u16 = 1;
     const LOGS_MARGIN_LINES: u16 = 1;
 
     let (width, height) = hooks.use_terminal_size();
-    let focused_panel = hooks.use_state(|| FocusPanel::SpotHistory);
+    let active_tab = hooks.use_state(|| Tab::Dashboard);
 
     // Ensure enough space for display, reserve 1 line each for top and bottom
-    let usable_height = height.saturating_sub(2);
-
-    let left_width = LEFT_WIDTH;
-    let remaining_width = width.saturating_sub(left_width);
-    let center_width = remaining_width / 2;
-    let right_width = remaining_width - center_width;
+    let usable_height = height.saturating_sub(2 + STATUS_BAR_LINES + TAB_BAR_LINES);
 
-    // Left column ratio: 45% top, 55% bottom (optimized ratio to avoid being too tall)
-    let left_top_height = (usable_height * 45) / 100;
-    let left_bottom_height = usable_height - left_top_height;
-
-    // Center column ratio: 70% top, 30% bottom (adjusted ratio to avoid being too tall)
-    let center_top_height = (usable_height * 70) / 100;
-    let center_bottom_height = usable_height - center_top_height;
-
-    let spot_history_list_height = left_bottom_height.saturating_sub(
+    let spot_history_list_height = usable_height.saturating_sub(
         BORDER_LINES + PANEL_PADDING + SPOT_HISTORY_HEADER_LINES + SPOT_HISTORY_MARGIN_LINES,
     );
     let logs_list_height = usable_height
         .saturating_sub(BORDER_LINES + PANEL_PADDING + LOGS_HEADER_LINES + LOGS_MARGIN_LINES);
 
-    let spot_history_focused = *focused_panel.read() == FocusPanel::SpotHistory;
-    let logs_focused = *focused_panel.read() == FocusPanel::Logs;
+    let active = active_tab.get();
+    let display = |tab: Tab| {
+        if tab == active {
+            Display::Flex
+        } else {
+            Display::None
+        }
+    };
 
     hooks.use_terminal_events({
-        let mut focused_panel = focused_panel;
+        let mut active_tab = active_tab;
         move |event| match event {
-            TerminalEvent::Key(KeyEvent { code, kind, .. }) if kind != KeyEventKind::Release => {
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && !input::input_captured() =>
+            {
                 match code {
-                    KeyCode::Left => focused_panel.set(FocusPanel::SpotHistory),
-                    KeyCode::Right => focused_panel.set(FocusPanel::Logs),
+                    KeyCode::Char(c) => {
+                        if let Some(tab) = Tab::from_key(c) {
+                            active_tab.set(tab);
+                        }
+                    }
+                    KeyCode::Tab => active_tab.set(active_tab.get().next()),
+                    KeyCode::BackTab => active_tab.set(active_tab.get().previous()),
                     _ => {}
                 }
             }
@@ -71,90 +158,61 @@ pub fn MainLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
         View(
             width,
             height,
-            flex_direction: FlexDirection::Row,
+            flex_direction: FlexDirection::Column,
             background_color: Color::Black,
             padding: 1,
         ) {
-            // Left column: NextGen + SpotHistory (dynamic width)
+            StatusBar()
+
+            View(height: TAB_BAR_LINES, flex_direction: FlexDirection::Row) {
+                Fragment(children: tab_bar(active))
+            }
+
             View(
-                width: left_width.saturating_sub(1),
                 height: usable_height,
+                border_style: BorderStyle::Round,
+                border_color: Color::Cyan,
+                background_color: Color::Black,
                 flex_direction: FlexDirection::Column,
-                margin_right: 1,
+                padding: 1,
             ) {
-                // NextGen area
-                View(
-                    height: left_top_height.saturating_sub(1),
-                    border_style: BorderStyle::Round,
-                    border_color: Color::Blue,
-                    background_color: Color::Black,
-                    margin_bottom: 1,
-                    padding: 1,
-                ) {
-                    nextgen::NextGenLayout()
+                View(display: display(Tab::Dashboard), flex_grow: 1.0) {
+                    dashboard::DashboardLayout()
                 }
-
-                // SpotHistory area
-                View(
-                    height: left_bottom_height,
-                    border_style: BorderStyle::Round,
-                    border_color: if spot_history_focused { Color::Cyan } else { Color::Green },
-                    background_color: Color::Black,
-                    padding: 1,
-                ) {
+                View(display: display(Tab::NextGen), flex_grow: 1.0) {
+                    nextgen::NextGenLayout(focused: active == Tab::NextGen)
+                }
+                View(display: display(Tab::History), flex_grow: 1.0) {
                     spot_history::SpotHistoryLayout(
-                        focused: spot_history_focused,
+                        focused: active == Tab::History,
                         list_height: spot_history_list_height,
                     )
                 }
-            }
-
-            // Center column: OpenStatus + Middle
-            View(
-                width: center_width.saturating_sub(1),
-                height: usable_height,
-                flex_direction: FlexDirection::Column,
-                margin_right: 1,
-            ) {
-                // OpenStatus area
-                View(
-                    height: center_top_height.saturating_sub(1),
-                    border_style: BorderStyle::Round,
-                    border_color: Color::Yellow,
-                    background_color: Color::Black,
-                    margin_bottom: 1,
-                    padding: 1,
-                ) {
-                    open_status::OpenStatusLayout()
+                View(display: display(Tab::Stats), flex_grow: 1.0) {
+                    stats::StatsLayout(focused: active == Tab::Stats)
                 }
-
-                // Middle area
-                View(
-                    height: center_bottom_height,
-                    border_style: BorderStyle::Round,
-                    border_color: Color::Magenta,
-                    background_color: Color::Black,
-                    padding: 1,
-                ) {
-                    middle::MiddleLayout()
+                View(display: display(Tab::Prized), flex_grow: 1.0) {
+                    spot_history::SpotHistoryLayout(
+                        focused: active == Tab::Prized,
+                        list_height: spot_history_list_height,
+                        winning_only: true,
+                    )
+                }
+                View(display: display(Tab::Daemon), flex_grow: 1.0) {
+                    daemon::DaemonLayout()
+                }
+                View(display: display(Tab::Logs), flex_grow: 1.0) {
+                    logs::LogsLayout(
+                        focused: active == Tab::Logs,
+                        list_height: logs_list_height,
+                    )
+                }
+                View(display: display(Tab::Report), flex_grow: 1.0) {
+                    report::ReportLayout(focused: active == Tab::Report)
                 }
             }
 
-            // Right column: log output (remove duplicate border)
-            View(
-                width: right_width,
-                height: usable_height,
-                border_style: BorderStyle::Round,
-                border_color: if logs_focused { Color::Cyan } else { Color::White },
-                background_color: Color::Black,
-                flex_direction: FlexDirection::Column,
-                padding: 1,
-            ) {
-                logs::LogsLayout(
-                    focused: logs_focused,
-                    list_height: logs_list_height,
-                )
-            }
+            ToastOverlay()
         }
     }
 }
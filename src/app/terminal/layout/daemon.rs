@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use dball_client::ipc::{client::client::ClientState, protocol::AppState};
+use iocraft::prelude::*;
+
+use crate::terminal::{
+    APP_UI_STATE, component::status_bar::connection_label, ipc::get_ipc_client_state, strings::Msg,
+};
+
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        Msg::UptimeDays.fill(&[("days", &days), ("hours", &hours), ("minutes", &minutes)])
+    } else {
+        Msg::UptimeHours.fill(&[("hours", &hours), ("minutes", &minutes)])
+    }
+}
+
+fn local_time(time: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    time.map(|time| {
+        time.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    })
+    .unwrap_or_else(|| "-".to_owned())
+}
+
+fn detail_line(label: Msg, value: String) -> AnyElement<'static> {
+    element! {
+        View(flex_direction: FlexDirection::Row) {
+            Text(content: format!("{:<18}", label.text()), color: Color::DarkGrey)
+            Text(content: value)
+        }
+    }
+    .into()
+}
+
+/// Daemon health: connection, uptime and API provider status
+#[component]
+pub fn DaemonLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+    let mut app_state = hooks.use_state(|| None::<AppState>);
+    let mut connection = hooks.use_state(|| None::<ClientState>);
+
+    hooks.use_future(async move {
+        loop {
+            app_state.set(APP_UI_STATE.read().await.clone());
+            connection.set(Some(get_ipc_client_state().await));
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+
+    let (connection_text, connection_color) = connection_label(connection.read().as_ref());
+
+    let details = match &*app_state.read() {
+        Some(state) => {
+            let api = &state.api_status;
+            let pending = if state.pending_tickets.is_empty() {
+                Msg::NoPendingTickets.text().to_owned()
+            } else {
+                state.pending_tickets.join(", ")
+            };
+            vec![
+                detail_line(Msg::Uptime, format_uptime(state.daemon_uptime)),
+                detail_line(Msg::LastUpdate, local_time(Some(state.last_update))),
+                detail_line(Msg::LastDraw, local_time(state.last_draw_time)),
+                detail_line(Msg::NextDraw, local_time(state.next_draw_time)),
+                detail_line(Msg::PendingTickets, pending),
+                detail_line(Msg::ApiProvider, api.api_provider.clone()),
+                detail_line(
+                    Msg::ApiSuccessRate,
+                    format!("{:.0}%", api.success_rate * 100.0),
+                ),
+                detail_line(
+                    Msg::ApiResponse,
+                    format!("{} ms", api.average_response_time.as_millis()),
+                ),
+                detail_line(Msg::ApiLastSuccess, local_time(api.last_success)),
+            ]
+        }
+        None => vec![
+            element! {
+                Text(content: Msg::NoDaemonState.text(), color: Color::DarkGrey)
+            }
+            .into(),
+        ],
+    };
+
+    element! {
+        View(
+            flex_grow: 1.0,
+            flex_direction: FlexDirection::Column,
+        ) {
+            Text(content: Msg::TabDaemon.text(), color: Color::Cyan, weight: Weight::Bold)
+            View(margin_top: 1) {
+                Text(content: connection_text, color: connection_color, weight: Weight::Bold)
+            }
+            View(
+                margin_top: 1,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Fragment(children: details)
+            }
+        }
+    }
+}
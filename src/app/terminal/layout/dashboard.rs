@@ -0,0 +1,154 @@
+use dball_client::ipc::protocol::{AppState, GenerationStatus};
+use iocraft::prelude::*;
+
+use crate::terminal::{APP_UI_STATE, component::progress::CrawlProgressBar, strings::Msg};
+
+use super::middle::MiddleLayout;
+
+fn generation_label(status: &GenerationStatus) -> (String, Color) {
+    match status {
+        GenerationStatus::Idle => (Msg::GenerationIdle.text().to_owned(), Color::White),
+        GenerationStatus::Generating => (Msg::GenerationRunning.text().to_owned(), Color::Cyan),
+        GenerationStatus::Generated => (Msg::GenerationDone.text().to_owned(), Color::Green),
+        GenerationStatus::Error(e) => (Msg::Error.fill(&[("error", e)]), Color::Red),
+    }
+}
+
+fn summary_elements(state: &AppState) -> Vec<AnyElement<'static>> {
+    let net = state.total_return - state.total_investment;
+    let (generation, generation_color) = generation_label(&state.generation_status);
+    let last_generation = state
+        .last_generation_time
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| "-".to_owned());
+
+    let latest_draw: AnyElement<'static> = match &state.latest_ticket {
+        Some(ticket) => element! {
+            View(flex_direction: FlexDirection::Row) {
+                Text(
+                    content: ticket
+                        .rball
+                        .iter()
+                        .map(|red| format!("{red:02}"))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    color: Color::Red,
+                    weight: Weight::Bold,
+                )
+                Text(content: " + ")
+                Text(content: format!("{:02}", ticket.bball), color: Color::Blue, weight: Weight::Bold)
+            }
+        }
+        .into(),
+        None => element! {
+            Text(content: Msg::NoDrawYet.text(), color: Color::DarkGrey)
+        }
+        .into(),
+    };
+
+    vec![
+        element! {
+            Text(
+                content: Msg::LatestDraw.fill(&[("period", &state.current_period)]),
+                color: Color::Yellow,
+                weight: Weight::Bold,
+            )
+        }
+        .into(),
+        latest_draw,
+        element! {
+            View(margin_top: 1) {
+                Text(
+                    content: Msg::NextPeriod.fill(&[("period", &state.next_period)]),
+                    color: Color::Cyan,
+                )
+            }
+        }
+        .into(),
+        element! {
+            Text(content: Msg::UnprizedSpots.fill(&[("count", &state.unprize_spots_count)]))
+        }
+        .into(),
+        element! {
+            View(margin_top: 1, flex_direction: FlexDirection::Row) {
+                Text(
+                    content: Msg::InvestmentSummary.fill(&[
+                        ("invested", &format!("{:.0}", state.total_investment)),
+                        ("returned", &format!("{:.0}", state.total_return)),
+                        ("claimed", &format!("{:.0}", state.claimed_return)),
+                    ]),
+                )
+                Text(
+                    content: format!("{net:+.0}"),
+                    color: if net >= 0.0 { Color::Green } else { Color::Red },
+                    weight: Weight::Bold,
+                )
+            }
+        }
+        .into(),
+        element! {
+            View(margin_top: 1, flex_direction: FlexDirection::Row) {
+                Text(content: Msg::Generation.text())
+                Text(content: generation, color: generation_color, weight: Weight::Bold)
+                Text(
+                    content: Msg::LastGeneration.fill(&[("time", &last_generation)]),
+                    color: Color::DarkGrey,
+                )
+            }
+        }
+        .into(),
+    ]
+}
+
+/// Overview of the subscribed daemon state with the current time
+#[component]
+pub fn DashboardLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+    let mut app_state = hooks.use_state(|| None::<AppState>);
+
+    hooks.use_future(async move {
+        loop {
+            app_state.set(APP_UI_STATE.read().await.clone());
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    });
+
+    let content_elements = match &*app_state.read() {
+        Some(state) => summary_elements(state),
+        None => vec![
+            element! {
+                Text(content: Msg::WaitingForState.text(), color: Color::DarkGrey, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+    };
+
+    element! {
+        View(
+            flex_grow: 1.0,
+            flex_direction: FlexDirection::Row,
+        ) {
+            View(
+                flex_grow: 1.0,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Text(content: Msg::TabDashboard.text(), color: Color::Cyan, weight: Weight::Bold)
+                View(
+                    margin_top: 1,
+                    flex_direction: FlexDirection::Column,
+                ) {
+                    Fragment(children: content_elements)
+                }
+                View(margin_top: 1) {
+                    CrawlProgressBar()
+                }
+            }
+            View(flex_grow: 1.0) {
+                MiddleLayout()
+            }
+        }
+    }
+}
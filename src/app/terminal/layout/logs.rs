@@ -1,6 +1,8 @@
 use iocraft::prelude::*;
 use std::time::{Duration, Instant};
 
+use crate::terminal::strings::Msg;
+
 #[derive(Default, Props)]
 pub struct LogsLayoutProps {
     pub focused: bool,
@@ -18,7 +20,11 @@ pub fn LogsLayout(
             flex_direction: FlexDirection::Column,
         ) {
             Text(
-                content: if props.focused { "Logs [FOCUS]" } else { "Logs" },
+                content: if props.focused {
+                    format!("{}{}", Msg::TabLogs.text(), Msg::FocusSuffix.text())
+                } else {
+                    Msg::TabLogs.text().to_owned()
+                },
                 color: if props.focused { Color::Cyan } else { Color::White },
                 weight: Weight::Bold,
             )
@@ -136,7 +142,7 @@ pub fn LogPanel(mut hooks: Hooks<'_, '_>, props: &LogPanelProps) -> impl Into<An
     // Generate log elements
     let log_element = if logs.is_empty() {
         element! {
-            Text(content: "No logs available yet...", color: Color::White)
+            Text(content: Msg::NoLogs.text(), color: Color::White)
         }
         .into()
     } else {
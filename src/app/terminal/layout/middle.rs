@@ -1,16 +1,19 @@
 use chrono::{Datelike as _, Weekday};
 use iocraft::prelude::*;
 
-fn get_weekday_chinese(weekday: Weekday) -> &'static str {
+use crate::terminal::strings::Msg;
+
+fn weekday_label(weekday: Weekday) -> &'static str {
     match weekday {
-        Weekday::Mon => "MON",
-        Weekday::Tue => "TUE",
-        Weekday::Wed => "WED",
-        Weekday::Thu => "THU",
-        Weekday::Fri => "FRI",
-        Weekday::Sat => "SAT",
-        Weekday::Sun => "SUN",
+        Weekday::Mon => Msg::Monday,
+        Weekday::Tue => Msg::Tuesday,
+        Weekday::Wed => Msg::Wednesday,
+        Weekday::Thu => Msg::Thursday,
+        Weekday::Fri => Msg::Friday,
+        Weekday::Sat => Msg::Saturday,
+        Weekday::Sun => Msg::Sunday,
     }
+    .text()
 }
 
 #[component]
@@ -26,7 +29,7 @@ pub fn MiddleLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
 
     let time = current_time.get();
     let date_time_str = time.format("%Y-%m-%d %H:%M:%S").to_string();
-    let weekday_str = get_weekday_chinese(time.weekday());
+    let weekday_str = weekday_label(time.weekday());
 
     element! {
         View(
@@ -1,34 +1,160 @@
-use dball_client::models::Spot;
+use dball_client::models::{Spot, SpotQuery, SpotScope};
+use dball_combora::dball::BatchSummary;
 use iocraft::prelude::*;
 
-use crate::terminal::{component::spot::SpotComponent, ipc::send_rpc_request};
+use crate::terminal::{
+    clipboard,
+    component::{
+        error_modal::{ErrorDialog, ErrorModal},
+        progress::{self, ProgressIndicator, RunningOperation},
+        search::{SearchAction, SearchBar, SearchInput},
+        spot::SpotComponent,
+        spot_list::{SpotGroupHeader, SpotListView, SpotRow},
+    },
+    input,
+    ipc::{RpcResult, send_rpc_request, send_rpc_request_with_progress},
+    strings::Msg,
+};
+
+#[derive(Default, Props)]
+pub struct NextGenProps {
+    pub focused: bool,
+}
 
 #[derive(Clone)]
 enum SpotsState {
     Init,
     Loading,
-    Loaded(Result<Vec<Spot>, String>),
+    Loaded(RpcResult<Vec<Spot>>),
+}
+
+/// Action on the highlighted spot, sent to the daemon by spot id
+#[derive(Clone)]
+enum SpotAction {
+    Deprecate(i32),
+    Annotate(i32, Option<String>),
+    MarkPurchased(i32, bool),
+}
+
+impl SpotAction {
+    fn into_rpc(self) -> (dball_client::ipc::RpcService, String) {
+        use dball_client::ipc::RpcService;
+        match self {
+            Self::Deprecate(id) => (
+                RpcService::DeprecateSpot(id),
+                Msg::SpotDeprecated.fill(&[("id", &id)]),
+            ),
+            Self::Annotate(id, note) => (
+                RpcService::AnnotateSpot(id, note),
+                Msg::SpotAnnotated.fill(&[("id", &id)]),
+            ),
+            Self::MarkPurchased(id, purchased) => (
+                RpcService::MarkSpotPurchased(id, purchased),
+                if purchased {
+                    Msg::SpotPurchased.fill(&[("id", &id)])
+                } else {
+                    Msg::SpotNotPurchased.fill(&[("id", &id)])
+                },
+            ),
+        }
+    }
+}
+
+/// Failed request the error modal can send again
+#[derive(Clone)]
+enum Retry {
+    Load(Option<SpotQuery>),
+    Generate,
+    Deprecate,
+    Restore,
+    Action(SpotAction, Option<SpotQuery>),
+}
+
+fn spot_numbers_text(spot: &Spot) -> String {
+    let numbers = |numbers: Vec<i32>| {
+        numbers
+            .iter()
+            .map(|number| format!("{number:02}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    format!(
+        "{} + {}",
+        numbers(spot.red_numbers()),
+        numbers(spot.blue_numbers())
+    )
+}
+
+/// Numbers of every listed spot in the same period as `selected`, one ticket per line
+fn summary_text(summary: &BatchSummary) -> String {
+    Msg::BatchSummary.fill(&[
+        (
+            "sum",
+            &format!(
+                "{:.0}-{:.0} (~{:.1})",
+                summary.sum.min, summary.sum.max, summary.sum.mean
+            ),
+        ),
+        (
+            "parity",
+            &format!("{}/{}", summary.odd_reds, summary.even_reds),
+        ),
+        (
+            "zones",
+            &summary.zones.map(|count| count.to_string()).join("/"),
+        ),
+        ("blues", &summary.distinct_blues),
+        (
+            "blue",
+            &format!("{:.0}-{:.0}", summary.blue.min, summary.blue.max),
+        ),
+        ("similarity", &format!("{:.2}", summary.similarity.max)),
+    ])
+}
+
+fn batch_numbers_text(spots: &[Spot], selected: &Spot) -> (usize, String) {
+    let lines = spots
+        .iter()
+        .filter(|spot| spot.period == selected.period)
+        .map(spot_numbers_text)
+        .collect::<Vec<_>>();
+    (lines.len(), lines.join("\n"))
 }
 
 #[component]
-pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>> {
+pub fn NextGenLayout(
+    mut hooks: Hooks<'_, '_>,
+    props: &NextGenProps,
+) -> impl Into<AnyElement<'static>> {
     let mut state = hooks.use_state(|| SpotsState::Init);
+    let mut search = hooks.use_state(SearchInput::default);
+    let mut cursor = hooks.use_state(|| 0usize);
+    let mut list_view = hooks.use_state(SpotListView::default);
+    let mut note_input = hooks.use_state(|| None::<String>);
+    let mut note_capturing = hooks.use_state(|| false);
+    let mut status = hooks.use_state(|| None::<(String, Color)>);
+    let mut operation = hooks.use_state(|| None::<RunningOperation>);
+    let mut operation_synced = hooks.use_state(|| false);
+    let mut errors = hooks.use_state(ErrorDialog::<Retry>::default);
 
-    // Load spots data handler
-    let mut load_spots = hooks.use_async_handler(move |_: ()| async move {
+    // Load spots data handler, searches unprized spots of all periods when a query is given
+    let mut load_spots = hooks.use_async_handler(move |query: Option<SpotQuery>| async move {
         state.set(SpotsState::Loading);
         log::debug!("Loading spots data...");
-        match send_rpc_request::<Result<Vec<Spot>, String>>(
-            dball_client::ipc::RpcService::GetUnprizeSpots,
-        )
-        .await
-        {
+        let service = match query.clone() {
+            Some(query) => {
+                dball_client::ipc::RpcService::QuerySpots(query.with_scope(SpotScope::Unprized))
+            }
+            None => dball_client::ipc::RpcService::GetUnprizeSpots,
+        };
+        match send_rpc_request::<RpcResult<Vec<Spot>>>(service).await {
             Ok(Ok(spots)) => {
                 log::debug!("Successfully fetched {} unprized spots", spots.len());
                 state.set(SpotsState::Loaded(Ok(spots)));
             }
             Err(e) | Ok(Err(e)) => {
                 log::error!("Failed to fetch unprized spots: {e}");
+                errors.write().show(e.clone(), Retry::Load(query));
                 state.set(SpotsState::Loaded(Err(e)));
             }
         }
@@ -40,15 +166,22 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
         move |_: ()| async move {
             state.set(SpotsState::Loading);
             log::debug!("Generating new batch spots...");
-            match send_rpc_request::<Result<(), String>>(
-                dball_client::ipc::RpcService::GenerateBatchSpots,
+            operation.set(Some(RunningOperation::new(Msg::GeneratingSpots.text())));
+            let result = send_rpc_request_with_progress::<RpcResult<()>>(
+                dball_client::ipc::RpcService::GenerateBatchSpots(None),
+                |report| {
+                    if let Some(running) = operation.write().as_mut() {
+                        running.progress = Some(report);
+                    }
+                },
             )
-            .await
-            {
+            .await;
+            operation.set(None);
+            match result {
                 Ok(Ok(_)) => {
                     log::info!("Successfully generated new batch spots, refreshing...");
                     // Reload spots after generation
-                    match send_rpc_request::<Result<Vec<Spot>, String>>(
+                    match send_rpc_request::<RpcResult<Vec<Spot>>>(
                         dball_client::ipc::RpcService::GetUnprizeSpots,
                     )
                     .await
@@ -62,12 +195,14 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
                         }
                         Err(e) | Ok(Err(e)) => {
                             log::error!("Failed to refresh after generation: {e}");
+                            errors.write().show(e.clone(), Retry::Load(None));
                             state.set(SpotsState::Loaded(Err(e)));
                         }
                     }
                 }
                 Err(e) | Ok(Err(e)) => {
                     log::error!("Failed to generate batch spots: {e}");
+                    errors.write().show(e.clone(), Retry::Generate);
                     state.set(SpotsState::Loaded(Err(e)));
                 }
             }
@@ -80,7 +215,7 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
         move |_: ()| async move {
             state.set(SpotsState::Loading);
             log::info!("Marking last batch spots as deprecated...");
-            match send_rpc_request::<Result<usize, String>>(
+            match send_rpc_request::<RpcResult<usize>>(
                 dball_client::ipc::RpcService::DeprecatedLastBatchUnprizedSpot,
             )
             .await
@@ -88,7 +223,7 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
                 Ok(Ok(count)) => {
                     log::info!("Successfully marked {count} spots as deprecated, refreshing...");
                     // Reload spots after deprecation
-                    match send_rpc_request::<Result<Vec<Spot>, String>>(
+                    match send_rpc_request::<RpcResult<Vec<Spot>>>(
                         dball_client::ipc::RpcService::GetUnprizeSpots,
                     )
                     .await
@@ -102,39 +237,287 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
                         }
                         Err(e) | Ok(Err(e)) => {
                             log::error!("Failed to refresh after deprecation: {e}");
+                            errors.write().show(e.clone(), Retry::Load(None));
                             state.set(SpotsState::Loaded(Err(e)));
                         }
                     }
                 }
                 Err(e) | Ok(Err(e)) => {
                     log::error!("Failed to mark spots as deprecated: {e}");
+                    errors.write().show(e.clone(), Retry::Deprecate);
                     state.set(SpotsState::Loaded(Err(e)));
                 }
             }
         }
     });
 
+    // Undo the latest deprecation, e.g. an accidental D press
+    let mut restore_spots = hooks.use_async_handler({
+        let mut state = state;
+        move |_: ()| async move {
+            let restored = match send_rpc_request::<RpcResult<Vec<i32>>>(
+                dball_client::ipc::RpcService::RestoreSpots(vec![], None),
+            )
+            .await
+            {
+                Ok(Ok(restored)) => restored,
+                Err(e) | Ok(Err(e)) => {
+                    log::error!("Failed to restore deprecated spots: {e}");
+                    errors.write().show(e, Retry::Restore);
+                    return;
+                }
+            };
+            let done = Msg::SpotsRestored.fill(&[("count", &restored.len())]);
+            log::info!("{done}");
+            status.set(Some((done, Color::Green)));
+
+            match send_rpc_request::<RpcResult<Vec<Spot>>>(
+                dball_client::ipc::RpcService::GetUnprizeSpots,
+            )
+            .await
+            {
+                Ok(Ok(spots)) => state.set(SpotsState::Loaded(Ok(spots))),
+                Err(e) | Ok(Err(e)) => {
+                    log::error!("Failed to refresh after restoring spots: {e}");
+                    errors.write().show(e.clone(), Retry::Load(None));
+                    state.set(SpotsState::Loaded(Err(e)));
+                }
+            }
+        }
+    });
+
+    // Per-spot action handler, reloads the current list afterwards
+    let mut spot_action = hooks.use_async_handler({
+        let mut state = state;
+        move |(action, query): (SpotAction, Option<SpotQuery>)| async move {
+            let (service, done) = action.clone().into_rpc();
+            match send_rpc_request::<RpcResult<()>>(service).await {
+                Ok(Ok(())) => {
+                    log::info!("{done}");
+                    status.set(Some((done, Color::Green)));
+                }
+                Err(e) | Ok(Err(e)) => {
+                    log::error!("Spot action failed: {e}");
+                    status.set(Some((Msg::Failed.fill(&[("error", &e)]), Color::Red)));
+                    errors.write().show(e, Retry::Action(action, query));
+                    return;
+                }
+            }
+
+            let service = match query.clone() {
+                Some(query) => {
+                    dball_client::ipc::RpcService::QuerySpots(query.with_scope(SpotScope::Unprized))
+                }
+                None => dball_client::ipc::RpcService::GetUnprizeSpots,
+            };
+            match send_rpc_request::<RpcResult<Vec<Spot>>>(service).await {
+                Ok(Ok(spots)) => state.set(SpotsState::Loaded(Ok(spots))),
+                Err(e) | Ok(Err(e)) => {
+                    log::error!("Failed to refresh after spot action: {e}");
+                    errors.write().show(e.clone(), Retry::Load(query));
+                    state.set(SpotsState::Loaded(Err(e)));
+                }
+            }
+        }
+    });
+
+    let mut cancel_operation = hooks.use_async_handler(move |id: String| async move {
+        if let Err(e) = progress::request_cancel(id).await {
+            log::error!("Failed to cancel operation: {e}");
+            status.set(Some((Msg::CancelFailed.fill(&[("error", &e)]), Color::Red)));
+        }
+    });
+
     // Initial load
     if matches!(*state.read(), SpotsState::Init) {
-        load_spots(());
+        load_spots(None);
+    }
+
+    let running = operation.read().is_some();
+    if operation_synced.get() != running {
+        input::set_operation_running(running);
+        operation_synced.set(running);
+    }
+
+    if search.read().capture_outdated() {
+        search.write().sync_capture();
+    }
+
+    if errors.read().capture_outdated(props.focused) {
+        errors.write().sync_capture(props.focused);
     }
 
+    // Same capture sync as the search bar, for the note input
+    let editing_note = note_input.read().is_some();
+    if note_capturing.get() != editing_note {
+        input::set_text_captured(editing_note);
+        note_capturing.set(editing_note);
+    }
+
+    // The cursor moves over the rows of the sorted and grouped list, headers included
+    let (row_count, highlighted, cursor_group) = match &*state.read() {
+        SpotsState::Loaded(Ok(spots)) => {
+            let rows = list_view.read().rows(spots);
+            let idx = cursor.get().min(rows.len().saturating_sub(1));
+            match rows.get(idx) {
+                Some(SpotRow::Spot(spot)) => (rows.len(), Some((*spot).clone()), None),
+                Some(SpotRow::Group { key, .. }) => (rows.len(), None, Some(key.clone())),
+                None => (0, None, None),
+            }
+        }
+        SpotsState::Loaded(Err(_)) | SpotsState::Loading | SpotsState::Init => (0, None, None),
+    };
+    let cursor_idx = cursor.get().min(row_count.saturating_sub(1));
+
+    let batch_text = match (&*state.read(), highlighted.as_ref()) {
+        (SpotsState::Loaded(Ok(spots)), Some(spot)) => {
+            Some((spot.period.clone(), batch_numbers_text(spots, spot)))
+        }
+        _ => None,
+    };
+
     // Handle terminal events
     hooks.use_terminal_events({
+        let focused = props.focused;
+        let highlighted = highlighted.clone();
         move |event| match event {
-            TerminalEvent::Key(KeyEvent { code, kind, .. }) if kind != KeyEventKind::Release => {
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && focused && errors.read().is_open() =>
+            {
+                let retry = errors.write().handle_key(code);
+                match retry {
+                    Some(Retry::Load(query)) => load_spots(query),
+                    Some(Retry::Generate) => generate_spots(()),
+                    Some(Retry::Deprecate) => deprecate_spots(()),
+                    Some(Retry::Restore) => restore_spots(()),
+                    Some(Retry::Action(action, query)) => spot_action((action, query)),
+                    None => {}
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && note_input.read().is_some() =>
+            {
+                match code {
+                    KeyCode::Char(c) => {
+                        if let Some(text) = note_input.write().as_mut() {
+                            text.push(c);
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        if let Some(text) = note_input.write().as_mut() {
+                            text.pop();
+                        }
+                    }
+                    KeyCode::Esc => note_input.set(None),
+                    KeyCode::Enter => {
+                        let note = note_input.read().clone().filter(|n| !n.trim().is_empty());
+                        note_input.set(None);
+                        if let Some(id) = highlighted.as_ref().and_then(|spot| spot.id) {
+                            spot_action((SpotAction::Annotate(id, note), search.read().query()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && search.read().editing =>
+            {
+                match search.write().handle_key(code) {
+                    SearchAction::Apply(query) => load_spots(Some(query)),
+                    SearchAction::Clear => load_spots(None),
+                    SearchAction::None => {}
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && !input::input_captured() =>
+            {
                 match code {
+                    // Press / to search spots
+                    KeyCode::Char('/') if focused => search.write().start(),
+                    KeyCode::Up if focused => cursor.set(cursor.get().saturating_sub(1)),
+                    KeyCode::Down if focused => {
+                        cursor.set((cursor.get() + 1).min(row_count.saturating_sub(1)));
+                    }
+                    // Press O / V / B to sort, reverse or group, Space to collapse a group
+                    KeyCode::Char('o' | 'O' | 'v' | 'V' | 'b' | 'B' | ' ') if focused => {
+                        list_view.write().handle_key(code, cursor_group.as_deref());
+                    }
+                    // Actions on the highlighted spot
+                    KeyCode::Char('x' | 'X' | 'n' | 'N' | 'p' | 'P' | 'c' | 'C') if focused => {
+                        let Some(spot) = highlighted.as_ref() else {
+                            return;
+                        };
+                        let Some(id) = spot.id else {
+                            status.set(Some((Msg::SpotWithoutId.text().to_owned(), Color::Red)));
+                            return;
+                        };
+                        let query = search.read().query();
+                        match code {
+                            KeyCode::Char('x' | 'X') => {
+                                spot_action((SpotAction::Deprecate(id), query));
+                            }
+                            KeyCode::Char('p' | 'P') => {
+                                spot_action((
+                                    SpotAction::MarkPurchased(id, !spot.purchased),
+                                    query,
+                                ));
+                            }
+                            KeyCode::Char('n' | 'N') => {
+                                note_input.set(Some(spot.note.clone().unwrap_or_default()));
+                            }
+                            _ => {
+                                let text = spot_numbers_text(spot);
+                                let message = match clipboard::copy_text(&text) {
+                                    Ok(()) => (Msg::Copied.fill(&[("text", &text)]), Color::Green),
+                                    Err(e) => (e.to_string(), Color::Red),
+                                };
+                                status.set(Some(message));
+                            }
+                        }
+                    }
+                    // Press Y to copy the highlighted batch
+                    KeyCode::Char('y' | 'Y') if focused => {
+                        let Some((period, (count, text))) = batch_text.as_ref() else {
+                            return;
+                        };
+                        let message = match clipboard::copy_text(text) {
+                            Ok(()) => (
+                                Msg::CopiedBatch.fill(&[("count", count), ("period", period)]),
+                                Color::Green,
+                            ),
+                            Err(e) => (e.to_string(), Color::Red),
+                        };
+                        status.set(Some(message));
+                    }
+                    // Press Esc to cancel the running generation
+                    KeyCode::Esc => {
+                        let id = operation
+                            .write()
+                            .as_mut()
+                            .and_then(RunningOperation::start_cancel);
+                        if let Some(id) = id {
+                            cancel_operation(id);
+                        }
+                    }
                     // Press G to generate new spots
-                    KeyCode::Char('g' | 'G') => {
+                    KeyCode::Char('g' | 'G') if focused && !running => {
+                        // Refreshing after generation lists the next period only
+                        search.write().applied = None;
                         generate_spots(());
                     }
                     // Press D to deprecate last batch spots
-                    KeyCode::Char('d' | 'D') => {
+                    KeyCode::Char('d' | 'D') if focused => {
+                        search.write().applied = None;
                         deprecate_spots(());
                     }
+                    // Press U to restore the spots deprecated last
+                    KeyCode::Char('u' | 'U') if focused => {
+                        search.write().applied = None;
+                        restore_spots(());
+                    }
                     // Press R to refresh/reload spots
-                    KeyCode::Char('r' | 'R') => {
-                        load_spots(());
+                    KeyCode::Char('r' | 'R') if focused => {
+                        load_spots(search.read().query());
                     }
                     _ => {}
                 }
@@ -148,18 +531,33 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
             if spots.is_empty() {
                 vec![
                     element! {
-                        Text(content: "No next generation data available", color: Color::Red, weight: Weight::Bold)
+                        Text(content: Msg::NoNextGen.text(), color: Color::Red, weight: Weight::Bold)
                     }
                     .into(),
                 ]
             } else {
-                spots
-                    .iter()
-                    .map(|spot| {
-                        element! {
-                            SpotComponent(value: spot.clone(), has_focus: false)
+                list_view
+                    .read()
+                    .rows(spots)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, row)| {
+                        let has_focus = props.focused && idx == cursor_idx;
+                        match row {
+                            SpotRow::Group {
+                                label,
+                                spots,
+                                collapsed,
+                                ..
+                            } => element! {
+                                SpotGroupHeader(label, spots, collapsed, has_focus)
+                            }
+                            .into(),
+                            SpotRow::Spot(spot) => element! {
+                                SpotComponent(value: spot.clone(), has_focus)
+                            }
+                            .into(),
                         }
-                        .into()
                     })
                     .collect::<Vec<_>>()
             }
@@ -167,7 +565,15 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
         SpotsState::Loaded(Err(error)) => {
             vec![
                 element! {
-                    Text(content: format!("Error: {error}"), color: Color::Red, weight: Weight::Bold)
+                    Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red, weight: Weight::Bold)
+                }
+                .into(),
+            ]
+        }
+        SpotsState::Loading if running => {
+            vec![
+                element! {
+                    ProgressIndicator(operation: operation.read().clone())
                 }
                 .into(),
             ]
@@ -175,7 +581,7 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
         SpotsState::Loading => {
             vec![
                 element! {
-                    Text(content: "Loading...", color: Color::Yellow, weight: Weight::Bold)
+                    Text(content: Msg::Loading.text(), color: Color::Yellow, weight: Weight::Bold)
                 }
                 .into(),
             ]
@@ -183,26 +589,56 @@ pub fn NextGenLayout(mut hooks: Hooks<'_, '_>) -> impl Into<AnyElement<'static>>
         SpotsState::Init => {
             vec![
                 element! {
-                    Text(content: "Initializing...", color: Color::DarkGrey, weight: Weight::Bold)
+                    Text(content: Msg::Initializing.text(), color: Color::DarkGrey, weight: Weight::Bold)
                 }
                 .into(),
             ]
         }
     };
 
+    let summary_text = match &*state.read() {
+        SpotsState::Loaded(Ok(spots)) if !spots.is_empty() => {
+            summary_text(&dball_client::service::spots_summary(spots))
+        }
+        _ => String::new(),
+    };
+
+    let search = search.read();
+    let (status_text, status_color) = match note_input.read().as_ref() {
+        Some(text) => (Msg::NoteInput.fill(&[("text", text)]), Color::White),
+        None => status
+            .read()
+            .clone()
+            .unwrap_or_else(|| (String::new(), Color::White)),
+    };
+
     element! {
         View(
             flex_grow: 1.0,
             flex_direction: FlexDirection::Column,
         ) {
-            Text(content: "Next Generation", color: Color::Cyan, weight: Weight::Bold)
-            Text(content: "Press G to generate batch spots\nPress D to deprecate last batch\nPress R to refresh", color: Color::Yellow)
+            Text(content: Msg::NextGenTitle.text(), color: Color::Cyan, weight: Weight::Bold)
+            Text(content: Msg::NextGenKeys.text(), color: Color::Yellow)
+            Text(content: Msg::NextGenSpotKeys.text(), color: Color::DarkYellow)
+            Text(
+                content: Msg::NextGenViewKeys.fill(&[("view", &list_view.read().label())]),
+                color: Color::DarkGrey,
+            )
+            SearchBar(
+                editing: search.editing,
+                text: search.text.clone(),
+                error: search.error.clone(),
+                applied: search.applied.as_ref().map(|(text, _)| text.clone()),
+            )
             View(
                 margin_top: 1,
                 flex_direction: FlexDirection::Column,
             ) {
                 Fragment(children: content_elements)
             }
+            Text(content: summary_text, color: Color::DarkGrey)
+            Text(content: status_text, color: status_color)
+            ErrorModal(error: props.focused.then(|| errors.read().error()).flatten())
         }
     }
 }
@@ -0,0 +1,234 @@
+use chrono::Datelike as _;
+use dball_client::service::PeriodicReport;
+use iocraft::prelude::*;
+
+use crate::terminal::{
+    input,
+    ipc::{RpcResult, send_rpc_request},
+    strings::Msg,
+};
+
+#[derive(Clone)]
+enum ReportState {
+    Init,
+    Loading,
+    Loaded(RpcResult<PeriodicReport>),
+}
+
+/// Month or year the report covers, `None` month for the whole year
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct ReportScope {
+    year: i32,
+    month: Option<u32>,
+}
+
+impl ReportScope {
+    fn current() -> Self {
+        let today = chrono::Local::now().date_naive();
+        Self {
+            year: today.year(),
+            month: Some(today.month()),
+        }
+    }
+
+    /// The month or year before, or after with `forward`
+    fn step(self, forward: bool) -> Self {
+        match (self.month, forward) {
+            (None, _) => Self {
+                year: if forward {
+                    self.year + 1
+                } else {
+                    self.year - 1
+                },
+                month: None,
+            },
+            (Some(12), true) => Self {
+                year: self.year + 1,
+                month: Some(1),
+            },
+            (Some(1), false) => Self {
+                year: self.year - 1,
+                month: Some(12),
+            },
+            (Some(month), _) => Self {
+                year: self.year,
+                month: Some(if forward { month + 1 } else { month - 1 }),
+            },
+        }
+    }
+
+    /// The whole year of a month, or the current month of the year
+    fn toggle_year(self) -> Self {
+        Self {
+            year: self.year,
+            month: match self.month {
+                Some(_) => None,
+                None => Some(Self::current().month.unwrap_or(1)),
+            },
+        }
+    }
+}
+
+#[derive(Default, Props)]
+pub struct ReportProps {
+    pub focused: bool,
+}
+
+/// Monthly or annual summary, fed by the `GetPeriodicReport` RPC
+#[component]
+pub fn ReportLayout(
+    mut hooks: Hooks<'_, '_>,
+    props: &ReportProps,
+) -> impl Into<AnyElement<'static>> {
+    let mut state = hooks.use_state(|| ReportState::Init);
+    let scope = hooks.use_state(ReportScope::current);
+
+    let mut load_report = hooks.use_async_handler(move |scope: ReportScope| async move {
+        state.set(ReportState::Loading);
+        let result = send_rpc_request::<RpcResult<PeriodicReport>>(
+            dball_client::ipc::RpcService::GetPeriodicReport(scope.year, scope.month),
+        )
+        .await
+        .flatten();
+        if let Err(e) = &result {
+            log::error!("Failed to fetch the report: {e}");
+        }
+        state.set(ReportState::Loaded(result));
+    });
+
+    // Initial load
+    if matches!(*state.read(), ReportState::Init) {
+        load_report(scope.get());
+    }
+
+    hooks.use_terminal_events({
+        let mut scope = scope;
+        let focused = props.focused;
+        move |event| match event {
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && focused && !input::input_captured() =>
+            {
+                let next = match code {
+                    // Press [ / ] to go to the month or year before or after
+                    KeyCode::Char('[') => scope.get().step(false),
+                    KeyCode::Char(']') => scope.get().step(true),
+                    // Press Y to switch between the month and its whole year
+                    KeyCode::Char('y' | 'Y') => scope.get().toggle_year(),
+                    KeyCode::Char('r' | 'R') => scope.get(),
+                    _ => return,
+                };
+                scope.set(next);
+                load_report(next);
+            }
+            _ => {}
+        }
+    });
+
+    let content_elements: Vec<AnyElement<'static>> = match &*state.read() {
+        ReportState::Loaded(Ok(report)) => report_lines(report),
+        ReportState::Loaded(Err(error)) => vec![
+            element! {
+                Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        ReportState::Loading => vec![
+            element! {
+                Text(content: Msg::Loading.text(), color: Color::Yellow, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        ReportState::Init => vec![
+            element! {
+                Text(content: Msg::Initializing.text(), color: Color::DarkGrey, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+    };
+
+    let scope = scope.get();
+    let title = match scope.month {
+        Some(month) => format!("{}-{month:02}", scope.year),
+        None => scope.year.to_string(),
+    };
+
+    element! {
+        View(
+            flex_grow: 1.0,
+            flex_direction: FlexDirection::Column,
+        ) {
+            Text(
+                content: Msg::ReportTitle.fill(&[("title", &title)]),
+                color: Color::Cyan,
+                weight: Weight::Bold,
+            )
+            Text(
+                content: Msg::ReportKeys.text(),
+                color: Color::Yellow,
+            )
+            View(
+                margin_top: 1,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Fragment(children: content_elements)
+            }
+        }
+    }
+}
+
+fn report_lines(report: &PeriodicReport) -> Vec<AnyElement<'static>> {
+    if report.periods == 0 {
+        return vec![
+            element! {
+                Text(content: Msg::ReportEmpty.text(), color: Color::DarkGrey)
+            }
+            .into(),
+        ];
+    }
+
+    let tiers = report
+        .wins_by_tier
+        .iter()
+        .enumerate()
+        .map(|(tier, wins)| format!("#{} {wins}", tier + 1))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let mut lines = vec![
+        element! {
+            Text(
+                content: Msg::ReportTotals.fill(&[
+                    ("periods", &report.periods),
+                    ("spots", &report.spots),
+                    ("cost", &report.cost),
+                    ("winnings", &report.winnings),
+                    ("claimed", &report.claimed),
+                    ("net", &report.net()),
+                ]),
+                color: if report.net() >= 0 { Color::Green } else { Color::Red },
+                weight: Weight::Bold,
+            )
+        }
+        .into(),
+        element! {
+            Text(content: Msg::ReportWinsByTier.fill(&[("tiers", &tiers)]))
+        }
+        .into(),
+    ];
+    for (label, period) in [
+        (Msg::ReportBest, &report.best),
+        (Msg::ReportWorst, &report.worst),
+    ] {
+        if let Some(period) = period {
+            lines.push(
+                element! {
+                    Text(
+                        content: label.fill(&[("period", &period.period), ("net", &period.net())]),
+                        color: Color::DarkGrey,
+                    )
+                }
+                .into(),
+            );
+        }
+    }
+    lines
+}
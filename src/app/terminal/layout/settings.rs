@@ -0,0 +1,330 @@
+use dball_client::config::{ConfigIssue, DballConfig, Locale};
+use dball_combora::generator::Generator;
+use iocraft::prelude::*;
+
+use crate::app::i18n;
+use crate::terminal::{
+    ipc::{RpcResult, send_rpc_request},
+    strings::Msg,
+};
+
+const STRATEGY_FIELD: &str = "generator.strategy";
+const LOCALE_FIELD: &str = "ui.locale";
+
+#[derive(Default, Props)]
+pub struct SettingsProps {
+    pub on_close: Handler<'static, ()>,
+}
+
+#[derive(Clone)]
+enum SettingsState {
+    Init,
+    Loading,
+    Loaded(Box<RpcResult<DballConfig>>),
+}
+
+fn field_label(field: &str) -> &'static str {
+    match field {
+        "budget.per_period" => Msg::FieldBudgetPerPeriod,
+        "budget.per_week" => Msg::FieldBudgetPerWeek,
+        "budget.per_month" => Msg::FieldBudgetPerMonth,
+        "crawl.concurrency" => Msg::FieldCrawlConcurrency,
+        "crawl.max_attempts" => Msg::FieldCrawlMaxAttempts,
+        "crawl.backoff_ms" => Msg::FieldCrawlBackoff,
+        "generator.strategy" => Msg::FieldGenerator,
+        "generator.batch_size" => Msg::FieldBatchSize,
+        "generator.frequency_window" => Msg::FieldFrequencyWindow,
+        "generator.frequency_bias" => Msg::FieldFrequencyBias,
+        "generator.rng" => Msg::FieldRng,
+        "maintenance.interval_hours" => Msg::FieldMaintenanceInterval,
+        "maintenance.vacuum_free_percent" => Msg::FieldVacuumFreePercent,
+        "notification.webhook_url" => Msg::FieldWebhook,
+        "notification.email" => Msg::FieldEmail,
+        "notification.telegram_chat_id" => Msg::FieldTelegram,
+        "notification.telegram_commands" => Msg::FieldTelegramCommands,
+        "notification.do_not_disturb" => Msg::FieldDoNotDisturb,
+        "notification.min_tier" => Msg::FieldMinTier,
+        "notification.smtp.host" => Msg::FieldSmtpHost,
+        "notification.smtp.port" => Msg::FieldSmtpPort,
+        "notification.smtp.username" => Msg::FieldSmtpUsername,
+        "notification.smtp.from" => Msg::FieldSmtpFrom,
+        "notification.smtp.tls" => Msg::FieldSmtpTls,
+        "refresh.state_secs" => Msg::FieldStateRefresh,
+        "refresh.spots_secs" => Msg::FieldSpotsRefresh,
+        "scheduler.enabled" => Msg::FieldSchedulerEnabled,
+        "scheduler.delay_minutes" => Msg::FieldSchedulerDelay,
+        "scheduler.max_attempts" => Msg::FieldSchedulerAttempts,
+        "scheduler.retry_secs" => Msg::FieldSchedulerRetry,
+        "spots.duplicates" => Msg::FieldDuplicates,
+        "ui.locale" => Msg::FieldLanguage,
+        _ => Msg::FieldUnknown,
+    }
+    .text()
+}
+
+/// Cycle the generator strategy through all known generators
+fn cycle_strategy(config: &mut DballConfig, forward: bool) {
+    let count = Generator::ALL.len();
+    let current = Generator::ALL
+        .iter()
+        .position(|g| g.name() == config.generator.strategy);
+    let next = match (current, forward) {
+        (Some(idx), true) => (idx + 1) % count,
+        (Some(idx), false) => (idx + count - 1) % count,
+        (None, _) => 0,
+    };
+    Generator::ALL[next]
+        .name()
+        .clone_into(&mut config.generator.strategy);
+}
+
+/// Cycle the UI language through all supported locales
+fn cycle_locale(config: &mut DballConfig, forward: bool) {
+    let count = Locale::ALL.len();
+    let current = Locale::ALL
+        .iter()
+        .position(|locale| *locale == config.ui.locale)
+        .unwrap_or_default();
+    let next = if forward {
+        (current + 1) % count
+    } else {
+        (current + count - 1) % count
+    };
+    config.ui.locale = Locale::ALL[next];
+}
+
+/// Settings editor for `dball.toml`, read and written through the config RPCs
+#[component]
+pub fn SettingsLayout(
+    mut hooks: Hooks<'_, '_>,
+    props: &mut SettingsProps,
+) -> impl Into<AnyElement<'static>> {
+    let mut state = hooks.use_state(|| SettingsState::Init);
+    let mut draft = hooks.use_state(DballConfig::default);
+    let mut selected = hooks.use_state(|| 0usize);
+    let mut input = hooks.use_state(|| None::<String>);
+    let mut issues = hooks.use_state(Vec::<ConfigIssue>::new);
+    let mut status = hooks.use_state(|| None::<(String, Color)>);
+
+    let mut load_config = hooks.use_async_handler(move |_: ()| async move {
+        state.set(SettingsState::Loading);
+        match send_rpc_request::<RpcResult<DballConfig>>(dball_client::ipc::RpcService::GetConfig)
+            .await
+        {
+            Ok(Ok(config)) => {
+                issues.set(config.validate());
+                draft.set(config.clone());
+                state.set(SettingsState::Loaded(Box::new(Ok(config))));
+            }
+            Err(e) | Ok(Err(e)) => {
+                log::error!("Failed to load config: {e}");
+                state.set(SettingsState::Loaded(Box::new(Err(e))));
+            }
+        }
+    });
+
+    let mut save_config = hooks.use_async_handler(move |config: DballConfig| async move {
+        status.set(Some((Msg::Saving.text().to_owned(), Color::Yellow)));
+        match send_rpc_request::<RpcResult<Vec<ConfigIssue>>>(
+            dball_client::ipc::RpcService::UpdateConfig(Box::new(config.clone())),
+        )
+        .await
+        {
+            Ok(Ok(rejected)) if rejected.is_empty() => {
+                log::info!("Config saved");
+                i18n::set_locale(config.ui.locale);
+                issues.set(vec![]);
+                state.set(SettingsState::Loaded(Box::new(Ok(config))));
+                status.set(Some((Msg::Saved.text().to_owned(), Color::Green)));
+            }
+            Ok(Ok(rejected)) => {
+                issues.set(rejected);
+                status.set(Some((Msg::NotSaved.text().to_owned(), Color::Red)));
+            }
+            Err(e) | Ok(Err(e)) => {
+                log::error!("Failed to save config: {e}");
+                status.set(Some((Msg::SaveFailed.fill(&[("error", &e)]), Color::Red)));
+            }
+        }
+    });
+
+    if matches!(*state.read(), SettingsState::Init) {
+        load_config(());
+    }
+
+    let field_count = DballConfig::FIELDS.len();
+
+    hooks.use_terminal_events({
+        let mut on_close = props.on_close.take();
+        move |event| match event {
+            TerminalEvent::Key(KeyEvent { code, kind, .. }) if kind != KeyEventKind::Release => {
+                let field = DballConfig::FIELDS[selected.get()];
+                let editing = input.read().is_some();
+
+                if editing {
+                    match code {
+                        KeyCode::Char(c) => {
+                            if let Some(text) = input.write().as_mut() {
+                                text.push(c);
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            if let Some(text) = input.write().as_mut() {
+                                text.pop();
+                            }
+                        }
+                        KeyCode::Esc => input.set(None),
+                        KeyCode::Enter => {
+                            let text = input.read().clone().unwrap_or_default();
+                            let mut config = draft.read().clone();
+                            let mut found = match config.set_field(field, &text) {
+                                Ok(()) => {
+                                    input.set(None);
+                                    vec![]
+                                }
+                                Err(e) => vec![ConfigIssue {
+                                    field: field.to_owned(),
+                                    message: e.to_string(),
+                                }],
+                            };
+                            found.extend(config.validate());
+                            issues.set(found);
+                            draft.set(config);
+                            status.set(None);
+                        }
+                        _ => {}
+                    }
+                    return;
+                }
+
+                match code {
+                    KeyCode::Up => selected.set(selected.get().saturating_sub(1)),
+                    KeyCode::Down => selected.set((selected.get() + 1).min(field_count - 1)),
+                    KeyCode::Left | KeyCode::Right | KeyCode::Enter if field == STRATEGY_FIELD => {
+                        cycle_strategy(&mut draft.write(), code != KeyCode::Left);
+                        issues.set(draft.read().validate());
+                        status.set(None);
+                    }
+                    KeyCode::Left | KeyCode::Right | KeyCode::Enter if field == LOCALE_FIELD => {
+                        cycle_locale(&mut draft.write(), code != KeyCode::Left);
+                        status.set(None);
+                    }
+                    KeyCode::Enter => {
+                        input.set(Some(draft.read().field_value(field).unwrap_or_default()));
+                    }
+                    // Press W to write the config
+                    KeyCode::Char('w' | 'W') => save_config(draft.read().clone()),
+                    // Press R to discard edits and reload
+                    KeyCode::Char('r' | 'R') => {
+                        status.set(None);
+                        load_config(());
+                    }
+                    KeyCode::Esc | KeyCode::Char('s' | 'S') => on_close(()),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    });
+
+    let content_elements: Vec<AnyElement<'static>> = match &*state.read() {
+        SettingsState::Loaded(loaded) => match loaded.as_ref() {
+        Ok(saved) => {
+            let draft = draft.read();
+            let issues = issues.read();
+            let input = input.read();
+            DballConfig::FIELDS
+                .iter()
+                .enumerate()
+                .map(|(idx, &field)| {
+                    let is_selected = idx == selected.get();
+                    let value = match input.as_ref() {
+                        Some(text) if is_selected => format!("{text}_"),
+                        _ => draft.field_value(field).unwrap_or_default(),
+                    };
+                    let value = if value.is_empty() {
+                        Msg::Unset.text().to_owned()
+                    } else {
+                        value
+                    };
+                    let modified = draft.field_value(field) != saved.field_value(field);
+                    let error = issues
+                        .iter()
+                        .filter(|issue| issue.field == field)
+                        .map(|issue| issue.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; ");
+
+                    element! {
+                        View(flex_direction: FlexDirection::Row) {
+                            Text(
+                                content: format!(
+                                    "{} {:<22} ",
+                                    if is_selected { ">" } else { " " },
+                                    field_label(field)
+                                ),
+                                color: if is_selected { Color::Cyan } else { Color::White },
+                                weight: if is_selected { Weight::Bold } else { Weight::Normal },
+                            )
+                            Text(
+                                content: format!("{value}{}", if modified { " *" } else { "" }),
+                                color: if modified { Color::Yellow } else { Color::White },
+                            )
+                            Text(
+                                content: if error.is_empty() { String::new() } else { format!("  {error}") },
+                                color: Color::Red,
+                            )
+                        }
+                    }
+                    .into()
+                })
+                .collect()
+        }
+        Err(error) => vec![
+            element! {
+                Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        },
+        SettingsState::Loading => vec![
+            element! {
+                Text(content: Msg::Loading.text(), color: Color::Yellow, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        SettingsState::Init => vec![
+            element! {
+                Text(content: Msg::Initializing.text(), color: Color::DarkGrey, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+    };
+
+    let (status_text, status_color) = status
+        .read()
+        .clone()
+        .unwrap_or_else(|| (String::new(), Color::White));
+
+    element! {
+        View(
+            flex_grow: 1.0,
+            flex_direction: FlexDirection::Column,
+            border_style: BorderStyle::Round,
+            border_color: Color::Cyan,
+            padding: 1,
+        ) {
+            Text(content: Msg::SettingsTitle.text(), color: Color::Cyan, weight: Weight::Bold)
+            Text(content: Msg::SettingsKeys.text(), color: Color::Yellow)
+            View(
+                margin_top: 1,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Fragment(children: content_elements)
+            }
+            View(margin_top: 1) {
+                Text(content: status_text, color: status_color, weight: Weight::Bold)
+            }
+        }
+    }
+}
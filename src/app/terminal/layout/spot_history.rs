@@ -1,22 +1,122 @@
-use dball_client::models::Spot;
+use dball_client::{
+    models::{Spot, SpotQuery, SpotScope},
+    service::{PeriodRoi, RoiReport},
+};
 use iocraft::prelude::*;
 
 use crate::terminal::{
-    component::spot::SpotComponent,
-    ipc::{RpcResult, send_rpc_request},
+    component::{
+        error_modal::{ErrorDialog, ErrorModal},
+        progress::{self, ProgressIndicator, RunningOperation},
+        search::{SearchAction, SearchBar, SearchInput},
+        spot::SpotComponent,
+        spot_list::{SpotListView, SpotSort},
+    },
+    input,
+    ipc::{RpcResult, send_rpc_request, send_rpc_request_with_progress},
+    strings::Msg,
 };
 
 #[derive(Default, Props)]
 pub struct SpotHistoryProps {
     pub focused: bool,
     pub list_height: u16,
+    /// Only list spots that won a prize
+    pub winning_only: bool,
 }
 
 #[derive(Clone)]
 enum HistoryState {
     Init,
     Loading,
-    Loaded(Result<Vec<Spot>, String>),
+    Loaded(RpcResult<RoiReport>),
+}
+
+/// Failed request the error modal can send again, with the query it was made with
+#[derive(Clone)]
+enum Retry {
+    Load(Option<SpotQuery>),
+    Update(Option<SpotQuery>),
+}
+
+/// A line of the history list, either a period header or one of its spots
+enum HistoryRow<'a> {
+    /// Header of a period, along with whether its spots are collapsed
+    Period(&'a PeriodRoi, bool),
+    Spot(&'a Spot),
+}
+
+/// Flatten the report into period headers followed by their sorted spots,
+/// each row tagged with the index of the period it belongs to
+///
+/// Periods keep the order of the report unless sorted by period
+fn history_rows<'a>(
+    report: &'a RoiReport,
+    list_view: &SpotListView,
+) -> Vec<(usize, HistoryRow<'a>)> {
+    let mut periods = report.periods.iter().enumerate().collect::<Vec<_>>();
+    if list_view.sort == SpotSort::Period {
+        periods.sort_by(|(_, a), (_, b)| {
+            let order = a.period.cmp(&b.period);
+            if list_view.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+
+    periods
+        .into_iter()
+        .flat_map(|(idx, period)| {
+            let collapsed = list_view.is_collapsed(&period.period);
+            let spots = if collapsed {
+                Vec::new()
+            } else {
+                list_view.sort_spots(&period.spots)
+            };
+            std::iter::once((idx, HistoryRow::Period(period, collapsed))).chain(
+                spots
+                    .into_iter()
+                    .map(move |spot| (idx, HistoryRow::Spot(spot))),
+            )
+        })
+        .collect()
+}
+
+/// Fetch the ROI report, built from the matching prized spots when a query is given
+async fn fetch_report(query: Option<SpotQuery>, winning_only: bool) -> RpcResult<RoiReport> {
+    let query = if winning_only {
+        Some(SpotQuery {
+            winning_only: true,
+            ..query.unwrap_or_default()
+        })
+    } else {
+        query
+    };
+    match query {
+        Some(query) => send_rpc_request::<RpcResult<Vec<Spot>>>(
+            dball_client::ipc::RpcService::QuerySpots(query.with_scope(SpotScope::Prized)),
+        )
+        .await?
+        .map(RoiReport::from_spots),
+        None => {
+            send_rpc_request::<RpcResult<RoiReport>>(dball_client::ipc::RpcService::GetRoiReport)
+                .await?
+        }
+    }
+}
+
+fn format_net(net: i64) -> String {
+    if net >= 0 {
+        format!("+¥{net}")
+    } else {
+        format!("-¥{}", net.unsigned_abs())
+    }
+}
+
+fn net_color(net: i64) -> Color {
+    if net >= 0 { Color::Green } else { Color::Red }
 }
 
 #[component]
@@ -25,71 +125,145 @@ pub fn SpotHistoryLayout(
     props: &SpotHistoryProps,
 ) -> impl Into<AnyElement<'static>> {
     let mut state = hooks.use_state(|| HistoryState::Init);
-    let scroll_offset = hooks.use_state(|| 0usize);
+    let mut scroll_offset = hooks.use_state(|| 0usize);
+    let mut search = hooks.use_state(SearchInput::default);
+    let mut list_view = hooks.use_state(SpotListView::default);
+    let mut operation = hooks.use_state(|| None::<RunningOperation>);
+    let mut operation_synced = hooks.use_state(|| false);
+    let mut errors = hooks.use_state(ErrorDialog::<Retry>::default);
     let list_height = props.list_height.max(1) as usize;
+    let winning_only = props.winning_only;
 
-    // Load prized spots data handler
-    let mut load_prized_spots = hooks.use_async_handler(move |_: ()| async move {
-        state.set(HistoryState::Loading);
-        log::debug!("Loading prized spots data...");
-        match send_rpc_request::<Result<Vec<Spot>, String>>(
-            dball_client::ipc::RpcService::GetPrizedSpots,
-        )
-        .await
-        {
-            Ok(Ok(spots)) => {
-                log::debug!("Successfully fetched {} prized spots", spots.len());
-                state.set(HistoryState::Loaded(Ok(spots)));
-            }
-            Err(e) | Ok(Err(e)) => {
-                log::error!("Failed to fetch prized spots: {e}");
-                state.set(HistoryState::Loaded(Err(e)));
+    // Load ROI report of prized spots handler
+    let mut load_prized_spots =
+        hooks.use_async_handler(move |query: Option<SpotQuery>| async move {
+            state.set(HistoryState::Loading);
+            scroll_offset.set(0);
+            log::debug!("Loading ROI report...");
+            match fetch_report(query.clone(), winning_only).await {
+                Ok(report) => {
+                    log::debug!(
+                        "Successfully fetched ROI report over {} periods",
+                        report.periods.len()
+                    );
+                    state.set(HistoryState::Loaded(Ok(report)));
+                }
+                Err(e) => {
+                    log::error!("Failed to fetch ROI report: {e}");
+                    errors.write().show(e.clone(), Retry::Load(query));
+                    state.set(HistoryState::Loaded(Err(e)));
+                }
             }
-        }
-    });
+        });
 
     // Update all unprize spots handler
     let mut update_spots = hooks.use_async_handler({
         let mut state = state;
-        move |_: ()| async move {
+        move |query: Option<SpotQuery>| async move {
             state.set(HistoryState::Loading);
             log::info!("Updating all unprize spots...");
-            match send_rpc_request::<RpcResult<Vec<Spot>>>(
+            operation.set(Some(RunningOperation::new(Msg::UpdatingSpots.text())));
+            let result = send_rpc_request_with_progress::<RpcResult<Vec<Spot>>>(
                 dball_client::ipc::RpcService::UpdateAllUnprizeSpots,
+                |report| {
+                    if let Some(running) = operation.write().as_mut() {
+                        running.progress = Some(report);
+                    }
+                },
             )
-            .await
-            {
+            .await;
+            operation.set(None);
+            match result {
                 Ok(Ok(updated_spots)) => {
                     log::info!(
                         "Successfully updated spots, got {} spots back",
                         updated_spots.len()
                     );
-                    state.set(HistoryState::Loaded(Ok(updated_spots)));
+                    // Reload the ROI report after the update
+                    match fetch_report(query.clone(), winning_only).await {
+                        Ok(report) => state.set(HistoryState::Loaded(Ok(report))),
+                        Err(e) => {
+                            log::error!("Failed to refresh ROI report after update: {e}");
+                            errors.write().show(e.clone(), Retry::Load(query));
+                            state.set(HistoryState::Loaded(Err(e)));
+                        }
+                    }
                 }
                 Err(e) | Ok(Err(e)) => {
                     log::error!("Failed to update spots: {e}");
+                    errors.write().show(e.clone(), Retry::Update(query));
                     state.set(HistoryState::Loaded(Err(e)));
                 }
             }
         }
     });
 
+    let mut cancel_operation = hooks.use_async_handler(move |id: String| async move {
+        if let Err(e) = progress::request_cancel(id).await {
+            log::error!("Failed to cancel operation: {e}");
+        }
+    });
+
     // Initial load
     if matches!(*state.read(), HistoryState::Init) {
-        load_prized_spots(());
+        load_prized_spots(None);
+    }
+
+    let running = operation.read().is_some();
+    if operation_synced.get() != running {
+        input::set_operation_running(running);
+        operation_synced.set(running);
+    }
+
+    if search.read().capture_outdated() {
+        search.write().sync_capture();
+    }
+
+    if errors.read().capture_outdated(props.focused) {
+        errors.write().sync_capture(props.focused);
     }
 
     // Handle terminal events
     hooks.use_terminal_events({
         let focused = props.focused;
-        let max_offset = match &*state.read() {
-            HistoryState::Loaded(Ok(spots)) => spots.len().saturating_sub(list_height),
-            HistoryState::Loaded(Err(_)) | HistoryState::Loading | HistoryState::Init => 0,
+        // Space collapses the period of the top row
+        let (max_offset, top_period) = match &*state.read() {
+            HistoryState::Loaded(Ok(report)) => {
+                let rows = history_rows(report, &list_view.read());
+                let max_offset = rows.len().saturating_sub(list_height);
+                let top_period = rows
+                    .get(scroll_offset.get().min(max_offset))
+                    .map(|(idx, _)| report.periods[*idx].period.clone());
+                (max_offset, top_period)
+            }
+            HistoryState::Loaded(Err(_)) | HistoryState::Loading | HistoryState::Init => (0, None),
         };
-        let mut scroll_offset = scroll_offset;
         move |event| match event {
-            TerminalEvent::Key(KeyEvent { code, kind, .. }) if kind != KeyEventKind::Release => {
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && focused && errors.read().is_open() =>
+            {
+                let retry = errors.write().handle_key(code);
+                match retry {
+                    Some(Retry::Load(query)) => load_prized_spots(query),
+                    Some(Retry::Update(query)) => update_spots(query),
+                    None => {}
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && search.read().editing =>
+            {
+                match search.write().handle_key(code) {
+                    SearchAction::Apply(query) => load_prized_spots(Some(query)),
+                    SearchAction::Clear => load_prized_spots(None),
+                    SearchAction::None => {}
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && !input::input_captured() =>
+            {
                 match code {
+                    // Press / to search prized spots
+                    KeyCode::Char('/') if focused => search.write().start(),
                     KeyCode::Up if focused => {
                         let next = scroll_offset.get().saturating_sub(1);
                         scroll_offset.set(next.min(max_offset));
@@ -98,13 +272,27 @@ pub fn SpotHistoryLayout(
                         let next = scroll_offset.get().saturating_add(1);
                         scroll_offset.set(next.min(max_offset));
                     }
+                    // Press O / V to sort or reverse, Space to collapse the top period
+                    KeyCode::Char('o' | 'O' | 'v' | 'V' | ' ') if focused => {
+                        list_view.write().handle_key(code, top_period.as_deref());
+                    }
+                    // Press Esc to cancel the running update
+                    KeyCode::Esc => {
+                        let id = operation
+                            .write()
+                            .as_mut()
+                            .and_then(RunningOperation::start_cancel);
+                        if let Some(id) = id {
+                            cancel_operation(id);
+                        }
+                    }
                     // Press U to update all unprize spots
-                    KeyCode::Char('u' | 'U') => {
-                        update_spots(());
+                    KeyCode::Char('u' | 'U') if focused && !running => {
+                        update_spots(search.read().query());
                     }
                     // Press R to refresh/reload prized spots
-                    KeyCode::Char('r' | 'R') => {
-                        load_prized_spots(());
+                    KeyCode::Char('r' | 'R') if focused => {
+                        load_prized_spots(search.read().query());
                     }
                     _ => {}
                 }
@@ -113,29 +301,74 @@ pub fn SpotHistoryLayout(
         }
     });
 
-    let header_suffix = if props.focused { " [FOCUS]" } else { "" };
+    let header_suffix = if props.focused {
+        Msg::FocusSuffix.text()
+    } else {
+        ""
+    };
+    let title = if winning_only {
+        Msg::PrizedSpotsTitle
+    } else {
+        Msg::SpotHistoryTitle
+    }
+    .text();
 
+    let mut summary = None;
     let content_elements = match &*state.read() {
-        HistoryState::Loaded(Ok(spots)) => {
-            if spots.is_empty() {
+        HistoryState::Loaded(Ok(report)) => {
+            if report.periods.is_empty() {
                 vec![
                     element! {
-                        Text(content: "No history spots", color: Color::White, weight: Weight::Bold)
+                        Text(content: Msg::NoHistory.text(), color: Color::White, weight: Weight::Bold)
                     }
                     .into(),
                 ]
             } else {
-                let max_offset = spots.len().saturating_sub(list_height);
+                let rows = history_rows(report, &list_view.read());
+                let max_offset = rows.len().saturating_sub(list_height);
                 let offset = scroll_offset.get().min(max_offset);
-                spots
+                let visible = &rows[offset..rows.len().min(offset + list_height)];
+
+                // Summarize every period that has at least one visible row
+                let mut visible_periods = visible.iter().map(|(idx, _)| *idx).collect::<Vec<_>>();
+                visible_periods.dedup();
+                let (cost, winnings) = visible_periods
+                    .iter()
+                    .map(|&idx| &report.periods[idx])
+                    .fold((0, 0), |(cost, winnings), p| {
+                        (cost + p.cost, winnings + p.winnings)
+                    });
+                summary = Some((cost, winnings));
+
+                visible
                     .iter()
-                    .skip(offset)
-                    .take(list_height)
-                    .map(|spot| {
-                        element! {
-                            SpotComponent(value: spot.clone(), has_focus: false)
+                    .map(|(_, row)| match row {
+                        HistoryRow::Period(period, collapsed) => element! {
+                            View(flex_direction: FlexDirection::Row) {
+                                Text(
+                                    content: Msg::PeriodSummary.fill(&[
+                                        ("marker", &if *collapsed { "▶" } else { "▼" }),
+                                        ("period", &period.period),
+                                        ("cost", &period.cost),
+                                        ("winnings", &period.winnings),
+                                    ]),
+                                    color: Color::White,
+                                    weight: Weight::Bold,
+                                )
+                                Text(
+                                    content: format_net(period.net()),
+                                    color: net_color(period.net()),
+                                    weight: Weight::Bold,
+                                )
+                            }
+                        }
+                        .into(),
+                        HistoryRow::Spot(spot) => element! {
+                            View(padding_left: 2) {
+                                SpotComponent(value: (*spot).clone(), has_focus: false)
+                            }
                         }
-                        .into()
+                        .into(),
                     })
                     .collect::<Vec<_>>()
             }
@@ -143,7 +376,15 @@ pub fn SpotHistoryLayout(
         HistoryState::Loaded(Err(error)) => {
             vec![
                 element! {
-                    Text(content: format!("Error: {error}"), color: Color::Red, weight: Weight::Bold)
+                    Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red, weight: Weight::Bold)
+                }
+                .into(),
+            ]
+        }
+        HistoryState::Loading if running => {
+            vec![
+                element! {
+                    ProgressIndicator(operation: operation.read().clone())
                 }
                 .into(),
             ]
@@ -151,7 +392,7 @@ pub fn SpotHistoryLayout(
         HistoryState::Loading => {
             vec![
                 element! {
-                    Text(content: "Loading...", color: Color::Yellow, weight: Weight::Bold)
+                    Text(content: Msg::Loading.text(), color: Color::Yellow, weight: Weight::Bold)
                 }
                 .into(),
             ]
@@ -159,33 +400,58 @@ pub fn SpotHistoryLayout(
         HistoryState::Init => {
             vec![
                 element! {
-                    Text(content: "Initializing...", color: Color::DarkGrey, weight: Weight::Bold)
+                    Text(content: Msg::Initializing.text(), color: Color::DarkGrey, weight: Weight::Bold)
                 }
                 .into(),
             ]
         }
     };
 
+    let (summary_text, summary_color) = match summary {
+        Some((cost, winnings)) => {
+            let net = winnings as i64 - cost as i64;
+            (
+                Msg::HistorySummary.fill(&[
+                    ("cost", &cost),
+                    ("winnings", &winnings),
+                    ("net", &format_net(net)),
+                ]),
+                net_color(net),
+            )
+        }
+        None => (Msg::HistorySummaryEmpty.text().to_owned(), Color::DarkGrey),
+    };
+
+    let search = search.read();
+
     element! {
         View(
             flex_grow: 1.0,
             flex_direction: FlexDirection::Column,
         ) {
             Text(
-                content: format!("Spot History{header_suffix}"),
+                content: format!("{title}{header_suffix}"),
                 color: if props.focused { Color::Cyan } else { Color::White },
                 weight: Weight::Bold,
             )
             Text(
-                content: "Press U to update all unprize spots\nPress R to refresh",
+                content: Msg::HistoryKeys.fill(&[("sort", &list_view.read().sort_label())]),
                 color: Color::Yellow,
             )
+            SearchBar(
+                editing: search.editing,
+                text: search.text.clone(),
+                error: search.error.clone(),
+                applied: search.applied.as_ref().map(|(text, _)| text.clone()),
+            )
+            Text(content: summary_text, color: summary_color, weight: Weight::Bold)
             View(
                 margin_top: 1,
                 flex_direction: FlexDirection::Column,
             ) {
                 Fragment(children: content_elements)
             }
+            ErrorModal(error: props.focused.then(|| errors.read().error()).flatten())
         }
     }
 }
@@ -0,0 +1,361 @@
+use chrono::Datelike as _;
+use dball_client::service::{NumberFrequencies, NumberStat, StrategySimulation};
+use iocraft::prelude::*;
+
+use crate::app::heat::{HeatMetric, heat_ratio, heat_rgb};
+use crate::terminal::{
+    component::error_modal::{ErrorDialog, ErrorModal},
+    input,
+    ipc::{RpcResult, send_rpc_request},
+    strings::Msg,
+};
+
+/// Lookback windows selectable with `[` and `]`
+const LOOKBACK_WINDOWS: [usize; 4] = [30, 50, 100, 200];
+const DEFAULT_WINDOW_INDEX: usize = 2;
+const RED_PER_ROW: usize = 11;
+const BLUE_PER_ROW: usize = 8;
+
+#[derive(Clone)]
+enum StatsState {
+    Init,
+    Loading,
+    Loaded(RpcResult<NumberFrequencies>),
+}
+
+/// What the configured strategy would have earned in a past year
+#[derive(Clone)]
+enum SimulationState {
+    Idle,
+    Running(i32),
+    Done(RpcResult<StrategySimulation>),
+}
+
+impl HeatMetric {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Frequency => Self::Omission,
+            Self::Omission => Self::Frequency,
+        }
+    }
+
+    fn value(self, stat: &NumberStat) -> usize {
+        match self {
+            Self::Frequency => stat.frequency,
+            Self::Omission => stat.omission,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Frequency => Msg::MetricFrequency,
+            Self::Omission => Msg::MetricOmission,
+        }
+        .text()
+    }
+}
+
+#[derive(Default, Props)]
+pub struct StatsProps {
+    pub focused: bool,
+}
+
+/// Number statistics heatmap, fed by the `GetNumberFrequencies` RPC
+#[component]
+pub fn StatsLayout(mut hooks: Hooks<'_, '_>, props: &StatsProps) -> impl Into<AnyElement<'static>> {
+    let mut state = hooks.use_state(|| StatsState::Init);
+    let window_index = hooks.use_state(|| DEFAULT_WINDOW_INDEX);
+    let metric = hooks.use_state(|| HeatMetric::Frequency);
+    let mut simulation = hooks.use_state(|| SimulationState::Idle);
+    // retried with the lookback window of the failed request
+    let mut errors = hooks.use_state(ErrorDialog::<usize>::default);
+
+    let mut load_stats = hooks.use_async_handler(move |window: usize| async move {
+        state.set(StatsState::Loading);
+        log::debug!("Loading number statistics over {window} draws...");
+        match send_rpc_request::<RpcResult<NumberFrequencies>>(
+            dball_client::ipc::RpcService::GetNumberFrequencies(Some(window)),
+        )
+        .await
+        {
+            Ok(Ok(stats)) => {
+                log::debug!("Fetched number statistics over {} draws", stats.draws);
+                state.set(StatsState::Loaded(Ok(stats)));
+            }
+            Err(e) | Ok(Err(e)) => {
+                log::error!("Failed to fetch number statistics: {e}");
+                errors.write().show(e.clone(), window);
+                state.set(StatsState::Loaded(Err(e)));
+            }
+        }
+    });
+
+    let mut simulate = hooks.use_async_handler(move |year: i32| async move {
+        simulation.set(SimulationState::Running(year));
+        let result = send_rpc_request::<RpcResult<StrategySimulation>>(
+            dball_client::ipc::RpcService::SimulateStrategy(year, None),
+        )
+        .await
+        .flatten();
+        if let Err(e) = &result {
+            log::error!("Failed to simulate the strategy over {year}: {e}");
+        }
+        simulation.set(SimulationState::Done(result));
+    });
+
+    // Initial load
+    if matches!(*state.read(), StatsState::Init) {
+        load_stats(LOOKBACK_WINDOWS[window_index.get()]);
+    }
+
+    if errors.read().capture_outdated(props.focused) {
+        errors.write().sync_capture(props.focused);
+    }
+
+    hooks.use_terminal_events({
+        let mut window_index = window_index;
+        let mut metric = metric;
+        let focused = props.focused;
+        move |event| match event {
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && focused && errors.read().is_open() =>
+            {
+                let retry = errors.write().handle_key(code);
+                if let Some(window) = retry {
+                    load_stats(window);
+                }
+            }
+            TerminalEvent::Key(KeyEvent { code, kind, .. })
+                if kind != KeyEventKind::Release && focused && !input::input_captured() =>
+            {
+                match code {
+                    // Press [ / ] to shrink or grow the lookback window
+                    KeyCode::Char('[') => {
+                        let next = window_index.get().saturating_sub(1);
+                        if next != window_index.get() {
+                            window_index.set(next);
+                            load_stats(LOOKBACK_WINDOWS[next]);
+                        }
+                    }
+                    KeyCode::Char(']') => {
+                        let next = (window_index.get() + 1).min(LOOKBACK_WINDOWS.len() - 1);
+                        if next != window_index.get() {
+                            window_index.set(next);
+                            load_stats(LOOKBACK_WINDOWS[next]);
+                        }
+                    }
+                    // Press O to switch between frequency and omission
+                    KeyCode::Char('o' | 'O') => metric.set(metric.get().toggle()),
+                    // Press B to replay the configured strategy over last year
+                    KeyCode::Char('b' | 'B')
+                        if !matches!(*simulation.read(), SimulationState::Running(_)) =>
+                    {
+                        simulate(chrono::Local::now().year() - 1);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    });
+
+    let metric = metric.get();
+    let window = LOOKBACK_WINDOWS[window_index.get()];
+
+    let content_elements: Vec<AnyElement<'static>> = match &*state.read() {
+        StatsState::Loaded(Ok(stats)) => {
+            let mut elements = vec![
+                element! {
+                    Text(
+                        content: Msg::StatsDraws.fill(&[
+                            ("draws", &stats.draws),
+                            ("period", &stats.latest_period.as_deref().unwrap_or("-")),
+                        ]),
+                        color: Color::DarkGrey,
+                    )
+                }
+                .into(),
+            ];
+            elements.extend(heatmap_rows(
+                &stats.red,
+                RED_PER_ROW,
+                metric,
+                stats.max_red_frequency(),
+                stats.draws,
+            ));
+            elements.push(element! { View(height: 1) }.into());
+            elements.extend(heatmap_rows(
+                &stats.blue,
+                BLUE_PER_ROW,
+                metric,
+                stats.max_blue_frequency(),
+                stats.draws,
+            ));
+            elements
+        }
+        StatsState::Loaded(Err(error)) => vec![
+            element! {
+                Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        StatsState::Loading => vec![
+            element! {
+                Text(content: Msg::Loading.text(), color: Color::Yellow, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+        StatsState::Init => vec![
+            element! {
+                Text(content: Msg::Initializing.text(), color: Color::DarkGrey, weight: Weight::Bold)
+            }
+            .into(),
+        ],
+    };
+
+    let simulation_elements = simulation_lines(&simulation.read());
+
+    element! {
+        View(
+            flex_grow: 1.0,
+            flex_direction: FlexDirection::Column,
+        ) {
+            Text(
+                content: Msg::StatsTitle.fill(&[("metric", &metric.label()), ("window", &window)]),
+                color: Color::Cyan,
+                weight: Weight::Bold,
+            )
+            Text(
+                content: Msg::StatsKeys.text(),
+                color: Color::Yellow,
+            )
+            View(
+                margin_top: 1,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Fragment(children: content_elements)
+            }
+            View(
+                margin_top: 1,
+                flex_direction: FlexDirection::Column,
+            ) {
+                Fragment(children: simulation_elements)
+            }
+            ErrorModal(error: props.focused.then(|| errors.read().error()).flatten())
+        }
+    }
+}
+
+fn simulation_lines(state: &SimulationState) -> Vec<AnyElement<'static>> {
+    match state {
+        SimulationState::Idle => Vec::new(),
+        SimulationState::Running(year) => vec![
+            element! {
+                Text(content: Msg::Simulating.fill(&[("year", year)]), color: Color::Yellow)
+            }
+            .into(),
+        ],
+        SimulationState::Done(Ok(simulation)) => {
+            let result = &simulation.result;
+            let roi = format!("{:.1}", result.roi() * 100.0);
+            let mut lines = vec![
+                element! {
+                    Text(
+                        content: Msg::SimulationResult.fill(&[
+                            ("generator", &result.generator),
+                            ("year", &simulation.year),
+                            ("periods", &result.periods),
+                            ("cost", &result.cost),
+                            ("winnings", &result.winnings),
+                            ("roi", &roi),
+                        ]),
+                        color: if result.net() >= 0 { Color::Green } else { Color::Red },
+                        weight: Weight::Bold,
+                    )
+                }
+                .into(),
+            ];
+            let best = simulation
+                .weeks
+                .iter()
+                .max_by_key(|week| week.winnings as i64 - week.cost as i64);
+            if let Some(week) = best {
+                let net = week.winnings as i64 - week.cost as i64;
+                lines.push(
+                    element! {
+                        Text(
+                            content: Msg::SimulationBestWeek.fill(&[
+                                ("week", &week.week_start),
+                                ("net", &net),
+                            ]),
+                            color: Color::DarkGrey,
+                        )
+                    }
+                    .into(),
+                );
+            }
+            lines
+        }
+        SimulationState::Done(Err(error)) => vec![
+            element! {
+                Text(content: Msg::Error.fill(&[("error", error)]), color: Color::Red)
+            }
+            .into(),
+        ],
+    }
+}
+
+fn heatmap_rows(
+    stats: &[NumberStat],
+    per_row: usize,
+    metric: HeatMetric,
+    max_frequency: usize,
+    draws: usize,
+) -> Vec<AnyElement<'static>> {
+    stats
+        .chunks(per_row)
+        .map(|row| {
+            let cells = row
+                .iter()
+                .map(|stat| {
+                    let heat = heat_ratio(stat, metric, max_frequency, draws);
+                    element! {
+                        View(
+                            width: 4,
+                            margin_right: 1,
+                            background_color: heat_color(heat),
+                            justify_content: JustifyContent::Center,
+                        ) {
+                            Text(
+                                content: format!("{:02}", stat.number),
+                                color: Color::Black,
+                                weight: Weight::Bold,
+                            )
+                        }
+                    }
+                    .into()
+                })
+                .collect::<Vec<AnyElement<'static>>>();
+            let values = row
+                .iter()
+                .map(|stat| format!("{:>4} ", metric.value(stat)))
+                .collect::<String>();
+
+            element! {
+                View(flex_direction: FlexDirection::Column) {
+                    View(flex_direction: FlexDirection::Row) {
+                        Fragment(children: cells)
+                    }
+                    Text(content: values, color: Color::DarkGrey)
+                }
+            }
+            .into()
+        })
+        .collect()
+}
+
+/// [`heat_rgb`] as a terminal color
+fn heat_color(ratio: f32) -> Color {
+    let (r, g, b) = heat_rgb(ratio);
+    Color::Rgb { r, g, b }
+}
@@ -0,0 +1,209 @@
+//! Strings of the terminal UI
+
+use crate::app::i18n::string_table;
+
+string_table! {
+    // Tabs
+    TabDashboard => "Dashboard", "总览";
+    TabNextGen => "NextGen", "下期";
+    TabHistory => "History", "历史";
+    TabStats => "Stats", "统计";
+    TabPrized => "Prized", "中奖";
+    TabDaemon => "Daemon", "守护进程";
+    TabLogs => "Logs", "日志";
+    TabReport => "Report", "报表";
+    FocusSuffix => " [FOCUS]", " [焦点]";
+
+    // Shared states
+    Loading => "Loading...", "加载中...";
+    Initializing => "Initializing...", "初始化中...";
+    Error => "Error: {$error}", "错误: {$error}";
+    Failed => "Failed: {$error}", "失败: {$error}";
+    CancelFailed => "Cancel failed: {$error}", "取消失败: {$error}";
+    SaveFailed => "Save failed: {$error}", "保存失败: {$error}";
+    WaitingForState => "Waiting for daemon state...", "等待守护进程状态...";
+
+    // Status bar
+    DrawingNow => "drawing now", "开奖中";
+    CountdownDays => "{$days}d {$clock}", "{$days}天 {$clock}";
+    Connected => "● daemon connected", "● 已连接守护进程";
+    Connecting => "◌ connecting", "◌ 连接中";
+    Disconnected => "○ daemon disconnected", "○ 守护进程已断开";
+    ConnectionError => "○ daemon error: {$error}", "○ 守护进程错误: {$error}";
+    StatusPeriods => "Period {$current} | Next {$next} ", "本期 {$current} | 下期 {$next} ";
+    StatusDrawIn => "| Draw in {$countdown} ", "| 距开奖 {$countdown} ";
+    StatusUnprized => "| Unprized {$count} ", "| 待开奖 {$count} ";
+
+    // Dashboard
+    GenerationIdle => "Idle", "空闲";
+    GenerationRunning => "Generating", "生成中";
+    GenerationDone => "Generated", "已生成";
+    NoDrawYet => "No draw yet", "暂无开奖";
+    LatestDraw => "Latest draw {$period}", "最新开奖 {$period}";
+    NextPeriod => "Next period {$period}", "下一期 {$period}";
+    UnprizedSpots => "Unprized spots {$count}", "待开奖号码 {$count}";
+    InvestmentSummary => "Invested ¥{$invested} | Returned ¥{$returned} (claimed ¥{$claimed}) | Net ", "投入 ¥{$invested} | 回报 ¥{$returned} (已兑奖 ¥{$claimed}) | 净收益 ";
+    Generation => "Generation ", "生成状态 ";
+    LastGeneration => " (last {$time})", " (上次 {$time})";
+    Monday => "MON", "周一";
+    Tuesday => "TUE", "周二";
+    Wednesday => "WED", "周三";
+    Thursday => "THU", "周四";
+    Friday => "FRI", "周五";
+    Saturday => "SAT", "周六";
+    Sunday => "SUN", "周日";
+
+    // Daemon
+    UptimeDays => "{$days}d {$hours}h {$minutes}m", "{$days}天 {$hours}时 {$minutes}分";
+    UptimeHours => "{$hours}h {$minutes}m", "{$hours}时 {$minutes}分";
+    Uptime => "Uptime", "运行时间";
+    LastUpdate => "Last update", "最近更新";
+    LastDraw => "Last draw", "上次开奖";
+    NextDraw => "Next draw", "下次开奖";
+    PendingTickets => "Pending tickets", "待开奖期号";
+    NoPendingTickets => "none", "无";
+    ApiProvider => "API provider", "API 提供方";
+    ApiSuccessRate => "API success rate", "API 成功率";
+    ApiResponse => "API response", "API 响应时间";
+    ApiLastSuccess => "API last success", "API 最近成功";
+    NoDaemonState => "No state received from the daemon yet", "尚未收到守护进程状态";
+
+    // Next generation
+    NextGenTitle => "Next Generation", "下期号码";
+    NextGenKeys => "Press G to generate batch spots\nPress D to deprecate last batch\nPress U to undo the last deprecation\nPress R to refresh", "按 G 生成一批号码\n按 D 废弃上一批\n按 U 撤销上次废弃\n按 R 刷新";
+    NextGenSpotKeys => "Up/Down select, X deprecate, C copy, Y copy batch, N note, P purchased", "上/下 选择，X 废弃，C 复制，Y 复制整批，N 备注，P 已购买";
+    NextGenViewKeys => "{$view} (O sort, V reverse, B group, Space collapse)", "{$view} (O 排序，V 反转，B 分组，空格 折叠)";
+    NoNextGen => "No next generation data available", "暂无下期号码";
+    BatchSummary => "Sum {$sum} · odd/even {$parity} · zones {$zones} · {$blues} blues {$blue} · similarity ≤{$similarity}", "和值 {$sum} · 奇偶 {$parity} · 分区 {$zones} · {$blues} 个蓝球 {$blue} · 相似度 ≤{$similarity}";
+    GeneratingSpots => "Generating spots", "正在生成号码";
+    SpotDeprecated => "Deprecated spot #{$id}", "已废弃号码 #{$id}";
+    SpotsRestored => "Restored {$count} deprecated spots", "已恢复 {$count} 注废弃号码";
+    SpotAnnotated => "Updated note of spot #{$id}", "已更新号码 #{$id} 的备注";
+    SpotPurchased => "Marked spot #{$id} as purchased", "已将号码 #{$id} 标记为已购买";
+    SpotNotPurchased => "Unmarked spot #{$id} as purchased", "已取消号码 #{$id} 的已购买标记";
+    SpotWithoutId => "Spot has no id", "号码没有 id";
+    Copied => "Copied {$text}", "已复制 {$text}";
+    CopiedBatch => "Copied {$count} tickets of period {$period}", "已复制第 {$period} 期的 {$count} 注";
+    NoteInput => "Note: {$text}_", "备注: {$text}_";
+
+    // Spot lists
+    SortCreated => "created", "创建时间";
+    SortPeriod => "period", "期号";
+    SortPrize => "prize", "奖金";
+    SortCost => "cost", "花费";
+    GroupNone => "none", "无";
+    GroupBatch => "batch", "批次";
+    GroupPeriod => "period", "期号";
+    Descending => "desc", "降序";
+    Ascending => "asc", "升序";
+    SortLabel => "Sort: {$key} {$direction}", "排序: {$key} {$direction}";
+    ViewLabel => "{$sort} | Group: {$group}", "{$sort} | 分组: {$group}";
+    PeriodGroup => "Period {$period}", "第 {$period} 期";
+    BatchGroup => "Period {$period} batch {$batch} ({$time})", "第 {$period} 期 第 {$batch} 批 ({$time})";
+    GroupHeader => "{$marker} {$label} ({$count} spots)", "{$marker} {$label} ({$count} 注)";
+    SpotHit => "hit#{$status}", "中奖#{$status}";
+    SpotNoPrize => "non-prize", "未中奖";
+    SpotPending => "pending", "待开奖";
+    SearchFilter => "Filter: {$filter}", "筛选: {$filter}";
+    SearchHint => "Press / to search (2025 win #tag r:1,2 b:3)", "按 / 搜索 (2025 win #tag r:1,2 b:3)";
+
+    // History
+    SpotHistoryTitle => "Spot History", "历史号码";
+    PrizedSpotsTitle => "Prized Spots", "中奖号码";
+    HistoryKeys => "Press U to update all unprize spots\nPress R to refresh | {$sort} (O sort, V reverse, Space collapse)", "按 U 更新全部待开奖号码\n按 R 刷新 | {$sort} (O 排序，V 反转，空格 折叠)";
+    NoHistory => "No history spots", "暂无历史号码";
+    UpdatingSpots => "Updating unprized spots", "正在更新待开奖号码";
+    PeriodSummary => "{$marker} {$period} cost ¥{$cost} win ¥{$winnings} ", "{$marker} {$period} 花费 ¥{$cost} 奖金 ¥{$winnings} ";
+    HistorySummary => "Cost ¥{$cost} | Win ¥{$winnings} | Net {$net}", "花费 ¥{$cost} | 奖金 ¥{$winnings} | 净收益 {$net}";
+    HistorySummaryEmpty => "Cost - | Win - | Net -", "花费 - | 奖金 - | 净收益 -";
+
+    // Stats
+    MetricFrequency => "frequency", "频次";
+    MetricOmission => "omission", "遗漏";
+    StatsTitle => "Number Stats - {$metric} over last {$window}", "号码统计 - 最近 {$window} 期{$metric}";
+    StatsKeys => "Press [ / ] to change window\nPress O to toggle omission | B to simulate last year", "按 [ / ] 调整统计期数\n按 O 切换遗漏 | 按 B 模拟去年";
+    StatsDraws => "{$draws} draws up to {$period}", "截至 {$period} 共 {$draws} 期";
+    Simulating => "Simulating the configured strategy over {$year}...", "正在用当前策略模拟 {$year} 年...";
+    SimulationResult => "{$generator} in {$year}: {$periods} draws, cost ¥{$cost}, win ¥{$winnings}, ROI {$roi}%", "{$generator} 在 {$year} 年: {$periods} 期, 花费 ¥{$cost}, 奖金 ¥{$winnings}, 回报率 {$roi}%";
+    SimulationBestWeek => "Best week from {$week}: net {$net}", "最佳一周 {$week} 起: 净收益 {$net}";
+
+    // Report
+    ReportTitle => "Report - {$title}", "报表 - {$title}";
+    ReportKeys => "Press [ / ] to change month or year\nPress Y to toggle the whole year | R to refresh", "按 [ / ] 切换月份或年份\n按 Y 切换全年 | 按 R 刷新";
+    ReportEmpty => "No settled spots drawn in this time", "该时段暂无已开奖号码";
+    ReportTotals => "{$periods} periods, {$spots} spots | Cost ¥{$cost} | Win ¥{$winnings} | Claimed ¥{$claimed} | Net {$net}", "{$periods} 期, {$spots} 注 | 花费 ¥{$cost} | 奖金 ¥{$winnings} | 已兑奖 ¥{$claimed} | 净收益 {$net}";
+    ReportWinsByTier => "Wins by tier: {$tiers}", "各等奖中奖注数: {$tiers}";
+    ReportBest => "Best period {$period}: net {$net}", "最佳一期 {$period}: 净收益 {$net}";
+    ReportWorst => "Worst period {$period}: net {$net}", "最差一期 {$period}: 净收益 {$net}";
+
+    // Logs
+    NoLogs => "No logs available yet...", "暂无日志...";
+
+    // Settings
+    SettingsTitle => "Settings - dball.toml", "设置 - dball.toml";
+    SettingsKeys => "Up/Down to select, Enter to edit, Left/Right to switch generator or language\nPress W to save, R to reload, Esc to close", "上/下 选择，Enter 编辑，左/右 切换生成器或语言\n按 W 保存，R 重新加载，Esc 关闭";
+    FieldBudgetPerPeriod => "Budget per period (¥)", "每期预算 (¥)";
+    FieldBudgetPerWeek => "Budget per week (¥)", "每周预算 (¥)";
+    FieldBudgetPerMonth => "Budget per month (¥)", "每月预算 (¥)";
+    FieldCrawlConcurrency => "Crawl concurrency", "抓取并发数";
+    FieldCrawlMaxAttempts => "Crawl attempts", "抓取尝试次数";
+    FieldCrawlBackoff => "Crawl backoff (ms)", "抓取退避 (毫秒)";
+    FieldGenerator => "Generator", "生成器";
+    FieldBatchSize => "Spots per batch", "每批注数";
+    FieldFrequencyWindow => "Frequency window (draws)", "频率窗口 (期)";
+    FieldFrequencyBias => "Frequency bias (%)", "频率偏好 (%)";
+    FieldRng => "Random numbers", "随机数生成器";
+    FieldMaintenanceInterval => "Maintenance every (h)", "维护间隔 (小时)";
+    FieldVacuumFreePercent => "Vacuum at free (%)", "空闲页清理阈值 (%)";
+    FieldWebhook => "Webhook URL", "Webhook 地址";
+    FieldEmail => "Email", "邮箱";
+    FieldTelegram => "Telegram chat id", "Telegram 会话 id";
+    FieldTelegramCommands => "Telegram commands", "Telegram 指令";
+    FieldDoNotDisturb => "Do not disturb", "免打扰";
+    FieldMinTier => "Lowest tier notified", "最低通知奖级";
+    FieldSmtpHost => "SMTP server", "SMTP 服务器";
+    FieldSmtpPort => "SMTP port", "SMTP 端口";
+    FieldSmtpUsername => "SMTP user", "SMTP 用户名";
+    FieldSmtpFrom => "Mail sender", "发件地址";
+    FieldSmtpTls => "SMTP over TLS", "SMTP 使用 TLS";
+    FieldStateRefresh => "State refresh (s)", "状态刷新 (秒)";
+    FieldSpotsRefresh => "Spots refresh (s)", "号码刷新 (秒)";
+    FieldSchedulerEnabled => "Run after draws", "开奖后自动运行";
+    FieldSchedulerDelay => "Run after draw (min)", "开奖后延迟 (分钟)";
+    FieldSchedulerAttempts => "Attempts per step", "每步尝试次数";
+    FieldSchedulerRetry => "Retry after (s)", "重试间隔 (秒)";
+    FieldDuplicates => "Duplicate spots", "重复号码";
+    FieldLanguage => "Language", "语言";
+    FieldUnknown => "Unknown", "未知";
+    Unset => "(unset)", "(未设置)";
+    Saving => "Saving...", "保存中...";
+    Saved => "Saved to dball.toml", "已保存到 dball.toml";
+    NotSaved => "Not saved, fix the fields marked below", "未保存，请修正下方标出的字段";
+
+    // Error modal
+    RequestFailed => "Request failed", "请求失败";
+    ErrorCode => "Code:    {$code}", "代码:    {$code}";
+    ErrorMessage => "Message: {$message}", "信息:    {$message}";
+    ErrorRequest => "Request: {$id}", "请求:    {$id}";
+    HintNone => "Retrying is unlikely to help", "重试大概率无济于事";
+    RetryButton => "[ Enter/R Retry ]", "[ Enter/R 重试 ]";
+    DismissButton => "[ Esc Dismiss ]", "[ Esc 关闭 ]";
+
+    // Progress
+    ProgressStep => "step {$step} - {$message}", "第 {$step} 步 - {$message}";
+    WaitingForDaemon => "Waiting for the daemon...", "等待守护进程...";
+    Cancelling => "Cancelling...", "取消中...";
+    CancelHint => "Press Esc to cancel", "按 Esc 取消";
+    CrawlingYear => "Crawling {$year}, at period {$period}", "正在抓取 {$year} 年，当前第 {$period} 期";
+    CrawlCounts => "{$inserted} inserted, {$skipped} skipped, {$failed} failed", "新增 {$inserted}，跳过 {$skipped}，失败 {$failed}";
+
+    // Toasts
+    ToastDrawSettled => "Draw {$period} settled, {$spots} spots checked", "第 {$period} 期已开奖，核对了 {$spots} 注";
+    ToastPeriodEvaluated => "Period {$period} evaluated, {$spots} spots won ¥{$winnings}", "第 {$period} 期已核算，{$spots} 注共中奖 ¥{$winnings}";
+    ToastPrizeWon => "Prize {$tier} won in {$period}: ¥{$amount}", "第 {$period} 期中奖 {$tier}: ¥{$amount}";
+    ToastGenerated => "Generated {$spots} spots for {$period}", "已为第 {$period} 期生成 {$spots} 注";
+    ToastApiFailure => "API failure: {$message}", "API 故障: {$message}";
+    ToastCrawlFinished => "Crawled {$year}: {$inserted} inserted, {$failed} failed", "{$year} 年抓取完成: 新增 {$inserted}，失败 {$failed}";
+    DoNotDisturbOn => "Do not disturb on", "免打扰已开启";
+    DoNotDisturbOff => "Do not disturb off", "免打扰已关闭";
+}
@@ -1,6 +1,11 @@
+//! Helpers of the criterion benchmarks under `benches/`
+
 use std::collections::HashSet;
 use std::hash::Hash;
 
+use dball_combora::dball::{DBall, DBallBatch};
+use dball_combora::generator::{RandomGenerator, bluemorn::BlueMorn};
+
 /// Method 1: `HashSet` (generic version)
 pub fn has_duplicates_hashset<T>(data: &[T]) -> bool
 where
@@ -33,3 +38,35 @@ where
     v.sort_unstable();
     v.windows(2).any(|w| w[0] == w[1])
 }
+
+/// Step between the seeds of consecutive candidates, spreads them over the generator's state
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// `count` candidates of the `BlueMorn` generator, the same for the same `seed`
+pub fn seeded_candidates(count: usize, seed: u64) -> Vec<DBall> {
+    (0..count as u64)
+        .map(|i| BlueMorn::generate_with_seed(seed ^ i.wrapping_mul(SEED_STEP)))
+        .collect()
+}
+
+/// `count` batches of five seeded candidates, the same for the same `seed`
+pub fn seeded_batches(count: usize, seed: u64) -> Vec<DBallBatch> {
+    seeded_candidates(count * 5, seed)
+        .chunks_exact(5)
+        .map(|chunk| DBallBatch(chunk.to_vec()))
+        .collect()
+}
+
+/// Share of the batches the generator accepts
+///
+/// A batch is kept with its score as the probability, so the rate is the mean score
+pub fn acceptance_rate(generator: &dyn RandomGenerator, batches: &[DBallBatch]) -> f64 {
+    if batches.is_empty() {
+        return 0.0;
+    }
+    let total = batches
+        .iter()
+        .map(|batch| generator.evaluate_batch(batch))
+        .sum::<f64>();
+    total / batches.len() as f64
+}
@@ -0,0 +1,130 @@
+//! Replay generators over past draws of the local database and compare what they would have won
+//!
+//! Point `DATABASE_URL` at a snapshot, e.g. one written by `dball-cli db backup`,
+//! to keep the live database out of it
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::path::PathBuf;
+
+    use anyhow::{Result, anyhow};
+    use clap::Parser;
+    use dball_client::service::{self, BacktestResult, BacktestRun};
+    use dball_combora::generator::Generator;
+
+    #[derive(Parser)]
+    #[command(
+        name = "backtest",
+        version,
+        about = "Replay generators over past draws and compare their winnings"
+    )]
+    struct Args {
+        /// Generators to compare, all of them when none is given
+        #[arg(long, value_parser = parse_generator)]
+        generator: Vec<Generator>,
+        /// First period replayed, e.g. `2020001`
+        #[arg(long)]
+        from: String,
+        /// Last period replayed, e.g. `2024153`
+        #[arg(long)]
+        to: String,
+        /// Seeds of the generated spots, every generator runs once per seed
+        #[arg(long, value_delimiter = ',', default_value = "1")]
+        seed: Vec<u64>,
+        /// Yuan spent per period, one batch of 5 spots per period when none is given
+        #[arg(long, value_delimiter = ',')]
+        budget: Vec<u32>,
+        /// Also write the results as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+    }
+
+    impl Args {
+        /// Every combination of generator, seed and budget
+        fn runs(&self) -> Vec<BacktestRun> {
+            let generators = if self.generator.is_empty() {
+                Generator::ALL.to_vec()
+            } else {
+                self.generator.clone()
+            };
+            let budgets = if self.budget.is_empty() {
+                vec![None]
+            } else {
+                self.budget.iter().copied().map(Some).collect()
+            };
+            let mut runs = Vec::new();
+            for &generator in &generators {
+                for &seed in &self.seed {
+                    for &budget in &budgets {
+                        runs.push(BacktestRun {
+                            generator,
+                            seed,
+                            budget,
+                        });
+                    }
+                }
+            }
+            runs
+        }
+    }
+
+    fn parse_generator(name: &str) -> Result<Generator> {
+        Generator::ALL
+            .into_iter()
+            .find(|generator| generator.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let known = Generator::ALL.map(|g| g.name()).join(", ");
+                anyhow!("Unknown generator `{name}`, expected one of {known}")
+            })
+    }
+
+    fn print_table(results: &[BacktestResult]) {
+        println!(
+            "{:<10} {:>8} {:>8} {:>8} {:>8} {:>10} {:>10} {:>10} {:>8}  prizes #1-#6",
+            "generator", "seed", "budget", "periods", "spots", "cost", "winnings", "net", "roi"
+        );
+        for result in results {
+            let budget = result
+                .budget
+                .map_or_else(|| "-".to_owned(), |budget| budget.to_string());
+            let prizes = result.prizes.map(|count| count.to_string()).join("/");
+            println!(
+                "{:<10} {:>8} {:>8} {:>8} {:>8} {:>10} {:>10} {:>+10} {:>7.1}%  {prizes}",
+                result.generator,
+                result.seed,
+                budget,
+                result.periods,
+                result.spots,
+                result.cost,
+                result.winnings,
+                result.net(),
+                result.roi() * 100.0
+            );
+        }
+    }
+
+    pub async fn run() -> Result<()> {
+        let args = Args::parse();
+        dball_client::Client::builder()
+            .log_level(Some(log::LevelFilter::Warn))
+            .build()?;
+
+        let results = service::backtest(&args.runs(), &args.from, &args.to).await?;
+        print_table(&results);
+        if let Some(path) = &args.json {
+            std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+            println!("Wrote {} results to {}", results.len(), path.display());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    native::run().await
+}
+
+/// Reads a local database, there is nothing to run in the browser
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -2,14 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 // When compiling natively:
-#[cfg(not(feature = "terminal"))]
+#[cfg(all(not(feature = "terminal"), not(target_arch = "wasm32")))]
 fn main() -> eframe::Result {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([400.0, 300.0])
-            .with_min_inner_size([300.0, 220.0])
+            .with_inner_size([760.0, 560.0])
+            .with_min_inner_size([480.0, 360.0])
             .with_icon(
                 // NOTE: Adding an icon is optional
                 eframe::icon_data::from_png_bytes(&include_bytes!("../assets/icon-256.png")[..])
@@ -20,18 +20,76 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "double",
         native_options,
-        Box::new(|cc| Ok(Box::new(dball::eframe::TemplateApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(dball::eframe::DballApp::new(cc)))),
     )
 }
 
+// When compiling to web using trunk:
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast as _;
+
+    // Redirect `log` message to `console.log` and friends:
+    eframe::WebLogger::init(log::LevelFilter::Debug).ok();
+
+    let web_options = eframe::WebOptions::default();
+
+    wasm_bindgen_futures::spawn_local(async {
+        let document = web_sys::window()
+            .expect("No window")
+            .document()
+            .expect("No document");
+
+        let canvas = document
+            .get_element_by_id("the_canvas_id")
+            .expect("Failed to find the_canvas_id")
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .expect("the_canvas_id was not a HtmlCanvasElement");
+
+        let start_result = eframe::WebRunner::new()
+            .start(
+                canvas,
+                web_options,
+                Box::new(|cc| Ok(Box::new(dball::eframe::DballApp::new(cc)))),
+            )
+            .await;
+
+        // Remove the loading text and spinner:
+        if let Some(loading_text) = document.get_element_by_id("loading_text") {
+            match start_result {
+                Ok(_) => {
+                    loading_text.remove();
+                }
+                Err(e) => {
+                    loading_text.set_inner_html(
+                        "<p> The app has crashed. See the developer console for details. </p>",
+                    );
+                    panic!("Failed to start eframe: {e:?}");
+                }
+            }
+        }
+    });
+}
+
 #[cfg(feature = "terminal")]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    use dball::terminal::DballApp;
-    use dball_client::ipc::client::IpcClient;
+    use dball::terminal::{DballApp, TransportMode, connect_transport, set_transport_mode};
     use iocraft::prelude::*;
     use std::io::IsTerminal as _;
-    IpcClient::new().connect().await?;
+
+    // `--transport auto|ipc|http`, HTTP reaches a daemon on another machine
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--transport" {
+            let name = args.next().unwrap_or_default();
+            let mode = TransportMode::from_name(&name).ok_or_else(|| {
+                anyhow::anyhow!("Unknown transport `{name}`, expected auto, ipc or http")
+            })?;
+            set_transport_mode(mode);
+        }
+    }
+    connect_transport().await?;
 
     if std::io::stdout().is_terminal() {
         element!(DballApp).fullscreen().await?;